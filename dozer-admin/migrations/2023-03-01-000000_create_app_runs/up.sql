@@ -0,0 +1,14 @@
+-- Your SQL goes here
+create table app_runs (
+    id TEXT NOT NULL PRIMARY KEY,
+    app_name TEXT NOT NULL,
+    config TEXT NOT NULL,
+    status TEXT NOT NULL,
+    restart_policy TEXT NOT NULL,
+    max_restarts INTEGER NOT NULL,
+    restart_count INTEGER DEFAULT 0 NOT NULL,
+    last_error TEXT,
+    pid INTEGER,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -21,10 +21,15 @@ pub struct AdminCliConfig {
     pub home_dir: String,
     #[serde(default = "default_ui_path")]
     pub ui_path: String,
+    #[serde(default = "default_dozer_path")]
+    pub dozer_path: String,
 }
 fn default_ui_path() -> String {
     AdminCliConfig::default().ui_path
 }
+fn default_dozer_path() -> String {
+    AdminCliConfig::default().dozer_path
+}
 fn default_ui_port() -> u32 {
     AdminCliConfig::default().ui_port
 }
@@ -50,6 +55,7 @@ impl Default for AdminCliConfig {
             cors: true,
             home_dir: default_home_dir(),
             ui_path: "./ui".to_owned(),
+            dozer_path: "./dozer".to_owned(),
         }
     }
 }
@@ -18,9 +18,13 @@ pub fn get_db_path() -> String {
 
 pub fn init_db() {
     let db_path = get_db_path();
-    if !Path::new(&db_path).exists() {
-        let db_pool = establish_connection(db_path);
-        let mut db_connection = db_pool.get().unwrap();
+    let exists = Path::new(&db_path).exists();
+    let db_pool = establish_connection(db_path);
+    let mut db_connection = db_pool.get().unwrap();
+    if exists {
+        // keep existing data, only apply migrations added since
+        db_connection.run_pending_migrations(MIGRATIONS).unwrap();
+    } else {
         // run migration
         run_migrations(&mut db_connection).unwrap();
     }
@@ -0,0 +1,28 @@
+use super::schema::app_runs;
+use diesel::{Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+#[derive(Identifiable, Queryable, PartialEq, Eq, Debug, Clone, Serialize, Deserialize, Default)]
+#[diesel(table_name = app_runs)]
+pub struct AppRun {
+    pub(crate) id: String,
+    pub(crate) app_name: String,
+    pub(crate) config: String,
+    pub(crate) status: String,
+    pub(crate) restart_policy: String,
+    pub(crate) max_restarts: i32,
+    pub(crate) restart_count: i32,
+    pub(crate) last_error: Option<String>,
+    pub(crate) pid: Option<i32>,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+#[derive(Insertable, PartialEq, Debug, Serialize, Deserialize)]
+#[diesel(table_name = app_runs)]
+pub struct NewAppRun {
+    pub(crate) id: String,
+    pub(crate) app_name: String,
+    pub(crate) config: String,
+    pub(crate) status: String,
+    pub(crate) restart_policy: String,
+    pub(crate) max_restarts: i32,
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod app_run;
 pub mod connection;
 pub mod pool;
 pub mod schema;
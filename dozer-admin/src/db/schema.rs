@@ -1,5 +1,21 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    app_runs (id) {
+        id -> Text,
+        app_name -> Text,
+        config -> Text,
+        status -> Text,
+        restart_policy -> Text,
+        max_restarts -> Integer,
+        restart_count -> Integer,
+        last_error -> Nullable<Text>,
+        pid -> Nullable<Integer>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     apps (id) {
         id -> Text,
@@ -20,4 +36,4 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(apps, connections,);
+diesel::allow_tables_to_appear_in_same_query!(app_runs, apps, connections,);
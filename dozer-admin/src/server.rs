@@ -1,11 +1,21 @@
-use std::sync::atomic::Ordering;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use crate::{
     cli::{utils::get_db_path, AdminCliConfig},
     db::pool::establish_connection,
-    services::{application_service::AppService, connection_service::ConnectionService},
+    services::{
+        application_service::AppService, connection_service::ConnectionService, constants,
+        supervisor::SupervisorSettings,
+    },
+};
+use dozer_types::{
+    log::{error, info},
+    tracing::Level,
 };
-use dozer_types::{log::info, tracing::Level};
 use tonic::{transport::Server, Request, Response, Status};
 use tower_http::trace::{self, TraceLayer};
 pub mod dozer_admin_grpc {
@@ -15,10 +25,11 @@ pub mod dozer_admin_grpc {
         tonic::include_file_descriptor_set!("dozer_admin_grpc_descriptor");
 }
 use self::dozer_admin_grpc::{
-    GenerateGraphRequest, GenerateGraphResponse, GenerateYamlRequest, GenerateYamlResponse,
-    ListAppRequest, ListAppResponse, ParseRequest, ParseResponse, ParseYamlRequest,
-    ParseYamlResponse, StartRequest, StartResponse, StopRequest, StopResponse, UpdateAppRequest,
-    ValidateConnectionResponse,
+    DozerRun, DozerStatusRequest, GenerateGraphRequest, GenerateGraphResponse, GenerateYamlRequest,
+    GenerateYamlResponse, ListAppRequest, ListAppResponse, ListDozerRunsRequest,
+    ListDozerRunsResponse, ParseRequest, ParseResponse, ParseYamlRequest, ParseYamlResponse,
+    StartRequest, StartResponse, StopRequest, StopResponse, TailDozerLogsRequest,
+    TailDozerLogsResponse, UpdateAppRequest, ValidateConnectionResponse,
 };
 use dozer_admin_grpc::{
     dozer_admin_server::{DozerAdmin, DozerAdminServer},
@@ -203,6 +214,49 @@ impl DozerAdmin for GrpcService {
             Err(e) => Err(Status::new(tonic::Code::Internal, e.message)),
         }
     }
+
+    async fn get_dozer_status(
+        &self,
+        request: tonic::Request<DozerStatusRequest>,
+    ) -> Result<tonic::Response<DozerRun>, tonic::Status> {
+        let result = self.app_service.get_dozer_status(request.into_inner());
+        match result {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::new(tonic::Code::Internal, e.message)),
+        }
+    }
+
+    async fn list_dozer_runs(
+        &self,
+        request: tonic::Request<ListDozerRunsRequest>,
+    ) -> Result<tonic::Response<ListDozerRunsResponse>, tonic::Status> {
+        let result = self.app_service.list_dozer_runs(request.into_inner());
+        match result {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::new(tonic::Code::Internal, e.message)),
+        }
+    }
+
+    async fn tail_dozer_logs(
+        &self,
+        request: tonic::Request<TailDozerLogsRequest>,
+    ) -> Result<tonic::Response<TailDozerLogsResponse>, tonic::Status> {
+        let result = self.app_service.tail_dozer_logs(request.into_inner());
+        match result {
+            Ok(response) => Ok(Response::new(response)),
+            Err(e) => Err(Status::new(tonic::Code::Internal, e.message)),
+        }
+    }
+}
+
+// Apps run in their own working directory, so relative paths have to be resolved up front.
+fn absolute_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_owned()
+    } else {
+        env::current_dir().unwrap().join(path)
+    }
 }
 
 pub async fn start_admin_server(config: AdminCliConfig) -> Result<(), tonic::transport::Error> {
@@ -214,7 +268,23 @@ pub async fn start_admin_server(config: AdminCliConfig) -> Result<(), tonic::tra
     let database_url: String = get_db_path();
 
     let db_pool = establish_connection(database_url);
-    let app_service = AppService::new(db_pool.to_owned());
+    // A bare binary name is looked up in PATH
+    let dozer_path = if config.dozer_path.contains(std::path::MAIN_SEPARATOR) {
+        absolute_path(&config.dozer_path)
+            .to_string_lossy()
+            .to_string()
+    } else {
+        config.dozer_path.to_owned()
+    };
+    let supervisor_settings = SupervisorSettings {
+        dozer_path,
+        runs_dir: absolute_path(&config.home_dir).join("runs"),
+        restart_delay: Duration::from_secs(constants::RESTART_DELAY_SECS),
+    };
+    let app_service = AppService::new(db_pool.to_owned(), supervisor_settings);
+    if let Err(e) = app_service.recover_dozer_runs() {
+        error!("Failed to recover dozer runs: {}", e.message);
+    }
     let grpc_service = GrpcService {
         connection_service: ConnectionService::new(db_pool.to_owned()),
         app_service: app_service.to_owned(),
@@ -239,9 +309,5 @@ pub async fn start_admin_server(config: AdminCliConfig) -> Result<(), tonic::tra
         .add_service(server)
         .serve(addr)
         .await?;
-
-    for (_, r) in app_service.apps.write().iter_mut() {
-        r.store(false, Ordering::Relaxed);
-    }
     Ok(())
 }
@@ -1,5 +1,6 @@
 use super::constants;
 use super::graph;
+use super::supervisor::{Supervisor, SupervisorSettings};
 use crate::server::dozer_admin_grpc::StopRequest;
 use crate::server::dozer_admin_grpc::StopResponse;
 use crate::{
@@ -9,36 +10,29 @@ use crate::{
         schema::apps::{self, dsl::*},
     },
     server::dozer_admin_grpc::{
-        AppResponse, CreateAppRequest, ErrorResponse, GenerateGraphRequest, GenerateGraphResponse,
-        GenerateYamlRequest, GenerateYamlResponse, GetAppRequest, ListAppRequest, ListAppResponse,
-        Pagination, ParseRequest, ParseResponse, ParseYamlRequest, ParseYamlResponse, StartRequest,
-        StartResponse, UpdateAppRequest,
+        AppResponse, CreateAppRequest, DozerRun, DozerStatusRequest, ErrorResponse,
+        GenerateGraphRequest, GenerateGraphResponse, GenerateYamlRequest, GenerateYamlResponse,
+        GetAppRequest, ListAppRequest, ListAppResponse, ListDozerRunsRequest,
+        ListDozerRunsResponse, Pagination, ParseRequest, ParseResponse, ParseYamlRequest,
+        ParseYamlResponse, RestartPolicy, StartRequest, StartResponse, TailDozerLogsRequest,
+        TailDozerLogsResponse, UpdateAppRequest,
     },
 };
 use diesel::prelude::*;
 use diesel::{insert_into, QueryDsl, RunQueryDsl};
-use dozer_orchestrator::simple::SimpleOrchestrator as Dozer;
 use dozer_orchestrator::wrapped_statement_to_pipeline;
-use dozer_orchestrator::Orchestrator;
-use dozer_types::parking_lot::RwLock;
 use dozer_types::serde_yaml;
-use std::collections::HashMap;
-
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::thread;
 
 #[derive(Clone)]
 pub struct AppService {
     db_pool: DbPool,
-    pub apps: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    supervisor: Supervisor,
 }
 impl AppService {
-    pub fn new(db_pool: DbPool) -> Self {
+    pub fn new(db_pool: DbPool, supervisor_settings: SupervisorSettings) -> Self {
         Self {
+            supervisor: Supervisor::new(db_pool.clone(), supervisor_settings),
             db_pool,
-            apps: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -269,36 +263,60 @@ impl AppService {
     }
 
     pub fn start_dozer(&self, input: StartRequest) -> Result<StartResponse, ErrorResponse> {
-        let generated_id = uuid::Uuid::new_v4().to_string();
-
-        //validate config
-        let c = serde_yaml::from_str::<dozer_types::models::app_config::Config>(&input.config)
-            .map_err(|op| ErrorResponse {
-                message: op.to_string(),
+        let restart_policy =
+            RestartPolicy::from_i32(input.restart_policy).ok_or_else(|| ErrorResponse {
+                message: format!("Unknown restart policy : {}", input.restart_policy),
             })?;
-        let running = Arc::new(AtomicBool::new(true));
-        let r = running.clone();
-        thread::spawn(move || {
-            let mut dozer = Dozer::new(c);
-            dozer.run_all(running).unwrap();
-        });
-        self.apps.write().insert(generated_id.clone(), r);
+        let run = self
+            .supervisor
+            .start(input.config, restart_policy, input.max_restarts)?;
         Ok(StartResponse {
             success: true,
-            id: generated_id,
+            id: run.id,
         })
     }
 
     pub fn stop_dozer(&self, input: StopRequest) -> Result<StopResponse, ErrorResponse> {
-        let i = input.id;
-        self.apps.read().get(&i).map_or(
-            Err(ErrorResponse {
-                message: "Cannot find dozer with id : {i}".to_string(),
+        self.supervisor.stop(&input.id)?;
+        Ok(StopResponse { success: true })
+    }
+
+    pub fn get_dozer_status(&self, input: DozerStatusRequest) -> Result<DozerRun, ErrorResponse> {
+        self.supervisor.get(&input.id)
+    }
+
+    pub fn list_dozer_runs(
+        &self,
+        input: ListDozerRunsRequest,
+    ) -> Result<ListDozerRunsResponse, ErrorResponse> {
+        let offset = input.offset.unwrap_or(constants::OFFSET);
+        let limit = input.limit.unwrap_or(constants::LIMIT);
+        let (runs, total) = self.supervisor.list(limit, offset)?;
+        let total = u32::try_from(total).map_err(|err| ErrorResponse {
+            message: err.to_string(),
+        })?;
+        Ok(ListDozerRunsResponse {
+            runs,
+            pagination: Some(Pagination {
+                limit,
+                total,
+                offset,
             }),
-            |r| {
-                r.store(false, Ordering::Relaxed);
-                Ok(StopResponse { success: true })
-            },
-        )
+        })
+    }
+
+    pub fn tail_dozer_logs(
+        &self,
+        input: TailDozerLogsRequest,
+    ) -> Result<TailDozerLogsResponse, ErrorResponse> {
+        let lines = self
+            .supervisor
+            .tail_logs(&input.id, input.lines.unwrap_or(constants::LOG_LINES))?;
+        Ok(TailDozerLogsResponse { lines })
+    }
+
+    /// Resumes supervision of the apps left running by a previous dozer-admin.
+    pub fn recover_dozer_runs(&self) -> Result<(), ErrorResponse> {
+        self.supervisor.recover()
     }
 }
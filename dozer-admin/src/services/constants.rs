@@ -1,2 +1,7 @@
 pub const LIMIT: u32 = 10;
 pub const OFFSET: u32 = 0;
+pub const MAX_RESTARTS: u32 = 3;
+pub const RESTART_DELAY_SECS: u64 = 5;
+pub const STOP_TIMEOUT_SECS: u64 = 30;
+pub const LOG_LINES: u32 = 100;
+pub const MAX_LOG_TAIL_BYTES: u64 = 1024 * 1024;
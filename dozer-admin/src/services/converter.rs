@@ -1,4 +1,5 @@
-use crate::server::dozer_admin_grpc::{self};
+use crate::db::app_run::AppRun;
+use crate::server::dozer_admin_grpc::{self, DozerStatus, RestartPolicy};
 
 use std::convert::From;
 
@@ -20,3 +21,20 @@ impl From<dozer_orchestrator::TableInfo> for dozer_admin_grpc::TableInfo {
         }
     }
 }
+
+impl From<AppRun> for dozer_admin_grpc::DozerRun {
+    fn from(run: AppRun) -> Self {
+        dozer_admin_grpc::DozerRun {
+            id: run.id,
+            app_name: run.app_name,
+            status: DozerStatus::from_str_name(&run.status).unwrap_or(DozerStatus::Failed) as i32,
+            last_error: run.last_error,
+            restart_policy: RestartPolicy::from_str_name(&run.restart_policy)
+                .unwrap_or(RestartPolicy::Never) as i32,
+            max_restarts: run.max_restarts as u32,
+            restart_count: run.restart_count as u32,
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+        }
+    }
+}
@@ -3,3 +3,4 @@ pub mod connection_service;
 pub mod constants;
 pub mod converter;
 mod graph;
+pub mod supervisor;
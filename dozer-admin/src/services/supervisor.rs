@@ -0,0 +1,437 @@
+use super::constants;
+use crate::{
+    db::{
+        app_run::{AppRun, NewAppRun},
+        pool::DbPool,
+        schema::app_runs,
+    },
+    server::dozer_admin_grpc::{DozerRun, DozerStatus, ErrorResponse, RestartPolicy},
+};
+use diesel::prelude::*;
+use diesel::{dsl::now, insert_into};
+use dozer_types::constants::DEFAULT_HOME_DIR;
+use dozer_types::log::{error, info};
+use dozer_types::parking_lot::{Mutex, RwLock};
+use dozer_types::serde_yaml;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const CONFIG_FILE: &str = "dozer-config.yaml";
+const LOG_FILE: &str = "dozer.log";
+
+#[derive(Debug, Clone)]
+pub struct SupervisorSettings {
+    /// Path of the `dozer` binary apps are started with.
+    pub dozer_path: String,
+    /// Every run keeps its config and logs in a sub directory, used as the app's working directory.
+    pub runs_dir: PathBuf,
+    /// Delay before an exited app is restarted.
+    pub restart_delay: Duration,
+}
+
+impl Default for SupervisorSettings {
+    fn default() -> Self {
+        Self {
+            dozer_path: "dozer".to_owned(),
+            runs_dir: Path::new(DEFAULT_HOME_DIR).join("runs"),
+            restart_delay: Duration::from_secs(constants::RESTART_DELAY_SECS),
+        }
+    }
+}
+
+/// Runs dozer apps as child processes, tracking them in the `app_runs` table so that status and
+/// errors survive a restart of dozer-admin.
+#[derive(Clone)]
+pub struct Supervisor {
+    db_pool: DbPool,
+    settings: SupervisorSettings,
+    processes: Arc<RwLock<HashMap<String, Arc<Process>>>>,
+}
+
+#[derive(Default)]
+struct Process {
+    /// Pid of the running child, 0 while there is none. Stop requests are made with the lock held,
+    /// so that a child is either not started or signaled.
+    pid: Mutex<u32>,
+    stop_requested: AtomicBool,
+}
+
+enum Launch {
+    Spawn,
+    /// Process started by a previous dozer-admin that is still alive.
+    Attach(u32),
+    /// Process started by a previous dozer-admin that exited in the meantime.
+    Lost,
+}
+
+impl Supervisor {
+    pub fn new(db_pool: DbPool, settings: SupervisorSettings) -> Self {
+        Self {
+            db_pool,
+            settings,
+            processes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn start(
+        &self,
+        config: String,
+        restart_policy: RestartPolicy,
+        max_restarts: Option<u32>,
+    ) -> Result<DozerRun, ErrorResponse> {
+        //validate config
+        let c = serde_yaml::from_str::<dozer_types::models::app_config::Config>(&config)
+            .map_err(to_error)?;
+
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let dir = self.run_dir(&run_id);
+        fs::create_dir_all(&dir).map_err(to_error)?;
+        fs::write(dir.join(CONFIG_FILE), &config).map_err(to_error)?;
+
+        let new_run = NewAppRun {
+            id: run_id.clone(),
+            app_name: c.app_name,
+            config,
+            status: DozerStatus::Starting.as_str_name().to_owned(),
+            restart_policy: restart_policy.as_str_name().to_owned(),
+            max_restarts: max_restarts.unwrap_or(constants::MAX_RESTARTS) as i32,
+        };
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        insert_into(app_runs::table)
+            .values(&new_run)
+            .execute(&mut db)
+            .map_err(to_error)?;
+        drop(db);
+
+        self.supervise(run_id.clone(), Launch::Spawn);
+        self.get(&run_id)
+    }
+
+    /// Asks the app to shut down gracefully, killing it if it is still alive after a timeout.
+    pub fn stop(&self, run_id: &str) -> Result<(), ErrorResponse> {
+        let process = self
+            .processes
+            .read()
+            .get(run_id)
+            .cloned()
+            .ok_or_else(|| ErrorResponse {
+                message: format!("Cannot find running dozer with id : {run_id}"),
+            })?;
+        let child_pid = {
+            let pid = process.pid.lock();
+            process.stop_requested.store(true, Ordering::SeqCst);
+            *pid
+        };
+        if child_pid != 0 {
+            interrupt(process, child_pid);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, run_id: &str) -> Result<DozerRun, ErrorResponse> {
+        self.load(run_id).map(DozerRun::from)
+    }
+
+    pub fn list(&self, limit: u32, offset: u32) -> Result<(Vec<DozerRun>, i64), ErrorResponse> {
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        let runs: Vec<AppRun> = app_runs::table
+            .offset(offset.into())
+            .order_by(app_runs::created_at.desc())
+            .limit(limit.into())
+            .load(&mut db)
+            .map_err(to_error)?;
+        let total: i64 = app_runs::table
+            .count()
+            .get_result(&mut db)
+            .map_err(to_error)?;
+        Ok((runs.into_iter().map(DozerRun::from).collect(), total))
+    }
+
+    /// Returns the last `lines` lines written by the app to stdout and stderr.
+    pub fn tail_logs(&self, run_id: &str, lines: u32) -> Result<Vec<String>, ErrorResponse> {
+        self.load(run_id)?;
+        match tail(&self.run_dir(run_id).join(LOG_FILE), lines as usize) {
+            Ok(lines) => Ok(lines),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(to_error(e)),
+        }
+    }
+
+    /// Resumes supervision of the apps that were starting or running when dozer-admin exited.
+    pub fn recover(&self) -> Result<(), ErrorResponse> {
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        let runs: Vec<AppRun> = app_runs::table
+            .filter(app_runs::status.eq_any([
+                DozerStatus::Starting.as_str_name(),
+                DozerStatus::Running.as_str_name(),
+            ]))
+            .load(&mut db)
+            .map_err(to_error)?;
+        drop(db);
+
+        for run in runs {
+            let launch = match run.pid {
+                Some(child_pid) if self.is_run_process(&run.id, child_pid as u32) => {
+                    Launch::Attach(child_pid as u32)
+                }
+                _ => Launch::Lost,
+            };
+            info!("Recovering dozer run {}", run.id);
+            self.supervise(run.id, launch);
+        }
+        Ok(())
+    }
+
+    fn supervise(&self, run_id: String, mut launch: Launch) {
+        let process = Arc::new(Process::default());
+        self.processes
+            .write()
+            .insert(run_id.clone(), process.clone());
+
+        let supervisor = self.clone();
+        thread::spawn(move || {
+            loop {
+                let result = match launch {
+                    Launch::Spawn => supervisor.run(&run_id, &process),
+                    Launch::Attach(child_pid) => supervisor.wait_for(&run_id, child_pid, &process),
+                    Launch::Lost => Err("Exited while dozer-admin was not running".to_owned()),
+                };
+                let restart = supervisor
+                    .on_exit(&run_id, &process, result)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to update dozer run {}: {}", run_id, e.message);
+                        false
+                    });
+                if !restart {
+                    break;
+                }
+
+                thread::sleep(supervisor.settings.restart_delay);
+                if process.stop_requested.load(Ordering::SeqCst) {
+                    if let Err(e) = supervisor.set_status(&run_id, DozerStatus::Stopped) {
+                        error!("Failed to update dozer run {}: {}", run_id, e.message);
+                    }
+                    break;
+                }
+                launch = Launch::Spawn;
+            }
+            supervisor.processes.write().remove(&run_id);
+        });
+    }
+
+    fn run(&self, run_id: &str, process: &Process) -> Result<(), String> {
+        let mut pid = process.pid.lock();
+        if process.stop_requested.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let dir = self.run_dir(run_id);
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE))
+            .map_err(|e| format!("Failed to open log file: {e}"))?;
+        let stdout = log
+            .try_clone()
+            .map_err(|e| format!("Failed to open log file: {e}"))?;
+        let mut child = Command::new(&self.settings.dozer_path)
+            .arg("-c")
+            .arg(CONFIG_FILE)
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .stdout(stdout)
+            .stderr(log)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {e}", self.settings.dozer_path))?;
+        *pid = child.id();
+        drop(pid);
+
+        if let Err(e) = self.set_running(run_id, child.id()) {
+            error!("Failed to update dozer run {}: {}", run_id, e.message);
+        }
+        let exit_status = child.wait();
+        *process.pid.lock() = 0;
+
+        match exit_status {
+            Ok(exit_status) if exit_status.success() => Ok(()),
+            Ok(exit_status) => Err(format!("Dozer exited with {exit_status}")),
+            Err(e) => Err(format!("Failed to wait for dozer: {e}")),
+        }
+    }
+
+    fn wait_for(&self, run_id: &str, child_pid: u32, process: &Arc<Process>) -> Result<(), String> {
+        {
+            let mut pid = process.pid.lock();
+            *pid = child_pid;
+            if process.stop_requested.load(Ordering::SeqCst) {
+                interrupt(process.clone(), child_pid);
+            }
+        }
+        while self.is_run_process(run_id, child_pid) {
+            thread::sleep(Duration::from_secs(1));
+        }
+        *process.pid.lock() = 0;
+        Err("Exited with unknown status after dozer-admin restarted".to_owned())
+    }
+
+    /// Records how the app exited and returns whether it should be restarted.
+    fn on_exit(
+        &self,
+        run_id: &str,
+        process: &Process,
+        result: Result<(), String>,
+    ) -> Result<bool, ErrorResponse> {
+        if process.stop_requested.load(Ordering::SeqCst) {
+            self.set_status(run_id, DozerStatus::Stopped)?;
+            return Ok(false);
+        }
+
+        let run = self.load(run_id)?;
+        let last_error = result.err().map(|message| {
+            match tail(&self.run_dir(run_id).join(LOG_FILE), 1)
+                .ok()
+                .and_then(|lines| lines.into_iter().next())
+            {
+                Some(line) => format!("{message}: {line}"),
+                None => message,
+            }
+        });
+        let restart = match RestartPolicy::from_str_name(&run.restart_policy) {
+            Some(RestartPolicy::Always) => true,
+            Some(RestartPolicy::OnFailure) => last_error.is_some(),
+            Some(RestartPolicy::Never) | None => false,
+        } && run.restart_count < run.max_restarts;
+        let new_status = if restart {
+            DozerStatus::Starting
+        } else if last_error.is_some() {
+            DozerStatus::Failed
+        } else {
+            DozerStatus::Stopped
+        };
+
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        diesel::update(app_runs::table.find(run_id))
+            .set((
+                app_runs::status.eq(new_status.as_str_name()),
+                app_runs::restart_count.eq(run.restart_count + restart as i32),
+                app_runs::last_error.eq(last_error),
+                app_runs::pid.eq(None::<i32>),
+                app_runs::updated_at.eq(now),
+            ))
+            .execute(&mut db)
+            .map_err(to_error)?;
+        Ok(restart)
+    }
+
+    fn set_running(&self, run_id: &str, child_pid: u32) -> Result<(), ErrorResponse> {
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        diesel::update(app_runs::table.find(run_id))
+            .set((
+                app_runs::status.eq(DozerStatus::Running.as_str_name()),
+                app_runs::pid.eq(child_pid as i32),
+                app_runs::updated_at.eq(now),
+            ))
+            .execute(&mut db)
+            .map_err(to_error)?;
+        Ok(())
+    }
+
+    fn set_status(&self, run_id: &str, new_status: DozerStatus) -> Result<(), ErrorResponse> {
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        diesel::update(app_runs::table.find(run_id))
+            .set((
+                app_runs::status.eq(new_status.as_str_name()),
+                app_runs::pid.eq(None::<i32>),
+                app_runs::updated_at.eq(now),
+            ))
+            .execute(&mut db)
+            .map_err(to_error)?;
+        Ok(())
+    }
+
+    fn load(&self, run_id: &str) -> Result<AppRun, ErrorResponse> {
+        let mut db = self.db_pool.get().map_err(to_error)?;
+        app_runs::table
+            .find(run_id)
+            .first(&mut db)
+            .map_err(to_error)
+    }
+
+    fn run_dir(&self, run_id: &str) -> PathBuf {
+        self.settings.runs_dir.join(run_id)
+    }
+
+    /// Checks that `pid` is still the app of this run and not an unrelated process that reused
+    /// the pid, by comparing its working directory with the run directory.
+    ///
+    /// Only supported where `/proc` is available, elsewhere the app is considered gone.
+    fn is_run_process(&self, run_id: &str, pid: u32) -> bool {
+        if !is_alive(pid) {
+            return false;
+        }
+        match (
+            fs::read_link(format!("/proc/{pid}/cwd")),
+            fs::canonicalize(self.run_dir(run_id)),
+        ) {
+            (Ok(cwd), Ok(run_dir)) => cwd == run_dir,
+            _ => false,
+        }
+    }
+}
+
+fn to_error(e: impl ToString) -> ErrorResponse {
+    ErrorResponse {
+        message: e.to_string(),
+    }
+}
+
+/// Sends SIGINT to the child, then SIGKILL if it is still running after a timeout.
+fn interrupt(process: Arc<Process>, child_pid: u32) {
+    send_signal(child_pid, Some(Signal::SIGINT));
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(constants::STOP_TIMEOUT_SECS));
+        if *process.pid.lock() == child_pid {
+            send_signal(child_pid, Some(Signal::SIGKILL));
+        }
+    });
+}
+
+fn send_signal(pid: u32, signal: Option<Signal>) -> bool {
+    signal::kill(Pid::from_raw(pid as i32), signal).is_ok()
+}
+
+fn is_alive(pid: u32) -> bool {
+    send_signal(pid, None)
+}
+
+fn tail(path: &Path, lines: usize) -> io::Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let start = file
+        .metadata()?
+        .len()
+        .saturating_sub(constants::MAX_LOG_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = vec![];
+    file.read_to_end(&mut bytes)?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut all_lines: Vec<&str> = text.lines().filter(|line| !line.is_empty()).collect();
+    if start > 0 && !all_lines.is_empty() {
+        // The first line is probably cut in the middle
+        all_lines.remove(0);
+    }
+    let skip = all_lines.len().saturating_sub(lines);
+    Ok(all_lines[skip..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
+}
@@ -8,6 +8,7 @@ mod grpc_service {
         AppResponse, CreateAppRequest, ListAppRequest, ListAppResponse, UpdateAppRequest,
     };
     use crate::services::application_service::AppService;
+    use crate::services::supervisor::SupervisorSettings;
     use crate::tests::utils::database_url_for_test_env;
     use crate::tests::utils::{establish_test_connection, get_setup_ids};
     #[test]
@@ -15,7 +16,7 @@ mod grpc_service {
         let test_db_connection = database_url_for_test_env();
         let db_pool = establish_test_connection(test_db_connection);
         let setup_ids = get_setup_ids();
-        let application_service = AppService::new(db_pool);
+        let application_service = AppService::new(db_pool, SupervisorSettings::default());
 
         let config = generate_connection("Postgres");
         let config = Config {
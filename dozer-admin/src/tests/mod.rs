@@ -1,4 +1,5 @@
 pub mod applications;
 pub mod connections;
+pub mod supervisor;
 #[cfg(test)]
 pub mod utils;
@@ -0,0 +1,218 @@
+#[cfg(all(test, unix))]
+mod grpc_service {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use diesel::insert_into;
+    use diesel::prelude::*;
+    use dozer_types::models::app_config::Config;
+
+    use crate::db::app_run::NewAppRun;
+    use crate::db::pool::DbPool;
+    use crate::db::schema::app_runs;
+    use crate::server::dozer_admin_grpc::{
+        DozerRun, DozerStatus, DozerStatusRequest, ListDozerRunsRequest, RestartPolicy,
+        StartRequest, StopRequest, TailDozerLogsRequest,
+    };
+    use crate::services::application_service::AppService;
+    use crate::services::supervisor::SupervisorSettings;
+    use crate::tests::utils::{database_url_for_test_env, establish_single_test_connection};
+
+    fn setup(script: &str) -> (AppService, PathBuf) {
+        setup_with_pool(
+            script,
+            establish_single_test_connection(database_url_for_test_env()),
+            Duration::ZERO,
+        )
+    }
+
+    fn setup_with_pool(
+        script: &str,
+        db_pool: DbPool,
+        restart_delay: Duration,
+    ) -> (AppService, PathBuf) {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&dir).unwrap();
+        let dozer_path = dir.join("dozer");
+        fs::write(&dozer_path, format!("#!/bin/sh\n{script}")).unwrap();
+        fs::set_permissions(&dozer_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let application_service = AppService::new(
+            db_pool,
+            SupervisorSettings {
+                dozer_path: dozer_path.to_string_lossy().to_string(),
+                runs_dir: dir.join("runs"),
+                restart_delay,
+            },
+        );
+        (application_service, dir)
+    }
+
+    fn start(application_service: &AppService, restart_policy: RestartPolicy) -> String {
+        let config = Config {
+            app_name: "supervised_app".to_owned(),
+            ..Default::default()
+        };
+        application_service
+            .start_dozer(StartRequest {
+                config: serde_yaml::to_string(&config).unwrap(),
+                restart_policy: restart_policy as i32,
+                max_restarts: Some(2),
+            })
+            .unwrap()
+            .id
+    }
+
+    fn wait_for_status(
+        application_service: &AppService,
+        id: &str,
+        status: DozerStatus,
+    ) -> DozerRun {
+        let started = Instant::now();
+        loop {
+            let run = application_service
+                .get_dozer_status(DozerStatusRequest { id: id.to_owned() })
+                .unwrap();
+            if run.status == status as i32 {
+                return run;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "Dozer run is still {:?}",
+                DozerStatus::from_i32(run.status)
+            );
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    pub fn restart_on_failure() {
+        let (application_service, dir) = setup("echo \"starting with $2\"\necho boom >&2\nexit 1");
+        let id = start(&application_service, RestartPolicy::OnFailure);
+
+        // Initial run and two restarts
+        let run = wait_for_status(&application_service, &id, DozerStatus::Failed);
+        assert_eq!(run.app_name, "supervised_app");
+        assert_eq!(run.restart_count, 2);
+        assert_eq!(
+            run.last_error.unwrap(),
+            "Dozer exited with exit status: 1: boom"
+        );
+
+        let logs = application_service
+            .tail_dozer_logs(TailDozerLogsRequest {
+                id: id.clone(),
+                lines: None,
+            })
+            .unwrap();
+        assert_eq!(
+            logs.lines,
+            ["starting with dozer-config.yaml", "boom"].repeat(3)
+        );
+        let logs = application_service
+            .tail_dozer_logs(TailDozerLogsRequest {
+                id: id.clone(),
+                lines: Some(1),
+            })
+            .unwrap();
+        assert_eq!(logs.lines, vec!["boom".to_owned()]);
+
+        let runs = application_service
+            .list_dozer_runs(ListDozerRunsRequest {
+                limit: Some(10),
+                offset: Some(0),
+            })
+            .unwrap();
+        assert_eq!(runs.runs.len(), 1);
+        assert_eq!(runs.runs[0].id, id);
+        assert_eq!(runs.pagination.unwrap().total, 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn never_restart_after_success() {
+        let (application_service, dir) = setup("exit 0");
+        let id = start(&application_service, RestartPolicy::OnFailure);
+
+        let run = wait_for_status(&application_service, &id, DozerStatus::Stopped);
+        assert_eq!(run.restart_count, 0);
+        assert_eq!(run.last_error, None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn stop() {
+        let (application_service, dir) = setup("exec sleep 30");
+        let id = start(&application_service, RestartPolicy::Always);
+
+        wait_for_status(&application_service, &id, DozerStatus::Running);
+        application_service
+            .stop_dozer(StopRequest { id: id.clone() })
+            .unwrap();
+        let run = wait_for_status(&application_service, &id, DozerStatus::Stopped);
+        assert_eq!(run.restart_count, 0);
+        assert_eq!(run.last_error, None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn stop_while_restarting() {
+        let (application_service, dir) = setup_with_pool(
+            "exit 1",
+            establish_single_test_connection(database_url_for_test_env()),
+            Duration::from_secs(1),
+        );
+        let id = start(&application_service, RestartPolicy::Always);
+
+        // The app exits at once and waits to be restarted, without a process to signal
+        thread::sleep(Duration::from_millis(300));
+        application_service
+            .stop_dozer(StopRequest { id: id.clone() })
+            .unwrap();
+        let run = wait_for_status(&application_service, &id, DozerStatus::Stopped);
+        assert_eq!(run.restart_count, 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn recover_ignores_reused_pid() {
+        let db_pool = establish_single_test_connection(database_url_for_test_env());
+        let (application_service, dir) = setup_with_pool("exit 0", db_pool.clone(), Duration::ZERO);
+
+        // A run left behind by a previous dozer-admin, whose pid now belongs to another process
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut db = db_pool.get().unwrap();
+        insert_into(app_runs::table)
+            .values(&NewAppRun {
+                id: id.clone(),
+                app_name: "supervised_app".to_owned(),
+                config: String::new(),
+                status: DozerStatus::Running.as_str_name().to_owned(),
+                restart_policy: RestartPolicy::Never.as_str_name().to_owned(),
+                max_restarts: 0,
+            })
+            .execute(&mut db)
+            .unwrap();
+        diesel::update(app_runs::table.find(&id))
+            .set(app_runs::pid.eq(std::process::id() as i32))
+            .execute(&mut db)
+            .unwrap();
+        drop(db);
+
+        application_service.recover_dozer_runs().unwrap();
+        let run = wait_for_status(&application_service, &id, DozerStatus::Failed);
+        assert_eq!(
+            run.last_error.unwrap(),
+            "Exited while dozer-admin was not running"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
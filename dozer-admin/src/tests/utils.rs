@@ -36,6 +36,16 @@ pub fn establish_test_connection(database_url: String) -> DbPool {
         .expect("Failed to create DB pool.")
 }
 
+/// Pool sharing a single connection, so that an in-memory database is visible to every thread.
+pub fn establish_single_test_connection(database_url: String) -> DbPool {
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+    r2d2::Pool::builder()
+        .max_size(1)
+        .connection_customizer(Box::new(TestConnectionCustomizer))
+        .build(manager)
+        .expect("Failed to create DB pool.")
+}
+
 fn prepare_test_db(connection: &mut SqliteConnection, config_id: TestConfigId) {
     run_migrations(connection).unwrap();
     setup_data(connection, config_id)
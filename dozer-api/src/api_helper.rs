@@ -1,9 +1,9 @@
 use crate::auth::Access;
 use crate::errors::{ApiError, AuthError};
 use dozer_cache::cache::expression::QueryExpression;
-use dozer_cache::cache::RecordWithId;
+use dozer_cache::cache::{Plan, RecordWithId};
 use dozer_cache::{AccessFilter, CacheReader};
-use dozer_types::types::Schema;
+use dozer_types::types::{Record, Schema};
 
 pub fn get_record(
     cache_reader: &CacheReader,
@@ -24,25 +24,62 @@ pub fn get_records_count(
     access: Option<Access>,
 ) -> Result<usize, ApiError> {
     let access_filter = get_access_filter(access)?;
+    if exp.aggregation.is_some() {
+        // Count the groups.
+        return cache_reader
+            .aggregate(endpoint_name, exp, access_filter)
+            .map(|(_, records)| records.len())
+            .map_err(ApiError::CountFailed);
+    }
     cache_reader
         .count(endpoint_name, exp, access_filter)
         .map_err(ApiError::CountFailed)
 }
 
-/// Get multiple records
+/// Get multiple records, and the encoded cursor of the next page if there's one
 pub fn get_records<'a>(
     cache_reader: &'a CacheReader,
     endpoint_name: &str,
     exp: &mut QueryExpression,
     access: Option<Access>,
-) -> Result<(&'a Schema, Vec<RecordWithId>), ApiError> {
+) -> Result<(&'a Schema, Vec<RecordWithId>, Option<String>), ApiError> {
     let access_filter = get_access_filter(access)?;
-    cache_reader
+    let (schema, records) = cache_reader
         .query(endpoint_name, exp, access_filter)
+        .map_err(ApiError::QueryFailed)?;
+    let next_cursor = cache_reader
+        .next_cursor(endpoint_name, exp, &records)
+        .map_err(ApiError::QueryFailed)?;
+    Ok((schema, records, next_cursor.map(|cursor| cursor.encode())))
+}
+
+/// Get one record per group of `exp.aggregation`, with the schema of the output
+pub fn get_aggregation(
+    cache_reader: &CacheReader,
+    endpoint_name: &str,
+    exp: &mut QueryExpression,
+    access: Option<Access>,
+) -> Result<(Schema, Vec<Record>), ApiError> {
+    let access_filter = get_access_filter(access)?;
+    cache_reader
+        .aggregate(endpoint_name, exp, access_filter)
+        .map_err(ApiError::QueryFailed)
+}
+
+/// Get the plan the cache would execute for `exp`
+pub fn get_plan(
+    cache_reader: &CacheReader,
+    endpoint_name: &str,
+    exp: &mut QueryExpression,
+    access: Option<Access>,
+) -> Result<Plan, ApiError> {
+    let access_filter = get_access_filter(access)?;
+    cache_reader
+        .explain(endpoint_name, exp, access_filter)
         .map_err(ApiError::QueryFailed)
 }
 
-fn get_access_filter(access: Option<Access>) -> Result<AccessFilter, ApiError> {
+pub fn get_access_filter(access: Option<Access>) -> Result<AccessFilter, ApiError> {
     match access {
         None | Some(Access::All) => Ok(AccessFilter {
             filter: None,
@@ -3,13 +3,15 @@ use actix_web::{
     web::{self, ReqData},
     Error, HttpMessage, HttpRequest, HttpResponse,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
+use actix_web_httpauth::extractors::bearer::{BearerAuth, Config};
+use actix_web_httpauth::extractors::AuthenticationError;
 use dozer_types::{
     models::api_security::ApiSecurity,
     serde_json::{json, Value},
 };
 
 use crate::errors::{ApiError, AuthError};
+use crate::graphql::GRAPHQL_WS_PATH;
 
 use super::{Access, Authorizer};
 
@@ -46,10 +48,21 @@ fn get_secret(req: &HttpRequest) -> Result<&str, AuthError> {
         ApiSecurity::Jwt(secret) => Ok(secret.as_str()),
     }
 }
+
+/// Requests without a bearer token are rejected, except GraphQL WebSocket upgrades, which can
+/// send the token in their `connection_init` message instead.
 pub async fn validate(
     req: ServiceRequest,
-    credentials: BearerAuth,
+    credentials: Option<BearerAuth>,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    let credentials = match credentials {
+        Some(credentials) => credentials,
+        None if req.path() == GRAPHQL_WS_PATH => return Ok(req),
+        None => {
+            let config = req.app_data::<Config>().cloned().unwrap_or_default();
+            return Err((AuthenticationError::from(config).into(), req));
+        }
+    };
     let api_security = req
         .app_data::<ApiSecurity>()
         .expect("We only validate bearer tokens if ApiSecurity is set");
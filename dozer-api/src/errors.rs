@@ -39,6 +39,8 @@ pub enum ApiError {
     TypeError(#[from] TypeError),
     #[error("Failed to bind to address {0}: {1}")]
     FailedToBindToAddress(String, #[source] std::io::Error),
+    #[error("Generation error: {0}")]
+    GenerationError(#[from] GenerationError),
 }
 
 impl ApiError {
@@ -115,6 +117,8 @@ pub enum GenerationError {
     MissingCountMethod(String),
     #[error("Missing query method for: {0}")]
     MissingQueryMethod(String),
+    #[error("Failed to build GraphQL schema: {0}")]
+    GraphQLSchema(#[source] async_graphql::dynamic::SchemaError),
 }
 
 #[derive(Error, Debug)]
@@ -149,7 +153,8 @@ impl actix_web::error::ResponseError for ApiError {
             | ApiError::CacheNotFound(_)
             | ApiError::QueryFailed(_)
             | ApiError::CountFailed(_)
-            | ApiError::FailedToBindToAddress(_, _) => StatusCode::INTERNAL_SERVER_ERROR,
+            | ApiError::FailedToBindToAddress(_, _)
+            | ApiError::GenerationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
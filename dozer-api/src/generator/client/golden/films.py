@@ -0,0 +1,266 @@
+# Dozer generated client for endpoint `films`.
+#
+# Depends on `requests` and `grpcio`, and the modules generated from `common.proto` and `types.proto`
+# by `grpc_tools.protoc`.
+
+import dataclasses
+import json
+from datetime import date, datetime, timezone
+from decimal import Decimal
+from typing import Any, Dict, Iterator, List, Literal, Optional, Tuple
+from urllib.parse import quote
+
+import grpc
+import requests
+
+import common_pb2
+import common_pb2_grpc
+import types_pb2
+
+NEXT_CURSOR_HEADER = "X-Dozer-Next-Cursor"
+
+
+@dataclasses.dataclass
+class Point:
+    x: float
+    y: float
+
+
+@dataclasses.dataclass
+class Film:
+    film_id: int
+    description: Optional[str]
+    rental_rate: Optional[str]
+    release_year: Optional[int]
+    # Records with same primary key will have increasing version.
+    dozer_record_version: int
+
+    @staticmethod
+    def from_json(data: Dict[str, Any]) -> "Film":
+        return Film(
+            film_id=data["film_id"],
+            description=data["description"],
+            rental_rate=data["rental_rate"],
+            release_year=data["release_year"],
+            dozer_record_version=data["__dozer_record_version"],
+        )
+
+    @staticmethod
+    def from_grpc(record: Any) -> "Film":
+        values = [_value_from_grpc(value) for value in record.values]
+        return Film(
+            film_id=values[0],
+            description=values[1],
+            rental_rate=values[2],
+            release_year=values[3],
+            dozer_record_version=record.version,
+        )
+
+
+@dataclasses.dataclass
+class FilmWithId:
+    """A record with its id in cache."""
+
+    id: int
+    record: Film
+
+    @staticmethod
+    def from_json(data: Dict[str, Any]) -> "FilmWithId":
+        return FilmWithId(id=data["__dozer_record_id"], record=Film.from_json(data))
+
+
+@dataclasses.dataclass
+class FilmEvent:
+    typ: Literal["INSERT", "DELETE", "UPDATE"]
+    # Old record data, only applicable for UPDATE type.
+    old: Optional[Film]
+    new: Optional[Film]
+    # New record id, only applicable for INSERT type.
+    new_id: Optional[int]
+
+    @staticmethod
+    def from_grpc(operation: Any) -> "FilmEvent":
+        return FilmEvent(
+            typ=types_pb2.OperationType.Name(operation.typ),
+            old=Film.from_grpc(operation.old) if operation.HasField("old") else None,
+            new=Film.from_grpc(operation.new) if operation.HasField("new") else None,
+            new_id=operation.new_id if operation.HasField("new_id") else None,
+        )
+
+
+EventType = Literal["ALL", "INSERT_ONLY", "UPDATE_ONLY", "DELETE_ONLY"]
+ComparisonOperator = Literal["$eq", "$lt", "$lte", "$gt", "$gte"]
+# Full text operators need a full text index on the field.
+TextOperator = Literal["$eq", "$lt", "$lte", "$gt", "$gte", "$contains", "$matches_any", "$matches_all"]
+# Operands are like `{"center": Point, "max_distance": meters}`, `{"min": Point, "max": Point}`
+# and `{"center": Point, "radius": meters}`, with points in degrees.
+GeoOperator = Literal["$near", "$within_box", "$within_radius"]
+
+
+class FilmFilter:
+    """Builds a filter matching records that satisfy all the added conditions."""
+
+    def __init__(self) -> None:
+        self._conditions: List[Dict[str, Any]] = []
+
+    def film_id(self, operator: ComparisonOperator, value: int) -> "FilmFilter":
+        return self._condition("film_id", operator, value)
+
+    def description(self, operator: TextOperator, value: str) -> "FilmFilter":
+        return self._condition("description", operator, value)
+
+    def rental_rate(self, operator: TextOperator, value: str) -> "FilmFilter":
+        return self._condition("rental_rate", operator, value)
+
+    def release_year(self, operator: ComparisonOperator, value: int) -> "FilmFilter":
+        return self._condition("release_year", operator, value)
+
+    def build(self) -> Optional[Dict[str, Any]]:
+        """Returns `None` if no condition was added."""
+        if not self._conditions:
+            return None
+        if len(self._conditions) == 1:
+            return self._conditions[0]
+        return {"$and": self._conditions}
+
+    def _condition(self, field: str, operator: str, value: Any) -> "FilmFilter":
+        self._conditions.append({field: {operator: _to_json(value)}})
+        return self
+
+
+FilmField = Literal["film_id", "description", "rental_rate", "release_year"]
+
+
+@dataclasses.dataclass
+class FilmQuery:
+    filter: Optional[FilmFilter] = None
+    order_by: List[Tuple[FilmField, Literal["asc", "desc"]]] = dataclasses.field(default_factory=list)
+    limit: Optional[int] = None
+    skip: Optional[int] = None
+    # The cursor returned with the previous page, can't be used with `skip`.
+    cursor: Optional[str] = None
+
+    def to_json(self) -> Dict[str, Any]:
+        query: Dict[str, Any] = {}
+        condition = self.filter.build() if self.filter else None
+        if condition is not None:
+            query["$filter"] = condition
+        if self.order_by:
+            query["$order_by"] = dict(self.order_by)
+        if self.limit is not None:
+            query["$limit"] = self.limit
+        if self.skip is not None:
+            query["$skip"] = self.skip
+        if self.cursor is not None:
+            query["$cursor"] = self.cursor
+        return query
+
+
+class FilmsClient:
+    ENDPOINT = "films"
+    PATH = "/films"
+
+    def __init__(
+        self,
+        rest_url: str = "http://localhost:8080",
+        grpc_url: str = "localhost:50051",
+        token: Optional[str] = None,
+    ) -> None:
+        self.rest_url = rest_url.rstrip("/")
+        self.grpc_url = grpc_url
+        self.token = token
+
+    def count(self, filter: Optional[FilmFilter] = None) -> int:
+        return self._post("count", FilmQuery(filter=filter)).json()
+
+    def query(self, query: Optional[FilmQuery] = None) -> Tuple[List[FilmWithId], Optional[str]]:
+        """Returns the records and the cursor of the next page, which is `None` on the last page."""
+        response = self._post("query", query or FilmQuery())
+        records = [FilmWithId.from_json(data) for data in response.json()]
+        return records, response.headers.get(NEXT_CURSOR_HEADER)
+
+    def get(self, film_id: int) -> Optional[FilmWithId]:
+        """Returns `None` if there's no record with the primary key."""
+        response = requests.get(self._url(quote(str(film_id), safe="")), headers=self._headers())
+        if response.status_code == 404:
+            return None
+        response.raise_for_status()
+        return FilmWithId.from_json(response.json())
+
+    def on_event(
+        self, event_type: EventType = "ALL", filter: Optional[FilmFilter] = None
+    ) -> Iterator[FilmEvent]:
+        """Yields the events of the endpoint that match `filter`."""
+        condition = filter.build() if filter else None
+        request = common_pb2.OnEventRequest(
+            type=types_pb2.EventType.Value(event_type),
+            endpoint=self.ENDPOINT,
+            filter=None if condition is None else json.dumps(condition),
+        )
+        metadata = [("authorization", f"Bearer {self.token}")] if self.token else None
+        with grpc.insecure_channel(self.grpc_url) as channel:
+            stub = common_pb2_grpc.CommonGrpcServiceStub(channel)
+            for operation in stub.OnEvent(request, metadata=metadata):
+                yield FilmEvent.from_grpc(operation)
+
+    def _post(self, method: str, query: FilmQuery) -> requests.Response:
+        response = requests.post(self._url(method), json=query.to_json(), headers=self._headers())
+        response.raise_for_status()
+        return response
+
+    def _url(self, segment: str) -> str:
+        return f"{self.rest_url}{self.PATH}/{segment}"
+
+    def _headers(self) -> Dict[str, str]:
+        return {"Authorization": f"Bearer {self.token}"} if self.token else {}
+
+
+def _value_from_grpc(value: Any) -> Any:
+    """Converts a gRPC value to the JSON representation of the REST API."""
+    kind = value.WhichOneof("value")
+    if kind is None:
+        return None
+    if kind == "decimal_value":
+        # `RustDecimal` doesn't carry the scale.
+        raise ValueError("Decimal values are not supported in events")
+    if kind == "timestamp_value":
+        return value.timestamp_value.ToDatetime(tzinfo=timezone.utc)
+    if kind == "point_value":
+        return {"x": value.point_value.x, "y": value.point_value.y}
+    return getattr(value, kind)
+
+
+def _to_json(value: Any) -> Any:
+    if isinstance(value, (datetime, date)):
+        return value.isoformat()
+    if isinstance(value, Decimal):
+        return str(value)
+    if isinstance(value, bytes):
+        return list(value)
+    if isinstance(value, Point):
+        return {"x": value.x, "y": value.y}
+    if isinstance(value, dict):
+        return {key: _to_json(item) for key, item in value.items()}
+    return value
+
+
+def _bytes(value: Any) -> Optional[bytes]:
+    return None if value is None else bytes(value)
+
+
+def _decimal(value: Any) -> Optional[Decimal]:
+    return None if value is None else Decimal(value)
+
+
+def _timestamp(value: Any) -> Optional[datetime]:
+    if value is None or isinstance(value, datetime):
+        return value
+    return datetime.fromisoformat(value.replace("Z", "+00:00"))
+
+
+def _date(value: Any) -> Optional[date]:
+    return None if value is None else date.fromisoformat(value)
+
+
+def _point(value: Any) -> Optional[Point]:
+    return None if value is None else Point(x=value["x"], y=value["y"])
@@ -0,0 +1,490 @@
+// Dozer generated client for endpoint `films`.
+//
+// Depends on `dozer-types` and `reqwest` with the `json` feature.
+#![allow(dead_code, unused_imports)]
+
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use dozer_types::grpc_types::common::common_grpc_service_client::CommonGrpcServiceClient;
+use dozer_types::grpc_types::common::OnEventRequest;
+use dozer_types::grpc_types::types::{value, EventType, Operation, OperationType, Record, Value};
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde::ser::SerializeMap;
+use dozer_types::serde::{Deserialize, Serialize, Serializer};
+use dozer_types::serde_json::{self, json, Map, Value as JsonValue};
+use dozer_types::thiserror::{self, Error};
+use dozer_types::tonic::{self, Streaming};
+
+const NEXT_CURSOR_HEADER: &str = "X-Dozer-Next-Cursor";
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("gRPC transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+    #[error("gRPC error: {0}")]
+    Grpc(#[from] tonic::Status),
+    #[error("Token cannot be sent as gRPC metadata")]
+    InvalidToken,
+    #[error("Unexpected value of field `{0}`")]
+    UnexpectedValue(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Film {
+    #[serde(rename = "film_id")]
+    pub film_id: u64,
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+    #[serde(rename = "rental_rate")]
+    pub rental_rate: Option<String>,
+    #[serde(rename = "release_year")]
+    pub release_year: Option<u64>,
+    /// Records with same primary key will have increasing version.
+    #[serde(rename = "__dozer_record_version")]
+    pub dozer_record_version: u32,
+}
+
+impl Film {
+    fn from_record(record: Record) -> Result<Self, ClientError> {
+        let mut values = record.values.into_iter();
+        Ok(Self {
+            film_id: required(field(values.next(), "film_id", convert::uint)?, "film_id")?,
+            description: field(values.next(), "description", convert::string)?,
+            rental_rate: field(values.next(), "rental_rate", convert::string)?,
+            release_year: field(values.next(), "release_year", convert::uint)?,
+            dozer_record_version: record.version,
+        })
+    }
+}
+
+/// A record with its id in cache.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct FilmWithId {
+    #[serde(rename = "__dozer_record_id")]
+    pub id: u64,
+    #[serde(flatten)]
+    pub record: Film,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilmEvent {
+    pub typ: OperationType,
+    /// Old record data, only applicable for UPDATE type.
+    pub old: Option<Film>,
+    pub new: Option<Film>,
+    /// New record id, only applicable for INSERT type.
+    pub new_id: Option<u64>,
+}
+
+impl FilmEvent {
+    fn from_operation(operation: Operation) -> Result<Self, ClientError> {
+        Ok(Self {
+            typ: operation.typ(),
+            old: operation.old.map(Film::from_record).transpose()?,
+            new: operation.new.map(Film::from_record).transpose()?,
+            new_id: operation.new_id,
+        })
+    }
+}
+
+/// A filter operator, written as `{"$op": value}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum Comparison<T> {
+    #[serde(rename = "$eq")]
+    Eq(T),
+    #[serde(rename = "$lt")]
+    Lt(T),
+    #[serde(rename = "$lte")]
+    Lte(T),
+    #[serde(rename = "$gt")]
+    Gt(T),
+    #[serde(rename = "$gte")]
+    Gte(T),
+}
+
+/// Comparisons and full text operators, which need a full text index on the field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum TextOperator {
+    #[serde(rename = "$eq")]
+    Eq(String),
+    #[serde(rename = "$lt")]
+    Lt(String),
+    #[serde(rename = "$lte")]
+    Lte(String),
+    #[serde(rename = "$gt")]
+    Gt(String),
+    #[serde(rename = "$gte")]
+    Gte(String),
+    #[serde(rename = "$contains")]
+    Contains(String),
+    #[serde(rename = "$matches_any")]
+    MatchesAny(String),
+    #[serde(rename = "$matches_all")]
+    MatchesAll(String),
+}
+
+/// Geo operators, with points in degrees and distances in meters.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum GeoOperator {
+    #[serde(rename = "$near")]
+    Near { center: Point, max_distance: f64 },
+    #[serde(rename = "$within_box")]
+    WithinBox { min: Point, max: Point },
+    #[serde(rename = "$within_radius")]
+    WithinRadius { center: Point, radius: f64 },
+}
+
+/// Builds a filter matching records that satisfy all the added conditions.
+#[derive(Debug, Clone, Default)]
+pub struct FilmFilter {
+    conditions: Vec<JsonValue>,
+}
+
+impl FilmFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn film_id(self, operator: Comparison<u64>) -> Self {
+        self.condition("film_id", operator)
+    }
+
+    pub fn description(self, operator: TextOperator) -> Self {
+        self.condition("description", operator)
+    }
+
+    pub fn rental_rate(self, operator: TextOperator) -> Self {
+        self.condition("rental_rate", operator)
+    }
+
+    pub fn release_year(self, operator: Comparison<u64>) -> Self {
+        self.condition("release_year", operator)
+    }
+
+    /// Returns `None` if no condition was added.
+    pub fn build(&self) -> Option<JsonValue> {
+        match self.conditions.as_slice() {
+            [] => None,
+            [condition] => Some(condition.clone()),
+            conditions => Some(json!({ "$and": conditions })),
+        }
+    }
+
+    fn condition(mut self, field: &str, operator: impl Serialize) -> Self {
+        let operator = serde_json::to_value(operator).expect("operators are serializable");
+        let mut condition = Map::new();
+        condition.insert(field.to_string(), operator);
+        self.conditions.push(JsonValue::Object(condition));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilmField {
+    FilmId,
+    Description,
+    RentalRate,
+    ReleaseYear,
+}
+
+impl FilmField {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilmField::FilmId => "film_id",
+            FilmField::Description => "description",
+            FilmField::RentalRate => "rental_rate",
+            FilmField::ReleaseYear => "release_year",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FilmQuery {
+    pub filter: FilmFilter,
+    pub order_by: Vec<(FilmField, SortDirection)>,
+    pub limit: Option<usize>,
+    pub skip: Option<usize>,
+    /// The cursor returned with the previous page, can't be used with `skip`.
+    pub cursor: Option<String>,
+}
+
+impl Serialize for FilmQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(filter) = self.filter.build() {
+            map.serialize_entry("$filter", &filter)?;
+        }
+        if !self.order_by.is_empty() {
+            map.serialize_entry("$order_by", &OrderBy(&self.order_by))?;
+        }
+        if let Some(limit) = self.limit {
+            map.serialize_entry("$limit", &limit)?;
+        }
+        if let Some(skip) = self.skip {
+            map.serialize_entry("$skip", &skip)?;
+        }
+        if let Some(cursor) = &self.cursor {
+            map.serialize_entry("$cursor", cursor)?;
+        }
+        map.end()
+    }
+}
+
+/// Keeps the order of the sort options, which `Map` may not.
+struct OrderBy<'a>(&'a [(FilmField, SortDirection)]);
+
+impl Serialize for OrderBy<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|(field, direction)| (field.name(), direction)),
+        )
+    }
+}
+
+/// Stream of events returned by `FilmsClient::on_event`.
+pub struct FilmEvents(Streaming<Operation>);
+
+impl FilmEvents {
+    /// Returns `None` when the stream ends.
+    pub async fn next(&mut self) -> Result<Option<FilmEvent>, ClientError> {
+        match self.0.message().await? {
+            Some(operation) => FilmEvent::from_operation(operation).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FilmsClient {
+    http: reqwest::Client,
+    rest_url: String,
+    grpc_url: String,
+    token: Option<String>,
+}
+
+impl FilmsClient {
+    pub const ENDPOINT: &'static str = "films";
+    pub const PATH: &'static str = "/films";
+
+    /// `rest_url` and `grpc_url` are like `http://localhost:8080` and `http://localhost:50051`.
+    pub fn new(rest_url: impl Into<String>, grpc_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            rest_url: rest_url.into(),
+            grpc_url: grpc_url.into(),
+            token: None,
+        }
+    }
+
+    /// Sends `token` as a bearer token with every request.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub async fn count(&self, filter: &FilmFilter) -> Result<u64, ClientError> {
+        let query = FilmQuery {
+            filter: filter.clone(),
+            ..Default::default()
+        };
+        let response = self.post("count", &query).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Returns the records and the cursor of the next page, which is `None` on the last page.
+    pub async fn query(
+        &self,
+        query: &FilmQuery,
+    ) -> Result<(Vec<FilmWithId>, Option<String>), ClientError> {
+        let response = self.post("query", query).await?;
+        let next_cursor = response
+            .headers()
+            .get(NEXT_CURSOR_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok((response.json().await?, next_cursor))
+    }
+
+    /// Returns `None` if there's no record with the primary key.
+    pub async fn get(
+        &self,
+        film_id: u64,
+    ) -> Result<Option<FilmWithId>, ClientError> {
+        let request = self.http.get(self.url(&film_id.to_string())?);
+        let response = self.authorize(request).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// Subscribes to the events of the endpoint that match `filter`.
+    pub async fn on_event(
+        &self,
+        event_type: EventType,
+        filter: &FilmFilter,
+    ) -> Result<FilmEvents, ClientError> {
+        let mut client = CommonGrpcServiceClient::connect(self.grpc_url.clone()).await?;
+        let mut request = tonic::Request::new(OnEventRequest {
+            r#type: event_type as i32,
+            endpoint: Self::ENDPOINT.to_string(),
+            filter: filter.build().map(|filter| filter.to_string()),
+        });
+        if let Some(token) = &self.token {
+            let value = format!("Bearer {token}")
+                .parse()
+                .map_err(|_| ClientError::InvalidToken)?;
+            request.metadata_mut().insert("authorization", value);
+        }
+        let stream = client.on_event(request).await?.into_inner();
+        Ok(FilmEvents(stream))
+    }
+
+    async fn post(
+        &self,
+        method: &str,
+        query: &FilmQuery,
+    ) -> Result<reqwest::Response, ClientError> {
+        let request = self.http.post(self.url(method)?).json(query);
+        Ok(self.authorize(request).send().await?.error_for_status()?)
+    }
+
+    fn url(&self, segment: &str) -> Result<reqwest::Url, ClientError> {
+        let url = format!("{}{}", self.rest_url.trim_end_matches('/'), Self::PATH);
+        let mut url =
+            reqwest::Url::parse(&url).map_err(|_| ClientError::InvalidUrl(url.clone()))?;
+        url.path_segments_mut()
+            .map_err(|_| ClientError::InvalidUrl(self.rest_url.clone()))?
+            .push(segment);
+        Ok(url)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+fn field<T>(
+    value: Option<Value>,
+    name: &'static str,
+    convert: fn(value::Value) -> Option<T>,
+) -> Result<Option<T>, ClientError> {
+    match value.and_then(|value| value.value) {
+        Some(value) => convert(value)
+            .map(Some)
+            .ok_or(ClientError::UnexpectedValue(name)),
+        None => Ok(None),
+    }
+}
+
+fn required<T>(value: Option<T>, name: &'static str) -> Result<T, ClientError> {
+    value.ok_or(ClientError::UnexpectedValue(name))
+}
+
+/// Converts the gRPC representation of field values.
+mod convert {
+    use super::*;
+
+    pub fn uint(value: value::Value) -> Option<u64> {
+        match value {
+            value::Value::UintValue(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn int(value: value::Value) -> Option<i64> {
+        match value {
+            value::Value::IntValue(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn float(value: value::Value) -> Option<f64> {
+        match value {
+            value::Value::FloatValue(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn boolean(value: value::Value) -> Option<bool> {
+        match value {
+            value::Value::BoolValue(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn string(value: value::Value) -> Option<String> {
+        match value {
+            value::Value::StringValue(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn bytes(value: value::Value) -> Option<Vec<u8>> {
+        match value {
+            value::Value::BytesValue(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Decimals can't be converted because `RustDecimal` doesn't carry the scale.
+    pub fn decimal(_value: value::Value) -> Option<Decimal> {
+        None
+    }
+
+    pub fn timestamp(value: value::Value) -> Option<DateTime<FixedOffset>> {
+        match value {
+            value::Value::TimestampValue(ts) => Utc
+                .timestamp_opt(ts.seconds, ts.nanos as u32)
+                .single()
+                .map(Into::into),
+            _ => None,
+        }
+    }
+
+    pub fn date(value: value::Value) -> Option<NaiveDate> {
+        match value {
+            value::Value::StringValue(s) | value::Value::DateValue(s) => {
+                NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn point(value: value::Value) -> Option<Point> {
+        match value {
+            value::Value::PointValue(point) => Some(Point {
+                x: point.x,
+                y: point.y,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// Dozer generated client for endpoint `films`.
+//
+// Depends on `@grpc/grpc-js` and `@grpc/proto-loader`, and a `fetch` implementation.
+
+import * as path from "path";
+import * as grpc from "@grpc/grpc-js";
+import * as protoLoader from "@grpc/proto-loader";
+
+export interface Point {
+  x: number;
+  y: number;
+}
+
+export interface Film {
+  film_id: number;
+  description: string | null;
+  rental_rate: string | null;
+  release_year: number | null;
+  // Records with same primary key will have increasing version.
+  __dozer_record_version: number;
+}
+
+// A record with its id in cache.
+export type FilmWithId = Film & { __dozer_record_id: number };
+
+export interface FilmEvent {
+  typ: "INSERT" | "DELETE" | "UPDATE";
+  // Old record data, only applicable for UPDATE type.
+  old: Film | null;
+  new: Film | null;
+  // New record id, only applicable for INSERT type.
+  new_id: number | null;
+}
+
+export type EventType = "ALL" | "INSERT_ONLY" | "UPDATE_ONLY" | "DELETE_ONLY";
+
+export type Comparison<T> = { $eq: T } | { $lt: T } | { $lte: T } | { $gt: T } | { $gte: T };
+
+// Full text operators need a full text index on the field.
+export type TextOperator =
+  | Comparison<string>
+  | { $contains: string }
+  | { $matches_any: string }
+  | { $matches_all: string };
+
+// Points are in degrees and distances in meters.
+export type GeoOperator =
+  | { $near: { center: Point; max_distance: number } }
+  | { $within_box: { min: Point; max: Point } }
+  | { $within_radius: { center: Point; radius: number } };
+
+// Builds a filter matching records that satisfy all the added conditions.
+export class FilmFilter {
+  private readonly conditions: object[] = [];
+
+  film_id(operator: Comparison<number>): this {
+    return this.condition("film_id", operator);
+  }
+
+  description(operator: TextOperator): this {
+    return this.condition("description", operator);
+  }
+
+  rental_rate(operator: TextOperator): this {
+    return this.condition("rental_rate", operator);
+  }
+
+  release_year(operator: Comparison<number>): this {
+    return this.condition("release_year", operator);
+  }
+
+  // Returns `undefined` if no condition was added.
+  build(): object | undefined {
+    switch (this.conditions.length) {
+      case 0:
+        return undefined;
+      case 1:
+        return this.conditions[0];
+      default:
+        return { $and: this.conditions };
+    }
+  }
+
+  private condition(field: string, operator: object): this {
+    this.conditions.push({ [field]: operator });
+    return this;
+  }
+}
+
+export type FilmField = "film_id" | "description" | "rental_rate" | "release_year";
+
+export interface FilmQuery {
+  filter?: FilmFilter;
+  order_by?: [FilmField, "asc" | "desc"][];
+  limit?: number;
+  skip?: number;
+  // The cursor returned with the previous page, can't be used with `skip`.
+  cursor?: string;
+}
+
+export interface ClientOptions {
+  // Defaults to `http://localhost:8080`.
+  restUrl?: string;
+  // Defaults to `localhost:50051`.
+  grpcUrl?: string;
+  // Sent as a bearer token with every request.
+  token?: string;
+  // Directory of `common.proto` and `types.proto`, used by `onEvent`. Defaults to the current directory.
+  protoDir?: string;
+}
+
+export class FilmsClient {
+  static readonly endpoint = "films";
+  static readonly path = "/films";
+
+  constructor(private readonly options: ClientOptions = {}) {}
+
+  async count(filter?: FilmFilter): Promise<number> {
+    const response = await this.post("count", { filter });
+    return await response.json();
+  }
+
+  // `nextCursor` is `undefined` on the last page.
+  async query(
+    query: FilmQuery = {},
+  ): Promise<{ records: FilmWithId[]; nextCursor?: string }> {
+    const response = await this.post("query", query);
+    const nextCursor = response.headers.get("X-Dozer-Next-Cursor") ?? undefined;
+    return { records: await response.json(), nextCursor };
+  }
+
+  // Returns `undefined` if there's no record with the primary key.
+  async get(film_id: number): Promise<FilmWithId | undefined> {
+    const response = await this.request(encodeURIComponent(String(film_id)), { method: "GET" });
+    if (response.status === 404) {
+      return undefined;
+    }
+    await checkStatus(response);
+    return await response.json();
+  }
+
+  // Calls `onEvent` with the events of the endpoint that match `filter`. Cancel the returned stream to unsubscribe.
+  onEvent(
+    onEvent: (event: FilmEvent) => void,
+    eventType: EventType = "ALL",
+    filter?: FilmFilter,
+  ): grpc.ClientReadableStream<any> {
+    const protoDir = this.options.protoDir ?? ".";
+    const definition = protoLoader.loadSync(path.join(protoDir, "common.proto"), {
+      keepCase: true,
+      longs: Number,
+      enums: String,
+      oneofs: true,
+      includeDirs: [protoDir],
+    });
+    const proto = grpc.loadPackageDefinition(definition) as any;
+    const client = new proto.dozer.common.CommonGrpcService(
+      this.options.grpcUrl ?? "localhost:50051",
+      grpc.credentials.createInsecure(),
+    );
+    const metadata = new grpc.Metadata();
+    if (this.options.token) {
+      metadata.set("authorization", `Bearer ${this.options.token}`);
+    }
+    const condition = filter?.build();
+    const request = {
+      type: eventType,
+      endpoint: FilmsClient.endpoint,
+      filter: condition && JSON.stringify(condition),
+    };
+    const stream: grpc.ClientReadableStream<any> = client.OnEvent(request, metadata);
+    stream.on("data", (operation: any) => onEvent(eventFromGrpc(operation)));
+    return stream;
+  }
+
+  private async post(method: string, query: FilmQuery): Promise<Response> {
+    const response = await this.request(method, {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(queryToJson(query)),
+    });
+    await checkStatus(response);
+    return response;
+  }
+
+  private request(segment: string, init: RequestInit): Promise<Response> {
+    const headers = new Headers(init.headers);
+    if (this.options.token) {
+      headers.set("Authorization", `Bearer ${this.options.token}`);
+    }
+    const restUrl = (this.options.restUrl ?? "http://localhost:8080").replace(/\/$/, "");
+    return fetch(restUrl + FilmsClient.path + "/" + segment, { ...init, headers });
+  }
+}
+
+function queryToJson(query: FilmQuery): object {
+  const json: Record<string, unknown> = {};
+  const filter = query.filter?.build();
+  if (filter !== undefined) {
+    json.$filter = filter;
+  }
+  if (query.order_by?.length) {
+    json.$order_by = Object.fromEntries(query.order_by);
+  }
+  if (query.limit !== undefined) {
+    json.$limit = query.limit;
+  }
+  if (query.skip !== undefined) {
+    json.$skip = query.skip;
+  }
+  if (query.cursor !== undefined) {
+    json.$cursor = query.cursor;
+  }
+  return json;
+}
+
+async function checkStatus(response: Response): Promise<void> {
+  if (!response.ok) {
+    throw new Error(`Request failed with status ${response.status}: ${await response.text()}`);
+  }
+}
+
+function eventFromGrpc(operation: any): FilmEvent {
+  return {
+    typ: operation.typ,
+    old: operation.old ? recordFromGrpc(operation.old) : null,
+    new: operation.new ? recordFromGrpc(operation.new) : null,
+    new_id: operation.new_id ?? null,
+  };
+}
+
+function recordFromGrpc(record: any): Film {
+  const values = record.values.map(valueFromGrpc);
+  return {
+    film_id: values[0] as number,
+    description: values[1] as string | null,
+    rental_rate: values[2] as string | null,
+    release_year: values[3] as number | null,
+    __dozer_record_version: record.version,
+  };
+}
+
+// Converts a gRPC value to the JSON representation of the REST API.
+function valueFromGrpc(value: any): unknown {
+  switch (value.value) {
+    case undefined:
+      return null;
+    case "bytes_value":
+      return Array.from(value.bytes_value);
+    case "decimal_value":
+      // `RustDecimal` doesn't carry the scale.
+      throw new Error("Decimal values are not supported in events");
+    case "timestamp_value": {
+      const { seconds, nanos } = value.timestamp_value;
+      return new Date(seconds * 1000 + Math.floor(nanos / 1e6)).toISOString();
+    }
+    case "point_value":
+      return { x: value.point_value.x, y: value.point_value.y };
+    default:
+      return value[value.value];
+  }
+}
@@ -0,0 +1,351 @@
+use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::serde::{self, Serialize};
+use dozer_types::serde_json;
+use dozer_types::types::{FieldDefinition, FieldType, Schema};
+use handlebars::Handlebars;
+use inflector::Inflector;
+
+use super::ClientLanguage;
+use crate::errors::GenerationError;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+struct ClientMetadata {
+    endpoint_name: String,
+    endpoint_literal: String,
+    path_literal: String,
+    plural_pascal_name: String,
+    pascal_name: String,
+    fields: Vec<ClientField>,
+    /// Only set for a single field primary key that can be used in a URL path.
+    primary_key: Option<ClientField>,
+}
+
+/// Code snippets of a schema field, in the target language. Unused snippets are empty.
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+struct ClientField {
+    /// The field name as a string literal.
+    literal: String,
+    /// The field name as a property key, quoted if it's not an identifier.
+    key: String,
+    ident: String,
+    /// Name of the enum variant representing the field.
+    variant: String,
+    typ: String,
+    /// Type of the primary key parameter.
+    key_typ: String,
+    filter_typ: String,
+    /// Type of the filter operand.
+    operand_typ: String,
+    /// Converts the value in REST API JSON.
+    from_json: String,
+    /// Converts the value in a gRPC record.
+    from_grpc: String,
+}
+
+pub fn render(
+    language: ClientLanguage,
+    endpoint: &ApiEndpoint,
+    schema: &Schema,
+) -> Result<String, GenerationError> {
+    let template = match language {
+        ClientLanguage::TypeScript => include_str!("template/typescript.tmpl"),
+        ClientLanguage::Rust => include_str!("template/rust.tmpl"),
+        ClientLanguage::Python => include_str!("template/python.tmpl"),
+    };
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+        .register_template_string("main", template)
+        .map_err(|e| GenerationError::HandlebarsTemplate(Box::new(e)))?;
+
+    let metadata = metadata(language, endpoint, schema);
+    Ok(handlebars.render("main", &metadata)?)
+}
+
+/// Same as the name of the generated proto file.
+pub fn file_stem(endpoint_name: &str) -> String {
+    endpoint_name
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        .to_lowercase()
+}
+
+fn metadata(language: ClientLanguage, endpoint: &ApiEndpoint, schema: &Schema) -> ClientMetadata {
+    let schema_name = endpoint
+        .name
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let fields = schema
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| client_field(language, index, field))
+        .collect::<Vec<_>>();
+    let primary_key = match schema.primary_index.as_slice() {
+        [index]
+            if matches!(
+                schema.fields[*index].typ,
+                FieldType::UInt | FieldType::Int | FieldType::String | FieldType::Text
+            ) =>
+        {
+            Some(fields[*index].clone())
+        }
+        _ => None,
+    };
+
+    ClientMetadata {
+        endpoint_name: endpoint.name.clone(),
+        endpoint_literal: literal(language, &endpoint.name),
+        path_literal: literal(language, &endpoint.path),
+        plural_pascal_name: schema_name.to_pascal_case().to_plural(),
+        pascal_name: schema_name.to_pascal_case().to_singular(),
+        fields,
+        primary_key,
+    }
+}
+
+fn client_field(language: ClientLanguage, index: usize, field: &FieldDefinition) -> ClientField {
+    let literal = literal(language, &field.name);
+    match language {
+        ClientLanguage::TypeScript => {
+            let typ = typescript_type(field.typ);
+            ClientField {
+                key: if is_identifier(&field.name) {
+                    field.name.clone()
+                } else {
+                    literal.clone()
+                },
+                ident: identifier(&field.name, TYPESCRIPT_KEYWORDS),
+                variant: String::new(),
+                typ: if field.nullable {
+                    format!("{typ} | null")
+                } else {
+                    typ.to_string()
+                },
+                key_typ: typ.to_string(),
+                filter_typ: match field.typ {
+                    FieldType::String | FieldType::Text => "TextOperator".to_string(),
+                    FieldType::Point => "GeoOperator".to_string(),
+                    _ => format!("Comparison<{typ}>"),
+                },
+                operand_typ: String::new(),
+                from_json: String::new(),
+                from_grpc: String::new(),
+                literal,
+            }
+        }
+        ClientLanguage::Rust => {
+            let ident = identifier(&field.name, RUST_KEYWORDS);
+            let (typ, converter) = rust_type(field.typ);
+            let field_value = format!("field(values.next(), {literal}, convert::{converter})?");
+            ClientField {
+                key: String::new(),
+                variant: variant(&ident),
+                ident,
+                typ: if field.nullable {
+                    format!("Option<{typ}>")
+                } else {
+                    typ.to_string()
+                },
+                key_typ: match field.typ {
+                    FieldType::String | FieldType::Text => "&str".to_string(),
+                    _ => typ.to_string(),
+                },
+                filter_typ: match field.typ {
+                    FieldType::String | FieldType::Text => "TextOperator".to_string(),
+                    FieldType::Point => "GeoOperator".to_string(),
+                    _ => format!("Comparison<{typ}>"),
+                },
+                operand_typ: String::new(),
+                from_json: String::new(),
+                from_grpc: if field.nullable {
+                    field_value
+                } else {
+                    format!("required({field_value}, {literal})?")
+                },
+                literal,
+            }
+        }
+        ClientLanguage::Python => {
+            let (typ, converter) = python_type(field.typ);
+            let convert = |value: String| match converter {
+                Some(converter) => format!("{converter}({value})"),
+                None => value,
+            };
+            ClientField {
+                key: String::new(),
+                ident: identifier(&field.name, PYTHON_KEYWORDS),
+                variant: String::new(),
+                typ: if field.nullable {
+                    format!("Optional[{typ}]")
+                } else {
+                    typ.to_string()
+                },
+                key_typ: typ.to_string(),
+                filter_typ: match field.typ {
+                    FieldType::String | FieldType::Text => "TextOperator".to_string(),
+                    FieldType::Point => "GeoOperator".to_string(),
+                    _ => "ComparisonOperator".to_string(),
+                },
+                operand_typ: match field.typ {
+                    FieldType::Point => "Dict[str, Any]".to_string(),
+                    _ => typ.to_string(),
+                },
+                from_json: convert(format!("data[{literal}]")),
+                from_grpc: convert(format!("values[{index}]")),
+                literal,
+            }
+        }
+    }
+}
+
+/// Same as the REST API JSON representation of the field type.
+fn typescript_type(typ: FieldType) -> &'static str {
+    match typ {
+        FieldType::UInt | FieldType::Int | FieldType::Float => "number",
+        FieldType::Boolean => "boolean",
+        FieldType::String
+        | FieldType::Text
+        | FieldType::Decimal
+        | FieldType::Timestamp
+        | FieldType::Date
+        | FieldType::Duration
+        | FieldType::BigInt => "string",
+        FieldType::Binary | FieldType::Bson => "number[]",
+        FieldType::Point => "Point",
+    }
+}
+
+/// Returns the type and the function of the `convert` module in the template that reads it from a gRPC value.
+fn rust_type(typ: FieldType) -> (&'static str, &'static str) {
+    match typ {
+        FieldType::UInt => ("u64", "uint"),
+        FieldType::Int => ("i64", "int"),
+        FieldType::Float => ("f64", "float"),
+        FieldType::Boolean => ("bool", "boolean"),
+        FieldType::String | FieldType::Text | FieldType::Duration | FieldType::BigInt => {
+            ("String", "string")
+        }
+        FieldType::Binary | FieldType::Bson => ("Vec<u8>", "bytes"),
+        FieldType::Decimal => ("Decimal", "decimal"),
+        FieldType::Timestamp => ("DateTime<FixedOffset>", "timestamp"),
+        FieldType::Date => ("NaiveDate", "date"),
+        FieldType::Point => ("Point", "point"),
+    }
+}
+
+/// Returns the type and the function in the template that converts it from JSON, if needed.
+fn python_type(typ: FieldType) -> (&'static str, Option<&'static str>) {
+    match typ {
+        FieldType::UInt | FieldType::Int => ("int", None),
+        FieldType::Float => ("float", None),
+        FieldType::Boolean => ("bool", None),
+        FieldType::String | FieldType::Text | FieldType::Duration | FieldType::BigInt => {
+            ("str", None)
+        }
+        FieldType::Binary | FieldType::Bson => ("bytes", Some("_bytes")),
+        FieldType::Decimal => ("Decimal", Some("_decimal")),
+        FieldType::Timestamp => ("datetime", Some("_timestamp")),
+        FieldType::Date => ("date", Some("_date")),
+        FieldType::Point => ("Point", Some("_point")),
+    }
+}
+
+fn literal(language: ClientLanguage, value: &str) -> String {
+    match language {
+        ClientLanguage::Rust => format!("{value:?}"),
+        ClientLanguage::TypeScript | ClientLanguage::Python => {
+            serde_json::Value::from(value).to_string()
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn identifier(name: &str, keywords: &[&str]) -> String {
+    let mut ident = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        ident.insert(0, '_');
+    }
+    if keywords.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+fn variant(ident: &str) -> String {
+    let variant = ident.to_pascal_case();
+    if variant.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        variant
+    } else {
+        format!("Field{variant}")
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::types::Schema;
+
+use crate::errors::GenerationError;
+
+mod implementation;
+
+/// Language of a generated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientLanguage {
+    TypeScript,
+    Rust,
+    Python,
+}
+
+impl ClientLanguage {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ClientLanguage::TypeScript => "ts",
+            ClientLanguage::Rust => "rs",
+            ClientLanguage::Python => "py",
+        }
+    }
+}
+
+/// Generates typed clients of an endpoint, with record types, filter builders and event subscription helpers.
+pub struct ClientGenerator;
+
+impl ClientGenerator {
+    pub fn render(
+        language: ClientLanguage,
+        endpoint: &ApiEndpoint,
+        schema: &Schema,
+    ) -> Result<String, GenerationError> {
+        implementation::render(language, endpoint, schema)
+    }
+
+    /// Writes the client to `folder_path` and returns the path of the file.
+    pub fn generate(
+        folder_path: &Path,
+        language: ClientLanguage,
+        endpoint: &ApiEndpoint,
+        schema: &Schema,
+    ) -> Result<PathBuf, GenerationError> {
+        if !folder_path.exists() {
+            return Err(GenerationError::DirPathNotExist(folder_path.to_path_buf()));
+        }
+
+        let client = Self::render(language, endpoint, schema)?;
+        let file_name = format!(
+            "{}.{}",
+            implementation::file_stem(&endpoint.name),
+            language.extension()
+        );
+        let file_path = folder_path.join(file_name);
+        std::fs::write(&file_path, client)
+            .map_err(|e| GenerationError::FailedToWriteToFile(file_path.clone(), e))?;
+        Ok(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests;
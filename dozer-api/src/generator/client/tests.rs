@@ -0,0 +1,78 @@
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+use tempdir::TempDir;
+
+use super::{ClientGenerator, ClientLanguage};
+use crate::errors::GenerationError;
+use crate::test_utils;
+
+/// Schema of the `Film` message in `dozer-types/protos/films.proto`.
+fn films_schema() -> Schema {
+    let field = |name: &str, typ, nullable| {
+        FieldDefinition::new(name.to_string(), typ, nullable, SourceDefinition::Dynamic)
+    };
+    let mut schema = Schema::empty();
+    schema
+        .field(field("film_id", FieldType::UInt, false), true)
+        .field(field("description", FieldType::String, true), false)
+        .field(field("rental_rate", FieldType::String, true), false)
+        .field(field("release_year", FieldType::UInt, true), false);
+    schema
+}
+
+fn render(language: ClientLanguage) -> String {
+    ClientGenerator::render(language, &test_utils::get_endpoint(), &films_schema()).unwrap()
+}
+
+#[test]
+fn test_generate_typescript_client() {
+    assert_eq!(
+        render(ClientLanguage::TypeScript),
+        include_str!("golden/films.ts")
+    );
+}
+
+#[test]
+fn test_generate_rust_client() {
+    assert_eq!(
+        render(ClientLanguage::Rust),
+        include_str!("golden/films.rs")
+    );
+}
+
+#[test]
+fn test_generate_python_client() {
+    assert_eq!(
+        render(ClientLanguage::Python),
+        include_str!("golden/films.py")
+    );
+}
+
+#[test]
+fn test_generate_client_file() {
+    let tmp_dir = TempDir::new("client_generated").unwrap();
+    let endpoint = test_utils::get_endpoint();
+
+    let path = ClientGenerator::generate(
+        tmp_dir.path(),
+        ClientLanguage::Python,
+        &endpoint,
+        &films_schema(),
+    )
+    .unwrap();
+    assert_eq!(path, tmp_dir.path().join("films.py"));
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap(),
+        include_str!("golden/films.py")
+    );
+
+    let missing_dir = tmp_dir.path().join("missing");
+    assert!(matches!(
+        ClientGenerator::generate(
+            &missing_dir,
+            ClientLanguage::Python,
+            &endpoint,
+            &films_schema()
+        ),
+        Err(GenerationError::DirPathNotExist(_))
+    ));
+}
@@ -1,5 +1,6 @@
 use super::utils::{
     convert_cache_to_oapi_schema, create_contact_info, create_reference_response, create_response,
+    with_next_cursor_header,
 };
 use dozer_types::indexmap::{self, IndexMap};
 use dozer_types::serde_json;
@@ -42,6 +43,8 @@ impl<'a> OpenApiGenerator<'a> {
 
                     FieldType::Text => Value::from("lorem ipsum".to_string()),
                     FieldType::Date => Value::from("2022-11-24"),
+                    FieldType::Duration => Value::from("PT1H30M"),
+                    FieldType::BigInt => Value::from("-1"),
                     FieldType::Point => {
                         let mut m = Map::new();
                         m.insert("x".to_string(), Value::from(3.3));
@@ -106,7 +109,7 @@ impl<'a> OpenApiGenerator<'a> {
     fn generate_list_route(&self) -> ReferenceOr<PathItem> {
         let responses = Responses {
             responses: indexmap::indexmap! {
-                StatusCode::Code(200) => ReferenceOr::Item(create_reference_response(format!("A page array of {}", self.endpoint.name.to_owned()), format!("#/components/schemas/{}",self.get_plural_name())))
+                StatusCode::Code(200) => ReferenceOr::Item(with_next_cursor_header(create_reference_response(format!("A page array of {}", self.endpoint.name.to_owned()), format!("#/components/schemas/{}",self.get_plural_name()))))
             },
             ..Default::default()
         };
@@ -177,7 +180,7 @@ impl<'a> OpenApiGenerator<'a> {
         };
         let responses = Responses {
             responses: indexmap::indexmap! {
-                StatusCode::Code(200) => ReferenceOr::Item(create_reference_response(format!("A page array of {}", self.endpoint.name.to_owned()), format!("#/components/schemas/{}", self.get_plural_name()) ))
+                StatusCode::Code(200) => ReferenceOr::Item(with_next_cursor_header(create_reference_response(format!("A page array of {}", self.endpoint.name.to_owned()), format!("#/components/schemas/{}", self.get_plural_name()) )))
             },
             ..Default::default()
         };
@@ -185,7 +188,8 @@ impl<'a> OpenApiGenerator<'a> {
             tags: vec![format!("{}", self.endpoint.name)],
             summary: Some("Query documents based on an expression".to_owned()),
             description: Some(
-                "Documents can be queried based on a simple or a composite expression".to_owned(),
+                "Documents can be queried based on a simple or a composite expression. Pass the `X-Dozer-Next-Cursor` response header as `$cursor` to get the next page."
+                    .to_owned(),
             ),
             operation_id: Some(format!("query-{}", self.endpoint.name)),
             request_body: Some(ReferenceOr::Item(request_body)),
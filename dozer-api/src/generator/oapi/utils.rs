@@ -1,11 +1,12 @@
+use crate::rest::NEXT_CURSOR_HEADER;
 use dozer_types::{
     indexmap::{self, IndexMap},
     types::{FieldType, DATE_FORMAT},
 };
 use openapiv3::{
-    ArrayType, Contact, IntegerFormat, IntegerType, MediaType, NumberFormat, NumberType,
-    ObjectType, Parameter, ParameterData, ParameterSchemaOrContent, PathStyle, ReferenceOr,
-    Response, Schema, SchemaData, SchemaKind, StringFormat, StringType, Type,
+    ArrayType, Contact, Header, HeaderStyle, IntegerFormat, IntegerType, MediaType, NumberFormat,
+    NumberType, ObjectType, Parameter, ParameterData, ParameterSchemaOrContent, PathStyle,
+    ReferenceOr, Response, Schema, SchemaData, SchemaKind, StringFormat, StringType, Type,
     VariantOrUnknownOrEmpty,
 };
 
@@ -58,6 +59,30 @@ pub fn create_response(description: String, schema: Schema) -> Response {
     }
 }
 
+/// Documents the `NEXT_CURSOR_HEADER` of paginated responses.
+pub fn with_next_cursor_header(mut response: Response) -> Response {
+    response.headers.insert(
+        NEXT_CURSOR_HEADER.to_string(),
+        ReferenceOr::Item(Header {
+            description: Some(
+                "Pass as `$cursor` in the query to get the next page. Absent on the last page."
+                    .to_string(),
+            ),
+            style: HeaderStyle::Simple,
+            required: false,
+            deprecated: None,
+            format: ParameterSchemaOrContent::Schema(ReferenceOr::Item(Schema {
+                schema_data: Default::default(),
+                schema_kind: SchemaKind::Type(Type::String(Default::default())),
+            })),
+            example: None,
+            examples: IndexMap::new(),
+            extensions: IndexMap::new(),
+        }),
+    );
+    response
+}
+
 pub fn create_reference_response(description: String, schema_reference_path: String) -> Response {
     Response {
         description,
@@ -116,7 +141,9 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
         | FieldType::Text
         | FieldType::Decimal
         | FieldType::Timestamp
-        | FieldType::Date => {
+        | FieldType::Date
+        | FieldType::Duration
+        | FieldType::BigInt => {
             let (format, pattern) = if field_type == FieldType::Timestamp {
                 (
                     VariantOrUnknownOrEmpty::Item(StringFormat::DateTime),
@@ -127,6 +154,16 @@ fn convert_cache_type_to_schema_type(field_type: dozer_types::types::FieldType)
                     VariantOrUnknownOrEmpty::Item(StringFormat::Date),
                     Some(DATE_FORMAT.to_string()),
                 )
+            } else if field_type == FieldType::Duration {
+                (
+                    VariantOrUnknownOrEmpty::Unknown("duration".to_string()),
+                    None,
+                )
+            } else if field_type == FieldType::BigInt {
+                (
+                    VariantOrUnknownOrEmpty::Unknown("bigint".to_string()),
+                    Some("^-?[0-9]+$".to_string()),
+                )
             } else {
                 (VariantOrUnknownOrEmpty::Empty, None)
             };
@@ -239,11 +239,13 @@ impl<'a> ProtoGeneratorImpl<'a> {
                             actual: record_field_kind
                         });
                     };
+                    let next_cursor_field = message.get_field_by_name("next_cursor");
                     query = Some(QueryMethodDesc {
                         method,
                         response_desc: QueryResponseDesc {
                             message,
                             records_field,
+                            next_cursor_field,
                             record_with_id_desc: RecordWithIdDesc {
                                 message: record_with_id_message,
                                 id_field,
@@ -371,5 +373,7 @@ fn convert_dozer_type_to_proto_type(field_type: FieldType) -> Result<String, Gen
         FieldType::Date => Ok("string".to_owned()),
         FieldType::Bson => Ok("bytes".to_owned()),
         FieldType::Point => Ok(POINT_TYPE_CLASS.to_owned()),
+        FieldType::Duration => Ok("string".to_owned()),
+        FieldType::BigInt => Ok("string".to_owned()),
     }
 }
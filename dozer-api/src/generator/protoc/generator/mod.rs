@@ -86,6 +86,8 @@ pub struct RecordWithIdDesc {
 pub struct QueryResponseDesc {
     pub message: MessageDescriptor,
     pub records_field: FieldDescriptor,
+    /// `None` for protos generated before cursor pagination.
+    pub next_cursor_field: Option<FieldDescriptor>,
     pub record_with_id_desc: RecordWithIdDesc,
 }
 
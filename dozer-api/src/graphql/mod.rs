@@ -0,0 +1,80 @@
+use actix_web::web::{self, ReqData};
+use actix_web::{HttpRequest, HttpResponse};
+use async_graphql::dynamic::Schema;
+use async_graphql::{Data, Error};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use dozer_types::models::api_security::ApiSecurity;
+use dozer_types::serde_json::Value;
+
+use crate::auth::{Access, Authorizer};
+
+mod resolver;
+mod schema;
+
+pub use schema::build_schema;
+
+pub const GRAPHQL_WS_PATH: &str = "/graphql/ws";
+
+/// Executes GraphQL queries, passing the access claims of the request to the resolvers.
+pub async fn graphql(
+    access: Option<ReqData<Access>>,
+    schema: web::Data<Schema>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let mut request = request.into_inner();
+    if let Some(access) = access {
+        request = request.data(access.into_inner());
+    }
+    schema.execute(request).await.into()
+}
+
+/// Serves GraphQL subscriptions over WebSocket, with the access claims of the upgrade request.
+///
+/// Browsers can't set headers on WebSocket requests, so if the upgrade request has no token, it's
+/// read from the `connection_init` payload instead, as in `{ "Authorization": "Bearer <token>" }`.
+pub async fn graphql_ws(
+    access: Option<ReqData<Access>>,
+    schema: web::Data<Schema>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    let subscription = GraphQLSubscription::new(Schema::clone(&schema));
+    match (access, req.app_data::<ApiSecurity>()) {
+        (Some(access), _) => {
+            let mut data = Data::default();
+            data.insert(access.into_inner());
+            subscription.with_data(data).start(&req, payload)
+        }
+        (None, Some(security)) => {
+            let security = security.clone();
+            subscription
+                .on_connection_init(move |init_payload| async move {
+                    let mut data = Data::default();
+                    data.insert(connection_init_access(&security, &init_payload)?);
+                    Ok(data)
+                })
+                .start(&req, payload)
+        }
+        (None, None) => subscription.start(&req, payload),
+    }
+}
+
+fn connection_init_access(security: &ApiSecurity, payload: &Value) -> Result<Access, Error> {
+    let token = payload
+        .as_object()
+        .and_then(|payload| {
+            payload
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("authorization"))
+        })
+        .and_then(|(_, value)| value.as_str())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| Error::new("Missing bearer token in connection_init payload"))?;
+    let claims = Authorizer::from(security)
+        .validate_token(token)
+        .map_err(|e| Error::new(e.to_string()))?;
+    Ok(claims.access)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,304 @@
+use async_graphql::dynamic::{ResolverContext, ValueAccessor};
+use async_graphql::{Error, Value};
+use dozer_cache::cache::expression::{
+    Cursor, FilterExpression, QueryExpression, Skip, SortDirection, SortOption,
+};
+use dozer_cache::cache::{index, RecordWithId};
+use dozer_types::chrono::{SecondsFormat, TimeZone, Utc};
+use dozer_types::grpc_types::types::{value, Operation, OperationType, Record as GrpcRecord};
+use dozer_types::serde_json::{self, json, Map};
+use dozer_types::types::{Record, Schema};
+use dozer_types::{field_to_json_value, json_value_to_field};
+use futures_util::Stream;
+use tokio::sync::broadcast::Receiver;
+
+use crate::api_helper::{get_access_filter, get_record, get_records, get_records_count};
+use crate::auth::Access;
+use crate::errors::ApiError;
+use crate::grpc::shared_impl::event_stream;
+use crate::RoCacheEndpoint;
+
+use super::schema::{Names, COMPARISON_OPERATORS, GEO_OPERATORS, TEXT_OPERATORS, VERSION_FIELD};
+
+pub fn query(
+    ctx: &ResolverContext,
+    cache_endpoint: &RoCacheEndpoint,
+    names: &Names,
+) -> Result<Value, Error> {
+    let cache_reader = cache_endpoint.cache_reader();
+    let endpoint_name = &cache_endpoint.endpoint().name;
+    let schema = &cache_reader
+        .get_schema_and_indexes_by_name(endpoint_name)
+        .map_err(ApiError::SchemaNotFound)?
+        .0;
+
+    let mut exp = QueryExpression::with_default_limit();
+    exp.filter = filter_argument(ctx, names, schema)?;
+    if let Some(order_by) = argument(ctx, "order_by") {
+        for option in order_by.list()?.iter() {
+            let option = option.object()?;
+            let field_name = field_name(names, schema, option.try_get("field")?.enum_name()?)?;
+            let direction = match option.try_get("direction")?.enum_name()? {
+                "DESC" => SortDirection::Descending,
+                _ => SortDirection::Ascending,
+            };
+            exp.order_by.0.push(SortOption::new(field_name, direction));
+        }
+    }
+    if let Some(limit) = argument(ctx, "limit") {
+        exp.limit = Some(limit.u64()? as usize);
+    }
+    if let Some(skip) = argument(ctx, "skip") {
+        exp.skip = Skip::Skip(skip.u64()? as usize);
+    }
+    if let Some(cursor) = argument(ctx, "cursor") {
+        if exp.skip != Skip::Skip(0) {
+            return Err(Error::new("`cursor` cannot be used with `skip`"));
+        }
+        exp.cursor =
+            Some(Cursor::decode(cursor.string()?).ok_or_else(|| Error::new("Invalid cursor"))?);
+    }
+
+    let (schema, records, next_cursor) = get_records(
+        &cache_reader,
+        endpoint_name,
+        &mut exp,
+        ctx.data_opt::<Access>().cloned(),
+    )?;
+    let records = records
+        .into_iter()
+        .map(|record| record_with_id_to_json(record, schema, names))
+        .collect::<Vec<_>>();
+    to_value(json!({ "records": records, "next_cursor": next_cursor }))
+}
+
+pub fn count(
+    ctx: &ResolverContext,
+    cache_endpoint: &RoCacheEndpoint,
+    names: &Names,
+) -> Result<Value, Error> {
+    let cache_reader = cache_endpoint.cache_reader();
+    let endpoint_name = &cache_endpoint.endpoint().name;
+    let schema = &cache_reader
+        .get_schema_and_indexes_by_name(endpoint_name)
+        .map_err(ApiError::SchemaNotFound)?
+        .0;
+
+    let mut exp = QueryExpression::with_no_limit();
+    exp.filter = filter_argument(ctx, names, schema)?;
+    let count = get_records_count(
+        &cache_reader,
+        endpoint_name,
+        &mut exp,
+        ctx.data_opt::<Access>().cloned(),
+    )?;
+    Ok(Value::from(count as u64))
+}
+
+/// Returns `None` if there's no record with the id.
+pub fn by_id(
+    ctx: &ResolverContext,
+    cache_endpoint: &RoCacheEndpoint,
+    names: &Names,
+    schema: &Schema,
+) -> Result<Option<Value>, Error> {
+    let field = &schema.fields[schema.primary_index[0]];
+    let key = ctx.args.try_get("id")?.as_value().clone().into_json()?;
+    let key = json_value_to_field(key, field.typ, false)?;
+    let key = index::get_primary_key(&[0], &[key]);
+
+    match get_record(
+        &cache_endpoint.cache_reader(),
+        &key,
+        ctx.data_opt::<Access>().cloned(),
+    ) {
+        Ok(record) => to_value(record_with_id_to_json(record, schema, names)).map(Some),
+        Err(ApiError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Streams the operations of the endpoint that satisfy both the `filter` argument and the access filter.
+pub fn events(
+    ctx: &ResolverContext,
+    endpoint_name: &str,
+    names: &Names,
+    schema: &Schema,
+    operations_receiver: Receiver<Operation>,
+) -> Result<impl Stream<Item = Result<Value, Error>> + Send + 'static, Error> {
+    let access_filter = get_access_filter(ctx.data_opt::<Access>().cloned())?;
+    let filter = match (filter_argument(ctx, names, schema)?, access_filter.filter) {
+        (Some(filter), Some(access_filter)) => {
+            Some(FilterExpression::And(vec![filter, access_filter]))
+        }
+        (filter, access_filter) => filter.or(access_filter),
+    };
+
+    let endpoint_name = endpoint_name.to_string();
+    let record_fields = names.record_fields.clone();
+    let stream = event_stream(schema.clone(), filter, operations_receiver, move |op| {
+        if op.endpoint_name == endpoint_name {
+            Some(operation_to_value(op, &record_fields))
+        } else {
+            None
+        }
+    });
+    Ok(stream)
+}
+
+fn argument<'a>(ctx: &'a ResolverContext, name: &str) -> Option<ValueAccessor<'a>> {
+    ctx.args.get(name).filter(|value| !value.is_null())
+}
+
+fn filter_argument(
+    ctx: &ResolverContext,
+    names: &Names,
+    schema: &Schema,
+) -> Result<Option<FilterExpression>, Error> {
+    argument(ctx, "filter")
+        .map(|filter| filter_expression(filter.as_value(), names, schema))
+        .transpose()
+}
+
+/// Converts a value of the generated filter input type to a `FilterExpression` on the schema fields.
+fn filter_expression(
+    value: &Value,
+    names: &Names,
+    schema: &Schema,
+) -> Result<FilterExpression, Error> {
+    let Value::Object(object) = value else {
+        return Err(Error::new("Filter must be an object"));
+    };
+
+    let mut expressions = vec![];
+    for (key, value) in object {
+        if let Value::Null = value {
+            continue;
+        }
+        if key.as_str() == "and" {
+            let Value::List(filters) = value else {
+                return Err(Error::new("`and` must be a list"));
+            };
+            expressions.push(FilterExpression::And(
+                filters
+                    .iter()
+                    .map(|filter| filter_expression(filter, names, schema))
+                    .collect::<Result<_, _>>()?,
+            ));
+            continue;
+        }
+
+        let field_name = field_name(names, schema, key.as_str())?;
+        let Value::Object(operators) = value else {
+            return Err(Error::new(format!("Filter of `{key}` must be an object")));
+        };
+        for (operator_name, operand) in operators {
+            if let Value::Null = operand {
+                continue;
+            }
+            let operator = COMPARISON_OPERATORS
+                .iter()
+                .chain(&TEXT_OPERATORS)
+                .chain(&GEO_OPERATORS)
+                .find(|(name, _)| *name == operator_name.as_str())
+                .map(|(_, operator)| *operator)
+                .ok_or_else(|| Error::new(format!("Unknown filter operator `{operator_name}`")))?;
+            expressions.push(FilterExpression::Simple(
+                field_name.clone(),
+                operator,
+                operand.clone().into_json()?,
+            ));
+        }
+    }
+
+    Ok(if expressions.len() == 1 {
+        expressions.remove(0)
+    } else {
+        FilterExpression::And(expressions)
+    })
+}
+
+/// Maps a GraphQL field name back to the schema field name.
+fn field_name(names: &Names, schema: &Schema, graphql_name: &str) -> Result<String, Error> {
+    names
+        .record_fields
+        .iter()
+        .position(|name| name == graphql_name)
+        .map(|index| schema.fields[index].name.clone())
+        .ok_or_else(|| Error::new(format!("Unknown field `{graphql_name}`")))
+}
+
+fn to_value(json: serde_json::Value) -> Result<Value, Error> {
+    Value::from_json(json).map_err(Error::from)
+}
+
+fn record_with_id_to_json(
+    record: RecordWithId,
+    schema: &Schema,
+    names: &Names,
+) -> serde_json::Value {
+    json!({ "id": record.id, "record": record_to_json(record.record, schema, names) })
+}
+
+fn record_to_json(record: Record, schema: &Schema, names: &Names) -> serde_json::Value {
+    debug_assert_eq!(record.values.len(), schema.fields.len());
+    let mut map = names
+        .record_fields
+        .iter()
+        .cloned()
+        .zip(record.values.into_iter().map(field_to_json_value))
+        .collect::<Map<_, _>>();
+    map.insert(VERSION_FIELD.to_string(), json!(record.version));
+    serde_json::Value::Object(map)
+}
+
+/// Converts an operation from the broadcast channel, whose values are already in the gRPC representation.
+fn operation_to_value(op: Operation, record_fields: &[String]) -> Result<Value, Error> {
+    let typ = match OperationType::from_i32(op.typ) {
+        Some(OperationType::Insert) => "INSERT",
+        Some(OperationType::Delete) => "DELETE",
+        Some(OperationType::Update) => "UPDATE",
+        None => return Err(Error::new(format!("Unknown operation type {}", op.typ))),
+    };
+    to_value(json!({
+        "typ": typ,
+        "old": op.old.map(|record| grpc_record_to_json(record, record_fields)),
+        "new": op.new.map(|record| grpc_record_to_json(record, record_fields)),
+        "new_id": op.new_id,
+    }))
+}
+
+fn grpc_record_to_json(record: GrpcRecord, record_fields: &[String]) -> serde_json::Value {
+    let mut map = Map::new();
+    for (name, value) in record_fields.iter().zip(record.values) {
+        if let Some(value) = grpc_value_to_json(value.value) {
+            map.insert(name.clone(), value);
+        }
+    }
+    map.insert(VERSION_FIELD.to_string(), json!(record.version));
+    serde_json::Value::Object(map)
+}
+
+/// Should be consistent with `field_to_json_value`.
+///
+/// Returns `None` for decimals, because `RustDecimal` doesn't carry the scale.
+fn grpc_value_to_json(value: Option<value::Value>) -> Option<serde_json::Value> {
+    let Some(value) = value else {
+        return Some(serde_json::Value::Null);
+    };
+    match value {
+        value::Value::UintValue(n) => Some(n.into()),
+        value::Value::IntValue(n) => Some(n.into()),
+        value::Value::FloatValue(n) => Some(n.into()),
+        value::Value::BoolValue(b) => Some(b.into()),
+        value::Value::StringValue(s) => Some(s.into()),
+        value::Value::BytesValue(b) => Some(b.into()),
+        value::Value::DecimalValue(_) => None,
+        value::Value::TimestampValue(ts) => Utc
+            .timestamp_opt(ts.seconds, ts.nanos as u32)
+            .single()
+            .map(|ts| ts.to_rfc3339_opts(SecondsFormat::Millis, true).into()),
+        value::Value::DateValue(s) => Some(s.into()),
+        value::Value::PointValue(point) => Some(json!({ "x": point.x, "y": point.y })),
+    }
+}
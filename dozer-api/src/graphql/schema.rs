@@ -0,0 +1,418 @@
+use std::sync::Arc;
+
+use async_graphql::dynamic::{
+    Enum, EnumItem, Field, FieldFuture, FieldValue, InputObject, InputValue, Object, Scalar,
+    Schema, Subscription, SubscriptionField, SubscriptionFieldFuture, Type, TypeRef,
+};
+use async_graphql::{Error, Name, Value};
+use dozer_cache::cache::expression::Operator;
+use dozer_types::grpc_types::types::Operation;
+use dozer_types::log::error;
+use dozer_types::types::{FieldType, Schema as DozerSchema};
+use inflector::Inflector;
+use tokio::sync::broadcast::Receiver;
+
+use crate::errors::{ApiError, GenerationError};
+use crate::RoCacheEndpoint;
+
+use super::resolver;
+
+/// Name of the record field carrying the record version. GraphQL reserves names starting with `__`.
+pub const VERSION_FIELD: &str = "_dozer_record_version";
+
+const UINT64: &str = "UInt64";
+const INT64: &str = "Int64";
+const BYTES: &str = "Bytes";
+const DECIMAL: &str = "Decimal";
+const TIMESTAMP: &str = "Timestamp";
+const JSON: &str = "JSON";
+const POINT: &str = "Point";
+const OPERATION_TYPE: &str = "OperationType";
+const SORT_DIRECTION: &str = "SortDirection";
+
+/// Filter operators of every type except `Point`, with their argument names.
+pub const COMPARISON_OPERATORS: [(&str, Operator); 5] = [
+    ("eq", Operator::EQ),
+    ("lt", Operator::LT),
+    ("lte", Operator::LTE),
+    ("gt", Operator::GT),
+    ("gte", Operator::GTE),
+];
+/// Additional filter operators of `String`.
+pub const TEXT_OPERATORS: [(&str, Operator); 3] = [
+    ("contains", Operator::Contains),
+    ("matches_any", Operator::MatchesAny),
+    ("matches_all", Operator::MatchesAll),
+];
+/// Filter operators of `Point`, whose arguments are passed as is to the geo index.
+pub const GEO_OPERATORS: [(&str, Operator); 3] = [
+    ("near", Operator::Near),
+    ("within_box", Operator::WithinBox),
+    ("within_radius", Operator::WithinRadius),
+];
+
+/// Builds a GraphQL schema serving `cache_endpoints`.
+///
+/// The `Subscription` root is only generated if `operations_receiver` is given.
+pub fn build_schema(
+    cache_endpoints: &[Arc<RoCacheEndpoint>],
+    operations_receiver: Option<&Receiver<Operation>>,
+) -> Result<Schema, ApiError> {
+    let mut query = Object::new("Query");
+    let mut subscription = operations_receiver.map(|_| Subscription::new("Subscription"));
+    let mut builder_types = shared_types();
+
+    for cache_endpoint in cache_endpoints {
+        let endpoint_name = &cache_endpoint.endpoint().name;
+        let schema = cache_endpoint
+            .cache_reader()
+            .get_schema_and_indexes_by_name(endpoint_name)
+            .map_err(ApiError::SchemaNotFound)?
+            .0
+            .clone();
+        let names = Arc::new(Names::new(endpoint_name, &schema));
+        let schema = Arc::new(schema);
+
+        builder_types.extend(endpoint_types(&names, &schema));
+
+        query = query
+            .field(query_field(cache_endpoint, &names))
+            .field(count_field(cache_endpoint, &names));
+        if let Some(field) = by_id_field(cache_endpoint, &names, &schema) {
+            query = query.field(field);
+        }
+        if let Some(receiver) = operations_receiver {
+            subscription = subscription
+                .map(|root| root.field(events_field(cache_endpoint, &names, &schema, receiver)));
+        }
+    }
+
+    let mut builder = Schema::build(
+        query.type_name(),
+        None,
+        subscription.as_ref().map(Subscription::type_name),
+    )
+    .register(query);
+    if let Some(subscription) = subscription {
+        builder = builder.register(subscription);
+    }
+    builder_types
+        .into_iter()
+        .fold(builder, |builder, ty| builder.register(ty))
+        .finish()
+        .map_err(|e| GenerationError::GraphQLSchema(e).into())
+}
+
+/// Names of the GraphQL fields and types generated for an endpoint.
+pub struct Names {
+    pub query: String,
+    pub count: String,
+    pub by_id: String,
+    pub events: String,
+    pub record: String,
+    pub record_with_id: String,
+    pub query_response: String,
+    pub event: String,
+    pub filter: String,
+    pub order_by: String,
+    pub field_enum: String,
+    /// GraphQL names of the schema fields, in schema order.
+    pub record_fields: Vec<String>,
+}
+
+impl Names {
+    pub fn new(endpoint_name: &str, schema: &DozerSchema) -> Self {
+        let endpoint_name = sanitize(endpoint_name);
+        let lower_name = endpoint_name.to_lowercase();
+        let plural_pascal_name = endpoint_name.to_pascal_case().to_plural();
+        let pascal_name = endpoint_name.to_pascal_case().to_singular();
+        let record_fields = schema
+            .fields
+            .iter()
+            .map(|field| sanitize(&field.name))
+            .collect();
+        Self {
+            count: format!("{lower_name}_count"),
+            by_id: format!("{lower_name}_by_id"),
+            events: format!("{lower_name}_events"),
+            query: lower_name,
+            record_with_id: format!("{pascal_name}WithId"),
+            query_response: format!("Query{plural_pascal_name}Response"),
+            event: format!("{pascal_name}Event"),
+            filter: format!("{pascal_name}Filter"),
+            order_by: format!("{pascal_name}OrderBy"),
+            field_enum: format!("{pascal_name}Field"),
+            record: pascal_name,
+            record_fields,
+        }
+    }
+}
+
+/// Replaces characters that are not allowed in GraphQL names, which also can't start with a digit.
+fn sanitize(name: &str) -> String {
+    if name.contains('-') {
+        error!("Name `{name}` should not contain `-`.");
+    }
+    let name = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn convert_dozer_type_to_graphql_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::UInt => UINT64,
+        FieldType::Int => INT64,
+        FieldType::Float => TypeRef::FLOAT,
+        FieldType::Boolean => TypeRef::BOOLEAN,
+        FieldType::String => TypeRef::STRING,
+        FieldType::Text => TypeRef::STRING,
+        FieldType::Binary => BYTES,
+        FieldType::Decimal => DECIMAL,
+        FieldType::Timestamp => TIMESTAMP,
+        FieldType::Date => TypeRef::STRING,
+        FieldType::Bson => BYTES,
+        FieldType::Point => POINT,
+        FieldType::Duration => TypeRef::STRING,
+        FieldType::BigInt => TypeRef::STRING,
+    }
+}
+
+fn filter_type_name(type_name: &str) -> String {
+    format!("{type_name}Filter")
+}
+
+/// Types shared by all endpoints.
+fn shared_types() -> Vec<Type> {
+    let mut types: Vec<Type> = vec![
+        Scalar::new(UINT64)
+            .description("Unsigned 64 bit integer.")
+            .into(),
+        Scalar::new(INT64)
+            .description("Signed 64 bit integer.")
+            .into(),
+        Scalar::new(BYTES)
+            .description("Binary data, as a list of bytes.")
+            .into(),
+        Scalar::new(DECIMAL)
+            .description("Decimal number, as a string.")
+            .into(),
+        Scalar::new(TIMESTAMP)
+            .description("RFC 3339 combined date and time with time zone.")
+            .into(),
+        Scalar::new(JSON)
+            .description("Any JSON value, used for arguments of geo filters.")
+            .into(),
+        Object::new(POINT)
+            .description("Geo point.")
+            .field(value_field("x", TypeRef::named_nn(TypeRef::FLOAT)))
+            .field(value_field("y", TypeRef::named_nn(TypeRef::FLOAT)))
+            .into(),
+        Enum::new(OPERATION_TYPE)
+            .items(["INSERT", "DELETE", "UPDATE"].map(EnumItem::new))
+            .into(),
+        Enum::new(SORT_DIRECTION)
+            .items(["ASC", "DESC"].map(EnumItem::new))
+            .into(),
+    ];
+
+    for type_name in [
+        UINT64,
+        INT64,
+        TypeRef::FLOAT,
+        TypeRef::BOOLEAN,
+        TypeRef::STRING,
+        BYTES,
+        DECIMAL,
+        TIMESTAMP,
+    ] {
+        let mut filter = InputObject::new(filter_type_name(type_name));
+        for (name, _) in COMPARISON_OPERATORS {
+            filter = filter.field(InputValue::new(name, TypeRef::named(type_name)));
+        }
+        if type_name == TypeRef::STRING {
+            for (name, _) in TEXT_OPERATORS {
+                filter = filter.field(InputValue::new(name, TypeRef::named(type_name)));
+            }
+        }
+        types.push(filter.into());
+    }
+    let mut point_filter = InputObject::new(filter_type_name(POINT));
+    for (name, _) in GEO_OPERATORS {
+        point_filter = point_filter.field(InputValue::new(name, TypeRef::named(JSON)));
+    }
+    types.push(point_filter.into());
+
+    types
+}
+
+/// Types generated for one endpoint.
+fn endpoint_types(names: &Names, schema: &DozerSchema) -> Vec<Type> {
+    let mut record = Object::new(&names.record);
+    let mut filter = InputObject::new(&names.filter);
+    for (field, name) in schema.fields.iter().zip(&names.record_fields) {
+        let type_name = convert_dozer_type_to_graphql_type(field.typ);
+        let type_ref = if field.nullable {
+            TypeRef::named(type_name)
+        } else {
+            TypeRef::named_nn(type_name)
+        };
+        record = record.field(value_field(name, type_ref));
+        filter = filter.field(InputValue::new(
+            name,
+            TypeRef::named(filter_type_name(type_name)),
+        ));
+    }
+    record = record.field(value_field(VERSION_FIELD, TypeRef::named_nn(UINT64)));
+    filter = filter.field(InputValue::new(
+        "and",
+        TypeRef::named_nn_list(&names.filter),
+    ));
+
+    vec![
+        record.into(),
+        filter.into(),
+        Object::new(&names.record_with_id)
+            .field(value_field("id", TypeRef::named_nn(UINT64)))
+            .field(value_field("record", TypeRef::named_nn(&names.record)))
+            .into(),
+        Object::new(&names.query_response)
+            .field(value_field(
+                "records",
+                TypeRef::named_nn_list_nn(&names.record_with_id),
+            ))
+            .field(value_field("next_cursor", TypeRef::named(TypeRef::STRING)))
+            .into(),
+        Object::new(&names.event)
+            .field(value_field("typ", TypeRef::named_nn(OPERATION_TYPE)))
+            .field(value_field("old", TypeRef::named(&names.record)))
+            .field(value_field("new", TypeRef::named_nn(&names.record)))
+            .field(value_field("new_id", TypeRef::named(UINT64)))
+            .into(),
+        Enum::new(&names.field_enum)
+            .items(names.record_fields.iter().map(EnumItem::new))
+            .into(),
+        InputObject::new(&names.order_by)
+            .field(InputValue::new(
+                "field",
+                TypeRef::named_nn(&names.field_enum),
+            ))
+            .field(
+                InputValue::new("direction", TypeRef::named_nn(SORT_DIRECTION))
+                    .default_value(Value::Enum(Name::new("ASC"))),
+            )
+            .into(),
+    ]
+}
+
+/// A field resolved from the object value of its parent.
+///
+/// A missing key is an error, which is how values that can't be represented are reported.
+fn value_field(name: impl Into<String>, type_ref: TypeRef) -> Field {
+    let name = name.into();
+    Field::new(name.clone(), type_ref, move |ctx| {
+        let name = name.clone();
+        FieldFuture::new(async move {
+            let Value::Object(object) = ctx.parent_value.try_to_value()? else {
+                return Err(Error::new(format!("Parent of `{name}` is not an object")));
+            };
+            match object.get(name.as_str()) {
+                Some(value) => Ok(Some(FieldValue::value(value.clone()))),
+                None => Err(Error::new(format!("Value of `{name}` is not available"))),
+            }
+        })
+    })
+}
+
+fn filter_argument(names: &Names) -> InputValue {
+    InputValue::new("filter", TypeRef::named(&names.filter))
+}
+
+fn query_field(cache_endpoint: &Arc<RoCacheEndpoint>, names: &Arc<Names>) -> Field {
+    let (cache_endpoint, resolver_names) = (cache_endpoint.clone(), names.clone());
+    Field::new(
+        &names.query,
+        TypeRef::named_nn(&names.query_response),
+        move |ctx| {
+            let (cache_endpoint, names) = (cache_endpoint.clone(), resolver_names.clone());
+            FieldFuture::new(
+                async move { resolver::query(&ctx, &cache_endpoint, &names).map(Some) },
+            )
+        },
+    )
+    .argument(filter_argument(names))
+    .argument(InputValue::new(
+        "order_by",
+        TypeRef::named_nn_list(&names.order_by),
+    ))
+    .argument(InputValue::new("limit", TypeRef::named(TypeRef::INT)))
+    .argument(InputValue::new("skip", TypeRef::named(TypeRef::INT)))
+    .argument(InputValue::new("cursor", TypeRef::named(TypeRef::STRING)))
+}
+
+fn count_field(cache_endpoint: &Arc<RoCacheEndpoint>, names: &Arc<Names>) -> Field {
+    let (cache_endpoint, resolver_names) = (cache_endpoint.clone(), names.clone());
+    Field::new(&names.count, TypeRef::named_nn(UINT64), move |ctx| {
+        let (cache_endpoint, names) = (cache_endpoint.clone(), resolver_names.clone());
+        FieldFuture::new(async move { resolver::count(&ctx, &cache_endpoint, &names).map(Some) })
+    })
+    .argument(filter_argument(names))
+}
+
+/// Only generated if the primary key is a single field of a scalar type.
+fn by_id_field(
+    cache_endpoint: &Arc<RoCacheEndpoint>,
+    names: &Arc<Names>,
+    schema: &Arc<DozerSchema>,
+) -> Option<Field> {
+    let [index] = schema.primary_index[..] else {
+        return None;
+    };
+    let key_type = convert_dozer_type_to_graphql_type(schema.fields[index].typ);
+    if key_type == POINT {
+        return None;
+    }
+    let (cache_endpoint, resolver_names, resolver_schema) =
+        (cache_endpoint.clone(), names.clone(), schema.clone());
+    Some(
+        Field::new(
+            &names.by_id,
+            TypeRef::named(&names.record_with_id),
+            move |ctx| {
+                let (cache_endpoint, names, schema) = (
+                    cache_endpoint.clone(),
+                    resolver_names.clone(),
+                    resolver_schema.clone(),
+                );
+                FieldFuture::new(
+                    async move { resolver::by_id(&ctx, &cache_endpoint, &names, &schema) },
+                )
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named_nn(key_type))),
+    )
+}
+
+fn events_field(
+    cache_endpoint: &Arc<RoCacheEndpoint>,
+    names: &Arc<Names>,
+    schema: &Arc<DozerSchema>,
+    operations_receiver: &Receiver<Operation>,
+) -> SubscriptionField {
+    let endpoint_name = cache_endpoint.endpoint().name.clone();
+    let (resolver_names, schema) = (names.clone(), schema.clone());
+    let operations_receiver = operations_receiver.resubscribe();
+    SubscriptionField::new(&names.events, TypeRef::named_nn(&names.event), move |ctx| {
+        let stream = resolver::events(
+            &ctx,
+            &endpoint_name,
+            &resolver_names,
+            &schema,
+            operations_receiver.resubscribe(),
+        );
+        SubscriptionFieldFuture::new(async move { stream })
+    })
+    .argument(filter_argument(names))
+}
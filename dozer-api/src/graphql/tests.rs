@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_graphql::dynamic::Schema;
+use async_graphql::{Request, Response};
+use dozer_cache::cache::expression::{FilterExpression, Operator};
+use dozer_cache::AccessFilter;
+use dozer_types::grpc_types::types::Operation;
+use dozer_types::models::api_security::ApiSecurity;
+use dozer_types::serde_json::{json, Value};
+use dozer_types::types::{Field, Record};
+use futures_util::StreamExt;
+use tokio::sync::broadcast::{self, Receiver};
+
+use super::{build_schema, connection_init_access};
+use crate::auth::{Access, Authorizer};
+use crate::grpc::types_helper::map_insert_operation;
+use crate::{test_utils, RoCacheEndpoint};
+
+fn films_schema(operations_receiver: Option<&Receiver<Operation>>) -> Schema {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let cache_endpoint = Arc::new(RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap());
+    build_schema(&[cache_endpoint], operations_receiver).unwrap()
+}
+
+fn data(response: Response) -> Value {
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data.into_json().unwrap()
+}
+
+#[test]
+fn test_generate_schema() {
+    let sdl = films_schema(None).sdl();
+    for expected in [
+        "type Film {",
+        "film_id: UInt64!",
+        "description: String",
+        "updated_at: Timestamp",
+        "_dozer_record_version: UInt64!",
+        "type QueryFilmsResponse {",
+        "input FilmFilter {",
+        "enum FilmField {",
+        "films(filter: FilmFilter, order_by: [FilmOrderBy!], limit: Int, skip: Int, cursor: String): QueryFilmsResponse!",
+        "films_count(filter: FilmFilter): UInt64!",
+        "films_by_id(id: UInt64!): FilmWithId",
+    ] {
+        assert!(sdl.contains(expected), "`{expected}` not found in:\n{sdl}");
+    }
+    assert!(!sdl.contains("type Subscription"));
+}
+
+#[tokio::test]
+async fn test_query() {
+    let schema = films_schema(None);
+
+    let response = schema
+        .execute(
+            "{ films(filter: { film_id: { gte: 10, lt: 20 } }, order_by: [{ field: film_id, direction: DESC }], limit: 2) { records { id record { film_id description } } next_cursor } }",
+        )
+        .await;
+    let films = &data(response)["films"];
+    let film_ids = films["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["record"]["film_id"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(film_ids, vec![json!(19), json!(18)]);
+    assert!(films["next_cursor"].is_string());
+
+    let response = schema
+        .execute("{ films_count(filter: { film_id: { lt: 20 } }) }")
+        .await;
+    assert_eq!(data(response)["films_count"], json!(19));
+}
+
+#[tokio::test]
+async fn test_query_by_id() {
+    let schema = films_schema(None);
+
+    let response = schema
+        .execute("{ films_by_id(id: 268) { record { film_id release_year } } }")
+        .await;
+    assert_eq!(
+        data(response)["films_by_id"]["record"],
+        json!({ "film_id": 268, "release_year": 2006 })
+    );
+
+    let response = schema.execute("{ films_by_id(id: 1000) { id } }").await;
+    assert_eq!(data(response)["films_by_id"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_query_without_access() {
+    let schema = films_schema(None);
+
+    let request = Request::new("{ films_count }").data(Access::Custom(HashMap::new()));
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+
+    let request = Request::new("{ films_count }").data(Access::All);
+    assert_eq!(
+        data(schema.execute(request).await)["films_count"],
+        json!(52)
+    );
+}
+
+fn insert(film_id: u64) -> Operation {
+    map_insert_operation(
+        "films".to_string(),
+        Record::new(
+            None,
+            vec![
+                Field::UInt(film_id),
+                Field::String(format!("Film {film_id}")),
+                Field::Null,
+                Field::Null,
+                Field::Null,
+            ],
+            Some(1),
+        ),
+        film_id,
+    )
+}
+
+#[tokio::test]
+async fn test_subscription() {
+    let (sender, receiver) = broadcast::channel(16);
+    let schema = films_schema(Some(&receiver));
+
+    let mut stream = schema.execute_stream(
+        "subscription { films_events(filter: { film_id: { eq: 1000 } }) { typ new_id new { film_id description } } }",
+    );
+    // Polls the stream so the subscription is set up before sending.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .is_err()
+    );
+
+    sender.send(insert(999)).unwrap();
+    sender.send(insert(1000)).unwrap();
+
+    let response = stream.next().await.unwrap();
+    assert_eq!(
+        data(response)["films_events"],
+        json!({
+            "typ": "INSERT",
+            "new_id": 1000,
+            "new": { "film_id": 1000, "description": "Film 1000" }
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_subscription_with_access() {
+    let (sender, receiver) = broadcast::channel(16);
+    let schema = films_schema(Some(&receiver));
+
+    let access = Access::Custom(HashMap::from([(
+        "get_records".to_string(),
+        AccessFilter {
+            filter: Some(FilterExpression::Simple(
+                "film_id".to_string(),
+                Operator::EQ,
+                json!(1000),
+            )),
+            fields: vec![],
+        },
+    )]));
+    let request = Request::new("subscription { films_events { new_id } }").data(access);
+    let mut stream = schema.execute_stream(request);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .is_err()
+    );
+
+    // Only the operation the access filter allows is delivered.
+    sender.send(insert(999)).unwrap();
+    sender.send(insert(1000)).unwrap();
+    sender.send(insert(1001)).unwrap();
+
+    let response = stream.next().await.unwrap();
+    assert_eq!(data(response)["films_events"], json!({ "new_id": 1000 }));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_connection_init_access() {
+    let security = ApiSecurity::Jwt("secret".to_string());
+    let token = Authorizer::from(&security)
+        .generate_token(Access::All, None)
+        .unwrap();
+
+    let payload = json!({ "Authorization": format!("Bearer {token}") });
+    assert_eq!(
+        connection_init_access(&security, &payload).unwrap(),
+        Access::All
+    );
+
+    for payload in [
+        json!({}),
+        json!({ "Authorization": token }),
+        json!({ "Authorization": "Bearer invalid" }),
+    ] {
+        assert!(connection_init_access(&security, &payload).is_err());
+    }
+}
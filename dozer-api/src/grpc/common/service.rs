@@ -3,8 +3,8 @@ use std::sync::Arc;
 
 use crate::auth::Access;
 
-use crate::grpc::shared_impl;
-use crate::grpc::types_helper::{map_field_definitions, map_record};
+use crate::grpc::shared_impl::{self, QueryResult};
+use crate::grpc::types_helper::{map_field_definitions, map_group, map_record};
 use crate::RoCacheEndpoint;
 use dozer_types::grpc_types::common::common_grpc_service_server::CommonGrpcService;
 use tokio_stream::wrappers::ReceiverStream;
@@ -86,16 +86,46 @@ impl CommonGrpcService for CommonService {
         let (cache_endpoint, query_request, access) = self.parse_request(request)?;
 
         let cache_reader = cache_endpoint.cache_reader();
-        let (schema, records) = shared_impl::query(
+        let result = shared_impl::query(
             &cache_reader,
             &cache_endpoint.endpoint.name,
             query_request.query.as_deref(),
             access,
         )?;
 
-        let fields = map_field_definitions(schema.fields.clone());
-        let records = records.into_iter().map(map_record).collect();
-        let reply = QueryResponse { fields, records };
+        let reply = match result {
+            QueryResult::Records {
+                schema,
+                mut records,
+                projection,
+                next_cursor,
+            } => {
+                let mut fields = schema.fields.clone();
+                if let Some(projection) = projection {
+                    // Only return the selected fields.
+                    fields = projection
+                        .iter()
+                        .map(|index| fields[*index].clone())
+                        .collect();
+                    for record in &mut records {
+                        record.record.values = projection
+                            .iter()
+                            .map(|index| record.record.values[*index].clone())
+                            .collect();
+                    }
+                }
+                QueryResponse {
+                    fields: map_field_definitions(fields),
+                    records: records.into_iter().map(map_record).collect(),
+                    next_cursor,
+                }
+            }
+            QueryResult::Aggregation { schema, records } => QueryResponse {
+                fields: map_field_definitions(schema.fields),
+                records: records.into_iter().map(map_group).collect(),
+                next_cursor: None,
+            },
+        };
 
         Ok(Response::new(reply))
     }
@@ -1,8 +1,8 @@
-use dozer_cache::cache::expression::{FilterExpression, Operator};
+use dozer_cache::cache::expression::{FilterExpression, GeoShape, Operator};
 use dozer_types::{
     json_value_to_field,
     ordered_float::OrderedFloat,
-    types::{Field, Schema},
+    types::{DozerPoint, Field, Schema},
 };
 
 use dozer_types::grpc_types::types::{value, Operation, OperationType, Record, Value};
@@ -45,6 +45,18 @@ fn record_satisfies_filter(record: &Record, filter: &FilterExpression, schema: &
                 return false;
             };
 
+            if operator.supported_by_geo() {
+                let Ok(shape) = GeoShape::from_filter(*operator, value) else {
+                    return false;
+                };
+                return match filed_value.value.as_ref() {
+                    Some(value::Value::PointValue(point)) => {
+                        shape.contains(&DozerPoint::from((point.x, point.y)))
+                    }
+                    _ => false,
+                };
+            }
+
             let Ok(value) = json_value_to_field(value.clone(), field_definition.typ, field_definition.nullable) else {
                 return false;
             };
@@ -106,6 +118,8 @@ fn field_satisfies_op(field: &Value, operator: Operator, value: &Field) -> bool
             _ => false,
         },
         Operator::MatchesAll | Operator::MatchesAny => unimplemented!(),
+        // Geo filters are checked in `record_satisfies_filter`.
+        Operator::Near | Operator::WithinBox | Operator::WithinRadius => false,
     }
 }
 
@@ -1,5 +1,5 @@
-use dozer_cache::cache::test_utils::schema_1;
-use dozer_types::serde_json::json;
+use dozer_cache::cache::test_utils::{schema_1, schema_geo};
+use dozer_types::{grpc_types::types::PointType, serde_json::json};
 
 use super::*;
 
@@ -766,6 +766,58 @@ fn test_record_satisfies_filter() {
     );
 }
 
+#[test]
+fn test_record_satisfies_geo_filter() {
+    let schema = schema_geo().0;
+    let record = Record {
+        values: vec![
+            Value {
+                value: Some(value::Value::StringValue("museum".into())),
+            },
+            Value {
+                value: Some(value::Value::PointValue(PointType {
+                    x: 13.3977,
+                    y: 52.5169,
+                })),
+            },
+        ],
+        version: 1,
+    };
+
+    let check = |operator, value, expected| {
+        assert_eq!(
+            record_satisfies_filter(
+                &record,
+                &FilterExpression::Simple("location".into(), operator, value),
+                &schema
+            ),
+            expected
+        );
+    };
+
+    check(
+        Operator::Near,
+        json!({"center": {"x": 13.405, "y": 52.52}, "max_distance": 1000}),
+        true,
+    );
+    check(
+        Operator::WithinRadius,
+        json!({"center": {"x": 13.405, "y": 52.52}, "radius": 100}),
+        false,
+    );
+    check(
+        Operator::WithinBox,
+        json!({"min": {"x": 13.3, "y": 52.5}, "max": {"x": 13.4, "y": 52.6}}),
+        true,
+    );
+    check(
+        Operator::WithinBox,
+        json!({"min": {"x": 13.4, "y": 52.5}, "max": {"x": 13.5, "y": 52.6}}),
+        false,
+    );
+    check(Operator::WithinBox, json!({"x": 13.4, "y": 52.5}), false);
+}
+
 #[test]
 fn test_op_satisfies_filter() {
     let schema = schema_1().0;
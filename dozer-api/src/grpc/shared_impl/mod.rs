@@ -1,16 +1,16 @@
-use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression};
+use dozer_cache::cache::expression::{default_limit_for_query, FilterExpression, QueryExpression};
 use dozer_cache::cache::RecordWithId;
 use dozer_cache::CacheReader;
 use dozer_types::grpc_types::types::Operation;
 use dozer_types::log::warn;
 use dozer_types::serde_json;
-use dozer_types::types::Schema;
+use dozer_types::types::{Record, Schema};
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Code, Response, Status};
 
-use crate::api_helper::{get_records, get_records_count};
+use crate::api_helper::{get_aggregation, get_records, get_records_count};
 use crate::auth::Access;
 
 mod filter;
@@ -50,35 +50,62 @@ pub fn count(
     )?)
 }
 
+pub enum QueryResult<'a> {
+    /// Matching records, the indexes of the fields selected by `$select` if present, and the cursor of the next page.
+    Records {
+        schema: &'a Schema,
+        records: Vec<RecordWithId>,
+        projection: Option<Vec<usize>>,
+        next_cursor: Option<String>,
+    },
+    /// One record per group, described by the schema of the aggregation output.
+    Aggregation {
+        schema: Schema,
+        records: Vec<Record>,
+    },
+}
+
 pub fn query<'a>(
     reader: &'a CacheReader,
     endpoint_name: &'a str,
     query: Option<&str>,
     access: Option<Access>,
-) -> Result<(&'a Schema, Vec<RecordWithId>), Status> {
+) -> Result<QueryResult<'a>, Status> {
     let mut query = parse_query(query, QueryExpression::with_default_limit)?;
     if query.limit.is_none() {
         query.limit = Some(default_limit_for_query());
     }
-    let (schema, records) = get_records(reader, endpoint_name, &mut query, access)?;
-    Ok((schema, records))
+    if query.aggregation.is_some() {
+        let (schema, records) = get_aggregation(reader, endpoint_name, &mut query, access)?;
+        return Ok(QueryResult::Aggregation { schema, records });
+    }
+    let (schema, records, next_cursor) = get_records(reader, endpoint_name, &mut query, access)?;
+    let projection = query
+        .projection(schema)
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    Ok(QueryResult::Records {
+        schema,
+        records,
+        projection,
+        next_cursor,
+    })
 }
 
 pub fn on_event<T: Send + 'static>(
     reader: &CacheReader,
     endpoint_name: &str,
     filter: Option<&str>,
-    mut broadcast_receiver: Option<Receiver<Operation>>,
+    broadcast_receiver: Option<Receiver<Operation>>,
     _access: Option<Access>,
     event_mapper: impl Fn(Operation) -> Option<T> + Send + Sync + 'static,
 ) -> Result<Response<ReceiverStream<T>>, Status> {
     // TODO: Use access.
 
-    if broadcast_receiver.is_none() {
+    let Some(broadcast_receiver) = broadcast_receiver else {
         return Err(Status::unavailable(
             "on_event is not enabled. This is currently an experimental feature. Enable it in the config.",
         ));
-    }
+    };
 
     let filter = match filter {
         Some(filter) => {
@@ -96,33 +123,46 @@ pub fn on_event<T: Send + 'static>(
         .0
         .clone();
 
+    Ok(Response::new(event_stream(
+        schema,
+        filter,
+        broadcast_receiver,
+        event_mapper,
+    )))
+}
+
+/// Streams the operations from `broadcast_receiver` that satisfy `filter`, as mapped by `event_mapper`.
+pub fn event_stream<T: Send + 'static>(
+    schema: Schema,
+    filter: Option<FilterExpression>,
+    mut broadcast_receiver: Receiver<Operation>,
+    event_mapper: impl Fn(Operation) -> Option<T> + Send + Sync + 'static,
+) -> ReceiverStream<T> {
     let (tx, rx) = tokio::sync::mpsc::channel(1);
 
     tokio::spawn(async move {
         loop {
-            if let Some(broadcast_receiver) = broadcast_receiver.as_mut() {
-                let event = broadcast_receiver.recv().await;
-                match event {
-                    Ok(op) => {
-                        if filter::op_satisfies_filter(&op, filter.as_ref(), &schema) {
-                            if let Some(event) = event_mapper(op) {
-                                if (tx.send(event).await).is_err() {
-                                    // receiver dropped
-                                    break;
-                                }
+            let event = broadcast_receiver.recv().await;
+            match event {
+                Ok(op) => {
+                    if filter::op_satisfies_filter(&op, filter.as_ref(), &schema) {
+                        if let Some(event) = event_mapper(op) {
+                            if (tx.send(event).await).is_err() {
+                                // receiver dropped
+                                break;
                             }
                         }
                     }
-                    Err(e) => {
-                        warn!("Failed to receive event from broadcast channel: {}", e);
-                        if e == RecvError::Closed {
-                            break;
-                        }
+                }
+                Err(e) => {
+                    warn!("Failed to receive event from broadcast channel: {}", e);
+                    if e == RecvError::Closed {
+                        break;
                     }
                 }
             }
         }
     });
 
-    Ok(Response::new(ReceiverStream::new(rx)))
+    ReceiverStream::new(rx)
 }
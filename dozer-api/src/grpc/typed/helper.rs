@@ -137,6 +137,7 @@ pub fn count_response_to_typed_response(
 
 pub fn query_response_to_typed_response(
     records: Vec<RecordWithId>,
+    next_cursor: Option<String>,
     response_desc: QueryResponseDesc,
 ) -> TypedResponse {
     let mut msg = DynamicMessage::new(response_desc.message);
@@ -153,6 +154,11 @@ pub fn query_response_to_typed_response(
         &response_desc.records_field,
         prost_reflect::Value::List(data),
     );
+    if let (Some(next_cursor_field), Some(next_cursor)) =
+        (&response_desc.next_cursor_field, next_cursor)
+    {
+        msg.set_field(next_cursor_field, prost_reflect::Value::String(next_cursor));
+    }
     TypedResponse::new(msg)
 }
 
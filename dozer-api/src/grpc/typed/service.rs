@@ -13,11 +13,11 @@ use crate::{
         CountResponseDesc, EventDesc, ProtoGenerator, QueryResponseDesc, ServiceDesc,
         TokenResponseDesc,
     },
-    grpc::shared_impl,
+    grpc::shared_impl::{self, QueryResult},
     RoCacheEndpoint,
 };
 use dozer_cache::CacheReader;
-use dozer_types::{grpc_types::types::Operation, models::api_security::ApiSecurity};
+use dozer_types::{grpc_types::types::Operation, models::api_security::ApiSecurity, types::Field};
 use futures_util::future;
 use prost_reflect::{MethodDescriptor, Value};
 use std::{borrow::Cow, collections::HashMap, convert::Infallible, path::Path};
@@ -324,8 +324,33 @@ fn query(
     let mut parts = request.into_parts();
     let (query, access) = parse_request(&mut parts)?;
 
-    let (_, records) = shared_impl::query(reader, endpoint_name, query.as_deref(), access)?;
-    let res = query_response_to_typed_response(records, response_desc);
+    let result = shared_impl::query(reader, endpoint_name, query.as_deref(), access)?;
+    let (records, next_cursor) = match result {
+        QueryResult::Records {
+            mut records,
+            projection,
+            next_cursor,
+            ..
+        } => {
+            if let Some(projection) = projection {
+                // Fields not selected are left unset in the typed response.
+                for record in &mut records {
+                    for (index, value) in record.record.values.iter_mut().enumerate() {
+                        if !projection.contains(&index) {
+                            *value = Field::Null;
+                        }
+                    }
+                }
+            }
+            (records, next_cursor)
+        }
+        QueryResult::Aggregation { .. } => {
+            return Err(Status::invalid_argument(
+                "$group_by and $aggregate are only supported by the common gRPC service",
+            ))
+        }
+    };
+    let res = query_response_to_typed_response(records, next_cursor, response_desc);
     Ok(Response::new(res))
 }
 
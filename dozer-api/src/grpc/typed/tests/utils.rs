@@ -16,7 +16,8 @@ fn test_records_to_typed_response() {
     let service_desc = ProtoGenerator::read_schema(&path, "films").unwrap();
 
     let records = get_sample_records(schema);
-    let res = query_response_to_typed_response(records, service_desc.query.response_desc.clone());
+    let res =
+        query_response_to_typed_response(records, None, service_desc.query.response_desc.clone());
     let records = res
         .message
         .get_field_by_name(service_desc.query.response_desc.records_field.name());
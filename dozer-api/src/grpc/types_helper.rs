@@ -64,6 +64,21 @@ pub fn map_record(record: CacheRecordWithId) -> RecordWithId {
     }
 }
 
+/// Maps a record of an aggregation output, which has neither id nor version.
+pub fn map_group(record: DozerRecord) -> RecordWithId {
+    RecordWithId {
+        id: 0,
+        record: Some(Record {
+            values: record
+                .values
+                .into_iter()
+                .map(field_to_prost_value)
+                .collect(),
+            version: 0,
+        }),
+    }
+}
+
 fn map_x_y_to_prost_coord_map((x, y): (OrderedFloat<f64>, OrderedFloat<f64>)) -> Value {
     Value {
         value: Some(value::Value::PointValue(PointType { x: x.0, y: y.0 })),
@@ -123,6 +138,12 @@ fn field_to_prost_value(f: Field) -> Value {
             )),
         },
         Field::Point(point) => map_x_y_to_prost_coord_map(point.0.x_y()),
+        Field::Duration(duration) => Value {
+            value: Some(value::Value::StringValue(duration.to_string())),
+        },
+        Field::BigInt(i) => Value {
+            value: Some(value::Value::StringValue(i.to_string())),
+        },
     }
 }
 
@@ -153,5 +174,7 @@ fn field_type_to_internal_type(typ: FieldType) -> Type {
         FieldType::Bson => Type::Bson,
         FieldType::Date => Type::String,
         FieldType::Point => Type::Point,
+        FieldType::Duration => Type::String,
+        FieldType::BigInt => Type::String,
     }
 }
@@ -50,6 +50,7 @@ fn open_cache_reader(
 pub mod auth;
 pub mod errors;
 pub mod generator;
+pub mod graphql;
 pub mod grpc;
 pub mod rest;
 // Re-exports
@@ -5,22 +5,22 @@ use actix_web::{web, HttpResponse};
 use dozer_cache::cache::expression::{default_limit_for_query, QueryExpression, Skip};
 use dozer_cache::cache::{index, RecordWithId};
 use dozer_cache::CacheReader;
-use dozer_types::chrono::SecondsFormat;
 use dozer_types::errors::types::TypeError;
+use dozer_types::field_to_json_value;
 use dozer_types::indexmap::IndexMap;
 use dozer_types::log::info;
 use dozer_types::models::api_endpoint::ApiEndpoint;
-use dozer_types::ordered_float::OrderedFloat;
-use dozer_types::types::{Field, Schema, DATE_FORMAT};
+use dozer_types::types::{Field, Record, Schema};
 use openapiv3::OpenAPI;
 
-use crate::api_helper::{get_record, get_records, get_records_count};
+use crate::api_helper::{get_aggregation, get_plan, get_record, get_records, get_records_count};
 use crate::generator::oapi::generator::OpenApiGenerator;
+use crate::rest::NEXT_CURSOR_HEADER;
 use crate::RoCacheEndpoint;
 use crate::{auth::Access, errors::ApiError};
 use dozer_types::grpc_types::health::health_check_response::ServingStatus;
 use dozer_types::serde_json;
-use dozer_types::serde_json::{json, Map, Value};
+use dozer_types::serde_json::{json, Value};
 
 fn generate_oapi3(reader: &CacheReader, endpoint: ApiEndpoint) -> Result<OpenAPI, ApiError> {
     let (schema, secondary_indexes) = reader
@@ -77,7 +77,7 @@ pub async fn get(
         access.map(|a| a.into_inner()),
     )?;
 
-    Ok(record_to_map(record, schema).map(|map| HttpResponse::Ok().json(map))?)
+    Ok(record_to_map(record, schema, None).map(|map| HttpResponse::Ok().json(map))?)
 }
 
 // Generated list function for multiple records with a default query expression
@@ -87,7 +87,7 @@ pub async fn list(
 ) -> Result<HttpResponse, ApiError> {
     let mut exp = QueryExpression::new(None, vec![], Some(50), Skip::Skip(0));
     match get_records_map(access, cache_endpoint, &mut exp) {
-        Ok(maps) => Ok(HttpResponse::Ok().json(maps)),
+        Ok((maps, next_cursor)) => Ok(records_response(maps, next_cursor)),
         Err(e) => match e {
             ApiError::QueryFailed(_) => {
                 let res: Vec<String> = vec![];
@@ -141,41 +141,81 @@ pub async fn query(
         query_expression.limit = Some(default_limit_for_query());
     }
 
+    if query_expression.explain {
+        return get_plan(
+            &cache_endpoint.cache_reader(),
+            &cache_endpoint.endpoint.name,
+            &mut query_expression,
+            access.map(|a| a.into_inner()),
+        )
+        .map(|plan| HttpResponse::Ok().json(plan));
+    }
+
     get_records_map(access, cache_endpoint, &mut query_expression)
-        .map(|maps| HttpResponse::Ok().json(maps))
+        .map(|(maps, next_cursor)| records_response(maps, next_cursor))
+}
+
+fn records_response(
+    maps: Vec<IndexMap<String, Value>>,
+    next_cursor: Option<String>,
+) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    if let Some(next_cursor) = next_cursor {
+        response.insert_header((NEXT_CURSOR_HEADER, next_cursor));
+    }
+    response.json(maps)
 }
 
-/// Get multiple records
+/// Get multiple records, and the cursor of the next page
 fn get_records_map(
     access: Option<ReqData<Access>>,
     cache_endpoint: ReqData<Arc<RoCacheEndpoint>>,
     exp: &mut QueryExpression,
-) -> Result<Vec<IndexMap<String, Value>>, ApiError> {
+) -> Result<(Vec<IndexMap<String, Value>>, Option<String>), ApiError> {
     let mut maps = vec![];
     let cache_reader = &cache_endpoint.cache_reader();
-    let (schema, records) = get_records(
+    if exp.aggregation.is_some() {
+        let (schema, records) = get_aggregation(
+            cache_reader,
+            &cache_endpoint.endpoint.name,
+            exp,
+            access.map(|a| a.into_inner()),
+        )?;
+        for record in records.into_iter() {
+            maps.push(group_to_map(record, &schema));
+        }
+        return Ok((maps, None));
+    }
+
+    let (schema, records, next_cursor) = get_records(
         cache_reader,
         &cache_endpoint.endpoint.name,
         exp,
         access.map(|a| a.into_inner()),
     )?;
+    let projection = exp
+        .projection(schema)
+        .map_err(|e| ApiError::QueryFailed(e.into()))?;
     for record in records.into_iter() {
-        let map = record_to_map(record, schema)?;
+        let map = record_to_map(record, schema, projection.as_deref())?;
         maps.push(map);
     }
-    Ok(maps)
+    Ok((maps, next_cursor))
 }
 
-/// Used in REST APIs for converting to JSON
+/// Used in REST APIs for converting to JSON, keeping only the fields in `projection` if present
 fn record_to_map(
     record: RecordWithId,
     schema: &Schema,
+    projection: Option<&[usize]>,
 ) -> Result<IndexMap<String, Value>, TypeError> {
     let mut map = IndexMap::new();
 
-    for (field_def, field) in schema.fields.iter().zip(record.record.values) {
-        let val = field_to_json_value(field);
-        map.insert(field_def.name.clone(), val);
+    for (index, (field_def, field)) in schema.fields.iter().zip(record.record.values).enumerate() {
+        if projection.map_or(true, |projection| projection.contains(&index)) {
+            let val = field_to_json_value(field);
+            map.insert(field_def.name.clone(), val);
+        }
     }
 
     map.insert("__dozer_record_id".to_string(), Value::from(record.id));
@@ -187,32 +227,14 @@ fn record_to_map(
     Ok(map)
 }
 
-fn convert_x_y_to_object((x, y): &(OrderedFloat<f64>, OrderedFloat<f64>)) -> Value {
-    let mut m = Map::new();
-    m.insert("x".to_string(), Value::from(x.0));
-    m.insert("y".to_string(), Value::from(y.0));
-    Value::Object(m)
-}
-
-/// Used in REST APIs for converting raw value back and forth.
-///
-/// Should be consistent with `convert_cache_type_to_schema_type`.
-fn field_to_json_value(field: Field) -> Value {
-    match field {
-        Field::UInt(n) => Value::from(n),
-        Field::Int(n) => Value::from(n),
-        Field::Float(n) => Value::from(n.0),
-        Field::Boolean(b) => Value::from(b),
-        Field::String(s) => Value::from(s),
-        Field::Text(n) => Value::from(n),
-        Field::Binary(b) => Value::from(b),
-        Field::Decimal(n) => Value::String(n.to_string()),
-        Field::Timestamp(ts) => Value::String(ts.to_rfc3339_opts(SecondsFormat::Millis, true)),
-        Field::Date(n) => Value::String(n.format(DATE_FORMAT).to_string()),
-        Field::Bson(b) => Value::from(b),
-        Field::Point(point) => convert_x_y_to_object(&point.0.x_y()),
-        Field::Null => Value::Null,
-    }
+/// Converts an aggregation output record, which has no id or version
+fn group_to_map(record: Record, schema: &Schema) -> IndexMap<String, Value> {
+    schema
+        .fields
+        .iter()
+        .zip(record.values)
+        .map(|(field_def, field)| (field_def.name.clone(), field_to_json_value(field)))
+        .collect()
 }
 
 #[cfg(test)]
@@ -222,7 +244,7 @@ mod tests {
         json_value_to_field,
         ordered_float::OrderedFloat,
         rust_decimal::Decimal,
-        types::{DozerPoint, Field, FieldType},
+        types::{DozerBigInt, DozerDuration, DozerPoint, Field, FieldType},
     };
 
     use super::*;
@@ -267,6 +289,14 @@ mod tests {
                 FieldType::Point,
                 Field::Point(DozerPoint::from((3.234, 4.567))),
             ),
+            (
+                FieldType::Duration,
+                Field::Duration(DozerDuration::new(1, 2, 3_500_000_000)),
+            ),
+            (
+                FieldType::BigInt,
+                Field::BigInt(DozerBigInt::from_unsigned_bytes_be(&[0xff; 32]).unwrap()),
+            ),
         ];
         for (field_type, field) in fields {
             test_field_conversion(field_type, field);
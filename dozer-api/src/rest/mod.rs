@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 // Exports
 use crate::errors::ApiError;
+use crate::graphql;
 use crate::rest::api_generator::health_route;
 use crate::{
     auth::api::{auth_route, validate},
@@ -15,15 +16,21 @@ use actix_web::{
     rt, web, App, HttpMessage, HttpServer,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use async_graphql::dynamic::Schema as GraphQLSchema;
+use dozer_types::grpc_types::types::Operation;
 use dozer_types::{crossbeam::channel::Sender, log::info, models::api_config::RestApiOptions};
 use dozer_types::{
     models::api_security::ApiSecurity,
     serde::{self, Deserialize, Serialize},
 };
+use tokio::sync::broadcast::Receiver;
 use tracing_actix_web::TracingLogger;
 
 mod api_generator;
 
+/// Response header of `query` and `list` carrying the `$cursor` of the next page, absent on the last page.
+pub const NEXT_CURSOR_HEADER: &str = "X-Dozer-Next-Cursor";
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 #[serde(crate = "self::serde")]
 enum CorsOptions {
@@ -68,6 +75,7 @@ impl ApiServer {
             CorsOptions::Custom(origins, max_age) => origins
                 .into_iter()
                 .fold(Cors::default(), |cors, origin| cors.allowed_origin(&origin))
+                .expose_headers([NEXT_CURSOR_HEADER])
                 .max_age(max_age),
         }
     }
@@ -76,6 +84,7 @@ impl ApiServer {
         security: Option<ApiSecurity>,
         cors: CorsOptions,
         cache_endpoints: Vec<Arc<RoCacheEndpoint>>,
+        graphql_schema: Option<GraphQLSchema>,
     ) -> App<
         impl ServiceFactory<
             ServiceRequest,
@@ -97,10 +106,17 @@ impl ApiServer {
             false
         };
         let auth_middleware =
-            Condition::new(is_auth_configured, HttpAuthentication::bearer(validate));
+            Condition::new(is_auth_configured, HttpAuthentication::with_fn(validate));
 
         let cors_middleware = Self::get_cors(cors);
 
+        if let Some(graphql_schema) = graphql_schema {
+            app = app
+                .app_data(web::Data::new(graphql_schema))
+                .route("/graphql", web::post().to(graphql::graphql))
+                .route(graphql::GRAPHQL_WS_PATH, web::get().to(graphql::graphql_ws));
+        }
+
         cache_endpoints
             .into_iter()
             .fold(app, |app, cache_endpoint| {
@@ -131,9 +147,11 @@ impl ApiServer {
             .wrap(cors_middleware)
     }
 
+    /// Also serves GraphQL at `/graphql` if there are endpoints, with subscriptions at `/graphql/ws` if `operations_receiver` is given.
     pub async fn run(
         &self,
         cache_endpoints: Vec<Arc<RoCacheEndpoint>>,
+        operations_receiver: Option<Receiver<Operation>>,
         tx: Sender<ServerHandle>,
     ) -> Result<(), ApiError> {
         info!(
@@ -146,11 +164,24 @@ impl ApiServer {
                     ApiSecurity::Jwt(_) => "JWT".to_string(),
                 })
         );
+        let graphql_schema = if cache_endpoints.is_empty() {
+            None
+        } else {
+            Some(graphql::build_schema(
+                &cache_endpoints,
+                operations_receiver.as_ref(),
+            )?)
+        };
         let cors = self.cors.clone();
         let security = self.security.clone();
         let address = format!("{}:{}", self.host, self.port);
         let server = HttpServer::new(move || {
-            ApiServer::create_app_entry(security.clone(), cors.clone(), cache_endpoints.clone())
+            ApiServer::create_app_entry(
+                security.clone(),
+                cors.clone(),
+                cache_endpoints.clone(),
+                graphql_schema.clone(),
+            )
         })
         .bind(&address)
         .map_err(|e| ApiError::FailedToBindToAddress(address, e))?
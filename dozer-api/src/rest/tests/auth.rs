@@ -3,7 +3,7 @@ use std::sync::Arc;
 use super::super::{ApiServer, CorsOptions};
 use crate::{
     auth::{Access, Authorizer},
-    test_utils, RoCacheEndpoint,
+    graphql, test_utils, RoCacheEndpoint,
 };
 use actix_web::{body::MessageBody, dev::ServiceResponse};
 use dozer_types::{
@@ -60,6 +60,36 @@ async fn verify_token_test() {
     assert!(res.status().is_success());
 }
 
+#[actix_web::test]
+async fn graphql_ws_defers_authentication() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let cache_endpoints = vec![Arc::new(
+        RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap(),
+    )];
+    let graphql_schema = graphql::build_schema(&cache_endpoints, None).unwrap();
+    let api_server = ApiServer::create_app_entry(
+        Some(ApiSecurity::Jwt("secret".to_string())),
+        CorsOptions::Permissive,
+        cache_endpoints,
+        Some(graphql_schema),
+    );
+    let app = actix_web::test::init_service(api_server).await;
+
+    // The token is sent in the `connection_init` message, after the upgrade.
+    let req = actix_web::test::TestRequest::get()
+        .uri(graphql::GRAPHQL_WS_PATH)
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_ne!(res.status().as_u16(), 401);
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/graphql")
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+    assert_eq!(res.status().as_u16(), 401, "Should be unauthorized.");
+}
+
 async fn check_status(
     security: Option<ApiSecurity>,
     token: Option<String>,
@@ -72,6 +102,7 @@ async fn check_status(
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -100,6 +131,7 @@ async fn _call_auth_token_api(
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
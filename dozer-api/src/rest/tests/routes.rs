@@ -1,7 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use super::super::{ApiServer, CorsOptions};
-use crate::{generator::oapi::generator::OpenApiGenerator, test_utils, RoCacheEndpoint};
+use crate::{generator::oapi::generator::OpenApiGenerator, graphql, test_utils, RoCacheEndpoint};
 use actix_http::{body::MessageBody, Request};
 use actix_web::dev::{Service, ServiceResponse};
 use dozer_types::serde_json::{json, Value};
@@ -32,6 +32,7 @@ async fn list_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -91,6 +92,7 @@ async fn count_and_query_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
 
@@ -136,6 +138,7 @@ async fn get_route() {
         vec![Arc::new(
             RoCacheEndpoint::new(&*cache_manager, endpoint.clone()).unwrap(),
         )],
+        None,
     );
     let app = actix_web::test::init_service(api_server).await;
     let req = actix_web::test::TestRequest::get()
@@ -154,3 +157,33 @@ async fn get_route() {
         "Must be equal"
     );
 }
+
+#[actix_web::test]
+async fn graphql_route() {
+    let endpoint = test_utils::get_endpoint();
+    let cache_manager = test_utils::initialize_cache(&endpoint.name, None);
+    let cache_endpoints = vec![Arc::new(
+        RoCacheEndpoint::new(&*cache_manager, endpoint).unwrap(),
+    )];
+    let graphql_schema = graphql::build_schema(&cache_endpoints, None).unwrap();
+    let api_server = ApiServer::create_app_entry(
+        None,
+        CorsOptions::Permissive,
+        cache_endpoints,
+        Some(graphql_schema),
+    );
+    let app = actix_web::test::init_service(api_server).await;
+    let req = actix_web::test::TestRequest::post()
+        .uri("/graphql")
+        .set_json(json!({ "query": "{ films_by_id(id: 268) { record { film_id } } }" }))
+        .to_request();
+
+    let res = actix_web::test::call_service(&app, req).await;
+    assert!(res.status().is_success());
+
+    let body: Value = actix_web::test::read_body_json(res).await;
+    assert_eq!(
+        body,
+        json!({ "data": { "films_by_id": { "record": { "film_id": 268 } } } })
+    );
+}
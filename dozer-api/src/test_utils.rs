@@ -64,8 +64,11 @@ pub fn get_endpoint() -> ApiEndpoint {
         path: "/films".to_string(),
         index: Some(ApiIndex {
             primary_key: vec!["film_id".to_string()],
+            full_text: vec![],
         }),
         table_name: "film".to_string(),
+        sinks: vec![],
+        retention: None,
     }
 }
 
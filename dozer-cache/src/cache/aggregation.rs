@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::prelude::ToPrimitive;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+
+use super::expression::{AggregateFunction, Aggregation, Skip, SortDirection, SortOptions};
+use crate::errors::PlanError;
+
+/// Groups records and computes the aggregates of every group.
+///
+/// The output has the group by fields followed by one field per aggregate.
+pub struct Aggregator {
+    group_by: Vec<usize>,
+    aggregates: Vec<(AggregateFunction, Option<usize>)>,
+    output_schema: Schema,
+    groups: BTreeMap<Vec<Field>, Vec<Accumulator>>,
+}
+
+impl Aggregator {
+    pub fn new(schema: &Schema, aggregation: &Aggregation) -> Result<Self, PlanError> {
+        let mut output_fields: Vec<FieldDefinition> = vec![];
+        let mut add_output_field = |field: FieldDefinition| {
+            if output_fields.iter().any(|output| output.name == field.name) {
+                return Err(PlanError::DuplicateAggregateField(field.name));
+            }
+            output_fields.push(field);
+            Ok(())
+        };
+
+        let mut group_by = vec![];
+        for field_name in &aggregation.group_by {
+            let index = field_index(schema, field_name)?;
+            add_output_field(schema.fields[index].clone())?;
+            group_by.push(index);
+        }
+
+        let mut aggregates = vec![];
+        for aggregate in &aggregation.aggregates.0 {
+            let index = aggregate
+                .field_name
+                .as_ref()
+                .map(|field_name| field_index(schema, field_name))
+                .transpose()?;
+            let field_type = index.map(|index| schema.fields[index].typ);
+            let (typ, nullable) = match (aggregate.function, field_type) {
+                (AggregateFunction::Count, _) => (FieldType::UInt, false),
+                (
+                    AggregateFunction::Sum,
+                    Some(
+                        typ @ (FieldType::Int
+                        | FieldType::UInt
+                        | FieldType::Float
+                        | FieldType::Decimal),
+                    ),
+                ) => (typ, true),
+                (
+                    AggregateFunction::Avg,
+                    Some(FieldType::Int | FieldType::UInt | FieldType::Float | FieldType::Decimal),
+                ) => (FieldType::Float, true),
+                (AggregateFunction::Min | AggregateFunction::Max, Some(typ))
+                    if typ != FieldType::Bson =>
+                {
+                    (typ, true)
+                }
+                (function, typ) => {
+                    return Err(PlanError::UnsupportedAggregate(
+                        function,
+                        aggregate.field_name.clone().unwrap_or_default(),
+                        typ.unwrap_or(FieldType::Bson),
+                    ))
+                }
+            };
+            add_output_field(FieldDefinition {
+                name: aggregate.alias.clone(),
+                typ,
+                nullable,
+                source: SourceDefinition::Dynamic,
+            })?;
+            aggregates.push((aggregate.function, index));
+        }
+
+        Ok(Self {
+            output_schema: Schema {
+                identifier: None,
+                fields: output_fields,
+                primary_index: (0..group_by.len()).collect(),
+            },
+            group_by,
+            aggregates,
+            groups: BTreeMap::new(),
+        })
+    }
+
+    /// If true, the output only depends on the number of records, see `add_count`.
+    pub fn only_counts_records(&self) -> bool {
+        self.group_by.is_empty()
+            && self
+                .aggregates
+                .iter()
+                .all(|(function, index)| *function == AggregateFunction::Count && index.is_none())
+    }
+
+    /// Counts `count` records at once, only valid if `only_counts_records`.
+    pub fn add_count(&mut self, count: usize) {
+        debug_assert!(self.only_counts_records());
+        for accumulator in self.group(vec![]) {
+            accumulator.count += count as u64;
+        }
+    }
+
+    pub fn add(&mut self, values: &[Field]) -> Result<(), PlanError> {
+        let key = self
+            .group_by
+            .iter()
+            .map(|index| values[*index].clone())
+            .collect();
+        let num_aggregates = self.aggregates.len();
+        let accumulators = self
+            .groups
+            .entry(key)
+            .or_insert_with(|| vec![Accumulator::default(); num_aggregates]);
+        let output_fields = &self.output_schema.fields[self.group_by.len()..];
+        for (((function, index), accumulator), output_field) in
+            self.aggregates.iter().zip(accumulators).zip(output_fields)
+        {
+            match index {
+                None => accumulator.count += 1,
+                Some(index) => accumulator
+                    .add(*function, &values[*index])
+                    .ok_or_else(|| PlanError::SumOverflow(output_field.name.clone()))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the output schema and one record per group, sorted by `order_by` or by the group by fields.
+    pub fn finish(
+        mut self,
+        order_by: &SortOptions,
+        skip: Skip,
+        limit: Option<usize>,
+    ) -> Result<(Schema, Vec<Record>), PlanError> {
+        // Without group by, there's always exactly one output record.
+        if self.group_by.is_empty() && self.groups.is_empty() {
+            self.group(vec![]);
+        }
+
+        let mut sort_fields = vec![];
+        for sort_option in &order_by.0 {
+            let index = field_index(&self.output_schema, &sort_option.field_name)?;
+            sort_fields.push((index, sort_option.direction));
+        }
+        let skip = match skip {
+            Skip::Skip(skip) => skip,
+            Skip::After(_) => return Err(PlanError::AfterWithAggregation),
+        };
+
+        let aggregates = self.aggregates;
+        let mut rows = self
+            .groups
+            .into_iter()
+            .map(|(mut key, accumulators)| {
+                key.extend(
+                    aggregates
+                        .iter()
+                        .zip(accumulators)
+                        .map(|((function, _), accumulator)| accumulator.finish(*function)),
+                );
+                key
+            })
+            .collect::<Vec<_>>();
+        if !sort_fields.is_empty() {
+            rows.sort_by(|a, b| {
+                sort_fields
+                    .iter()
+                    .map(|(index, direction)| match direction {
+                        SortDirection::Ascending => a[*index].cmp(&b[*index]),
+                        SortDirection::Descending => b[*index].cmp(&a[*index]),
+                    })
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        let records = rows
+            .into_iter()
+            .skip(skip)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|values| Record::new(None, values, None))
+            .collect();
+        Ok((self.output_schema, records))
+    }
+
+    fn group(&mut self, key: Vec<Field>) -> &mut Vec<Accumulator> {
+        let num_aggregates = self.aggregates.len();
+        self.groups
+            .entry(key)
+            .or_insert_with(|| vec![Accumulator::default(); num_aggregates])
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    /// Number of non-null values seen.
+    count: u64,
+    /// Sum, min or max of the values seen.
+    value: Option<Field>,
+    /// Sum of the values seen, for `Avg`.
+    float_sum: f64,
+}
+
+impl Accumulator {
+    /// Returns `None` if the sum overflows.
+    fn add(&mut self, function: AggregateFunction, field: &Field) -> Option<()> {
+        if field == &Field::Null {
+            return Some(());
+        }
+        self.count += 1;
+        match function {
+            AggregateFunction::Count => {}
+            AggregateFunction::Sum => {
+                self.value = Some(match self.value.take() {
+                    Some(sum) => add(sum, field)?,
+                    None => field.clone(),
+                });
+            }
+            AggregateFunction::Min => {
+                if self.value.as_ref().map_or(true, |min| field < min) {
+                    self.value = Some(field.clone());
+                }
+            }
+            AggregateFunction::Max => {
+                if self.value.as_ref().map_or(true, |max| field > max) {
+                    self.value = Some(field.clone());
+                }
+            }
+            AggregateFunction::Avg => self.float_sum += to_f64(field),
+        }
+        Some(())
+    }
+
+    fn finish(self, function: AggregateFunction) -> Field {
+        match function {
+            AggregateFunction::Count => Field::UInt(self.count),
+            AggregateFunction::Sum | AggregateFunction::Min | AggregateFunction::Max => {
+                self.value.unwrap_or(Field::Null)
+            }
+            AggregateFunction::Avg => {
+                if self.count == 0 {
+                    Field::Null
+                } else {
+                    Field::Float(OrderedFloat(self.float_sum / self.count as f64))
+                }
+            }
+        }
+    }
+}
+
+/// Returns `None` on overflow. Field types are validated in `Aggregator::new`.
+fn add(sum: Field, field: &Field) -> Option<Field> {
+    Some(match (sum, field) {
+        (Field::Int(sum), Field::Int(value)) => Field::Int(sum.checked_add(*value)?),
+        (Field::UInt(sum), Field::UInt(value)) => Field::UInt(sum.checked_add(*value)?),
+        (Field::Float(sum), Field::Float(value)) => Field::Float(sum + value),
+        (Field::Decimal(sum), Field::Decimal(value)) => Field::Decimal(sum.checked_add(*value)?),
+        (sum, _) => sum,
+    })
+}
+
+fn to_f64(field: &Field) -> f64 {
+    match field {
+        Field::Int(value) => *value as f64,
+        Field::UInt(value) => *value as f64,
+        Field::Float(value) => value.0,
+        Field::Decimal(value) => value.to_f64().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    }
+}
+
+fn field_index(schema: &Schema, field_name: &str) -> Result<usize, PlanError> {
+    schema
+        .fields
+        .iter()
+        .position(|field| field.name == field_name)
+        .ok_or_else(|| PlanError::FieldNotFound(field_name.to_string()))
+}
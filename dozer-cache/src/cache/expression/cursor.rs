@@ -0,0 +1,30 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use dozer_types::bincode;
+use dozer_types::serde::{Deserialize, Serialize};
+use dozer_types::types::Field;
+
+/// Position of the last record returned by a query. Passing it back as `$cursor` continues right after that record.
+///
+/// Clients see it as an opaque string, see `encode` and `decode`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum Cursor {
+    /// Key in the scanned index, or in the primary index, and id of the last record.
+    ///
+    /// The key is empty if the plan returns records in id order.
+    Key { key: Vec<u8>, id: u64 },
+    /// Values of the `order_by` fields and id of the last record, for full scans.
+    Sorted { values: Vec<Field>, id: u64 },
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let bytes = bincode::serialize(self).expect("Cursor can always be serialized");
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        bincode::deserialize(&bytes).ok()
+    }
+}
@@ -0,0 +1,178 @@
+use dozer_types::geo::GeodesicDistance;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde::{de::DeserializeOwned, Deserialize, Serialize};
+use dozer_types::serde_json::{self, Value};
+use dozer_types::types::DozerPoint;
+
+use super::Operator;
+use crate::errors::PlanError;
+
+/// Mean radius of the earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// The area matched by a geo filter.
+///
+/// Points are written as `{"x": longitude, "y": latitude}` in degrees, and distances are in meters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum GeoShape {
+    /// `$within_box`, written as `{"min": point, "max": point}`.
+    Box { min: DozerPoint, max: DozerPoint },
+    /// `$within_radius`, written as `{"center": point, "radius": meters}`,
+    /// or `$near`, written as `{"center": point, "max_distance": meters}`.
+    Circle {
+        center: DozerPoint,
+        radius: OrderedFloat<f64>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "dozer_types::serde", deny_unknown_fields)]
+struct BoxArgs {
+    min: DozerPoint,
+    max: DozerPoint,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "dozer_types::serde", deny_unknown_fields)]
+struct RadiusArgs {
+    center: DozerPoint,
+    radius: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "dozer_types::serde", deny_unknown_fields)]
+struct NearArgs {
+    center: DozerPoint,
+    max_distance: f64,
+}
+
+impl GeoShape {
+    /// Parses the value of a `$near`, `$within_box` or `$within_radius` filter.
+    pub fn from_filter(operator: Operator, value: &Value) -> Result<Self, PlanError> {
+        let shape = match operator {
+            Operator::WithinBox => {
+                let BoxArgs { min, max } = parse_args(value)?;
+                if min.0.x() > max.0.x() || min.0.y() > max.0.y() {
+                    return Err(PlanError::InvalidGeoFilter(
+                        "min of $within_box must not be greater than max".to_string(),
+                    ));
+                }
+                GeoShape::Box { min, max }
+            }
+            Operator::WithinRadius => {
+                let RadiusArgs { center, radius } = parse_args(value)?;
+                GeoShape::Circle {
+                    center,
+                    radius: OrderedFloat(radius),
+                }
+            }
+            Operator::Near => {
+                let NearArgs {
+                    center,
+                    max_distance,
+                } = parse_args(value)?;
+                GeoShape::Circle {
+                    center,
+                    radius: OrderedFloat(max_distance),
+                }
+            }
+            other => panic!("operator {other:?} is not a geo operator"),
+        };
+
+        match &shape {
+            GeoShape::Box { min, max } => {
+                check_point(min)?;
+                check_point(max)?;
+            }
+            GeoShape::Circle { center, radius } => {
+                check_point(center)?;
+                if !radius.0.is_finite() || radius.0 < 0.0 {
+                    return Err(PlanError::InvalidGeoFilter(format!(
+                        "distance must be a non-negative number of meters, got {radius}"
+                    )));
+                }
+            }
+        }
+        Ok(shape)
+    }
+
+    pub fn contains(&self, point: &DozerPoint) -> bool {
+        match self {
+            GeoShape::Box { min, max } => {
+                min.0.x() <= point.0.x()
+                    && point.0.x() <= max.0.x()
+                    && min.0.y() <= point.0.y()
+                    && point.0.y() <= max.0.y()
+            }
+            GeoShape::Circle { center, radius } => center.geodesic_distance(point) <= *radius,
+        }
+    }
+
+    /// Returns `(min, max)` boxes that together cover the shape. Circles crossing the antimeridian are split in two boxes.
+    pub fn bounding_boxes(&self) -> Vec<(DozerPoint, DozerPoint)> {
+        match self {
+            GeoShape::Box { min, max } => vec![(*min, *max)],
+            GeoShape::Circle { center, radius } => {
+                // Distances on the sphere differ from geodesic distances by less than 1%.
+                let angle = radius.0 * 1.01 / EARTH_RADIUS;
+                let (x, y) = (center.0.x().0, center.0.y().0);
+                let min_y = y - angle.to_degrees();
+                let max_y = y + angle.to_degrees();
+                if min_y <= -90.0 || max_y >= 90.0 {
+                    // The circle contains a pole, so all longitudes.
+                    return vec![(
+                        DozerPoint::from((-180.0, min_y.max(-90.0))),
+                        DozerPoint::from((180.0, max_y.min(90.0))),
+                    )];
+                }
+
+                let delta_x = (angle.sin() / y.to_radians().cos()).asin().to_degrees();
+                let (min_x, max_x) = (x - delta_x, x + delta_x);
+                if min_x < -180.0 {
+                    vec![
+                        (
+                            DozerPoint::from((min_x + 360.0, min_y)),
+                            DozerPoint::from((180.0, max_y)),
+                        ),
+                        (
+                            DozerPoint::from((-180.0, min_y)),
+                            DozerPoint::from((max_x, max_y)),
+                        ),
+                    ]
+                } else if max_x > 180.0 {
+                    vec![
+                        (
+                            DozerPoint::from((min_x, min_y)),
+                            DozerPoint::from((180.0, max_y)),
+                        ),
+                        (
+                            DozerPoint::from((-180.0, min_y)),
+                            DozerPoint::from((max_x - 360.0, max_y)),
+                        ),
+                    ]
+                } else {
+                    vec![(
+                        DozerPoint::from((min_x, min_y)),
+                        DozerPoint::from((max_x, max_y)),
+                    )]
+                }
+            }
+        }
+    }
+}
+
+fn parse_args<T: DeserializeOwned>(value: &Value) -> Result<T, PlanError> {
+    serde_json::from_value(value.clone()).map_err(|e| PlanError::InvalidGeoFilter(e.to_string()))
+}
+
+fn check_point(point: &DozerPoint) -> Result<(), PlanError> {
+    let (x, y) = (point.0.x().0, point.0.y().0);
+    if (-180.0..=180.0).contains(&x) && (-90.0..=90.0).contains(&y) {
+        Ok(())
+    } else {
+        Err(PlanError::InvalidGeoFilter(format!(
+            "point {point} is out of the longitude range [-180, 180] or the latitude range [-90, 90]"
+        )))
+    }
+}
@@ -1,8 +1,15 @@
+use crate::errors::PlanError;
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::serde_json::Value;
+use dozer_types::types::Schema;
+mod cursor;
+mod geo;
 mod query_helper;
 mod query_serde;
 
+pub use cursor::Cursor;
+pub use geo::GeoShape;
+
 #[cfg(test)]
 mod tests;
 
@@ -24,6 +31,14 @@ pub struct QueryExpression {
     pub order_by: SortOptions,
     pub limit: Option<usize>,
     pub skip: Skip,
+    /// Continues after the last record of a previous query, cannot be used with `skip`.
+    pub cursor: Option<Cursor>,
+    /// Names of the fields to return, all fields if `None`.
+    pub select: Option<Vec<String>>,
+    /// If present, the query returns one record per group instead of the matching records.
+    pub aggregation: Option<Aggregation>,
+    /// If true, the query returns the plan it would execute instead of the records.
+    pub explain: bool,
 }
 
 pub fn default_limit_for_query() -> usize {
@@ -37,6 +52,10 @@ impl QueryExpression {
             order_by: Default::default(),
             limit: Some(default_limit_for_query()),
             skip: Default::default(),
+            cursor: None,
+            select: None,
+            aggregation: None,
+            explain: false,
         }
     }
 
@@ -46,6 +65,10 @@ impl QueryExpression {
             order_by: Default::default(),
             limit: None,
             skip: Default::default(),
+            cursor: None,
+            select: None,
+            aggregation: None,
+            explain: false,
         }
     }
 }
@@ -68,8 +91,31 @@ impl QueryExpression {
             order_by: SortOptions(order_by),
             limit,
             skip,
+            cursor: None,
+            select: None,
+            aggregation: None,
+            explain: false,
         }
     }
+
+    /// Returns the indexes of the fields in `select`, or `None` if all fields are selected.
+    pub fn projection(&self, schema: &Schema) -> Result<Option<Vec<usize>>, PlanError> {
+        self.select
+            .as_ref()
+            .map(|select| {
+                select
+                    .iter()
+                    .map(|field_name| {
+                        schema
+                            .fields
+                            .iter()
+                            .position(|field| &field.name == field_name)
+                            .ok_or_else(|| PlanError::FieldNotFound(field_name.clone()))
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -98,6 +144,12 @@ pub enum Operator {
     MatchesAny,
     #[serde(rename = "$matches_all")]
     MatchesAll,
+    #[serde(rename = "$near")]
+    Near,
+    #[serde(rename = "$within_box")]
+    WithinBox,
+    #[serde(rename = "$within_radius")]
+    WithinRadius,
 }
 
 impl Operator {
@@ -105,6 +157,7 @@ impl Operator {
         match self {
             Operator::LT | Operator::LTE | Operator::EQ | Operator::GT | Operator::GTE => true,
             Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => false,
+            Operator::Near | Operator::WithinBox | Operator::WithinRadius => false,
         }
     }
 
@@ -112,15 +165,27 @@ impl Operator {
         match self {
             Operator::LT | Operator::LTE | Operator::EQ | Operator::GT | Operator::GTE => false,
             Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => true,
+            Operator::Near | Operator::WithinBox | Operator::WithinRadius => false,
         }
     }
 
+    pub fn supported_by_geo(&self) -> bool {
+        matches!(
+            self,
+            Operator::Near | Operator::WithinBox | Operator::WithinRadius
+        )
+    }
+
     pub fn is_range_operator(&self) -> bool {
         match self {
             Operator::LT | Operator::LTE | Operator::GT | Operator::GTE => true,
-            Operator::EQ | Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => {
-                false
-            }
+            Operator::EQ
+            | Operator::Contains
+            | Operator::MatchesAny
+            | Operator::MatchesAll
+            | Operator::Near
+            | Operator::WithinBox
+            | Operator::WithinRadius => false,
         }
     }
 }
@@ -152,3 +217,47 @@ pub enum SortDirection {
     #[serde(rename = "desc")]
     Descending,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Aggregation {
+    pub group_by: Vec<String>,
+    pub aggregates: Aggregates,
+}
+
+/// A wrapper of `Vec<Aggregate>`, for customizing the `Serialize` and `Deserialize` implementation.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Aggregates(pub Vec<Aggregate>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Aggregate {
+    /// Name of the output field.
+    pub alias: String,
+    pub function: AggregateFunction,
+    /// `None` for `$count` of all records, written as `"*"`.
+    pub field_name: Option<String>,
+}
+
+impl Aggregate {
+    pub fn new(alias: String, function: AggregateFunction, field_name: Option<String>) -> Self {
+        Self {
+            alias,
+            function,
+            field_name,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub enum AggregateFunction {
+    #[serde(rename = "$count")]
+    Count,
+    #[serde(rename = "$sum")]
+    Sum,
+    #[serde(rename = "$min")]
+    Min,
+    #[serde(rename = "$max")]
+    Max,
+    #[serde(rename = "$avg")]
+    Avg,
+}
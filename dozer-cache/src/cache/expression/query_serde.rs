@@ -6,7 +6,7 @@ use dozer_types::serde::{
 use super::{
     super::expression::{FilterExpression, Skip, SortOption},
     query_helper::{OperatorAndValue, OperatorAndValueBorrow},
-    QueryExpression, SortOptions,
+    Aggregate, AggregateFunction, Aggregates, Aggregation, Cursor, QueryExpression, SortOptions,
 };
 
 impl<'de> Deserialize<'de> for FilterExpression {
@@ -113,6 +113,122 @@ impl Serialize for SortOptions {
     }
 }
 
+impl<'de> Deserialize<'de> for Aggregates {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AggregatesVisitor {}
+        impl<'de> Visitor<'de> for AggregatesVisitor {
+            type Value = Aggregates;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("map from output field name to aggregate function map")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut aggregates = vec![];
+                while let Some((alias, function_and_field)) =
+                    map.next_entry::<String, FunctionAndField>()?
+                {
+                    aggregates.push(Aggregate {
+                        alias,
+                        function: function_and_field.function,
+                        field_name: function_and_field.field_name,
+                    });
+                }
+                Ok(Aggregates(aggregates))
+            }
+        }
+        deserializer.deserialize_map(AggregatesVisitor {})
+    }
+}
+
+impl Serialize for Aggregates {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(self.0.len()))?;
+        for aggregate in &self.0 {
+            state.serialize_entry(
+                &aggregate.alias,
+                &FunctionAndField {
+                    function: aggregate.function,
+                    field_name: aggregate.field_name.clone(),
+                },
+            )?;
+        }
+        state.end()
+    }
+}
+
+/// `{"$sum": "field_name"}`, where `"*"` stands for all records.
+struct FunctionAndField {
+    function: AggregateFunction,
+    field_name: Option<String>,
+}
+
+const ALL_RECORDS: &str = "*";
+
+impl<'de> Deserialize<'de> for FunctionAndField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FunctionAndFieldVisitor {}
+        impl<'de> Visitor<'de> for FunctionAndFieldVisitor {
+            type Value = FunctionAndField;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("map from aggregate function to field name")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let (function, field_name) = map
+                    .next_entry::<AggregateFunction, String>()?
+                    .ok_or_else(|| Error::custom("missing aggregate function"))?;
+                if map.next_key::<AggregateFunction>()?.is_some() {
+                    return Err(Error::custom("only one aggregate function is allowed"));
+                }
+                let field_name = if field_name == ALL_RECORDS {
+                    if function != AggregateFunction::Count {
+                        return Err(Error::custom("\"*\" can only be used with $count"));
+                    }
+                    None
+                } else {
+                    Some(field_name)
+                };
+                Ok(FunctionAndField {
+                    function,
+                    field_name,
+                })
+            }
+        }
+        deserializer.deserialize_map(FunctionAndFieldVisitor {})
+    }
+}
+
+impl Serialize for FunctionAndField {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(1))?;
+        state.serialize_entry(
+            &self.function,
+            self.field_name.as_deref().unwrap_or(ALL_RECORDS),
+        )?;
+        state.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for QueryExpression {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -133,6 +249,11 @@ impl<'de> Deserialize<'de> for QueryExpression {
                 let mut order_by = None;
                 let mut limit = None;
                 let mut skip = None;
+                let mut cursor = None;
+                let mut select = None;
+                let mut group_by = None;
+                let mut aggregates = None;
+                let mut explain = None;
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
                         "$filter" => {
@@ -145,25 +266,65 @@ impl<'de> Deserialize<'de> for QueryExpression {
                             limit = Some(map.next_value()?);
                         }
                         "$skip" => {
-                            if skip.is_some() {
-                                return Err(Error::custom("$skip cannot be used with $after"));
+                            if skip.is_some() || cursor.is_some() {
+                                return Err(Error::custom(
+                                    "$skip cannot be used with $after or $cursor",
+                                ));
                             }
                             skip = Some(Skip::Skip(map.next_value()?));
                         }
                         "$after" => {
-                            if skip.is_some() {
-                                return Err(Error::custom("$after cannot be used with $skip"));
+                            if skip.is_some() || cursor.is_some() {
+                                return Err(Error::custom(
+                                    "$after cannot be used with $skip or $cursor",
+                                ));
                             }
                             skip = Some(Skip::After(map.next_value()?));
                         }
+                        "$cursor" => {
+                            if skip.is_some() {
+                                return Err(Error::custom(
+                                    "$cursor cannot be used with $skip or $after",
+                                ));
+                            }
+                            let value = map.next_value::<String>()?;
+                            cursor = Some(
+                                Cursor::decode(&value)
+                                    .ok_or_else(|| Error::custom("invalid $cursor"))?,
+                            );
+                        }
+                        "$select" => {
+                            select = Some(map.next_value()?);
+                        }
+                        "$group_by" => {
+                            group_by = Some(map.next_value()?);
+                        }
+                        "$aggregate" => {
+                            aggregates = Some(map.next_value()?);
+                        }
+                        "$explain" => {
+                            explain = Some(map.next_value()?);
+                        }
                         _ => {}
                     }
                 }
+                let aggregation = if group_by.is_some() || aggregates.is_some() {
+                    Some(Aggregation {
+                        group_by: group_by.unwrap_or_default(),
+                        aggregates: aggregates.unwrap_or_default(),
+                    })
+                } else {
+                    None
+                };
                 Ok(QueryExpression {
                     filter,
                     order_by: order_by.unwrap_or_default(),
                     limit,
                     skip: skip.unwrap_or_default(),
+                    cursor,
+                    select,
+                    aggregation,
+                    explain: explain.unwrap_or_default(),
                 })
             }
         }
@@ -176,7 +337,7 @@ impl Serialize for QueryExpression {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_map(Some(4))?;
+        let mut state = serializer.serialize_map(None)?;
         if let Some(filter) = &self.filter {
             state.serialize_entry("$filter", filter)?;
         }
@@ -196,6 +357,23 @@ impl Serialize for QueryExpression {
                 state.serialize_entry("$after", &after)?;
             }
         }
+        if let Some(cursor) = &self.cursor {
+            state.serialize_entry("$cursor", &cursor.encode())?;
+        }
+        if let Some(select) = &self.select {
+            state.serialize_entry("$select", select)?;
+        }
+        if let Some(aggregation) = &self.aggregation {
+            if !aggregation.group_by.is_empty() {
+                state.serialize_entry("$group_by", &aggregation.group_by)?;
+            }
+            if !aggregation.aggregates.0.is_empty() {
+                state.serialize_entry("$aggregate", &aggregation.aggregates)?;
+            }
+        }
+        if self.explain {
+            state.serialize_entry("$explain", &true)?;
+        }
         state.end()
     }
 }
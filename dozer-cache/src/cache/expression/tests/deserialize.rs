@@ -1,7 +1,9 @@
+use crate::cache::expression::Cursor;
 use crate::cache::expression::FilterExpression;
 use crate::cache::expression::Operator;
 use crate::cache::expression::Skip;
 use crate::cache::expression::SortOptions;
+use crate::cache::expression::{Aggregate, AggregateFunction, Aggregates, Aggregation};
 use crate::cache::expression::{
     QueryExpression,
     SortDirection::{Ascending, Descending},
@@ -22,6 +24,9 @@ fn test_operators() {
         (Operator::Contains, "$contains"),
         (Operator::MatchesAny, "$matches_any"),
         (Operator::MatchesAll, "$matches_all"),
+        (Operator::Near, "$near"),
+        (Operator::WithinBox, "$within_box"),
+        (Operator::WithinRadius, "$within_radius"),
     ];
     for (op, op_str) in operators {
         let fetched = serde_json::from_value(Value::String(op_str.to_string())).unwrap();
@@ -84,6 +89,14 @@ fn test_filter_query_deserialize_simple() {
         json!({ "a": null }),
         FilterExpression::Simple("a".to_string(), Operator::EQ, Value::Null),
     );
+    test_deserialize_filter(
+        json!({"a": {"$near": {"center": {"x": 13.4, "y": 52.5}, "max_distance": 2000}}}),
+        FilterExpression::Simple(
+            "a".to_string(),
+            Operator::Near,
+            json!({"center": {"x": 13.4, "y": 52.5}, "max_distance": 2000}),
+        ),
+    );
 
     test_deserialize_filter_error(json!({"a":  []}));
     test_deserialize_filter_error(json!({"a":  {}}));
@@ -215,9 +228,80 @@ fn test_query_expression_deserialize() {
     );
 }
 
+#[test]
+fn test_query_expression_deserialize_select_and_aggregation() {
+    test_deserialize_query(
+        json!({ "$select": ["a", "b"] }),
+        QueryExpression {
+            select: Some(vec!["a".to_string(), "b".to_string()]),
+            ..QueryExpression::new(None, vec![], None, Skip::Skip(0))
+        },
+    );
+    test_deserialize_query(
+        json!({
+            "$group_by": ["a"],
+            "$aggregate": { "n": { "$count": "*" }, "total": { "$sum": "b" } }
+        }),
+        QueryExpression {
+            aggregation: Some(Aggregation {
+                group_by: vec!["a".to_string()],
+                aggregates: Aggregates(vec![
+                    Aggregate::new("n".to_string(), AggregateFunction::Count, None),
+                    Aggregate::new(
+                        "total".to_string(),
+                        AggregateFunction::Sum,
+                        Some("b".to_string()),
+                    ),
+                ]),
+            }),
+            ..QueryExpression::new(None, vec![], None, Skip::Skip(0))
+        },
+    );
+    test_deserialize_query(
+        json!({ "$explain": true }),
+        QueryExpression {
+            explain: true,
+            ..QueryExpression::new(None, vec![], None, Skip::Skip(0))
+        },
+    );
+    let cursor = Cursor::Key {
+        key: vec![1, 2],
+        id: 3,
+    };
+    test_deserialize_query(
+        json!({ "$cursor": cursor.encode() }),
+        QueryExpression {
+            cursor: Some(cursor),
+            ..QueryExpression::new(None, vec![], None, Skip::Skip(0))
+        },
+    );
+    test_deserialize_query(
+        json!({ "$group_by": ["a"] }),
+        QueryExpression {
+            aggregation: Some(Aggregation {
+                group_by: vec!["a".to_string()],
+                aggregates: Aggregates(vec![]),
+            }),
+            ..QueryExpression::new(None, vec![], None, Skip::Skip(0))
+        },
+    );
+}
+
 #[test]
 fn test_query_expression_deserialize_error() {
     test_deserialize_query_error(json!({ "$skip": 20, "$after": 30 }));
+    let cursor = Cursor::Key {
+        key: vec![],
+        id: 10,
+    }
+    .encode();
+    test_deserialize_query_error(json!({ "$skip": 20, "$cursor": cursor }));
+    test_deserialize_query_error(json!({ "$cursor": cursor, "$after": 30 }));
+    test_deserialize_query_error(json!({ "$cursor": "not a cursor" }));
+    test_deserialize_query_error(json!({ "$select": "a" }));
+    test_deserialize_query_error(json!({ "$aggregate": { "n": { "$sum": "*" } } }));
+    test_deserialize_query_error(json!({ "$aggregate": { "n": { "$median": "a" } } }));
+    test_deserialize_query_error(json!({ "$aggregate": { "n": { "$min": "a", "$max": "a" } } }));
 }
 
 fn test_deserialize_query(a: Value, b: QueryExpression) {
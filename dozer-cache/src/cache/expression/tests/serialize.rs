@@ -1,3 +1,4 @@
+use crate::cache::expression::Cursor;
 use crate::cache::expression::FilterExpression;
 use crate::cache::expression::Operator;
 use crate::cache::expression::QueryExpression;
@@ -5,9 +6,11 @@ use crate::cache::expression::Skip;
 use crate::cache::expression::SortDirection::{Ascending, Descending};
 use crate::cache::expression::SortOption;
 use crate::cache::expression::SortOptions;
+use crate::cache::expression::{Aggregate, AggregateFunction, Aggregates, Aggregation};
 use dozer_types::serde_json;
 use dozer_types::serde_json::json;
 use dozer_types::serde_json::Value;
+use dozer_types::types::Field;
 
 #[test]
 fn test_serialize_filter_simple() {
@@ -214,6 +217,62 @@ fn test_serialize_query_expression() {
         },
         json!({"$after": 10}),
     );
+    test_serialize_query_expression_impl(
+        QueryExpression {
+            select: Some(vec!["a".to_string()]),
+            aggregation: Some(Aggregation {
+                group_by: vec!["a".to_string()],
+                aggregates: Aggregates(vec![
+                    Aggregate::new("n".to_string(), AggregateFunction::Count, None),
+                    Aggregate::new(
+                        "avg_b".to_string(),
+                        AggregateFunction::Avg,
+                        Some("b".to_string()),
+                    ),
+                ]),
+            }),
+            limit: None,
+            explain: true,
+            ..Default::default()
+        },
+        json!({
+            "$explain": true,
+            "$select": ["a"],
+            "$group_by": ["a"],
+            "$aggregate": { "n": { "$count": "*" }, "avg_b": { "$avg": "b" } }
+        }),
+    );
+}
+
+#[test]
+fn test_serialize_cursor() {
+    let cursors = [
+        Cursor::Key {
+            key: vec![0, 255, 7],
+            id: 42,
+        },
+        Cursor::Sorted {
+            values: vec![Field::String("foo".to_string()), Field::Null],
+            id: 0,
+        },
+        Cursor::Key {
+            key: vec![],
+            id: 100,
+        },
+    ];
+    for cursor in cursors {
+        let encoded = cursor.encode();
+        assert_eq!(Cursor::decode(&encoded), Some(cursor.clone()));
+        test_serialize_query_expression_impl(
+            QueryExpression {
+                cursor: Some(cursor),
+                limit: None,
+                ..Default::default()
+            },
+            json!({ "$cursor": encoded }),
+        );
+    }
+    assert_eq!(Cursor::decode("not a cursor"), None);
 }
 
 fn test_serialize_query_expression_impl(query: QueryExpression, json: Value) {
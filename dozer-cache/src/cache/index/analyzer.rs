@@ -0,0 +1,165 @@
+use dozer_types::types::{FullTextAnalyzer, Language};
+use itertools::Itertools;
+use rust_stemmers::{Algorithm, Stemmer};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Splits `text` into tokens with `analyzer`, keeping duplicates and order.
+///
+/// Filter values are analyzed the same way as indexed text, so they match each other.
+pub fn analyze(analyzer: &FullTextAnalyzer, text: &str) -> Vec<String> {
+    let text = if analyzer.normalize {
+        text.nfkc().collect::<String>()
+    } else {
+        text.to_string()
+    };
+    let stemmer = analyzer
+        .stemming
+        .map(|language| Stemmer::create(algorithm(language)));
+
+    text.unicode_words()
+        .map(|word| {
+            if analyzer.lowercase {
+                word.to_lowercase()
+            } else {
+                word.to_string()
+            }
+        })
+        .filter(|word| {
+            analyzer
+                .stop_words
+                .map_or(true, |language| !is_stop_word(language, word))
+        })
+        .map(|word| match &stemmer {
+            Some(stemmer) => stemmer.stem(&word).into_owned(),
+            None => word,
+        })
+        .collect()
+}
+
+/// Returns the tokens that `text` is indexed under: the unique analyzed tokens, and their prefixes if prefix search is enabled.
+pub fn get_full_text_tokens(analyzer: &FullTextAnalyzer, text: &str) -> Vec<String> {
+    let tokens = analyze(analyzer, text);
+    let prefixes = analyzer
+        .prefix_search
+        .map(|prefix_search| {
+            tokens
+                .iter()
+                .flat_map(|token| {
+                    let chars = token.chars().collect::<Vec<_>>();
+                    // Empty keys can't be indexed, and the whole token is indexed anyway.
+                    let max_len = prefix_search.max_len.min(chars.len().saturating_sub(1));
+                    (prefix_search.min_len.max(1)..=max_len)
+                        .map(move |len| chars[..len].iter().collect::<String>())
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    tokens.into_iter().chain(prefixes).unique().collect()
+}
+
+/// Checks if a query token matches an analyzed token of a document, as an index lookup would.
+pub fn token_matches(analyzer: &FullTextAnalyzer, token: &str, query_token: &str) -> bool {
+    if token == query_token {
+        return true;
+    }
+    let Some(prefix_search) = analyzer.prefix_search else {
+        return false;
+    };
+    let len = query_token.chars().count();
+    prefix_search.min_len <= len && len <= prefix_search.max_len && token.starts_with(query_token)
+}
+
+fn algorithm(language: Language) -> Algorithm {
+    match language {
+        Language::English => Algorithm::English,
+        Language::French => Algorithm::French,
+        Language::German => Algorithm::German,
+        Language::Spanish => Algorithm::Spanish,
+    }
+}
+
+/// Stop words are compared in lowercase, whether the analyzer lowercases tokens or not.
+fn is_stop_word(language: Language, word: &str) -> bool {
+    let stop_words = match language {
+        Language::English => ENGLISH_STOP_WORDS,
+        Language::French => FRENCH_STOP_WORDS,
+        Language::German => GERMAN_STOP_WORDS,
+        Language::Spanish => SPANISH_STOP_WORDS,
+    };
+    stop_words.contains(&word.to_lowercase().as_str())
+}
+
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+const FRENCH_STOP_WORDS: &[&str] = &[
+    "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "et", "eux", "il",
+    "je", "la", "le", "les", "leur", "lui", "ma", "mais", "me", "mes", "moi", "mon", "ne", "nos",
+    "notre", "nous", "on", "ou", "par", "pas", "pour", "qu", "que", "qui", "sa", "se", "ses",
+    "son", "sur", "ta", "te", "tes", "toi", "ton", "tu", "un", "une", "vos", "votre", "vous",
+];
+
+const GERMAN_STOP_WORDS: &[&str] = &[
+    "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "das", "dass", "dem",
+    "den", "der", "des", "die", "du", "ein", "eine", "einem", "einen", "einer", "eines", "er",
+    "es", "für", "hat", "ich", "im", "in", "ist", "mit", "nach", "nicht", "noch", "oder", "sie",
+    "sind", "so", "und", "von", "vor", "war", "was", "wie", "wir", "zu", "zum", "zur",
+];
+
+const SPANISH_STOP_WORDS: &[&str] = &[
+    "a", "al", "como", "con", "de", "del", "el", "ella", "en", "es", "esta", "este", "la", "las",
+    "le", "lo", "los", "más", "me", "mi", "no", "o", "para", "pero", "por", "que", "se", "si",
+    "sin", "su", "sus", "te", "tu", "un", "una", "uno", "y", "ya",
+];
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::PrefixSearch;
+
+    use super::*;
+
+    #[test]
+    fn test_default_analyzer_keeps_words() {
+        assert_eq!(
+            analyze(&FullTextAnalyzer::default(), "The Quick fox"),
+            vec!["The", "Quick", "fox"]
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        let analyzer = FullTextAnalyzer {
+            lowercase: true,
+            normalize: true,
+            stop_words: Some(Language::English),
+            stemming: Some(Language::English),
+            prefix_search: None,
+        };
+        assert_eq!(
+            analyze(&analyzer, "The ﬁsh are Running to the boats"),
+            vec!["fish", "run", "boat"]
+        );
+    }
+
+    #[test]
+    fn test_prefix_search() {
+        let analyzer = FullTextAnalyzer {
+            prefix_search: Some(PrefixSearch {
+                min_len: 2,
+                max_len: 3,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_full_text_tokens(&analyzer, "laptop ab"),
+            vec!["laptop", "ab", "la", "lap"]
+        );
+        assert!(token_matches(&analyzer, "laptop", "lap"));
+        assert!(!token_matches(&analyzer, "laptop", "l"));
+        assert!(!token_matches(&analyzer, "laptop", "lapt"));
+    }
+}
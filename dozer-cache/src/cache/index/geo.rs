@@ -0,0 +1,73 @@
+use dozer_types::types::DozerPoint;
+
+/// Most cells used to cover a box. More cells read fewer records outside the box, at the cost of more seeks.
+const MAX_COVERING_CELLS: u64 = 32;
+
+/// Returns the geo index key of a point, the Z-order code of its quantized longitude and latitude.
+///
+/// Points in the same cell of any size have consecutive keys, so a box can be scanned as a few key ranges.
+pub fn get_geo_secondary_index(point: &DozerPoint) -> Vec<u8> {
+    let (x, y) = quantize(point);
+    interleave(x, y).to_be_bytes().to_vec()
+}
+
+/// Returns inclusive key ranges, in ascending order, that contain the keys of all points in the box from `min` to `max`.
+///
+/// The ranges cover whole cells, so they may also contain points outside the box.
+pub fn get_geo_key_ranges(min: &DozerPoint, max: &DozerPoint) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let (min_x, min_y) = quantize(min);
+    let (max_x, max_y) = quantize(max);
+
+    // Use the smallest cells that cover the box with at most `MAX_COVERING_CELLS` cells. Cells have `2^shift` quantums per side.
+    let shift = (0..32)
+        .find(|shift| {
+            let num_x = ((max_x >> shift) - (min_x >> shift)) as u64 + 1;
+            let num_y = ((max_y >> shift) - (min_y >> shift)) as u64 + 1;
+            num_x * num_y <= MAX_COVERING_CELLS
+        })
+        .expect("Any box is covered by at most 4 cells of shift 31");
+
+    let mut cells = vec![];
+    for cell_x in (min_x >> shift)..=(max_x >> shift) {
+        for cell_y in (min_y >> shift)..=(max_y >> shift) {
+            let start = interleave(cell_x << shift, cell_y << shift);
+            cells.push((start, start | ((1 << (2 * shift)) - 1)));
+        }
+    }
+    cells.sort_unstable();
+
+    // Merge cells with consecutive keys.
+    let mut ranges: Vec<(u64, u64)> = vec![];
+    for (start, end) in cells {
+        match ranges.last_mut() {
+            Some(last) if last.1.checked_add(1) == Some(start) => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| (start.to_be_bytes().to_vec(), end.to_be_bytes().to_vec()))
+        .collect()
+}
+
+/// Maps longitude and latitude to `u32`s. Out of range coordinates are clamped because `as` saturates.
+fn quantize(point: &DozerPoint) -> (u32, u32) {
+    const SCALE: f64 = (1u64 << 32) as f64;
+    let x = (point.0.x().0 + 180.0) / 360.0;
+    let y = (point.0.y().0 + 90.0) / 180.0;
+    ((x * SCALE) as u32, (y * SCALE) as u32)
+}
+
+fn interleave(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// Moves bit `i` of `value` to bit `2 * i`.
+fn spread(value: u32) -> u64 {
+    let mut value = value as u64;
+    value = (value | (value << 16)) & 0x0000_FFFF_0000_FFFF;
+    value = (value | (value << 8)) & 0x00FF_00FF_00FF_00FF;
+    value = (value | (value << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    value = (value | (value << 2)) & 0x3333_3333_3333_3333;
+    (value | (value << 1)) & 0x5555_5555_5555_5555
+}
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use dozer_types::types::{FieldBorrow, IndexDefinition, Record};
+use dozer_types::types::{FieldBorrow, FullTextAnalyzer, IndexDefinition, Record};
 
 pub trait CacheIndex {
     // Builds one index based on index definition and record
@@ -11,7 +11,13 @@ pub trait CacheIndex {
 
 use dozer_types::types::Field;
 
-use crate::errors::CompareError;
+use crate::errors::{CacheError, CompareError, IndexError};
+
+mod analyzer;
+mod geo;
+
+pub use analyzer::{analyze, get_full_text_tokens, token_matches};
+pub use geo::{get_geo_key_ranges, get_geo_secondary_index};
 
 pub fn get_primary_key(primary_index: &[usize], values: &[Field]) -> Vec<u8> {
     debug_assert!(
@@ -71,6 +77,98 @@ pub fn get_full_text_secondary_index(token: &str) -> Vec<u8> {
     token.as_bytes().to_vec()
 }
 
+/// Returns the keys a record with `values` has in secondary index `index`, and the number of analyzed tokens
+/// if it's a full text index, which is 0 otherwise.
+pub fn get_secondary_index_keys(
+    index: &IndexDefinition,
+    values: &[Field],
+) -> Result<(Vec<Vec<u8>>, u64), CacheError> {
+    match index {
+        IndexDefinition::SortedInverted(fields) => {
+            Ok((vec![get_sorted_inverted_key(fields, values)], 0))
+        }
+        IndexDefinition::FullText(field_index, analyzer) => {
+            get_full_text_keys(*field_index, analyzer, values)
+        }
+        IndexDefinition::Geo(field_index) => {
+            Ok((get_geo_key(*field_index, values)?.into_iter().collect(), 0))
+        }
+    }
+}
+
+fn get_sorted_inverted_key(fields: &[usize], values: &[Field]) -> Vec<u8> {
+    let values = fields
+        .iter()
+        .copied()
+        .filter_map(|index| (values.get(index)))
+        .collect::<Vec<_>>();
+    // `values.len() == 1` criteria must be kept the same with `comparator.rs`.
+    get_secondary_index(&values, values.len() == 1)
+}
+
+fn get_full_text_keys(
+    field_index: usize,
+    analyzer: &FullTextAnalyzer,
+    values: &[Field],
+) -> Result<(Vec<Vec<u8>>, u64), CacheError> {
+    let Some(field) = values.get(field_index) else {
+        return Err(CacheError::Index(IndexError::FieldIndexOutOfRange));
+    };
+
+    let string = match field {
+        Field::String(string) => string,
+        Field::Text(string) => string,
+        Field::Null => "",
+        _ => {
+            return Err(CacheError::Index(IndexError::FieldNotCompatibleIndex(
+                field_index,
+            )))
+        }
+    };
+
+    let keys = get_full_text_tokens(analyzer, string)
+        .iter()
+        .map(|token| get_full_text_secondary_index(token))
+        .collect();
+    Ok((keys, analyze(analyzer, string).len() as u64))
+}
+
+/// Returns `None` for `null`, which is not indexed.
+fn get_geo_key(field_index: usize, values: &[Field]) -> Result<Option<Vec<u8>>, CacheError> {
+    match values.get(field_index) {
+        Some(Field::Point(point)) => Ok(Some(get_geo_secondary_index(point))),
+        Some(Field::Null) => Ok(None),
+        Some(_) => Err(CacheError::Index(IndexError::FieldNotCompatibleIndex(
+            field_index,
+        ))),
+        None => Err(CacheError::Index(IndexError::FieldIndexOutOfRange)),
+    }
+}
+
+/// Compares two keys of secondary index `index` in the order the index stores them.
+pub fn compare_secondary_index_keys(index: &IndexDefinition, a: &[u8], b: &[u8]) -> Ordering {
+    if has_composite_keys(index) {
+        compare_composite_keys(a, b)
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Composite keys are compared field by field, other keys bytewise.
+///
+/// The criteria must be kept the same with `comparator.rs`.
+pub fn has_composite_keys(index: &IndexDefinition) -> bool {
+    matches!(index, IndexDefinition::SortedInverted(fields) if fields.len() != 1)
+}
+
+/// Compares composite keys, treating keys that can't be decoded as equal.
+pub fn compare_composite_keys(a: &[u8], b: &[u8]) -> Ordering {
+    compare_composite_secondary_index(a, b).unwrap_or_else(|e| {
+        dozer_types::log::error!("Error deserializing secondary index key: {}", e);
+        Ordering::Equal
+    })
+}
+
 fn get_composite_secondary_index(fields: &[&Field]) -> Vec<u8> {
     fn get_field_encoding_len(field: &Field) -> usize {
         8 + field.encoding_len()
@@ -1,14 +1,58 @@
-use dozer_types::types::field_test_cases;
+use dozer_types::types::{
+    field_test_cases, DozerPoint, Field, FullTextAnalyzer, IndexDefinition, Language,
+};
 
 use crate::cache::index::{get_composite_secondary_index, CompositeSecondaryIndexKey};
 
-use super::get_full_text_secondary_index;
+use super::{
+    get_full_text_secondary_index, get_geo_key_ranges, get_geo_secondary_index,
+    get_secondary_index_keys,
+};
 
 #[test]
 fn test_get_full_text_secondary_index() {
     assert_eq!(get_full_text_secondary_index("foo"), b"foo",);
 }
 
+#[test]
+fn test_full_text_secondary_index_keys() {
+    assert_eq!(
+        get_secondary_index_keys(
+            &IndexDefinition::FullText(0, Default::default()),
+            &[Field::String("today is a good day".into())]
+        )
+        .unwrap(),
+        (
+            vec![
+                get_full_text_secondary_index("today"),
+                get_full_text_secondary_index("is"),
+                get_full_text_secondary_index("a"),
+                get_full_text_secondary_index("good"),
+                get_full_text_secondary_index("day"),
+            ],
+            5
+        )
+    );
+}
+
+#[test]
+fn test_full_text_secondary_index_keys_with_analyzer() {
+    let analyzer = FullTextAnalyzer {
+        lowercase: true,
+        stop_words: Some(Language::English),
+        stemming: Some(Language::English),
+        ..Default::default()
+    };
+    assert_eq!(
+        get_secondary_index_keys(
+            &IndexDefinition::FullText(0, analyzer),
+            &[Field::Text("The Cats and the cat".into())]
+        )
+        .unwrap(),
+        (vec![get_full_text_secondary_index("cat")], 2)
+    );
+}
+
 #[test]
 fn test_composite_key_encode_roundtrip() {
     // Single field
@@ -41,3 +85,33 @@ fn composite_key_encode_preserves_prefix() {
         }
     }
 }
+
+#[test]
+fn test_geo_key_ranges_contain_points_in_box() {
+    let min = DozerPoint::from((-0.2, 51.4));
+    let max = DozerPoint::from((0.1, 51.6));
+    let ranges = get_geo_key_ranges(&min, &max);
+    assert!(!ranges.is_empty() && ranges.len() <= 32);
+    assert!(ranges.windows(2).all(|pair| pair[0].1 < pair[1].0));
+
+    let in_range = |point: (f64, f64)| {
+        let key = get_geo_secondary_index(&DozerPoint::from(point));
+        ranges
+            .iter()
+            .any(|(start, end)| start <= &key && &key <= end)
+    };
+    for x in [-0.2, -0.05, 0.0, 0.1] {
+        for y in [51.4, 51.5, 51.6] {
+            assert!(in_range((x, y)));
+        }
+    }
+    assert!(!in_range((2.35, 48.85)));
+    assert!(!in_range((-74.0, 40.7)));
+}
+
+#[test]
+fn test_geo_key_ranges_of_single_point() {
+    let point = DozerPoint::from((13.4, 52.5));
+    let key = get_geo_secondary_index(&point);
+    assert_eq!(get_geo_key_ranges(&point, &point), vec![(key.clone(), key)]);
+}
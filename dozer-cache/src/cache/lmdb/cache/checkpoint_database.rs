@@ -10,11 +10,16 @@ use crate::errors::{CacheError, QueryError};
 pub struct CheckpointDatabase(Database);
 
 impl CheckpointDatabase {
-    pub fn new(env: &mut LmdbEnvironmentManager) -> Result<Self, CacheError> {
-        Ok(Self(env.create_database(
-            Some("checkpoint"),
-            Some(DatabaseFlags::empty()),
-        )?))
+    pub fn new(
+        env: &mut LmdbEnvironmentManager,
+        create_if_not_exist: bool,
+    ) -> Result<Self, CacheError> {
+        let flags = if create_if_not_exist {
+            Some(DatabaseFlags::empty())
+        } else {
+            None
+        };
+        Ok(Self(env.create_database(Some("checkpoint"), flags)?))
     }
 
     pub fn write(
@@ -61,7 +66,7 @@ mod tests {
     #[test]
     fn test_checkpoint_database() {
         let mut env = init_env(&CacheOptions::default()).unwrap().0;
-        let db = CheckpointDatabase::new(&mut env).unwrap();
+        let db = CheckpointDatabase::new(&mut env, true).unwrap();
         let txn = env.create_txn().unwrap();
         let mut txn = txn.write();
 
@@ -2,7 +2,6 @@ use crate::errors::{CacheError, QueryError};
 use dozer_storage::lmdb::{Database, Transaction};
 use dozer_storage::lmdb_sys as ffi;
 use dozer_types::{bincode, serde};
-use std::{cmp::Ordering, ffi::c_void};
 pub fn get<T>(txn: &impl Transaction, db: Database, id: &[u8]) -> Result<T, CacheError>
 where
     T: for<'a> serde::de::Deserialize<'a>,
@@ -13,19 +12,6 @@ where
     bincode::deserialize(rec).map_err(CacheError::map_deserialization_error)
 }
 
-pub fn lmdb_cmp<T: Transaction>(txn: &T, db: Database, a: &[u8], b: &[u8]) -> Ordering {
-    let a: ffi::MDB_val = ffi::MDB_val {
-        mv_size: a.len(),
-        mv_data: a.as_ptr() as *mut c_void,
-    };
-    let b: ffi::MDB_val = ffi::MDB_val {
-        mv_size: b.len(),
-        mv_data: b.as_ptr() as *mut c_void,
-    };
-    let result = unsafe { dozer_storage::lmdb_sys::mdb_cmp(txn.txn(), db.dbi(), &a, &b) };
-    result.cmp(&0)
-}
-
 pub fn lmdb_stat<T: Transaction>(
     txn: &T,
     db: Database,
@@ -59,6 +59,17 @@ impl IdDatabase {
         Ok(id)
     }
 
+    /// Inserts an existing mapping, such as one read from a snapshot.
+    pub fn insert(
+        &self,
+        txn: &mut RwTransaction,
+        key: &[u8],
+        id: [u8; 8],
+    ) -> Result<(), CacheError> {
+        txn.put(self.0, &key, &id, WriteFlags::NO_OVERWRITE)
+            .map_err(|e| CacheError::Query(QueryError::InsertValue(e)))
+    }
+
     pub fn get<T: Transaction>(&self, txn: &T, key: &[u8]) -> Result<[u8; 8], CacheError> {
         txn.get(self.0, &key)
             .map_err(|e| CacheError::Query(QueryError::GetValue(e)))
@@ -68,6 +79,12 @@ impl IdDatabase {
             })
     }
 
+    pub fn count(&self, txn: &impl Transaction) -> Result<usize, CacheError> {
+        helper::lmdb_stat(txn, self.0)
+            .map(|stat| stat.ms_entries)
+            .map_err(|e| CacheError::Internal(Box::new(e)))
+    }
+
     pub fn open_ro_cursor<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use dozer_storage::lmdb::{RoTransaction, RwTransaction, Transaction};
 use dozer_storage::lmdb_storage::{
@@ -13,29 +15,35 @@ use dozer_types::parking_lot::RwLockReadGuard;
 use dozer_types::types::{Field, FieldType, IndexDefinition, Record};
 use dozer_types::types::{Schema, SchemaIdentifier};
 
-use super::super::{RoCache, RwCache};
+use super::super::retention::RetentionJob;
+use super::super::{OnExpired, Plan, RetentionOptions, RetentionPolicy, RoCache, RwCache};
 use super::indexer::Indexer;
 use super::utils::{self, CacheReadOptions};
 use super::utils::{CacheOptions, CacheOptionsKind};
-use crate::cache::expression::QueryExpression;
+use crate::cache::expression::{Cursor, QueryExpression};
 use crate::cache::index::get_primary_key;
+use crate::cache::query::QueryHandler;
 use crate::cache::RecordWithId;
 use crate::errors::CacheError;
-use query::LmdbQueryHandler;
+use query::LmdbQueryStorage;
 
 mod checkpoint_database;
 mod helper;
 mod id_database;
 mod query;
 mod record_database;
+mod retention;
 mod schema_database;
 mod secondary_index_database;
+mod snapshot;
 
 use checkpoint_database::CheckpointDatabase;
 pub use id_database::IdDatabase;
 pub use record_database::RecordDatabase;
+use retention::Retention;
 use schema_database::SchemaDatabase;
 use secondary_index_database::SecondaryIndexDatabase;
+pub use snapshot::SnapshotHeader;
 
 pub type SecondaryIndexDatabases = HashMap<(SchemaIdentifier, usize), SecondaryIndexDatabase>;
 
@@ -49,6 +57,9 @@ pub struct CacheCommonOptions {
     /// The chunk size when calculating intersection of index queries.
     pub intersection_chunk_size: usize,
 
+    /// The max number of records a query can scan when no index fully answers it.
+    pub max_scanned_rows: usize,
+
     /// Provide a path where db will be created. If nothing is provided, will default to a temp location.
     /// Db path will be `PathBuf.join(String)`.
     pub path: Option<(PathBuf, String)>,
@@ -60,6 +71,7 @@ impl Default for CacheCommonOptions {
             max_readers: 1000,
             max_db_size: 1000,
             intersection_chunk_size: 100,
+            max_scanned_rows: 100_000,
             path: None,
         }
     }
@@ -68,6 +80,7 @@ impl Default for CacheCommonOptions {
 #[derive(Debug)]
 pub struct LmdbRoCache {
     common: LmdbCacheCommon,
+    checkpoint_db: CheckpointDatabase,
     env: LmdbEnvironmentManager,
 }
 
@@ -78,7 +91,12 @@ impl LmdbRoCache {
             kind: CacheOptionsKind::ReadOnly(CacheReadOptions {}),
         })?;
         let common = LmdbCacheCommon::new(&mut env, options, name, true)?;
-        Ok(Self { common, env })
+        let checkpoint_db = CheckpointDatabase::new(&mut env, false)?;
+        Ok(Self {
+            common,
+            checkpoint_db,
+            env,
+        })
     }
 }
 
@@ -102,6 +120,9 @@ pub struct LmdbRwCache {
     common: LmdbCacheCommon,
     checkpoint_db: CheckpointDatabase,
     txn: SharedTransaction,
+    /// Set while `txn` has writes that `commit` hasn't committed yet. Only accessed while holding `txn`.
+    has_uncommitted_writes: Arc<AtomicBool>,
+    retention: Option<RetentionJob>,
 }
 
 impl LmdbRwCache {
@@ -134,12 +155,14 @@ impl LmdbRwCache {
             kind: CacheOptionsKind::Write(write_options),
         })?;
         let common = LmdbCacheCommon::new(&mut env, common_options, name, false)?;
-        let checkpoint_db = CheckpointDatabase::new(&mut env)?;
+        let checkpoint_db = CheckpointDatabase::new(&mut env, true)?;
         let txn = env.create_txn()?;
         Ok(Self {
             common,
             checkpoint_db,
             txn,
+            has_uncommitted_writes: Arc::new(AtomicBool::new(false)),
+            retention: None,
         })
     }
 }
@@ -165,7 +188,8 @@ impl<C: LmdbCache> RoCache for C {
             .schema_db
             .get_schema_from_name(schema_name)
             .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
-        let handler = LmdbQueryHandler::new(self.common(), txn, schema, secondary_indexes, query);
+        let storage = LmdbQueryStorage::new(self.common(), txn);
+        let handler = QueryHandler::new(&storage, schema, secondary_indexes, query);
         handler.count()
     }
 
@@ -181,11 +205,61 @@ impl<C: LmdbCache> RoCache for C {
             .schema_db
             .get_schema_from_name(schema_name)
             .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
-        let handler = LmdbQueryHandler::new(self.common(), txn, schema, secondary_indexes, query);
+        let storage = LmdbQueryStorage::new(self.common(), txn);
+        let handler = QueryHandler::new(&storage, schema, secondary_indexes, query);
         let records = handler.query()?;
         Ok((schema, records))
     }
 
+    fn aggregate(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+    ) -> Result<(Schema, Vec<Record>), CacheError> {
+        let txn = self.begin_txn()?;
+        let txn = txn.as_txn();
+        let (schema, secondary_indexes) = self
+            .common()
+            .schema_db
+            .get_schema_from_name(schema_name)
+            .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
+        let storage = LmdbQueryStorage::new(self.common(), txn);
+        let handler = QueryHandler::new(&storage, schema, secondary_indexes, query);
+        let aggregation = query.aggregation.clone().unwrap_or_default();
+        handler.aggregate(&aggregation)
+    }
+
+    fn explain(&self, schema_name: &str, query: &QueryExpression) -> Result<Plan, CacheError> {
+        let txn = self.begin_txn()?;
+        let txn = txn.as_txn();
+        let (schema, secondary_indexes) = self
+            .common()
+            .schema_db
+            .get_schema_from_name(schema_name)
+            .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
+        let storage = LmdbQueryStorage::new(self.common(), txn);
+        let handler = QueryHandler::new(&storage, schema, secondary_indexes, query);
+        handler.plan()
+    }
+
+    fn next_cursor(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+        records: &[RecordWithId],
+    ) -> Result<Option<Cursor>, CacheError> {
+        let txn = self.begin_txn()?;
+        let txn = txn.as_txn();
+        let (schema, secondary_indexes) = self
+            .common()
+            .schema_db
+            .get_schema_from_name(schema_name)
+            .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
+        let storage = LmdbQueryStorage::new(self.common(), txn);
+        let handler = QueryHandler::new(&storage, schema, secondary_indexes, query);
+        handler.next_cursor(records)
+    }
+
     fn get_schema_and_indexes_by_name(
         &self,
         name: &str,
@@ -230,6 +304,7 @@ impl RwCache for LmdbRwCache {
         let mut txn = self.txn.write();
         self.checkpoint_db.write(txn.txn_mut(), checkpoint)?;
         txn.commit_and_renew()?;
+        self.has_uncommitted_writes.store(false, Ordering::SeqCst);
         Ok(())
     }
 
@@ -237,14 +312,61 @@ impl RwCache for LmdbRwCache {
         let txn = self.txn.read();
         self.checkpoint_db.read(txn.txn())
     }
+
+    fn start_retention(
+        &mut self,
+        schema_name: &str,
+        policy: RetentionPolicy,
+        options: RetentionOptions,
+        on_expired: OnExpired,
+    ) -> Result<(), CacheError> {
+        let retention = self.retention(schema_name, policy)?;
+        // Stop the previous job before starting a new one.
+        self.retention = None;
+        self.retention = Some(RetentionJob::start(
+            move |batch_size| retention.delete_expired(batch_size),
+            options,
+            on_expired,
+        ));
+        Ok(())
+    }
 }
 
 impl LmdbRwCache {
+    fn retention(
+        &self,
+        schema_name: &str,
+        policy: RetentionPolicy,
+    ) -> Result<Retention, CacheError> {
+        let (schema, secondary_indexes) = self
+            .common
+            .schema_db
+            .get_schema_from_name(schema_name)
+            .ok_or_else(|| CacheError::SchemaNotFound(schema_name.to_string()))?;
+        policy.validate(
+            schema_name,
+            schema,
+            self.common.schema_db.get_all_schemas().len(),
+        )?;
+
+        Ok(Retention {
+            txn: self.txn.clone(),
+            has_uncommitted_writes: self.has_uncommitted_writes.clone(),
+            db: self.common.db,
+            id: self.common.id,
+            secondary_index_databases: self.common.secondary_indexes.clone(),
+            schema: schema.clone(),
+            secondary_indexes: secondary_indexes.clone(),
+            policy,
+        })
+    }
+
     fn delete_impl(&self, key: &[u8]) -> Result<(&Schema, &[IndexDefinition], u32), CacheError> {
         let record = self.get(key)?.record;
         let (schema, secondary_indexes) = self.get_schema_and_indexes_from_record(&record)?;
 
         let mut txn = self.txn.write();
+        self.has_uncommitted_writes.store(true, Ordering::SeqCst);
         let txn = txn.txn_mut();
 
         let id = self.common.id.get(txn, key)?;
@@ -267,6 +389,7 @@ impl LmdbRwCache {
         secondary_indexes: &[IndexDefinition],
     ) -> Result<u64, CacheError> {
         let mut txn = self.txn.write();
+        self.has_uncommitted_writes.store(true, Ordering::SeqCst);
         let txn = txn.txn_mut();
 
         let id = if schema.primary_index.is_empty() {
@@ -397,6 +520,8 @@ fn debug_check_schema_record_consistency(schema: &Schema, record: &Record) {
             FieldType::Date => debug_assert!(value.as_date().is_some()),
             FieldType::Bson => debug_assert!(value.as_bson().is_some()),
             FieldType::Point => debug_assert!(value.as_point().is_some()),
+            FieldType::Duration => debug_assert!(value.as_duration().is_some()),
+            FieldType::BigInt => debug_assert!(value.as_bigint().is_some()),
         }
     }
 }
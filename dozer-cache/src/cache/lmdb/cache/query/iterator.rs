@@ -2,31 +2,24 @@ use std::marker::PhantomData;
 
 use dozer_storage::lmdb::Cursor;
 use dozer_storage::lmdb_sys::{
-    MDB_FIRST, MDB_LAST, MDB_NEXT, MDB_NEXT_NODUP, MDB_PREV, MDB_PREV_NODUP, MDB_SET_RANGE,
+    MDB_FIRST, MDB_GET_BOTH_RANGE, MDB_GET_CURRENT, MDB_LAST, MDB_LAST_DUP, MDB_NEXT,
+    MDB_NEXT_NODUP, MDB_PREV, MDB_PREV_NODUP, MDB_SET_RANGE,
 };
 
 use crate::cache::expression::SortDirection;
-
-#[derive(Debug, Clone)]
-pub enum KeyEndpoint {
-    Including(Vec<u8>),
-    Excluding(Vec<u8>),
-}
-
-impl KeyEndpoint {
-    pub fn key(&self) -> &[u8] {
-        match self {
-            KeyEndpoint::Including(key) => key,
-            KeyEndpoint::Excluding(key) => key,
-        }
-    }
-}
+use crate::cache::query::KeyEndpoint;
 
 enum CacheIteratorState {
     First {
         starting_key: Option<KeyEndpoint>,
         direction: SortDirection,
     },
+    /// Starts right after the entry `(key, value)`, which may not exist anymore.
+    After {
+        key: Vec<u8>,
+        value: Vec<u8>,
+        direction: SortDirection,
+    },
     NotFirst {
         direction: SortDirection,
     },
@@ -101,6 +94,67 @@ impl<'txn, C: Cursor<'txn>> Iterator for CacheIterator<'txn, C> {
                 };
                 res
             }
+            CacheIteratorState::After {
+                key,
+                value,
+                direction,
+            } => {
+                let res = match direction {
+                    SortDirection::Ascending => {
+                        match self.cursor.get(
+                            Some(key.as_slice()),
+                            Some(value.as_slice()),
+                            MDB_GET_BOTH_RANGE,
+                        ) {
+                            // Now at the first entry of `key` not less than `value`.
+                            Ok(_) => match self.cursor.get(None, None, MDB_GET_CURRENT) {
+                                Ok((_, current)) if current == value.as_slice() => {
+                                    self.cursor.get(None, None, MDB_NEXT)
+                                }
+                                res => res,
+                            },
+                            // Either `key` doesn't exist or all its values are less than `value`.
+                            Err(dozer_storage::lmdb::Error::NotFound) => {
+                                match self.cursor.get(Some(key.as_slice()), None, MDB_SET_RANGE) {
+                                    Ok((current, _)) if current == Some(key.as_slice()) => {
+                                        self.cursor.get(None, None, MDB_NEXT_NODUP)
+                                    }
+                                    res => res,
+                                }
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                    SortDirection::Descending => {
+                        match self.cursor.get(
+                            Some(key.as_slice()),
+                            Some(value.as_slice()),
+                            MDB_GET_BOTH_RANGE,
+                        ) {
+                            // Everything before the first entry of `key` not less than `value`.
+                            Ok(_) => self.cursor.get(None, None, MDB_PREV),
+                            Err(dozer_storage::lmdb::Error::NotFound) => {
+                                match self.cursor.get(Some(key.as_slice()), None, MDB_SET_RANGE) {
+                                    Ok((current, _)) if current == Some(key.as_slice()) => self
+                                        .cursor
+                                        .get(None, None, MDB_LAST_DUP)
+                                        .and_then(|_| self.cursor.get(None, None, MDB_GET_CURRENT)),
+                                    Ok(_) => self.cursor.get(None, None, MDB_PREV),
+                                    Err(dozer_storage::lmdb::Error::NotFound) => {
+                                        self.cursor.get(None, None, MDB_LAST)
+                                    }
+                                    Err(e) => Err(e),
+                                }
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                };
+                self.state = CacheIteratorState::NotFirst {
+                    direction: *direction,
+                };
+                res
+            }
             CacheIteratorState::NotFirst { direction } => match direction {
                 SortDirection::Ascending => self.cursor.get(None, None, MDB_NEXT),
                 SortDirection::Descending => self.cursor.get(None, None, MDB_PREV),
@@ -124,6 +178,19 @@ impl<'txn, C: Cursor<'txn>> CacheIterator<'txn, C> {
             _marker: PhantomData::default(),
         }
     }
+
+    /// Iterates from the entry right after `(key, value)` in a `DUP_SORT` database.
+    pub fn new_after(cursor: C, key: Vec<u8>, value: Vec<u8>, direction: SortDirection) -> Self {
+        CacheIterator {
+            cursor,
+            state: CacheIteratorState::After {
+                key,
+                value,
+                direction,
+            },
+            _marker: PhantomData::default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -270,4 +337,110 @@ mod tests {
             vec![],
         );
     }
+
+    #[test]
+    fn test_cache_iterator_after() {
+        let options = CacheOptions::default();
+        let mut env = init_env(&options).unwrap().0;
+        let db = env
+            .create_database(None, Some(DatabaseFlags::DUP_SORT))
+            .unwrap();
+        let txn = env.create_txn().unwrap();
+        let mut txn = txn.write();
+
+        // Insert test data.
+        let txn = txn.txn_mut();
+        for key in [b"a", b"b", b"c"] {
+            for value in [1u8, 2, 3] {
+                txn.put(db, key, &[value], WriteFlags::empty()).unwrap();
+            }
+        }
+
+        // Create testing cursor and utility function.
+        let check = |key: &[u8], value: u8, direction, expected: Vec<(&[u8], u8)>| {
+            let cursor = txn.open_ro_cursor(db).unwrap();
+            let actual = CacheIterator::new_after(cursor, key.to_vec(), vec![value], direction)
+                .map(|(key, value)| (key, value[0]))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected);
+        };
+
+        // Existing entry.
+        check(
+            b"b",
+            2,
+            SortDirection::Ascending,
+            vec![(b"b", 3), (b"c", 1), (b"c", 2), (b"c", 3)],
+        );
+        check(
+            b"b",
+            2,
+            SortDirection::Descending,
+            vec![(b"b", 1), (b"a", 3), (b"a", 2), (b"a", 1)],
+        );
+
+        // Missing value, less than all values of the key.
+        check(
+            b"c",
+            0,
+            SortDirection::Ascending,
+            vec![(b"c", 1), (b"c", 2), (b"c", 3)],
+        );
+        check(
+            b"b",
+            0,
+            SortDirection::Descending,
+            vec![(b"a", 3), (b"a", 2), (b"a", 1)],
+        );
+
+        // Missing value, greater than all values of the key.
+        check(
+            b"b",
+            4,
+            SortDirection::Ascending,
+            vec![(b"c", 1), (b"c", 2), (b"c", 3)],
+        );
+        check(
+            b"a",
+            4,
+            SortDirection::Descending,
+            vec![(b"a", 3), (b"a", 2), (b"a", 1)],
+        );
+
+        // Missing key.
+        check(
+            b"bb",
+            1,
+            SortDirection::Ascending,
+            vec![(b"c", 1), (b"c", 2), (b"c", 3)],
+        );
+        check(
+            b"ab",
+            1,
+            SortDirection::Descending,
+            vec![(b"a", 3), (b"a", 2), (b"a", 1)],
+        );
+
+        // Key past db end.
+        check(b"d", 1, SortDirection::Ascending, vec![]);
+        check(
+            b"d",
+            1,
+            SortDirection::Descending,
+            vec![
+                (b"c", 3),
+                (b"c", 2),
+                (b"c", 1),
+                (b"b", 3),
+                (b"b", 2),
+                (b"b", 1),
+                (b"a", 3),
+                (b"a", 2),
+                (b"a", 1),
+            ],
+        );
+
+        // Key before db start.
+        check(b"0", 1, SortDirection::Descending, vec![]);
+    }
 }
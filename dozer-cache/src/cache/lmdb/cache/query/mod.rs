@@ -1,8 +1,7 @@
-mod handler;
-mod intersection;
 mod iterator;
+mod storage;
 
-pub use handler::LmdbQueryHandler;
+pub use storage::LmdbQueryStorage;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,116 @@
+use dozer_storage::lmdb::Transaction;
+use dozer_types::types::Record;
+
+use super::super::secondary_index_database::SecondaryIndexDatabase;
+use super::super::{id_from_bytes, id_to_bytes, LmdbCacheCommon};
+use super::iterator::CacheIterator;
+use crate::cache::expression::SortDirection;
+use crate::cache::query::{
+    FullTextStats, IndexEntries, KeyEndpoint, QueryStorage, SecondaryIndexId,
+};
+use crate::errors::CacheError;
+
+/// Reads the databases of a LMDB cache in one transaction.
+pub struct LmdbQueryStorage<'a, T: Transaction> {
+    common: &'a LmdbCacheCommon,
+    txn: &'a T,
+}
+
+impl<'a, T: Transaction> LmdbQueryStorage<'a, T> {
+    pub fn new(common: &'a LmdbCacheCommon, txn: &'a T) -> Self {
+        Self { common, txn }
+    }
+
+    fn secondary_index_database(
+        &self,
+        index: SecondaryIndexId,
+    ) -> Result<SecondaryIndexDatabase, CacheError> {
+        self.common
+            .secondary_indexes
+            .get(&index)
+            .copied()
+            .ok_or(CacheError::SecondaryIndexDatabaseNotFound)
+    }
+}
+
+impl<'a, T: Transaction> QueryStorage for LmdbQueryStorage<'a, T> {
+    fn intersection_chunk_size(&self) -> usize {
+        self.common.cache_options.intersection_chunk_size
+    }
+
+    fn max_scanned_rows(&self) -> usize {
+        self.common.cache_options.max_scanned_rows
+    }
+
+    fn count_records(&self) -> Result<usize, CacheError> {
+        self.common.db.count(self.txn)
+    }
+
+    fn get_record(&self, id: u64) -> Result<Record, CacheError> {
+        self.common.db.get(self.txn, id_to_bytes(id))
+    }
+
+    fn ids(&self, after: Option<&[u8]>) -> Result<Box<dyn Iterator<Item = u64> + '_>, CacheError> {
+        let cursor = self.common.id.open_ro_cursor(self.txn)?;
+        let start = after.map(|key| KeyEndpoint::Excluding(key.to_vec()));
+        Ok(Box::new(
+            CacheIterator::new(cursor, start, SortDirection::Ascending)
+                .map(|entry| map_database_entry_to_id(entry).1),
+        ))
+    }
+
+    fn index_entries(
+        &self,
+        index: SecondaryIndexId,
+        start: Option<KeyEndpoint>,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError> {
+        let cursor = self
+            .secondary_index_database(index)?
+            .open_ro_cursor(self.txn)?;
+        Ok(Box::new(
+            CacheIterator::new(cursor, start, direction).map(map_database_entry_to_id),
+        ))
+    }
+
+    fn index_entries_after(
+        &self,
+        index: SecondaryIndexId,
+        key: Vec<u8>,
+        id: u64,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError> {
+        let cursor = self
+            .secondary_index_database(index)?
+            .open_ro_cursor(self.txn)?;
+        Ok(Box::new(
+            CacheIterator::new_after(cursor, key, id_to_bytes(id).to_vec(), direction)
+                .map(map_database_entry_to_id),
+        ))
+    }
+
+    fn full_text_stats(&self, index: SecondaryIndexId) -> Result<FullTextStats, CacheError> {
+        self.secondary_index_database(index)?
+            .full_text_stats(self.txn)
+    }
+
+    fn full_text_document_frequency(
+        &self,
+        index: SecondaryIndexId,
+        key: &[u8],
+    ) -> Result<u64, CacheError> {
+        self.secondary_index_database(index)?
+            .full_text_document_frequency(self.txn, key)
+    }
+}
+
+/// The id and secondary index databases both map keys to ids.
+fn map_database_entry_to_id<'a>((key, id): (&'a [u8], &[u8])) -> (&'a [u8], u64) {
+    (
+        key,
+        id_from_bytes(
+            id.try_into()
+                .expect("All values must be u64 ids in id and index databases"),
+        ),
+    )
+}
@@ -1,15 +1,22 @@
 use crate::cache::{
-    expression::{FilterExpression, Operator, QueryExpression},
+    expression::{Cursor, FilterExpression, Operator, QueryExpression},
+    index::get_primary_key,
     lmdb::{
-        cache::LmdbRwCache,
-        tests::utils::{create_cache, insert_rec_1},
+        cache::CacheCommonOptions,
+        tests::utils::{create_caches, create_caches_with_options, insert_rec_1},
     },
-    test_utils::{query_from_filter, schema_1, schema_full_text, schema_multi_indices},
+    plan::{FullScan, IndexScanKind, Plan},
+    test_utils::{query_from_filter, schema_1, schema_full_text, schema_geo, schema_multi_indices},
     RecordWithId, RoCache, RwCache,
 };
+use crate::errors::{CacheError, PlanError};
 use dozer_types::{
+    ordered_float::OrderedFloat,
     serde_json::{from_value, json, Value},
-    types::{Field, Record, Schema},
+    types::{
+        DozerPoint, Field, FullTextAnalyzer, IndexDefinition, Language, PrefixSearch, Record,
+        Schema,
+    },
 };
 
 #[test]
@@ -20,8 +27,8 @@ fn query_secondary() {
     let full_text_schema_name = "full_text_sample";
     let (full_text_schema, full_text_secondary_indexes) = schema_full_text();
 
-    let cache = LmdbRwCache::create(
-        [
+    for cache in create_caches_with_options(
+        vec![
             (schema_name.to_string(), schema.clone(), secondary_indexes),
             (
                 full_text_schema_name.to_string(),
@@ -30,295 +37,888 @@ fn query_secondary() {
             ),
         ],
         Default::default(),
-        Default::default(),
-    )
-    .unwrap();
-    let mut record = Record::new(
-        schema.identifier,
-        vec![
-            Field::Int(1),
-            Field::String("test".to_string()),
-            Field::Int(2),
-        ],
-        None,
-    );
+    ) {
+        let mut record = Record::new(
+            schema.identifier,
+            vec![
+                Field::Int(1),
+                Field::String("test".to_string()),
+                Field::Int(2),
+            ],
+            None,
+        );
 
-    cache.insert(&mut record).unwrap();
-    assert!(record.version.is_some());
+        cache.insert(&mut record).unwrap();
+        assert!(record.version.is_some());
 
-    let filter = FilterExpression::And(vec![
-        FilterExpression::Simple("a".to_string(), Operator::EQ, Value::from(1)),
-        FilterExpression::Simple(
-            "b".to_string(),
-            Operator::EQ,
-            Value::from("test".to_string()),
-        ),
-    ]);
+        let filter = FilterExpression::And(vec![
+            FilterExpression::Simple("a".to_string(), Operator::EQ, Value::from(1)),
+            FilterExpression::Simple(
+                "b".to_string(),
+                Operator::EQ,
+                Value::from("test".to_string()),
+            ),
+        ]);
 
-    // Query with an expression
-    let query = query_from_filter(filter);
+        // Query with an expression
+        let query = query_from_filter(filter);
 
-    let records = cache.query(schema_name, &query).unwrap().1;
-    assert_eq!(cache.count(schema_name, &query).unwrap(), 1);
-    assert_eq!(records.len(), 1, "must be equal");
-    assert_eq!(records[0].record, record, "must be equal");
+        let records = cache.query(schema_name, &query).unwrap().1;
+        assert_eq!(cache.count(schema_name, &query).unwrap(), 1);
+        assert_eq!(records.len(), 1, "must be equal");
+        assert_eq!(records[0].record, record, "must be equal");
 
-    // Full text query.
-    let mut record = Record::new(
-        full_text_schema.identifier,
-        vec![
-            Field::String("today is a good day".into()),
-            Field::Text("marry has a little lamb".into()),
-        ],
-        None,
-    );
+        // Full text query.
+        let mut record = Record::new(
+            full_text_schema.identifier,
+            vec![
+                Field::String("today is a good day".into()),
+                Field::Text("marry has a little lamb".into()),
+            ],
+            None,
+        );
 
-    cache.insert(&mut record).unwrap();
-    assert!(record.version.is_some());
+        cache.insert(&mut record).unwrap();
+        assert!(record.version.is_some());
 
-    let filter = FilterExpression::Simple("foo".into(), Operator::Contains, "good".into());
+        let filter = FilterExpression::Simple("foo".into(), Operator::Contains, "good".into());
 
-    let query = query_from_filter(filter);
+        let query = query_from_filter(filter);
 
-    let records = cache.query(full_text_schema_name, &query).unwrap().1;
-    assert_eq!(cache.count(full_text_schema_name, &query).unwrap(), 1);
-    assert_eq!(records.len(), 1);
-    assert_eq!(records[0].record, record);
+        let records = cache.query(full_text_schema_name, &query).unwrap().1;
+        assert_eq!(cache.count(full_text_schema_name, &query).unwrap(), 1);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record, record);
 
-    let filter = FilterExpression::Simple("bar".into(), Operator::Contains, "lamb".into());
-    let query = query_from_filter(filter);
-    let records = cache.query(full_text_schema_name, &query).unwrap().1;
-    assert_eq!(cache.count(full_text_schema_name, &query).unwrap(), 1);
-    assert_eq!(records.len(), 1);
-    assert_eq!(records[0].record, record);
+        let filter = FilterExpression::Simple("bar".into(), Operator::Contains, "lamb".into());
+        let query = query_from_filter(filter);
+        let records = cache.query(full_text_schema_name, &query).unwrap().1;
+        assert_eq!(cache.count(full_text_schema_name, &query).unwrap(), 1);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record, record);
+    }
 }
 
 #[test]
 fn query_secondary_vars() {
     let schema_name = "sample";
-    let (cache, schema, _) = create_cache(schema_name, schema_1);
+    for (cache, schema, _) in create_caches(schema_name, schema_1) {
+        let items = vec![
+            (1, Some("yuri".to_string()), Some(521)),
+            (2, Some("mega".to_string()), Some(521)),
+            (3, Some("james".to_string()), Some(523)),
+            (4, Some("james".to_string()), Some(524)),
+            (5, Some("steff".to_string()), Some(526)),
+            (6, Some("mega".to_string()), Some(527)),
+            (7, Some("james".to_string()), Some(528)),
+            (8, Some("ava".to_string()), None),
+        ];
+        // 26 alphabets
+        for val in items {
+            insert_rec_1(&cache, &schema, val);
+        }
 
-    let items = vec![
-        (1, Some("yuri".to_string()), Some(521)),
-        (2, Some("mega".to_string()), Some(521)),
-        (3, Some("james".to_string()), Some(523)),
-        (4, Some("james".to_string()), Some(524)),
-        (5, Some("steff".to_string()), Some(526)),
-        (6, Some("mega".to_string()), Some(527)),
-        (7, Some("james".to_string()), Some(528)),
-        (8, Some("ava".to_string()), None),
-    ];
-    // 26 alphabets
-    for val in items {
-        insert_rec_1(&cache, &schema, val);
-    }
+        test_query(json!({}), 8, &cache, schema_name);
 
-    test_query(json!({}), 8, &cache, schema_name);
+        test_query(
+            json!({
+                "$order_by": { "c": "desc" }
+            }),
+            8,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({
-            "$order_by": { "c": "desc" }
-        }),
-        8,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "a": {"$eq": 1}}}),
+            1,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "a": {"$eq": 1}}}),
-        1,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "a": {"$eq": null}}}),
+            0,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "a": {"$eq": null}}}),
-        0,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$eq": 521}}}),
+            2,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$eq": 521}}}),
-        2,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$eq": null}}}),
+            1,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$eq": null}}}),
-        1,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "a": 1, "b": "yuri".to_string()}}),
+            1,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "a": 1, "b": "yuri".to_string()}}),
-        1,
-        &cache,
-        schema_name,
-    );
+        // No compound index for a,c, falls back to a full scan.
+        test_query(
+            json!({"$filter":{ "a": 1, "c": 521}}),
+            1,
+            &cache,
+            schema_name,
+        );
 
-    // No compound index for a,c
-    test_query_err(json!({"$filter":{ "a": 1, "c": 521}}), &cache, schema_name);
-
-    test_query(
-        json!({
-            "$filter":{ "c": {"$eq": 521}},
-            "$order_by": { "c": "asc" }
-        }),
-        2,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({
+                "$filter":{ "c": {"$eq": 521}},
+                "$order_by": { "c": "asc" }
+            }),
+            2,
+            &cache,
+            schema_name,
+        );
 
-    test_query_record(
-        json!({
-            "$filter":{ "a": {"$eq": 1}},
-            "$order_by": { "b": "asc" }
-        }),
-        vec![(0, 1, "yuri".to_string(), 521)],
-        &schema,
-        &cache,
-        schema_name,
-    );
+        test_query_record(
+            json!({
+                "$filter":{ "a": {"$eq": 1}},
+                "$order_by": { "b": "asc" }
+            }),
+            vec![(0, 1, "yuri".to_string(), 521)],
+            &schema,
+            &cache,
+            schema_name,
+        );
 
-    // Range tests
-    test_query(
-        json!({"$filter":{ "c": {"$lte": null}}}),
-        0,
-        &cache,
-        schema_name,
-    );
+        // Range tests
+        test_query(
+            json!({"$filter":{ "c": {"$lte": null}}}),
+            0,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$lte": 521}}}),
-        2,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$lte": 521}}}),
+            2,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$gte": 521}}}),
-        7,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$gte": 521}}}),
+            7,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$gt": 521}}}),
-        5,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$gt": 521}}}),
+            5,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$lte": 524}}}),
-        4,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$lte": 524}}}),
+            4,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$lt": 524}}}),
-        3,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$lt": 524}}}),
+            3,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$lt": 600}}}),
-        7,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$lt": 600}}}),
+            7,
+            &cache,
+            schema_name,
+        );
 
-    test_query(
-        json!({"$filter":{ "c": {"$gt": 200}}}),
-        7,
-        &cache,
-        schema_name,
-    );
+        test_query(
+            json!({"$filter":{ "c": {"$gt": 200}}}),
+            7,
+            &cache,
+            schema_name,
+        );
 
-    test_query_record(
-        json!({
-            "$filter":{ "c": {"$gt": 526}},
-            "$order_by": { "c": "asc" }
-        }),
-        vec![
-            (5, 6, "mega".to_string(), 527),
-            (6, 7, "james".to_string(), 528),
-        ],
-        &schema,
-        &cache,
-        schema_name,
-    );
+        test_query_record(
+            json!({
+                "$filter":{ "c": {"$gt": 526}},
+                "$order_by": { "c": "asc" }
+            }),
+            vec![
+                (5, 6, "mega".to_string(), 527),
+                (6, 7, "james".to_string(), 528),
+            ],
+            &schema,
+            &cache,
+            schema_name,
+        );
 
-    test_query_record(
-        json!({
-            "$filter":{ "c": {"$gt": 526}},
-            "$order_by": { "c": "desc" }
-        }),
-        vec![
-            (6, 7, "james".to_string(), 528),
-            (5, 6, "mega".to_string(), 527),
-        ],
-        &schema,
-        &cache,
-        schema_name,
-    );
+        test_query_record(
+            json!({
+                "$filter":{ "c": {"$gt": 526}},
+                "$order_by": { "c": "desc" }
+            }),
+            vec![
+                (6, 7, "james".to_string(), 528),
+                (5, 6, "mega".to_string(), 527),
+            ],
+            &schema,
+            &cache,
+            schema_name,
+        );
+    }
 }
 
 #[test]
 fn query_secondary_multi_indices() {
     let schema_name = "sample";
-    let (cache, schema, _) = create_cache(schema_name, schema_multi_indices);
-
-    for (id, text) in [
-        (1, "apple ball cake dance"),
-        (2, "ball cake dance egg"),
-        (3, "cake dance egg fish"),
-        (4, "dance egg fish glove"),
-        (5, "egg fish glove heart"),
-        (6, "fish glove heart igloo"),
-        (7, "glove heart igloo jump"),
-    ] {
-        let mut record = Record {
-            schema_id: schema.identifier,
-            values: vec![Field::Int(id), Field::String(text.into())],
-            version: None,
-        };
+    for (cache, schema, _) in create_caches(schema_name, schema_multi_indices) {
+        for (id, text) in [
+            (1, "apple ball cake dance"),
+            (2, "ball cake dance egg"),
+            (3, "cake dance egg fish"),
+            (4, "dance egg fish glove"),
+            (5, "egg fish glove heart"),
+            (6, "fish glove heart igloo"),
+            (7, "glove heart igloo jump"),
+        ] {
+            let mut record = Record {
+                schema_id: schema.identifier,
+                values: vec![Field::Int(id), Field::String(text.into())],
+                version: None,
+            };
+            cache.insert(&mut record).unwrap();
+            assert!(record.version.is_some());
+        }
+
+        let query = query_from_filter(FilterExpression::And(vec![
+            FilterExpression::Simple("id".into(), Operator::GT, Value::from(2)),
+            FilterExpression::Simple("text".into(), Operator::Contains, Value::from("dance")),
+        ]));
+
+        let records = cache.query(schema_name, &query).unwrap().1;
+        assert_eq!(cache.count(schema_name, &query).unwrap(), 2);
+        assert_eq!(
+            records,
+            vec![
+                RecordWithId::new(
+                    2,
+                    Record {
+                        schema_id: schema.identifier,
+                        values: vec![Field::Int(3), Field::String("cake dance egg fish".into())],
+                        version: Some(1)
+                    }
+                ),
+                RecordWithId::new(
+                    3,
+                    Record {
+                        schema_id: schema.identifier,
+                        values: vec![Field::Int(4), Field::String("dance egg fish glove".into())],
+                        version: Some(1)
+                    }
+                ),
+            ]
+        );
+
+        // The range scan orders the intersection, paginated with a key cursor.
+        let mut query = from_value::<QueryExpression>(json!({
+            "$filter": { "text": { "$contains": "egg" } },
+            "$order_by": { "id": "desc" },
+            "$limit": 2
+        }))
+        .unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![5, 4]);
+        assert!(matches!(query.cursor, Some(Cursor::Key { .. })));
+        let mut record = Record::new(
+            schema.identifier,
+            vec![Field::Int(8), Field::String("egg".into())],
+            None,
+        );
         cache.insert(&mut record).unwrap();
-        assert!(record.version.is_some());
+        cache
+            .delete(&get_primary_key(&schema.primary_index, &[Field::Int(3)]))
+            .unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![2]);
+        assert_eq!(query.cursor, None);
     }
+}
 
-    let query = query_from_filter(FilterExpression::And(vec![
-        FilterExpression::Simple("id".into(), Operator::GT, Value::from(2)),
-        FilterExpression::Simple("text".into(), Operator::Contains, Value::from("dance")),
-    ]));
+#[test]
+fn query_full_scan() {
+    let schema_name = "sample";
+    let (schema, secondary_indexes) = schema_1();
+    for cache in create_caches_with_options(
+        vec![(schema_name.to_string(), schema.clone(), secondary_indexes)],
+        CacheCommonOptions {
+            max_scanned_rows: 5,
+            ..Default::default()
+        },
+    ) {
+        let items = vec![
+            (1, Some("yuri".to_string()), Some(521)),
+            (2, Some("mega".to_string()), Some(521)),
+            (3, Some("james".to_string()), Some(523)),
+            (4, Some("james".to_string()), Some(524)),
+            (5, Some("steff".to_string()), Some(526)),
+            (6, Some("mega".to_string()), Some(527)),
+            (7, Some("james".to_string()), Some(528)),
+            (8, Some("ava".to_string()), None),
+        ];
+        for val in items {
+            insert_rec_1(&cache, &schema, val);
+        }
 
-    let records = cache.query(schema_name, &query).unwrap().1;
-    assert_eq!(cache.count(schema_name, &query).unwrap(), 2);
-    assert_eq!(
-        records,
-        vec![
-            RecordWithId::new(
-                2,
-                Record {
-                    schema_id: schema.identifier,
-                    values: vec![Field::Int(3), Field::String("cake dance egg fish".into())],
-                    version: Some(1)
+        // Scans `a > 4` and filters `c < 527` in memory. `null` never matches a range filter.
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": { "a": { "$gt": 4 }, "c": { "$lt": 527 } },
+        }))
+        .unwrap();
+        let Plan::FullScan(FullScan {
+            index_scan,
+            residual_filters,
+            ..
+        }) = cache.explain(schema_name, &query).unwrap()
+        else {
+            panic!("FullScan expected")
+        };
+        assert_eq!(index_scan.unwrap().index_id, 0);
+        assert_eq!(residual_filters.len(), 1);
+        test_query_record(
+            json!({
+                "$filter": { "a": { "$gt": 4 }, "c": { "$lt": 527 } },
+            }),
+            vec![(4, 5, "steff".to_string(), 526)],
+            &schema,
+            &cache,
+            schema_name,
+        );
+
+        // Sorts in memory.
+        test_query_record(
+            json!({
+                "$filter": { "a": 4, "c": { "$gte": 521 } },
+                "$order_by": { "b": "asc" }
+            }),
+            vec![(3, 4, "james".to_string(), 524)],
+            &schema,
+            &cache,
+            schema_name,
+        );
+        test_query_record(
+            json!({
+                "$filter": { "b": "james" },
+                "$order_by": { "c": "desc" },
+                "$skip": 1
+            }),
+            vec![
+                (3, 4, "james".to_string(), 524),
+                (2, 3, "james".to_string(), 523),
+            ],
+            &schema,
+            &cache,
+            schema_name,
+        );
+
+        // No full text index on b.
+        test_query(
+            json!({ "$filter": { "a": 2, "b": { "$contains": "mega" } } }),
+            1,
+            &cache,
+            schema_name,
+        );
+
+        // Scans more than 5 records.
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": { "a": { "$gt": 2 }, "c": { "$lt": 527 } },
+        }))
+        .unwrap();
+        assert!(matches!(
+            cache.query(schema_name, &query).unwrap_err(),
+            crate::errors::CacheError::ScanLimitExceeded(5)
+        ));
+        assert!(matches!(
+            cache.count(schema_name, &query).unwrap_err(),
+            crate::errors::CacheError::ScanLimitExceeded(5)
+        ));
+        // Index scans have no limit.
+        test_query(
+            json!({ "$filter": { "a": { "$gt": 2 } } }),
+            6,
+            &cache,
+            schema_name,
+        );
+    }
+}
+
+#[test]
+fn query_aggregation() {
+    let schema_name = "sample";
+    for (cache, schema, _) in create_caches(schema_name, schema_1) {
+        let items = vec![
+            (1, Some("yuri".to_string()), Some(521)),
+            (2, Some("mega".to_string()), Some(521)),
+            (3, Some("james".to_string()), Some(523)),
+            (4, Some("james".to_string()), Some(524)),
+            (5, Some("steff".to_string()), Some(526)),
+            (6, Some("mega".to_string()), Some(527)),
+            (7, Some("james".to_string()), Some(528)),
+            (8, Some("ava".to_string()), None),
+        ];
+        for val in items {
+            insert_rec_1(&cache, &schema, val);
+        }
+
+        let query = from_value::<QueryExpression>(json!({
+            "$group_by": ["b"],
+            "$aggregate": {
+                "n": { "$count": "*" },
+                "total": { "$sum": "c" },
+                "max_a": { "$max": "a" },
+                "avg": { "$avg": "c" }
+            }
+        }))
+        .unwrap();
+        let (output_schema, records) = cache.aggregate(schema_name, &query).unwrap();
+        assert_eq!(
+            output_schema
+                .fields
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", "n", "total", "max_a", "avg"]
+        );
+        let group = |b: &str, n: u64, total: Option<i64>, max_a: i64, avg: Option<f64>| {
+            vec![
+                Field::String(b.to_string()),
+                Field::UInt(n),
+                total.map_or(Field::Null, Field::Int),
+                Field::Int(max_a),
+                avg.map_or(Field::Null, |avg| Field::Float(OrderedFloat(avg))),
+            ]
+        };
+        assert_eq!(
+            records
+                .into_iter()
+                .map(|record| record.values)
+                .collect::<Vec<_>>(),
+            vec![
+                group("ava", 1, None, 8, None),
+                group("james", 3, Some(1575), 7, Some(525.0)),
+                group("mega", 2, Some(1048), 6, Some(524.0)),
+                group("steff", 1, Some(526), 5, Some(526.0)),
+                group("yuri", 1, Some(521), 1, Some(521.0)),
+            ]
+        );
+
+        // Filter uses the index, `order_by` and `limit` apply to the groups.
+        test_aggregation(
+            json!({
+                "$filter": { "c": { "$gte": 524 } },
+                "$group_by": ["b"],
+                "$aggregate": { "n": { "$count": "*" } },
+                "$order_by": { "n": "desc" },
+                "$limit": 1
+            }),
+            vec![vec![Field::String("james".to_string()), Field::UInt(2)]],
+            &cache,
+            schema_name,
+        );
+
+        // Without group by there's exactly one group, even if nothing matches.
+        test_aggregation(
+            json!({
+                "$filter": { "c": { "$gt": 521 } },
+                "$aggregate": { "n": { "$count": "*" } }
+            }),
+            vec![vec![Field::UInt(5)]],
+            &cache,
+            schema_name,
+        );
+        test_aggregation(
+            json!({ "$aggregate": { "n": { "$count": "c" }, "min_c": { "$min": "c" } } }),
+            vec![vec![Field::UInt(7), Field::Int(521)]],
+            &cache,
+            schema_name,
+        );
+        test_aggregation(
+            json!({
+                "$filter": { "c": { "$gt": 1000 } },
+                "$aggregate": { "n": { "$count": "*" }, "total": { "$sum": "c" } }
+            }),
+            vec![vec![Field::UInt(0), Field::Null]],
+            &cache,
+            schema_name,
+        );
+
+        for query in [
+            json!({ "$aggregate": { "total": { "$sum": "b" } } }),
+            json!({ "$group_by": ["d"] }),
+            json!({ "$group_by": ["b"], "$aggregate": { "b": { "$count": "*" } } }),
+            json!({ "$group_by": ["b"], "$order_by": { "c": "asc" } }),
+        ] {
+            let query = from_value::<QueryExpression>(query).unwrap();
+            assert!(matches!(
+                cache.aggregate(schema_name, &query).unwrap_err(),
+                crate::errors::CacheError::Plan(_)
+            ));
+        }
+    }
+}
+
+#[test]
+fn query_aggregation_sum_overflow() {
+    let schema_name = "sample";
+    for (cache, schema, _) in create_caches(schema_name, schema_1) {
+        insert_rec_1(
+            &cache,
+            &schema,
+            (1, Some("yuri".to_string()), Some(i64::MAX)),
+        );
+        insert_rec_1(&cache, &schema, (2, Some("yuri".to_string()), Some(1)));
+
+        let query = from_value::<QueryExpression>(json!({
+            "$aggregate": { "total": { "$sum": "c" } }
+        }))
+        .unwrap();
+        assert!(matches!(
+            cache.aggregate(schema_name, &query).unwrap_err(),
+            CacheError::Plan(PlanError::SumOverflow(name)) if name == "total"
+        ));
+    }
+}
+
+#[test]
+fn query_aggregation_scan_limit() {
+    let schema_name = "sample";
+    let (schema, secondary_indexes) = schema_1();
+    for cache in create_caches_with_options(
+        vec![(schema_name.to_string(), schema.clone(), secondary_indexes)],
+        CacheCommonOptions {
+            max_scanned_rows: 5,
+            ..Default::default()
+        },
+    ) {
+        for a in 1..=8 {
+            insert_rec_1(&cache, &schema, (a, Some("james".to_string()), Some(a)));
+        }
+
+        // Grouping reads every record.
+        let query = from_value::<QueryExpression>(json!({
+            "$group_by": ["b"],
+            "$aggregate": { "n": { "$count": "*" } }
+        }))
+        .unwrap();
+        assert!(matches!(
+            cache.aggregate(schema_name, &query).unwrap_err(),
+            CacheError::ScanLimitExceeded(5)
+        ));
+
+        // Counting doesn't read the records, and a selective filter stays under the limit.
+        test_aggregation(
+            json!({ "$aggregate": { "n": { "$count": "*" } } }),
+            vec![vec![Field::UInt(8)]],
+            &cache,
+            schema_name,
+        );
+        test_aggregation(
+            json!({
+                "$filter": { "c": { "$gt": 5 } },
+                "$group_by": ["b"],
+                "$aggregate": { "n": { "$count": "*" } }
+            }),
+            vec![vec![Field::String("james".to_string()), Field::UInt(3)]],
+            &cache,
+            schema_name,
+        );
+    }
+}
+
+#[test]
+fn query_select() {
+    let schema_name = "sample";
+    for (cache, schema, _) in create_caches(schema_name, schema_1) {
+        insert_rec_1(&cache, &schema, (1, Some("yuri".to_string()), Some(521)));
+
+        let query = from_value::<QueryExpression>(json!({ "$select": ["c", "a"] })).unwrap();
+        assert_eq!(query.projection(&schema).unwrap(), Some(vec![2, 0]));
+        assert_eq!(cache.query(schema_name, &query).unwrap().1.len(), 1);
+
+        test_query_err(json!({ "$select": ["d"] }), &cache, schema_name);
+    }
+}
+
+#[test]
+fn query_cursor() {
+    let schema_name = "sample";
+    let (schema, _) = schema_1();
+    let items = vec![
+        (1, Some("yuri".to_string()), Some(521)),
+        (2, Some("mega".to_string()), Some(521)),
+        (3, Some("james".to_string()), Some(523)),
+        (4, Some("james".to_string()), Some(524)),
+        (5, Some("steff".to_string()), Some(526)),
+        (6, Some("mega".to_string()), Some(527)),
+        (7, Some("james".to_string()), Some(528)),
+    ];
+    let create_items_caches = || {
+        create_caches(schema_name, schema_1)
+            .into_iter()
+            .map(|(cache, _, _)| {
+                for val in items.clone() {
+                    insert_rec_1(&*cache, &schema, val);
                 }
-            ),
-            RecordWithId::new(
-                3,
-                Record {
-                    schema_id: schema.identifier,
-                    values: vec![Field::Int(4), Field::String("dance egg fish glove".into())],
-                    version: Some(1)
+                cache
+            })
+            .collect::<Vec<_>>()
+    };
+    let delete = |cache: &dyn RwCache, a: i64| {
+        cache
+            .delete(&get_primary_key(&schema.primary_index, &[Field::Int(a)]))
+            .unwrap();
+    };
+
+    // Sequential scan, records inserted before the cursor are not returned.
+    for cache in create_items_caches() {
+        let mut query = from_value::<QueryExpression>(json!({ "$limit": 3 })).unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![1, 2, 3]);
+        insert_rec_1(&cache, &schema, (0, Some("ava".to_string()), Some(520)));
+        insert_rec_1(&cache, &schema, (9, Some("ava".to_string()), Some(529)));
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![4, 5, 6]);
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![7, 9]);
+        assert_eq!(query.cursor, None);
+    }
+
+    // Index scan on duplicate keys, the record at the cursor is deleted.
+    for cache in create_items_caches() {
+        let mut query = from_value::<QueryExpression>(json!({
+            "$filter": { "b": "james" },
+            "$limit": 2
+        }))
+        .unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![3, 4]);
+        delete(&cache, 4);
+        insert_rec_1(&cache, &schema, (8, Some("james".to_string()), Some(529)));
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![7, 8]);
+        assert_eq!(
+            query_page(&mut query, &cache, schema_name),
+            Vec::<i64>::new()
+        );
+        assert_eq!(query.cursor, None);
+    }
+
+    // Descending range scan, the key at the cursor is deleted.
+    for cache in create_items_caches() {
+        let mut query = from_value::<QueryExpression>(json!({
+            "$order_by": { "c": "desc" },
+            "$limit": 3
+        }))
+        .unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![7, 6, 5]);
+        delete(&cache, 5);
+        insert_rec_1(&cache, &schema, (8, Some("ava".to_string()), Some(525)));
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![8, 4, 3]);
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![2, 1]);
+        assert_eq!(query.cursor, None);
+    }
+
+    // Full scan, sorted in memory.
+    for cache in create_items_caches() {
+        let mut query = from_value::<QueryExpression>(json!({
+            "$filter": { "a": { "$gt": 1 }, "c": { "$lt": 528 } },
+            "$order_by": { "b": "asc" },
+            "$limit": 2
+        }))
+        .unwrap();
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![3, 4]);
+        insert_rec_1(&cache, &schema, (8, Some("ava".to_string()), Some(522)));
+        insert_rec_1(&cache, &schema, (9, Some("kim".to_string()), Some(522)));
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![9, 2]);
+        assert_eq!(query_page(&mut query, &cache, schema_name), vec![6, 5]);
+        assert_eq!(
+            query_page(&mut query, &cache, schema_name),
+            Vec::<i64>::new()
+        );
+
+        // Cursor of another plan.
+        let mut query = from_value::<QueryExpression>(json!({ "$limit": 3 })).unwrap();
+        query.cursor = Some(Cursor::Sorted {
+            values: vec![],
+            id: 0,
+        });
+        assert!(matches!(
+            cache.query(schema_name, &query).unwrap_err(),
+            CacheError::Plan(PlanError::InvalidCursor)
+        ));
+        query.aggregation = Some(Default::default());
+        assert!(matches!(
+            cache.aggregate(schema_name, &query).unwrap_err(),
+            CacheError::Plan(PlanError::AfterWithAggregation)
+        ));
+    }
+}
+
+#[test]
+fn query_geo() {
+    let schema_name = "places";
+    let (schema, secondary_indexes) = schema_geo();
+    for cache in create_caches_with_options(
+        vec![(schema_name.to_string(), schema.clone(), secondary_indexes)],
+        Default::default(),
+    ) {
+        let places = [
+            ("zoo", Some((13.3376, 52.5079))),
+            ("potsdam", Some((13.0645, 52.3906))),
+            ("alex", Some((13.4132, 52.525))),
+            ("museum", Some((13.3977, 52.5169))),
+            ("fiji", Some((178.0, -17.7))),
+            ("east", Some((-179.9, -17.0))),
+            ("samoa", Some((-172.0, -13.8))),
+            ("nowhere", None),
+        ];
+        for (name, location) in places {
+            let mut record = Record::new(
+                schema.identifier,
+                vec![
+                    Field::String(name.to_string()),
+                    location.map_or(Field::Null, |location| {
+                        Field::Point(DozerPoint::from(location))
+                    }),
+                ],
+                None,
+            );
+            cache.insert(&mut record).unwrap();
+        }
+        let query_names = |query: &QueryExpression| {
+            let records = cache.query(schema_name, query).unwrap().1;
+            assert_eq!(cache.count(schema_name, query).unwrap(), records.len());
+            let cursor = cache.next_cursor(schema_name, query, &records).unwrap();
+            let names = records
+                .into_iter()
+                .map(|record| match &record.record.values[0] {
+                    Field::String(name) => name.clone(),
+                    _ => panic!("name must be a string"),
+                })
+                .collect::<Vec<_>>();
+            (names, cursor)
+        };
+
+        // Nearest first, paginated with the cursor.
+        let mut query = from_value::<QueryExpression>(json!({
+            "$filter": {
+                "location": { "$near": { "center": { "x": 13.405, "y": 52.52 }, "max_distance": 5000 } }
+            },
+            "$limit": 2
+        }))
+        .unwrap();
+        let Plan::FullScan(FullScan {
+            index_scan: Some(index_scan),
+            ..
+        }) = cache.explain(schema_name, &query).unwrap()
+        else {
+            panic!("FullScan with geo index scan expected")
+        };
+        assert!(matches!(index_scan.kind, IndexScanKind::Geo { .. }));
+        let (names, cursor) = query_names(&query);
+        assert_eq!(names, vec!["museum", "alex"]);
+        query.cursor = cursor;
+        assert_eq!(query_names(&query), (vec!["zoo".to_string()], None));
+
+        // Combined with other filters.
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": {
+                "location": { "$near": { "center": { "x": 13.405, "y": 52.52 }, "max_distance": 5000 } },
+                "name": { "$gt": "b" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(query_names(&query).0, vec!["museum", "zoo"]);
+
+        // Box.
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": {
+                "location": { "$within_box": { "min": { "x": 13.3, "y": 52.5 }, "max": { "x": 13.42, "y": 52.53 } } }
+            },
+            "$order_by": { "name": "asc" }
+        }))
+        .unwrap();
+        assert_eq!(query_names(&query).0, vec!["alex", "museum", "zoo"]);
+
+        // Circle crossing the antimeridian.
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": {
+                "location": { "$within_radius": { "center": { "x": 179.9, "y": -17.0 }, "radius": 300000 } }
+            },
+            "$order_by": { "name": "asc" }
+        }))
+        .unwrap();
+        assert_eq!(query_names(&query).0, vec!["east", "fiji"]);
+
+        // Deleted records are removed from the geo index.
+        cache
+            .delete(&get_primary_key(
+                &schema.primary_index,
+                &[Field::String("museum".to_string())],
+            ))
+            .unwrap();
+        let query = from_value::<QueryExpression>(json!({
+            "$filter": {
+                "location": { "$near": { "center": { "x": 13.405, "y": 52.52 }, "max_distance": 5000 } }
+            }
+        }))
+        .unwrap();
+        assert_eq!(query_names(&query).0, vec!["alex", "zoo"]);
+
+        test_query_err(
+            json!({
+                "$filter": {
+                    "name": { "$within_radius": { "center": { "x": 0, "y": 0 }, "radius": 1 } }
                 }
-            ),
-        ]
+            }),
+            &cache,
+            schema_name,
+        );
+    }
+}
+
+/// Returns the `a` of the records in the page, and sets `query.cursor` to continue after them.
+fn query_page(query: &mut QueryExpression, cache: &dyn RwCache, schema_name: &str) -> Vec<i64> {
+    let records = cache.query(schema_name, query).unwrap().1;
+    assert_eq!(cache.count(schema_name, query).unwrap(), records.len());
+    query.cursor = cache.next_cursor(schema_name, query, &records).unwrap();
+    records
+        .into_iter()
+        .map(|record| match record.record.values[0] {
+            Field::Int(a) => a,
+            _ => panic!("a must be an int"),
+        })
+        .collect()
+}
+
+fn test_aggregation(
+    query: Value,
+    expected: Vec<Vec<Field>>,
+    cache: &dyn RwCache,
+    schema_name: &str,
+) {
+    let query = from_value::<QueryExpression>(query).unwrap();
+    let records = cache.aggregate(schema_name, &query).unwrap().1;
+    assert_eq!(
+        records
+            .into_iter()
+            .map(|record| record.values)
+            .collect::<Vec<_>>(),
+        expected
     );
 }
 
@@ -369,3 +969,113 @@ fn test_query_record(
         .collect::<Vec<_>>();
     assert_eq!(records, expected);
 }
+
+#[test]
+fn query_full_text_analyzer() {
+    let schema_name = "products";
+    let (schema, mut secondary_indexes) = schema_multi_indices();
+    secondary_indexes[1] = IndexDefinition::FullText(
+        1,
+        FullTextAnalyzer {
+            lowercase: true,
+            normalize: true,
+            stop_words: Some(Language::English),
+            stemming: Some(Language::English),
+            prefix_search: Some(PrefixSearch {
+                min_len: 3,
+                max_len: 5,
+            }),
+        },
+    );
+    for cache in create_caches_with_options(
+        vec![(schema_name.to_string(), schema.clone(), secondary_indexes)],
+        Default::default(),
+    ) {
+        for (id, text) in [
+            (1, "Running shoes for trail runners"),
+            (2, "The red shoe"),
+            (3, "Laptop bag and laptop sleeve"),
+            (4, "A bag"),
+        ] {
+            let mut record = Record::new(
+                schema.identifier,
+                vec![Field::Int(id), Field::String(text.into())],
+                None,
+            );
+            cache.insert(&mut record).unwrap();
+        }
+        let query_ids = |query: &QueryExpression| {
+            let records = cache.query(schema_name, query).unwrap().1;
+            assert_eq!(cache.count(schema_name, query).unwrap(), records.len());
+            let cursor = cache.next_cursor(schema_name, query, &records).unwrap();
+            let ids = records
+                .into_iter()
+                .map(|record| record.record.values[0].as_int().unwrap())
+                .collect::<Vec<_>>();
+            (ids, cursor)
+        };
+        let check = |query: Value, expected: Vec<i64>| {
+            let query = from_value::<QueryExpression>(query).unwrap();
+            assert_eq!(query_ids(&query).0, expected);
+        };
+
+        // Filter values are analyzed like the indexed text.
+        check(
+            json!({"$filter": { "text": { "$contains": "SHOES" } }}),
+            vec![1, 2],
+        );
+        check(
+            json!({"$filter": { "text": { "$matches_all": "running shoe" } }}),
+            vec![1],
+        );
+        check(
+            json!({"$filter": { "text": { "$matches_any": "lapt red" } }}),
+            vec![2, 3],
+        );
+        // Only stop words.
+        check(
+            json!({"$filter": { "text": { "$matches_any": "the" } }}),
+            vec![],
+        );
+        // Residual filters use the analyzer too. Two range filters fall back to a full scan of `$contains`.
+        check(
+            json!({"$filter": { "$and": [
+                { "text": { "$contains": "shoe" } },
+                { "text": { "$matches_any": "RED lapt" } },
+                { "id": { "$gt": 0 } },
+                { "id": { "$lt": 4 } }
+            ] }}),
+            vec![2],
+        );
+
+        // Most relevant first, paginated with the cursor.
+        let mut query = from_value::<QueryExpression>(json!({
+            "$filter": { "text": { "$matches_any": "laptop bag" } },
+            "$order_by": { "text": "desc" },
+            "$limit": 1
+        }))
+        .unwrap();
+        let (ids, cursor) = query_ids(&query);
+        assert_eq!(ids, vec![3]);
+        assert!(matches!(cursor, Some(Cursor::Sorted { .. })));
+        query.cursor = cursor;
+        assert_eq!(query_ids(&query).0, vec![4]);
+        check(
+            json!({
+                "$filter": { "text": { "$matches_any": "laptop bag" } },
+                "$order_by": { "text": "asc" }
+            }),
+            vec![4, 3],
+        );
+
+        // Deleted records leave the index.
+        cache.delete(&Field::Int(3).encode()).unwrap();
+        check(
+            json!({
+                "$filter": { "text": { "$matches_any": "laptop bag" } },
+                "$order_by": { "text": "desc" }
+            }),
+            vec![4],
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use dozer_storage::{
-    lmdb::{Database, DatabaseFlags, RwTransaction, Transaction, WriteFlags},
+    lmdb::{Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags},
     lmdb_storage::LmdbEnvironmentManager,
 };
 use dozer_types::{bincode, types::Record};
@@ -51,6 +51,14 @@ impl RecordDatabase {
             .map(|stat| stat.ms_entries)
             .map_err(|e| CacheError::Internal(Box::new(e)))
     }
+
+    pub fn open_ro_cursor<'txn, T: Transaction>(
+        &self,
+        txn: &'txn T,
+    ) -> Result<RoCursor<'txn>, CacheError> {
+        txn.open_ro_cursor(self.0)
+            .map_err(|e| CacheError::Internal(Box::new(e)))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,291 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use dozer_storage::lmdb_storage::SharedTransaction;
+use dozer_types::types::{IndexDefinition, Record, Schema};
+
+use super::super::indexer::Indexer;
+use super::{id_to_bytes, IdDatabase, RecordDatabase, SecondaryIndexDatabases};
+use crate::cache::RetentionPolicy;
+use crate::errors::CacheError;
+
+/// Deletes expired records of an append-only schema, oldest first.
+///
+/// The schema must be the only one in the cache, and records must only be deleted by `Retention`.
+#[derive(Debug)]
+pub struct Retention {
+    pub txn: SharedTransaction,
+    /// Shared with the cache, see `LmdbRwCache`.
+    pub has_uncommitted_writes: Arc<AtomicBool>,
+    pub db: RecordDatabase,
+    pub id: IdDatabase,
+    pub secondary_index_databases: SecondaryIndexDatabases,
+    pub schema: Schema,
+    pub secondary_indexes: Vec<IndexDefinition>,
+    pub policy: RetentionPolicy,
+}
+
+impl Retention {
+    /// Deletes at most `batch_size` expired records and returns them.
+    ///
+    /// The deletions are committed right away, unless the cache has uncommitted writes, which
+    /// must be committed with their checkpoint. Then the deletions are committed with them.
+    pub fn delete_expired(&self, batch_size: usize) -> Result<Vec<Record>, CacheError> {
+        let mut exclusive_txn = self.txn.write();
+        let txn = exclusive_txn.txn_mut();
+
+        // Ids are generated in insertion order and the oldest records are deleted first,
+        // so the remaining records are the ones with the largest ids.
+        let num_ids = self.id.count(txn)? as u64;
+        let num_records = self.db.count(txn)? as u64;
+        let expiry = self.policy.expiry();
+
+        let indexer = Indexer {
+            secondary_indexes: &self.secondary_index_databases,
+        };
+        let mut deleted = vec![];
+        for id in num_ids.saturating_sub(num_records)..num_ids {
+            if deleted.len() == batch_size {
+                break;
+            }
+            let id = id_to_bytes(id);
+            let record = self.db.get(txn, id)?;
+            if !expiry.is_expired(&record, num_records - deleted.len() as u64) {
+                break;
+            }
+            self.db.delete(txn, id)?;
+            indexer.delete_indexes(txn, &record, &self.schema, &self.secondary_indexes, id)?;
+            deleted.push(record);
+        }
+
+        if !deleted.is_empty() && !self.has_uncommitted_writes.load(Ordering::SeqCst) {
+            exclusive_txn.commit_and_renew()?;
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use dozer_types::chrono::{self, Utc};
+    use dozer_types::types::{
+        Field, FieldDefinition, FieldType, Record, SchemaIdentifier, SourceDefinition,
+    };
+
+    use crate::cache::lmdb::tests::utils::{create_cache, get_indexes};
+    use crate::cache::{
+        CacheManager, CacheManagerOptions, LmdbCacheManager, RetentionOptions, RoCache, RwCache,
+    };
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn schema() -> (Schema, Vec<IndexDefinition>) {
+        (
+            Schema {
+                identifier: Some(SchemaIdentifier { id: 1, version: 1 }),
+                fields: vec![
+                    FieldDefinition {
+                        name: "value".to_string(),
+                        typ: FieldType::Int,
+                        nullable: false,
+                        source: SourceDefinition::Dynamic,
+                    },
+                    FieldDefinition {
+                        name: "timestamp".to_string(),
+                        typ: FieldType::Timestamp,
+                        nullable: true,
+                        source: SourceDefinition::Dynamic,
+                    },
+                ],
+                primary_index: vec![],
+            },
+            vec![IndexDefinition::SortedInverted(vec![0])],
+        )
+    }
+
+    fn insert(cache: &dyn RwCache, schema: &Schema, value: i64, age: Option<i64>) {
+        let timestamp = age.map_or(Field::Null, |age| {
+            Field::Timestamp(
+                (Utc::now() - chrono::Duration::seconds(age))
+                    .with_timezone(&chrono::FixedOffset::east_opt(3600).unwrap()),
+            )
+        });
+        let mut record = Record::new(schema.identifier, vec![Field::Int(value), timestamp], None);
+        cache.insert(&mut record).unwrap();
+    }
+
+    fn values(cache: &dyn RoCache) -> Vec<i64> {
+        cache
+            .query("logs", &Default::default())
+            .unwrap()
+            .1
+            .into_iter()
+            .map(|record| record.record.values[0].as_int().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_max_count() {
+        let (cache, schema, _) = create_cache("logs", schema);
+        for value in 0..5 {
+            insert(&cache, &schema, value, None);
+        }
+        let retention = cache
+            .retention(
+                "logs",
+                RetentionPolicy {
+                    max_age: None,
+                    max_count: Some(2),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(retention.delete_expired(2).unwrap().len(), 2);
+        assert_eq!(values(&cache), vec![2, 3, 4]);
+        assert_eq!(retention.delete_expired(2).unwrap().len(), 1);
+        assert_eq!(retention.delete_expired(2).unwrap().len(), 0);
+        assert_eq!(values(&cache), vec![3, 4]);
+        assert_eq!(get_indexes(&cache)[0].len(), 2);
+
+        // Ids keep growing after deletions.
+        insert(&cache, &schema, 5, None);
+        assert_eq!(retention.delete_expired(2).unwrap().len(), 1);
+        assert_eq!(values(&cache), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_max_age() {
+        let (cache, schema, _) = create_cache("logs", schema);
+        insert(&cache, &schema, 0, Some(100));
+        insert(&cache, &schema, 1, Some(90));
+        insert(&cache, &schema, 2, Some(10));
+        insert(&cache, &schema, 3, Some(95));
+        insert(&cache, &schema, 4, None);
+        let retention = cache
+            .retention(
+                "logs",
+                RetentionPolicy {
+                    max_age: Some((1, Duration::from_secs(60))),
+                    max_count: None,
+                },
+            )
+            .unwrap();
+
+        // Stops at the first record that's not expired.
+        assert_eq!(retention.delete_expired(10).unwrap().len(), 2);
+        assert_eq!(values(&cache), vec![2, 3, 4]);
+        assert_eq!(get_indexes(&cache)[0].len(), 3);
+    }
+
+    #[test]
+    fn test_retention_job() {
+        let (mut cache, schema, _) = create_cache("logs", schema);
+        for value in 0..10 {
+            insert(&cache, &schema, value, None);
+        }
+        cache
+            .start_retention(
+                "logs",
+                RetentionPolicy {
+                    max_age: None,
+                    max_count: Some(3),
+                },
+                RetentionOptions {
+                    interval: Duration::from_millis(10),
+                    batch_size: 2,
+                },
+                Box::new(|_| {}),
+            )
+            .unwrap();
+
+        for _ in 0..500 {
+            if values(&cache).len() == 3 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(values(&cache), vec![7, 8, 9]);
+        cache.commit(&Default::default()).unwrap();
+    }
+
+    #[test]
+    fn test_retention_job_commits() {
+        let path = TempDir::new("dozer").unwrap();
+        let cache_manager = LmdbCacheManager::new(CacheManagerOptions {
+            path: Some(path.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+        let (schema, secondary_indexes) = schema();
+        let mut cache = cache_manager
+            .create_cache(vec![(
+                "logs".to_string(),
+                schema.clone(),
+                secondary_indexes,
+            )])
+            .unwrap();
+        for value in 0..5 {
+            insert(&*cache, &schema, value, None);
+        }
+        cache.commit(&Default::default()).unwrap();
+        let reader = cache_manager.open_ro_cache(cache.name()).unwrap().unwrap();
+
+        let (expired, expired_receiver) = mpsc::channel();
+        cache
+            .start_retention(
+                "logs",
+                RetentionPolicy {
+                    max_age: None,
+                    max_count: Some(2),
+                },
+                RetentionOptions {
+                    interval: Duration::from_millis(10),
+                    batch_size: 10,
+                },
+                Box::new(move |records| expired.send(records).unwrap()),
+            )
+            .unwrap();
+        let timeout = Duration::from_secs(5);
+        let records = expired_receiver.recv_timeout(timeout).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].values[0], Field::Int(0));
+        // Committed although the cache has no new writes.
+        assert_eq!(values(&*reader), vec![3, 4]);
+
+        // Deletions are committed with uncommitted writes.
+        insert(&*cache, &schema, 5, None);
+        assert_eq!(expired_receiver.recv_timeout(timeout).unwrap().len(), 1);
+        assert_eq!(values(&*reader), vec![3, 4]);
+        cache.commit(&Default::default()).unwrap();
+        assert_eq!(values(&*reader), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_invalid_retention_policy() {
+        let (cache, _, _) = create_cache("logs", schema);
+        // Not a timestamp, and out of range.
+        for field_index in [0, 2] {
+            assert!(matches!(
+                cache.retention(
+                    "logs",
+                    RetentionPolicy {
+                        max_age: Some((field_index, Duration::from_secs(1))),
+                        max_count: None,
+                    },
+                ),
+                Err(CacheError::InvalidRetentionPolicy(_))
+            ));
+        }
+
+        let (cache, _, _) = create_cache("docs", crate::cache::test_utils::schema_0);
+        assert!(matches!(
+            cache.retention("docs", Default::default()),
+            Err(CacheError::InvalidRetentionPolicy(_))
+        ));
+    }
+}
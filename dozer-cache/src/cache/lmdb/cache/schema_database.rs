@@ -12,6 +12,12 @@ use dozer_types::{
 
 use crate::errors::{CacheError, QueryError};
 
+/// Name of the database storing schemas and their index definitions. It's versioned because
+/// index definitions are bincode encoded, so changing `IndexDefinition` breaks existing caches.
+const SCHEMAS_DATABASE_NAME: &str = "schemas_v2";
+/// Names of the schema databases of incompatible cache formats.
+const LEGACY_SCHEMAS_DATABASE_NAMES: &[&str] = &["schemas"];
+
 #[derive(Debug, Clone)]
 pub struct SchemaDatabase {
     inner: Database,
@@ -25,13 +31,20 @@ impl SchemaDatabase {
         env: &mut LmdbEnvironmentManager,
         create_if_not_exist: bool,
     ) -> Result<Self, CacheError> {
+        // Refuse caches whose index definitions can't be decoded.
+        for name in LEGACY_SCHEMAS_DATABASE_NAMES {
+            if env.create_database(Some(name), None).is_ok() {
+                return Err(CacheError::IncompatibleCacheFormat);
+            }
+        }
+
         // Open or create database.
         let flags = if create_if_not_exist {
             Some(DatabaseFlags::empty())
         } else {
             None
         };
-        let db = env.create_database(Some("schemas"), flags)?;
+        let db = env.create_database(Some(SCHEMAS_DATABASE_NAME), flags)?;
 
         // Collect existing schemas.
         let txn = env.begin_ro_txn()?;
@@ -101,6 +114,19 @@ impl SchemaDatabase {
     pub fn get_all_schemas(&self) -> &[(Schema, Vec<IndexDefinition>)] {
         &self.schemas
     }
+
+    /// Returns all schemas with their names, in insertion order.
+    pub fn get_all_schemas_with_names(&self) -> Vec<(&str, &Schema, &[IndexDefinition])> {
+        let mut names = self.schema_name_to_index.iter().collect::<Vec<_>>();
+        names.sort_by_key(|(_, index)| **index);
+        names
+            .into_iter()
+            .map(|(name, index)| {
+                let (schema, indexes) = &self.schemas[*index];
+                (name.as_str(), schema, indexes.as_slice())
+            })
+            .collect()
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -187,4 +213,16 @@ mod tests {
             vec![(schema_name, schema, secondary_indexes)]
         );
     }
+
+    #[test]
+    fn rejects_legacy_schema_database() {
+        let mut env = init_env(&CacheOptions::default()).unwrap().0;
+        env.create_database(Some("schemas"), Some(DatabaseFlags::empty()))
+            .unwrap();
+
+        assert!(matches!(
+            SchemaDatabase::new(&mut env, true),
+            Err(CacheError::IncompatibleCacheFormat)
+        ));
+    }
 }
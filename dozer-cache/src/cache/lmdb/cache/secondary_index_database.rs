@@ -1,5 +1,3 @@
-use std::cmp::Ordering;
-
 use dozer_storage::{
     errors::StorageError,
     lmdb::{Database, DatabaseFlags, RoCursor, RwTransaction, Transaction, WriteFlags},
@@ -7,15 +5,20 @@ use dozer_storage::{
 };
 use dozer_types::types::{IndexDefinition, SchemaIdentifier};
 
-use super::helper::lmdb_cmp;
 use crate::{
-    cache::lmdb::comparator,
+    cache::{lmdb::comparator, query::FullTextStats},
     errors::{CacheError, QueryError},
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct SecondaryIndexDatabase(Database);
 
+/// Full text index stats are stored under keys starting with `\0`, which no analyzed token starts with.
+const FULL_TEXT_DOCUMENT_COUNT_KEY: &[u8] = b"\0documents";
+const FULL_TEXT_TOKEN_COUNT_KEY: &[u8] = b"\0tokens";
+/// Followed by a token key, counts the documents indexed under that key.
+const FULL_TEXT_DOCUMENT_FREQUENCY_PREFIX: &[u8] = b"\0frequency\0";
+
 impl SecondaryIndexDatabase {
     pub fn open(
         env: &mut LmdbEnvironmentManager,
@@ -90,6 +93,68 @@ impl SecondaryIndexDatabase {
             .map_err(|e| CacheError::Query(QueryError::DeleteValue(e)))
     }
 
+    /// Returns the number of documents and tokens in a full text index.
+    pub fn full_text_stats<T: Transaction>(&self, txn: &T) -> Result<FullTextStats, CacheError> {
+        Ok(FullTextStats {
+            num_documents: self.get_counter(txn, FULL_TEXT_DOCUMENT_COUNT_KEY)?,
+            num_tokens: self.get_counter(txn, FULL_TEXT_TOKEN_COUNT_KEY)?,
+        })
+    }
+
+    /// Returns the number of documents indexed under `key` in a full text index.
+    pub fn full_text_document_frequency<T: Transaction>(
+        &self,
+        txn: &T,
+        key: &[u8],
+    ) -> Result<u64, CacheError> {
+        self.get_counter(txn, &document_frequency_key(key))
+    }
+
+    /// Adds a document with `num_tokens` tokens, indexed under `keys`, to the full text index stats, or removes it if `sign` is negative.
+    pub fn update_full_text_stats(
+        &self,
+        txn: &mut RwTransaction,
+        sign: i64,
+        num_tokens: u64,
+        keys: &[Vec<u8>],
+    ) -> Result<(), CacheError> {
+        for key in keys {
+            self.add_to_counter(txn, &document_frequency_key(key), sign)?;
+        }
+        self.add_to_counter(txn, FULL_TEXT_DOCUMENT_COUNT_KEY, sign)?;
+        self.add_to_counter(txn, FULL_TEXT_TOKEN_COUNT_KEY, sign * num_tokens as i64)
+    }
+
+    fn get_counter<T: Transaction>(&self, txn: &T, key: &[u8]) -> Result<u64, CacheError> {
+        match txn.get(self.0, &key) {
+            Ok(count) => Ok(u64::from_be_bytes(
+                count
+                    .try_into()
+                    .expect("All values must be u64 in this database"),
+            )),
+            Err(dozer_storage::lmdb::Error::NotFound) => Ok(0),
+            Err(e) => Err(CacheError::Query(QueryError::GetValue(e))),
+        }
+    }
+
+    /// Counters are stored as the only value of their key, and removed when they reach 0.
+    fn add_to_counter(
+        &self,
+        txn: &mut RwTransaction,
+        key: &[u8],
+        delta: i64,
+    ) -> Result<(), CacheError> {
+        let count = self.get_counter(&*txn, key)?;
+        if count > 0 {
+            self.delete(txn, key, count.to_be_bytes())?;
+        }
+        let count = (count as i64 + delta).max(0) as u64;
+        if count > 0 {
+            self.insert(txn, key, count.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
     pub fn open_ro_cursor<'txn, T: Transaction>(
         &self,
         txn: &'txn T,
@@ -97,10 +162,10 @@ impl SecondaryIndexDatabase {
         txn.open_ro_cursor(self.0)
             .map_err(|e| CacheError::Internal(Box::new(e)))
     }
+}
 
-    pub fn cmp<T: Transaction>(&self, txn: &T, a: &[u8], b: &[u8]) -> Ordering {
-        lmdb_cmp(txn, self.0, a, b)
-    }
+fn document_frequency_key(key: &[u8]) -> Vec<u8> {
+    [FULL_TEXT_DOCUMENT_FREQUENCY_PREFIX, key].concat()
 }
 
 #[cfg(test)]
@@ -0,0 +1,147 @@
+use std::io::{Read, Write};
+
+use dozer_storage::lmdb::Cursor;
+use dozer_types::bincode;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+use dozer_types::serde::{de::DeserializeOwned, Deserialize, Serialize};
+use dozer_types::types::{IndexDefinition, Record, Schema};
+
+use super::super::indexer::Indexer;
+use super::{LmdbCache, LmdbRoCache, LmdbRwCache};
+use crate::errors::{CacheError, QueryError};
+
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// The beginning of a snapshot.
+///
+/// It's followed by `num_ids` id mappings and `num_records` records, as they're stored in the cache.
+/// Secondary indexes are not part of the snapshot and are rebuilt on restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct SnapshotHeader {
+    format_version: u32,
+    /// The name the cache was exported with, usually the endpoint name.
+    pub name: String,
+    pub schemas: Vec<(String, Schema, Vec<IndexDefinition>)>,
+    checkpoint: Vec<(Vec<u8>, [u8; 16])>,
+    num_ids: u64,
+    num_records: u64,
+}
+
+impl SnapshotHeader {
+    pub fn read(reader: impl Read) -> Result<Self, CacheError> {
+        let header: Self = read(reader)?;
+        if header.format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(CacheError::InvalidSnapshot(format!(
+                "unsupported format version {}",
+                header.format_version
+            )));
+        }
+        Ok(header)
+    }
+
+    fn checkpoint(&self) -> SourceStates {
+        self.checkpoint
+            .iter()
+            .map(|(node_handle, op_identifier)| {
+                (
+                    NodeHandle::from_bytes(node_handle),
+                    OpIdentifier::from_bytes(*op_identifier),
+                )
+            })
+            .collect()
+    }
+}
+
+impl LmdbRoCache {
+    /// Writes the schemas, records and checkpoint of the cache to `writer`, reading them in one transaction.
+    pub fn export_snapshot(&self, name: &str, mut writer: impl Write) -> Result<(), CacheError> {
+        let txn = self.env.begin_ro_txn()?;
+
+        let header = SnapshotHeader {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            name: name.to_string(),
+            schemas: self
+                .common
+                .schema_db
+                .get_all_schemas_with_names()
+                .into_iter()
+                .map(|(name, schema, indexes)| (name.to_string(), schema.clone(), indexes.to_vec()))
+                .collect(),
+            checkpoint: self
+                .checkpoint_db
+                .read(&txn)?
+                .into_iter()
+                .map(|(node_handle, op_identifier)| {
+                    (node_handle.to_bytes(), op_identifier.to_bytes())
+                })
+                .collect(),
+            num_ids: self.common.id.count(&txn)? as u64,
+            num_records: self.common.db.count(&txn)? as u64,
+        };
+        write(&mut writer, &header)?;
+
+        let mut cursor = self.common.id.open_ro_cursor(&txn)?;
+        for item in cursor.iter_start() {
+            let (key, id) = item.map_err(QueryError::GetValue)?;
+            write(&mut writer, &(key, id))?;
+        }
+        let mut cursor = self.common.db.open_ro_cursor(&txn)?;
+        for item in cursor.iter_start() {
+            let (id, record) = item.map_err(QueryError::GetValue)?;
+            write(&mut writer, &(id, record))?;
+        }
+        Ok(())
+    }
+}
+
+impl LmdbRwCache {
+    /// Inserts the id mappings and records following `header` in `reader`, builds their secondary indexes,
+    /// and commits them with the snapshot's checkpoint.
+    ///
+    /// The cache must be empty and created with the snapshot's schemas.
+    pub fn restore_snapshot(
+        &self,
+        header: &SnapshotHeader,
+        mut reader: impl Read,
+    ) -> Result<(), CacheError> {
+        let mut txn = self.txn.write();
+
+        for _ in 0..header.num_ids {
+            let (key, id): (Vec<u8>, Vec<u8>) = read(&mut reader)?;
+            self.common.id.insert(txn.txn_mut(), &key, to_id(id)?)?;
+        }
+
+        let indexer = Indexer {
+            secondary_indexes: &self.common.secondary_indexes,
+        };
+        for _ in 0..header.num_records {
+            let (id, record): (Vec<u8>, Vec<u8>) = read(&mut reader)?;
+            let id = to_id(id)?;
+            let record: Record =
+                bincode::deserialize(&record).map_err(CacheError::map_deserialization_error)?;
+            let (schema, secondary_indexes) = self.get_schema_and_indexes_from_record(&record)?;
+            self.common.db.insert(txn.txn_mut(), id, &record)?;
+            indexer.build_indexes(txn.txn_mut(), &record, schema, secondary_indexes, id)?;
+        }
+
+        self.checkpoint_db
+            .write(txn.txn_mut(), &header.checkpoint())?;
+        txn.commit_and_renew()?;
+        Ok(())
+    }
+}
+
+fn write<T: Serialize>(writer: impl Write, value: &T) -> Result<(), CacheError> {
+    bincode::serialize_into(writer, value).map_err(CacheError::map_serialization_error)
+}
+
+fn read<T: DeserializeOwned>(reader: impl Read) -> Result<T, CacheError> {
+    bincode::deserialize_from(reader).map_err(CacheError::map_deserialization_error)
+}
+
+fn to_id(bytes: Vec<u8>) -> Result<[u8; 8], CacheError> {
+    bytes
+        .try_into()
+        .map_err(|_| CacheError::InvalidSnapshot("ids must be 8 bytes".to_string()))
+}
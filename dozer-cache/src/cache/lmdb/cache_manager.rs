@@ -1,3 +1,4 @@
+use std::io::{Read, Write};
 use std::path::PathBuf;
 
 use dozer_storage::{
@@ -13,7 +14,9 @@ use crate::{
     errors::CacheError,
 };
 
-use super::cache::{CacheCommonOptions, CacheWriteOptions, LmdbRoCache, LmdbRwCache};
+use super::cache::{
+    CacheCommonOptions, CacheWriteOptions, LmdbRoCache, LmdbRwCache, SnapshotHeader,
+};
 
 #[derive(Debug, Clone)]
 pub struct CacheManagerOptions {
@@ -25,6 +28,9 @@ pub struct CacheManagerOptions {
     /// The chunk size when calculating intersection of index queries.
     pub intersection_chunk_size: usize,
 
+    /// The max number of records a query can scan when no index fully answers it.
+    pub max_scanned_rows: usize,
+
     // Total size allocated for data in a memory mapped file.
     // This size is allocated at initialization.
     pub max_size: usize,
@@ -41,6 +47,7 @@ impl Default for CacheManagerOptions {
             max_readers: cache_common_options.max_readers,
             max_db_size: cache_common_options.max_db_size,
             intersection_chunk_size: cache_common_options.intersection_chunk_size,
+            max_scanned_rows: cache_common_options.max_scanned_rows,
             max_size: cache_write_options.max_size,
             path: None,
         }
@@ -86,6 +93,35 @@ impl LmdbCacheManager {
             _temp_dir: temp_dir,
         })
     }
+
+    /// Writes a snapshot of cache `name`, or the cache that `name` is an alias of, to `writer`.
+    ///
+    /// The snapshot is read in one transaction, so its records are consistent with its checkpoint.
+    pub fn export_snapshot(&self, name: &str, writer: impl Write) -> Result<(), CacheError> {
+        let mut txn = self.txn.write();
+        let real_name = self.resolve_alias(name, &txn)?.unwrap_or(name).to_string();
+        txn.commit_and_renew()?;
+        drop(txn);
+
+        if !LmdbEnvironmentManager::exists(&self.base_path, &real_name) {
+            return Err(CacheError::CacheNotFound(name.to_string()));
+        }
+        let cache = LmdbRoCache::new(self.cache_common_options(real_name))?;
+        cache.export_snapshot(name, writer)
+    }
+
+    /// Creates a cache from a snapshot written by `export_snapshot`, and points the name it was exported with to the new cache.
+    pub fn restore_snapshot(&self, mut reader: impl Read) -> Result<Box<dyn RwCache>, CacheError> {
+        let header = SnapshotHeader::read(&mut reader)?;
+        let cache = LmdbRwCache::create(
+            header.schemas.clone(),
+            self.cache_common_options(self.generate_unique_name()),
+            self.cache_write_options(),
+        )?;
+        cache.restore_snapshot(&header, reader)?;
+        self.create_alias(cache.name(), &header.name)?;
+        Ok(Box::new(cache))
+    }
 }
 
 impl CacheManager for LmdbCacheManager {
@@ -149,6 +185,7 @@ impl LmdbCacheManager {
             max_db_size: self.options.max_db_size,
             max_readers: self.options.max_readers,
             intersection_chunk_size: self.options.intersection_chunk_size,
+            max_scanned_rows: self.options.max_scanned_rows,
             path: Some((self.base_path.clone(), name)),
         }
     }
@@ -178,6 +215,12 @@ impl LmdbCacheManager {
 
 #[cfg(test)]
 mod tests {
+    use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+    use dozer_types::types::{Field, Record};
+
+    use crate::cache::expression::{FilterExpression, Operator};
+    use crate::cache::{index, test_utils};
+
     use super::*;
 
     #[test]
@@ -258,4 +301,88 @@ mod tests {
             real_name
         );
     }
+
+    #[test]
+    fn test_export_and_restore_snapshot() {
+        let (schema, secondary_indexes) = test_utils::schema_1();
+        let cache_manager = LmdbCacheManager::new(Default::default()).unwrap();
+        let cache = cache_manager
+            .create_cache(vec![(
+                "films".to_string(),
+                schema.clone(),
+                secondary_indexes,
+            )])
+            .unwrap();
+        cache_manager.create_alias(cache.name(), "films").unwrap();
+        for (a, b) in [(1, "a"), (2, "b"), (3, "c")] {
+            let mut record = Record::new(
+                schema.identifier,
+                vec![
+                    Field::Int(a),
+                    Field::String(b.to_string()),
+                    Field::Int(a * 10),
+                ],
+                None,
+            );
+            cache.insert(&mut record).unwrap();
+        }
+        let key = index::get_primary_key(&schema.primary_index, &[Field::Int(2)]);
+        let mut record = cache.get(&key).unwrap().record;
+        record.values[1] = Field::String("updated".to_string());
+        cache.update(&key, &mut record).unwrap();
+        cache
+            .delete(&index::get_primary_key(
+                &schema.primary_index,
+                &[Field::Int(3)],
+            ))
+            .unwrap();
+        let mut checkpoint = SourceStates::new();
+        checkpoint.insert(
+            NodeHandle::new(Some(1), "films".to_string()),
+            OpIdentifier::new(42, 3),
+        );
+        cache.commit(&checkpoint).unwrap();
+
+        let mut snapshot = vec![];
+        cache_manager
+            .export_snapshot("films", &mut snapshot)
+            .unwrap();
+        assert!(matches!(
+            cache_manager.export_snapshot("missing", &mut vec![]),
+            Err(CacheError::CacheNotFound(_))
+        ));
+
+        let restored_manager = LmdbCacheManager::new(Default::default()).unwrap();
+        let restored = restored_manager
+            .restore_snapshot(snapshot.as_slice())
+            .unwrap();
+        assert_eq!(restored.get_checkpoint().unwrap(), checkpoint);
+
+        let served = restored_manager.open_ro_cache("films").unwrap().unwrap();
+        assert_eq!(served.name(), restored.name());
+        let query = Default::default();
+        assert_eq!(
+            served.query("films", &query).unwrap().1,
+            cache.query("films", &query).unwrap().1
+        );
+        assert_eq!(served.get(&key).unwrap(), cache.get(&key).unwrap());
+
+        // Secondary indexes are rebuilt, and new records don't reuse ids.
+        let query = test_utils::query_from_filter(FilterExpression::Simple(
+            "c".to_string(),
+            Operator::GT,
+            5.into(),
+        ));
+        assert_eq!(served.count("films", &query).unwrap(), 2);
+        let mut record = Record::new(
+            schema.identifier,
+            vec![
+                Field::Int(4),
+                Field::String("d".to_string()),
+                Field::Int(40),
+            ],
+            None,
+        );
+        assert_eq!(restored.insert(&mut record).unwrap(), 3);
+    }
 }
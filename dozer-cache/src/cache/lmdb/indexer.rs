@@ -1,10 +1,8 @@
 use crate::errors::{CacheError, IndexError};
 use dozer_storage::lmdb::RwTransaction;
-use dozer_types::types::{Field, IndexDefinition, Record, Schema};
-use itertools::Itertools;
-use unicode_segmentation::UnicodeSegmentation;
+use dozer_types::types::{IndexDefinition, Record, Schema};
 
-use crate::cache::index::{self, get_full_text_secondary_index};
+use crate::cache::index::get_secondary_index_keys;
 
 use super::cache::SecondaryIndexDatabases;
 
@@ -31,18 +29,12 @@ impl<'a> Indexer<'a> {
                 .get(&(schema_id, idx))
                 .ok_or(CacheError::SecondaryIndexDatabaseNotFound)?;
 
-            match index {
-                IndexDefinition::SortedInverted(fields) => {
-                    let secondary_key = Self::_build_index_sorted_inverted(fields, &record.values);
-                    db.insert(txn, &secondary_key, id)?;
-                }
-                IndexDefinition::FullText(field_index) => {
-                    for secondary_key in
-                        Self::_build_indices_full_text(*field_index, &record.values)?
-                    {
-                        db.insert(txn, &secondary_key, id)?;
-                    }
-                }
+            let (secondary_keys, num_tokens) = get_secondary_index_keys(index, &record.values)?;
+            for secondary_key in &secondary_keys {
+                db.insert(txn, secondary_key, id)?;
+            }
+            if let IndexDefinition::FullText(..) = index {
+                db.update_full_text_stats(txn, 1, num_tokens, &secondary_keys)?;
             }
         }
         Ok(())
@@ -63,59 +55,17 @@ impl<'a> Indexer<'a> {
                 .get(&(schema_id, idx))
                 .ok_or(CacheError::SecondaryIndexDatabaseNotFound)?;
 
-            match index {
-                IndexDefinition::SortedInverted(fields) => {
-                    let secondary_key = Self::_build_index_sorted_inverted(fields, &record.values);
-                    db.delete(txn, &secondary_key, id)?;
-                }
-                IndexDefinition::FullText(field_index) => {
-                    for secondary_key in
-                        Self::_build_indices_full_text(*field_index, &record.values)?
-                    {
-                        db.delete(txn, &secondary_key, id)?;
-                    }
-                }
+            let (secondary_keys, num_tokens) = get_secondary_index_keys(index, &record.values)?;
+            for secondary_key in &secondary_keys {
+                db.delete(txn, secondary_key, id)?;
+            }
+            if let IndexDefinition::FullText(..) = index {
+                db.update_full_text_stats(txn, -1, num_tokens, &secondary_keys)?;
             }
         }
 
         Ok(())
     }
-
-    fn _build_index_sorted_inverted(fields: &[usize], values: &[Field]) -> Vec<u8> {
-        let values = fields
-            .iter()
-            .copied()
-            .filter_map(|index| (values.get(index)))
-            .collect::<Vec<_>>();
-        // `values.len() == 1` criteria must be kept the same with `comparator.rs`.
-        index::get_secondary_index(&values, values.len() == 1)
-    }
-
-    fn _build_indices_full_text(
-        field_index: usize,
-        values: &[Field],
-    ) -> Result<Vec<Vec<u8>>, CacheError> {
-        let Some(field) = values.get(field_index) else {
-            return Err(CacheError::Index(IndexError::FieldIndexOutOfRange));
-        };
-
-        let string = match field {
-            Field::String(string) => string,
-            Field::Text(string) => string,
-            Field::Null => "",
-            _ => {
-                return Err(CacheError::Index(IndexError::FieldNotCompatibleIndex(
-                    field_index,
-                )))
-            }
-        };
-
-        Ok(string
-            .unicode_words()
-            .map(get_full_text_secondary_index)
-            .unique()
-            .collect())
-    }
 }
 
 #[cfg(test)]
@@ -127,6 +77,7 @@ mod tests {
         },
         test_utils, RwCache,
     };
+    use dozer_types::types::Field;
 
     use super::*;
 
@@ -177,25 +128,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_build_indices_full_text() {
-        let field_index = 0;
-        assert_eq!(
-            Indexer::_build_indices_full_text(
-                field_index,
-                &[Field::String("today is a good day".into())]
-            )
-            .unwrap(),
-            vec![
-                get_full_text_secondary_index("today"),
-                get_full_text_secondary_index("is"),
-                get_full_text_secondary_index("a"),
-                get_full_text_secondary_index("good"),
-                get_full_text_secondary_index("day"),
-            ]
-        );
-    }
-
     #[test]
     fn test_full_text_secondary_index_with_duplicated_words() {
         let schema_name = "sample";
@@ -230,4 +162,40 @@ mod tests {
             "Must delete every index"
         );
     }
+
+    #[test]
+    fn test_full_text_document_frequency() {
+        let schema_name = "sample";
+        let (cache, schema, _) = create_cache(schema_name, test_utils::schema_full_text);
+
+        lmdb_utils::insert_full_text(
+            &cache,
+            &schema,
+            (
+                Some("a".to_string()),
+                Some("regular test regular".to_string()),
+            ),
+        );
+        lmdb_utils::insert_full_text(
+            &cache,
+            &schema,
+            (Some("b".to_string()), Some("test".to_string())),
+        );
+
+        let document_frequency = |token: &[u8]| {
+            let (txn, secondary_indexes) = cache.get_txn_and_secondary_indexes();
+            let txn = txn.read();
+            secondary_indexes[&(schema.identifier.unwrap(), 1)]
+                .full_text_document_frequency(txn.txn(), token)
+                .unwrap()
+        };
+        assert_eq!(document_frequency(b"regular"), 1);
+        assert_eq!(document_frequency(b"test"), 2);
+
+        cache
+            .delete(&Field::String("a".to_string()).encode())
+            .unwrap();
+        assert_eq!(document_frequency(b"regular"), 0);
+        assert_eq!(document_frequency(b"test"), 1);
+    }
 }
@@ -1,27 +1,31 @@
 use crate::cache::{
     expression::{self, FilterExpression, QueryExpression, Skip},
     index,
-    lmdb::cache::LmdbRwCache,
     test_utils::{self, query_from_filter},
-    RoCache, RwCache,
+    RwCache,
 };
 use dozer_types::{
     serde_json::Value,
     types::{Field, Record, Schema},
 };
 
-use super::utils::create_cache;
+use super::utils::create_caches;
 
-fn _setup() -> (LmdbRwCache, Schema, &'static str) {
+/// Returns a cache of each backend.
+fn _setup() -> Vec<(Box<dyn RwCache>, Schema, &'static str)> {
     let schema_name = "doc";
-    let (cache, schema, _) = create_cache(schema_name, test_utils::schema_0);
-    (cache, schema, schema_name)
+    create_caches(schema_name, test_utils::schema_0)
+        .into_iter()
+        .map(|(cache, schema, _)| (cache, schema, schema_name))
+        .collect()
 }
 
-fn _setup_empty_primary_index() -> (LmdbRwCache, Schema, &'static str) {
+fn _setup_empty_primary_index() -> Vec<(Box<dyn RwCache>, Schema, &'static str)> {
     let schema_name = "doc";
-    let (cache, schema, _) = create_cache(schema_name, test_utils::schema_empty_primary_index);
-    (cache, schema, schema_name)
+    create_caches(schema_name, test_utils::schema_empty_primary_index)
+        .into_iter()
+        .map(|(cache, schema, _)| (cache, schema, schema_name))
+        .collect()
 }
 
 fn query_and_test(
@@ -36,55 +40,58 @@ fn query_and_test(
 
 #[test]
 fn get_schema() {
-    let (cache, _, schema_name) = _setup();
-    let schema = &cache.get_schema_and_indexes_by_name(schema_name).unwrap().0;
+    for (cache, _, schema_name) in _setup() {
+        let schema = &cache.get_schema_and_indexes_by_name(schema_name).unwrap().0;
 
-    let get_schema = cache.get_schema(schema.identifier.unwrap()).unwrap();
-    assert_eq!(get_schema, schema, "must be equal");
+        let get_schema = cache.get_schema(schema.identifier.unwrap()).unwrap();
+        assert_eq!(get_schema, schema, "must be equal");
+    }
 }
 
 #[test]
 fn insert_get_and_delete_record() {
-    let val = "bar".to_string();
-    let (cache, schema, _) = _setup();
-    let mut record = Record::new(schema.identifier, vec![Field::String(val.clone())], None);
-    cache.insert(&mut record).unwrap();
-    let version = record.version.unwrap();
+    for (cache, schema, _) in _setup() {
+        let val = "bar".to_string();
+        let mut record = Record::new(schema.identifier, vec![Field::String(val.clone())], None);
+        cache.insert(&mut record).unwrap();
+        let version = record.version.unwrap();
 
-    let key = index::get_primary_key(&[0], &[Field::String(val)]);
+        let key = index::get_primary_key(&[0], &[Field::String(val)]);
 
-    let get_record = cache.get(&key).unwrap().record;
-    assert_eq!(get_record, record, "must be equal");
+        let get_record = cache.get(&key).unwrap().record;
+        assert_eq!(get_record, record, "must be equal");
 
-    assert_eq!(cache.delete(&key).unwrap(), version);
+        assert_eq!(cache.delete(&key).unwrap(), version);
 
-    cache.get(&key).expect_err("Must not find a record");
+        cache.get(&key).expect_err("Must not find a record");
+    }
 }
 
 #[test]
 fn insert_and_update_record() {
-    let (cache, schema, _) = _setup();
-    let mut foo = Record::new(
-        schema.identifier,
-        vec![Field::String("foo".to_string())],
-        None,
-    );
-    let mut bar = Record::new(
-        schema.identifier,
-        vec![Field::String("bar".to_string())],
-        None,
-    );
-    cache.insert(&mut foo).unwrap();
-    let old_version = foo.version.unwrap();
-    cache.insert(&mut bar).unwrap();
-
-    let key = index::get_primary_key(&schema.primary_index, &foo.values);
-
-    assert_eq!(cache.update(&key, &mut foo).unwrap(), old_version);
-    assert_eq!(foo.version.unwrap(), old_version + 1);
+    for (cache, schema, _) in _setup() {
+        let mut foo = Record::new(
+            schema.identifier,
+            vec![Field::String("foo".to_string())],
+            None,
+        );
+        let mut bar = Record::new(
+            schema.identifier,
+            vec![Field::String("bar".to_string())],
+            None,
+        );
+        cache.insert(&mut foo).unwrap();
+        let old_version = foo.version.unwrap();
+        cache.insert(&mut bar).unwrap();
+
+        let key = index::get_primary_key(&schema.primary_index, &foo.values);
+
+        assert_eq!(cache.update(&key, &mut foo).unwrap(), old_version);
+        assert_eq!(foo.version.unwrap(), old_version + 1);
+    }
 }
 
-fn insert_and_query_record_impl(cache: LmdbRwCache, schema: Schema, schema_name: &str) {
+fn insert_and_query_record_impl(cache: &dyn RwCache, schema: Schema, schema_name: &str) {
     let val = "bar".to_string();
     let mut record = Record::new(schema.identifier, vec![Field::String(val)], None);
 
@@ -97,11 +104,11 @@ fn insert_and_query_record_impl(cache: LmdbRwCache, schema: Schema, schema_name:
         Value::from("bar".to_string()),
     ));
 
-    query_and_test(&cache, &record, schema_name, &exp);
+    query_and_test(cache, &record, schema_name, &exp);
 
     // Query without an expression
     query_and_test(
-        &cache,
+        cache,
         &record,
         schema_name,
         &QueryExpression::new(None, vec![], Some(10), Skip::Skip(0)),
@@ -110,8 +117,7 @@ fn insert_and_query_record_impl(cache: LmdbRwCache, schema: Schema, schema_name:
 
 #[test]
 fn insert_and_query_record() {
-    let (cache, schema, schema_name) = _setup();
-    insert_and_query_record_impl(cache, schema, schema_name);
-    let (cache, schema, schema_name) = _setup_empty_primary_index();
-    insert_and_query_record_impl(cache, schema, schema_name);
+    for (cache, schema, schema_name) in _setup().into_iter().chain(_setup_empty_primary_index()) {
+        insert_and_query_record_impl(&*cache, schema, schema_name);
+    }
 }
@@ -0,0 +1,70 @@
+use dozer_types::types::{Field, Record};
+
+use crate::cache::{test_utils, CacheManager, LmdbCacheManager, MemoryCacheManager};
+
+/// Returns a cache manager of each backend.
+fn cache_managers() -> Vec<Box<dyn CacheManager>> {
+    vec![
+        Box::new(LmdbCacheManager::new(Default::default()).unwrap()),
+        Box::new(MemoryCacheManager::default()),
+    ]
+}
+
+#[test]
+fn aliases() {
+    let schema_name = "doc";
+    let (schema, secondary_indexes) = test_utils::schema_0();
+    for cache_manager in cache_managers() {
+        let create_cache = || {
+            cache_manager
+                .create_cache(vec![(
+                    schema_name.to_string(),
+                    schema.clone(),
+                    secondary_indexes.clone(),
+                )])
+                .unwrap()
+                .name()
+                .to_string()
+        };
+        let open_name = |name: &str| {
+            let rw_name = cache_manager
+                .open_rw_cache(name)
+                .unwrap()
+                .map(|cache| cache.name().to_string());
+            let ro_name = cache_manager
+                .open_ro_cache(name)
+                .unwrap()
+                .map(|cache| cache.name().to_string());
+            assert_eq!(rw_name, ro_name);
+            rw_name
+        };
+
+        let real_name = create_cache();
+        assert_eq!(open_name(&real_name), Some(real_name.clone()));
+        assert_eq!(open_name("alias"), None);
+
+        // Writes through the alias are read through the real name.
+        cache_manager.create_alias(&real_name, "alias").unwrap();
+        assert_eq!(open_name("alias"), Some(real_name.clone()));
+        let mut record = Record::new(
+            schema.identifier,
+            vec![Field::String("foo".to_string())],
+            None,
+        );
+        let rw_cache = cache_manager.open_rw_cache("alias").unwrap().unwrap();
+        rw_cache.insert(&mut record).unwrap();
+        rw_cache.commit(&Default::default()).unwrap();
+        let ro_cache = cache_manager.open_ro_cache(&real_name).unwrap().unwrap();
+        assert_eq!(ro_cache.count(schema_name, &Default::default()).unwrap(), 1);
+
+        // Creating an alias again points it to the new cache.
+        let real_name2 = create_cache();
+        cache_manager.create_alias(&real_name2, "alias").unwrap();
+        assert_eq!(open_name("alias"), Some(real_name2.clone()));
+        assert_eq!(open_name(&real_name), Some(real_name.clone()));
+
+        // If name is both alias and real name, alias shadows real name.
+        cache_manager.create_alias(&real_name, &real_name2).unwrap();
+        assert_eq!(open_name(&real_name2), Some(real_name.clone()));
+    }
+}
@@ -1,3 +1,4 @@
 mod basic;
+mod cache_manager;
 mod read_write;
 pub mod utils;
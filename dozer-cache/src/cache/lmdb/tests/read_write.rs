@@ -1,31 +1,43 @@
 use crate::cache::expression::{FilterExpression, Operator, QueryExpression};
-use crate::cache::lmdb::cache::{CacheCommonOptions, CacheWriteOptions, LmdbRoCache, LmdbRwCache};
-use crate::cache::{lmdb::tests::utils as lmdb_utils, test_utils, RoCache, RwCache};
+use crate::cache::{
+    lmdb::tests::utils as lmdb_utils, test_utils, CacheManager, CacheManagerOptions,
+    LmdbCacheManager, MemoryCacheManager,
+};
 use dozer_types::serde_json::Value;
 use dozer_types::types::Field;
 use tempdir::TempDir;
 #[test]
 fn read_and_write() {
     let path = TempDir::new("dozer").unwrap();
-    let path = (path.path().to_path_buf(), "cache".to_string());
+    let options = CacheManagerOptions {
+        max_readers: 1,
+        max_db_size: 100,
+        intersection_chunk_size: 1,
+        max_scanned_rows: 100,
+        max_size: 1024 * 1024,
+        path: Some(path.path().to_path_buf()),
+    };
+    let cache_managers: Vec<Box<dyn CacheManager>> = vec![
+        Box::new(LmdbCacheManager::new(options.clone()).unwrap()),
+        Box::new(MemoryCacheManager::new(options)),
+    ];
+    for cache_manager in cache_managers {
+        read_and_write_impl(&*cache_manager);
+    }
+}
 
-    // write and read from cache from two different threads.
+fn read_and_write_impl(cache_manager: &dyn CacheManager) {
+    // write and read from cache from two different handles.
 
     let schema_name = "sample";
     let (schema, secondary_indexes) = test_utils::schema_1();
-    let cache_writer = LmdbRwCache::create(
-        [(schema_name.to_string(), schema.clone(), secondary_indexes)],
-        CacheCommonOptions {
-            max_readers: 1,
-            max_db_size: 100,
-            path: Some(path.clone()),
-            intersection_chunk_size: 1,
-        },
-        CacheWriteOptions {
-            max_size: 1024 * 1024,
-        },
-    )
-    .unwrap();
+    let cache_writer = cache_manager
+        .create_cache(vec![(
+            schema_name.to_string(),
+            schema.clone(),
+            secondary_indexes,
+        )])
+        .unwrap();
 
     let items = vec![
         (1, Some("a".to_string()), Some(521)),
@@ -35,15 +47,14 @@ fn read_and_write() {
     ];
 
     for val in items.clone() {
-        lmdb_utils::insert_rec_1(&cache_writer, &schema, val.clone());
+        lmdb_utils::insert_rec_1(&*cache_writer, &schema, val.clone());
     }
     cache_writer.commit(&Default::default()).unwrap();
 
-    let read_options = CacheCommonOptions {
-        path: Some(path),
-        ..Default::default()
-    };
-    let cache_reader = LmdbRoCache::new(read_options).unwrap();
+    let cache_reader = cache_manager
+        .open_ro_cache(cache_writer.name())
+        .unwrap()
+        .unwrap();
     for (a, b, c) in items {
         let rec = cache_reader.get(&Field::Int(a).encode()).unwrap();
         let values = vec![
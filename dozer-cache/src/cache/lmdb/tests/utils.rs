@@ -1,7 +1,10 @@
 use dozer_storage::lmdb::Cursor;
 use dozer_types::types::{Field, IndexDefinition, Record, Schema};
 
-use crate::cache::{lmdb::cache::LmdbRwCache, RwCache};
+use crate::cache::{
+    lmdb::cache::{CacheCommonOptions, LmdbRwCache},
+    CacheManager, CacheManagerOptions, MemoryCacheManager, RwCache,
+};
 
 pub fn create_cache(
     schema_name: &str,
@@ -21,8 +24,45 @@ pub fn create_cache(
     (cache, schema, secondary_indexes)
 }
 
+/// Creates a cache with the schema in each cache backend, so the same test can run on all of them.
+pub fn create_caches(
+    schema_name: &str,
+    schema_gen: impl FnOnce() -> (Schema, Vec<IndexDefinition>),
+) -> Vec<(Box<dyn RwCache>, Schema, Vec<IndexDefinition>)> {
+    let (schema, secondary_indexes) = schema_gen();
+    create_caches_with_options(
+        vec![(
+            schema_name.to_string(),
+            schema.clone(),
+            secondary_indexes.clone(),
+        )],
+        Default::default(),
+    )
+    .into_iter()
+    .map(|cache| (cache, schema.clone(), secondary_indexes.clone()))
+    .collect()
+}
+
+/// Creates a cache with the schemas in each cache backend. Only `intersection_chunk_size` and
+/// `max_scanned_rows` of `options` apply to all backends.
+pub fn create_caches_with_options(
+    schemas: Vec<(String, Schema, Vec<IndexDefinition>)>,
+    options: CacheCommonOptions,
+) -> Vec<Box<dyn RwCache>> {
+    let memory_cache = MemoryCacheManager::new(CacheManagerOptions {
+        intersection_chunk_size: options.intersection_chunk_size,
+        max_scanned_rows: options.max_scanned_rows,
+        ..Default::default()
+    })
+    .create_cache(schemas.clone())
+    .unwrap();
+    let lmdb_cache = LmdbRwCache::create(schemas, options, Default::default()).unwrap();
+
+    vec![Box::new(lmdb_cache), memory_cache]
+}
+
 pub fn insert_rec_1(
-    cache: &LmdbRwCache,
+    cache: &dyn RwCache,
     schema: &Schema,
     (a, b, c): (i64, Option<String>, Option<i64>),
 ) {
@@ -39,7 +79,7 @@ pub fn insert_rec_1(
 }
 
 pub fn insert_full_text(
-    cache: &LmdbRwCache,
+    cache: &dyn RwCache,
     schema: &Schema,
     (a, b): (Option<String>, Option<String>),
 ) {
@@ -0,0 +1,479 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::Arc;
+
+use dozer_types::node::SourceStates;
+use dozer_types::parking_lot::RwLock;
+use dozer_types::types::{IndexDefinition, Record, Schema, SchemaIdentifier};
+
+use super::secondary_index::SecondaryIndex;
+use crate::cache::expression::{Cursor, QueryExpression, SortDirection};
+use crate::cache::index::get_primary_key;
+use crate::cache::query::{
+    FullTextStats, IndexEntries, KeyEndpoint, QueryHandler, QueryStorage, SecondaryIndexId,
+};
+use crate::cache::retention::RetentionJob;
+use crate::cache::{
+    OnExpired, Plan, RecordWithId, RetentionOptions, RetentionPolicy, RoCache, RwCache,
+};
+use crate::errors::{CacheError, IndexError};
+
+#[derive(Debug, Clone)]
+pub struct MemoryCacheOptions {
+    /// The chunk size when calculating intersection of index queries.
+    pub intersection_chunk_size: usize,
+
+    /// The max number of records a query can scan when no index fully answers it.
+    pub max_scanned_rows: usize,
+}
+
+/// The schemas of a cache, which don't change after it's created.
+#[derive(Debug, Default)]
+struct Schemas {
+    schemas: Vec<(Schema, Vec<IndexDefinition>)>,
+    schema_name_to_index: HashMap<String, usize>,
+    schema_id_to_index: HashMap<SchemaIdentifier, usize>,
+}
+
+impl Schemas {
+    fn new(schemas: Vec<(String, Schema, Vec<IndexDefinition>)>) -> Result<Self, CacheError> {
+        let mut result = Self::default();
+        for (index, (schema_name, schema, secondary_indexes)) in schemas.into_iter().enumerate() {
+            let identifier = schema.identifier.ok_or(CacheError::SchemaHasNoIdentifier)?;
+            if result
+                .schema_id_to_index
+                .insert(identifier, index)
+                .is_some()
+            {
+                return Err(CacheError::DuplicateSchemaIdentifier(identifier));
+            }
+            result.schema_name_to_index.insert(schema_name, index);
+            result.schemas.push((schema, secondary_indexes));
+        }
+        Ok(result)
+    }
+}
+
+/// The records, secondary indexes and checkpoint of a cache.
+#[derive(Debug, Default)]
+struct CacheData {
+    records: BTreeMap<u64, Record>,
+    /// Ids by primary key, or by big endian id bytes for records without primary key.
+    ///
+    /// As in the LMDB cache, ids are never removed, so the number of ids is the next id,
+    /// and a record inserted again after being deleted keeps its id.
+    ids: BTreeMap<Vec<u8>, u64>,
+    secondary_indexes: HashMap<SecondaryIndexId, SecondaryIndex>,
+    checkpoint: SourceStates,
+}
+
+impl CacheData {
+    fn new(schemas: &Schemas) -> Result<Self, CacheError> {
+        let mut data = Self::default();
+        for (schema, secondary_indexes) in &schemas.schemas {
+            let schema_id = schema.identifier.ok_or(CacheError::SchemaHasNoIdentifier)?;
+            for (index, index_definition) in secondary_indexes.iter().enumerate() {
+                data.secondary_indexes.insert(
+                    (schema_id, index),
+                    SecondaryIndex::new(index_definition.clone()),
+                );
+            }
+        }
+        Ok(data)
+    }
+
+    fn get_id(&self, key: &[u8]) -> Result<u64, CacheError> {
+        self.ids.get(key).copied().ok_or(CacheError::RecordNotFound)
+    }
+
+    fn get_record(&self, id: u64) -> Result<&Record, CacheError> {
+        self.records.get(&id).ok_or(CacheError::RecordNotFound)
+    }
+
+    fn insert(
+        &mut self,
+        record: &Record,
+        schema: &Schema,
+        secondary_indexes: &[IndexDefinition],
+    ) -> Result<u64, CacheError> {
+        let schema_id = schema.identifier.ok_or(CacheError::SchemaHasNoIdentifier)?;
+        if secondary_indexes.is_empty() {
+            return Err(CacheError::Index(IndexError::MissingSecondaryIndexes));
+        }
+
+        let next_id = self.ids.len() as u64;
+        let key = if schema.primary_index.is_empty() {
+            next_id.to_be_bytes().to_vec()
+        } else {
+            get_primary_key(&schema.primary_index, &record.values)
+        };
+        let id = *self.ids.entry(key).or_insert(next_id);
+        if self.records.contains_key(&id) {
+            return Err(CacheError::RecordExists);
+        }
+
+        for index in 0..secondary_indexes.len() {
+            self.secondary_index_mut((schema_id, index))?
+                .insert(record, id)?;
+        }
+        self.records.insert(id, record.clone());
+        Ok(id)
+    }
+
+    /// Deletes the record with id `id`, which must be of `schema`.
+    fn delete(
+        &mut self,
+        id: u64,
+        schema: &Schema,
+        secondary_indexes: &[IndexDefinition],
+    ) -> Result<Record, CacheError> {
+        let schema_id = schema.identifier.ok_or(CacheError::SchemaHasNoIdentifier)?;
+        let record = self.records.remove(&id).ok_or(CacheError::RecordNotFound)?;
+        for index in 0..secondary_indexes.len() {
+            self.secondary_index_mut((schema_id, index))?
+                .delete(&record, id)?;
+        }
+        Ok(record)
+    }
+
+    fn secondary_index(&self, index: SecondaryIndexId) -> Result<&SecondaryIndex, CacheError> {
+        self.secondary_indexes
+            .get(&index)
+            .ok_or(CacheError::SecondaryIndexDatabaseNotFound)
+    }
+
+    fn secondary_index_mut(
+        &mut self,
+        index: SecondaryIndexId,
+    ) -> Result<&mut SecondaryIndex, CacheError> {
+        self.secondary_indexes
+            .get_mut(&index)
+            .ok_or(CacheError::SecondaryIndexDatabaseNotFound)
+    }
+
+    /// Deletes at most `batch_size` expired records of `schema`, oldest first. Returns the deleted records.
+    ///
+    /// The schema must be the only one in the cache, and have no primary key.
+    fn delete_expired(
+        &mut self,
+        schema: &Schema,
+        secondary_indexes: &[IndexDefinition],
+        policy: &RetentionPolicy,
+        batch_size: usize,
+    ) -> Result<Vec<Record>, CacheError> {
+        let num_records = self.records.len() as u64;
+        let expiry = policy.expiry();
+        let mut deleted = vec![];
+        while deleted.len() < batch_size {
+            // Ids are generated in insertion order, so the first record is the oldest.
+            let Some((&id, record)) = self.records.first_key_value() else {
+                break;
+            };
+            if !expiry.is_expired(record, num_records - deleted.len() as u64) {
+                break;
+            }
+            deleted.push(self.delete(id, schema, secondary_indexes)?);
+        }
+        Ok(deleted)
+    }
+}
+
+/// The state of a cache, shared by the cache manager and every opened `MemoryCache` of it.
+#[derive(Debug, Clone)]
+pub struct SharedCache {
+    schemas: Arc<Schemas>,
+    data: Arc<RwLock<CacheData>>,
+}
+
+/// A cache that keeps its records and secondary indexes in memory.
+///
+/// All caches opened with the same name share their data. Writes are visible to readers right away,
+/// `commit` only records the checkpoint.
+#[derive(Debug)]
+pub struct MemoryCache {
+    name: String,
+    shared: SharedCache,
+    options: MemoryCacheOptions,
+    retention: Option<RetentionJob>,
+}
+
+impl MemoryCache {
+    pub fn create(
+        name: String,
+        schemas: Vec<(String, Schema, Vec<IndexDefinition>)>,
+        options: MemoryCacheOptions,
+    ) -> Result<Self, CacheError> {
+        let schemas = Schemas::new(schemas)?;
+        let data = CacheData::new(&schemas)?;
+        let shared = SharedCache {
+            schemas: Arc::new(schemas),
+            data: Arc::new(RwLock::new(data)),
+        };
+        Ok(Self::open(name, shared, options))
+    }
+
+    pub fn open(name: String, shared: SharedCache, options: MemoryCacheOptions) -> Self {
+        Self {
+            name,
+            shared,
+            options,
+            retention: None,
+        }
+    }
+
+    pub fn shared(&self) -> SharedCache {
+        self.shared.clone()
+    }
+
+    fn get_schema_and_indexes_from_record(
+        &self,
+        record: &Record,
+    ) -> Result<&(Schema, Vec<IndexDefinition>), CacheError> {
+        let schema_identifier = record.schema_id.ok_or(CacheError::SchemaHasNoIdentifier)?;
+        let schemas = &self.shared.schemas;
+        schemas
+            .schema_id_to_index
+            .get(&schema_identifier)
+            .map(|index| &schemas.schemas[*index])
+            .ok_or(CacheError::SchemaIdentifierNotFound(schema_identifier))
+    }
+
+    /// Runs `f` with a handler of `query` on schema `schema_name`, holding the read lock.
+    fn with_query_handler<T>(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+        f: impl FnOnce(QueryHandler<'_, MemoryQueryStorage<'_>>) -> Result<T, CacheError>,
+    ) -> Result<T, CacheError> {
+        let (schema, secondary_indexes) = self.get_schema_and_indexes_by_name(schema_name)?;
+        let data = self.shared.data.read();
+        let storage = MemoryQueryStorage {
+            data: &data,
+            options: &self.options,
+        };
+        f(QueryHandler::new(
+            &storage,
+            schema,
+            secondary_indexes,
+            query,
+        ))
+    }
+}
+
+impl RoCache for MemoryCache {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_schema(&self, schema_identifier: SchemaIdentifier) -> Result<&Schema, CacheError> {
+        let schemas = &self.shared.schemas;
+        schemas
+            .schema_id_to_index
+            .get(&schema_identifier)
+            .map(|index| &schemas.schemas[*index].0)
+            .ok_or(CacheError::SchemaIdentifierNotFound(schema_identifier))
+    }
+
+    fn get_schema_and_indexes_by_name(
+        &self,
+        name: &str,
+    ) -> Result<&(Schema, Vec<IndexDefinition>), CacheError> {
+        let schemas = &self.shared.schemas;
+        schemas
+            .schema_name_to_index
+            .get(name)
+            .map(|index| &schemas.schemas[*index])
+            .ok_or_else(|| CacheError::SchemaNotFound(name.to_string()))
+    }
+
+    fn get(&self, key: &[u8]) -> Result<RecordWithId, CacheError> {
+        let data = self.shared.data.read();
+        let id = data.get_id(key)?;
+        let record = data.get_record(id)?.clone();
+        Ok(RecordWithId::new(id, record))
+    }
+
+    fn count(&self, schema_name: &str, query: &QueryExpression) -> Result<usize, CacheError> {
+        self.with_query_handler(schema_name, query, |handler| handler.count())
+    }
+
+    fn query(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+    ) -> Result<(&Schema, Vec<RecordWithId>), CacheError> {
+        let records = self.with_query_handler(schema_name, query, |handler| handler.query())?;
+        let (schema, _) = self.get_schema_and_indexes_by_name(schema_name)?;
+        Ok((schema, records))
+    }
+
+    fn aggregate(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+    ) -> Result<(Schema, Vec<Record>), CacheError> {
+        let aggregation = query.aggregation.clone().unwrap_or_default();
+        self.with_query_handler(schema_name, query, |handler| {
+            handler.aggregate(&aggregation)
+        })
+    }
+
+    fn explain(&self, schema_name: &str, query: &QueryExpression) -> Result<Plan, CacheError> {
+        self.with_query_handler(schema_name, query, |handler| handler.plan())
+    }
+
+    fn next_cursor(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+        records: &[RecordWithId],
+    ) -> Result<Option<Cursor>, CacheError> {
+        self.with_query_handler(schema_name, query, |handler| handler.next_cursor(records))
+    }
+}
+
+impl RwCache for MemoryCache {
+    fn insert(&self, record: &mut Record) -> Result<u64, CacheError> {
+        let (schema, secondary_indexes) = self.get_schema_and_indexes_from_record(record)?;
+        record.version = Some(INITIAL_RECORD_VERSION);
+        self.shared
+            .data
+            .write()
+            .insert(record, schema, secondary_indexes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<u32, CacheError> {
+        let mut data = self.shared.data.write();
+        let id = data.get_id(key)?;
+        let (schema, secondary_indexes) =
+            self.get_schema_and_indexes_from_record(data.get_record(id)?)?;
+        let record = data.delete(id, schema, secondary_indexes)?;
+        Ok(record
+            .version
+            .expect("All records in cache should have a version"))
+    }
+
+    fn update(&self, key: &[u8], record: &mut Record) -> Result<u32, CacheError> {
+        let mut data = self.shared.data.write();
+        let id = data.get_id(key)?;
+        let (schema, secondary_indexes) =
+            self.get_schema_and_indexes_from_record(data.get_record(id)?)?;
+        let old_version = data
+            .delete(id, schema, secondary_indexes)?
+            .version
+            .expect("All records in cache should have a version");
+        record.version = Some(old_version + 1);
+        data.insert(record, schema, secondary_indexes)?;
+        Ok(old_version)
+    }
+
+    fn commit(&self, checkpoint: &SourceStates) -> Result<(), CacheError> {
+        self.shared.data.write().checkpoint = checkpoint.clone();
+        Ok(())
+    }
+
+    fn get_checkpoint(&self) -> Result<SourceStates, CacheError> {
+        Ok(self.shared.data.read().checkpoint.clone())
+    }
+
+    fn start_retention(
+        &mut self,
+        schema_name: &str,
+        policy: RetentionPolicy,
+        options: RetentionOptions,
+        on_expired: OnExpired,
+    ) -> Result<(), CacheError> {
+        let (schema, secondary_indexes) = self.get_schema_and_indexes_by_name(schema_name)?;
+        policy.validate(schema_name, schema, self.shared.schemas.schemas.len())?;
+
+        let schema = schema.clone();
+        let secondary_indexes = secondary_indexes.clone();
+        let data = self.shared.data.clone();
+        // Stop the previous job before starting a new one.
+        self.retention = None;
+        self.retention = Some(RetentionJob::start(
+            move |batch_size| {
+                data.write()
+                    .delete_expired(&schema, &secondary_indexes, &policy, batch_size)
+            },
+            options,
+            on_expired,
+        ));
+        Ok(())
+    }
+}
+
+const INITIAL_RECORD_VERSION: u32 = 1_u32;
+
+struct MemoryQueryStorage<'a> {
+    data: &'a CacheData,
+    options: &'a MemoryCacheOptions,
+}
+
+impl<'a> QueryStorage for MemoryQueryStorage<'a> {
+    fn intersection_chunk_size(&self) -> usize {
+        self.options.intersection_chunk_size
+    }
+
+    fn max_scanned_rows(&self) -> usize {
+        self.options.max_scanned_rows
+    }
+
+    fn count_records(&self) -> Result<usize, CacheError> {
+        Ok(self.data.records.len())
+    }
+
+    fn get_record(&self, id: u64) -> Result<Record, CacheError> {
+        self.data.get_record(id).cloned()
+    }
+
+    fn ids(&self, after: Option<&[u8]>) -> Result<Box<dyn Iterator<Item = u64> + '_>, CacheError> {
+        let start = after.map_or(Bound::Unbounded, Bound::Excluded);
+        let records = &self.data.records;
+        Ok(Box::new(
+            self.data
+                .ids
+                .range::<[u8], _>((start, Bound::Unbounded))
+                .map(|(_, id)| *id)
+                // Ids of deleted records are kept.
+                .filter(move |id| records.contains_key(id)),
+        ))
+    }
+
+    fn index_entries(
+        &self,
+        index: SecondaryIndexId,
+        start: Option<KeyEndpoint>,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError> {
+        Ok(self.data.secondary_index(index)?.entries(start, direction))
+    }
+
+    fn index_entries_after(
+        &self,
+        index: SecondaryIndexId,
+        key: Vec<u8>,
+        id: u64,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError> {
+        Ok(self
+            .data
+            .secondary_index(index)?
+            .entries_after(key, id, direction))
+    }
+
+    fn full_text_stats(&self, index: SecondaryIndexId) -> Result<FullTextStats, CacheError> {
+        Ok(self.data.secondary_index(index)?.full_text_stats())
+    }
+
+    fn full_text_document_frequency(
+        &self,
+        index: SecondaryIndexId,
+        key: &[u8],
+    ) -> Result<u64, CacheError> {
+        Ok(self
+            .data
+            .secondary_index(index)?
+            .full_text_document_frequency(key))
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use dozer_types::parking_lot::RwLock;
+use dozer_types::types::{IndexDefinition, Schema};
+
+use crate::{
+    cache::{CacheManager, CacheManagerOptions, RoCache, RwCache},
+    errors::CacheError,
+};
+
+use super::cache::{MemoryCache, MemoryCacheOptions, SharedCache};
+
+/// A cache manager whose caches and aliases live in memory and are lost when it's dropped.
+#[derive(Debug)]
+pub struct MemoryCacheManager {
+    options: MemoryCacheOptions,
+    caches: RwLock<HashMap<String, SharedCache>>,
+    aliases: RwLock<HashMap<String, String>>,
+}
+
+impl MemoryCacheManager {
+    /// Only `intersection_chunk_size` and `max_scanned_rows` of `options` apply to memory caches.
+    pub fn new(options: CacheManagerOptions) -> Self {
+        Self {
+            options: MemoryCacheOptions {
+                intersection_chunk_size: options.intersection_chunk_size,
+                max_scanned_rows: options.max_scanned_rows,
+            },
+            caches: Default::default(),
+            aliases: Default::default(),
+        }
+    }
+
+    fn open(&self, name: &str) -> Option<MemoryCache> {
+        let aliases = self.aliases.read();
+        let real_name = aliases.get(name).map_or(name, String::as_str);
+        self.caches.read().get(real_name).map(|shared| {
+            MemoryCache::open(real_name.to_string(), shared.clone(), self.options.clone())
+        })
+    }
+}
+
+impl Default for MemoryCacheManager {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl CacheManager for MemoryCacheManager {
+    fn open_rw_cache(&self, name: &str) -> Result<Option<Box<dyn RwCache>>, CacheError> {
+        Ok(self
+            .open(name)
+            .map(|cache| Box::new(cache) as Box<dyn RwCache>))
+    }
+
+    fn open_ro_cache(&self, name: &str) -> Result<Option<Box<dyn RoCache>>, CacheError> {
+        Ok(self
+            .open(name)
+            .map(|cache| Box::new(cache) as Box<dyn RoCache>))
+    }
+
+    fn create_cache(
+        &self,
+        schemas: Vec<(String, Schema, Vec<IndexDefinition>)>,
+    ) -> Result<Box<dyn RwCache>, CacheError> {
+        let name = uuid::Uuid::new_v4().to_string();
+        let cache = MemoryCache::create(name.clone(), schemas, self.options.clone())?;
+        self.caches.write().insert(name, cache.shared());
+        Ok(Box::new(cache))
+    }
+
+    fn create_alias(&self, name: &str, alias: &str) -> Result<(), CacheError> {
+        self.aliases
+            .write()
+            .insert(alias.to_string(), name.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_cache_manager() {
+        let cache_manager = MemoryCacheManager::default();
+        let real_name = cache_manager
+            .create_cache(vec![])
+            .unwrap()
+            .name()
+            .to_string();
+        assert!(cache_manager.open_ro_cache("missing").unwrap().is_none());
+        // Test open with real name.
+        assert_eq!(
+            cache_manager
+                .open_rw_cache(&real_name)
+                .unwrap()
+                .unwrap()
+                .name(),
+            real_name
+        );
+        // Test open with alias.
+        let alias = "alias";
+        cache_manager.create_alias(&real_name, alias).unwrap();
+        assert_eq!(
+            cache_manager.open_ro_cache(alias).unwrap().unwrap().name(),
+            real_name
+        );
+        // If name is both alias and real name, alias shadows real name.
+        let real_name2 = cache_manager
+            .create_cache(vec![])
+            .unwrap()
+            .name()
+            .to_string();
+        cache_manager.create_alias(&real_name, &real_name2).unwrap();
+        assert_eq!(
+            cache_manager
+                .open_rw_cache(&real_name2)
+                .unwrap()
+                .unwrap()
+                .name(),
+            real_name
+        );
+    }
+}
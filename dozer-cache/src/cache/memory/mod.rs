@@ -0,0 +1,3 @@
+mod cache;
+pub mod cache_manager;
+mod secondary_index;
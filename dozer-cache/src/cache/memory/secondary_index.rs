@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound;
+
+use dozer_types::types::{IndexDefinition, Record};
+
+use crate::cache::expression::SortDirection;
+use crate::cache::index::{compare_composite_keys, get_secondary_index_keys, has_composite_keys};
+use crate::cache::query::{FullTextStats, IndexEntries, KeyEndpoint};
+use crate::errors::CacheError;
+
+/// A key of a secondary index, ordered the way the LMDB comparator orders keys of the same index.
+#[derive(Debug, Clone)]
+struct IndexKey {
+    key: Vec<u8>,
+    composite: bool,
+}
+
+impl Ord for IndexKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.composite {
+            compare_composite_keys(&self.key, &other.key)
+        } else {
+            self.key.cmp(&other.key)
+        }
+    }
+}
+
+impl PartialOrd for IndexKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for IndexKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for IndexKey {}
+
+type Entry = (IndexKey, u64);
+
+/// A secondary index stored like a `DUP_SORT` LMDB database, as `(key, id)` entries ordered by key then id.
+#[derive(Debug)]
+pub struct SecondaryIndex {
+    definition: IndexDefinition,
+    entries: BTreeSet<Entry>,
+    full_text_stats: FullTextStats,
+    /// Number of documents indexed under each key of a full text index.
+    document_frequencies: HashMap<Vec<u8>, u64>,
+}
+
+impl SecondaryIndex {
+    pub fn new(definition: IndexDefinition) -> Self {
+        Self {
+            definition,
+            entries: BTreeSet::new(),
+            full_text_stats: FullTextStats::default(),
+            document_frequencies: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, record: &Record, id: u64) -> Result<(), CacheError> {
+        let (keys, num_tokens) = get_secondary_index_keys(&self.definition, &record.values)?;
+        let is_full_text = matches!(self.definition, IndexDefinition::FullText(..));
+        for key in keys {
+            if is_full_text {
+                *self.document_frequencies.entry(key.clone()).or_default() += 1;
+            }
+            let key = self.key(key);
+            self.entries.insert((key, id));
+        }
+        if is_full_text {
+            self.full_text_stats.num_documents += 1;
+            self.full_text_stats.num_tokens += num_tokens;
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, record: &Record, id: u64) -> Result<(), CacheError> {
+        let (keys, num_tokens) = get_secondary_index_keys(&self.definition, &record.values)?;
+        let is_full_text = matches!(self.definition, IndexDefinition::FullText(..));
+        for key in keys {
+            if is_full_text {
+                if let Some(frequency) = self.document_frequencies.get_mut(&key) {
+                    *frequency -= 1;
+                    if *frequency == 0 {
+                        self.document_frequencies.remove(&key);
+                    }
+                }
+            }
+            let key = self.key(key);
+            self.entries.remove(&(key, id));
+        }
+        if is_full_text {
+            let stats = &mut self.full_text_stats;
+            stats.num_documents = stats.num_documents.saturating_sub(1);
+            stats.num_tokens = stats.num_tokens.saturating_sub(num_tokens);
+        }
+        Ok(())
+    }
+
+    pub fn full_text_stats(&self) -> FullTextStats {
+        self.full_text_stats
+    }
+
+    pub fn full_text_document_frequency(&self, key: &[u8]) -> u64 {
+        self.document_frequencies.get(key).copied().unwrap_or(0)
+    }
+
+    /// Returns the entries in `direction`, beginning at `start` or at the first entry in that direction.
+    pub fn entries(
+        &self,
+        start: Option<KeyEndpoint>,
+        direction: SortDirection,
+    ) -> IndexEntries<'_> {
+        let range = match (start, direction) {
+            (None, _) => (Bound::Unbounded, Bound::Unbounded),
+            (Some(KeyEndpoint::Including(key)), SortDirection::Ascending) => {
+                (Bound::Included((self.key(key), 0)), Bound::Unbounded)
+            }
+            (Some(KeyEndpoint::Excluding(key)), SortDirection::Ascending) => {
+                (Bound::Excluded((self.key(key), u64::MAX)), Bound::Unbounded)
+            }
+            (Some(KeyEndpoint::Including(key)), SortDirection::Descending) => {
+                (Bound::Unbounded, Bound::Included((self.key(key), u64::MAX)))
+            }
+            (Some(KeyEndpoint::Excluding(key)), SortDirection::Descending) => {
+                (Bound::Unbounded, Bound::Excluded((self.key(key), 0)))
+            }
+        };
+        self.range(range, direction)
+    }
+
+    /// Returns the entries in `direction`, beginning right after `(key, id)`, which may not exist anymore.
+    pub fn entries_after(
+        &self,
+        key: Vec<u8>,
+        id: u64,
+        direction: SortDirection,
+    ) -> IndexEntries<'_> {
+        let entry = (self.key(key), id);
+        let range = match direction {
+            SortDirection::Ascending => (Bound::Excluded(entry), Bound::Unbounded),
+            SortDirection::Descending => (Bound::Unbounded, Bound::Excluded(entry)),
+        };
+        self.range(range, direction)
+    }
+
+    fn range(
+        &self,
+        range: (Bound<Entry>, Bound<Entry>),
+        direction: SortDirection,
+    ) -> IndexEntries<'_> {
+        let entries = self
+            .entries
+            .range(range)
+            .map(|(key, id)| (key.key.as_slice(), *id));
+        match direction {
+            SortDirection::Ascending => Box::new(entries),
+            SortDirection::Descending => Box::new(entries.rev()),
+        }
+    }
+
+    fn key(&self, key: Vec<u8>) -> IndexKey {
+        IndexKey {
+            key,
+            composite: has_composite_keys(&self.definition),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::Field;
+
+    use super::*;
+
+    fn values(entries: IndexEntries<'_>) -> Vec<(i64, u64)> {
+        entries
+            .map(|(key, id)| (Field::decode(key).unwrap().as_int().unwrap(), id))
+            .collect()
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut index = SecondaryIndex::new(IndexDefinition::SortedInverted(vec![0]));
+        for (id, value) in [(0, 1), (1, 2), (2, 2), (3, 3)] {
+            let record = Record::new(None, vec![Field::Int(value)], None);
+            index.insert(&record, id).unwrap();
+        }
+        let key = |value: i64| Field::Int(value).encode();
+
+        let all = index.entries(None, SortDirection::Ascending);
+        assert_eq!(values(all), vec![(1, 0), (2, 1), (2, 2), (3, 3)]);
+        let all = index.entries(None, SortDirection::Descending);
+        assert_eq!(values(all), vec![(3, 3), (2, 2), (2, 1), (1, 0)]);
+
+        let start = Some(KeyEndpoint::Including(key(2)));
+        let from = index.entries(start.clone(), SortDirection::Ascending);
+        assert_eq!(values(from), vec![(2, 1), (2, 2), (3, 3)]);
+        let from = index.entries(start, SortDirection::Descending);
+        assert_eq!(values(from), vec![(2, 2), (2, 1), (1, 0)]);
+
+        let start = Some(KeyEndpoint::Excluding(key(2)));
+        let from = index.entries(start.clone(), SortDirection::Ascending);
+        assert_eq!(values(from), vec![(3, 3)]);
+        let from = index.entries(start, SortDirection::Descending);
+        assert_eq!(values(from), vec![(1, 0)]);
+
+        let after = index.entries_after(key(2), 1, SortDirection::Ascending);
+        assert_eq!(values(after), vec![(2, 2), (3, 3)]);
+        let after = index.entries_after(key(2), 1, SortDirection::Descending);
+        assert_eq!(values(after), vec![(1, 0)]);
+
+        let record = Record::new(None, vec![Field::Int(2)], None);
+        index.delete(&record, 1).unwrap();
+        let all = index.entries(None, SortDirection::Ascending);
+        assert_eq!(values(all), vec![(1, 0), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_full_text_stats() {
+        let mut index = SecondaryIndex::new(IndexDefinition::FullText(0, Default::default()));
+        let regular = Record::new(
+            None,
+            vec![Field::String("regular test regular".into())],
+            None,
+        );
+        let test = Record::new(None, vec![Field::String("test".into())], None);
+        index.insert(&regular, 0).unwrap();
+        index.insert(&test, 1).unwrap();
+
+        let stats = index.full_text_stats();
+        assert_eq!((stats.num_documents, stats.num_tokens), (2, 4));
+        assert_eq!(index.full_text_document_frequency(b"regular"), 1);
+        assert_eq!(index.full_text_document_frequency(b"test"), 2);
+
+        index.delete(&regular, 0).unwrap();
+        let stats = index.full_text_stats();
+        assert_eq!((stats.num_documents, stats.num_tokens), (1, 1));
+        assert_eq!(index.full_text_document_frequency(b"regular"), 0);
+        assert_eq!(index.full_text_document_frequency(b"test"), 1);
+    }
+}
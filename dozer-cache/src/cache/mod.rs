@@ -1,7 +1,7 @@
 mod lmdb;
 use std::fmt::Debug;
 
-use self::expression::QueryExpression;
+use self::expression::{Cursor, QueryExpression};
 use crate::errors::CacheError;
 use dozer_types::{
     node::SourceStates,
@@ -9,9 +9,16 @@ use dozer_types::{
     types::{IndexDefinition, Record, Schema, SchemaIdentifier},
 };
 pub use lmdb::cache_manager::{CacheManagerOptions, LmdbCacheManager};
+mod memory;
+pub use memory::cache_manager::MemoryCacheManager;
+mod aggregation;
 pub mod expression;
 pub mod index;
 mod plan;
+pub use plan::Plan;
+mod query;
+mod retention;
+pub use retention::{OnExpired, RetentionOptions, RetentionPolicy};
 pub mod test_utils;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -73,6 +80,23 @@ pub trait RoCache: Send + Sync + Debug {
         schema_name: &str,
         query: &QueryExpression,
     ) -> Result<(&Schema, Vec<RecordWithId>), CacheError>;
+    /// Groups the records matching `query.filter` as described by `query.aggregation`.
+    ///
+    /// Returns the schema of the output and one record per group. `limit`, `skip` and `order_by` apply to the groups.
+    fn aggregate(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+    ) -> Result<(Schema, Vec<Record>), CacheError>;
+    /// Returns the plan `count` and `query` execute, without executing it.
+    fn explain(&self, schema_name: &str, query: &QueryExpression) -> Result<Plan, CacheError>;
+    /// Returns the cursor that continues after `records`, which `query` returned. `None` if there're no more records.
+    fn next_cursor(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+        records: &[RecordWithId],
+    ) -> Result<Option<Cursor>, CacheError>;
 }
 
 pub trait RwCache: RoCache {
@@ -87,4 +111,15 @@ pub trait RwCache: RoCache {
     fn commit(&self, checkpoint: &SourceStates) -> Result<(), CacheError>;
     /// Get the current checkpoint.
     fn get_checkpoint(&self) -> Result<SourceStates, CacheError>;
+    /// Starts deleting expired records of append-only schema `schema_name` in the background.
+    ///
+    /// Each batch of deletions is committed on its own, unless there're uncommitted writes, which it's committed with.
+    /// `on_expired` is called with the records deleted by each batch. A previously started retention is stopped.
+    fn start_retention(
+        &mut self,
+        schema_name: &str,
+        policy: RetentionPolicy,
+        options: RetentionOptions,
+        on_expired: OnExpired,
+    ) -> Result<(), CacheError>;
 }
@@ -1,27 +1,37 @@
 mod helper;
 mod planner;
-use dozer_types::types::Field;
+use std::collections::HashSet;
+
+use dozer_types::geo::GeodesicDistance;
+use dozer_types::serde::Serialize;
+use dozer_types::types::{Field, FullTextAnalyzer};
 pub use planner::QueryPlanner;
 
-use super::expression::{Operator, SortDirection};
+use super::expression::{GeoShape, Operator, SortDirection};
+use super::index;
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub enum Plan {
     IndexScans(Vec<IndexScan>),
     SeqScan(SeqScan),
+    /// No index can answer the query alone, so records are scanned, filtered and sorted in memory.
+    FullScan(FullScan),
     ReturnEmpty,
 }
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct IndexScan {
     pub index_id: usize,
     pub is_single_field_sorted_inverted: bool,
     pub kind: IndexScanKind,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub enum IndexScanKind {
     SortedInverted {
         eq_filters: Vec<(usize, Field)>,
@@ -30,21 +40,52 @@ pub enum IndexScanKind {
     FullText {
         filter: IndexFilter,
     },
+    /// Reads the records in the cells covering the filter shape, so it's only used in full scans.
+    Geo {
+        filter: GeoFilter,
+    },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct SortedInvertedRangeQuery {
     pub field_index: usize,
     pub sort_direction: SortDirection,
     pub operator_and_value: Option<(Operator, Field)>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct SeqScan {
     pub direction: SortDirection,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct FullScan {
+    /// The index scan that narrows down the scanned records. All records are scanned if `None`.
+    pub index_scan: Option<IndexScan>,
+    /// The filters not applied by `index_scan`.
+    pub residual_filters: Vec<IndexFilter>,
+    /// Always applied in memory, because a geo index scan returns records near but outside the shape too.
+    ///
+    /// Records are sorted by distance to the center of the `$near` filter first, if any.
+    pub geo_filters: Vec<GeoFilter>,
+    pub order_by: Vec<(usize, SortDirection)>,
+    /// If set, records are sorted by their BM25 score instead of the value of the filtered field.
+    pub relevance: Option<Relevance>,
+}
+
+/// A full text filter on a field in `order_by`, and the full text index that scores it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct Relevance {
+    pub index_id: usize,
+    pub filter: IndexFilter,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
 pub struct IndexFilter {
     pub field_index: usize,
     pub op: Operator,
@@ -59,4 +100,81 @@ impl IndexFilter {
             val,
         }
     }
+
+    /// Checks a record against the filter, returning the same result as an index scan would.
+    ///
+    /// `analyzer` is the one of the full text index on the field, or the default one if there's none.
+    pub fn matches(&self, values: &[Field], analyzer: &FullTextAnalyzer) -> bool {
+        let value = &values[self.field_index];
+        match self.op {
+            Operator::EQ => value == &self.val,
+            // `null` is not in any range, see `get_key_interval_from_range_query`.
+            _ if value == &Field::Null => false,
+            Operator::LT => value < &self.val,
+            Operator::LTE => value <= &self.val,
+            Operator::GT => value > &self.val,
+            Operator::GTE => value >= &self.val,
+            // Geo filters are planned as `GeoFilter`s.
+            Operator::Near | Operator::WithinBox | Operator::WithinRadius => false,
+            Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => {
+                let (Some(text), Some(query)) = (as_text(value), as_text(&self.val)) else {
+                    return false;
+                };
+                let tokens = index::get_full_text_tokens(analyzer, text)
+                    .into_iter()
+                    .collect::<HashSet<_>>();
+                let query_tokens = index::analyze(analyzer, query);
+                // A query without tokens looks up no keys, so it matches nothing.
+                if query_tokens.is_empty() {
+                    return false;
+                }
+                match self.op {
+                    Operator::MatchesAny => query_tokens.iter().any(|token| tokens.contains(token)),
+                    _ => query_tokens.iter().all(|token| tokens.contains(token)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct GeoFilter {
+    pub field_index: usize,
+    pub op: Operator,
+    pub shape: GeoShape,
+}
+
+impl GeoFilter {
+    pub fn new(field_index: usize, op: Operator, shape: GeoShape) -> Self {
+        Self {
+            field_index,
+            op,
+            shape,
+        }
+    }
+
+    pub fn matches(&self, values: &[Field]) -> bool {
+        match &values[self.field_index] {
+            Field::Point(point) => self.shape.contains(point),
+            _ => false,
+        }
+    }
+
+    /// Returns the distance in meters from the center of the shape to the point in `values`, `null` if it's not a point.
+    pub fn distance(&self, values: &[Field]) -> Field {
+        match (&self.shape, &values[self.field_index]) {
+            (GeoShape::Circle { center, .. }, Field::Point(point)) => {
+                Field::Float(center.geodesic_distance(point))
+            }
+            _ => Field::Null,
+        }
+    }
+}
+
+fn as_text(field: &Field) -> Option<&str> {
+    match field {
+        Field::String(text) | Field::Text(text) => Some(text),
+        _ => None,
+    }
 }
@@ -1,12 +1,14 @@
-use crate::cache::expression::{FilterExpression, Operator, QueryExpression, SortDirection};
+use crate::cache::expression::{
+    FilterExpression, GeoShape, Operator, QueryExpression, SortDirection,
+};
 use crate::errors::PlanError;
 use dozer_types::json_value_to_field;
 use dozer_types::types::{Field, FieldDefinition, Schema};
 use dozer_types::types::{FieldType, IndexDefinition};
 
 use super::helper::{RangeQuery, RangeQueryKind};
-use super::{helper, IndexScan, Plan, SeqScan};
-use super::{IndexFilter, IndexScanKind};
+use super::{helper, FullScan, IndexScan, Plan, SeqScan};
+use super::{GeoFilter, IndexFilter, IndexScanKind, Relevance, SortedInvertedRangeQuery};
 
 pub struct QueryPlanner<'a> {
     schema: &'a Schema,
@@ -30,9 +32,26 @@ impl<'a> QueryPlanner<'a> {
         // Collect all the filters.
         // TODO: Handle filters like And([a > 0, a < 10]).
         let mut filters = vec![];
+        let mut geo_filters = vec![];
         if let Some(expression) = &self.query.filter {
-            collect_filters(self.schema, expression, &mut filters)?;
+            collect_filters(self.schema, expression, &mut filters, &mut geo_filters)?;
         }
+        if geo_filters
+            .iter()
+            .filter(|filter| filter.op == Operator::Near)
+            .count()
+            > 1
+        {
+            return Err(PlanError::MultipleNearFilters);
+        }
+
+        // Keep the filters and sort options in case we have to fall back to a full scan.
+        let all_filters = filters
+            .iter()
+            .map(|(filter, _)| filter.clone())
+            .collect::<Vec<_>>();
+        let mut all_order_by = vec![];
+        let mut relevance = None;
 
         // Filter the sort options.
         // TODO: Handle duplicate fields.
@@ -42,6 +61,14 @@ impl<'a> QueryPlanner<'a> {
             let (field_index, _, _) =
                 get_field_index_and_type(&order.field_name, &self.schema.fields)
                     .ok_or_else(|| PlanError::FieldNotFound(order.field_name.clone()))?;
+            all_order_by.push((field_index, order.direction));
+            // Sorting by a field with a full text filter sorts by relevance, which only a full scan computes.
+            if relevance.is_none() {
+                if let Some(full_text_relevance) = self.find_relevance(field_index, &filters) {
+                    relevance = Some(full_text_relevance);
+                    continue;
+                }
+            }
             // If the field is already in a filter supported by `SortedInverted`, mark the corresponding filter.
             if seen_in_sorted_inverted_filter(field_index, order.direction, &mut filters)? {
                 continue;
@@ -50,6 +77,16 @@ impl<'a> QueryPlanner<'a> {
             order_by.push((field_index, order.direction));
         }
 
+        // Geo filters are always applied in memory, see `FullScan::geo_filters`.
+        if !geo_filters.is_empty() || relevance.is_some() {
+            return Ok(Plan::FullScan(self.plan_full_scan(
+                all_filters,
+                geo_filters,
+                all_order_by,
+                relevance,
+            )));
+        }
+
         // If no filter and sort is requested, return a SeqScan.
         if filters.is_empty() && order_by.is_empty() {
             return Ok(Plan::SeqScan(SeqScan {
@@ -66,20 +103,136 @@ impl<'a> QueryPlanner<'a> {
         }
 
         // Find the range query, can be a range filter or a sort option.
-        let range_query = find_range_query(&mut filters, &order_by)?;
+        match find_range_query(&mut filters, &order_by) {
+            Ok(range_query) => {
+                // Generate some index scans that can answer this query, lazily.
+                let all_index_scans = helper::get_all_indexes(filters, range_query);
 
-        // Generate some index scans that can answer this query, lazily.
-        let all_index_scans = helper::get_all_indexes(filters, range_query);
+                // Check if existing secondary indexes can satisfy any of the scans.
+                for index_scans in all_index_scans {
+                    if let Some(index_scans) =
+                        all_indexes_are_present(self.secondary_indexes, index_scans)
+                    {
+                        return Ok(Plan::IndexScans(index_scans));
+                    }
+                }
+            }
+            // Indexes can't answer more than one range query, fall back to a full scan.
+            Err(PlanError::RangeQueryLimit) => (),
+            Err(e) => return Err(e),
+        }
 
-        // Check if existing secondary indexes can satisfy any of the scans.
-        for index_scans in all_index_scans {
-            if let Some(index_scans) = all_indexes_are_present(self.secondary_indexes, index_scans)
+        Ok(Plan::FullScan(self.plan_full_scan(
+            all_filters,
+            geo_filters,
+            all_order_by,
+            None,
+        )))
+    }
+
+    /// Returns the full text filter on `field_index` if a full text index can score it.
+    fn find_relevance(
+        &self,
+        field_index: usize,
+        filters: &[(IndexFilter, Option<SortDirection>)],
+    ) -> Option<Relevance> {
+        let (filter, _) = filters.iter().find(|(filter, _)| {
+            filter.field_index == field_index && filter.op.supported_by_full_text()
+        })?;
+        let index_id = self.secondary_indexes.iter().position(|index| {
+            matches!(index, IndexDefinition::FullText(index_field, _) if *index_field == field_index)
+        })?;
+        Some(Relevance {
+            index_id,
+            filter: filter.clone(),
+        })
+    }
+
+    /// Picks the most selective single filter that an index can answer, and applies the rest in memory.
+    fn plan_full_scan(
+        &self,
+        mut filters: Vec<IndexFilter>,
+        geo_filters: Vec<GeoFilter>,
+        order_by: Vec<(usize, SortDirection)>,
+        relevance: Option<Relevance>,
+    ) -> FullScan {
+        // Geo filters stay in `geo_filters` even if an index scans them, so they have no index in `filters`.
+        let mut candidates = filters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, filter)| Some((Some(i), single_filter_index_scan(filter)?)))
+            .chain(geo_filters.iter().map(|filter| {
+                (
+                    None,
+                    IndexScanKind::Geo {
+                        filter: filter.clone(),
+                    },
+                )
+            }))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|(_, kind)| selectivity_rank(kind));
+
+        for (i, kind) in candidates {
+            if let Some(mut index_scans) =
+                all_indexes_are_present(self.secondary_indexes, vec![kind])
             {
-                return Ok(Plan::IndexScans(index_scans));
+                if let Some(i) = i {
+                    filters.remove(i);
+                }
+                return FullScan {
+                    index_scan: index_scans.pop(),
+                    residual_filters: filters,
+                    geo_filters,
+                    order_by,
+                    relevance,
+                };
             }
         }
 
-        Err(PlanError::MatchingIndexNotFound)
+        FullScan {
+            index_scan: None,
+            residual_filters: filters,
+            geo_filters,
+            order_by,
+            relevance,
+        }
+    }
+}
+
+fn single_filter_index_scan(filter: &IndexFilter) -> Option<IndexScanKind> {
+    match filter.op {
+        Operator::EQ => Some(IndexScanKind::SortedInverted {
+            eq_filters: vec![(filter.field_index, filter.val.clone())],
+            range_query: None,
+        }),
+        Operator::LT | Operator::LTE | Operator::GT | Operator::GTE => {
+            Some(IndexScanKind::SortedInverted {
+                eq_filters: vec![],
+                range_query: Some(SortedInvertedRangeQuery {
+                    field_index: filter.field_index,
+                    sort_direction: SortDirection::Ascending,
+                    operator_and_value: Some((filter.op, filter.val.clone())),
+                }),
+            })
+        }
+        Operator::Contains | Operator::MatchesAny | Operator::MatchesAll => {
+            Some(IndexScanKind::FullText {
+                filter: filter.clone(),
+            })
+        }
+        // Geo filters are planned as `GeoFilter`s.
+        Operator::Near | Operator::WithinBox | Operator::WithinRadius => None,
+    }
+}
+
+/// Lower is more selective. `Eq` filters usually match fewer records than full text, geo or range filters.
+fn selectivity_rank(kind: &IndexScanKind) -> usize {
+    match kind {
+        IndexScanKind::SortedInverted {
+            range_query: None, ..
+        } => 0,
+        IndexScanKind::FullText { .. } | IndexScanKind::Geo { .. } => 1,
+        IndexScanKind::SortedInverted { .. } => 2,
     }
 }
 
@@ -98,18 +251,27 @@ fn collect_filters(
     schema: &Schema,
     expression: &FilterExpression,
     filters: &mut Vec<(IndexFilter, Option<SortDirection>)>,
+    geo_filters: &mut Vec<GeoFilter>,
 ) -> Result<(), PlanError> {
     match expression {
         FilterExpression::Simple(field_name, operator, value) => {
             let (field_index, field_type, nullable) =
                 get_field_index_and_type(field_name, &schema.fields)
                     .ok_or_else(|| PlanError::FieldNotFound(field_name.clone()))?;
-            let field = json_value_to_field(value.clone(), field_type, nullable)?;
-            filters.push((IndexFilter::new(field_index, *operator, field), None));
+            if operator.supported_by_geo() {
+                if field_type != FieldType::Point {
+                    return Err(PlanError::NotAPointField(field_name.clone()));
+                }
+                let shape = GeoShape::from_filter(*operator, value)?;
+                geo_filters.push(GeoFilter::new(field_index, *operator, shape));
+            } else {
+                let field = json_value_to_field(value.clone(), field_type, nullable)?;
+                filters.push((IndexFilter::new(field_index, *operator, field), None));
+            }
         }
         FilterExpression::And(expressions) => {
             for expression in expressions {
-                collect_filters(schema, expression, filters)?;
+                collect_filters(schema, expression, filters, geo_filters)?;
             }
         }
     }
@@ -211,7 +373,10 @@ impl IndexScanKind {
                     fields.len() == eq_filters.len()
                 }
             }
-            (IndexScanKind::FullText { filter }, IndexDefinition::FullText(field_index)) => {
+            (IndexScanKind::FullText { filter }, IndexDefinition::FullText(field_index, _)) => {
+                filter.field_index == *field_index
+            }
+            (IndexScanKind::Geo { filter }, IndexDefinition::Geo(field_index)) => {
                 filter.field_index == *field_index
             }
             _ => false,
@@ -297,14 +462,17 @@ mod tests {
                 val: Field::Null,
             },
         };
-        assert!(full_text_scan.is_supported_by_index(&IndexDefinition::FullText(0)),);
-        assert!(!full_text_scan.is_supported_by_index(&IndexDefinition::FullText(1)));
+        assert!(
+            full_text_scan.is_supported_by_index(&IndexDefinition::FullText(0, Default::default())),
+        );
+        assert!(!full_text_scan
+            .is_supported_by_index(&IndexDefinition::FullText(1, Default::default())));
 
         assert!(!full_text_scan.is_supported_by_index(&IndexDefinition::SortedInverted(vec![0])),);
         assert!(!IndexScanKind::SortedInverted {
             eq_filters: vec![(0, Field::Null)],
             range_query: None
         }
-        .is_supported_by_index(&IndexDefinition::FullText(0)),);
+        .is_supported_by_index(&IndexDefinition::FullText(0, Default::default())),);
     }
 }
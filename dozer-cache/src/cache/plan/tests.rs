@@ -3,11 +3,15 @@ use crate::cache::{
     expression::{
         self, FilterExpression, Operator, QueryExpression, Skip, SortDirection, SortOption,
     },
-    plan::{IndexScanKind, SortedInvertedRangeQuery},
+    plan::{FullScan, GeoFilter, IndexFilter, IndexScanKind, Relevance, SortedInvertedRangeQuery},
     test_utils::{self, query_from_filter},
 };
+use crate::errors::PlanError;
 
-use dozer_types::{serde_json::Value, types::Field};
+use dozer_types::{
+    serde_json::{json, Value},
+    types::{DozerPoint, Field},
+};
 
 #[test]
 fn test_generate_plan_simple() {
@@ -124,3 +128,232 @@ fn test_generate_plan_empty() {
     let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
     assert!(matches!(planner.plan().unwrap(), Plan::ReturnEmpty));
 }
+
+#[test]
+fn test_generate_plan_full_scan() {
+    let (schema, secondary_indexes) = test_utils::schema_1();
+
+    // More than one range query.
+    let filter = FilterExpression::And(vec![
+        FilterExpression::Simple("c".into(), Operator::GT, 1.into()),
+        FilterExpression::Simple("a".into(), Operator::LT, 5.into()),
+    ]);
+    let query = query_from_filter(filter);
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
+    let Plan::FullScan(FullScan {
+        index_scan,
+        residual_filters,
+        order_by,
+        ..
+    }) = planner.plan().unwrap()
+    else {
+        panic!("FullScan expected")
+    };
+    assert_eq!(index_scan.unwrap().index_id, 2);
+    assert_eq!(
+        residual_filters,
+        vec![IndexFilter::new(0, Operator::LT, Field::Int(5))]
+    );
+    assert!(order_by.is_empty());
+
+    // No compound index for a, c. The `Eq` filter is picked over the range filter.
+    let filter = FilterExpression::And(vec![
+        FilterExpression::Simple("c".into(), Operator::GTE, 1.into()),
+        FilterExpression::Simple("a".into(), Operator::EQ, 1.into()),
+    ]);
+    let query = QueryExpression::new(
+        Some(filter),
+        vec![SortOption {
+            field_name: "b".into(),
+            direction: SortDirection::Descending,
+        }],
+        Some(10),
+        Skip::Skip(0),
+    );
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
+    let Plan::FullScan(FullScan {
+        index_scan,
+        residual_filters,
+        order_by,
+        ..
+    }) = planner.plan().unwrap()
+    else {
+        panic!("FullScan expected")
+    };
+    let index_scan = index_scan.unwrap();
+    assert_eq!(index_scan.index_id, 0);
+    assert_eq!(
+        index_scan.kind,
+        IndexScanKind::SortedInverted {
+            eq_filters: vec![(0, Field::Int(1))],
+            range_query: None
+        }
+    );
+    assert_eq!(
+        residual_filters,
+        vec![IndexFilter::new(2, Operator::GTE, Field::Int(1))]
+    );
+    assert_eq!(order_by, vec![(1, SortDirection::Descending)]);
+
+    // No full text index.
+    let query = query_from_filter(FilterExpression::Simple(
+        "b".into(),
+        Operator::Contains,
+        "foo".into(),
+    ));
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
+    let Plan::FullScan(FullScan {
+        index_scan,
+        residual_filters,
+        ..
+    }) = planner.plan().unwrap()
+    else {
+        panic!("FullScan expected")
+    };
+    assert_eq!(index_scan, None);
+    assert_eq!(residual_filters.len(), 1);
+}
+
+#[test]
+fn test_generate_plan_geo() {
+    let (schema, secondary_indexes) = test_utils::schema_geo();
+
+    // Geo index scan, then the exact check in memory.
+    let filter = FilterExpression::And(vec![
+        FilterExpression::Simple(
+            "location".into(),
+            Operator::Near,
+            json!({"center": {"x": 13.4, "y": 52.5}, "max_distance": 2000}),
+        ),
+        FilterExpression::Simple("name".into(), Operator::GT, "a".into()),
+    ]);
+    let query = query_from_filter(filter);
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
+    let Plan::FullScan(FullScan {
+        index_scan,
+        residual_filters,
+        geo_filters,
+        order_by,
+        ..
+    }) = planner.plan().unwrap()
+    else {
+        panic!("FullScan expected")
+    };
+    let geo_filter = GeoFilter::new(
+        1,
+        Operator::Near,
+        expression::GeoShape::Circle {
+            center: DozerPoint::from((13.4, 52.5)),
+            radius: 2000.0.into(),
+        },
+    );
+    let index_scan = index_scan.unwrap();
+    assert_eq!(index_scan.index_id, 1);
+    assert_eq!(
+        index_scan.kind,
+        IndexScanKind::Geo {
+            filter: geo_filter.clone()
+        }
+    );
+    assert_eq!(
+        residual_filters,
+        vec![IndexFilter::new(0, Operator::GT, Field::String("a".into()))]
+    );
+    assert_eq!(geo_filters, vec![geo_filter]);
+    assert!(order_by.is_empty());
+
+    // Invalid geo filters.
+    let check_error = |field_name: &str, operator, value| {
+        let query = query_from_filter(FilterExpression::Simple(field_name.into(), operator, value));
+        assert!(QueryPlanner::new(&schema, &secondary_indexes, &query)
+            .plan()
+            .is_err());
+    };
+    check_error(
+        "name",
+        Operator::WithinRadius,
+        json!({"center": {"x": 0, "y": 0}, "radius": 1}),
+    );
+    check_error(
+        "location",
+        Operator::WithinRadius,
+        json!({"center": {"x": 0, "y": 0}, "radius": -1}),
+    );
+    check_error(
+        "location",
+        Operator::WithinBox,
+        json!({"min": {"x": 1, "y": 0}, "max": {"x": 0, "y": 1}}),
+    );
+    check_error(
+        "location",
+        Operator::WithinBox,
+        json!({"min": {"x": 0, "y": 0}, "max": {"x": 0, "y": 91}}),
+    );
+    check_error("location", Operator::Near, json!({"x": 0, "y": 0}));
+}
+
+#[test]
+fn test_generate_plan_relevance() {
+    let (schema, secondary_indexes) = test_utils::schema_multi_indices();
+
+    // Sorting by a field with a full text filter sorts by relevance.
+    let filter = FilterExpression::Simple("text".into(), Operator::MatchesAny, "dance egg".into());
+    let query = QueryExpression::new(
+        Some(filter),
+        vec![SortOption {
+            field_name: "text".into(),
+            direction: SortDirection::Descending,
+        }],
+        Some(10),
+        Skip::Skip(0),
+    );
+    let planner = QueryPlanner::new(&schema, &secondary_indexes, &query);
+    let Plan::FullScan(FullScan {
+        index_scan,
+        residual_filters,
+        order_by,
+        relevance,
+        ..
+    }) = planner.plan().unwrap()
+    else {
+        panic!("FullScan expected")
+    };
+    let filter = IndexFilter::new(1, Operator::MatchesAny, Field::String("dance egg".into()));
+    let index_scan = index_scan.unwrap();
+    assert_eq!(index_scan.index_id, 1);
+    assert_eq!(
+        index_scan.kind,
+        IndexScanKind::FullText {
+            filter: filter.clone()
+        }
+    );
+    assert!(residual_filters.is_empty());
+    assert_eq!(order_by, vec![(1, SortDirection::Descending)]);
+    assert_eq!(
+        relevance,
+        Some(Relevance {
+            index_id: 1,
+            filter
+        })
+    );
+
+    // No full text index to score the filter.
+    let (schema, secondary_indexes) = test_utils::schema_1();
+    let query = QueryExpression::new(
+        Some(FilterExpression::Simple(
+            "b".into(),
+            Operator::Contains,
+            "foo".into(),
+        )),
+        vec![SortOption {
+            field_name: "b".into(),
+            direction: SortDirection::Ascending,
+        }],
+        Some(10),
+        Skip::Skip(0),
+    );
+    assert!(matches!(
+        QueryPlanner::new(&schema, &secondary_indexes, &query).plan(),
+        Err(PlanError::CannotSortFullTextFilter)
+    ));
+}
@@ -0,0 +1,842 @@
+use std::cmp::Ordering;
+
+use super::intersection::intersection;
+use super::{KeyEndpoint, QueryStorage, SecondaryIndexId};
+use crate::cache::aggregation::Aggregator;
+use crate::cache::expression::{Aggregation, Cursor, Skip};
+use crate::cache::RecordWithId;
+use crate::cache::{
+    expression::{Operator, QueryExpression, SortDirection},
+    index::{self, get_primary_key},
+    plan::{
+        FullScan, GeoFilter, IndexScan, IndexScanKind, Plan, QueryPlanner, SortedInvertedRangeQuery,
+    },
+};
+use crate::errors::{CacheError, IndexError, PlanError};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FullTextAnalyzer, IndexDefinition, Record, Schema};
+use itertools::{Either, Itertools};
+use roaring::RoaringTreemap;
+
+/// Plans and executes a query on a schema, reading from the `QueryStorage` of a cache backend.
+pub struct QueryHandler<'a, S: QueryStorage> {
+    storage: &'a S,
+    schema: &'a Schema,
+    secondary_indexes: &'a [IndexDefinition],
+    query: &'a QueryExpression,
+}
+impl<'a, S: QueryStorage> QueryHandler<'a, S> {
+    pub fn new(
+        storage: &'a S,
+        schema: &'a Schema,
+        secondary_indexes: &'a [IndexDefinition],
+        query: &'a QueryExpression,
+    ) -> Self {
+        Self {
+            storage,
+            schema,
+            secondary_indexes,
+            query,
+        }
+    }
+
+    /// Returns the plan that `count` and `query` execute.
+    pub fn plan(&self) -> Result<Plan, CacheError> {
+        let planner = QueryPlanner::new(self.schema, self.secondary_indexes, self.query);
+        let plan = planner.plan()?;
+        if let Some(cursor) = &self.query.cursor {
+            if !cursor_matches_plan(cursor, &plan) {
+                return Err(PlanError::InvalidCursor.into());
+            }
+        }
+        Ok(plan)
+    }
+
+    pub fn count(&self) -> Result<usize, CacheError> {
+        match self.plan()? {
+            Plan::IndexScans(index_scans) => Ok(self.build_index_scan(index_scans)?.count()),
+            Plan::SeqScan(_) => Ok(match (self.query.skip, &self.query.cursor) {
+                (Skip::Skip(skip), None) => self
+                    .storage
+                    .count_records()?
+                    .saturating_sub(skip)
+                    .min(self.query.limit.unwrap_or(usize::MAX)),
+                _ => self.all_ids()?.count(),
+            }),
+            Plan::FullScan(full_scan) => Ok(self.full_scan(full_scan)?.len()),
+            Plan::ReturnEmpty => Ok(0),
+        }
+    }
+
+    pub fn query(&self) -> Result<Vec<RecordWithId>, CacheError> {
+        // Validate `$select` here so that invalid field names fail the query.
+        self.query.projection(self.schema)?;
+        match self.plan()? {
+            // Full scan has read the records already.
+            Plan::FullScan(full_scan) => self.full_scan(full_scan),
+            plan => self.collect_records(self.ids(plan)?),
+        }
+    }
+
+    /// Returns the cursor that continues after `records`, which `query` returned.
+    ///
+    /// Returns `None` if `records` is the last page.
+    pub fn next_cursor(&self, records: &[RecordWithId]) -> Result<Option<Cursor>, CacheError> {
+        let (Some(limit), Some(last)) = (self.query.limit, records.last()) else {
+            return Ok(None);
+        };
+        if records.len() < limit {
+            return Ok(None);
+        }
+
+        Ok(match self.plan()? {
+            Plan::SeqScan(_) => Some(Cursor::Key {
+                key: if self.schema.primary_index.is_empty() {
+                    last.id.to_be_bytes().to_vec()
+                } else {
+                    get_primary_key(&self.schema.primary_index, &last.record.values)
+                },
+                id: last.id,
+            }),
+            Plan::IndexScans(index_scans) => Some(Cursor::Key {
+                key: match ordering_scan(&index_scans) {
+                    Some(index_scan) => self.index_key(index_scan, &last.record.values)?,
+                    // Intersection of single key scans is ordered by id.
+                    None => vec![],
+                },
+                id: last.id,
+            }),
+            Plan::FullScan(full_scan) => {
+                let bm25 = self.bm25(&full_scan)?;
+                Some(Cursor::Sorted {
+                    values: sort_key(&full_scan, bm25.as_ref(), &last.record.values),
+                    id: last.id,
+                })
+            }
+            Plan::ReturnEmpty => None,
+        })
+    }
+
+    /// Groups the records matching the filter. `limit`, `skip` and `order_by` apply to the groups.
+    ///
+    /// Reading more than `max_scanned_rows` records fails, unless only records are counted.
+    pub fn aggregate(
+        &self,
+        aggregation: &Aggregation,
+    ) -> Result<(Schema, Vec<Record>), CacheError> {
+        if self.query.cursor.is_some() {
+            return Err(PlanError::AfterWithAggregation.into());
+        }
+        let mut aggregator = Aggregator::new(self.schema, aggregation)?;
+
+        let filter_query = QueryExpression {
+            filter: self.query.filter.clone(),
+            ..QueryExpression::with_no_limit()
+        };
+        let filter_handler = QueryHandler::new(
+            self.storage,
+            self.schema,
+            self.secondary_indexes,
+            &filter_query,
+        );
+        if aggregator.only_counts_records() {
+            // No need to read the records.
+            aggregator.add_count(filter_handler.count()?);
+        } else {
+            let max_scanned_rows = self.storage.max_scanned_rows();
+            for (num_scanned, id) in filter_handler.ids(filter_handler.plan()?)?.enumerate() {
+                if num_scanned >= max_scanned_rows {
+                    return Err(CacheError::ScanLimitExceeded(max_scanned_rows));
+                }
+                let record = self.storage.get_record(id)?;
+                aggregator.add(&record.values)?;
+            }
+        }
+
+        Ok(aggregator.finish(&self.query.order_by, self.query.skip, self.query.limit)?)
+    }
+
+    fn ids(&self, plan: Plan) -> Result<impl Iterator<Item = u64> + '_, CacheError> {
+        Ok(match plan {
+            Plan::IndexScans(index_scans) => {
+                Either::Left(Either::Left(self.build_index_scan(index_scans)?))
+            }
+            Plan::SeqScan(_seq_scan) => Either::Left(Either::Right(self.all_ids()?)),
+            Plan::FullScan(full_scan) => Either::Right(Either::Left(
+                self.full_scan(full_scan)?
+                    .into_iter()
+                    .map(|record| record.id),
+            )),
+            Plan::ReturnEmpty => Either::Right(Either::Right(std::iter::empty())),
+        })
+    }
+
+    /// Reads at most `max_scanned_rows` records, then filters, sorts, skips and limits them in memory.
+    fn full_scan(&self, full_scan: FullScan) -> Result<Vec<RecordWithId>, CacheError> {
+        let ids = match &full_scan.index_scan {
+            Some(index_scan) => Either::Left(self.query_with_secondary_index(index_scan, None)?),
+            None => Either::Right(self.storage.ids(None)?),
+        };
+
+        let analyzers = full_scan
+            .residual_filters
+            .iter()
+            .map(|filter| self.full_text_analyzer(filter.field_index))
+            .collect::<Vec<_>>();
+        let bm25 = self.bm25(&full_scan)?;
+
+        let max_scanned_rows = self.storage.max_scanned_rows();
+        let mut records = vec![];
+        for (num_scanned, id) in ids.enumerate() {
+            if num_scanned >= max_scanned_rows {
+                return Err(CacheError::ScanLimitExceeded(max_scanned_rows));
+            }
+            let record = self.storage.get_record(id)?;
+            if full_scan
+                .residual_filters
+                .iter()
+                .zip(&analyzers)
+                .all(|(filter, analyzer)| filter.matches(&record.values, analyzer))
+                && full_scan
+                    .geo_filters
+                    .iter()
+                    .all(|filter| filter.matches(&record.values))
+            {
+                let key = sort_key(&full_scan, bm25.as_ref(), &record.values);
+                records.push((key, RecordWithId::new(id, record)));
+            }
+        }
+
+        // Ties are broken by id so that `Cursor::Sorted` identifies a position.
+        let directions = sort_directions(&full_scan);
+        records.sort_by(|(a_key, a), (b_key, b)| {
+            compare_sorted(&directions, (a_key, a.id), (b_key, b.id))
+        });
+        if let Some(Cursor::Sorted { values, id }) = &self.query.cursor {
+            records.retain(|(key, record)| {
+                compare_sorted(&directions, (key, record.id), (values, *id)) == Ordering::Greater
+            });
+        }
+
+        let records = records.into_iter().map(|(_, record)| record);
+        let records = match self.query.skip {
+            Skip::Skip(n) => Either::Left(records.skip(n)),
+            Skip::After(after) => {
+                Either::Right(records.skip_while(move |record| record.id != after).skip(1))
+            }
+        };
+        Ok(records
+            .take(self.query.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    pub fn all_ids(&self) -> Result<impl Iterator<Item = u64> + '_, CacheError> {
+        // Ids are ordered by primary key, so the cursor key is enough to continue.
+        let after = match &self.query.cursor {
+            Some(Cursor::Key { key, .. }) => Some(key.as_slice()),
+            _ => None,
+        };
+        Ok(self.skip_and_limit(self.storage.ids(after)?))
+    }
+
+    fn build_index_scan(
+        &self,
+        index_scans: Vec<IndexScan>,
+    ) -> Result<impl Iterator<Item = u64> + '_, CacheError> {
+        debug_assert!(
+            !index_scans.is_empty(),
+            "Planner should not generate empty index scan"
+        );
+        let after = match &self.query.cursor {
+            Some(Cursor::Key { key, id }) => Some((key.as_slice(), *id)),
+            _ => None,
+        };
+        let ids = if index_scans.len() == 1 {
+            // The fast path, without intersection calculation.
+            let after = after.map(|(key, id)| (Some(key), id));
+            Either::Left(self.query_with_secondary_index(&index_scans[0], after)?)
+        } else if let Some(range_scan) = ordering_scan(&index_scans) {
+            // The range scan orders the records, the other scans are ordered by id and filter them.
+            let mut id_sets = vec![];
+            for index_scan in &index_scans {
+                if !std::ptr::eq(index_scan, range_scan) {
+                    id_sets.push(
+                        self.query_with_secondary_index(index_scan, None)?
+                            .collect::<RoaringTreemap>(),
+                    );
+                }
+            }
+            let after = after.map(|(key, id)| (Some(key), id));
+            Either::Right(Either::Left(
+                self.query_with_secondary_index(range_scan, after)?
+                    .filter(move |id| id_sets.iter().all(|ids| ids.contains(*id))),
+            ))
+        } else {
+            // Intersection of single key scans is ordered by id, every scan continues after the cursor id.
+            let after = after.map(|(_, id)| (None, id));
+            let iterators = index_scans
+                .iter()
+                .map(|index_scan| self.query_with_secondary_index(index_scan, after))
+                .collect::<Result<Vec<_>, CacheError>>()?;
+            Either::Right(Either::Right(intersection(
+                iterators,
+                self.storage.intersection_chunk_size(),
+            )))
+        };
+        Ok(self.skip_and_limit(ids))
+    }
+
+    /// Returns the analyzer of the full text index on `field_index`, so that in memory filters match what an index scan would.
+    fn full_text_analyzer(&self, field_index: usize) -> FullTextAnalyzer {
+        self.secondary_indexes
+            .iter()
+            .find_map(|index| match index {
+                IndexDefinition::FullText(index_field, analyzer) if *index_field == field_index => {
+                    Some(analyzer.clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn secondary_index(&self, index_id: usize) -> Result<SecondaryIndexId, CacheError> {
+        let schema_id = self
+            .schema
+            .identifier
+            .ok_or(CacheError::SchemaHasNoIdentifier)?;
+        Ok((schema_id, index_id))
+    }
+
+    /// Reads the ids indexed under `key` in id order, continuing after the id `after` if set.
+    fn full_text_token_ids(
+        &self,
+        index: SecondaryIndexId,
+        key: Vec<u8>,
+        after: Option<u64>,
+    ) -> Result<impl Iterator<Item = u64> + 'a, CacheError> {
+        let entries = match after {
+            Some(id) => self.storage.index_entries_after(
+                index,
+                key.clone(),
+                id,
+                SortDirection::Ascending,
+            )?,
+            None => self.storage.index_entries(
+                index,
+                Some(KeyEndpoint::Including(key.clone())),
+                SortDirection::Ascending,
+            )?,
+        };
+        Ok(entries
+            .take_while(move |(entry_key, _)| *entry_key == key.as_slice())
+            .map(|(_, id)| id))
+    }
+
+    /// Prepares BM25 scoring if the full scan sorts by relevance.
+    fn bm25(&self, full_scan: &FullScan) -> Result<Option<Bm25>, CacheError> {
+        let Some(relevance) = &full_scan.relevance else {
+            return Ok(None);
+        };
+        let IndexDefinition::FullText(field_index, analyzer) =
+            &self.secondary_indexes[relevance.index_id]
+        else {
+            panic!("relevance must be scored by a full text index");
+        };
+        let index = self.secondary_index(relevance.index_id)?;
+        let stats = self.storage.full_text_stats(index)?;
+        let num_documents = stats.num_documents as f64;
+
+        let mut terms = vec![];
+        for token in index::analyze(analyzer, full_text_query(&relevance.filter.val)?)
+            .into_iter()
+            .unique()
+        {
+            let key = index::get_full_text_secondary_index(&token);
+            let document_frequency = self.storage.full_text_document_frequency(index, &key)? as f64;
+            let idf = (1.0
+                + (num_documents - document_frequency + 0.5) / (document_frequency + 0.5))
+                .ln();
+            terms.push((token, idf));
+        }
+
+        Ok(Some(Bm25 {
+            field_index: *field_index,
+            analyzer: analyzer.clone(),
+            terms,
+            average_len: if stats.num_documents == 0 {
+                0.0
+            } else {
+                stats.num_tokens as f64 / num_documents
+            },
+        }))
+    }
+
+    /// `after` is the index key and id to continue after. If the key is `None`, the scan must be a single key scan and its key is used.
+    ///
+    /// Full text scans return records in id order, so they only use the id.
+    fn query_with_secondary_index(
+        &'a self,
+        index_scan: &IndexScan,
+        after: Option<(Option<&[u8]>, u64)>,
+    ) -> Result<impl Iterator<Item = u64> + 'a, CacheError> {
+        let index = self.secondary_index(index_scan.index_id)?;
+
+        if let IndexScanKind::FullText { filter } = &index_scan.kind {
+            let IndexDefinition::FullText(_, analyzer) =
+                &self.secondary_indexes[index_scan.index_id]
+            else {
+                panic!("full text scan must scan a full text index");
+            };
+            let mut iterators = vec![];
+            for token in index::analyze(analyzer, full_text_query(&filter.val)?)
+                .into_iter()
+                .unique()
+            {
+                let key = index::get_full_text_secondary_index(&token);
+                iterators.push(self.full_text_token_ids(index, key, after.map(|(_, id)| id))?);
+            }
+            let ids = if iterators.is_empty() {
+                // A query without tokens matches nothing.
+                Either::Left(Either::Left(std::iter::empty()))
+            } else if filter.op == Operator::MatchesAny {
+                Either::Left(Either::Right(iterators.into_iter().kmerge().dedup()))
+            } else {
+                Either::Right(intersection(
+                    iterators,
+                    self.storage.intersection_chunk_size(),
+                ))
+            };
+            return Ok(Either::Left(Either::Right(ids)));
+        }
+
+        if let IndexScanKind::Geo { filter } = &index_scan.kind {
+            // Geo scans are only used in full scans, which don't continue after an index key.
+            debug_assert!(after.is_none());
+            let mut iterators = vec![];
+            for (min, max) in filter.shape.bounding_boxes() {
+                for (start, end) in index::get_geo_key_ranges(&min, &max) {
+                    iterators.push(
+                        self.storage
+                            .index_entries(
+                                index,
+                                Some(KeyEndpoint::Including(start)),
+                                SortDirection::Ascending,
+                            )?
+                            .take_while(move |(key, _)| *key <= end.as_slice())
+                            .map(|(_, id)| id),
+                    );
+                }
+            }
+            return Ok(Either::Left(Either::Left(iterators.into_iter().flatten())));
+        }
+
+        let RangeSpec {
+            start,
+            end,
+            direction,
+        } = get_range_spec(&index_scan.kind, index_scan.is_single_field_sorted_inverted)?;
+
+        let index_definition = &self.secondary_indexes[index_scan.index_id];
+        let after = after.and_then(|(key, id)| {
+            let key = match key {
+                Some(key) => key,
+                None => start.as_ref()?.key(),
+            };
+            // A cursor before the start of the range, which no query result produces, is ignored.
+            let before_start = start.as_ref().map_or(false, |start| {
+                match (
+                    index::compare_secondary_index_keys(index_definition, key, start.key()),
+                    direction,
+                ) {
+                    (Ordering::Equal, _) => matches!(start, KeyEndpoint::Excluding(_)),
+                    (Ordering::Less, SortDirection::Ascending) => true,
+                    (Ordering::Greater, SortDirection::Descending) => true,
+                    _ => false,
+                }
+            });
+            (!before_start).then(|| (key.to_vec(), id))
+        });
+        let entries = match after {
+            Some((key, id)) => self
+                .storage
+                .index_entries_after(index, key, id, direction)?,
+            None => self.storage.index_entries(index, start, direction)?,
+        };
+
+        Ok(Either::Right(
+            entries
+                .take_while(move |(key, _)| {
+                    if let Some(end_key) = &end {
+                        match index::compare_secondary_index_keys(
+                            index_definition,
+                            key,
+                            end_key.key(),
+                        ) {
+                            Ordering::Less => matches!(direction, SortDirection::Ascending),
+                            Ordering::Equal => matches!(end_key, KeyEndpoint::Including(_)),
+                            Ordering::Greater => matches!(direction, SortDirection::Descending),
+                        }
+                    } else {
+                        true
+                    }
+                })
+                .map(|(_, id)| id),
+        ))
+    }
+
+    /// Applies the `skip` and `limit` of the query.
+    fn skip_and_limit(&self, iter: impl Iterator<Item = u64>) -> impl Iterator<Item = u64> {
+        skip(iter, self.query.skip).take(self.query.limit.unwrap_or(usize::MAX))
+    }
+
+    /// Returns the key of the record with `values` in the index that `index_scan` scans.
+    fn index_key(&self, index_scan: &IndexScan, values: &[Field]) -> Result<Vec<u8>, CacheError> {
+        match (
+            &index_scan.kind,
+            &self.secondary_indexes[index_scan.index_id],
+        ) {
+            (IndexScanKind::SortedInverted { .. }, IndexDefinition::SortedInverted(fields)) => {
+                let fields = fields
+                    .iter()
+                    .map(|field_index| &values[*field_index])
+                    .collect::<Vec<_>>();
+                Ok(index::get_secondary_index(
+                    &fields,
+                    index_scan.is_single_field_sorted_inverted,
+                ))
+            }
+            // Full text scans return records in id order.
+            (IndexScanKind::FullText { .. }, _) => Ok(vec![]),
+            // Geo scans are only used in full scans, which continue after sorted values.
+            _ => Err(PlanError::InvalidCursor.into()),
+        }
+    }
+
+    fn collect_records(
+        &self,
+        ids: impl Iterator<Item = u64>,
+    ) -> Result<Vec<RecordWithId>, CacheError> {
+        ids.map(|id| {
+            self.storage
+                .get_record(id)
+                .map(|record| RecordWithId::new(id, record))
+        })
+        .collect()
+    }
+}
+
+fn near_filter(full_scan: &FullScan) -> Option<&GeoFilter> {
+    full_scan
+        .geo_filters
+        .iter()
+        .find(|filter| filter.op == Operator::Near)
+}
+
+/// Returns the values a full scan sorts a record by: distance to the `$near` center if any, then the `order_by` fields.
+///
+/// A field sorted by relevance is replaced by its BM25 score.
+fn sort_key(full_scan: &FullScan, bm25: Option<&Bm25>, values: &[Field]) -> Vec<Field> {
+    near_filter(full_scan)
+        .map(|filter| filter.distance(values))
+        .into_iter()
+        .chain(
+            full_scan
+                .order_by
+                .iter()
+                .map(|(field_index, _)| match bm25 {
+                    Some(bm25) if bm25.field_index == *field_index => {
+                        Field::Float(OrderedFloat(bm25.score(values)))
+                    }
+                    _ => values[*field_index].clone(),
+                }),
+        )
+        .collect()
+}
+
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// Scores the relevance of a text field to the tokens of a full text filter.
+struct Bm25 {
+    field_index: usize,
+    analyzer: FullTextAnalyzer,
+    /// Analyzed filter tokens and their inverse document frequency.
+    terms: Vec<(String, f64)>,
+    /// Average number of tokens in a document of the index.
+    average_len: f64,
+}
+
+impl Bm25 {
+    fn score(&self, values: &[Field]) -> f64 {
+        let tokens = match &values[self.field_index] {
+            Field::String(text) | Field::Text(text) => index::analyze(&self.analyzer, text),
+            _ => vec![],
+        };
+        // Avoids dividing by zero if no document has any token.
+        let length_ratio = tokens.len() as f64 / self.average_len.max(1.0);
+        self.terms
+            .iter()
+            .map(|(term, idf)| {
+                let frequency = tokens
+                    .iter()
+                    .filter(|token| index::token_matches(&self.analyzer, token, term))
+                    .count() as f64;
+                idf * frequency * (BM25_K1 + 1.0)
+                    / (frequency + BM25_K1 * (1.0 - BM25_B + BM25_B * length_ratio))
+            })
+            .sum()
+    }
+}
+
+fn full_text_query(value: &Field) -> Result<&str, CacheError> {
+    match value {
+        Field::String(query) | Field::Text(query) => Ok(query),
+        _ => Err(CacheError::Index(IndexError::ExpectedStringFullText)),
+    }
+}
+
+/// Returns the direction of each value in `sort_key`. Nearest records come first.
+fn sort_directions(full_scan: &FullScan) -> Vec<SortDirection> {
+    near_filter(full_scan)
+        .map(|_| SortDirection::Ascending)
+        .into_iter()
+        .chain(full_scan.order_by.iter().map(|(_, direction)| *direction))
+        .collect()
+}
+
+/// Compares `(key, id)` pairs, `key` being the `sort_key` of a record.
+fn compare_sorted(
+    directions: &[SortDirection],
+    (a, a_id): (&[Field], u64),
+    (b, b_id): (&[Field], u64),
+) -> Ordering {
+    a.iter()
+        .zip(b)
+        .zip(directions)
+        .map(|((a, b), direction)| match direction {
+            SortDirection::Ascending => a.cmp(b),
+            SortDirection::Descending => b.cmp(a),
+        })
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or_else(|| a_id.cmp(&b_id))
+}
+
+fn is_single_key_scan(index_scan: &IndexScan) -> bool {
+    match &index_scan.kind {
+        IndexScanKind::SortedInverted { range_query, .. } => range_query.is_none(),
+        IndexScanKind::FullText { .. } => true,
+        IndexScanKind::Geo { .. } => false,
+    }
+}
+
+/// Returns the scan that orders the records of `index_scans`, whose key a cursor continues after.
+///
+/// Returns `None` for an intersection of single key scans, which is ordered by id.
+fn ordering_scan(index_scans: &[IndexScan]) -> Option<&IndexScan> {
+    if index_scans.len() == 1 {
+        return index_scans.first();
+    }
+    // The planner generates at most one range scan.
+    index_scans
+        .iter()
+        .find(|index_scan| !is_single_key_scan(index_scan))
+}
+
+/// Checks that `cursor` is of the kind `next_cursor` returns for `plan`.
+fn cursor_matches_plan(cursor: &Cursor, plan: &Plan) -> bool {
+    match (cursor, plan) {
+        (Cursor::Key { .. }, Plan::SeqScan(_)) => true,
+        (Cursor::Key { .. }, Plan::IndexScans(_)) => true,
+        (Cursor::Sorted { values, .. }, Plan::FullScan(full_scan)) => {
+            values.len() == sort_directions(full_scan).len()
+        }
+        (_, Plan::ReturnEmpty) => true,
+        _ => false,
+    }
+}
+
+#[derive(Debug)]
+struct RangeSpec {
+    start: Option<KeyEndpoint>,
+    end: Option<KeyEndpoint>,
+    direction: SortDirection,
+}
+
+fn get_range_spec(
+    index_scan_kind: &IndexScanKind,
+    is_single_field_sorted_inverted: bool,
+) -> Result<RangeSpec, CacheError> {
+    match &index_scan_kind {
+        IndexScanKind::SortedInverted {
+            eq_filters,
+            range_query,
+        } => {
+            let comparison_key = build_sorted_inverted_comparision_key(
+                eq_filters,
+                range_query.as_ref(),
+                is_single_field_sorted_inverted,
+            );
+            // There're 3 cases:
+            // 1. Range query with operator.
+            // 2. Range query without operator (only order by).
+            // 3. No range query.
+            Ok(if let Some(range_query) = range_query {
+                match range_query.operator_and_value {
+                    Some((operator, _)) => {
+                        // Here we respond to case 1, examples are `a = 1 && b > 2` or `b < 2`.
+                        let comparison_key = comparison_key.expect("here's at least a range query");
+                        let null_key = build_sorted_inverted_comparision_key(
+                            eq_filters,
+                            Some(&SortedInvertedRangeQuery {
+                                field_index: range_query.field_index,
+                                operator_and_value: Some((operator, Field::Null)),
+                                sort_direction: range_query.sort_direction,
+                            }),
+                            is_single_field_sorted_inverted,
+                        )
+                        .expect("we provided a range query");
+                        get_key_interval_from_range_query(
+                            comparison_key,
+                            null_key,
+                            operator,
+                            range_query.sort_direction,
+                        )
+                    }
+                    None => {
+                        // Here we respond to case 2, examples are `a = 1 && b asc` or `b desc`.
+                        if let Some(comparison_key) = comparison_key {
+                            // This is the case like `a = 1 && b asc`. The comparison key is only built from `a = 1`.
+                            // We use `a = 1 && b = null` as a sentinel, using the invariant that `null` is greater than anything.
+                            let null_key = build_sorted_inverted_comparision_key(
+                                eq_filters,
+                                Some(&SortedInvertedRangeQuery {
+                                    field_index: range_query.field_index,
+                                    operator_and_value: Some((Operator::LT, Field::Null)),
+                                    sort_direction: range_query.sort_direction,
+                                }),
+                                is_single_field_sorted_inverted,
+                            )
+                            .expect("we provided a range query");
+                            match range_query.sort_direction {
+                                SortDirection::Ascending => RangeSpec {
+                                    start: Some(KeyEndpoint::Excluding(comparison_key)),
+                                    end: Some(KeyEndpoint::Including(null_key)),
+                                    direction: SortDirection::Ascending,
+                                },
+                                SortDirection::Descending => RangeSpec {
+                                    start: Some(KeyEndpoint::Including(null_key)),
+                                    end: Some(KeyEndpoint::Excluding(comparison_key)),
+                                    direction: SortDirection::Descending,
+                                },
+                            }
+                        } else {
+                            // Just all of them.
+                            RangeSpec {
+                                start: None,
+                                end: None,
+                                direction: range_query.sort_direction,
+                            }
+                        }
+                    }
+                }
+            } else {
+                // Here we respond to case 3, examples are `a = 1` or `a = 1 && b = 2`.
+                let comparison_key = comparison_key
+                    .expect("here's at least a eq filter because there's no range query");
+                RangeSpec {
+                    start: Some(KeyEndpoint::Including(comparison_key.clone())),
+                    end: Some(KeyEndpoint::Including(comparison_key)),
+                    direction: SortDirection::Ascending, // doesn't matter
+                }
+            })
+        }
+        IndexScanKind::FullText { .. } => panic!("full text scans read a key per token"),
+        IndexScanKind::Geo { .. } => panic!("geo index scans cover many key ranges"),
+    }
+}
+
+fn build_sorted_inverted_comparision_key(
+    eq_filters: &[(usize, Field)],
+    range_query: Option<&SortedInvertedRangeQuery>,
+    is_single_field_index: bool,
+) -> Option<Vec<u8>> {
+    let mut fields = vec![];
+    eq_filters.iter().for_each(|filter| {
+        fields.push(&filter.1);
+    });
+    if let Some(range_query) = range_query {
+        if let Some((_, val)) = &range_query.operator_and_value {
+            fields.push(val);
+        }
+    }
+    if fields.is_empty() {
+        None
+    } else {
+        Some(index::get_secondary_index(&fields, is_single_field_index))
+    }
+}
+
+/// Here we use the invariant that `null` is greater than anything.
+fn get_key_interval_from_range_query(
+    comparison_key: Vec<u8>,
+    null_key: Vec<u8>,
+    operator: Operator,
+    sort_direction: SortDirection,
+) -> RangeSpec {
+    match (operator, sort_direction) {
+        (Operator::LT, SortDirection::Ascending) => RangeSpec {
+            start: None,
+            end: Some(KeyEndpoint::Excluding(comparison_key)),
+            direction: SortDirection::Ascending,
+        },
+        (Operator::LT, SortDirection::Descending) => RangeSpec {
+            start: Some(KeyEndpoint::Excluding(comparison_key)),
+            end: None,
+            direction: SortDirection::Descending,
+        },
+        (Operator::LTE, SortDirection::Ascending) => RangeSpec {
+            start: None,
+            end: Some(KeyEndpoint::Including(comparison_key)),
+            direction: SortDirection::Ascending,
+        },
+        (Operator::LTE, SortDirection::Descending) => RangeSpec {
+            start: Some(KeyEndpoint::Including(comparison_key)),
+            end: None,
+            direction: SortDirection::Descending,
+        },
+        (Operator::GT, SortDirection::Ascending) => RangeSpec {
+            start: Some(KeyEndpoint::Excluding(comparison_key)),
+            end: Some(KeyEndpoint::Excluding(null_key)),
+            direction: SortDirection::Ascending,
+        },
+        (Operator::GT, SortDirection::Descending) => RangeSpec {
+            start: Some(KeyEndpoint::Excluding(null_key)),
+            end: Some(KeyEndpoint::Excluding(comparison_key)),
+            direction: SortDirection::Descending,
+        },
+        (Operator::GTE, SortDirection::Ascending) => RangeSpec {
+            start: Some(KeyEndpoint::Including(comparison_key)),
+            end: Some(KeyEndpoint::Excluding(null_key)),
+            direction: SortDirection::Ascending,
+        },
+        (Operator::GTE, SortDirection::Descending) => RangeSpec {
+            start: Some(KeyEndpoint::Excluding(null_key)),
+            end: Some(KeyEndpoint::Including(comparison_key)),
+            direction: SortDirection::Descending,
+        },
+        (other, _) => {
+            panic!("operator {other:?} is not supported by sorted inverted index range query")
+        }
+    }
+}
+
+fn skip(iter: impl Iterator<Item = u64>, skip: Skip) -> impl Iterator<Item = u64> {
+    match skip {
+        Skip::Skip(n) => Either::Left(iter.skip(n)),
+        Skip::After(after) => Either::Right(skip_after(iter, after)),
+    }
+}
+
+fn skip_after(iter: impl Iterator<Item = u64>, after: u64) -> impl Iterator<Item = u64> {
+    iter.skip_while(move |id| *id != after).skip(1)
+}
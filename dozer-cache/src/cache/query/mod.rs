@@ -0,0 +1,77 @@
+use dozer_types::types::{Record, SchemaIdentifier};
+
+use super::expression::SortDirection;
+use crate::errors::CacheError;
+
+mod handler;
+mod intersection;
+
+pub use handler::QueryHandler;
+
+#[derive(Debug, Clone)]
+pub enum KeyEndpoint {
+    Including(Vec<u8>),
+    Excluding(Vec<u8>),
+}
+
+impl KeyEndpoint {
+    pub fn key(&self) -> &[u8] {
+        match self {
+            KeyEndpoint::Including(key) => key,
+            KeyEndpoint::Excluding(key) => key,
+        }
+    }
+}
+
+/// Corpus statistics that BM25 scoring needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FullTextStats {
+    pub num_documents: u64,
+    /// Total number of analyzed tokens, counting duplicates and excluding prefixes.
+    pub num_tokens: u64,
+}
+
+/// A secondary index of a schema, identified by the schema and the position of the index definition.
+pub type SecondaryIndexId = (SchemaIdentifier, usize);
+
+/// `(key, id)` entries of a secondary index, ordered by key as `index::compare_secondary_index_keys` compares them, then by id.
+pub type IndexEntries<'a> = Box<dyn Iterator<Item = (&'a [u8], u64)> + 'a>;
+
+/// The reads `QueryHandler` makes, implemented by each cache backend on a consistent view of the cache.
+pub trait QueryStorage {
+    /// The chunk size when calculating intersection of index queries.
+    fn intersection_chunk_size(&self) -> usize;
+    /// The max number of records a query can scan when no index fully answers it.
+    fn max_scanned_rows(&self) -> usize;
+
+    fn count_records(&self) -> Result<usize, CacheError>;
+    fn get_record(&self, id: u64) -> Result<Record, CacheError>;
+    /// Returns the ids of the cache in primary key order, or in id order for records without primary key.
+    ///
+    /// Continues after the primary key, or the id bytes, `after` if set.
+    fn ids(&self, after: Option<&[u8]>) -> Result<Box<dyn Iterator<Item = u64> + '_>, CacheError>;
+
+    /// Returns the entries of secondary index `index` in `direction`, beginning at `start` or at the first entry in that direction.
+    fn index_entries(
+        &self,
+        index: SecondaryIndexId,
+        start: Option<KeyEndpoint>,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError>;
+    /// Returns the entries of secondary index `index` in `direction`, beginning right after `(key, id)`, which may not exist anymore.
+    fn index_entries_after(
+        &self,
+        index: SecondaryIndexId,
+        key: Vec<u8>,
+        id: u64,
+        direction: SortDirection,
+    ) -> Result<IndexEntries<'_>, CacheError>;
+    /// Returns the number of documents and tokens in full text index `index`.
+    fn full_text_stats(&self, index: SecondaryIndexId) -> Result<FullTextStats, CacheError>;
+    /// Returns the number of documents indexed under `key` in full text index `index`.
+    fn full_text_document_frequency(
+        &self,
+        index: SecondaryIndexId,
+        key: &[u8],
+    ) -> Result<u64, CacheError>;
+}
@@ -0,0 +1,166 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use dozer_types::chrono::{self, DateTime, Utc};
+use dozer_types::log::error;
+use dozer_types::types::{FieldType, Record, Schema};
+
+use crate::errors::CacheError;
+
+/// Which records of an append-only schema expire. A record expires if any of the limits says so.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RetentionPolicy {
+    /// Records whose timestamp field, given by index, is older than the duration expire.
+    pub max_age: Option<(usize, Duration)>,
+    /// Only the newest records up to this count are kept.
+    pub max_count: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionOptions {
+    /// How often expired records are looked for.
+    pub interval: Duration,
+    /// The max number of records deleted and committed at a time, while holding the write transaction.
+    pub batch_size: usize,
+}
+
+impl Default for RetentionOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            batch_size: 1000,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Checks that the policy can be applied to `schema`, which must be append-only and the only schema of its cache.
+    pub(crate) fn validate(
+        &self,
+        schema_name: &str,
+        schema: &Schema,
+        num_schemas: usize,
+    ) -> Result<(), CacheError> {
+        if !schema.primary_index.is_empty() {
+            return Err(CacheError::InvalidRetentionPolicy(format!(
+                "{schema_name} has a primary key, only append-only schemas can expire records"
+            )));
+        }
+        if num_schemas != 1 {
+            return Err(CacheError::InvalidRetentionPolicy(format!(
+                "{schema_name} is not the only schema in the cache"
+            )));
+        }
+        if let Some((field_index, _)) = self.max_age {
+            let field_type = schema.fields.get(field_index).map(|field| field.typ);
+            if field_type != Some(FieldType::Timestamp) {
+                return Err(CacheError::InvalidRetentionPolicy(format!(
+                    "field {field_index} of {schema_name} is not a timestamp"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the expiry check for a batch of deletions starting now.
+    pub(crate) fn expiry(&self) -> Expiry<'_> {
+        Expiry {
+            policy: self,
+            expire_before: self.max_age.and_then(|(_, max_age)| {
+                chrono::Duration::from_std(max_age)
+                    .ok()
+                    .and_then(|max_age| Utc::now().checked_sub_signed(max_age))
+            }),
+        }
+    }
+}
+
+pub(crate) struct Expiry<'a> {
+    policy: &'a RetentionPolicy,
+    expire_before: Option<DateTime<Utc>>,
+}
+
+impl<'a> Expiry<'a> {
+    /// Checks the oldest remaining record, when there're `num_records` records.
+    ///
+    /// Records are assumed to be inserted roughly in time order, so retention stops at the first record that's not expired.
+    pub fn is_expired(&self, record: &Record, num_records: u64) -> bool {
+        if let Some(max_count) = self.policy.max_count {
+            if num_records > max_count {
+                return true;
+            }
+        }
+        match (self.policy.max_age, self.expire_before) {
+            (Some((field_index, _)), Some(expire_before)) => record.values[field_index]
+                .as_timestamp()
+                .map_or(false, |timestamp| {
+                    timestamp.with_timezone(&Utc) < expire_before
+                }),
+            _ => false,
+        }
+    }
+}
+
+/// Called with the records deleted by each batch of a retention job.
+pub type OnExpired = Box<dyn Fn(Vec<Record>) + Send>;
+
+/// Calls `delete_expired` on a background thread until dropped.
+#[derive(Debug)]
+pub(crate) struct RetentionJob {
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RetentionJob {
+    /// `delete_expired` deletes at most the given number of expired records and returns the deleted records, which are passed to `on_expired`.
+    pub fn start(
+        delete_expired: impl Fn(usize) -> Result<Vec<Record>, CacheError> + Send + 'static,
+        options: RetentionOptions,
+        on_expired: OnExpired,
+    ) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || loop {
+            match stopped.recv_timeout(options.interval) {
+                Err(RecvTimeoutError::Timeout) => loop {
+                    // The write transaction is released between batches so the sink isn't blocked for long.
+                    match delete_expired(options.batch_size) {
+                        Ok(records) => {
+                            let num_deleted = records.len();
+                            if num_deleted > 0 {
+                                on_expired(records);
+                            }
+                            if num_deleted < options.batch_size {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            error!("Failed to delete expired records: {e}");
+                            break;
+                        }
+                    }
+                    if !matches!(stopped.try_recv(), Err(TryRecvError::Empty)) {
+                        return;
+                    }
+                },
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        });
+        Self {
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for RetentionJob {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the thread up.
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                error!("Retention thread panicked");
+            }
+        }
+    }
+}
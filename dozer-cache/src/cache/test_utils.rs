@@ -76,7 +76,10 @@ pub fn schema_full_text() -> (Schema, Vec<IndexDefinition>) {
             ],
             primary_index: vec![0],
         },
-        vec![IndexDefinition::FullText(0), IndexDefinition::FullText(1)],
+        vec![
+            IndexDefinition::FullText(0, Default::default()),
+            IndexDefinition::FullText(1, Default::default()),
+        ],
     )
 }
 
@@ -119,7 +122,34 @@ pub fn schema_multi_indices() -> (Schema, Vec<IndexDefinition>) {
         },
         vec![
             IndexDefinition::SortedInverted(vec![0]),
-            IndexDefinition::FullText(1),
+            IndexDefinition::FullText(1, Default::default()),
+        ],
+    )
+}
+
+pub fn schema_geo() -> (Schema, Vec<IndexDefinition>) {
+    (
+        Schema {
+            identifier: Some(SchemaIdentifier { id: 5, version: 1 }),
+            fields: vec![
+                FieldDefinition {
+                    name: "name".to_string(),
+                    typ: dozer_types::types::FieldType::String,
+                    nullable: false,
+                    source: SourceDefinition::Dynamic,
+                },
+                FieldDefinition {
+                    name: "location".to_string(),
+                    typ: dozer_types::types::FieldType::Point,
+                    nullable: true,
+                    source: SourceDefinition::Dynamic,
+                },
+            ],
+            primary_index: vec![0],
+        },
+        vec![
+            IndexDefinition::SortedInverted(vec![0]),
+            IndexDefinition::Geo(1),
         ],
     )
 }
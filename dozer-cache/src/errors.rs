@@ -3,7 +3,9 @@ use dozer_types::thiserror::Error;
 
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
-use dozer_types::types::SchemaIdentifier;
+use dozer_types::types::{FieldType, SchemaIdentifier};
+
+use crate::cache::expression::AggregateFunction;
 
 #[derive(Error, Debug)]
 pub enum CacheError {
@@ -31,6 +33,20 @@ pub enum CacheError {
     PathNotInitialized,
     #[error("Secondary index database is not found")]
     SecondaryIndexDatabaseNotFound,
+    #[error("Query scanned more than {0} records, add a secondary index matching its filter and sort options")]
+    ScanLimitExceeded(usize),
+    #[error("Invalid retention policy: {0}")]
+    InvalidRetentionPolicy(String),
+    #[error("Cache is not present: {0}")]
+    CacheNotFound(String),
+    #[error("Invalid cache snapshot: {0}")]
+    InvalidSnapshot(String),
+    #[error("Cache was created by an incompatible version, delete it so that it's rebuilt")]
+    IncompatibleCacheFormat,
+    #[error("Record is not present")]
+    RecordNotFound,
+    #[error("Record already exists")]
+    RecordExists,
 }
 
 impl CacheError {
@@ -104,6 +120,20 @@ pub enum PlanError {
     ConflictingSortOptions,
     #[error("Cannot have more than one range query")]
     RangeQueryLimit,
-    #[error("Matching index not found")]
-    MatchingIndexNotFound,
+    #[error("Cannot apply {0:?} to field {1} of type {2:?}")]
+    UnsupportedAggregate(AggregateFunction, String, FieldType),
+    #[error("Field {0} appears more than once in aggregation output")]
+    DuplicateAggregateField(String),
+    #[error("$after and $cursor cannot be used with aggregation")]
+    AfterWithAggregation,
+    #[error("Sum {0} overflows its type")]
+    SumOverflow(String),
+    #[error("$cursor was not returned by this query")]
+    InvalidCursor,
+    #[error("Invalid geo filter: {0}")]
+    InvalidGeoFilter(String),
+    #[error("Geo filters can only be applied to point fields, {0} is not a point")]
+    NotAPointField(String),
+    #[error("Cannot have more than one $near filter")]
+    MultipleNearFilters,
 }
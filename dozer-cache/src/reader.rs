@@ -1,4 +1,7 @@
-use crate::cache::{expression::QueryExpression, RecordWithId, RoCache};
+use crate::cache::{
+    expression::{Cursor, QueryExpression},
+    Plan, RecordWithId, RoCache,
+};
 
 use super::cache::expression::FilterExpression;
 use crate::errors::CacheError;
@@ -65,6 +68,16 @@ impl CacheReader {
         self.cache.query(schema_name, query)
     }
 
+    pub fn aggregate(
+        &self,
+        schema_name: &str,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+    ) -> Result<(Schema, Vec<Record>), CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.aggregate(schema_name, query)
+    }
+
     pub fn count(
         &self,
         schema_name: &str,
@@ -75,6 +88,26 @@ impl CacheReader {
         self.cache.count(schema_name, query)
     }
 
+    pub fn explain(
+        &self,
+        schema_name: &str,
+        query: &mut QueryExpression,
+        access_filter: AccessFilter,
+    ) -> Result<Plan, CacheError> {
+        self.apply_access_filter(query, access_filter);
+        self.cache.explain(schema_name, query)
+    }
+
+    /// `query` must be the one passed to `query`, which has the access filter applied.
+    pub fn next_cursor(
+        &self,
+        schema_name: &str,
+        query: &QueryExpression,
+        records: &[RecordWithId],
+    ) -> Result<Option<Cursor>, CacheError> {
+        self.cache.next_cursor(schema_name, query, records)
+    }
+
     // Apply filter if specified in access
     fn apply_access_filter(&self, query: &mut QueryExpression, access_filter: AccessFilter) {
         // TODO: Use `fields` in `access_filter`.
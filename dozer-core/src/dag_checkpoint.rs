@@ -104,6 +104,16 @@ impl<T> DagCheckpoint<T> {
             }
         }
 
+        // Restore source states, after the checks above that may have cleared them.
+        for node_index in node_indexes {
+            let Some(state) = dag_metadata.node_weight_mut(node_index).source_state.take() else {
+                continue;
+            };
+            if let Some((_, Some(source))) = storage_and_sources[node_index.index()].as_mut() {
+                source.restore_state(state)?;
+            }
+        }
+
         // Create new graph.
         let graph = dag_metadata.into_graph().map_owned(
             |node_index, node| {
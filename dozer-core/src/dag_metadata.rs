@@ -24,6 +24,7 @@ pub(crate) const METADATA_DB_NAME: &str = "__META__";
 const SOURCE_ID_IDENTIFIER: u8 = 0_u8;
 pub(crate) const OUTPUT_SCHEMA_IDENTIFIER: u8 = 1_u8;
 pub(crate) const INPUT_SCHEMA_IDENTIFIER: u8 = 2_u8;
+const SOURCE_STATE_IDENTIFIER: u8 = 3_u8;
 
 #[derive(Debug, Clone)]
 /// A node's storage environment.
@@ -40,6 +41,8 @@ pub struct NodeType<T> {
     pub handle: NodeHandle,
     /// Checkpoint read from the storage, or empty if the storage isn't created yet.
     pub commits: SourceStates,
+    /// State the source attached to its checkpoint, if the node is a source and has one.
+    pub source_state: Option<Vec<u8>>,
     /// The node's storage, if it was created in last dozer run.
     pub storage: Option<NodeStorage>,
     /// The node kind.
@@ -83,7 +86,7 @@ impl<T> DagMetadata<T> {
                     node.handle,
                     SourceStates::new()
                 );
-                metadata.push(Some((None, SourceStates::new())));
+                metadata.push(Some((None, SourceStates::new(), None)));
                 continue;
             }
 
@@ -97,6 +100,7 @@ impl<T> DagMetadata<T> {
             let master_txn = env.create_txn()?;
 
             let mut commits = SourceStates::new();
+            let mut source_state = None;
             let mut input_schemas: HashMap<PortHandle, Schema> = HashMap::new();
             let mut output_schemas: HashMap<PortHandle, Schema> = HashMap::new();
 
@@ -119,6 +123,9 @@ impl<T> DagMetadata<T> {
                             let (source, op_id) = deserialize_source_metadata(key, value);
                             commits.insert(source, op_id);
                         }
+                        SOURCE_STATE_IDENTIFIER => {
+                            source_state = Some(value.to_vec());
+                        }
                         OUTPUT_SCHEMA_IDENTIFIER => {
                             let (handle, schema) = deserialize_schema(key, value)?;
                             output_schemas.insert(handle, schema);
@@ -164,17 +171,19 @@ impl<T> DagMetadata<T> {
                     meta_db,
                 }),
                 commits,
+                source_state,
             )));
         }
 
         let graph = dag_schemas.into_graph().map_owned(
             |node_index, node| {
-                let (storage, commits) = metadata[node_index.index()]
+                let (storage, commits, source_state) = metadata[node_index.index()]
                     .take()
                     .expect("We loaded all metadata");
                 NodeType {
                     handle: node.handle,
                     commits,
+                    source_state,
                     storage,
                     kind: node.kind,
                 }
@@ -189,6 +198,7 @@ impl<T> DagMetadata<T> {
             let env_name = node_environment_name(&node.handle);
             LmdbEnvironmentManager::remove(&self.path, &env_name);
             node.commits.clear();
+            node.source_state = None;
         }
     }
 
@@ -243,6 +253,44 @@ impl<T> DagMetadata<T> {
         })
     }
 
+    /// Creates the storages of a DAG that has none, with every node at `checkpoint`.
+    ///
+    /// Sources missing from `checkpoint` start from the beginning. Returns `false` without
+    /// writing anything if any node already has a storage.
+    pub fn initialize_checkpoint(
+        &self,
+        checkpoint: &SourceStates,
+        storage_options: LmdbEnvironmentOptions,
+    ) -> Result<bool, StorageError> {
+        if self.graph.node_weights().any(|node| node.storage.is_some()) {
+            return Ok(false);
+        }
+
+        let mut all_commits: HashMap<NodeIndex, SourceStates> = HashMap::new();
+        for node_index in Topo::new(&self.graph).iter(&self.graph) {
+            let node = &self.graph[node_index];
+            let mut commits = SourceStates::new();
+            if let NodeKind::Source(_) = node.kind {
+                if let Some(op_id) = checkpoint.get(&node.handle) {
+                    commits.insert(node.handle.clone(), *op_id);
+                }
+            }
+            for edge in self.graph.edges_directed(node_index, Direction::Incoming) {
+                commits.extend(all_commits[&edge.source()].clone().into_iter());
+            }
+
+            let storage = self.initialize_node_storage(node_index, storage_options)?;
+            write_source_metadata(
+                &mut storage.master_txn.write(),
+                storage.meta_db,
+                commits.iter().map(|(source, op_id)| (source, *op_id)),
+            )?;
+            storage.master_txn.write().commit_and_renew()?;
+            all_commits.insert(node_index, commits);
+        }
+        Ok(true)
+    }
+
     pub fn check_consistency(&self) -> bool {
         for node_index in Topo::new(&self.graph).iter(&self.graph) {
             let mut all_parent_commits = SourceStates::new();
@@ -324,6 +372,17 @@ pub fn write_source_metadata<'a>(
     Ok(())
 }
 
+pub fn write_source_state(
+    txn: &mut LmdbExclusiveTransaction,
+    db: Database,
+    source: &NodeHandle,
+    state: &[u8],
+) -> Result<(), StorageError> {
+    let mut key: Vec<u8> = vec![SOURCE_STATE_IDENTIFIER];
+    key.extend(source.to_bytes());
+    txn.put(db, &key, state)
+}
+
 fn serialize_source_metadata(node_handle: &NodeHandle, op_id: OpIdentifier) -> (Vec<u8>, [u8; 16]) {
     let mut key: Vec<u8> = vec![SOURCE_ID_IDENTIFIER];
     key.extend(node_handle.to_bytes());
@@ -166,6 +166,24 @@ pub enum SinkError {
 
     #[error("Failed to count thre records during init in Cache: {0:?}, Error: {1:?}")]
     CacheCountFailed(String, #[source] BoxedError),
+
+    #[error("Failed to start retention in Cache: {0:?}, Error: {1:?}")]
+    CacheStartRetentionFailed(String, #[source] BoxedError),
+
+    #[error("Unsupported format {1:?} for sink of endpoint {0:?}.")]
+    UnsupportedSinkFormat(String, String),
+
+    #[error("Sink of endpoint {0:?} requires a primary key.")]
+    SinkPrimaryKeyMissing(String),
+
+    #[error("Failed to connect sink of endpoint {0:?}, Error: {1:?}.")]
+    SinkConnectionFailed(String, #[source] BoxedError),
+
+    #[error("Failed to write sink of endpoint {0:?}, Error: {1:?}.")]
+    SinkWriteFailed(String, #[source] BoxedError),
+
+    #[error("Failed to restore state of sink of endpoint {0:?}, Error: {1:?}.")]
+    SinkRestoreFailed(String, #[source] BoxedError),
 }
 
 #[derive(Error, Debug)]
@@ -5,7 +5,8 @@ use crate::errors::ExecutionError;
 use crate::Dag;
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
-use dozer_types::node::NodeHandle;
+use dozer_storage::lmdb_storage::LmdbEnvironmentOptions;
+use dozer_types::node::{NodeHandle, SourceStates};
 use dozer_types::types::Operation;
 
 use crate::epoch::Epoch;
@@ -99,6 +100,26 @@ impl DagExecutor {
         Ok(())
     }
 
+    /// Initializes the state of a pipeline that has none at `checkpoint`, so it resumes from there.
+    ///
+    /// Returns `false` if the pipeline already has a state, which is left as is.
+    pub fn initialize_checkpoint<T: Clone + Debug>(
+        dag: Dag<T>,
+        path: PathBuf,
+        options: &ExecutorOptions,
+        checkpoint: &SourceStates,
+    ) -> Result<bool, ExecutionError> {
+        let dag_schemas = DagSchemas::new(dag)?;
+        let dag_metadata = DagMetadata::new(dag_schemas, path)?;
+        Ok(dag_metadata.initialize_checkpoint(
+            checkpoint,
+            LmdbEnvironmentOptions {
+                max_map_sz: options.max_map_size,
+                ..LmdbEnvironmentOptions::default()
+            },
+        )?)
+    }
+
     pub fn start(self, running: Arc<AtomicBool>) -> Result<DagExecutorJoinHandle, ExecutionError> {
         // Construct execution dag.
         let mut execution_dag =
@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use super::dag_metadata::write_source_metadata;
+use super::dag_metadata::{write_source_metadata, write_source_state};
 
 #[derive(Debug)]
 pub(crate) struct StateWriter {
@@ -47,6 +47,15 @@ impl StateWriter {
         }
     }
 
+    fn store_source_state(
+        &mut self,
+        source: &NodeHandle,
+        state: &[u8],
+    ) -> Result<(), ExecutionError> {
+        write_source_state(&mut self.tx.write(), self.meta_db, source, state)?;
+        Ok(())
+    }
+
     pub fn store_commit_info(&mut self, epoch_details: &Epoch) -> Result<(), ExecutionError> {
         write_source_metadata(
             &mut self.tx.write(),
@@ -148,6 +157,8 @@ pub(crate) struct SourceChannelManager {
     manager: ChannelManager,
     curr_txid: u64,
     curr_seq_in_tx: u64,
+    /// State attached to the messages since last commit, if any.
+    curr_state: Option<Vec<u8>>,
     commit_sz: u32,
     num_uncommitted_ops: u32,
     max_duration_between_commits: Duration,
@@ -171,6 +182,7 @@ impl SourceChannelManager {
             // FIXME: Read curr_txid and curr_seq_in_tx from persisted state.
             curr_txid: 0,
             curr_seq_in_tx: 0,
+            curr_state: None,
             source_handle: owner,
             commit_sz,
             num_uncommitted_ops: 0,
@@ -190,6 +202,12 @@ impl SourceChannelManager {
             .epoch_manager
             .wait_for_epoch_close(request_termination, self.num_uncommitted_ops > 0);
         if let Some(epoch_id) = epoch {
+            // The state is committed with the checkpoint, which it must be consistent with.
+            if let Some(state) = self.curr_state.take() {
+                self.manager
+                    .state_writer
+                    .store_source_state(&self.source_handle, &state)?;
+            }
             self.manager.store_and_send_commit(&Epoch::from(
                 epoch_id,
                 self.source_handle.clone(),
@@ -222,6 +240,9 @@ impl SourceChannelManager {
         //
         self.curr_txid = message.identifier.txid;
         self.curr_seq_in_tx = message.identifier.seq_in_tx;
+        if let Some(state) = message.state {
+            self.curr_state = Some(state);
+        }
         match message.kind {
             IngestionMessageKind::OperationEvent(op) => {
                 self.manager.send_op(op, port)?;
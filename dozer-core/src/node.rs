@@ -58,6 +58,11 @@ pub trait Source: Send + Sync + Debug {
     /// Checks if the source can start from the given checkpoint.
     /// If this function returns false, the executor will start the source from the beginning.
     fn can_start_from(&self, last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError>;
+    /// Restores the state the source attached to its last checkpointed message.
+    /// Called before `start` if the source is resumed and has a state.
+    fn restore_state(&mut self, _state: Vec<u8>) -> Result<(), ExecutionError> {
+        Ok(())
+    }
     fn start(
         &self,
         fw: &mut dyn SourceChannelForwarder,
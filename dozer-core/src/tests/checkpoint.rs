@@ -1,13 +1,13 @@
 use crate::chk;
 use crate::dag_metadata::DagMetadata;
-use crate::dag_schemas::DagSchemas;
+use crate::dag_schemas::{DagHaveSchemas, DagSchemas};
 use crate::executor::{DagExecutor, ExecutorOptions};
 use crate::tests::dag_base_run::NoopJoinProcessorFactory;
 use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
 use crate::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
 use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
 use dozer_storage::lmdb_storage::LmdbEnvironmentManager;
-use dozer_types::node::NodeHandle;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
 
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -90,6 +90,20 @@ fn test_checkpoint_consistency() {
     let dag_metadata = DagMetadata::new(dag_schemas.clone(), tmp_dir.path().to_path_buf()).unwrap();
     assert!(dag_metadata.check_consistency());
 
+    // Sources' states are stored with their checkpoints
+    let source_state = |id: &str| {
+        dag_metadata
+            .graph()
+            .node_weights()
+            .find(|node| node.handle == NodeHandle::new(Some(1), id.to_string()))
+            .and_then(|node| node.source_state.clone())
+    };
+    assert_eq!(
+        source_state(SRC1_HANDLE_ID),
+        Some(SRC1_MSG_COUNT.to_be_bytes().to_vec())
+    );
+    assert_eq!(source_state(PROC_HANDLE_ID), None);
+
     LmdbEnvironmentManager::remove(tmp_dir.path(), format!("{proc_handle}").as_str());
     let dag_metadata = DagMetadata::new(dag_schemas, tmp_dir.path().to_path_buf()).unwrap();
     assert!(!dag_metadata.check_consistency());
@@ -205,3 +219,70 @@ fn test_checkpoint_consistency_resume() {
     let dag_metadata = DagMetadata::new(dag_schemas, tmp_dir.path().to_path_buf()).unwrap();
     assert!(dag_metadata.check_consistency());
 }
+
+#[test]
+fn test_checkpoint_initialize() {
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Arc::new(GeneratorSourceFactory::new(5, latch.clone(), false).without_state()),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Arc::new(CountingSinkFactory::new(5, latch)),
+    );
+
+    chk!(dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle.clone(), COUNTING_SINK_INPUT_PORT),
+    ));
+
+    // The pipeline is initialized at the checkpoint of a restored sink, as if it had run before
+    let tmp_dir = chk!(TempDir::new("test"));
+    let checkpoint = [(source_handle.clone(), OpIdentifier::new(10, 0))]
+        .into_iter()
+        .collect::<SourceStates>();
+    let options = ExecutorOptions::default();
+    assert!(DagExecutor::initialize_checkpoint(
+        dag.clone(),
+        tmp_dir.path().to_path_buf(),
+        &options,
+        &checkpoint,
+    )
+    .unwrap());
+    assert!(!DagExecutor::initialize_checkpoint(
+        dag.clone(),
+        tmp_dir.path().to_path_buf(),
+        &options,
+        &checkpoint,
+    )
+    .unwrap());
+
+    DagExecutor::new(dag.clone(), tmp_dir.path().to_path_buf(), options)
+        .unwrap()
+        .start(Arc::new(AtomicBool::new(true)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    // The source resumed from the checkpoint instead of starting over
+    let dag_schemas = DagSchemas::new(dag).unwrap();
+    let dag_metadata = DagMetadata::new(dag_schemas, tmp_dir.path().to_path_buf()).unwrap();
+    assert!(dag_metadata.check_consistency());
+    let sink = dag_metadata
+        .graph()
+        .node_weights()
+        .find(|node| node.handle == sink_handle)
+        .unwrap();
+    assert_eq!(
+        sink.commits,
+        [(source_handle, OpIdentifier::new(15, 0))]
+            .into_iter()
+            .collect::<SourceStates>()
+    );
+}
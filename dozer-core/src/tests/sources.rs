@@ -22,6 +22,7 @@ pub(crate) struct GeneratorSourceFactory {
     count: u64,
     running: Arc<AtomicBool>,
     stateful: bool,
+    with_state: bool,
 }
 
 impl GeneratorSourceFactory {
@@ -30,8 +31,15 @@ impl GeneratorSourceFactory {
             count,
             running: barrier,
             stateful,
+            with_state: true,
         }
     }
+
+    /// Sends messages without state, and resumes from checkpoints without state.
+    pub fn without_state(mut self) -> Self {
+        self.with_state = false;
+        self
+    }
 }
 
 impl SourceFactory<NoneContext> for GeneratorSourceFactory {
@@ -85,6 +93,8 @@ impl SourceFactory<NoneContext> for GeneratorSourceFactory {
         Ok(Box::new(GeneratorSource {
             count: self.count,
             running: self.running.clone(),
+            with_state: self.with_state,
+            restored_state: None,
         }))
     }
 }
@@ -93,6 +103,8 @@ impl SourceFactory<NoneContext> for GeneratorSourceFactory {
 pub(crate) struct GeneratorSource {
     count: u64,
     running: Arc<AtomicBool>,
+    with_state: bool,
+    restored_state: Option<Vec<u8>>,
 }
 
 impl Source for GeneratorSource {
@@ -100,31 +112,46 @@ impl Source for GeneratorSource {
         Ok(true)
     }
 
+    fn restore_state(&mut self, state: Vec<u8>) -> Result<(), ExecutionError> {
+        self.restored_state = Some(state);
+        Ok(())
+    }
+
     fn start(
         &self,
         fw: &mut dyn SourceChannelForwarder,
         last_checkpoint: Option<(u64, u64)>,
     ) -> Result<(), ExecutionError> {
         let start = last_checkpoint.unwrap_or((0, 0)).0;
+        // The state of every message is its txid, so it must be restored with the checkpoint
+        if self.with_state {
+            assert_eq!(
+                self.restored_state,
+                last_checkpoint.map(|(txid, _)| txid.to_be_bytes().to_vec())
+            );
+        }
 
         for n in start + 1..(start + self.count + 1) {
-            fw.send(
-                IngestionMessage::new_op(
-                    n,
-                    0,
-                    Operation::Insert {
-                        new: Record::new(
-                            None,
-                            vec![
-                                Field::String(format!("key_{n}")),
-                                Field::String(format!("value_{n}")),
-                            ],
-                            None,
-                        ),
-                    },
-                ),
-                GENERATOR_SOURCE_OUTPUT_PORT,
-            )?;
+            let message = IngestionMessage::new_op(
+                n,
+                0,
+                Operation::Insert {
+                    new: Record::new(
+                        None,
+                        vec![
+                            Field::String(format!("key_{n}")),
+                            Field::String(format!("value_{n}")),
+                        ],
+                        None,
+                    ),
+                },
+            );
+            let message = if self.with_state {
+                message.with_state(n.to_be_bytes().to_vec())
+            } else {
+                message
+            };
+            fw.send(message, GENERATOR_SOURCE_OUTPUT_PORT)?;
         }
 
         loop {
@@ -353,6 +380,7 @@ impl SourceFactory<NoneContext> for NoPkGeneratorSourceFactory {
         Ok(Box::new(NoPkGeneratorSource {
             count: self.count,
             running: self.running.clone(),
+            restored_state: None,
         }))
     }
 }
@@ -361,6 +389,7 @@ impl SourceFactory<NoneContext> for NoPkGeneratorSourceFactory {
 pub(crate) struct NoPkGeneratorSource {
     count: u64,
     running: Arc<AtomicBool>,
+    restored_state: Option<Vec<u8>>,
 }
 
 impl Source for NoPkGeneratorSource {
@@ -368,12 +397,22 @@ impl Source for NoPkGeneratorSource {
         Ok(true)
     }
 
+    fn restore_state(&mut self, state: Vec<u8>) -> Result<(), ExecutionError> {
+        self.restored_state = Some(state);
+        Ok(())
+    }
+
     fn start(
         &self,
         fw: &mut dyn SourceChannelForwarder,
         last_checkpoint: Option<(u64, u64)>,
     ) -> Result<(), ExecutionError> {
         let start = last_checkpoint.unwrap_or((0, 0)).0;
+        // The state of every message is its txid, so it must be restored with the checkpoint
+        assert_eq!(
+            self.restored_state,
+            last_checkpoint.map(|(txid, _)| txid.to_be_bytes().to_vec())
+        );
 
         for n in start + 1..(start + self.count + 1) {
             fw.send(
@@ -390,7 +429,8 @@ impl Source for NoPkGeneratorSource {
                             None,
                         ),
                     },
-                ),
+                )
+                .with_state(n.to_be_bytes().to_vec()),
                 GENERATOR_SOURCE_OUTPUT_PORT,
             )?;
         }
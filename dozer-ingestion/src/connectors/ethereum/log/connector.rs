@@ -31,6 +31,7 @@ pub struct EthLogConnector {
 pub struct ContractTuple(pub Contract, pub String);
 
 pub const ETH_LOGS_TABLE: &str = "eth_logs";
+pub const EVENT_LOG_ID_COLUMN: &str = "log_id";
 impl EthLogConnector {
     pub fn build_filter(filter: &EthFilter) -> Filter {
         let builder = FilterBuilder::default();
@@ -171,6 +172,7 @@ impl Connector for EthLogConnector {
                 self.schema_map.to_owned(),
                 from_seq,
                 self.conn_name.clone(),
+                self.config.confirmations,
             ));
             run(details).await
         })
@@ -195,7 +197,10 @@ impl Connector for EthLogConnector {
         self.get_tables_default(tables)
     }
 
-    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError> {
-        Ok(false)
+    fn can_start_from(&self, (block_no, _): (u64, u64)) -> Result<bool, ConnectorError> {
+        // Checkpoints are `(block_number, seq_in_tx)`, resumable as long as the block is still in range
+        let filter = self.config.filter.to_owned().unwrap_or_default();
+        Ok(filter.from_block.map_or(true, |from| block_no >= from)
+            && filter.to_block.map_or(true, |to| block_no <= to))
     }
 }
@@ -1,17 +1,17 @@
 use dozer_types::log::error;
+use dozer_types::serde_json::{self, Value};
 use dozer_types::types::{
-    Field, FieldDefinition, FieldType, Operation, Record, ReplicationChangesTrackingType, Schema,
-    SchemaIdentifier, SourceDefinition, SourceSchema,
+    DozerBigInt, Field, FieldDefinition, FieldType, Operation, Record,
+    ReplicationChangesTrackingType, Schema, SchemaIdentifier, SourceDefinition, SourceSchema,
 };
 use std::collections::HashMap;
-use std::sync::Arc;
 
-use web3::ethabi::RawLog;
-use web3::types::{Log, H256};
+use web3::ethabi::{RawLog, Token};
+use web3::types::{Bytes, Log, H256, U256};
 
 use crate::connectors::TableInfo;
 
-use super::connector::{ContractTuple, ETH_LOGS_TABLE};
+use super::connector::{ContractTuple, ETH_LOGS_TABLE, EVENT_LOG_ID_COLUMN};
 use super::sender::EthDetails;
 
 pub fn get_contract_event_schemas(
@@ -30,11 +30,11 @@ pub fn get_contract_event_schemas(
                         web3::ethabi::ParamType::Address => FieldType::String,
                         web3::ethabi::ParamType::Bytes => FieldType::Binary,
                         web3::ethabi::ParamType::FixedBytes(_) => FieldType::Binary,
-                        web3::ethabi::ParamType::Int(_) => FieldType::UInt,
-                        web3::ethabi::ParamType::Uint(_) => FieldType::UInt,
+                        web3::ethabi::ParamType::Int(_) => FieldType::BigInt,
+                        web3::ethabi::ParamType::Uint(_) => FieldType::BigInt,
                         web3::ethabi::ParamType::Bool => FieldType::Boolean,
                         web3::ethabi::ParamType::String => FieldType::String,
-                        // TODO: These are to be mapped to appropriate types
+                        // Encoded as a JSON array, see `map_token_to_json`
                         web3::ethabi::ParamType::Array(_)
                         | web3::ethabi::ParamType::FixedArray(_, _)
                         | web3::ethabi::ParamType::Tuple(_) => FieldType::Text,
@@ -43,6 +43,14 @@ pub fn get_contract_event_schemas(
                     source: SourceDefinition::Dynamic,
                 });
             }
+            // Same id as in `eth_logs`, so that events can be retracted after a reorg
+            fields.push(FieldDefinition {
+                name: EVENT_LOG_ID_COLUMN.to_string(),
+                typ: FieldType::UInt,
+                nullable: false,
+                source: SourceDefinition::Dynamic,
+            });
+            let primary_index = vec![fields.len() - 1];
 
             let schema_id = schema_map
                 .get(&event.signature())
@@ -57,7 +65,7 @@ pub fn get_contract_event_schemas(
                         version: 1,
                     }),
                     fields,
-                    primary_index: vec![],
+                    primary_index,
                 },
                 ReplicationChangesTrackingType::Nothing,
             ));
@@ -72,7 +80,7 @@ pub fn decode_event(
     contracts: HashMap<String, ContractTuple>,
     tables: Vec<TableInfo>,
     schema_map: HashMap<H256, usize>,
-) -> Option<Operation> {
+) -> Option<Record> {
     let address = format!("{:?}", log.address);
 
     let mut c = contracts.get(&address);
@@ -107,6 +115,7 @@ pub fn decode_event(
             let table_name = get_table_name(contract_tuple, &event.name);
             let is_table_required = tables.iter().any(|t| t.table_name == table_name);
             if is_table_required {
+                let log_id = get_id(&log);
                 let parsed_event = event.parse_log(RawLog {
                     topics: log.topics,
                     data: log.data.0,
@@ -114,20 +123,19 @@ pub fn decode_event(
 
                 match parsed_event {
                     Ok(parsed_event) => {
-                        let values = parsed_event
+                        let mut values: Vec<Field> = parsed_event
                             .params
                             .into_iter()
                             .map(|p| map_abitype_to_field(p.value))
                             .collect();
-                        return Some(Operation::Insert {
-                            new: Record {
-                                schema_id: Some(SchemaIdentifier {
-                                    id: schema_id as u32,
-                                    version: 1,
-                                }),
-                                values,
-                                version: None,
-                            },
+                        values.push(Field::UInt(log_id));
+                        return Some(Record {
+                            schema_id: Some(SchemaIdentifier {
+                                id: schema_id as u32,
+                                version: 1,
+                            }),
+                            values,
+                            version: None,
                         });
                     }
                     Err(_) => {
@@ -150,27 +158,52 @@ pub fn get_table_name(contract_tuple: &ContractTuple, event_name: &str) -> Strin
     format!("{}_{}", contract_tuple.1, event_name)
 }
 
-pub fn map_abitype_to_field(f: web3::ethabi::Token) -> Field {
+pub fn map_abitype_to_field(f: Token) -> Field {
     match f {
-        web3::ethabi::Token::Address(f) => Field::String(format!("{f:?}")),
-        web3::ethabi::Token::FixedBytes(f) => Field::Binary(f),
-        web3::ethabi::Token::Bytes(f) => Field::Binary(f),
-        // TODO: Convert i64 appropriately
-        web3::ethabi::Token::Int(f) => Field::UInt(f.low_u64()),
-        web3::ethabi::Token::Uint(f) => Field::UInt(f.low_u64()),
-        web3::ethabi::Token::Bool(f) => Field::Boolean(f),
-        web3::ethabi::Token::String(f) => Field::String(f),
-        web3::ethabi::Token::FixedArray(f)
-        | web3::ethabi::Token::Array(f)
-        | web3::ethabi::Token::Tuple(f) => Field::Text(
-            f.iter()
-                .map(|f| f.to_string())
-                .collect::<Vec<String>>()
-                .join(","),
-        ),
+        Token::Address(f) => Field::String(format!("{f:?}")),
+        Token::FixedBytes(f) => Field::Binary(f),
+        Token::Bytes(f) => Field::Binary(f),
+        Token::Int(f) => Field::BigInt(map_int(f)),
+        Token::Uint(f) => Field::BigInt(map_uint(f)),
+        Token::Bool(f) => Field::Boolean(f),
+        Token::String(f) => Field::String(f),
+        f @ (Token::FixedArray(_) | Token::Array(_) | Token::Tuple(_)) => {
+            Field::Text(map_token_to_json(f).to_string())
+        }
     }
 }
-pub fn map_log_to_event(log: Log, details: Arc<EthDetails>) -> Option<Operation> {
+
+/// `int<M>` values are sign extended to 256 bits by the ABI, and ethabi keeps those bits as is.
+fn map_int(f: U256) -> DozerBigInt {
+    let mut bytes = [0_u8; 32];
+    f.to_big_endian(&mut bytes);
+    DozerBigInt::from_signed_bytes_be(&bytes).expect("256-bit integers always fit")
+}
+
+fn map_uint(f: U256) -> DozerBigInt {
+    let mut bytes = [0_u8; 32];
+    f.to_big_endian(&mut bytes);
+    DozerBigInt::from_unsigned_bytes_be(&bytes).expect("256-bit integers always fit")
+}
+
+/// Arrays and tuples become JSON arrays. Integers are written as decimal strings so that
+/// JSON parsers don't round them, and bytes as `0x` prefixed hex strings.
+pub fn map_token_to_json(f: Token) -> Value {
+    match f {
+        Token::Address(f) => Value::String(format!("{f:?}")),
+        Token::FixedBytes(f) | Token::Bytes(f) => {
+            serde_json::to_value(Bytes(f)).expect("bytes are serialized as a hex string")
+        }
+        Token::Int(f) => Value::String(map_int(f).to_string()),
+        Token::Uint(f) => Value::String(map_uint(f).to_string()),
+        Token::Bool(f) => Value::Bool(f),
+        Token::String(f) => Value::String(f),
+        Token::FixedArray(f) | Token::Array(f) | Token::Tuple(f) => {
+            Value::Array(f.into_iter().map(map_token_to_json).collect())
+        }
+    }
+}
+pub fn map_log_to_event(log: Log, details: &EthDetails) -> Option<Record> {
     // Check if table is requested
     let is_table_required = details
         .tables
@@ -181,18 +214,25 @@ pub fn map_log_to_event(log: Log, details: Arc<EthDetails>) -> Option<Operation>
         None
     } else if log.log_index.is_some() {
         let values = map_log_to_values(log);
-        Some(Operation::Insert {
-            new: Record {
-                schema_id: Some(SchemaIdentifier { id: 1, version: 1 }),
-                values,
-                version: None,
-            },
+        Some(Record {
+            schema_id: Some(SchemaIdentifier { id: 1, version: 1 }),
+            values,
+            version: None,
         })
     } else {
         None
     }
 }
 
+/// Logs from orphaned blocks are retracted with a `Delete` of the record that was inserted.
+pub fn map_record_to_operation(record: Record, removed: bool) -> Operation {
+    if removed {
+        Operation::Delete { old: record }
+    } else {
+        Operation::Insert { new: record }
+    }
+}
+
 pub fn get_id(log: &Log) -> u64 {
     let block_no = log
         .block_number
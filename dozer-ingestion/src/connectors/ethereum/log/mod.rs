@@ -1,6 +1,7 @@
 mod connector;
 mod helper;
 mod sender;
+mod tracker;
 pub use connector::EthLogConnector;
 
 #[cfg(test)]
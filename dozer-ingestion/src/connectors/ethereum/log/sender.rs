@@ -1,6 +1,6 @@
 use core::time;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::connectors::ethereum::log::connector::EthLogConnector;
 use crate::ingestion::Ingestor;
@@ -10,20 +10,24 @@ use crate::{
 };
 use dozer_types::ingestion_types::{EthFilter, IngestionMessage};
 use dozer_types::log::{debug, info, trace, warn};
+use dozer_types::types::Operation;
 
 use futures::StreamExt;
 
 use futures::future::{BoxFuture, FutureExt};
 
-use web3::transports::WebSocket;
 use web3::types::{Log, H256};
-use web3::Web3;
+use web3::{Transport, Web3};
 
 use super::connector::ContractTuple;
 use super::helper;
+use super::tracker::{BlockTracker, LogChange};
 
 const MAX_RETRIES: usize = 3;
 
+// How often the chain head is polled to confirm blocks when `confirmations` is set
+const HEAD_POLL_INTERVAL: time::Duration = time::Duration::from_secs(5);
+
 pub struct EthDetails<'a> {
     wss_url: String,
     filter: EthFilter,
@@ -33,6 +37,10 @@ pub struct EthDetails<'a> {
     pub schema_map: HashMap<H256, usize>,
     from_seq: Option<(u64, u64)>,
     pub conn_name: String,
+    confirmations: u64,
+    tracker: Mutex<BlockTracker>,
+    // Highest `(block_number, seq_in_tx)` sent to the ingestor so far
+    position: Mutex<(u64, u64)>,
 }
 
 impl<'a> EthDetails<'a> {
@@ -46,6 +54,7 @@ impl<'a> EthDetails<'a> {
         schema_map: HashMap<H256, usize>,
         from_seq: Option<(u64, u64)>,
         conn_name: String,
+        confirmations: u64,
     ) -> Self {
         EthDetails {
             wss_url,
@@ -56,16 +65,29 @@ impl<'a> EthDetails<'a> {
             schema_map,
             from_seq,
             conn_name,
+            confirmations,
+            tracker: Mutex::new(BlockTracker::new(confirmations)),
+            position: Mutex::new(from_seq.unwrap_or((0, 0))),
         }
     }
 }
 
-#[allow(unreachable_code)]
 pub async fn run(details: Arc<EthDetails<'_>>) -> Result<(), ConnectorError> {
     let client = conn_helper::get_wss_client(&details.wss_url)
         .await
         .map_err(ConnectorError::EthError)?;
+    run_with_client(details, client).await
+}
 
+#[allow(unreachable_code)]
+pub async fn run_with_client<T>(
+    details: Arc<EthDetails<'_>>,
+    client: Web3<T>,
+) -> Result<(), ConnectorError>
+where
+    T: Transport + Send + Sync + 'static,
+    T::Out: Send,
+{
     // Get current block no.
     let latest_block_no = client
         .eth()
@@ -79,11 +101,15 @@ pub async fn run(details: Arc<EthDetails<'_>>) -> Result<(), ConnectorError> {
         Some(block_no) => block_no,
     };
 
-    // Default to current block if from_block is not specified
+    let changes = details.tracker.lock().unwrap().set_head(latest_block_no);
+    process_changes(&details, changes)?;
+
+    // Default to current block if from_block is not specified.
+    // When resuming, the checkpoint block is fetched again as it may only be partially committed.
     let block_start = match (details.from_seq, details.filter.from_block) {
         (Some((0, _)), Some(block_no)) | (None, Some(block_no)) => block_no,
         (Some((0, _)), None) | (None, None) => block_end,
-        (Some((lsn, _)), _) => lsn + 1,
+        (Some((block_no, _)), _) => block_no,
     };
 
     fetch_logs(
@@ -131,17 +157,30 @@ pub async fn run(details: Arc<EthDetails<'_>>) -> Result<(), ConnectorError> {
             .map_err(ConnectorError::EthError)?;
 
         let stream = filter.stream(time::Duration::from_secs(1));
+        let mut head_interval = tokio::time::interval(HEAD_POLL_INTERVAL);
 
         tokio::pin!(stream);
 
         loop {
-            let msg = stream.next().await;
-
-            let msg = msg
-                .map_or(Err(ConnectorError::EmptyMessage), Ok)?
-                .map_err(ConnectorError::EthError)?;
+            tokio::select! {
+                msg = stream.next() => {
+                    let msg = msg
+                        .map_or(Err(ConnectorError::EmptyMessage), Ok)?
+                        .map_err(ConnectorError::EthError)?;
 
-            process_log(details.clone(), msg)?;
+                    process_log(details.clone(), msg)?;
+                }
+                _ = head_interval.tick(), if details.confirmations > 0 => {
+                    let head = client
+                        .eth()
+                        .block_number()
+                        .await
+                        .map_err(ConnectorError::EthError)?
+                        .as_u64();
+                    let changes = details.tracker.lock().unwrap().set_head(head);
+                    process_changes(&details, changes)?;
+                }
+            }
         }
     } else {
         info!("[{}] Reading reached block_to limit", details.conn_name)
@@ -149,14 +188,18 @@ pub async fn run(details: Arc<EthDetails<'_>>) -> Result<(), ConnectorError> {
     Ok(())
 }
 
-pub fn fetch_logs(
+pub fn fetch_logs<T>(
     details: Arc<EthDetails>,
-    client: Web3<WebSocket>,
+    client: Web3<T>,
     block_start: u64,
     block_end: u64,
     depth: usize,
     retries_left: usize,
-) -> BoxFuture<'_, Result<(), ConnectorError>> {
+) -> BoxFuture<'_, Result<(), ConnectorError>>
+where
+    T: Transport + Send + Sync + 'static,
+    T::Out: Send,
+{
     let filter = details.filter.clone();
     let depth_str = (0..depth)
         .map(|_| " ".to_string())
@@ -236,39 +279,85 @@ fn process_log(details: Arc<EthDetails>, msg: Log) -> Result<(), ConnectorError>
     if msg.log_index.is_none() {
         Ok(())
     } else {
-        if let Some(op) = helper::map_log_to_event(msg.to_owned(), details.clone()) {
+        let changes = details.tracker.lock().unwrap().process(msg);
+        process_changes(&details, changes)
+    }
+}
+
+fn process_changes(details: &EthDetails, changes: Vec<LogChange>) -> Result<(), ConnectorError> {
+    for change in changes {
+        let (msg, removed) = match change {
+            LogChange::Insert(msg) => (msg, false),
+            LogChange::Delete(msg) => (msg, true),
+        };
+        let block_no = msg.block_number.expect("expected for non pending").as_u64();
+        let log_index = msg.log_index.expect("expected for non pending").as_u64();
+
+        if let Some(record) = helper::map_log_to_event(msg.to_owned(), details) {
+            let op = helper::map_record_to_operation(record, removed);
             trace!("Writing log : {:?}", op);
             // Write eth_log record
-            details
-                .ingestor
-                .handle_message(IngestionMessage::new_op(
-                    msg.block_number.expect("expected for non pending").as_u64(),
-                    0,
-                    op,
-                ))
-                .map_err(ConnectorError::IngestorError)?;
+            send_op(details, (block_no, log_seq(log_index)), op, removed)?;
         } else {
             trace!("Ignoring log : {:?}", msg);
         }
 
         // write event record optionally
 
-        let op = helper::decode_event(
+        let record = helper::decode_event(
             msg,
             details.contracts.to_owned(),
             details.tables.clone(),
             details.schema_map.clone(),
         );
-        if let Some(op) = op {
-            trace!("Writing event : {:?}", op);
-            details
-                .ingestor
-                .handle_message(IngestionMessage::new_op(0, 0, op))
-                .map_err(ConnectorError::IngestorError)?;
-        } else {
+        if let Some(record) = record {
+            let op = helper::map_record_to_operation(record, removed);
             trace!("Writing event : {:?}", op);
+            send_op(details, (block_no, event_seq(log_index)), op, removed)?;
         }
+    }
 
-        Ok(())
+    Ok(())
+}
+
+/// Sequence number of the `eth_logs` record of the log at `log_index` within its block.
+pub fn log_seq(log_index: u64) -> u64 {
+    log_index * 2
+}
+
+/// Sequence number of the decoded event record of the log at `log_index` within its block.
+pub fn event_seq(log_index: u64) -> u64 {
+    log_index * 2 + 1
+}
+
+/// Whether the record identified by `(block_number, seq_in_tx)` was committed before the checkpoint.
+pub fn is_committed(from_seq: Option<(u64, u64)>, id: (u64, u64)) -> bool {
+    match from_seq {
+        None | Some((0, _)) => false,
+        Some(checkpoint) => id <= checkpoint,
     }
 }
+
+fn send_op(
+    details: &EthDetails,
+    id: (u64, u64),
+    op: Operation,
+    removed: bool,
+) -> Result<(), ConnectorError> {
+    let mut position = details.position.lock().unwrap();
+    let (txid, seq_in_tx) = if removed {
+        // Retractions are tagged with the current position so that checkpoints never move backwards
+        *position
+    } else {
+        if is_committed(details.from_seq, id) {
+            trace!("Skipping already committed record: {:?}", id);
+            return Ok(());
+        }
+        *position = (*position).max(id);
+        *position
+    };
+    details
+        .ingestor
+        .handle_message(IngestionMessage::new_op(txid, seq_in_tx, op))
+        .map_err(ConnectorError::IngestorError)
+}
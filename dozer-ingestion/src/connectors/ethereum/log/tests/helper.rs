@@ -70,6 +70,7 @@ pub fn get_eth_producer(
                     .trim_end()
                     .to_string(),
             }],
+            confirmations: 0,
         },
         "eth_test".to_string(),
     );
@@ -0,0 +1,60 @@
+use dozer_types::types::{DozerBigInt, Field};
+use web3::ethabi::Token;
+use web3::types::{H160, U256};
+
+use crate::connectors::ethereum::log::helper::map_abitype_to_field;
+
+#[test]
+fn test_map_uint_keeps_full_precision() {
+    assert_eq!(
+        map_abitype_to_field(Token::Uint(U256::MAX)),
+        Field::BigInt(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+                .parse()
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        map_abitype_to_field(Token::Uint(U256::from(u64::MAX) + 1)),
+        Field::BigInt("18446744073709551616".parse().unwrap())
+    );
+}
+
+#[test]
+fn test_map_int_is_signed() {
+    // -1 as 256-bit two's complement
+    assert_eq!(
+        map_abitype_to_field(Token::Int(U256::MAX)),
+        Field::BigInt(DozerBigInt::from(-1_i64))
+    );
+    assert_eq!(
+        map_abitype_to_field(Token::Int(U256::one() << 255)),
+        Field::BigInt(
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+                .parse()
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        map_abitype_to_field(Token::Int(U256::from(42))),
+        Field::BigInt(DozerBigInt::from(42_i64))
+    );
+}
+
+#[test]
+fn test_map_arrays_and_tuples_to_json() {
+    let token = Token::Tuple(vec![
+        Token::Address(H160::from_low_u64_be(1)),
+        Token::Array(vec![Token::Uint(U256::MAX), Token::Int(U256::MAX)]),
+        Token::Bytes(vec![0xab, 0xcd]),
+        Token::Bool(true),
+        Token::String("dozer".to_string()),
+    ]);
+    assert_eq!(
+        map_abitype_to_field(token),
+        Field::Text(
+            r#"["0x0000000000000000000000000000000000000001",["115792089237316195423570985008687907853269984665640564039457584007913129639935","-1"],"0xabcd",true,"dozer"]"#
+                .to_string()
+        )
+    );
+}
@@ -1,2 +1,5 @@
 mod connector;
 pub mod helper;
+mod mapping;
+mod resume;
+mod tracker;
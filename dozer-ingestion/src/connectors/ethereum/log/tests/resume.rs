@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{EthFilter, IngestionMessageKind};
+use dozer_types::serde_json::{self, json, Value};
+use dozer_types::types::Operation;
+
+use crate::connectors::ethereum::log::connector::ETH_LOGS_TABLE;
+use crate::connectors::ethereum::log::sender::{
+    event_seq, is_committed, log_seq, run_with_client, EthDetails,
+};
+use crate::connectors::TableInfo;
+use crate::ingestion::{IngestionConfig, Ingestor};
+
+#[test]
+fn test_log_and_event_sequences_are_ordered() {
+    assert!(log_seq(0) < event_seq(0));
+    assert!(event_seq(0) < log_seq(1));
+    assert!(event_seq(1) < log_seq(2));
+}
+
+#[test]
+fn test_is_committed() {
+    // No checkpoint or a reset checkpoint
+    assert!(!is_committed(None, (10, 0)));
+    assert!(!is_committed(Some((0, 0)), (10, 0)));
+
+    let checkpoint = Some((10, event_seq(3)));
+    // Earlier blocks and earlier logs of the checkpoint block
+    assert!(is_committed(checkpoint, (9, log_seq(100))));
+    assert!(is_committed(checkpoint, (10, log_seq(0))));
+    assert!(is_committed(checkpoint, (10, event_seq(3))));
+
+    // Later logs of the checkpoint block and later blocks
+    assert!(!is_committed(checkpoint, (10, log_seq(4))));
+    assert!(!is_committed(checkpoint, (11, log_seq(0))));
+
+    // The event of a log whose `eth_logs` record was the last one committed
+    let checkpoint = Some((10, log_seq(3)));
+    assert!(is_committed(checkpoint, (10, log_seq(3))));
+    assert!(!is_committed(checkpoint, (10, event_seq(3))));
+}
+
+const LOGS_PER_BLOCK: u64 = 3;
+
+fn hex_to_u64(value: &Value) -> u64 {
+    u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
+
+/// Starts a JSON-RPC node at block 100 whose blocks 10 to 12 have `LOGS_PER_BLOCK` logs each.
+/// Returns the node url.
+fn start_mock_node() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut content_length = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = Some(value.trim().parse::<usize>().unwrap());
+                        }
+                    }
+                }
+                let Some(content_length) = content_length else {
+                    break;
+                };
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let result = match request["method"].as_str().unwrap() {
+                    "eth_blockNumber" => json!("0x64"),
+                    "eth_getLogs" => {
+                        let from_block = hex_to_u64(&request["params"][0]["fromBlock"]);
+                        let to_block = hex_to_u64(&request["params"][0]["toBlock"]);
+                        let logs: Vec<Value> = (from_block.max(10)..=to_block.min(12))
+                            .flat_map(|block| {
+                                (0..LOGS_PER_BLOCK).map(move |log_index| {
+                                    json!({
+                                        "address": "0x0000000000000000000000000000000000000001",
+                                        "topics": [],
+                                        "data": "0x",
+                                        "blockHash": format!("{block:#066x}"),
+                                        "blockNumber": format!("{block:#x}"),
+                                        "transactionIndex": "0x0",
+                                        "logIndex": format!("{log_index:#x}"),
+                                        "removed": false
+                                    })
+                                })
+                            })
+                            .collect();
+                        json!(logs)
+                    }
+                    method => panic!("Unexpected method {method}"),
+                };
+                let body = serde_json::to_vec(&json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": result
+                }))
+                .unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        }
+    });
+
+    url
+}
+
+async fn read_identifiers(from_seq: Option<(u64, u64)>) -> Vec<(u64, u64)> {
+    let url = start_mock_node();
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+
+    let details = Arc::new(EthDetails::new(
+        url.clone(),
+        EthFilter {
+            from_block: Some(10),
+            to_block: Some(12),
+            addresses: vec![],
+            topics: vec![],
+        },
+        &ingestor,
+        HashMap::new(),
+        vec![TableInfo {
+            name: ETH_LOGS_TABLE.to_string(),
+            table_name: ETH_LOGS_TABLE.to_string(),
+            id: 0,
+            columns: None,
+        }],
+        HashMap::new(),
+        from_seq,
+        "test".to_string(),
+        0,
+    ));
+    let client = web3::Web3::new(web3::transports::Http::new(&url).unwrap());
+    run_with_client(details, client).await.unwrap();
+
+    let mut identifiers = vec![];
+    while let Some(msg) = iterator.next_timeout(Duration::from_millis(100)) {
+        assert!(matches!(
+            msg.kind,
+            IngestionMessageKind::OperationEvent(Operation::Insert { .. })
+        ));
+        identifiers.push((msg.identifier.txid, msg.identifier.seq_in_tx));
+    }
+    identifiers
+}
+
+#[tokio::test]
+async fn test_resume_from_mid_block_checkpoint() {
+    let all = read_identifiers(None).await;
+    let expected: Vec<_> = (10..=12)
+        .flat_map(|block| (0..LOGS_PER_BLOCK).map(move |index| (block, log_seq(index))))
+        .collect();
+    assert_eq!(all, expected);
+
+    // The checkpoint block is fetched again, only the logs after the checkpoint are sent.
+    let checkpoint = (11, log_seq(1));
+    let resumed = read_identifiers(Some(checkpoint)).await;
+    assert_eq!(resumed.first(), Some(&(11, log_seq(2))));
+
+    let mut replayed: Vec<_> = all.into_iter().filter(|id| *id <= checkpoint).collect();
+    replayed.extend(resumed);
+    assert_eq!(replayed, expected);
+}
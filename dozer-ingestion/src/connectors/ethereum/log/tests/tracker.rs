@@ -0,0 +1,114 @@
+use web3::types::{Bytes, Log, H160, H256, U256, U64};
+
+use crate::connectors::ethereum::log::tracker::{BlockTracker, LogChange};
+
+fn log(block_no: u64, block_hash: u64, log_index: u64, removed: bool) -> Log {
+    Log {
+        address: H160::zero(),
+        topics: vec![],
+        data: Bytes(vec![]),
+        block_hash: Some(H256::from_low_u64_be(block_hash)),
+        block_number: Some(U64::from(block_no)),
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: Some(U256::from(log_index)),
+        transaction_log_index: None,
+        log_type: None,
+        removed: Some(removed),
+    }
+}
+
+fn inserted(changes: &[LogChange]) -> Vec<(u64, u64)> {
+    changes
+        .iter()
+        .filter_map(|c| match c {
+            LogChange::Insert(l) => Some((
+                l.block_number.unwrap().as_u64(),
+                l.log_index.unwrap().as_u64(),
+            )),
+            LogChange::Delete(_) => None,
+        })
+        .collect()
+}
+
+fn deleted(changes: &[LogChange]) -> Vec<(u64, u64)> {
+    changes
+        .iter()
+        .filter_map(|c| match c {
+            LogChange::Delete(l) => Some((
+                l.block_number.unwrap().as_u64(),
+                l.log_index.unwrap().as_u64(),
+            )),
+            LogChange::Insert(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_forward_immediately_without_confirmations() {
+    let mut tracker = BlockTracker::new(0);
+    let changes = tracker.process(log(10, 1, 0, false));
+    assert_eq!(inserted(&changes), vec![(10, 0)]);
+
+    // Duplicates from the historical fetch and the subscription are ignored
+    assert!(tracker.process(log(10, 1, 0, false)).is_empty());
+
+    // Pending logs are ignored
+    let mut pending = log(11, 2, 0, false);
+    pending.log_index = None;
+    assert!(tracker.process(pending).is_empty());
+}
+
+#[test]
+fn test_removed_log_is_deleted() {
+    let mut tracker = BlockTracker::new(0);
+    tracker.process(log(10, 1, 0, false));
+    tracker.process(log(10, 1, 1, false));
+
+    let changes = tracker.process(log(10, 1, 1, true));
+    assert_eq!(deleted(&changes), vec![(10, 1)]);
+    assert_eq!(changes.len(), 1);
+
+    // Removing an unknown log has no effect
+    assert!(tracker.process(log(10, 1, 5, true)).is_empty());
+    assert!(tracker.process(log(10, 9, 0, true)).is_empty());
+}
+
+#[test]
+fn test_replaced_block_is_retracted() {
+    let mut tracker = BlockTracker::new(0);
+    tracker.process(log(10, 1, 0, false));
+    tracker.process(log(11, 2, 0, false));
+    tracker.process(log(11, 2, 1, false));
+
+    // Block 11 is replaced without the node reporting the old logs as removed
+    let changes = tracker.process(log(11, 3, 0, false));
+    assert_eq!(deleted(&changes), vec![(11, 0), (11, 1)]);
+    assert_eq!(inserted(&changes), vec![(11, 0)]);
+
+    // A replaced block orphans the blocks built on top of it
+    tracker.process(log(12, 4, 0, false));
+    let changes = tracker.process(log(10, 5, 0, false));
+    assert_eq!(deleted(&changes), vec![(10, 0), (11, 0), (12, 0)]);
+    assert_eq!(inserted(&changes), vec![(10, 0)]);
+}
+
+#[test]
+fn test_confirmations() {
+    let mut tracker = BlockTracker::new(2);
+    assert!(tracker.process(log(10, 1, 0, false)).is_empty());
+    assert!(tracker.process(log(11, 2, 0, false)).is_empty());
+
+    // Logs removed before their block is confirmed are never forwarded
+    assert!(tracker.process(log(11, 2, 0, true)).is_empty());
+
+    let changes = tracker.process(log(12, 3, 0, false));
+    assert_eq!(inserted(&changes), vec![(10, 0)]);
+
+    // An unconfirmed block replaced by a reorg is dropped silently
+    assert!(tracker.process(log(12, 4, 0, false)).is_empty());
+
+    let changes = tracker.set_head(14);
+    assert_eq!(inserted(&changes), vec![(12, 0)]);
+    assert!(deleted(&changes).is_empty());
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use web3::types::{Log, H256};
+
+/// Number of blocks past the confirmation depth for which logs are remembered, so that they can
+/// still be retracted if their block leaves the canonical chain.
+const REORG_WINDOW: u64 = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogChange {
+    Insert(Log),
+    Delete(Log),
+}
+
+#[derive(Debug)]
+struct TrackedBlock {
+    hash: H256,
+    logs: Vec<Log>,
+    forwarded: bool,
+}
+
+/// Tracks the logs of recent blocks by block number and hash.
+///
+/// A block's logs are forwarded once the block is `confirmations` blocks deep. Forwarded logs are
+/// retracted when the node reports them as `removed`, or when a log arrives for the same block
+/// number with a different block hash, which orphans that block and every block after it.
+#[derive(Debug)]
+pub struct BlockTracker {
+    confirmations: u64,
+    head: u64,
+    blocks: BTreeMap<u64, TrackedBlock>,
+}
+
+impl BlockTracker {
+    pub fn new(confirmations: u64) -> Self {
+        Self {
+            confirmations,
+            head: 0,
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Advances the chain head and forwards the logs of the blocks that became confirmed.
+    pub fn set_head(&mut self, block_no: u64) -> Vec<LogChange> {
+        self.head = self.head.max(block_no);
+        let mut changes = vec![];
+        self.confirm(&mut changes);
+        self.prune();
+        changes
+    }
+
+    /// Pending logs, which have no block yet, are ignored.
+    pub fn process(&mut self, log: Log) -> Vec<LogChange> {
+        let (Some(block_no), Some(hash), Some(_)) =
+            (log.block_number, log.block_hash, log.log_index)
+        else {
+            return vec![];
+        };
+        let block_no = block_no.as_u64();
+
+        let mut changes = vec![];
+        if log.removed == Some(true) {
+            self.remove(block_no, hash, &log, &mut changes);
+        } else {
+            if self
+                .blocks
+                .get(&block_no)
+                .map_or(false, |block| block.hash != hash)
+            {
+                self.retract_from(block_no, &mut changes);
+            }
+
+            let block = self.blocks.entry(block_no).or_insert_with(|| TrackedBlock {
+                hash,
+                logs: vec![],
+                forwarded: false,
+            });
+            // The same log can be delivered by both the historical fetch and the subscription
+            if !block.logs.iter().any(|l| l.log_index == log.log_index) {
+                if block.forwarded {
+                    changes.push(LogChange::Insert(log.clone()));
+                }
+                block.logs.push(log);
+            }
+
+            self.head = self.head.max(block_no);
+            self.confirm(&mut changes);
+        }
+        self.prune();
+        changes
+    }
+
+    fn remove(&mut self, block_no: u64, hash: H256, log: &Log, changes: &mut Vec<LogChange>) {
+        let Some(block) = self.blocks.get_mut(&block_no) else {
+            return;
+        };
+        if block.hash != hash {
+            return;
+        }
+        if let Some(pos) = block.logs.iter().position(|l| l.log_index == log.log_index) {
+            let removed = block.logs.remove(pos);
+            if block.forwarded {
+                changes.push(LogChange::Delete(removed));
+            }
+        }
+    }
+
+    fn retract_from(&mut self, block_no: u64, changes: &mut Vec<LogChange>) {
+        for (_, block) in self.blocks.split_off(&block_no) {
+            if block.forwarded {
+                changes.extend(block.logs.into_iter().map(LogChange::Delete));
+            }
+        }
+    }
+
+    fn confirm(&mut self, changes: &mut Vec<LogChange>) {
+        for (block_no, block) in self.blocks.iter_mut() {
+            if block_no.saturating_add(self.confirmations) > self.head {
+                break;
+            }
+            if !block.forwarded {
+                block.forwarded = true;
+                changes.extend(block.logs.iter().cloned().map(LogChange::Insert));
+            }
+        }
+    }
+
+    fn prune(&mut self) {
+        let keep_from = self
+            .head
+            .saturating_sub(self.confirmations.saturating_add(REORG_WINDOW));
+        self.blocks = self.blocks.split_off(&keep_from);
+    }
+}
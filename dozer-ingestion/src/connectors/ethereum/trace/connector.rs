@@ -47,7 +47,7 @@ impl Connector for EthTraceConnector {
 
     fn start(
         &self,
-        _from_seq: Option<(u64, u64)>,
+        from_seq: Option<(u64, u64)>,
         ingestor: &Ingestor,
         _tables: Vec<TableInfo>,
     ) -> Result<(), ConnectorError> {
@@ -55,7 +55,7 @@ impl Connector for EthTraceConnector {
         let conn_name = self.conn_name.clone();
         Runtime::new()
             .unwrap()
-            .block_on(async { run(ingestor, config, conn_name).await })
+            .block_on(async { run(ingestor, config, conn_name, from_seq).await })
     }
 
     fn validate(&self, _tables: Option<Vec<TableInfo>>) -> Result<(), ConnectorError> {
@@ -73,8 +73,10 @@ impl Connector for EthTraceConnector {
         self.get_tables_default(tables)
     }
 
-    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError> {
-        Ok(false)
+    fn can_start_from(&self, (block_no, _): (u64, u64)) -> Result<bool, ConnectorError> {
+        // Checkpoints are `(block_number, seq_in_block)`, resumable as long as the block is still in range
+        Ok(block_no >= self.config.from_block
+            && self.config.to_block.map_or(true, |to| block_no <= to))
     }
 }
 
@@ -94,16 +96,21 @@ pub async fn run(
     ingestor: &Ingestor,
     config: EthTraceConfig,
     conn_name: String,
+    from_seq: Option<(u64, u64)>,
 ) -> Result<(), ConnectorError> {
     let client_tuple = conn_helper::get_batch_http_client(&config.https_url)
         .await
         .map_err(ConnectorError::EthError)?;
 
+    // The checkpoint block is fetched again as it may only be partially committed
+    let checkpoint = from_seq.filter(|(block_no, _)| *block_no >= config.from_block);
+    let from_block = checkpoint.map_or(config.from_block, |(block_no, _)| block_no);
+
     info!(
         "Starting Eth Trace connector: {} from block {}",
-        conn_name, config.from_block
+        conn_name, from_block
     );
-    let batch_iter = BatchIterator::new(config.from_block, config.to_block, config.batch_size);
+    let batch_iter = BatchIterator::new(from_block, config.to_block, config.batch_size);
 
     let mut errors: Vec<ConnectorError> = vec![];
     for batch in batch_iter {
@@ -116,12 +123,18 @@ pub async fn run(
             let res = get_block_traces(client_tuple.clone(), batch).await;
             match res {
                 Ok(arr) => {
-                    for result in arr {
-                        let ops = map_trace_to_ops(&result.result);
-
-                        for op in ops {
+                    for (block_no, results) in arr {
+                        let ops = results
+                            .iter()
+                            .flat_map(|result| map_trace_to_ops(&result.result));
+
+                        for (seq, op) in ops.enumerate() {
+                            let id = (block_no, seq as u64);
+                            if checkpoint.map_or(false, |checkpoint| id <= checkpoint) {
+                                continue;
+                            }
                             ingestor
-                                .handle_message(IngestionMessage::new_op(batch.0, 0, op))
+                                .handle_message(IngestionMessage::new_op(id.0, id.1, op))
                                 .map_err(ConnectorError::IngestorError)?;
                         }
                     }
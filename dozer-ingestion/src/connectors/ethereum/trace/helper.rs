@@ -37,7 +37,7 @@ pub struct Trace {
 pub async fn get_block_traces(
     tuple: (Web3<Batch<Http>>, Http),
     batch: (u64, u64),
-) -> Result<Vec<TraceResult>, ConnectorError> {
+) -> Result<Vec<(u64, Vec<TraceResult>)>, ConnectorError> {
     debug_assert!(batch.0 < batch.1, "Batch start must be less than batch end");
     let (client, transport) = tuple;
     let mut requests = vec![];
@@ -82,7 +82,7 @@ pub async fn get_block_traces(
 
         debug!("Idx: {} : Response: {:?}", idx, r);
 
-        results.extend(r);
+        results.push((from + idx as u64, r));
     }
     Ok(results)
 }
@@ -1,8 +1,11 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
 use std::{env, thread, time::Duration};
 
 use dozer_types::{
     ingestion_types::{EthTraceConfig, IngestionMessage, IngestionMessageKind},
     log::info,
+    serde_json::{self, json, Value},
     types::{Field, Operation},
 };
 
@@ -94,3 +97,140 @@ fn test_trace_iterator() {
         panic!("No message received");
     }
 }
+
+/// Starts a JSON-RPC node answering `debug_traceBlockByNumber` with a call that has one nested call,
+/// so every block maps to two operations. Returns the node url.
+fn start_mock_node() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut content_length = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 {
+                        break;
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = Some(value.trim().parse::<usize>().unwrap());
+                        }
+                    }
+                }
+                let Some(content_length) = content_length else {
+                    break;
+                };
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let requests: Vec<Value> = serde_json::from_slice(&body).unwrap();
+                let responses: Vec<Value> = requests
+                    .iter()
+                    .map(|request| {
+                        let trace = json!({
+                            "type": "CALL",
+                            "from": "0x0000000000000000000000000000000000000001",
+                            "to": "0x0000000000000000000000000000000000000002",
+                            "value": "0x1",
+                            "gas": "0x10",
+                            "gasUsed": "0x8",
+                            "input": request["params"][0],
+                            "calls": [{
+                                "type": "CALL",
+                                "from": "0x0000000000000000000000000000000000000002",
+                                "to": "0x0000000000000000000000000000000000000003",
+                                "gas": "0x4",
+                                "gasUsed": "0x2"
+                            }]
+                        });
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "result": [{ "result": trace }]
+                        })
+                    })
+                    .collect();
+                let body = serde_json::to_vec(&responses).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        }
+    });
+
+    url
+}
+
+fn read_identifiers(from_seq: Option<(u64, u64)>) -> Vec<(u64, u64)> {
+    let https_url = start_mock_node();
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+
+    let _t = thread::spawn(move || {
+        let connector = EthTraceConnector::new(
+            1,
+            EthTraceConfig {
+                https_url,
+                from_block: 10,
+                to_block: Some(12),
+                batch_size: 2,
+            },
+            "test".to_string(),
+        );
+        connector.start(from_seq, &ingestor, vec![]).unwrap();
+    });
+
+    let mut identifiers = vec![];
+    while let Some(msg) = iterator.next_timeout(Duration::from_millis(2000)) {
+        assert!(matches!(
+            msg.kind,
+            IngestionMessageKind::OperationEvent(Operation::Insert { .. })
+        ));
+        identifiers.push((msg.identifier.txid, msg.identifier.seq_in_tx));
+    }
+    identifiers
+}
+
+#[test]
+fn test_trace_identifiers() {
+    assert_eq!(
+        read_identifiers(None),
+        vec![(10, 0), (10, 1), (11, 0), (11, 1), (12, 0), (12, 1)]
+    );
+}
+
+#[test]
+fn test_trace_resume_from_checkpoint() {
+    assert_eq!(
+        read_identifiers(Some((11, 0))),
+        vec![(11, 1), (12, 0), (12, 1)]
+    );
+}
+
+#[test]
+fn test_trace_can_start_from() {
+    let connector = EthTraceConnector::new(
+        1,
+        EthTraceConfig {
+            https_url: "http://localhost:8545".to_string(),
+            from_block: 10,
+            to_block: Some(12),
+            batch_size: 2,
+        },
+        "test".to_string(),
+    );
+    assert!(!connector.can_start_from((9, 0)).unwrap());
+    assert!(connector.can_start_from((10, 0)).unwrap());
+    assert!(connector.can_start_from((12, 1)).unwrap());
+    assert!(!connector.can_start_from((13, 0)).unwrap());
+}
@@ -144,6 +144,20 @@ fn map_record(rec: grpc_types::types::Record, schema: &Schema) -> Result<Record,
                 grpc_types::types::value::Value::StringValue(a),
                 dozer_types::types::FieldType::Text,
             ) => Ok(dozer_types::types::Field::Text(a.clone())),
+            (
+                grpc_types::types::value::Value::StringValue(a),
+                dozer_types::types::FieldType::Duration,
+            ) => a
+                .parse::<dozer_types::types::DozerDuration>()
+                .map(dozer_types::types::Field::Duration)
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string())),
+            (
+                grpc_types::types::value::Value::StringValue(a),
+                dozer_types::types::FieldType::BigInt,
+            ) => a
+                .parse::<dozer_types::types::DozerBigInt>()
+                .map(dozer_types::types::Field::BigInt)
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string())),
             (
                 grpc_types::types::value::Value::BytesValue(a),
                 dozer_types::types::FieldType::Bson,
@@ -3,13 +3,16 @@ use crate::ingestion::Ingestor;
 use crate::{connectors::TableInfo, errors::ConnectorError};
 use dozer_types::ingestion_types::KafkaConfig;
 
-use dozer_types::types::SourceSchema;
+use dozer_types::types::{ReplicationChangesTrackingType, SchemaIdentifier, SourceSchema};
 use kafka::consumer::{Consumer, FetchOffset, GroupOffsetStorage};
 use tokio::runtime::Runtime;
 
 use crate::connectors::kafka::debezium::no_schema_registry::NoSchemaRegistry;
 use crate::connectors::kafka::debezium::schema_registry::SchemaRegistry;
 use crate::connectors::kafka::debezium::stream_consumer::DebeziumStreamConsumer;
+use crate::connectors::kafka::plain::client::KafkaPartitionClient;
+use crate::connectors::kafka::plain::decoder::{get_schema, ValueDecoder};
+use crate::connectors::kafka::plain::stream_consumer::PlainStreamConsumer;
 use crate::connectors::kafka::stream_consumer::StreamConsumer;
 use crate::errors::DebeziumError::{DebeziumConnectionError, TopicNotDefined};
 
@@ -17,19 +20,40 @@ use crate::errors::DebeziumError::{DebeziumConnectionError, TopicNotDefined};
 pub struct KafkaConnector {
     pub id: u64,
     config: KafkaConfig,
+    state: Option<Vec<u8>>,
 }
 
 impl KafkaConnector {
     pub fn new(id: u64, config: KafkaConfig) -> Self {
-        Self { id, config }
+        Self {
+            id,
+            config,
+            state: None,
+        }
     }
 }
 
+fn get_schema_id() -> SchemaIdentifier {
+    SchemaIdentifier { id: 1, version: 1 }
+}
+
 impl Connector for KafkaConnector {
     fn get_schemas(
         &self,
         table_names: Option<Vec<TableInfo>>,
     ) -> Result<Vec<SourceSchema>, ConnectorError> {
+        if let Some(format) = &self.config.format {
+            let Some(table) = table_names.as_ref().and_then(|tables| tables.get(0)) else {
+                return Ok(vec![]);
+            };
+            let decoder = ValueDecoder::new(format)?;
+            return Ok(vec![SourceSchema::new(
+                table.table_name.clone(),
+                get_schema(&self.config, &decoder, get_schema_id())?,
+                ReplicationChangesTrackingType::FullChanges,
+            )]);
+        }
+
         self.config.schema_registry_url.clone().map_or(
             NoSchemaRegistry::get_schema(table_names.clone(), self.config.clone()),
             |_| SchemaRegistry::get_schema(table_names, self.config.clone()),
@@ -38,7 +62,7 @@ impl Connector for KafkaConnector {
 
     fn start(
         &self,
-        _from_seq: Option<(u64, u64)>,
+        from_seq: Option<(u64, u64)>,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
     ) -> Result<(), ConnectorError> {
@@ -46,6 +70,18 @@ impl Connector for KafkaConnector {
             .get(0)
             .map_or(Err(TopicNotDefined), |table| Ok(&table.table_name))?;
 
+        if let Some(format) = &self.config.format {
+            let mut client = KafkaPartitionClient::connect(self.config.broker.clone())?;
+            let mut consumer = PlainStreamConsumer::new(
+                topic.clone(),
+                ValueDecoder::new(format)?,
+                get_schema_id(),
+                from_seq,
+                self.state.as_deref(),
+            )?;
+            return consumer.run(&mut client, ingestor);
+        }
+
         let broker = self.config.broker.to_owned();
         Runtime::new()
             .unwrap()
@@ -64,8 +100,14 @@ impl Connector for KafkaConnector {
         self.get_tables_default(tables)
     }
 
+    fn restore_state(&mut self, state: Vec<u8>) -> Result<(), ConnectorError> {
+        self.state = Some(state);
+        Ok(())
+    }
+
     fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError> {
-        Ok(false)
+        // Plain messages are identified by `(offset + 1, partition)`, Debezium messages aren't
+        Ok(self.config.format.is_some())
     }
 }
 
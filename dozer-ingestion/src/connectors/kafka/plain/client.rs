@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use kafka::client::{FetchOffset, FetchPartition, KafkaClient};
+
+use crate::errors::{ConnectorError, KafkaError};
+
+pub struct KafkaMessage {
+    pub offset: i64,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+/// The requests the plain consumer sends to Kafka.
+pub trait PartitionClient {
+    fn partitions(&mut self, topic: &str) -> Result<Vec<i32>, ConnectorError>;
+
+    /// Returns the offset of the first available message of every partition.
+    fn earliest_offsets(&mut self, topic: &str) -> Result<HashMap<i32, i64>, ConnectorError>;
+
+    /// Returns the next messages of `partition`, starting from `offset`.
+    fn fetch(
+        &mut self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<Vec<KafkaMessage>, ConnectorError>;
+}
+
+pub struct KafkaPartitionClient {
+    client: KafkaClient,
+}
+
+impl KafkaPartitionClient {
+    pub fn connect(broker: String) -> Result<Self, ConnectorError> {
+        let mut client = KafkaClient::new(vec![broker]);
+        client
+            .load_metadata_all()
+            .map_err(KafkaError::ClientError)?;
+        Ok(Self { client })
+    }
+}
+
+impl PartitionClient for KafkaPartitionClient {
+    fn partitions(&mut self, topic: &str) -> Result<Vec<i32>, ConnectorError> {
+        self.client
+            .load_metadata(&[topic])
+            .map_err(KafkaError::ClientError)?;
+        self.client
+            .topics()
+            .partitions(topic)
+            .map(|partitions| partitions.available_ids())
+            .ok_or_else(|| KafkaError::TopicNotFound(topic.to_string()).into())
+    }
+
+    fn earliest_offsets(&mut self, topic: &str) -> Result<HashMap<i32, i64>, ConnectorError> {
+        let offsets = self
+            .client
+            .fetch_offsets(&[topic], FetchOffset::Earliest)
+            .map_err(KafkaError::ClientError)?;
+        Ok(offsets
+            .get(topic)
+            .map(|offsets| {
+                offsets
+                    .iter()
+                    .map(|offset| (offset.partition, offset.offset))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn fetch(
+        &mut self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<Vec<KafkaMessage>, ConnectorError> {
+        let responses = self
+            .client
+            .fetch_messages_for_partition(&FetchPartition::new(topic, partition, offset))
+            .map_err(KafkaError::ClientError)?;
+
+        let mut messages = vec![];
+        for response in responses.iter() {
+            for topic in response.topics() {
+                for partition in topic.partitions() {
+                    let data = partition.data().map_err(|e| {
+                        KafkaError::FetchError(partition.partition(), e.to_string())
+                    })?;
+                    // Fetches can return messages of the same message set before `offset`
+                    messages.extend(
+                        data.messages()
+                            .iter()
+                            .filter(|message| message.offset >= offset)
+                            .map(|message| KafkaMessage {
+                                offset: message.offset,
+                                key: message.key.to_vec(),
+                                value: message.value.to_vec(),
+                            }),
+                    );
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
@@ -0,0 +1,243 @@
+use dozer_types::chrono::NaiveDateTime;
+use dozer_types::ingestion_types::{KafkaConfig, KafkaFormat};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json;
+use dozer_types::serde_json::Value as JsonValue;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Schema, SchemaIdentifier, SourceDefinition,
+};
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, FieldDescriptor, Kind, MessageDescriptor, SerializeOptions,
+    Value,
+};
+
+use crate::errors::{ConnectorError, KafkaError};
+
+/// Decodes the values of a topic whose messages aren't Debezium envelopes.
+pub enum ValueDecoder {
+    Json(Vec<FieldDefinition>),
+    Protobuf(MessageDescriptor),
+}
+
+impl ValueDecoder {
+    pub fn new(format: &KafkaFormat) -> Result<Self, ConnectorError> {
+        match format {
+            KafkaFormat::Json(json) => Ok(Self::Json(
+                json.fields
+                    .iter()
+                    .map(|field| {
+                        let typ = FieldType::try_from(field.typ.as_str()).map_err(|_| {
+                            KafkaError::FieldTypeNotSupported(field.name.clone(), field.typ.clone())
+                        })?;
+                        Ok(FieldDefinition {
+                            name: field.name.clone(),
+                            typ,
+                            nullable: field.nullable,
+                            source: SourceDefinition::Dynamic,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ConnectorError>>()?,
+            )),
+            KafkaFormat::Protobuf(protobuf) => {
+                let bytes = std::fs::read(&protobuf.descriptor_path).map_err(|e| {
+                    KafkaError::DescriptorReadError(protobuf.descriptor_path.clone(), e)
+                })?;
+                let pool = DescriptorPool::decode(bytes.as_slice())
+                    .map_err(KafkaError::InvalidDescriptor)?;
+                let message = pool
+                    .get_message_by_name(&protobuf.message)
+                    .ok_or_else(|| KafkaError::MessageTypeNotFound(protobuf.message.clone()))?;
+                Ok(Self::Protobuf(message))
+            }
+        }
+    }
+
+    /// Definitions of the fields decoded from every value.
+    pub fn fields(&self) -> Vec<FieldDefinition> {
+        match self {
+            Self::Json(fields) => fields.clone(),
+            Self::Protobuf(message) => message
+                .fields()
+                .map(|field| FieldDefinition {
+                    name: field.name().to_string(),
+                    typ: map_protobuf_type(&field),
+                    nullable: true,
+                    source: SourceDefinition::Dynamic,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn decode(&self, value: &[u8]) -> Result<Vec<Field>, ConnectorError> {
+        match self {
+            Self::Json(fields) => {
+                let value: JsonValue =
+                    serde_json::from_slice(value).map_err(KafkaError::JsonDecodeError)?;
+                fields
+                    .iter()
+                    .map(|field| {
+                        convert_json_value(
+                            field,
+                            value.get(&field.name).unwrap_or(&JsonValue::Null),
+                        )
+                    })
+                    .collect()
+            }
+            Self::Protobuf(descriptor) => {
+                let message = DynamicMessage::decode(descriptor.clone(), value)
+                    .map_err(KafkaError::ProtobufDecodeError)?;
+                // Lists, maps and nested messages are kept as JSON
+                let mut json = None;
+                descriptor
+                    .fields()
+                    .map(|field| match map_protobuf_type(&field) {
+                        FieldType::Bson => {
+                            if json.is_none() {
+                                json = Some(protobuf_to_json(&message)?);
+                            }
+                            Ok(json
+                                .as_ref()
+                                .and_then(|json| json.get(field.json_name()))
+                                .filter(|value| !value.is_null())
+                                .map_or(Field::Null, |value| {
+                                    Field::Bson(value.to_string().into_bytes())
+                                }))
+                        }
+                        _ => convert_protobuf_field(&message, &field),
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Returns the schema of a topic, whose first field is the message key.
+pub fn get_schema(
+    config: &KafkaConfig,
+    decoder: &ValueDecoder,
+    schema_id: SchemaIdentifier,
+) -> Result<Schema, ConnectorError> {
+    let mut fields = vec![FieldDefinition {
+        name: config.key_column.clone(),
+        typ: FieldType::String,
+        nullable: false,
+        source: SourceDefinition::Dynamic,
+    }];
+    for field in decoder.fields() {
+        if field.name == config.key_column {
+            return Err(KafkaError::KeyColumnConflict(field.name).into());
+        }
+        fields.push(field);
+    }
+
+    Ok(Schema {
+        identifier: Some(schema_id),
+        fields,
+        primary_index: vec![0],
+    })
+}
+
+pub fn convert_json_value(
+    field: &FieldDefinition,
+    value: &JsonValue,
+) -> Result<Field, ConnectorError> {
+    let invalid = || -> ConnectorError {
+        KafkaError::InvalidFieldValue(field.name.clone(), field.typ).into()
+    };
+
+    let result = match (field.typ, value) {
+        (_, JsonValue::Null) if field.nullable => Some(Field::Null),
+        (FieldType::Int, JsonValue::Number(number)) => number.as_i64().map(Field::Int),
+        (FieldType::UInt, JsonValue::Number(number)) => number.as_u64().map(Field::UInt),
+        (FieldType::Float, JsonValue::Number(number)) => number
+            .as_f64()
+            .map(|number| Field::Float(OrderedFloat(number))),
+        (FieldType::Decimal | FieldType::BigInt, JsonValue::Number(number)) => {
+            Field::from_str(&number.to_string(), field.typ, field.nullable).ok()
+        }
+        (FieldType::Boolean, JsonValue::Bool(value)) => Some(Field::Boolean(*value)),
+        (FieldType::String, JsonValue::String(value)) => Some(Field::String(value.clone())),
+        (FieldType::Text, JsonValue::String(value)) => Some(Field::Text(value.clone())),
+        (FieldType::Bson, value) => Some(Field::Bson(value.to_string().into_bytes())),
+        (_, JsonValue::String(value)) => Field::from_str(value, field.typ, field.nullable).ok(),
+        _ => None,
+    };
+    result.ok_or_else(invalid)
+}
+
+pub fn map_protobuf_type(field: &FieldDescriptor) -> FieldType {
+    if field.is_list() || field.is_map() {
+        return FieldType::Bson;
+    }
+    match field.kind() {
+        Kind::Double | Kind::Float => FieldType::Float,
+        Kind::Int32
+        | Kind::Int64
+        | Kind::Sint32
+        | Kind::Sint64
+        | Kind::Sfixed32
+        | Kind::Sfixed64 => FieldType::Int,
+        Kind::Uint32 | Kind::Uint64 | Kind::Fixed32 | Kind::Fixed64 => FieldType::UInt,
+        Kind::Bool => FieldType::Boolean,
+        Kind::String | Kind::Enum(_) => FieldType::String,
+        Kind::Bytes => FieldType::Binary,
+        Kind::Message(message) if message.full_name() == "google.protobuf.Timestamp" => {
+            FieldType::Timestamp
+        }
+        Kind::Message(_) => FieldType::Bson,
+    }
+}
+
+fn convert_protobuf_field(
+    message: &DynamicMessage,
+    field: &FieldDescriptor,
+) -> Result<Field, ConnectorError> {
+    if matches!(field.kind(), Kind::Message(_)) && !message.has_field(field) {
+        return Ok(Field::Null);
+    }
+
+    let invalid = || -> ConnectorError {
+        KafkaError::InvalidFieldValue(field.name().to_string(), map_protobuf_type(field)).into()
+    };
+
+    let value = message.get_field(field);
+    let result = match (value.as_ref(), field.kind()) {
+        (Value::Bool(value), _) => Some(Field::Boolean(*value)),
+        (Value::I32(value), _) => Some(Field::Int(*value as i64)),
+        (Value::I64(value), _) => Some(Field::Int(*value)),
+        (Value::U32(value), _) => Some(Field::UInt(*value as u64)),
+        (Value::U64(value), _) => Some(Field::UInt(*value)),
+        (Value::F32(value), _) => Some(Field::Float(OrderedFloat(*value as f64))),
+        (Value::F64(value), _) => Some(Field::Float(OrderedFloat(*value))),
+        (Value::String(value), _) => Some(Field::String(value.clone())),
+        (Value::Bytes(value), _) => Some(Field::Binary(value.to_vec())),
+        (Value::EnumNumber(number), Kind::Enum(descriptor)) => Some(Field::String(
+            descriptor
+                .get_value(*number)
+                .map_or_else(|| number.to_string(), |value| value.name().to_string()),
+        )),
+        (Value::Message(timestamp), _) => {
+            let seconds = timestamp
+                .get_field_by_name("seconds")
+                .and_then(|value| value.as_i64());
+            let nanos = timestamp
+                .get_field_by_name("nanos")
+                .and_then(|value| value.as_i32());
+            match (seconds, nanos) {
+                (Some(seconds), Some(nanos)) => {
+                    NaiveDateTime::from_timestamp_opt(seconds, nanos as u32).map(Field::from)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    result.ok_or_else(invalid)
+}
+
+fn protobuf_to_json(message: &DynamicMessage) -> Result<JsonValue, ConnectorError> {
+    let options = SerializeOptions::new().skip_default_fields(false);
+    message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|e| KafkaError::JsonDecodeError(e).into())
+}
@@ -0,0 +1,5 @@
+pub mod client;
+pub mod decoder;
+pub mod stream_consumer;
+#[cfg(test)]
+mod tests;
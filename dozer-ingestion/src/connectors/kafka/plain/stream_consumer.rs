@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use dozer_types::bincode;
+use dozer_types::ingestion_types::IngestionMessage;
+use dozer_types::log::debug;
+use dozer_types::types::{Field, Operation, Record, SchemaIdentifier};
+
+use super::client::{KafkaMessage, PartitionClient};
+use super::decoder::ValueDecoder;
+use crate::errors::{ConnectorError, KafkaError};
+use crate::ingestion::Ingestor;
+
+/// Returns the identifier of the message at `offset` of `partition`.
+///
+/// The offset is incremented so that no message is identified by `(0, _)`.
+pub fn message_id(partition: i32, offset: i64) -> (u64, u64) {
+    (offset as u64 + 1, partition as u64)
+}
+
+/// Consumes a topic of keyed JSON or Protobuf messages.
+///
+/// The message key is the primary key: a message with a new key is an insert, a message with a
+/// known key is an update and a message with an empty value, a tombstone, is a delete.
+///
+/// Partitions are read from their earliest offset to know the current value of every key, but
+/// messages before the resume offset of their partition only update that state. The last message
+/// sent from every fetch carries the offsets the partitions resume from, which the pipeline
+/// persists with its checkpoint. On restart, partitions resume from the offsets of that state, and
+/// the partition of the checkpoint right after it, as the checkpoint may be in a later fetch.
+pub struct PlainStreamConsumer {
+    topic: String,
+    decoder: ValueDecoder,
+    schema_id: SchemaIdentifier,
+    checkpoint: Option<(u64, u64)>,
+    positions: Vec<(i32, i64)>,
+    resume_offsets: HashMap<i32, i64>,
+    rows: HashMap<String, Vec<Field>>,
+}
+
+impl PlainStreamConsumer {
+    pub fn new(
+        topic: String,
+        decoder: ValueDecoder,
+        schema_id: SchemaIdentifier,
+        checkpoint: Option<(u64, u64)>,
+        state: Option<&[u8]>,
+    ) -> Result<Self, ConnectorError> {
+        let checkpoint = checkpoint.filter(|(txid, _)| *txid > 0);
+        let resume_offsets = match (checkpoint, state) {
+            (Some(_), Some(state)) => decode_offsets(state)?,
+            _ => HashMap::new(),
+        };
+        Ok(Self {
+            topic,
+            decoder,
+            schema_id,
+            checkpoint,
+            positions: vec![],
+            resume_offsets,
+            rows: HashMap::new(),
+        })
+    }
+
+    pub fn run(
+        &mut self,
+        client: &mut dyn PartitionClient,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        loop {
+            self.poll(client, ingestor)?;
+        }
+    }
+
+    fn initialize(&mut self, client: &mut dyn PartitionClient) -> Result<(), ConnectorError> {
+        let mut partitions = client.partitions(&self.topic)?;
+        partitions.sort_unstable();
+        let earliest = client.earliest_offsets(&self.topic)?;
+
+        for partition in partitions {
+            let earliest = earliest.get(&partition).copied().unwrap_or(0);
+            let mut resume_offset = self
+                .resume_offsets
+                .get(&partition)
+                .copied()
+                .unwrap_or(earliest);
+            if let Some((txid, seq)) = self.checkpoint {
+                if seq == partition as u64 {
+                    resume_offset = resume_offset.max(txid as i64);
+                }
+            }
+            debug!(
+                "Partition {} of {} resumes from offset {}",
+                partition, self.topic, resume_offset
+            );
+            self.positions.push((partition, earliest));
+            self.resume_offsets.insert(partition, resume_offset);
+        }
+        Ok(())
+    }
+
+    /// Returns the offsets every partition resumes from if the pipeline stops after the messages
+    /// read so far.
+    fn state(&self) -> Vec<u8> {
+        let offsets = self
+            .positions
+            .iter()
+            .map(|(partition, position)| {
+                (*partition, (*position).max(self.resume_offsets[partition]))
+            })
+            .collect::<Vec<_>>();
+        bincode::serialize(&offsets).expect("Offsets must be serializable")
+    }
+
+    /// Fetches the next messages of every partition, returning how many were read.
+    pub fn poll(
+        &mut self,
+        client: &mut dyn PartitionClient,
+        ingestor: &Ingestor,
+    ) -> Result<usize, ConnectorError> {
+        if self.positions.is_empty() {
+            self.initialize(client)?;
+        }
+
+        let mut count = 0;
+        for idx in 0..self.positions.len() {
+            let (partition, position) = self.positions[idx];
+            let messages = client.fetch(&self.topic, partition, position)?;
+            let Some(last) = messages.last() else {
+                continue;
+            };
+            self.positions[idx].1 = last.offset + 1;
+            count += messages.len();
+
+            let mut ops = vec![];
+            for message in messages {
+                if let Some(op) = self.handle_message(partition, message)? {
+                    ops.push(op);
+                }
+            }
+            // Messages that aren't sent don't change the pipeline, so the state of the last
+            // message sent can include them
+            let Some(last) = ops.pop() else {
+                continue;
+            };
+            for message in ops {
+                ingestor
+                    .handle_message(message)
+                    .map_err(ConnectorError::IngestorError)?;
+            }
+            ingestor
+                .handle_message(last.with_state(self.state()))
+                .map_err(ConnectorError::IngestorError)?;
+        }
+        Ok(count)
+    }
+
+    fn handle_message(
+        &mut self,
+        partition: i32,
+        message: KafkaMessage,
+    ) -> Result<Option<IngestionMessage>, ConnectorError> {
+        if message.key.is_empty() {
+            return Err(KafkaError::MissingKey(partition, message.offset).into());
+        }
+        let key = String::from_utf8(message.key)
+            .map_err(|_| KafkaError::InvalidKey(partition, message.offset))?;
+
+        let op = if message.value.is_empty() {
+            self.rows.remove(&key).map(|old| Operation::Delete {
+                old: self.record(old),
+            })
+        } else {
+            let mut values = vec![Field::String(key.clone())];
+            values.extend(self.decoder.decode(&message.value)?);
+            match self.rows.insert(key, values.clone()) {
+                None => Some(Operation::Insert {
+                    new: self.record(values),
+                }),
+                Some(old) if old == values => None,
+                Some(old) => Some(Operation::Update {
+                    old: self.record(old),
+                    new: self.record(values),
+                }),
+            }
+        };
+
+        let Some(op) = op else {
+            return Ok(None);
+        };
+        if message.offset < self.resume_offsets[&partition] {
+            return Ok(None);
+        }
+        let (txid, seq_in_tx) = message_id(partition, message.offset);
+        Ok(Some(IngestionMessage::new_op(txid, seq_in_tx, op)))
+    }
+
+    fn record(&self, values: Vec<Field>) -> Record {
+        Record::new(Some(self.schema_id), values, None)
+    }
+}
+
+fn decode_offsets(state: &[u8]) -> Result<HashMap<i32, i64>, ConnectorError> {
+    let offsets: Vec<(i32, i64)> = bincode::deserialize(state).map_err(KafkaError::InvalidState)?;
+    Ok(offsets.into_iter().collect())
+}
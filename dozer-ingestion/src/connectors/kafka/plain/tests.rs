@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dozer_types::bincode;
+use dozer_types::ingestion_types::{
+    IngestionMessageKind, KafkaConfig, KafkaFormat, KafkaJsonField, KafkaJsonFormat,
+    KafkaProtobufFormat,
+};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Operation, SchemaIdentifier};
+use prost_reflect::prost::Message;
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+use prost_reflect::prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+};
+use prost_reflect::{DynamicMessage, Value};
+
+use super::client::{KafkaMessage, PartitionClient};
+use super::decoder::{convert_json_value, get_schema, ValueDecoder};
+use super::stream_consumer::{message_id, PlainStreamConsumer};
+use crate::errors::{ConnectorError, KafkaError};
+use crate::ingestion::{IngestionConfig, IngestionIterator, Ingestor};
+
+const TOPIC: &str = "users";
+
+/// Serves messages from memory, two at a time.
+#[derive(Default)]
+struct MockClient {
+    partitions: HashMap<i32, Vec<(String, String)>>,
+}
+
+impl MockClient {
+    fn send(&mut self, partition: i32, key: &str, value: &str) {
+        self.partitions
+            .entry(partition)
+            .or_default()
+            .push((key.to_string(), value.to_string()));
+    }
+}
+
+impl PartitionClient for MockClient {
+    fn partitions(&mut self, _topic: &str) -> Result<Vec<i32>, ConnectorError> {
+        Ok(self.partitions.keys().copied().collect())
+    }
+
+    fn earliest_offsets(&mut self, _topic: &str) -> Result<HashMap<i32, i64>, ConnectorError> {
+        Ok(self
+            .partitions
+            .keys()
+            .map(|partition| (*partition, 0))
+            .collect())
+    }
+
+    fn fetch(
+        &mut self,
+        _topic: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<Vec<KafkaMessage>, ConnectorError> {
+        Ok(self.partitions[&partition]
+            .iter()
+            .enumerate()
+            .skip(offset as usize)
+            .take(2)
+            .map(|(offset, (key, value))| KafkaMessage {
+                offset: offset as i64,
+                key: key.as_bytes().to_vec(),
+                value: value.as_bytes().to_vec(),
+            })
+            .collect())
+    }
+}
+
+fn json_format() -> KafkaFormat {
+    KafkaFormat::Json(KafkaJsonFormat {
+        fields: vec![
+            KafkaJsonField {
+                name: "name".to_string(),
+                typ: "string".to_string(),
+                nullable: true,
+            },
+            KafkaJsonField {
+                name: "age".to_string(),
+                typ: "int".to_string(),
+                nullable: true,
+            },
+        ],
+    })
+}
+
+fn new_consumer(
+    checkpoint: Option<(u64, u64)>,
+    state: Option<&[u8]>,
+) -> Result<PlainStreamConsumer, ConnectorError> {
+    PlainStreamConsumer::new(
+        TOPIC.to_string(),
+        ValueDecoder::new(&json_format()).unwrap(),
+        SchemaIdentifier { id: 1, version: 1 },
+        checkpoint,
+        state,
+    )
+}
+
+type Message = ((u64, u64), Operation, Option<Vec<u8>>);
+
+fn poll_all(
+    consumer: &mut PlainStreamConsumer,
+    client: &mut MockClient,
+    ingestor: &Ingestor,
+    iterator: &mut IngestionIterator,
+) -> Vec<Message> {
+    while consumer.poll(client, ingestor).unwrap() > 0 {}
+
+    let mut ops = vec![];
+    while let Some(msg) = iterator.next_timeout(Duration::from_millis(100)) {
+        if let IngestionMessageKind::OperationEvent(op) = msg.kind {
+            ops.push((
+                (msg.identifier.txid, msg.identifier.seq_in_tx),
+                op,
+                msg.state,
+            ));
+        }
+    }
+    ops
+}
+
+fn ids(ops: &[Message]) -> Vec<(u64, u64)> {
+    ops.iter().map(|(id, _, _)| *id).collect()
+}
+
+fn encode_offsets(offsets: Vec<(i32, i64)>) -> Vec<u8> {
+    bincode::serialize(&offsets).unwrap()
+}
+
+fn populate(client: &mut MockClient) {
+    client.send(0, "alice", r#"{"name": "Alice", "age": 30}"#);
+    client.send(1, "bob", r#"{"name": "Bob"}"#);
+    client.send(0, "alice", r#"{"name": "Alice", "age": 31}"#);
+    client.send(0, "carol", r#"{"name": "Carol", "age": 25}"#);
+    client.send(1, "bob", "");
+}
+
+#[test]
+fn test_plain_upserts_and_tombstones() {
+    let mut client = MockClient::default();
+    populate(&mut client);
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let mut consumer = new_consumer(None, None).unwrap();
+
+    let ops = poll_all(&mut consumer, &mut client, &ingestor, &mut iterator);
+    assert_eq!(ids(&ops), vec![(1, 0), (2, 0), (1, 1), (2, 1), (3, 0)]);
+
+    match &ops[0].1 {
+        Operation::Insert { new } => assert_eq!(
+            new.values,
+            vec![
+                Field::String("alice".to_string()),
+                Field::String("Alice".to_string()),
+                Field::Int(30)
+            ]
+        ),
+        op => panic!("Expected insert, got {op:?}"),
+    }
+    match &ops[1].1 {
+        Operation::Update { old, new } => {
+            assert_eq!(old.values[2], Field::Int(30));
+            assert_eq!(new.values[2], Field::Int(31));
+        }
+        op => panic!("Expected update, got {op:?}"),
+    }
+    match &ops[3].1 {
+        Operation::Delete { old } => {
+            assert_eq!(old.values[0], Field::String("bob".to_string()));
+            assert_eq!(old.values[2], Field::Null);
+        }
+        op => panic!("Expected delete, got {op:?}"),
+    }
+
+    // The last message sent from every fetch carries the offsets to resume from
+    let states = ops
+        .into_iter()
+        .map(|(_, _, state)| state)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        vec![
+            None,
+            Some(encode_offsets(vec![(0, 2), (1, 0)])),
+            None,
+            Some(encode_offsets(vec![(0, 2), (1, 2)])),
+            Some(encode_offsets(vec![(0, 3), (1, 2)])),
+        ]
+    );
+}
+
+#[test]
+fn test_plain_resume_from_checkpoint() {
+    let mut client = MockClient::default();
+    populate(&mut client);
+    // The pipeline committed up to the tombstone of bob, after the state sent with the update of
+    // alice, so partition 1 resumes after the checkpoint instead of the offset of the state
+    let checkpoint = message_id(1, 1);
+    assert_eq!(checkpoint, (2, 1));
+    let state = encode_offsets(vec![(0, 2), (1, 0)]);
+
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let mut consumer = new_consumer(Some(checkpoint), Some(state.as_slice())).unwrap();
+    let ops = poll_all(&mut consumer, &mut client, &ingestor, &mut iterator);
+    assert_eq!(ids(&ops), vec![(3, 0)]);
+    assert!(matches!(ops[0].1, Operation::Insert { .. }));
+
+    // Keys seen before the checkpoint are known
+    client.send(0, "alice", "");
+    client.send(1, "bob", r#"{"name": "Bob", "age": 40}"#);
+    let ops = poll_all(&mut consumer, &mut client, &ingestor, &mut iterator);
+    assert_eq!(ids(&ops), vec![(4, 0), (3, 1)]);
+    match &ops[0].1 {
+        Operation::Delete { old } => assert_eq!(old.values[2], Field::Int(31)),
+        op => panic!("Expected delete, got {op:?}"),
+    }
+    // The tombstone of bob was consumed, so bob is inserted again
+    assert!(matches!(ops[1].1, Operation::Insert { .. }));
+
+    assert!(matches!(
+        new_consumer(Some(checkpoint), Some(&[1][..])),
+        Err(ConnectorError::KafkaError(KafkaError::InvalidState(_)))
+    ));
+}
+
+#[test]
+fn test_plain_missing_key() {
+    let mut client = MockClient::default();
+    client.send(0, "", r#"{"name": "Nobody"}"#);
+    let (ingestor, _iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let mut consumer = new_consumer(None, None).unwrap();
+    assert!(matches!(
+        consumer.poll(&mut client, &ingestor),
+        Err(ConnectorError::KafkaError(KafkaError::MissingKey(0, 0)))
+    ));
+}
+
+#[test]
+fn test_plain_schema() {
+    let mut config = KafkaConfig {
+        broker: "localhost:9092".to_string(),
+        schema_registry_url: None,
+        format: Some(json_format()),
+        key_column: "key".to_string(),
+    };
+    let decoder = ValueDecoder::new(config.format.as_ref().unwrap()).unwrap();
+    let schema = get_schema(&config, &decoder, SchemaIdentifier { id: 1, version: 1 }).unwrap();
+    let fields = schema
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.typ))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("key", FieldType::String),
+            ("name", FieldType::String),
+            ("age", FieldType::Int)
+        ]
+    );
+    assert_eq!(schema.primary_index, vec![0]);
+
+    config.key_column = "name".to_string();
+    assert!(matches!(
+        get_schema(&config, &decoder, SchemaIdentifier { id: 1, version: 1 }),
+        Err(ConnectorError::KafkaError(KafkaError::KeyColumnConflict(_)))
+    ));
+}
+
+#[test]
+fn test_convert_json_value() {
+    let field = |typ, nullable| FieldDefinition {
+        name: "field".to_string(),
+        typ,
+        nullable,
+        source: dozer_types::types::SourceDefinition::Dynamic,
+    };
+    let json = |value: &str| dozer_types::serde_json::from_str(value).unwrap();
+
+    assert_eq!(
+        convert_json_value(&field(FieldType::UInt, true), &json("5")).unwrap(),
+        Field::UInt(5)
+    );
+    assert_eq!(
+        convert_json_value(&field(FieldType::Decimal, true), &json("1.5")).unwrap(),
+        Field::Decimal(dozer_types::rust_decimal::Decimal::new(15, 1))
+    );
+    assert_eq!(
+        convert_json_value(&field(FieldType::Bson, true), &json(r#"{"a":[1]}"#)).unwrap(),
+        Field::Bson(br#"{"a":[1]}"#.to_vec())
+    );
+    assert_eq!(
+        convert_json_value(&field(FieldType::Int, true), &json("null")).unwrap(),
+        Field::Null
+    );
+    assert!(convert_json_value(&field(FieldType::Int, false), &json("null")).is_err());
+    assert!(convert_json_value(&field(FieldType::Int, true), &json(r#""five""#)).is_err());
+    assert!(matches!(
+        convert_json_value(
+            &field(FieldType::Timestamp, true),
+            &json(r#""2023-01-01T00:00:00Z""#)
+        ),
+        Ok(Field::Timestamp(_))
+    ));
+}
+
+fn write_descriptor_set() -> String {
+    let field = |name: &str, number, typ: Type, label: Label| FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        r#type: Some(typ as i32),
+        label: Some(label as i32),
+        json_name: Some(name.to_string()),
+        ..Default::default()
+    };
+    let set = FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("user.proto".to_string()),
+            package: Some("test".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("User".to_string()),
+                field: vec![
+                    field("id", 1, Type::Int64, Label::Optional),
+                    field("name", 2, Type::String, Label::Optional),
+                    field("tags", 3, Type::String, Label::Repeated),
+                ],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        }],
+    };
+
+    let path = std::env::temp_dir()
+        .join(format!("dozer_kafka_{}.desc", rand::random::<u64>()))
+        .to_str()
+        .unwrap()
+        .to_string();
+    std::fs::write(&path, set.encode_to_vec()).unwrap();
+    path
+}
+
+#[test]
+fn test_protobuf_decoder() {
+    let path = write_descriptor_set();
+    let decoder = ValueDecoder::new(&KafkaFormat::Protobuf(KafkaProtobufFormat {
+        descriptor_path: path.clone(),
+        message: "test.User".to_string(),
+    }))
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let types = decoder
+        .fields()
+        .iter()
+        .map(|field| field.typ)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![FieldType::Int, FieldType::String, FieldType::Bson]
+    );
+
+    let ValueDecoder::Protobuf(descriptor) = &decoder else {
+        panic!("Expected protobuf decoder");
+    };
+    let mut message = DynamicMessage::new(descriptor.clone());
+    message.set_field_by_name("id", Value::I64(7));
+    message.set_field_by_name("name", Value::String("alice".to_string()));
+    message.set_field_by_name(
+        "tags",
+        Value::List(vec![Value::String("admin".to_string())]),
+    );
+
+    assert_eq!(
+        decoder.decode(&message.encode_to_vec()).unwrap(),
+        vec![
+            Field::Int(7),
+            Field::String("alice".to_string()),
+            Field::Bson(br#"["admin"]"#.to_vec())
+        ]
+    );
+
+    assert!(matches!(
+        ValueDecoder::new(&KafkaFormat::Protobuf(KafkaProtobufFormat {
+            descriptor_path: "missing.desc".to_string(),
+            message: "test.User".to_string(),
+        })),
+        Err(ConnectorError::KafkaError(KafkaError::DescriptorReadError(
+            _,
+            _
+        )))
+    ));
+}
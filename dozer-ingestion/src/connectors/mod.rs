@@ -3,6 +3,7 @@ pub mod grpc;
 pub mod kafka;
 pub mod object_store;
 pub mod postgres;
+pub mod sql_polling;
 
 use crate::connectors::postgres::connection::helper::map_connection_config;
 use std::collections::HashMap;
@@ -17,6 +18,7 @@ use dozer_types::models::connection::Connection;
 use dozer_types::models::connection::ConnectionConfig;
 
 use crate::connectors::object_store::connector::ObjectStoreConnector;
+use crate::connectors::sql_polling::connector::SqlPollingConnector;
 use dozer_types::prettytable::Table;
 use dozer_types::serde;
 use dozer_types::serde::{Deserialize, Serialize};
@@ -40,6 +42,10 @@ pub trait Connector: Send + Sync + Debug {
     ) -> Result<Vec<SourceSchema>, ConnectorError>;
 
     fn can_start_from(&self, last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError>;
+    /// Restores the state the connector attached to its last checkpointed message.
+    fn restore_state(&mut self, _state: Vec<u8>) -> Result<(), ConnectorError> {
+        Ok(())
+    }
     fn start(
         &self,
         last_checkpoint: Option<(u64, u64)>,
@@ -144,6 +150,11 @@ pub fn get_connector(connection: Connection) -> Result<Box<dyn Connector>, Conne
         ConnectionConfig::LocalStorage(object_store_config) => {
             Ok(Box::new(ObjectStoreConnector::new(5, object_store_config)))
         }
+        ConnectionConfig::SqlPolling(sql_polling_config) => Ok(Box::new(SqlPollingConnector::new(
+            6,
+            connection.name,
+            sql_polling_config,
+        ))),
     }
 }
 
@@ -155,6 +166,7 @@ pub fn get_connector_info_table(connection: &Connection) -> Option<Table> {
         Some(ConnectionConfig::Kafka(config)) => Some(config.convert_to_table()),
         Some(ConnectionConfig::S3Storage(config)) => Some(config.convert_to_table()),
         Some(ConnectionConfig::LocalStorage(config)) => Some(config.convert_to_table()),
+        Some(ConnectionConfig::SqlPolling(config)) => Some(config.convert_to_table()),
         _ => None,
     }
 }
@@ -147,6 +147,31 @@ pub fn convert_data(
     }
 }
 
+pub fn odbc_type_to_field_type(
+    column_descriptor: &ColumnDescriptor,
+) -> Result<FieldType, SnowflakeSchemaError> {
+    match column_descriptor.data_type {
+        SqlDataType::SQL_CHAR | SqlDataType::SQL_VARCHAR => Ok(FieldType::String),
+        SqlDataType::SQL_DECIMAL
+        | SqlDataType::SQL_NUMERIC
+        | SqlDataType::SQL_INTEGER
+        | SqlDataType::SQL_SMALLINT => match column_descriptor.decimal_digits {
+            None => Ok(FieldType::Int),
+            Some(_) => Ok(FieldType::Decimal),
+        },
+        SqlDataType::SQL_FLOAT | SqlDataType::SQL_REAL | SqlDataType::SQL_DOUBLE => {
+            Ok(FieldType::Float)
+        }
+        SqlDataType::SQL_TIMESTAMP => Ok(FieldType::Timestamp),
+        SqlDataType::SQL_DATE => Ok(FieldType::Date),
+        SqlDataType::SQL_EXT_BIT => Ok(FieldType::Boolean),
+        _ => Err(SnowflakeSchemaError::ColumnTypeNotSupported(format!(
+            "{:?}",
+            &column_descriptor.data_type
+        ))),
+    }
+}
+
 pub struct ResultIterator<'a, 'b> {
     stmt: Option<Statement<'a, 'b, Executed, HasResult, AutocommitOn>>,
     cols: i16,
@@ -221,6 +246,10 @@ impl Client {
         Self { conn_string }
     }
 
+    pub fn from_conn_string(conn_string: String) -> Self {
+        Self { conn_string }
+    }
+
     pub fn get_conn_string(&self) -> String {
         self.conn_string.clone()
     }
@@ -22,8 +22,9 @@ use tokio::runtime::Runtime;
 #[cfg(feature = "snowflake")]
 use tokio::time;
 
+use crate::errors::SnowflakeError;
 #[cfg(feature = "snowflake")]
-use crate::errors::{SnowflakeError, SnowflakeStreamError};
+use crate::errors::SnowflakeStreamError;
 
 #[derive(Debug)]
 pub struct SnowflakeConnector {
@@ -39,7 +40,7 @@ impl SnowflakeConnector {
 
 impl Connector for SnowflakeConnector {
     fn validate(&self, _tables: Option<Vec<TableInfo>>) -> Result<(), ConnectorError> {
-        Ok(())
+        validate_config(&self.config)
     }
 
     #[cfg(not(feature = "snowflake"))]
@@ -90,11 +91,31 @@ impl Connector for SnowflakeConnector {
         self.get_tables_default(tables)
     }
 
+    #[cfg(feature = "snowflake")]
+    fn can_start_from(&self, (batch_id, _): (u64, u64)) -> Result<bool, ConnectorError> {
+        // Checkpoints are `(batch_id, row_idx)`, see `StreamConsumer`. Batch ids only grow and the
+        // temp table of the last batch of every stream is kept, so a batch older than that one was
+        // fully sent, while a newer one was not produced by these streams.
+        let client = Client::new(&self.config);
+        Ok(StreamConsumer::get_last_batch_id(&client)?
+            .map_or(false, |last_batch_id| batch_id <= last_batch_id))
+    }
+
+    #[cfg(not(feature = "snowflake"))]
     fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError> {
-        Ok(false)
+        todo!()
     }
 }
 
+fn validate_config(config: &SnowflakeConfig) -> Result<(), ConnectorError> {
+    if config.poll_interval_seconds == 0 {
+        return Err(ConnectorError::SnowflakeError(
+            SnowflakeError::InvalidPollInterval,
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(feature = "snowflake")]
 async fn run(
     name: String,
@@ -103,13 +124,14 @@ async fn run(
     ingestor: &Ingestor,
     from_seq: Option<(u64, u64)>,
 ) -> Result<(), ConnectorError> {
+    validate_config(&config)?;
     let client = Client::new(&config);
 
     // SNAPSHOT part - run it when stream table doesnt exist
     let stream_client = Client::new(&config);
-    let mut interval = time::interval(Duration::from_secs(5));
+    let mut interval = time::interval(Duration::from_secs(config.poll_interval_seconds));
 
-    let mut consumer = StreamConsumer::new();
+    let mut consumer = StreamConsumer::new(from_seq);
     let mut iteration = 0;
     loop {
         for (idx, table) in tables.iter().enumerate() {
@@ -119,14 +141,14 @@ async fn run(
                     None | Some((0, _)) => {
                         info!("[{}][{}] Creating new stream", name, table.table_name);
                         StreamConsumer::drop_stream(&client, &table.table_name)?;
+                        StreamConsumer::drop_temp_table(&client, &table.table_name)?;
                         StreamConsumer::create_stream(&client, &table.table_name)?;
                     }
-                    Some((lsn, seq)) => {
+                    Some((batch_id, seq)) => {
                         info!(
                             "[{}][{}] Continuing ingestion from {}/{}",
-                            name, table.table_name, lsn, seq
+                            name, table.table_name, batch_id, seq
                         );
-                        iteration = lsn;
                         if let Ok(false) =
                             StreamConsumer::is_stream_created(&client, &table.table_name)
                         {
@@ -145,7 +167,7 @@ async fn run(
                 name, table.table_name
             );
 
-            consumer.consume_stream(&stream_client, &table.table_name, ingestor, idx, iteration)?;
+            consumer.consume_stream(&stream_client, &table.table_name, ingestor, idx)?;
 
             interval.tick().await;
         }
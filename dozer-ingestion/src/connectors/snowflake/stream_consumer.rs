@@ -4,16 +4,38 @@ use crate::errors::{ConnectorError, SnowflakeError};
 use crate::ingestion::Ingestor;
 use dozer_types::ingestion_types::IngestionMessage;
 
-use crate::errors::SnowflakeStreamError::{CannotDetermineAction, UnsupportedActionInStream};
+use crate::errors::SnowflakeStreamError::{
+    CannotDetermineAction, InvalidBatchId, UnsupportedActionInStream,
+};
 use dozer_types::types::{Field, Operation, Record, SchemaIdentifier};
-use odbc::create_environment_v3;
+use odbc::odbc_safe::AutocommitOn;
+use odbc::{create_environment_v3, Connection};
 
-#[derive(Default)]
-pub struct StreamConsumer {}
+/// Consumes table streams in batches.
+///
+/// Every time a stream is consumed its changes are moved into a transient table tagged with a
+/// batch id, and the changes are sent as `(batch_id, row_idx)`. The transient table is only dropped
+/// at the next poll, so if the process stops before the batch was committed, restarting with the
+/// last checkpoint replays the rows of the batch that come after it and skips the others.
+pub struct StreamConsumer {
+    last_sent: Option<(u64, u64)>,
+    next_batch_id: u64,
+}
 
 impl StreamConsumer {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(from_seq: Option<(u64, u64)>) -> Self {
+        Self {
+            last_sent: from_seq,
+            next_batch_id: from_seq.map_or(1, |(batch_id, _)| batch_id + 1),
+        }
+    }
+
+    /// Whether the row identified by `(batch_id, row_idx)` was sent before.
+    pub fn is_sent(last_sent: Option<(u64, u64)>, id: (u64, u64)) -> bool {
+        match last_sent {
+            None | Some((0, _)) => false,
+            Some(last_sent) => id <= last_sent,
+        }
     }
 
     pub fn get_stream_table_name(table_name: &str) -> String {
@@ -49,6 +71,23 @@ impl StreamConsumer {
         client.exec(&conn, query)
     }
 
+    pub fn drop_temp_table(
+        client: &Client,
+        table_name: &str,
+    ) -> Result<Option<bool>, SnowflakeError> {
+        let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+        let conn = env
+            .connect_with_connection_string(&client.get_conn_string())
+            .unwrap();
+
+        let query = format!(
+            "DROP TABLE IF EXISTS {}",
+            Self::get_stream_temp_table_name(table_name),
+        );
+
+        client.exec(&conn, query)
+    }
+
     pub fn create_stream(client: &Client, table_name: &String) -> Result<(), ConnectorError> {
         let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
         let conn = env
@@ -125,7 +164,6 @@ impl StreamConsumer {
         table_name: &str,
         ingestor: &Ingestor,
         table_idx: usize,
-        iteration: u64,
     ) -> Result<(), ConnectorError> {
         let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
         let conn = env
@@ -134,39 +172,122 @@ impl StreamConsumer {
 
         let temp_table_name = Self::get_stream_temp_table_name(table_name);
         let stream_name = Self::get_stream_table_name(table_name);
-        let temp_table_exist = client.table_exist(&conn, &temp_table_name)?;
 
-        if !temp_table_exist {
-            let query = format!(
-                "CREATE OR REPLACE TEMP TABLE {temp_table_name} AS
-                    SELECT * FROM {stream_name} ORDER BY METADATA$ACTION;"
-            );
+        // Rows of the previous batch were sent, but may not have been committed before a restart
+        if client.table_exist(&conn, &temp_table_name)? {
+            let batch_id =
+                Self::get_batch_id(client, &conn, &temp_table_name)?.ok_or_else(|| {
+                    SnowflakeError::SnowflakeStreamError(InvalidBatchId(temp_table_name.clone()))
+                })?;
+            self.next_batch_id = self.next_batch_id.max(batch_id + 1);
+            self.send_batch(
+                client,
+                &conn,
+                &temp_table_name,
+                ingestor,
+                table_idx,
+                batch_id,
+            )?;
 
-            client.exec(&conn, query)?;
+            client.exec(&conn, format!("DROP TABLE {temp_table_name};"))?;
         }
 
-        let result = client.fetch(&conn, format!("SELECT * FROM {temp_table_name};"))?;
-        if let Some((schema, iterator)) = result {
-            let mut truncated_schema = schema.clone();
-            truncated_schema.truncate(schema.len() - 3);
+        let batch_id = self.next_batch_id;
+        self.next_batch_id += 1;
+
+        let query = format!(
+            "CREATE OR REPLACE TRANSIENT TABLE {temp_table_name} COMMENT = '{batch_id}' AS
+                SELECT * FROM {stream_name} ORDER BY METADATA$ACTION;"
+        );
+        client.exec(&conn, query)?;
 
+        self.send_batch(
+            client,
+            &conn,
+            &temp_table_name,
+            ingestor,
+            table_idx,
+            batch_id,
+        )
+    }
+
+    /// Returns the id of the newest batch kept by the temp tables of all streams.
+    pub fn get_last_batch_id(client: &Client) -> Result<Option<u64>, ConnectorError> {
+        let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+        let conn = env
+            .connect_with_connection_string(&client.get_conn_string())
+            .unwrap();
+
+        let query = format!(
+            "SELECT TABLE_NAME, COMMENT FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_NAME ILIKE '{}';",
+            Self::get_stream_temp_table_name("%"),
+        );
+        let mut last_batch_id = None;
+        if let Some((_, iterator)) = client.fetch(&conn, query)? {
+            for row in iterator {
+                let batch_id = match row.get(1) {
+                    Some(Field::String(comment)) => comment.parse::<u64>().ok(),
+                    _ => None,
+                };
+                let batch_id = batch_id.ok_or_else(|| {
+                    let table_name = match row.get(0) {
+                        Some(Field::String(table_name)) => table_name.clone(),
+                        _ => String::new(),
+                    };
+                    SnowflakeError::SnowflakeStreamError(InvalidBatchId(table_name))
+                })?;
+                last_batch_id = last_batch_id.max(Some(batch_id));
+            }
+        }
+        Ok(last_batch_id)
+    }
+
+    fn get_batch_id(
+        client: &Client,
+        conn: &Connection<AutocommitOn>,
+        temp_table_name: &str,
+    ) -> Result<Option<u64>, ConnectorError> {
+        let query = format!(
+            "SELECT COMMENT FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_NAME = '{temp_table_name}';"
+        );
+        let batch_id = client.fetch(conn, query)?.and_then(|(_, mut iterator)| {
+            match iterator.next()?.into_iter().next()? {
+                Field::String(comment) => comment.parse::<u64>().ok(),
+                _ => None,
+            }
+        });
+        Ok(batch_id)
+    }
+
+    fn send_batch(
+        &mut self,
+        client: &Client,
+        conn: &Connection<AutocommitOn>,
+        temp_table_name: &str,
+        ingestor: &Ingestor,
+        table_idx: usize,
+        batch_id: u64,
+    ) -> Result<(), ConnectorError> {
+        let result = client.fetch(conn, format!("SELECT * FROM {temp_table_name};"))?;
+        if let Some((schema, iterator)) = result {
             let columns_length = schema.len();
             let used_columns_for_schema = columns_length - 3;
             let action_idx = used_columns_for_schema;
 
             for (idx, row) in iterator.enumerate() {
+                let id = (batch_id, idx as u64);
+                if Self::is_sent(self.last_sent, id) {
+                    continue;
+                }
+
                 let op = Self::get_operation(row, action_idx, used_columns_for_schema, table_idx)?;
                 ingestor
-                    .handle_message(IngestionMessage::new_op(iteration, idx as u64, op))
+                    .handle_message(IngestionMessage::new_op(id.0, id.1, op))
                     .map_err(ConnectorError::IngestorError)?;
+                self.last_sent = Some(id);
             }
         }
 
-        let query = format!("DROP TABLE {temp_table_name};");
-
-        client
-            .exec(&conn, query)
-            .map_err(ConnectorError::SnowflakeError)
-            .map(|_| ())
+        Ok(())
     }
 }
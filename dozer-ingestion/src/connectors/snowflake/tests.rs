@@ -264,3 +264,19 @@ fn test_disabled_connector_is_stream_created() {
         );
     });
 }
+
+#[test]
+fn test_stream_consumer_is_sent() {
+    assert!(!StreamConsumer::is_sent(None, (1, 0)));
+    assert!(!StreamConsumer::is_sent(Some((0, 0)), (1, 0)));
+
+    // Rows of the checkpoint batch up to the checkpoint row and rows of earlier batches are skipped
+    let checkpoint = Some((5, 3));
+    assert!(StreamConsumer::is_sent(checkpoint, (4, 100)));
+    assert!(StreamConsumer::is_sent(checkpoint, (5, 0)));
+    assert!(StreamConsumer::is_sent(checkpoint, (5, 3)));
+
+    // The rest of the checkpoint batch and later batches are replayed
+    assert!(!StreamConsumer::is_sent(checkpoint, (5, 4)));
+    assert!(!StreamConsumer::is_sent(checkpoint, (6, 0)));
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::Duration;
+
+use dozer_types::bincode;
+use dozer_types::ingestion_types::{IngestionMessage, SqlPollingConfig};
+use dozer_types::log::{debug, info};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, ReplicationChangesTrackingType, Schema,
+    SchemaIdentifier, SourceSchema,
+};
+
+use super::driver::{connect_driver, SqlDriver};
+use crate::connectors::{Connector, TableInfo, ValidationResults};
+use crate::errors::{ConnectorError, SqlPollingError};
+use crate::ingestion::Ingestor;
+
+#[derive(Debug)]
+pub struct SqlPollingConnector {
+    pub id: u64,
+    name: String,
+    config: SqlPollingConfig,
+    state: Option<Vec<u8>>,
+}
+
+impl SqlPollingConnector {
+    pub fn new(id: u64, name: String, config: SqlPollingConfig) -> Self {
+        Self {
+            id,
+            name,
+            config,
+            state: None,
+        }
+    }
+}
+
+impl Connector for SqlPollingConnector {
+    fn validate(&self, _tables: Option<Vec<TableInfo>>) -> Result<(), ConnectorError> {
+        let mut driver = connect_driver(&self.config)?;
+        get_schema(driver.as_mut(), &self.config).map(|_| ())
+    }
+
+    fn validate_schemas(&self, _tables: &[TableInfo]) -> Result<ValidationResults, ConnectorError> {
+        Ok(HashMap::new())
+    }
+
+    fn get_schemas(
+        &self,
+        _table_names: Option<Vec<TableInfo>>,
+    ) -> Result<Vec<SourceSchema>, ConnectorError> {
+        let mut driver = connect_driver(&self.config)?;
+        Ok(vec![SourceSchema::new(
+            self.config.table_name.clone(),
+            get_schema(driver.as_mut(), &self.config)?,
+            // Rows changed while the connector was stopped are unknown, see `Poller`
+            ReplicationChangesTrackingType::OnlyPK,
+        )])
+    }
+
+    fn can_start_from(&self, _last_checkpoint: (u64, u64)) -> Result<bool, ConnectorError> {
+        // Checkpoints are `(cursor, seq_in_cursor)` and the query can be resumed from any cursor
+        Ok(true)
+    }
+
+    fn restore_state(&mut self, state: Vec<u8>) -> Result<(), ConnectorError> {
+        self.state = Some(state);
+        Ok(())
+    }
+
+    fn start(
+        &self,
+        from_seq: Option<(u64, u64)>,
+        ingestor: &Ingestor,
+        _tables: Vec<TableInfo>,
+    ) -> Result<(), ConnectorError> {
+        info!(
+            "[{}] Polling {} every {}s",
+            self.name, self.config.table_name, self.config.poll_interval_seconds
+        );
+        let mut driver = connect_driver(&self.config)?;
+        let mut poller = Poller::new(self.config.clone(), from_seq, self.state.as_deref())?;
+        loop {
+            poller.poll(driver.as_mut(), ingestor)?;
+            thread::sleep(Duration::from_secs(self.config.poll_interval_seconds));
+        }
+    }
+
+    fn get_tables(&self, tables: Option<&[TableInfo]>) -> Result<Vec<TableInfo>, ConnectorError> {
+        self.get_tables_default(tables)
+    }
+}
+
+fn get_schema_id() -> SchemaIdentifier {
+    SchemaIdentifier { id: 0, version: 1 }
+}
+
+fn get_schema(
+    driver: &mut dyn SqlDriver,
+    config: &SqlPollingConfig,
+) -> Result<Schema, ConnectorError> {
+    let (fields, _) = driver.fetch(&format!(
+        "SELECT * FROM ({}) dozer_polling WHERE 1 = 0",
+        config.query
+    ))?;
+    let indexes = ColumnIndexes::new(&fields, config)?;
+    Ok(Schema {
+        identifier: Some(get_schema_id()),
+        fields,
+        primary_index: indexes.primary_key,
+    })
+}
+
+struct ColumnIndexes {
+    cursor: usize,
+    primary_key: Vec<usize>,
+}
+
+impl ColumnIndexes {
+    fn new(fields: &[FieldDefinition], config: &SqlPollingConfig) -> Result<Self, ConnectorError> {
+        let find = |name: &str| {
+            fields
+                .iter()
+                .position(|field| field.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| SqlPollingError::ColumnNotFound(name.to_string()))
+        };
+
+        let cursor = find(&config.cursor_column)?;
+        if !matches!(
+            fields[cursor].typ,
+            FieldType::Int | FieldType::UInt | FieldType::Timestamp
+        ) {
+            return Err(
+                SqlPollingError::CursorTypeNotSupported(config.cursor_column.clone()).into(),
+            );
+        }
+
+        if config.primary_key.is_empty() {
+            return Err(SqlPollingError::PrimaryKeyMissing.into());
+        }
+        let primary_key = config
+            .primary_key
+            .iter()
+            .map(|name| find(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cursor,
+            primary_key,
+        })
+    }
+}
+
+/// Maps a cursor value to the `txid` of the checkpoint.
+pub fn cursor_to_txid(cursor: &Field) -> Result<u64, ConnectorError> {
+    let txid = match cursor {
+        Field::Int(value) => u64::try_from(*value).ok(),
+        Field::UInt(value) => Some(*value),
+        Field::Timestamp(value) => u64::try_from(value.timestamp_micros()).ok(),
+        _ => None,
+    };
+    txid.filter(|txid| *txid > 0)
+        .ok_or_else(|| SqlPollingError::InvalidCursorValue(cursor.clone()).into())
+}
+
+/// Keys sent before a poll, and the keys the poll sends for the first time with their identifiers.
+type PollerState = (Vec<Vec<Field>>, Vec<((u64, u64), Vec<Field>)>);
+
+/// Runs the query of a [`SqlPollingConfig`] and sends its new and changed rows to the ingestor.
+///
+/// The first poll reads all rows, later polls read rows whose cursor is greater than or equal to
+/// the last cursor seen, so that rows added later with the same cursor are not missed. Rows are
+/// identified by `(cursor, seq_in_cursor)`, where `seq_in_cursor` increases with every row sent
+/// with that cursor.
+///
+/// The first message of a poll that sends new keys carries the keys of the pipeline as its state,
+/// see [`PollerState`]. On resume, the state tells exactly which keys were sent up to the
+/// checkpoint. Rows before the cursor of the checkpoint are not sent again, but still read to know
+/// the current row of every primary key. Rows from the cursor of the checkpoint on are sent again,
+/// as updates if their key was sent before. Their old values are unknown, so the pipeline looks
+/// them up by primary key, see [`ReplicationChangesTrackingType::OnlyPK`].
+pub struct Poller {
+    config: SqlPollingConfig,
+    checkpoint: Option<(u64, u64)>,
+    last_id: Option<(u64, u64)>,
+    cursor: Option<Field>,
+    rows: HashMap<Vec<Field>, Vec<Field>>,
+    sent_keys: HashSet<Vec<Field>>,
+}
+
+impl Poller {
+    pub fn new(
+        config: SqlPollingConfig,
+        checkpoint: Option<(u64, u64)>,
+        state: Option<&[u8]>,
+    ) -> Result<Self, ConnectorError> {
+        let checkpoint = checkpoint.filter(|(txid, _)| *txid > 0);
+        let mut sent_keys = HashSet::new();
+        if let (Some(checkpoint), Some(state)) = (checkpoint, state) {
+            let (sent, new): PollerState =
+                bincode::deserialize(state).map_err(SqlPollingError::InvalidState)?;
+            sent_keys.extend(sent);
+            sent_keys.extend(
+                new.into_iter()
+                    .filter(|(id, _)| *id <= checkpoint)
+                    .map(|(_, key)| key),
+            );
+        }
+        Ok(Self {
+            config,
+            checkpoint,
+            last_id: checkpoint,
+            cursor: None,
+            rows: HashMap::new(),
+            sent_keys,
+        })
+    }
+
+    fn is_committed(&self, txid: u64) -> bool {
+        matches!(self.checkpoint, Some((checkpoint_txid, _)) if txid < checkpoint_txid)
+    }
+
+    /// Returns an identifier greater than the ones of all the rows sent before.
+    fn next_id(&mut self, txid: u64) -> (u64, u64) {
+        let id = match self.last_id {
+            Some((last_txid, seq)) if last_txid >= txid => (last_txid, seq + 1),
+            _ => (txid, 0),
+        };
+        self.last_id = Some(id);
+        id
+    }
+
+    fn build_query(&self, driver: &dyn SqlDriver) -> String {
+        let literal = match &self.cursor {
+            Some(Field::Int(value)) => Some(value.to_string()),
+            Some(Field::UInt(value)) => Some(value.to_string()),
+            Some(Field::Timestamp(value)) => Some(driver.timestamp_literal(value.naive_utc())),
+            _ => None,
+        };
+        let filter = literal.map_or(String::new(), |literal| {
+            format!(" WHERE {} >= {}", self.config.cursor_column, literal)
+        });
+        format!(
+            "SELECT * FROM ({}) dozer_polling{} ORDER BY {}, {}",
+            self.config.query,
+            filter,
+            self.config.cursor_column,
+            self.config.primary_key.join(", ")
+        )
+    }
+
+    pub fn poll(
+        &mut self,
+        driver: &mut dyn SqlDriver,
+        ingestor: &Ingestor,
+    ) -> Result<(), ConnectorError> {
+        let query = self.build_query(driver);
+        debug!("Polling: {}", query);
+        let (fields, rows) = driver.fetch(&query)?;
+        let indexes = ColumnIndexes::new(&fields, &self.config)?;
+
+        let mut messages = vec![];
+        let mut new_keys = vec![];
+        for row in rows {
+            let cursor = row[indexes.cursor].clone();
+            let txid = cursor_to_txid(&cursor)?;
+            self.cursor = Some(cursor);
+
+            let key = indexes
+                .primary_key
+                .iter()
+                .map(|idx| row[*idx].clone())
+                .collect::<Vec<_>>();
+            let new = Record::new(Some(get_schema_id()), row.clone(), None);
+            let old = match self.rows.insert(key.clone(), row) {
+                Some(old) if old == new.values => continue,
+                old => old,
+            };
+
+            if self.is_committed(txid) {
+                self.sent_keys.insert(key);
+                continue;
+            }
+            let id = self.next_id(txid);
+            let op = if self.sent_keys.contains(&key) {
+                // Rows read on resume have no known old values
+                let old = old.unwrap_or_else(|| new.values.clone());
+                Operation::Update {
+                    old: Record::new(Some(get_schema_id()), old, None),
+                    new,
+                }
+            } else {
+                new_keys.push((id, key));
+                Operation::Insert { new }
+            };
+            messages.push(IngestionMessage::new_op(id.0, id.1, op));
+        }
+
+        // The state is only needed if the poll changes the keys of the pipeline
+        let mut state = (!new_keys.is_empty()).then(|| {
+            let sent = self.sent_keys.iter().collect::<Vec<_>>();
+            bincode::serialize(&(sent, &new_keys)).expect("Keys must be serializable")
+        });
+        self.sent_keys
+            .extend(new_keys.into_iter().map(|(_, key)| key));
+        for mut message in messages {
+            if let Some(state) = state.take() {
+                message = message.with_state(state);
+            }
+            ingestor
+                .handle_message(message)
+                .map_err(ConnectorError::IngestorError)?;
+        }
+
+        Ok(())
+    }
+}
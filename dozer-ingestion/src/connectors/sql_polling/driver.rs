@@ -0,0 +1,256 @@
+use std::str::FromStr;
+
+use dozer_types::chrono::{NaiveDateTime, TimeZone, Utc};
+use dozer_types::ingestion_types::SqlPollingConfig;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::prelude::FromPrimitive;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldDefinition, FieldType, SourceDefinition};
+use rusqlite::types::ValueRef;
+
+use crate::connectors::postgres::connection::helper::connect;
+use crate::connectors::postgres::helper::{convert_column_to_field, get_values};
+use crate::errors::{ConnectorError, PostgresConnectorError, SqlPollingError};
+
+/// A database the SQL polling connector can run queries against.
+pub trait SqlDriver {
+    /// Runs `query`, returning the definitions of the result columns and the result rows.
+    fn fetch(
+        &mut self,
+        query: &str,
+    ) -> Result<(Vec<FieldDefinition>, Vec<Vec<Field>>), ConnectorError>;
+
+    /// Formats `timestamp`, in UTC, as a literal that can be compared with a timestamp column.
+    fn timestamp_literal(&self, timestamp: NaiveDateTime) -> String {
+        format!("'{}'", timestamp.format("%Y-%m-%d %H:%M:%S%.f"))
+    }
+}
+
+pub fn connect_driver(config: &SqlPollingConfig) -> Result<Box<dyn SqlDriver>, ConnectorError> {
+    match config.driver.as_str() {
+        "postgres" => Ok(Box::new(PostgresDriver::new(&config.connection_string)?)),
+        "sqlite" => Ok(Box::new(SqliteDriver::new(&config.connection_string)?)),
+        #[cfg(feature = "snowflake")]
+        "odbc" => Ok(Box::new(odbc_driver::OdbcDriver::new(
+            config.connection_string.clone(),
+        ))),
+        driver => Err(SqlPollingError::UnsupportedDriver(driver.to_string()).into()),
+    }
+}
+
+pub struct PostgresDriver {
+    client: postgres::Client,
+}
+
+impl PostgresDriver {
+    pub fn new(connection_string: &str) -> Result<Self, ConnectorError> {
+        let config = tokio_postgres::Config::from_str(connection_string)
+            .map_err(PostgresConnectorError::ConnectionFailure)?;
+        Ok(Self {
+            client: connect(config)?,
+        })
+    }
+}
+
+impl SqlDriver for PostgresDriver {
+    fn fetch(
+        &mut self,
+        query: &str,
+    ) -> Result<(Vec<FieldDefinition>, Vec<Vec<Field>>), ConnectorError> {
+        let stmt = self
+            .client
+            .prepare(query)
+            .map_err(SqlPollingError::PostgresQueryError)?;
+        let fields = stmt
+            .columns()
+            .iter()
+            .map(convert_column_to_field)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PostgresConnectorError::PostgresSchemaError)?;
+
+        let rows = self
+            .client
+            .query(&stmt, &[])
+            .map_err(SqlPollingError::PostgresQueryError)?
+            .iter()
+            .map(|row| get_values(row, stmt.columns()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PostgresConnectorError::PostgresSchemaError)?;
+
+        Ok((fields, rows))
+    }
+}
+
+pub struct SqliteDriver {
+    conn: rusqlite::Connection,
+}
+
+impl SqliteDriver {
+    pub fn new(path: &str) -> Result<Self, ConnectorError> {
+        Ok(Self {
+            conn: rusqlite::Connection::open(path).map_err(SqlPollingError::SqliteQueryError)?,
+        })
+    }
+}
+
+impl SqlDriver for SqliteDriver {
+    fn fetch(
+        &mut self,
+        query: &str,
+    ) -> Result<(Vec<FieldDefinition>, Vec<Vec<Field>>), ConnectorError> {
+        let mut stmt = self
+            .conn
+            .prepare(query)
+            .map_err(SqlPollingError::SqliteQueryError)?;
+        let fields = stmt
+            .columns()
+            .iter()
+            .map(|column| FieldDefinition {
+                name: column.name().to_string(),
+                typ: sqlite_type_to_field_type(column.decl_type()),
+                nullable: true,
+                source: SourceDefinition::Dynamic,
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = stmt.query([]).map_err(SqlPollingError::SqliteQueryError)?;
+        let mut result = vec![];
+        while let Some(row) = rows.next().map_err(SqlPollingError::SqliteQueryError)? {
+            let mut values = Vec::with_capacity(fields.len());
+            for (idx, field) in fields.iter().enumerate() {
+                let value = row
+                    .get_ref(idx)
+                    .map_err(SqlPollingError::SqliteQueryError)?;
+                values.push(sqlite_value_to_field(value, field.typ)?);
+            }
+            result.push(values);
+        }
+
+        Ok((fields, result))
+    }
+}
+
+/// Maps a declared column type using SQLite's type affinity rules.
+pub fn sqlite_type_to_field_type(decl_type: Option<&str>) -> FieldType {
+    let decl_type = decl_type.unwrap_or_default().to_uppercase();
+    if decl_type.contains("TIME") {
+        FieldType::Timestamp
+    } else if decl_type.contains("DATE") {
+        FieldType::Date
+    } else if decl_type.contains("BOOL") {
+        FieldType::Boolean
+    } else if decl_type.contains("INT") {
+        FieldType::Int
+    } else if decl_type.contains("CHAR") || decl_type.contains("CLOB") || decl_type.contains("TEXT")
+    {
+        FieldType::String
+    } else if decl_type.contains("BLOB") {
+        FieldType::Binary
+    } else if decl_type.contains("REAL") || decl_type.contains("FLOA") || decl_type.contains("DOUB")
+    {
+        FieldType::Float
+    } else if decl_type.contains("DEC") || decl_type.contains("NUM") {
+        FieldType::Decimal
+    } else {
+        FieldType::String
+    }
+}
+
+pub fn sqlite_value_to_field(value: ValueRef, typ: FieldType) -> Result<Field, ConnectorError> {
+    let invalid = || -> ConnectorError {
+        SqlPollingError::ColumnTypeNotSupported(format!("{typ} from {:?}", value.data_type()))
+            .into()
+    };
+
+    match (value, typ) {
+        (ValueRef::Null, _) => Ok(Field::Null),
+        (ValueRef::Integer(value), FieldType::Int) => Ok(Field::Int(value)),
+        (ValueRef::Integer(value), FieldType::Boolean) => Ok(Field::Boolean(value != 0)),
+        (ValueRef::Integer(value), FieldType::Float) => {
+            Ok(Field::Float(OrderedFloat(value as f64)))
+        }
+        (ValueRef::Integer(value), FieldType::Decimal) => Ok(Field::Decimal(Decimal::from(value))),
+        (ValueRef::Integer(value), FieldType::Timestamp) => Utc
+            .timestamp_opt(value, 0)
+            .single()
+            .map(|timestamp| Field::Timestamp(timestamp.into()))
+            .ok_or_else(invalid),
+        (ValueRef::Integer(value), FieldType::String) => Ok(Field::String(value.to_string())),
+        (ValueRef::Real(value), FieldType::Float) => Ok(Field::Float(OrderedFloat(value))),
+        (ValueRef::Real(value), FieldType::Decimal) => Decimal::from_f64(value)
+            .map(Field::Decimal)
+            .ok_or_else(invalid),
+        (ValueRef::Real(value), FieldType::String) => Ok(Field::String(value.to_string())),
+        (ValueRef::Text(value), _) => {
+            let value = std::str::from_utf8(value).map_err(|_| invalid())?;
+            match typ {
+                FieldType::String => Ok(Field::String(value.to_string())),
+                // SQLite's date and time functions use `YYYY-MM-DD HH:MM:SS.SSS`
+                FieldType::Timestamp => match ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                {
+                    Some(timestamp) => Ok(Field::from(timestamp)),
+                    None => Ok(Field::from_str(value, typ, true)?),
+                },
+                _ => Ok(Field::from_str(value, typ, true)?),
+            }
+        }
+        (ValueRef::Blob(value), FieldType::Binary) => Ok(Field::Binary(value.to_vec())),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(feature = "snowflake")]
+mod odbc_driver {
+    use dozer_types::types::{Field, FieldDefinition, SourceDefinition};
+    use odbc::create_environment_v3;
+
+    use super::SqlDriver;
+    use crate::connectors::snowflake::connection::client::{odbc_type_to_field_type, Client};
+    use crate::errors::{ConnectorError, SnowflakeError};
+
+    /// Runs queries through the ODBC plumbing of the Snowflake connector.
+    pub struct OdbcDriver {
+        client: Client,
+    }
+
+    impl OdbcDriver {
+        pub fn new(connection_string: String) -> Self {
+            Self {
+                client: Client::from_conn_string(connection_string),
+            }
+        }
+    }
+
+    impl SqlDriver for OdbcDriver {
+        fn fetch(
+            &mut self,
+            query: &str,
+        ) -> Result<(Vec<FieldDefinition>, Vec<Vec<Field>>), ConnectorError> {
+            let env = create_environment_v3().map_err(|e| e.unwrap()).unwrap();
+            let conn = env
+                .connect_with_connection_string(&self.client.get_conn_string())
+                .map_err(|e| SnowflakeError::ConnectionError(Box::new(e)))?;
+
+            match self.client.fetch(&conn, query.to_string())? {
+                None => Ok((vec![], vec![])),
+                Some((schema, iterator)) => {
+                    let fields = schema
+                        .iter()
+                        .map(|column| {
+                            Ok(FieldDefinition {
+                                name: column.name.clone(),
+                                typ: odbc_type_to_field_type(column)
+                                    .map_err(SnowflakeError::SnowflakeSchemaError)?,
+                                nullable: true,
+                                source: SourceDefinition::Dynamic,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, ConnectorError>>()?;
+                    Ok((fields, iterator.collect()))
+                }
+            }
+        }
+    }
+}
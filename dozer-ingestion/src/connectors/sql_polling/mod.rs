@@ -0,0 +1,5 @@
+pub mod connector;
+mod driver;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use dozer_types::ingestion_types::{IngestionMessage, IngestionMessageKind, SqlPollingConfig};
+use dozer_types::types::{Field, FieldType, Operation};
+use rusqlite::Connection;
+
+use super::connector::{cursor_to_txid, Poller, SqlPollingConnector};
+use super::driver::{sqlite_type_to_field_type, SqliteDriver};
+use crate::connectors::Connector;
+use crate::errors::{ConnectorError, SqlPollingError};
+use crate::ingestion::{IngestionConfig, IngestionIterator, Ingestor};
+
+struct TestDatabase {
+    path: String,
+    conn: Connection,
+}
+
+impl TestDatabase {
+    fn new() -> Self {
+        let path = std::env::temp_dir()
+            .join(format!("dozer_sql_polling_{}.db", rand::random::<u64>()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, version INTEGER);
+            INSERT INTO users VALUES (1, 'alice', 1), (2, 'bob', 2), (3, 'carol', 2);",
+        )
+        .unwrap();
+        Self { path, conn }
+    }
+
+    fn config(&self) -> SqlPollingConfig {
+        SqlPollingConfig {
+            driver: "sqlite".to_string(),
+            connection_string: self.path.clone(),
+            table_name: "users".to_string(),
+            query: "SELECT id, name, version FROM users".to_string(),
+            cursor_column: "version".to_string(),
+            primary_key: vec!["id".to_string()],
+            poll_interval_seconds: 1,
+        }
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn read_messages(iterator: &mut IngestionIterator) -> Vec<IngestionMessage> {
+    let mut messages = vec![];
+    while let Some(msg) = iterator.next_timeout(Duration::from_millis(100)) {
+        messages.push(msg);
+    }
+    messages
+}
+
+fn read_ops(iterator: &mut IngestionIterator) -> Vec<((u64, u64), Operation)> {
+    read_messages(iterator)
+        .into_iter()
+        .filter_map(|msg| match msg.kind {
+            IngestionMessageKind::OperationEvent(op) => {
+                Some(((msg.identifier.txid, msg.identifier.seq_in_tx), op))
+            }
+            IngestionMessageKind::SnapshottingDone => None,
+        })
+        .collect()
+}
+
+fn ids(ops: &[((u64, u64), Operation)]) -> Vec<(u64, u64)> {
+    ops.iter().map(|(id, _)| *id).collect()
+}
+
+#[test]
+fn test_sql_polling_schema() {
+    let db = TestDatabase::new();
+    let connector = SqlPollingConnector::new(1, "test".to_string(), db.config());
+
+    let schemas = connector.get_schemas(None).unwrap();
+    assert_eq!(schemas.len(), 1);
+    assert_eq!(schemas[0].name, "users");
+    let types = schemas[0]
+        .schema
+        .fields
+        .iter()
+        .map(|f| f.typ)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![FieldType::Int, FieldType::String, FieldType::Int]
+    );
+    assert_eq!(schemas[0].schema.primary_index, vec![0]);
+
+    let mut config = db.config();
+    config.cursor_column = "name".to_string();
+    let connector = SqlPollingConnector::new(1, "test".to_string(), config);
+    assert!(matches!(
+        connector.get_schemas(None),
+        Err(ConnectorError::SqlPollingError(
+            SqlPollingError::CursorTypeNotSupported(_)
+        ))
+    ));
+}
+
+#[test]
+fn test_sql_polling_upserts() {
+    let db = TestDatabase::new();
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let mut driver = SqliteDriver::new(&db.path).unwrap();
+    let mut poller = Poller::new(db.config(), None, None).unwrap();
+
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let ops = read_ops(&mut iterator);
+    assert_eq!(ids(&ops), vec![(1, 0), (2, 0), (2, 1)]);
+    assert!(ops
+        .iter()
+        .all(|(_, op)| matches!(op, Operation::Insert { .. })));
+
+    // Nothing changed
+    poller.poll(&mut driver, &ingestor).unwrap();
+    assert!(read_ops(&mut iterator).is_empty());
+
+    db.conn
+        .execute_batch(
+            "UPDATE users SET name = 'alicia', version = 3 WHERE id = 1;
+            INSERT INTO users VALUES (4, 'dave', 3);",
+        )
+        .unwrap();
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let ops = read_ops(&mut iterator);
+    assert_eq!(ids(&ops), vec![(3, 0), (3, 1)]);
+    match &ops[0].1 {
+        Operation::Update { old, new } => {
+            assert_eq!(old.values[1], Field::String("alice".to_string()));
+            assert_eq!(new.values[1], Field::String("alicia".to_string()));
+        }
+        op => panic!("Expected update, got {op:?}"),
+    }
+    assert!(matches!(ops[1].1, Operation::Insert { .. }));
+}
+
+#[test]
+fn test_sql_polling_resume_from_checkpoint() {
+    let db = TestDatabase::new();
+    let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let mut driver = SqliteDriver::new(&db.path).unwrap();
+
+    // Only the first message of a poll sending new keys carries the state
+    let mut poller = Poller::new(db.config(), None, None).unwrap();
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let messages = read_messages(&mut iterator);
+    assert_eq!(messages.len(), 3);
+    assert!(messages[1..].iter().all(|msg| msg.state.is_none()));
+    let state = messages[0].state.clone().unwrap();
+
+    // Changed while the connector was stopped
+    db.conn
+        .execute(
+            "UPDATE users SET name = 'ann', version = 3 WHERE id = 1",
+            [],
+        )
+        .unwrap();
+    let mut poller = Poller::new(db.config(), Some((2, 0)), Some(&state)).unwrap();
+
+    // Rows from the cursor of the checkpoint on are sent again, as updates if they were sent
+    // before the checkpoint and as inserts otherwise
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let ops = read_ops(&mut iterator);
+    assert_eq!(ids(&ops), vec![(2, 1), (2, 2), (3, 0)]);
+    assert!(matches!(ops[0].1, Operation::Update { .. }));
+    assert!(matches!(ops[1].1, Operation::Insert { .. }));
+    match &ops[2].1 {
+        Operation::Update { new, .. } => {
+            assert_eq!(new.values[1], Field::String("ann".to_string()));
+        }
+        op => panic!("Expected update, got {op:?}"),
+    }
+
+    // Rows read on resume are known, so their changes are updates of their current rows
+    db.conn
+        .execute(
+            "UPDATE users SET name = 'bobby', version = 4 WHERE id = 2",
+            [],
+        )
+        .unwrap();
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let ops = read_ops(&mut iterator);
+    assert_eq!(ids(&ops), vec![(4, 0)]);
+    match &ops[0].1 {
+        Operation::Update { old, new } => {
+            assert_eq!(old.values[1], Field::String("bob".to_string()));
+            assert_eq!(new.values[1], Field::String("bobby".to_string()));
+        }
+        op => panic!("Expected update, got {op:?}"),
+    }
+
+    // Rows added later with the last cursor seen are not missed
+    db.conn
+        .execute("INSERT INTO users VALUES (0, 'eve', 4)", [])
+        .unwrap();
+    poller.poll(&mut driver, &ingestor).unwrap();
+    let ops = read_ops(&mut iterator);
+    assert_eq!(ids(&ops), vec![(4, 1)]);
+    assert!(matches!(ops[0].1, Operation::Insert { .. }));
+}
+
+#[test]
+fn test_cursor_to_txid() {
+    assert_eq!(cursor_to_txid(&Field::Int(5)).unwrap(), 5);
+    assert_eq!(cursor_to_txid(&Field::UInt(5)).unwrap(), 5);
+    assert!(cursor_to_txid(&Field::Int(0)).is_err());
+    assert!(cursor_to_txid(&Field::Int(-1)).is_err());
+    assert!(cursor_to_txid(&Field::Null).is_err());
+}
+
+#[test]
+fn test_sqlite_type_affinity() {
+    assert_eq!(sqlite_type_to_field_type(Some("BIGINT")), FieldType::Int);
+    assert_eq!(
+        sqlite_type_to_field_type(Some("VARCHAR(10)")),
+        FieldType::String
+    );
+    assert_eq!(sqlite_type_to_field_type(Some("DOUBLE")), FieldType::Float);
+    assert_eq!(
+        sqlite_type_to_field_type(Some("DATETIME")),
+        FieldType::Timestamp
+    );
+    assert_eq!(sqlite_type_to_field_type(Some("DATE")), FieldType::Date);
+    assert_eq!(
+        sqlite_type_to_field_type(Some("NUMERIC(10,2)")),
+        FieldType::Decimal
+    );
+    assert_eq!(sqlite_type_to_field_type(None), FieldType::String);
+}
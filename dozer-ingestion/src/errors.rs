@@ -4,6 +4,7 @@ use dozer_types::errors::internal::BoxedError;
 use dozer_types::errors::types::{SerializationError, TypeError};
 use dozer_types::ingestion_types::IngestorError;
 use dozer_types::thiserror::Error;
+use dozer_types::types::{Field, FieldType};
 use dozer_types::{bincode, serde_json};
 use dozer_types::{rust_decimal, thiserror};
 
@@ -57,9 +58,15 @@ pub enum ConnectorError {
     #[error(transparent)]
     DebeziumError(#[from] DebeziumError),
 
+    #[error(transparent)]
+    KafkaError(#[from] KafkaError),
+
     #[error(transparent)]
     ObjectStoreConnectorError(#[from] ObjectStoreConnectorError),
 
+    #[error(transparent)]
+    SqlPollingError(#[from] SqlPollingError),
+
     #[error(transparent)]
     TypeError(#[from] TypeError),
 
@@ -242,6 +249,9 @@ pub enum SnowflakeError {
 
     #[error(transparent)]
     SnowflakeStreamError(#[from] SnowflakeStreamError),
+
+    #[error("Poll interval must be at least one second")]
+    InvalidPollInterval,
 }
 
 #[cfg(feature = "snowflake")]
@@ -279,6 +289,9 @@ pub enum SnowflakeStreamError {
 
     #[error("Stream not found")]
     StreamNotFound,
+
+    #[error("Batch id of table {0} is missing or invalid")]
+    InvalidBatchId(String),
 }
 
 #[derive(Error, Debug)]
@@ -349,6 +362,81 @@ pub enum DebeziumSchemaError {
     InvalidTimestampError,
 }
 
+#[derive(Error, Debug)]
+pub enum KafkaError {
+    #[error("Kafka request failed: {0}")]
+    ClientError(#[source] kafka::Error),
+
+    #[error("Topic {0} not found")]
+    TopicNotFound(String),
+
+    #[error("Failed to fetch partition {0}: {1}")]
+    FetchError(i32, String),
+
+    #[error("Failed to read descriptor file {0}: {1}")]
+    DescriptorReadError(String, #[source] std::io::Error),
+
+    #[error("Invalid descriptor file: {0}")]
+    InvalidDescriptor(#[source] prost_reflect::DescriptorError),
+
+    #[error("Message type {0} not found in descriptor file")]
+    MessageTypeNotFound(String),
+
+    #[error("Failed to decode protobuf message: {0}")]
+    ProtobufDecodeError(#[source] prost::DecodeError),
+
+    #[error("Failed to decode JSON message: {0}")]
+    JsonDecodeError(#[source] serde_json::Error),
+
+    #[error("Field \"{0}\" has unsupported type {1}")]
+    FieldTypeNotSupported(String, String),
+
+    #[error("Value of field \"{0}\" doesn't match its type {1}")]
+    InvalidFieldValue(String, FieldType),
+
+    #[error("Key column \"{0}\" conflicts with a value field")]
+    KeyColumnConflict(String),
+
+    #[error("Message at offset {1} of partition {0} has no key")]
+    MissingKey(i32, i64),
+
+    #[error("Key of message at offset {1} of partition {0} is not valid UTF-8")]
+    InvalidKey(i32, i64),
+
+    #[error("Invalid partition offsets in checkpoint state: {0}")]
+    InvalidState(#[source] bincode::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum SqlPollingError {
+    #[error("Unsupported driver \"{0}\", expected one of postgres, sqlite or odbc")]
+    UnsupportedDriver(String),
+
+    #[error("Query failed: {0}")]
+    PostgresQueryError(#[source] tokio_postgres::Error),
+
+    #[error("Query failed: {0}")]
+    SqliteQueryError(#[source] rusqlite::Error),
+
+    #[error("Column type {0} not supported")]
+    ColumnTypeNotSupported(String),
+
+    #[error("Column \"{0}\" not found in query results")]
+    ColumnNotFound(String),
+
+    #[error("Primary key is not defined")]
+    PrimaryKeyMissing,
+
+    #[error("Cursor column \"{0}\" must be an integer or timestamp column")]
+    CursorTypeNotSupported(String),
+
+    #[error("Cursor value {0} is not a positive integer or a timestamp after 1970")]
+    InvalidCursorValue(Field),
+
+    #[error("Invalid sent keys in checkpoint state: {0}")]
+    InvalidState(#[source] bincode::Error),
+}
+
 #[derive(Error, Debug)]
 pub enum ObjectStoreConnectorError {
     #[error(transparent)]
@@ -70,6 +70,7 @@ pub fn generate_connection(connection_name: &str) -> Connection {
                 schema: "schema".to_owned(),
                 warehouse: "warehouse".to_owned(),
                 driver: Some("SnowflakeDSIIDriver".to_owned()),
+                poll_interval_seconds: 5,
             };
             let connection: Connection = Connection {
                 name: "snowflake".to_owned(),
@@ -89,6 +90,7 @@ pub fn generate_connection(connection_name: &str) -> Connection {
                     wss_url: "wss://link".to_owned(),
                     filter: Some(eth_filter),
                     contracts: vec![],
+                    confirmations: 0,
                 })),
             };
             let connection: Connection = Connection {
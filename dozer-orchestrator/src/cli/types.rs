@@ -1,4 +1,5 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dozer_api::generator::client;
 
 use super::helper::{DESCRIPTION, LOGO};
 
@@ -39,6 +40,8 @@ pub enum Commands {
     Connector(Connector),
     #[command(about = "Initalize an app using a template.")]
     Init,
+    #[command(about = "Export or restore cache snapshots")]
+    Cache(Cache),
 }
 
 #[derive(Debug, Args)]
@@ -69,6 +72,13 @@ pub struct Connector {
     pub command: ConnectorCommands,
 }
 
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cache {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ApiCommands {
     Run,
@@ -80,6 +90,38 @@ pub enum ApiCommands {
         that encapsulate different permissions."
     )]
     GenerateToken,
+    #[command(
+        about = "Generate typed clients of the endpoints",
+        long_about = "Generate a client for each endpoint, with record types, filter builders \
+        and event subscription helpers, from the endpoint schemas in the cache."
+    )]
+    GenerateClient {
+        #[arg(long, value_enum)]
+        lang: ClientLanguage,
+        #[arg(
+            short,
+            long,
+            help = "Output directory [default: <home_dir>/api/client/<extension>]"
+        )]
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ClientLanguage {
+    Ts,
+    Rust,
+    Python,
+}
+
+impl From<ClientLanguage> for client::ClientLanguage {
+    fn from(language: ClientLanguage) -> Self {
+        match language {
+            ClientLanguage::Ts => client::ClientLanguage::TypeScript,
+            ClientLanguage::Rust => client::ClientLanguage::Rust,
+            ClientLanguage::Python => client::ClientLanguage::Python,
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -91,3 +133,25 @@ pub enum AppCommands {
 pub enum ConnectorCommands {
     Ls,
 }
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommands {
+    #[command(
+        about = "Export a snapshot of an endpoint's cache",
+        long_about = "Export the records, schema, indexes and checkpoint of an endpoint's cache \
+        to a file or `s3://bucket/path`. AWS credentials are read from the environment."
+    )]
+    Export {
+        endpoint: String,
+        destination: String,
+    },
+    #[command(
+        about = "Restore a cache snapshot",
+        long_about = "Create a cache from a snapshot in a file or `s3://bucket/path`, \
+        and serve it for the endpoint it was exported from. If the pipeline has no \
+        state yet, it is initialized at the snapshot's checkpoint, so the pipeline \
+        resumes from there instead of rebuilding the cache. Restore the snapshots of \
+        all endpoints, exported at the same checkpoint, before running the pipeline."
+    )]
+    Restore { source: String },
+}
@@ -52,6 +52,10 @@ pub enum OrchestrationError {
     EndpointTableNotFound(String),
     #[error("Duplicate table name found: {0:?}")]
     DuplicateTable(String),
+    #[error("Sink of endpoint {0:?} has no config")]
+    EndpointSinkConfigMissing(String),
+    #[error("Failed to export or restore cache snapshot: {0}")]
+    CacheSnapshotFailed(#[source] BoxedError),
 }
 
 #[derive(Error, Debug)]
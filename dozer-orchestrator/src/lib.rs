@@ -2,6 +2,7 @@ pub mod cli;
 pub mod errors;
 pub mod pipeline;
 pub mod simple;
+use dozer_api::generator::client::ClientLanguage;
 use dozer_core::{app::AppPipeline, errors::ExecutionError};
 use dozer_sql::pipeline::{builder::statement_to_pipeline, errors::PipelineError};
 use dozer_types::{
@@ -38,6 +39,17 @@ pub trait Orchestrator {
     ) -> Result<(), OrchestrationError>;
     fn list_connectors(&self) -> Result<HashMap<String, Vec<SourceSchema>>, OrchestrationError>;
     fn generate_token(&self) -> Result<String, OrchestrationError>;
+    fn export_cache(
+        &self,
+        endpoint_name: &str,
+        destination: &str,
+    ) -> Result<(), OrchestrationError>;
+    fn restore_cache(&self, source: &str) -> Result<(), OrchestrationError>;
+    fn generate_client(
+        &self,
+        language: ClientLanguage,
+        output: Option<&str>,
+    ) -> Result<(), OrchestrationError>;
     fn query(
         &self,
         sql: String,
@@ -1,6 +1,8 @@
 use clap::Parser;
 use dozer_orchestrator::cli::generate_config_repl;
-use dozer_orchestrator::cli::types::{ApiCommands, AppCommands, Cli, Commands, ConnectorCommands};
+use dozer_orchestrator::cli::types::{
+    ApiCommands, AppCommands, CacheCommands, Cli, Commands, ConnectorCommands,
+};
 use dozer_orchestrator::cli::{configure, init_dozer, list_sources, LOGO};
 use dozer_orchestrator::errors::OrchestrationError;
 use dozer_orchestrator::{set_ctrl_handler, set_panic_hook, Orchestrator};
@@ -57,6 +59,10 @@ fn run() -> Result<(), OrchestrationError> {
                     info!("token: {:?} ", token);
                     Ok(())
                 }
+                ApiCommands::GenerateClient { lang, output } => {
+                    let dozer = init_dozer(cli.config_path)?;
+                    dozer.generate_client(lang.into(), output.as_deref())
+                }
             },
             Commands::App(apps) => match apps.command {
                 AppCommands::Run => {
@@ -79,6 +85,16 @@ fn run() -> Result<(), OrchestrationError> {
             }
             Commands::Configure => configure(cli.config_path, running),
             Commands::Init => generate_config_repl(),
+            Commands::Cache(cache) => {
+                let dozer = init_dozer(cli.config_path)?;
+                match cache.command {
+                    CacheCommands::Export {
+                        endpoint,
+                        destination,
+                    } => dozer.export_cache(&endpoint, &destination),
+                    CacheCommands::Restore { source } => dozer.restore_cache(&source),
+                }
+            }
         }
     } else {
         render_logo();
@@ -5,17 +5,22 @@ use dozer_api::grpc::internal::internal_pipeline_server::PipelineEventSenders;
 use dozer_cache::cache::{CacheManagerOptions, LmdbCacheManager};
 use dozer_core::app::AppPipeline;
 use dozer_core::executor::DagExecutor;
+use dozer_core::node::SinkFactory;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_sql::pipeline::builder::{OutputNodeInfo, SchemaSQLContext};
 
 use dozer_core::app::App;
 use dozer_sql::pipeline::builder::statement_to_pipeline;
-use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_sql::pipeline::udf::register_config_udfs;
+use dozer_types::models::api_endpoint::{ApiEndpoint, ApiSink, SinkConfig};
 use dozer_types::models::app_config::Config;
 use dozer_types::{indicatif::MultiProgress, log::debug};
 use std::path::PathBuf;
 
-use crate::pipeline::{CacheSinkFactory, CacheSinkSettings};
+use crate::pipeline::{
+    CacheSinkFactory, CacheSinkSettings, KafkaSinkFactory, ObjectStoreSinkFactory,
+    PostgresSinkFactory,
+};
 
 use super::source_builder::SourceBuilder;
 use super::validate::validate_grouped_connections;
@@ -83,6 +88,7 @@ impl PipelineBuilder {
         }
 
         if let Some(sql) = self.config.sql.clone() {
+            register_config_udfs(&self.config.udfs).map_err(OrchestrationError::PipelineError)?;
             let query_context = statement_to_pipeline(&sql, &mut pipeline, None)
                 .map_err(OrchestrationError::PipelineError)?;
 
@@ -131,47 +137,58 @@ impl PipelineBuilder {
                 .get(table_name)
                 .ok_or_else(|| OrchestrationError::EndpointTableNotFound(table_name.clone()))?;
 
-            let snk_factory = Arc::new(CacheSinkFactory::new(
-                cache_manager.clone(),
-                api_endpoint.clone(),
-                notifier.clone(),
-                self.progress.clone(),
-                settings.clone(),
-            )?);
-
-            match table_info {
-                OutputTableInfo::Transformed(table_info) => {
-                    pipeline.add_sink(snk_factory, api_endpoint.name.as_str());
-
-                    pipeline
-                        .connect_nodes(
-                            &table_info.node,
-                            Some(table_info.port),
-                            api_endpoint.name.as_str(),
-                            Some(DEFAULT_PORT_HANDLE),
-                            true,
-                        )
-                        .map_err(ExecutionError)?;
-                }
-                OutputTableInfo::Original(table_info) => {
-                    pipeline.add_sink(snk_factory, api_endpoint.name.as_str());
-
-                    let conn_port = conn_ports
-                        .get(&(
-                            table_info.connection_name.clone(),
-                            table_info.table_name.clone(),
-                        ))
-                        .expect("port should be present based on source mapping");
-
-                    pipeline
-                        .connect_nodes(
-                            &table_info.connection_name,
-                            Some(*conn_port),
-                            api_endpoint.name.as_str(),
-                            Some(DEFAULT_PORT_HANDLE),
-                            false,
-                        )
-                        .map_err(ExecutionError)?;
+            let mut snk_factories: Vec<(String, Arc<dyn SinkFactory<SchemaSQLContext>>)> = vec![(
+                api_endpoint.name.clone(),
+                Arc::new(CacheSinkFactory::new(
+                    cache_manager.clone(),
+                    api_endpoint.clone(),
+                    notifier.clone(),
+                    self.progress.clone(),
+                    settings.clone(),
+                )?),
+            )];
+            for (idx, sink) in api_endpoint.sinks.iter().enumerate() {
+                snk_factories.push((
+                    format!("{}_sink_{}", api_endpoint.name, idx),
+                    create_sink_factory(api_endpoint, sink)?,
+                ));
+            }
+
+            for (snk_name, snk_factory) in snk_factories {
+                match table_info {
+                    OutputTableInfo::Transformed(table_info) => {
+                        pipeline.add_sink(snk_factory, snk_name.as_str());
+
+                        pipeline
+                            .connect_nodes(
+                                &table_info.node,
+                                Some(table_info.port),
+                                snk_name.as_str(),
+                                Some(DEFAULT_PORT_HANDLE),
+                                true,
+                            )
+                            .map_err(ExecutionError)?;
+                    }
+                    OutputTableInfo::Original(table_info) => {
+                        pipeline.add_sink(snk_factory, snk_name.as_str());
+
+                        let conn_port = conn_ports
+                            .get(&(
+                                table_info.connection_name.clone(),
+                                table_info.table_name.clone(),
+                            ))
+                            .expect("port should be present based on source mapping");
+
+                        pipeline
+                            .connect_nodes(
+                                &table_info.connection_name,
+                                Some(*conn_port),
+                                snk_name.as_str(),
+                                Some(DEFAULT_PORT_HANDLE),
+                                false,
+                            )
+                            .map_err(ExecutionError)?;
+                    }
                 }
             }
         }
@@ -201,3 +218,26 @@ impl PipelineBuilder {
         Ok(dag)
     }
 }
+
+fn create_sink_factory(
+    api_endpoint: &ApiEndpoint,
+    sink: &ApiSink,
+) -> Result<Arc<dyn SinkFactory<SchemaSQLContext>>, OrchestrationError> {
+    match &sink.config {
+        Some(SinkConfig::Kafka(config)) => Ok(Arc::new(KafkaSinkFactory::new(
+            api_endpoint.clone(),
+            config.clone(),
+        ))),
+        Some(SinkConfig::ObjectStore(config)) => Ok(Arc::new(ObjectStoreSinkFactory::new(
+            api_endpoint.clone(),
+            config.clone(),
+        ))),
+        Some(SinkConfig::Postgres(config)) => Ok(Arc::new(PostgresSinkFactory::new(
+            api_endpoint.clone(),
+            config.clone(),
+        ))),
+        None => Err(OrchestrationError::EndpointSinkConfigMissing(
+            api_endpoint.name.clone(),
+        )),
+    }
+}
@@ -223,6 +223,12 @@ pub struct ConnectorSource {
 }
 
 impl Source for ConnectorSource {
+    fn restore_state(&mut self, state: Vec<u8>) -> Result<(), ExecutionError> {
+        self.connector
+            .restore_state(state)
+            .map_err(|e| ExecutionError::ConnectorError(Box::new(e)))
+    }
+
     fn can_start_from(&self, last_checkpoint: (u64, u64)) -> Result<bool, ExecutionError> {
         self.connector
             .as_ref()
@@ -252,7 +258,12 @@ impl Source for ConnectorSource {
 
             let mut iterator = self.iterator.lock();
 
-            for IngestionMessage { identifier, kind } in iterator.by_ref() {
+            for IngestionMessage {
+                identifier,
+                kind,
+                state,
+            } in iterator.by_ref()
+            {
                 let schema_id = match &kind {
                     IngestionMessageKind::OperationEvent(Operation::Delete { old }) => {
                         Some(get_schema_id(old.schema_id)?)
@@ -285,14 +296,22 @@ impl Source for ConnectorSource {
                             pb.set_position(*schema_counter);
                         }
                     }
-                    fw.send(IngestionMessage { identifier, kind }, *port)?
+                    fw.send(
+                        IngestionMessage {
+                            identifier,
+                            kind,
+                            state,
+                        },
+                        *port,
+                    )?
                 } else {
                     for port in self.schema_port_map.values() {
                         fw.send(
-                            IngestionMessage::new_snapshotting_done(
-                                identifier.txid,
-                                identifier.seq_in_tx,
-                            ),
+                            IngestionMessage {
+                                identifier,
+                                kind: IngestionMessageKind::SnapshottingDone,
+                                state: state.clone(),
+                            },
                             *port,
                         )?
                     }
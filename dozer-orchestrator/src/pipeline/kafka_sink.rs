@@ -0,0 +1,694 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::{ExecutionError, SinkError};
+use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::record_store::RecordReader;
+use dozer_core::storage::lmdb_storage::SharedTransaction;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::field_to_json_value;
+use dozer_types::log::debug;
+use dozer_types::models::api_endpoint::{ApiEndpoint, KafkaSinkConfig, KafkaSinkFormat};
+use dozer_types::node::SourceStates;
+use dozer_types::serde_json::{self, json, Map, Value};
+use dozer_types::types::{Operation, Record, Schema};
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::client::DefaultClientContext;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+use rdkafka::{ClientConfig, Message, Offset, TopicPartitionList};
+
+use super::sinks::{decode_source_states, encode_source_states, get_primary_index, is_applied};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Compacted topic holding the `SourceStates` of the last epoch published to `topic`.
+fn checkpoint_topic(topic: &str) -> String {
+    format!("__dozer_sink_checkpoints_{topic}")
+}
+
+/// A message to publish. A `None` value is a tombstone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaSinkMessage {
+    pub key: Vec<u8>,
+    pub value: Option<Vec<u8>>,
+}
+
+pub trait MessagePublisher: Send + Sync + Debug {
+    /// Atomically publishes `messages` in order along with their `checkpoint`.
+    fn publish(
+        &mut self,
+        messages: &[KafkaSinkMessage],
+        checkpoint: Vec<u8>,
+    ) -> Result<(), BoxedError>;
+
+    /// Returns the checkpoint published last, if any.
+    fn load_checkpoint(&mut self) -> Result<Option<Vec<u8>>, BoxedError>;
+}
+
+pub struct KafkaPublisher {
+    broker: String,
+    topic: String,
+    checkpoint_topic: String,
+    producer: BaseProducer,
+}
+
+impl KafkaPublisher {
+    pub fn connect(config: &KafkaSinkConfig) -> Result<Self, BoxedError> {
+        let checkpoint_topic = checkpoint_topic(&config.topic);
+        create_checkpoint_topic(&config.broker, &checkpoint_topic)?;
+
+        let producer: BaseProducer = ClientConfig::new()
+            .set("bootstrap.servers", &config.broker)
+            .set("transactional.id", format!("dozer-sink-{}", config.topic))
+            .create()?;
+        // Also fences the producers of previous runs, aborting their pending transactions.
+        producer.init_transactions(TIMEOUT)?;
+        Ok(Self {
+            broker: config.broker.clone(),
+            topic: config.topic.clone(),
+            checkpoint_topic,
+            producer,
+        })
+    }
+
+    fn send(&self, mut record: BaseRecord<'_, [u8], [u8]>) -> Result<(), KafkaError> {
+        loop {
+            match self.producer.send(record) {
+                Ok(()) => return Ok(()),
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), rejected)) => {
+                    self.producer.poll(Duration::from_millis(100));
+                    record = rejected;
+                }
+                Err((e, _)) => return Err(e),
+            }
+        }
+    }
+
+    fn send_all(&self, messages: &[KafkaSinkMessage], checkpoint: &[u8]) -> Result<(), KafkaError> {
+        for message in messages {
+            let mut record = BaseRecord::to(&self.topic).key(message.key.as_slice());
+            if let Some(value) = &message.value {
+                record = record.payload(value.as_slice());
+            }
+            self.send(record)?;
+        }
+        self.send(
+            BaseRecord::to(&self.checkpoint_topic)
+                .partition(0)
+                .key(self.topic.as_bytes())
+                .payload(checkpoint),
+        )
+    }
+}
+
+impl Debug for KafkaPublisher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaPublisher")
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+impl MessagePublisher for KafkaPublisher {
+    fn publish(
+        &mut self,
+        messages: &[KafkaSinkMessage],
+        checkpoint: Vec<u8>,
+    ) -> Result<(), BoxedError> {
+        self.producer.begin_transaction()?;
+        let result = self
+            .send_all(messages, &checkpoint)
+            .and_then(|()| self.producer.commit_transaction(TIMEOUT));
+        if let Err(e) = result {
+            self.producer.abort_transaction(TIMEOUT)?;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    fn load_checkpoint(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.broker)
+            .set("group.id", format!("dozer-sink-{}", self.topic))
+            .set("enable.auto.commit", "false")
+            .set("enable.partition.eof", "true")
+            .set("isolation.level", "read_committed")
+            .create()?;
+        let (low, high) = consumer.fetch_watermarks(&self.checkpoint_topic, 0, TIMEOUT)?;
+
+        // The checkpoint is the last record, but transaction markers and compaction leave gaps in
+        // the offsets, so read growing windows back from the end until a record is found.
+        let mut end = high;
+        let mut window = 16;
+        while end > low {
+            let start = (end - window).max(low);
+            let mut partitions = TopicPartitionList::new();
+            partitions.add_partition_offset(&self.checkpoint_topic, 0, Offset::Offset(start))?;
+            consumer.assign(&partitions)?;
+
+            let mut checkpoint = None;
+            loop {
+                let message = match consumer.poll(TIMEOUT) {
+                    Some(Ok(message)) => message,
+                    Some(Err(KafkaError::PartitionEOF(_))) => break,
+                    Some(Err(e)) => return Err(e.into()),
+                    None => return Err("Timed out reading the sink checkpoint".into()),
+                };
+                if message.offset() >= end {
+                    break;
+                }
+                if let Some(payload) = message.payload() {
+                    checkpoint = Some(payload.to_vec());
+                }
+            }
+            if checkpoint.is_some() {
+                return Ok(checkpoint);
+            }
+            end = start;
+            window *= 2;
+        }
+        Ok(None)
+    }
+}
+
+fn create_checkpoint_topic(broker: &str, topic: &str) -> Result<(), BoxedError> {
+    let admin: AdminClient<DefaultClientContext> = ClientConfig::new()
+        .set("bootstrap.servers", broker)
+        .create()?;
+    let new_topic =
+        NewTopic::new(topic, 1, TopicReplication::Fixed(-1)).set("cleanup.policy", "compact");
+    let results = futures::executor::block_on(admin.create_topics(
+        &[new_topic],
+        &AdminOptions::new().operation_timeout(Some(TIMEOUT)),
+    ))?;
+    for result in results {
+        match result {
+            Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => {}
+            Err((topic, code)) => {
+                return Err(format!("Failed to create topic {topic}: {code}").into())
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct KafkaSinkFactory {
+    api_endpoint: ApiEndpoint,
+    config: KafkaSinkConfig,
+}
+
+impl KafkaSinkFactory {
+    pub fn new(api_endpoint: ApiEndpoint, config: KafkaSinkConfig) -> Self {
+        Self {
+            api_endpoint,
+            config,
+        }
+    }
+
+    fn get_output_schema(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<(Schema, KafkaSinkFormat), ExecutionError> {
+        debug_assert!(input_schemas.len() == 1);
+        let mut schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .expect("Input schema should be on default port");
+        schema.primary_index = get_primary_index(&schema, &self.api_endpoint)?;
+
+        let format = self.config.format();
+        // Without a key, updates and deletes can't be related to the record they change.
+        if format == KafkaSinkFormat::Upsert && schema.primary_index.is_empty() {
+            return Err(ExecutionError::SinkError(SinkError::SinkPrimaryKeyMissing(
+                self.api_endpoint.name.clone(),
+            )));
+        }
+        Ok((schema, format))
+    }
+}
+
+impl SinkFactory<SchemaSQLContext> for KafkaSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        self.get_output_schema(
+            input_schemas
+                .into_iter()
+                .map(|(key, (schema, _))| (key, schema))
+                .collect(),
+        )?;
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint: &SourceStates,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        let (schema, format) = self.get_output_schema(input_schemas)?;
+        let publisher = KafkaPublisher::connect(&self.config).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkConnectionFailed(
+                self.api_endpoint.name.clone(),
+                e,
+            ))
+        })?;
+        debug!(
+            "SINK: Initialising KafkaSink: {} to topic {}",
+            self.api_endpoint.name, self.config.topic
+        );
+        Ok(Box::new(KafkaSink::new(
+            self.api_endpoint.name.clone(),
+            schema,
+            format,
+            Box::new(publisher),
+        )?))
+    }
+}
+
+/// Publishes every operation as a message keyed by the primary key of the record.
+///
+/// Messages are buffered and published when the epoch is committed, in a transaction that also
+/// stores the `SourceStates` of the epoch in the checkpoint topic of the sink. Epochs already
+/// published are skipped when the pipeline replays them after a restart, even from scratch, so
+/// `read_committed` consumers see every message exactly once.
+#[derive(Debug)]
+pub struct KafkaSink {
+    endpoint_name: String,
+    schema: Schema,
+    format: KafkaSinkFormat,
+    publisher: Box<dyn MessagePublisher>,
+    published: SourceStates,
+    messages: Vec<KafkaSinkMessage>,
+}
+
+impl KafkaSink {
+    pub fn new(
+        endpoint_name: String,
+        schema: Schema,
+        format: KafkaSinkFormat,
+        mut publisher: Box<dyn MessagePublisher>,
+    ) -> Result<Self, ExecutionError> {
+        let published = load_published(publisher.as_mut()).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkRestoreFailed(endpoint_name.clone(), e))
+        })?;
+        Ok(Self {
+            endpoint_name,
+            schema,
+            format,
+            publisher,
+            published,
+            messages: vec![],
+        })
+    }
+
+    fn publish(&mut self, epoch: &Epoch) -> Result<(), BoxedError> {
+        let checkpoint = serde_json::to_vec(&encode_source_states(&epoch.details))?;
+        self.publisher.publish(&self.messages, checkpoint)?;
+        self.published = epoch.details.clone();
+        Ok(())
+    }
+
+    fn key(&self, record: &Record) -> Vec<u8> {
+        if self.schema.primary_index.is_empty() {
+            return vec![];
+        }
+        let key: Map<String, Value> = self
+            .schema
+            .primary_index
+            .iter()
+            .map(|idx| {
+                (
+                    self.schema.fields[*idx].name.clone(),
+                    field_to_json_value(record.values[*idx].clone()),
+                )
+            })
+            .collect();
+        Value::Object(key).to_string().into_bytes()
+    }
+
+    fn value(&self, record: &Record) -> Value {
+        let value: Map<String, Value> = self
+            .schema
+            .fields
+            .iter()
+            .zip(record.values.iter())
+            .map(|(field, value)| (field.name.clone(), field_to_json_value(value.clone())))
+            .collect();
+        Value::Object(value)
+    }
+
+    fn push(&mut self, key: Vec<u8>, value: Option<Value>) {
+        self.messages.push(KafkaSinkMessage {
+            key,
+            value: value.map(|value| value.to_string().into_bytes()),
+        });
+    }
+
+    fn push_envelope(&mut self, key: Vec<u8>, before: Option<&Record>, after: Option<&Record>) {
+        let op = match (before, after) {
+            (None, _) => "c",
+            (Some(_), Some(_)) => "u",
+            (Some(_), None) => "d",
+        };
+        let envelope = json!({
+            "before": before.map(|record| self.value(record)),
+            "after": after.map(|record| self.value(record)),
+            "op": op,
+        });
+        self.push(key, Some(envelope));
+    }
+}
+
+impl Sink for KafkaSink {
+    fn commit(&mut self, epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        if self.messages.is_empty() {
+            return Ok(());
+        }
+        if is_applied(&self.published, &epoch.details) {
+            debug!(
+                "SINK: Skipping epoch {} of {}, already published",
+                epoch.id, self.endpoint_name
+            );
+            self.messages.clear();
+            return Ok(());
+        }
+        debug!(
+            "SINK: Publishing {} messages of {} at epoch {}",
+            self.messages.len(),
+            self.endpoint_name,
+            epoch.id
+        );
+        self.publish(epoch).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkWriteFailed(self.endpoint_name.clone(), e))
+        })?;
+        self.messages.clear();
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        _tx: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match (self.format, op) {
+            (KafkaSinkFormat::Debezium, Operation::Insert { new }) => {
+                self.push_envelope(self.key(&new), None, Some(&new));
+            }
+            (KafkaSinkFormat::Debezium, Operation::Delete { old }) => {
+                self.push_envelope(self.key(&old), Some(&old), None);
+            }
+            (KafkaSinkFormat::Debezium, Operation::Update { old, new }) => {
+                let (old_key, new_key) = (self.key(&old), self.key(&new));
+                if old_key == new_key {
+                    self.push_envelope(new_key, Some(&old), Some(&new));
+                } else {
+                    // Like Debezium, a primary key change is a delete followed by a create.
+                    self.push_envelope(old_key, Some(&old), None);
+                    self.push_envelope(new_key, None, Some(&new));
+                }
+            }
+            (KafkaSinkFormat::Upsert, Operation::Insert { new }) => {
+                self.push(self.key(&new), Some(self.value(&new)));
+            }
+            (KafkaSinkFormat::Upsert, Operation::Delete { old }) => {
+                self.push(self.key(&old), None);
+            }
+            (KafkaSinkFormat::Upsert, Operation::Update { old, new }) => {
+                let (old_key, new_key) = (self.key(&old), self.key(&new));
+                if old_key != new_key {
+                    self.push(old_key, None);
+                }
+                self.push(new_key, Some(self.value(&new)));
+            }
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+fn load_published(publisher: &mut dyn MessagePublisher) -> Result<SourceStates, BoxedError> {
+    match publisher.load_checkpoint()? {
+        Some(checkpoint) => decode_source_states(serde_json::from_slice(&checkpoint)?),
+        None => Ok(SourceStates::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+    use dozer_types::node::{NodeHandle, OpIdentifier};
+    use dozer_types::serde_json::{self, json, Value};
+    use dozer_types::types::{Field, Operation, Record};
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::test_utils;
+
+    #[derive(Debug, Default, Clone)]
+    struct MockPublisher {
+        published: Arc<Mutex<Vec<KafkaSinkMessage>>>,
+        checkpoint: Arc<Mutex<Option<Vec<u8>>>>,
+    }
+
+    impl MessagePublisher for MockPublisher {
+        fn publish(
+            &mut self,
+            messages: &[KafkaSinkMessage],
+            checkpoint: Vec<u8>,
+        ) -> Result<(), BoxedError> {
+            self.published
+                .lock()
+                .unwrap()
+                .extend(messages.iter().cloned());
+            *self.checkpoint.lock().unwrap() = Some(checkpoint);
+            Ok(())
+        }
+
+        fn load_checkpoint(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+            Ok(self.checkpoint.lock().unwrap().clone())
+        }
+    }
+
+    fn film(id: i64, name: &str) -> Record {
+        Record::new(
+            None,
+            vec![Field::Int(id), Field::String(name.to_string())],
+            None,
+        )
+    }
+
+    fn decode(bytes: &[u8]) -> Value {
+        serde_json::from_slice(bytes).unwrap()
+    }
+
+    fn run(format: KafkaSinkFormat, ops: Vec<Operation>) -> Vec<KafkaSinkMessage> {
+        let tmp_dir = TempDir::new("kafka_sink").unwrap();
+        let env =
+            LmdbEnvironmentManager::create(tmp_dir.path(), "test", Default::default()).unwrap();
+        let txn = env.create_txn().unwrap();
+
+        let publisher = MockPublisher::default();
+        let published = publisher.published.clone();
+        let mut sink = KafkaSink::new(
+            "films".to_string(),
+            test_utils::get_schema(),
+            format,
+            Box::new(publisher),
+        )
+        .unwrap();
+
+        for op in ops {
+            sink.process(DEFAULT_PORT_HANDLE, op, &txn, &HashMap::new())
+                .unwrap();
+        }
+        assert!(published.lock().unwrap().is_empty());
+
+        sink.commit(&Epoch::new(0, Default::default()), &txn)
+            .unwrap();
+        let messages = published.lock().unwrap();
+        messages.clone()
+    }
+
+    #[test]
+    fn publishes_debezium_envelopes_on_commit() {
+        let messages = run(
+            KafkaSinkFormat::Debezium,
+            vec![
+                Operation::Insert {
+                    new: film(1, "Film 1"),
+                },
+                Operation::Update {
+                    old: film(1, "Film 1"),
+                    new: film(1, "Film 2"),
+                },
+                Operation::Delete {
+                    old: film(1, "Film 2"),
+                },
+            ],
+        );
+
+        assert_eq!(messages.len(), 3);
+        for message in &messages {
+            assert_eq!(decode(&message.key), json!({"film_id": 1}));
+        }
+        assert_eq!(
+            decode(messages[0].value.as_ref().unwrap()),
+            json!({"before": null, "after": {"film_id": 1, "film_name": "Film 1"}, "op": "c"})
+        );
+        assert_eq!(
+            decode(messages[1].value.as_ref().unwrap()),
+            json!({
+                "before": {"film_id": 1, "film_name": "Film 1"},
+                "after": {"film_id": 1, "film_name": "Film 2"},
+                "op": "u"
+            })
+        );
+        assert_eq!(
+            decode(messages[2].value.as_ref().unwrap()),
+            json!({"before": {"film_id": 1, "film_name": "Film 2"}, "after": null, "op": "d"})
+        );
+    }
+
+    #[test]
+    fn publishes_upserts_and_tombstones() {
+        let messages = run(
+            KafkaSinkFormat::Upsert,
+            vec![
+                Operation::Insert {
+                    new: film(1, "Film 1"),
+                },
+                Operation::Update {
+                    old: film(1, "Film 1"),
+                    new: film(2, "Film 1"),
+                },
+                Operation::Delete {
+                    old: film(2, "Film 1"),
+                },
+            ],
+        );
+
+        let messages: Vec<_> = messages
+            .iter()
+            .map(|message| (decode(&message.key), message.value.as_deref().map(decode)))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    json!({"film_id": 1}),
+                    Some(json!({"film_id": 1, "film_name": "Film 1"}))
+                ),
+                (json!({"film_id": 1}), None),
+                (
+                    json!({"film_id": 2}),
+                    Some(json!({"film_id": 2, "film_name": "Film 1"}))
+                ),
+                (json!({"film_id": 2}), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_published_epochs() {
+        let tmp_dir = TempDir::new("kafka_sink").unwrap();
+        let env =
+            LmdbEnvironmentManager::create(tmp_dir.path(), "test", Default::default()).unwrap();
+        let txn = env.create_txn().unwrap();
+        let node = NodeHandle::new(None, "films".to_string());
+        let epoch = |id, txid| Epoch::new(id, [(node.clone(), OpIdentifier::new(txid, 0))].into());
+
+        let publisher = MockPublisher::default();
+        let new_sink = || {
+            KafkaSink::new(
+                "films".to_string(),
+                test_utils::get_schema(),
+                KafkaSinkFormat::Upsert,
+                Box::new(publisher.clone()),
+            )
+            .unwrap()
+        };
+        let insert = |sink: &mut KafkaSink, id| {
+            sink.process(
+                DEFAULT_PORT_HANDLE,
+                Operation::Insert {
+                    new: film(id, "Film"),
+                },
+                &txn,
+                &HashMap::new(),
+            )
+            .unwrap();
+        };
+
+        let mut sink = new_sink();
+        insert(&mut sink, 1);
+        sink.commit(&epoch(0, 1), &txn).unwrap();
+        insert(&mut sink, 2);
+        sink.commit(&epoch(1, 2), &txn).unwrap();
+        assert_eq!(publisher.published.lock().unwrap().len(), 2);
+
+        // The pipeline restarts from scratch and replays both epochs, then a new one
+        let mut sink = new_sink();
+        insert(&mut sink, 1);
+        sink.commit(&epoch(0, 1), &txn).unwrap();
+        insert(&mut sink, 2);
+        sink.commit(&epoch(1, 2), &txn).unwrap();
+        insert(&mut sink, 3);
+        sink.commit(&epoch(2, 3), &txn).unwrap();
+
+        let published = publisher.published.lock().unwrap();
+        let keys: Vec<_> = published
+            .iter()
+            .map(|message| decode(&message.key))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                json!({"film_id": 1}),
+                json!({"film_id": 2}),
+                json!({"film_id": 3})
+            ]
+        );
+    }
+
+    #[test]
+    fn upsert_requires_primary_key() {
+        let mut schema = test_utils::get_schema();
+        schema.primary_index = vec![];
+        let mut api_endpoint = test_utils::init_endpoint();
+        api_endpoint.index = None;
+        let factory = KafkaSinkFactory::new(
+            api_endpoint,
+            KafkaSinkConfig {
+                broker: "localhost:9092".to_string(),
+                topic: "films".to_string(),
+                format: KafkaSinkFormat::Upsert as i32,
+            },
+        );
+
+        let result = factory.get_output_schema(HashMap::from([(DEFAULT_PORT_HANDLE, schema)]));
+        assert!(matches!(
+            result,
+            Err(ExecutionError::SinkError(SinkError::SinkPrimaryKeyMissing(
+                _
+            )))
+        ));
+    }
+}
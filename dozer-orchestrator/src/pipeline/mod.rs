@@ -1,9 +1,15 @@
 mod builder;
 pub mod connector_source;
+mod kafka_sink;
+mod object_store_sink;
+mod postgres_sink;
 mod sinks;
 pub mod source_builder;
 mod streaming_sink;
 pub mod validate;
 pub use builder::PipelineBuilder;
+pub use kafka_sink::{KafkaSink, KafkaSinkFactory};
+pub use object_store_sink::{ObjectStoreSink, ObjectStoreSinkFactory};
+pub use postgres_sink::{PostgresSink, PostgresSinkFactory};
 pub use sinks::{CacheSink, CacheSinkFactory, CacheSinkSettings};
 pub(crate) use streaming_sink::StreamingSinkFactory;
@@ -0,0 +1,863 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Float64Array, Int64Array, StringArray,
+    TimestampNanosecondArray, UInt64Array,
+};
+use datafusion::arrow::datatypes::{
+    DataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use datafusion::parquet::arrow::ArrowWriter;
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::{ExecutionError, SinkError};
+use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::record_store::RecordReader;
+use dozer_core::storage::lmdb_storage::SharedTransaction;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::debug;
+use dozer_types::models::api_endpoint::{
+    ApiEndpoint, ObjectStoreSinkConfig, ObjectStoreSinkStorage,
+};
+use dozer_types::node::SourceStates;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Operation, Schema};
+use dozer_types::{field_to_json_value, json_value_to_field};
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use tokio::runtime::Runtime;
+
+use super::sinks::get_primary_index;
+
+/// Column of change files holding `insert`, `update` or `delete`.
+const OP_COLUMN: &str = "__dozer_op";
+/// Column of change files holding the epoch the change was committed in.
+const EPOCH_COLUMN: &str = "__dozer_epoch";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionBy {
+    Hour,
+    Day,
+    None,
+}
+
+impl PartitionBy {
+    fn new(endpoint_name: &str, partition_by: &str) -> Result<Self, ExecutionError> {
+        match partition_by {
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            "none" => Ok(Self::None),
+            _ => Err(ExecutionError::SinkError(SinkError::UnsupportedSinkFormat(
+                endpoint_name.to_string(),
+                partition_by.to_string(),
+            ))),
+        }
+    }
+
+    /// Returns the directory of the changes committed at `time`, relative to the changes directory.
+    fn partition(&self, time: DateTime<Utc>) -> Option<String> {
+        match self {
+            Self::Hour => Some(time.format("date=%Y-%m-%d/hour=%H").to_string()),
+            Self::Day => Some(time.format("date=%Y-%m-%d").to_string()),
+            Self::None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+
+    fn from_str(kind: &str) -> Option<Self> {
+        match kind {
+            "insert" => Some(Self::Insert),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+fn connect(storage: &Option<ObjectStoreSinkStorage>) -> Result<Arc<dyn ObjectStore>, BoxedError> {
+    match storage {
+        Some(ObjectStoreSinkStorage::S3(details)) => Ok(Arc::new(
+            AmazonS3Builder::new()
+                .with_bucket_name(&details.bucket_name)
+                .with_region(&details.region)
+                .with_access_key_id(&details.access_key_id)
+                .with_secret_access_key(&details.secret_access_key)
+                .build()?,
+        )),
+        Some(ObjectStoreSinkStorage::Local(details)) => {
+            std::fs::create_dir_all(&details.path)?;
+            Ok(Arc::new(LocalFileSystem::new_with_prefix(&details.path)?))
+        }
+        None => Err("Missing storage details".into()),
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStoreSinkFactory {
+    api_endpoint: ApiEndpoint,
+    config: ObjectStoreSinkConfig,
+}
+
+impl ObjectStoreSinkFactory {
+    pub fn new(api_endpoint: ApiEndpoint, config: ObjectStoreSinkConfig) -> Self {
+        Self {
+            api_endpoint,
+            config,
+        }
+    }
+
+    fn get_output_schema(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<(Schema, PartitionBy), ExecutionError> {
+        debug_assert!(input_schemas.len() == 1);
+        let mut schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .expect("Input schema should be on default port");
+        schema.primary_index = get_primary_index(&schema, &self.api_endpoint)?;
+
+        let partition_by = PartitionBy::new(&self.api_endpoint.name, &self.config.partition_by)?;
+        // Snapshots hold the latest version of every record, which requires a key.
+        if self.config.snapshot_every > 0 && schema.primary_index.is_empty() {
+            return Err(ExecutionError::SinkError(SinkError::SinkPrimaryKeyMissing(
+                self.api_endpoint.name.clone(),
+            )));
+        }
+        Ok((schema, partition_by))
+    }
+}
+
+impl SinkFactory<SchemaSQLContext> for ObjectStoreSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        self.get_output_schema(
+            input_schemas
+                .into_iter()
+                .map(|(key, (schema, _))| (key, schema))
+                .collect(),
+        )?;
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint: &SourceStates,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        let (schema, partition_by) = self.get_output_schema(input_schemas)?;
+        let store = connect(&self.config.storage).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkConnectionFailed(
+                self.api_endpoint.name.clone(),
+                e,
+            ))
+        })?;
+        debug!(
+            "SINK: Initialising ObjectStoreSink: {} to {:?}",
+            self.api_endpoint.name, self.config.prefix
+        );
+        Ok(Box::new(ObjectStoreSink::new(
+            self.api_endpoint.name.clone(),
+            schema,
+            store,
+            &self.config.prefix,
+            partition_by,
+            self.config.snapshot_every,
+        )?))
+    }
+}
+
+/// Writes the operations of every epoch to a Parquet change file, partitioned by commit time.
+///
+/// Change files have the fields of the endpoint plus the kind of change and the epoch. An update
+/// changing the primary key is written as a delete followed by an insert. Every `snapshot_every`
+/// commits writing changes, a snapshot with the latest version of every record is written too.
+/// The records are kept in memory and restored from the last snapshot and the following change
+/// files when the sink is built.
+///
+/// Files are written on commit, so changes written right before a crash are written again when
+/// the pipeline restarts from the previous checkpoint.
+#[derive(Debug)]
+pub struct ObjectStoreSink {
+    endpoint_name: String,
+    schema: Schema,
+    store: Arc<dyn ObjectStore>,
+    runtime: Runtime,
+    prefix: String,
+    partition_by: PartitionBy,
+    snapshot_every: u64,
+    commits: u64,
+    changes: Vec<(ChangeKind, Vec<Field>)>,
+    records: Option<BTreeMap<Vec<Field>, Vec<Field>>>,
+}
+
+impl ObjectStoreSink {
+    pub fn new(
+        endpoint_name: String,
+        schema: Schema,
+        store: Arc<dyn ObjectStore>,
+        prefix: &str,
+        partition_by: PartitionBy,
+        snapshot_every: u64,
+    ) -> Result<Self, ExecutionError> {
+        let runtime = Runtime::new().map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkConnectionFailed(
+                endpoint_name.clone(),
+                Box::new(e),
+            ))
+        })?;
+        let mut sink = Self {
+            endpoint_name,
+            schema,
+            store,
+            runtime,
+            prefix: prefix.trim_matches('/').to_string(),
+            partition_by,
+            snapshot_every,
+            commits: 0,
+            changes: vec![],
+            records: None,
+        };
+        if snapshot_every > 0 {
+            sink.restore().map_err(|e| {
+                ExecutionError::SinkError(SinkError::SinkRestoreFailed(
+                    sink.endpoint_name.clone(),
+                    e,
+                ))
+            })?;
+        }
+        Ok(sink)
+    }
+
+    fn path(&self, path: String) -> Path {
+        if self.prefix.is_empty() {
+            Path::from(path)
+        } else {
+            Path::from(format!("{}/{path}", self.prefix))
+        }
+    }
+
+    fn key(&self, values: &[Field]) -> Vec<Field> {
+        self.schema
+            .primary_index
+            .iter()
+            .map(|idx| values[*idx].clone())
+            .collect()
+    }
+
+    /// Returns the files of `dir`, with their names, in the order they were written.
+    fn list(&self, dir: &str) -> Result<Vec<(String, Path)>, BoxedError> {
+        let dir = self.path(dir.to_string());
+        let mut files: Vec<(String, Path)> = self.runtime.block_on(async {
+            let objects = self.store.list(Some(&dir)).await?;
+            objects
+                .map_ok(|object| {
+                    let name = object.location.filename().unwrap_or_default().to_string();
+                    (name, object.location)
+                })
+                .try_collect()
+                .await
+        })?;
+        files.retain(|(name, _)| name.ends_with(".parquet"));
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(files)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<RecordBatch>, BoxedError> {
+        let bytes = self
+            .runtime
+            .block_on(async { self.store.get(path).await?.bytes().await })?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()?;
+        Ok(reader.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn write(&self, path: Path, batch: &RecordBatch) -> Result<(), BoxedError> {
+        let mut buffer = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+        writer.write(batch)?;
+        writer.close()?;
+        self.runtime
+            .block_on(self.store.put(&path, buffer.into()))?;
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<(), BoxedError> {
+        let mut records = BTreeMap::new();
+
+        let snapshot = self.list("snapshots")?.pop();
+        if let Some((_, path)) = &snapshot {
+            for batch in self.read(path)? {
+                for values in read_rows(&self.schema.fields, &batch, 0)? {
+                    records.insert(self.key(&values), values);
+                }
+            }
+        }
+
+        let snapshot_name = snapshot.map(|(name, _)| name).unwrap_or_default();
+        for (name, path) in self.list("changes")? {
+            if name <= snapshot_name {
+                continue;
+            }
+            for batch in self.read(&path)? {
+                let ops = downcast::<StringArray>(batch.column(0))?;
+                for (row, values) in read_rows(&self.schema.fields, &batch, 2)?
+                    .into_iter()
+                    .enumerate()
+                {
+                    match ChangeKind::from_str(ops.value(row)) {
+                        Some(ChangeKind::Insert | ChangeKind::Update) => {
+                            records.insert(self.key(&values), values);
+                        }
+                        Some(ChangeKind::Delete) => {
+                            records.remove(&self.key(&values));
+                        }
+                        None => return Err(format!("Invalid change in {path}").into()),
+                    }
+                }
+            }
+            self.commits += 1;
+        }
+
+        debug!(
+            "SINK: Restored {} records of {} from {:?}",
+            records.len(),
+            self.endpoint_name,
+            snapshot_name
+        );
+        self.records = Some(records);
+        Ok(())
+    }
+
+    fn push(&mut self, kind: ChangeKind, values: Vec<Field>) {
+        let key = self.key(&values);
+        if let Some(records) = &mut self.records {
+            match kind {
+                ChangeKind::Insert | ChangeKind::Update => {
+                    records.insert(key, values.clone());
+                }
+                ChangeKind::Delete => {
+                    records.remove(&key);
+                }
+            }
+        }
+        self.changes.push((kind, values));
+    }
+
+    fn write_changes(
+        &self,
+        epoch: &Epoch,
+        name: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), BoxedError> {
+        let mut columns: Vec<(ArrowField, ArrayRef)> = vec![
+            (
+                ArrowField::new(OP_COLUMN, DataType::Utf8, false),
+                Arc::new(
+                    self.changes
+                        .iter()
+                        .map(|(kind, _)| Some(kind.as_str()))
+                        .collect::<StringArray>(),
+                ),
+            ),
+            (
+                ArrowField::new(EPOCH_COLUMN, DataType::UInt64, false),
+                Arc::new(
+                    self.changes
+                        .iter()
+                        .map(|_| Some(epoch.id))
+                        .collect::<UInt64Array>(),
+                ),
+            ),
+        ];
+        columns.extend(arrow_columns(
+            &self.schema.fields,
+            self.changes.iter().map(|(_, values)| values),
+        )?);
+
+        let path = match self.partition_by.partition(now) {
+            Some(partition) => format!("changes/{partition}/{name}"),
+            None => format!("changes/{name}"),
+        };
+        self.write(self.path(path), &record_batch(columns)?)
+    }
+
+    fn write_snapshot(&self, name: &str) -> Result<(), BoxedError> {
+        let records = self.records.as_ref().expect("Snapshots should be enabled");
+        let columns = arrow_columns(&self.schema.fields, records.values())?;
+        self.write(
+            self.path(format!("snapshots/{name}")),
+            &record_batch(columns)?,
+        )
+    }
+}
+
+impl Sink for ObjectStoreSink {
+    fn commit(&mut self, epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        // Names sort in commit order, whatever the partition.
+        let name = format!("{:013}-{:010}.parquet", now.timestamp_millis(), epoch.id);
+        let write_failed = |e| {
+            ExecutionError::SinkError(SinkError::SinkWriteFailed(self.endpoint_name.clone(), e))
+        };
+
+        debug!(
+            "SINK: Writing {} changes of {} at epoch {}",
+            self.changes.len(),
+            self.endpoint_name,
+            epoch.id
+        );
+        self.write_changes(epoch, &name, now)
+            .map_err(write_failed)?;
+        self.commits += 1;
+        if self.snapshot_every > 0 && self.commits % self.snapshot_every == 0 {
+            self.write_snapshot(&name).map_err(write_failed)?;
+        }
+        self.changes.clear();
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        _tx: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Insert { new } => self.push(ChangeKind::Insert, new.values),
+            Operation::Delete { old } => self.push(ChangeKind::Delete, old.values),
+            Operation::Update { old, new } => {
+                if self.key(&old.values) == self.key(&new.values) {
+                    self.push(ChangeKind::Update, new.values);
+                } else {
+                    self.push(ChangeKind::Delete, old.values);
+                    self.push(ChangeKind::Insert, new.values);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+fn record_batch(columns: Vec<(ArrowField, ArrayRef)>) -> Result<RecordBatch, BoxedError> {
+    let (fields, arrays): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+    Ok(RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(fields)),
+        arrays,
+    )?)
+}
+
+fn arrow_type(typ: FieldType) -> DataType {
+    match typ {
+        FieldType::UInt => DataType::UInt64,
+        FieldType::Int => DataType::Int64,
+        FieldType::Float => DataType::Float64,
+        FieldType::Boolean => DataType::Boolean,
+        FieldType::String | FieldType::Text => DataType::Utf8,
+        FieldType::Binary | FieldType::Bson => DataType::Binary,
+        FieldType::Timestamp => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        FieldType::Date => DataType::Date32,
+        // Written as their JSON representation
+        FieldType::Decimal | FieldType::Point | FieldType::Duration | FieldType::BigInt => {
+            DataType::Utf8
+        }
+    }
+}
+
+fn unix_epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("1970-01-01 should be a valid date")
+}
+
+/// Converts the values of `rows` to one column per field. Values not matching the type of their
+/// field are written as nulls.
+fn arrow_columns<'a>(
+    fields: &[FieldDefinition],
+    rows: impl Iterator<Item = &'a Vec<Field>> + Clone,
+) -> Result<Vec<(ArrowField, ArrayRef)>, BoxedError> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let values = rows.clone().map(move |values| &values[idx]);
+            let mismatch = |value: &Field| -> BoxedError {
+                format!(
+                    "Value {value} of field {} doesn't match its type {}",
+                    field.name, field.typ
+                )
+                .into()
+            };
+            let array: ArrayRef = match field.typ {
+                FieldType::UInt => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::UInt(value) => Ok(Some(*value)),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<UInt64Array, _>>()?,
+                ),
+                FieldType::Int => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Int(value) => Ok(Some(*value)),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<Int64Array, _>>()?,
+                ),
+                FieldType::Float => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Float(value) => Ok(Some(value.0)),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<Float64Array, _>>()?,
+                ),
+                FieldType::Boolean => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Boolean(value) => Ok(Some(*value)),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<BooleanArray, _>>()?,
+                ),
+                FieldType::String | FieldType::Text => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::String(value) | Field::Text(value) => Ok(Some(value.as_str())),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<StringArray, _>>()?,
+                ),
+                FieldType::Binary | FieldType::Bson => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Binary(value) | Field::Bson(value) => Ok(Some(value.as_slice())),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<BinaryArray, _>>()?,
+                ),
+                FieldType::Timestamp => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Timestamp(value) => Ok(Some(value.timestamp_nanos())),
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<TimestampNanosecondArray, _>>()?,
+                ),
+                FieldType::Date => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Field::Date(value) => {
+                                Ok(Some((*value - unix_epoch_date()).num_days() as i32))
+                            }
+                            Field::Null => Ok(None),
+                            value => Err(mismatch(value)),
+                        })
+                        .collect::<Result<Date32Array, _>>()?,
+                ),
+                FieldType::Decimal | FieldType::Point | FieldType::Duration | FieldType::BigInt => {
+                    Arc::new(
+                        values
+                            .map(|value| match (field.typ, value) {
+                                (_, Field::Null) => Ok(None),
+                                (FieldType::Decimal, Field::Decimal(_))
+                                | (FieldType::Point, Field::Point(_))
+                                | (FieldType::Duration, Field::Duration(_))
+                                | (FieldType::BigInt, Field::BigInt(_)) => {
+                                    Ok(Some(field_to_json_value(value.clone()).to_string()))
+                                }
+                                (_, value) => Err(mismatch(value)),
+                            })
+                            .collect::<Result<StringArray, _>>()?,
+                    )
+                }
+            };
+            Ok((
+                ArrowField::new(&field.name, arrow_type(field.typ), field.nullable),
+                array,
+            ))
+        })
+        .collect()
+}
+
+fn downcast<T: 'static>(column: &ArrayRef) -> Result<&T, BoxedError> {
+    column
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| format!("Unexpected column type {}", column.data_type()).into())
+}
+
+/// Reads the rows of a batch written by the sink, whose fields start at column `offset`.
+fn read_rows(
+    fields: &[FieldDefinition],
+    batch: &RecordBatch,
+    offset: usize,
+) -> Result<Vec<Vec<Field>>, BoxedError> {
+    let mut rows = vec![Vec::with_capacity(fields.len()); batch.num_rows()];
+    for (idx, field) in fields.iter().enumerate() {
+        let column = batch.column(offset + idx);
+        for (row, values) in rows.iter_mut().enumerate() {
+            values.push(read_field(field, column, row)?);
+        }
+    }
+    Ok(rows)
+}
+
+fn read_field(field: &FieldDefinition, column: &ArrayRef, row: usize) -> Result<Field, BoxedError> {
+    if column.is_null(row) {
+        return Ok(Field::Null);
+    }
+    Ok(match field.typ {
+        FieldType::UInt => Field::UInt(downcast::<UInt64Array>(column)?.value(row)),
+        FieldType::Int => Field::Int(downcast::<Int64Array>(column)?.value(row)),
+        FieldType::Float => {
+            Field::Float(OrderedFloat(downcast::<Float64Array>(column)?.value(row)))
+        }
+        FieldType::Boolean => Field::Boolean(downcast::<BooleanArray>(column)?.value(row)),
+        FieldType::String => Field::String(downcast::<StringArray>(column)?.value(row).to_string()),
+        FieldType::Text => Field::Text(downcast::<StringArray>(column)?.value(row).to_string()),
+        FieldType::Binary => Field::Binary(downcast::<BinaryArray>(column)?.value(row).to_vec()),
+        FieldType::Bson => Field::Bson(downcast::<BinaryArray>(column)?.value(row).to_vec()),
+        FieldType::Timestamp => Field::Timestamp(
+            Utc.timestamp_nanos(downcast::<TimestampNanosecondArray>(column)?.value(row))
+                .into(),
+        ),
+        FieldType::Date => Field::Date(
+            unix_epoch_date() + Duration::days(downcast::<Date32Array>(column)?.value(row) as i64),
+        ),
+        FieldType::Decimal | FieldType::Point | FieldType::Duration | FieldType::BigInt => {
+            let value = serde_json::from_str(downcast::<StringArray>(column)?.value(row))?;
+            json_value_to_field(value, field.typ, field.nullable)?
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+    use dozer_types::types::Record;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::test_utils;
+
+    fn film(id: i64, name: &str) -> Record {
+        Record::new(
+            None,
+            vec![Field::Int(id), Field::String(name.to_string())],
+            None,
+        )
+    }
+
+    fn create_sink(dir: &TempDir, snapshot_every: u64) -> ObjectStoreSink {
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path()).unwrap());
+        ObjectStoreSink::new(
+            "films".to_string(),
+            test_utils::get_schema(),
+            store,
+            "films",
+            PartitionBy::Hour,
+            snapshot_every,
+        )
+        .unwrap()
+    }
+
+    fn process_and_commit(sink: &mut ObjectStoreSink, epoch: u64, ops: Vec<Operation>) {
+        let tmp_dir = TempDir::new("object_store_sink_state").unwrap();
+        let env =
+            LmdbEnvironmentManager::create(tmp_dir.path(), "test", Default::default()).unwrap();
+        let txn = env.create_txn().unwrap();
+        for op in ops {
+            sink.process(DEFAULT_PORT_HANDLE, op, &txn, &HashMap::new())
+                .unwrap();
+        }
+        sink.commit(&Epoch::new(epoch, Default::default()), &txn)
+            .unwrap();
+    }
+
+    fn read_all(sink: &ObjectStoreSink, dir: &str, offset: usize) -> Vec<Vec<Field>> {
+        let mut rows = vec![];
+        for (_, path) in sink.list(dir).unwrap() {
+            for batch in sink.read(&path).unwrap() {
+                rows.extend(read_rows(&sink.schema.fields, &batch, offset).unwrap());
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn partitions_by_commit_time() {
+        let time = Utc.with_ymd_and_hms(2023, 3, 4, 5, 6, 7).unwrap();
+        assert_eq!(
+            PartitionBy::Hour.partition(time),
+            Some("date=2023-03-04/hour=05".to_string())
+        );
+        assert_eq!(
+            PartitionBy::Day.partition(time),
+            Some("date=2023-03-04".to_string())
+        );
+        assert_eq!(PartitionBy::None.partition(time), None);
+    }
+
+    #[test]
+    fn rejects_values_not_matching_their_field() {
+        let dir = TempDir::new("object_store_sink").unwrap();
+        let sink = create_sink(&dir, 0);
+
+        let rows = vec![film(1, "Film 1").values, vec![Field::Int(2), Field::Int(3)]];
+        let err = arrow_columns(&sink.schema.fields, rows.iter()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Value 3 (signed int) of field film_name doesn't match its type string"
+        );
+    }
+
+    #[test]
+    fn writes_a_change_file_per_epoch() {
+        let dir = TempDir::new("object_store_sink").unwrap();
+        let mut sink = create_sink(&dir, 0);
+
+        process_and_commit(
+            &mut sink,
+            1,
+            vec![Operation::Insert {
+                new: film(1, "Film 1"),
+            }],
+        );
+        // Epochs without changes aren't written.
+        process_and_commit(&mut sink, 2, vec![]);
+        process_and_commit(
+            &mut sink,
+            3,
+            vec![
+                Operation::Update {
+                    old: film(1, "Film 1"),
+                    new: film(2, "Film 1"),
+                },
+                Operation::Delete {
+                    old: film(2, "Film 1"),
+                },
+            ],
+        );
+
+        let files = sink.list("changes").unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[0].1.as_ref().starts_with("films/changes/date="));
+
+        let batch = &sink.read(&files[1].1).unwrap()[0];
+        let ops = downcast::<StringArray>(batch.column(0)).unwrap();
+        assert_eq!(
+            (0..ops.len()).map(|row| ops.value(row)).collect::<Vec<_>>(),
+            vec!["delete", "insert", "delete"]
+        );
+        assert_eq!(
+            read_all(&sink, "changes", 2),
+            vec![
+                film(1, "Film 1").values,
+                film(1, "Film 1").values,
+                film(2, "Film 1").values,
+                film(2, "Film 1").values,
+            ]
+        );
+        assert!(sink.list("snapshots").unwrap().is_empty());
+    }
+
+    #[test]
+    fn writes_snapshots_and_restores_them() {
+        let dir = TempDir::new("object_store_sink").unwrap();
+        let mut sink = create_sink(&dir, 2);
+
+        process_and_commit(
+            &mut sink,
+            1,
+            vec![
+                Operation::Insert {
+                    new: film(1, "Film 1"),
+                },
+                Operation::Insert {
+                    new: film(2, "Film 2"),
+                },
+            ],
+        );
+        assert!(sink.list("snapshots").unwrap().is_empty());
+        process_and_commit(
+            &mut sink,
+            2,
+            vec![Operation::Update {
+                old: film(1, "Film 1"),
+                new: film(1, "Film 3"),
+            }],
+        );
+        assert_eq!(
+            read_all(&sink, "snapshots", 0),
+            vec![film(1, "Film 3").values, film(2, "Film 2").values]
+        );
+        process_and_commit(
+            &mut sink,
+            3,
+            vec![Operation::Delete {
+                old: film(2, "Film 2"),
+            }],
+        );
+
+        // The restored sink continues counting from the last snapshot.
+        let mut sink = create_sink(&dir, 2);
+        process_and_commit(
+            &mut sink,
+            4,
+            vec![Operation::Insert {
+                new: film(4, "Film 4"),
+            }],
+        );
+        let snapshots = sink.list("snapshots").unwrap();
+        assert_eq!(snapshots.len(), 2);
+        let batch = &sink.read(&snapshots[1].1).unwrap()[0];
+        assert_eq!(
+            read_rows(&sink.schema.fields, batch, 0).unwrap(),
+            vec![film(1, "Film 3").values, film(4, "Film 4").values]
+        );
+    }
+}
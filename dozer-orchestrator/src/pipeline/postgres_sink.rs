@@ -0,0 +1,604 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+
+use dozer_core::epoch::Epoch;
+use dozer_core::errors::{ExecutionError, SinkError};
+use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::record_store::RecordReader;
+use dozer_core::storage::lmdb_storage::SharedTransaction;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::{debug, warn};
+use dozer_types::models::api_endpoint::{ApiEndpoint, PostgresSinkConfig};
+use dozer_types::models::connection::PostgresConfig;
+use dozer_types::node::SourceStates;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::serde_json::{self, Value};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Operation, Schema};
+use postgres::types::ToSql;
+use postgres::{Client, NoTls, Transaction};
+
+use super::sinks::{decode_source_states, encode_source_states, get_primary_index, is_applied};
+
+/// Table holding the `SourceStates` of the last epoch applied to every sink table. Only the tables
+/// created by the sink have a row.
+const CHECKPOINTS_TABLE: &str = "__dozer_sink_checkpoints";
+
+/// Postgres limits the number of parameters of a statement to 65535.
+const MAX_PARAMETERS: usize = 65535;
+const MAX_BATCH_ROWS: usize = 1000;
+
+#[derive(Debug)]
+pub struct PostgresSinkFactory {
+    api_endpoint: ApiEndpoint,
+    config: PostgresSinkConfig,
+}
+
+impl PostgresSinkFactory {
+    pub fn new(api_endpoint: ApiEndpoint, config: PostgresSinkConfig) -> Self {
+        Self {
+            api_endpoint,
+            config,
+        }
+    }
+
+    fn get_output_schema(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, ExecutionError> {
+        debug_assert!(input_schemas.len() == 1);
+        let mut schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .expect("Input schema should be on default port");
+        schema.primary_index = get_primary_index(&schema, &self.api_endpoint)?;
+
+        // Changes are applied by primary key.
+        if schema.primary_index.is_empty() {
+            return Err(ExecutionError::SinkError(SinkError::SinkPrimaryKeyMissing(
+                self.api_endpoint.name.clone(),
+            )));
+        }
+        Ok(schema)
+    }
+
+    fn table_name(&self) -> String {
+        if self.config.table_name.is_empty() {
+            self.api_endpoint.name.clone()
+        } else {
+            self.config.table_name.clone()
+        }
+    }
+}
+
+impl SinkFactory<SchemaSQLContext> for PostgresSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(
+        &self,
+        input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        self.get_output_schema(
+            input_schemas
+                .into_iter()
+                .map(|(key, (schema, _))| (key, schema))
+                .collect(),
+        )?;
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        checkpoint: &SourceStates,
+    ) -> Result<Box<dyn Sink>, ExecutionError> {
+        let schema = self.get_output_schema(input_schemas)?;
+        let client = connect(&self.config.connection).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkConnectionFailed(
+                self.api_endpoint.name.clone(),
+                e,
+            ))
+        })?;
+        debug!(
+            "SINK: Initialising PostgresSink: {} to table {}",
+            self.api_endpoint.name,
+            self.table_name()
+        );
+        Ok(Box::new(PostgresSink::new(
+            self.api_endpoint.name.clone(),
+            self.table_name(),
+            schema,
+            client,
+            checkpoint,
+        )?))
+    }
+}
+
+fn connect(config: &Option<PostgresConfig>) -> Result<Client, BoxedError> {
+    let config = config.as_ref().ok_or("Missing connection details")?;
+    Ok(postgres::Config::new()
+        .host(&config.host)
+        .port(config.port as u16)
+        .user(&config.user)
+        .dbname(&config.database)
+        .password(&config.password)
+        .connect(NoTls)?)
+}
+
+/// Mirrors the records of an endpoint into a Postgres table.
+///
+/// The changes of an epoch are compacted to the last one of every primary key, then applied with
+/// batched upserts and deletes in one transaction per commit. The transaction also stores the
+/// `SourceStates` of the epoch, so epochs already applied before a restart are skipped. When the
+/// pipeline starts from scratch, the table is truncated if the sink created it.
+pub struct PostgresSink {
+    endpoint_name: String,
+    table_name: String,
+    schema: Schema,
+    client: Mutex<Client>,
+    applied: SourceStates,
+    changes: BTreeMap<Vec<Field>, Option<Vec<Field>>>,
+}
+
+impl Debug for PostgresSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresSink")
+            .field("endpoint_name", &self.endpoint_name)
+            .field("table_name", &self.table_name)
+            .finish()
+    }
+}
+
+impl PostgresSink {
+    pub fn new(
+        endpoint_name: String,
+        table_name: String,
+        schema: Schema,
+        mut client: Client,
+        checkpoint: &SourceStates,
+    ) -> Result<Self, ExecutionError> {
+        let applied =
+            initialize_table(&mut client, &table_name, &schema, checkpoint).map_err(|e| {
+                ExecutionError::SinkError(SinkError::SinkRestoreFailed(endpoint_name.clone(), e))
+            })?;
+        Ok(Self {
+            endpoint_name,
+            table_name,
+            schema,
+            client: Mutex::new(client),
+            applied,
+            changes: BTreeMap::new(),
+        })
+    }
+
+    fn key(&self, values: &[Field]) -> Vec<Field> {
+        self.schema
+            .primary_index
+            .iter()
+            .map(|idx| values[*idx].clone())
+            .collect()
+    }
+
+    fn apply(&mut self, epoch: &Epoch) -> Result<(), BoxedError> {
+        let client = self.client.get_mut().map_err(|e| e.to_string())?;
+        let mut tx = client.transaction()?;
+
+        let (upserts, deletes): (Vec<_>, Vec<_>) = self
+            .changes
+            .iter()
+            .partition(|(_, values)| values.is_some());
+
+        let pk_fields: Vec<_> = self
+            .schema
+            .primary_index
+            .iter()
+            .map(|idx| &self.schema.fields[*idx])
+            .collect();
+        for batch in deletes.chunks(batch_rows(pk_fields.len())) {
+            let query = delete_query(&self.table_name, &self.schema, batch.len());
+            let params = batch
+                .iter()
+                .flat_map(|(key, _)| key.iter().zip(pk_fields.iter()))
+                .map(|(value, field)| to_sql(value, field))
+                .collect::<Result<Vec<_>, _>>()?;
+            tx.execute(query.as_str(), &params_refs(&params))?;
+        }
+
+        let fields = &self.schema.fields;
+        for batch in upserts.chunks(batch_rows(fields.len())) {
+            let query = upsert_query(&self.table_name, &self.schema, batch.len());
+            let params = batch
+                .iter()
+                .filter_map(|(_, values)| values.as_ref())
+                .flat_map(|values| values.iter().zip(fields.iter()))
+                .map(|(value, field)| to_sql(value, field))
+                .collect::<Result<Vec<_>, _>>()?;
+            tx.execute(query.as_str(), &params_refs(&params))?;
+        }
+
+        store_applied(&mut tx, &self.table_name, &epoch.details)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl Sink for PostgresSink {
+    fn commit(&mut self, epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+        if is_applied(&self.applied, &epoch.details) {
+            debug!(
+                "SINK: Skipping epoch {} of {}, already applied",
+                epoch.id, self.endpoint_name
+            );
+            self.changes.clear();
+            return Ok(());
+        }
+
+        debug!(
+            "SINK: Applying {} changes of {} at epoch {}",
+            self.changes.len(),
+            self.endpoint_name,
+            epoch.id
+        );
+        self.apply(epoch).map_err(|e| {
+            ExecutionError::SinkError(SinkError::SinkWriteFailed(self.endpoint_name.clone(), e))
+        })?;
+        self.applied = epoch.details.clone();
+        self.changes.clear();
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        _tx: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match op {
+            Operation::Insert { new } => {
+                self.changes.insert(self.key(&new.values), Some(new.values));
+            }
+            Operation::Delete { old } => {
+                self.changes.insert(self.key(&old.values), None);
+            }
+            Operation::Update { old, new } => {
+                let (old_key, new_key) = (self.key(&old.values), self.key(&new.values));
+                if old_key != new_key {
+                    self.changes.insert(old_key, None);
+                }
+                self.changes.insert(new_key, Some(new.values));
+            }
+        }
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(&mut self) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Creates the sink table and the checkpoints table, returning the states last applied to the
+/// sink table.
+fn initialize_table(
+    client: &mut Client,
+    table_name: &str,
+    schema: &Schema,
+    checkpoint: &SourceStates,
+) -> Result<SourceStates, BoxedError> {
+    let mut tx = client.transaction()?;
+    tx.batch_execute(&format!(
+        "CREATE TABLE IF NOT EXISTS {} (table_name TEXT PRIMARY KEY, source_states JSONB NOT NULL)",
+        quote(CHECKPOINTS_TABLE)
+    ))?;
+    let exists: bool = tx
+        .query_one("SELECT to_regclass($1) IS NOT NULL", &[&quote(table_name)])?
+        .get(0);
+    if !exists {
+        tx.batch_execute(&create_table_query(table_name, schema))?;
+        store_applied(&mut tx, table_name, &SourceStates::new())?;
+    }
+
+    let row = tx.query_opt(
+        format!(
+            "SELECT source_states FROM {} WHERE table_name = $1",
+            quote(CHECKPOINTS_TABLE)
+        )
+        .as_str(),
+        &[&table_name],
+    )?;
+    let applied = if checkpoint.is_empty() {
+        // The pipeline starts from scratch, so does the table, unless it belongs to the user.
+        if row.is_some() {
+            tx.batch_execute(&format!("TRUNCATE {}", quote(table_name)))?;
+            store_applied(&mut tx, table_name, &SourceStates::new())?;
+        } else {
+            warn!(
+                "[pipeline] Table {} wasn't created by the sink, keeping its rows",
+                table_name
+            );
+        }
+        SourceStates::new()
+    } else {
+        let applied = match row {
+            Some(row) => decode_source_states(row.get(0))?,
+            None => SourceStates::new(),
+        };
+        if !is_applied(&applied, checkpoint) {
+            warn!(
+                "[pipeline] Table {} is at {:?}, behind pipeline at {:?}",
+                table_name, applied, checkpoint
+            );
+        }
+        applied
+    };
+    tx.commit()?;
+    Ok(applied)
+}
+
+fn store_applied(
+    tx: &mut Transaction,
+    table_name: &str,
+    applied: &SourceStates,
+) -> Result<(), BoxedError> {
+    tx.execute(
+        format!(
+            "INSERT INTO {} (table_name, source_states) VALUES ($1, $2) \
+            ON CONFLICT (table_name) DO UPDATE SET source_states = EXCLUDED.source_states",
+            quote(CHECKPOINTS_TABLE)
+        )
+        .as_str(),
+        &[&table_name, &encode_source_states(applied)],
+    )?;
+    Ok(())
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Inverse of `postgres_type_to_dozer_type`, for the types it doesn't map.
+fn postgres_type(typ: FieldType) -> &'static str {
+    match typ {
+        FieldType::Boolean => "BOOLEAN",
+        FieldType::Int => "BIGINT",
+        FieldType::UInt | FieldType::Decimal | FieldType::BigInt => "NUMERIC",
+        FieldType::Float => "DOUBLE PRECISION",
+        FieldType::String | FieldType::Text => "TEXT",
+        FieldType::Binary => "BYTEA",
+        FieldType::Timestamp => "TIMESTAMPTZ",
+        FieldType::Bson => "JSONB",
+        FieldType::Date => "DATE",
+        FieldType::Point => "POINT",
+        FieldType::Duration => "INTERVAL",
+    }
+}
+
+/// Returns the placeholder of parameter `idx`. Types without a Rust equivalent are sent as text.
+fn placeholder(idx: usize, typ: FieldType) -> String {
+    match typ {
+        FieldType::UInt | FieldType::BigInt | FieldType::Point | FieldType::Duration => {
+            format!("${idx}::TEXT::{}", postgres_type(typ))
+        }
+        _ => format!("${idx}"),
+    }
+}
+
+fn to_sql(value: &Field, field: &FieldDefinition) -> Result<Box<dyn ToSql + Sync>, BoxedError> {
+    // Nulls are typed, as parameters are checked against the type of their column.
+    let param: Box<dyn ToSql + Sync> = match (field.typ, value) {
+        (FieldType::Boolean, Field::Boolean(value)) => Box::new(Some(*value)),
+        (FieldType::Boolean, Field::Null) => Box::new(None::<bool>),
+        (FieldType::Int, Field::Int(value)) => Box::new(Some(*value)),
+        (FieldType::Int, Field::Null) => Box::new(None::<i64>),
+        (FieldType::Float, Field::Float(value)) => Box::new(Some(value.0)),
+        (FieldType::Float, Field::Null) => Box::new(None::<f64>),
+        (FieldType::String | FieldType::Text, Field::String(value) | Field::Text(value)) => {
+            Box::new(Some(value.clone()))
+        }
+        (FieldType::String | FieldType::Text, Field::Null) => Box::new(None::<String>),
+        (FieldType::Binary, Field::Binary(value)) => Box::new(Some(value.clone())),
+        (FieldType::Binary, Field::Null) => Box::new(None::<Vec<u8>>),
+        (FieldType::Decimal, Field::Decimal(value)) => Box::new(Some(*value)),
+        (FieldType::Decimal, Field::Null) => Box::new(None::<Decimal>),
+        (FieldType::Timestamp, Field::Timestamp(value)) => Box::new(Some(*value)),
+        (FieldType::Timestamp, Field::Null) => Box::new(None::<DateTime<FixedOffset>>),
+        (FieldType::Date, Field::Date(value)) => Box::new(Some(*value)),
+        (FieldType::Date, Field::Null) => Box::new(None::<NaiveDate>),
+        (FieldType::Bson, Field::Bson(bytes)) => {
+            let value = serde_json::from_slice::<Value>(bytes)
+                .map_err(|e| format!("Value of field {} isn't valid JSON: {e}", field.name))?;
+            Box::new(Some(value))
+        }
+        (FieldType::Bson, Field::Null) => Box::new(None::<Value>),
+        (FieldType::UInt, Field::UInt(value)) => Box::new(Some(value.to_string())),
+        (FieldType::BigInt, Field::BigInt(value)) => Box::new(Some(value.to_string())),
+        (FieldType::Point, Field::Point(point)) => {
+            Box::new(Some(format!("({},{})", point.0.x().0, point.0.y().0)))
+        }
+        (FieldType::Duration, Field::Duration(duration)) => Box::new(Some(format!(
+            "{} months {} days {} microseconds",
+            duration.months,
+            duration.days,
+            duration.nanos / 1000
+        ))),
+        (
+            FieldType::UInt | FieldType::BigInt | FieldType::Point | FieldType::Duration,
+            Field::Null,
+        ) => Box::new(None::<String>),
+        (typ, value) => {
+            return Err(format!(
+                "Value {value} of field {} doesn't match its type {typ}",
+                field.name
+            )
+            .into())
+        }
+    };
+    Ok(param)
+}
+
+fn params_refs(params: &[Box<dyn ToSql + Sync>]) -> Vec<&(dyn ToSql + Sync)> {
+    params.iter().map(|param| param.as_ref()).collect()
+}
+
+fn batch_rows(columns: usize) -> usize {
+    (MAX_PARAMETERS / columns.max(1)).min(MAX_BATCH_ROWS)
+}
+
+fn create_table_query(table_name: &str, schema: &Schema) -> String {
+    let mut columns: Vec<_> = schema
+        .fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{} {}{}",
+                quote(&field.name),
+                postgres_type(field.typ),
+                if field.nullable { "" } else { " NOT NULL" }
+            )
+        })
+        .collect();
+    columns.push(format!("PRIMARY KEY ({})", primary_key_columns(schema)));
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        quote(table_name),
+        columns.join(", ")
+    )
+}
+
+fn primary_key_columns(schema: &Schema) -> String {
+    schema
+        .primary_index
+        .iter()
+        .map(|idx| quote(&schema.fields[*idx].name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the placeholders of `rows` rows of the fields at `indexes`.
+fn values_placeholders(schema: &Schema, indexes: &[usize], rows: usize) -> String {
+    (0..rows)
+        .map(|row| {
+            let values: Vec<_> = indexes
+                .iter()
+                .enumerate()
+                .map(|(column, idx)| {
+                    placeholder(row * indexes.len() + column + 1, schema.fields[*idx].typ)
+                })
+                .collect();
+            format!("({})", values.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn upsert_query(table_name: &str, schema: &Schema, rows: usize) -> String {
+    let indexes: Vec<_> = (0..schema.fields.len()).collect();
+    let columns: Vec<_> = schema
+        .fields
+        .iter()
+        .map(|field| quote(&field.name))
+        .collect();
+    let updates: Vec<_> = schema
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !schema.primary_index.contains(idx))
+        .map(|(_, field)| format!("{0} = EXCLUDED.{0}", quote(&field.name)))
+        .collect();
+    let on_conflict = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    format!(
+        "INSERT INTO {} ({}) VALUES {} ON CONFLICT ({}) {}",
+        quote(table_name),
+        columns.join(", "),
+        values_placeholders(schema, &indexes, rows),
+        primary_key_columns(schema),
+        on_conflict
+    )
+}
+
+fn delete_query(table_name: &str, schema: &Schema, rows: usize) -> String {
+    format!(
+        "DELETE FROM {} WHERE ({}) IN ({})",
+        quote(table_name),
+        primary_key_columns(schema),
+        values_placeholders(schema, &schema.primary_index, rows)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use dozer_types::types::{FieldDefinition, SourceDefinition};
+
+    fn schema() -> Schema {
+        let mut schema = test_utils::get_schema();
+        schema.fields.push(FieldDefinition {
+            name: "rental_count".to_string(),
+            typ: FieldType::UInt,
+            nullable: true,
+            source: SourceDefinition::Dynamic,
+        });
+        schema
+    }
+
+    #[test]
+    fn maps_schema_to_table() {
+        assert_eq!(
+            create_table_query("films", &schema()),
+            "CREATE TABLE IF NOT EXISTS \"films\" (\"film_id\" BIGINT NOT NULL, \
+            \"film_name\" TEXT NOT NULL, \"rental_count\" NUMERIC, PRIMARY KEY (\"film_id\"))"
+        );
+    }
+
+    #[test]
+    fn builds_batched_statements() {
+        assert_eq!(
+            upsert_query("films", &schema(), 2),
+            "INSERT INTO \"films\" (\"film_id\", \"film_name\", \"rental_count\") \
+            VALUES ($1, $2, $3::TEXT::NUMERIC), ($4, $5, $6::TEXT::NUMERIC) \
+            ON CONFLICT (\"film_id\") DO UPDATE SET \"film_name\" = EXCLUDED.\"film_name\", \
+            \"rental_count\" = EXCLUDED.\"rental_count\""
+        );
+        assert_eq!(
+            delete_query("films", &schema(), 2),
+            "DELETE FROM \"films\" WHERE (\"film_id\") IN (($1), ($2))"
+        );
+        assert_eq!(batch_rows(3), MAX_BATCH_ROWS);
+        assert_eq!(batch_rows(100), 655);
+    }
+
+    #[test]
+    fn rejects_values_not_matching_their_field() {
+        let schema = schema();
+        assert!(to_sql(&Field::Int(1), &schema.fields[0]).is_ok());
+        assert!(to_sql(&Field::Null, &schema.fields[2]).is_ok());
+        assert_eq!(
+            to_sql(&Field::Int(1), &schema.fields[2])
+                .err()
+                .unwrap()
+                .to_string(),
+            "Value 1 (signed int) of field rental_count doesn't match its type unsigned int"
+        );
+
+        let bson = FieldDefinition {
+            name: "meta".to_string(),
+            typ: FieldType::Bson,
+            nullable: true,
+            source: SourceDefinition::Dynamic,
+        };
+        assert!(to_sql(&Field::Bson(b"{\"a\": 1}".to_vec()), &bson).is_ok());
+        assert!(to_sql(&Field::Bson(b"{".to_vec()), &bson).is_err());
+    }
+}
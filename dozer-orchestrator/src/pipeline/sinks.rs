@@ -3,7 +3,7 @@ use dozer_api::grpc::internal::internal_pipeline_server::PipelineEventSenders;
 use dozer_api::grpc::types_helper;
 use dozer_cache::cache::expression::QueryExpression;
 use dozer_cache::cache::index::get_primary_key;
-use dozer_cache::cache::{CacheManager, RwCache};
+use dozer_cache::cache::{CacheManager, OnExpired, RetentionPolicy, RwCache};
 use dozer_core::epoch::Epoch;
 use dozer_core::errors::{ExecutionError, SinkError};
 use dozer_core::node::{PortHandle, Sink, SinkFactory};
@@ -12,18 +12,23 @@ use dozer_core::storage::lmdb_storage::SharedTransaction;
 use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_sql::pipeline::builder::SchemaSQLContext;
 use dozer_types::crossbeam::channel::Sender;
+use dozer_types::errors::internal::BoxedError;
 use dozer_types::grpc_types::internal::AliasRedirected;
+use dozer_types::grpc_types::types::Operation as GrpcOperation;
 use dozer_types::indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use dozer_types::log::{debug, info};
+use dozer_types::log::{debug, error, info};
 use dozer_types::models::api_endpoint::{ApiEndpoint, ApiIndex};
 use dozer_types::models::api_security::ApiSecurity;
 use dozer_types::models::flags::Flags;
-use dozer_types::node::SourceStates;
-use dozer_types::types::FieldType;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+use dozer_types::serde_json::{self, json, Value};
+use dozer_types::types::{FieldType, FullTextAnalyzer, Language, PrefixSearch};
 use dozer_types::types::{IndexDefinition, Operation, Schema, SchemaIdentifier};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 fn attach_progress(multi_pb: Option<MultiProgress>) -> ProgressBar {
     let pb = ProgressBar::new_spinner();
@@ -95,37 +100,18 @@ impl CacheSinkFactory {
             .remove(&DEFAULT_PORT_HANDLE)
             .expect("Input schema should be on default port");
 
-        // Generated Cache index based on api_index
-        let configured_index = create_primary_indexes(
-            &schema,
-            &self.api_endpoint.index.to_owned().unwrap_or_default(),
-        )?;
-        // Generated schema in SQL
-        let upstream_index = schema.primary_index.clone();
-
-        let index = match (configured_index.is_empty(), upstream_index.is_empty()) {
-            (true, true) => vec![],
-            (true, false) => upstream_index,
-            (false, true) => configured_index,
-            (false, false) => {
-                if !upstream_index.eq(&configured_index) {
-                    return Err(ExecutionError::MismatchPrimaryKey {
-                        endpoint_name: self.api_endpoint.name.clone(),
-                        expected: get_field_names(&schema, &upstream_index),
-                        actual: get_field_names(&schema, &configured_index),
-                    });
-                }
-                configured_index
-            }
-        };
-
-        schema.primary_index = index;
+        schema.primary_index = get_primary_index(&schema, &self.api_endpoint)?;
 
         schema.identifier = Some(SchemaIdentifier {
             id: DEFAULT_PORT_HANDLE as u32,
             version: 1,
         });
 
+        let mut full_text_analyzers = get_full_text_analyzers(
+            &schema,
+            &self.api_endpoint.index.to_owned().unwrap_or_default(),
+        )?;
+
         // Automatically create secondary indexes
         let secondary_indexes = schema
             .fields
@@ -140,17 +126,30 @@ impl CacheSinkFactory {
                 | FieldType::Decimal
                 | FieldType::Timestamp
                 | FieldType::Date
-                | FieldType::Point => vec![IndexDefinition::SortedInverted(vec![idx])],
+                | FieldType::Duration
+                | FieldType::BigInt => vec![IndexDefinition::SortedInverted(vec![idx])],
+
+                // Create sorted inverted and geo indexes for point fields.
+                FieldType::Point => vec![
+                    IndexDefinition::SortedInverted(vec![idx]),
+                    IndexDefinition::Geo(idx),
+                ],
 
                 // Create sorted inverted and full text indexes for string fields.
                 FieldType::String => vec![
                     IndexDefinition::SortedInverted(vec![idx]),
-                    IndexDefinition::FullText(idx),
+                    IndexDefinition::FullText(
+                        idx,
+                        full_text_analyzers.remove(&idx).unwrap_or_default(),
+                    ),
                 ],
 
-                // Create full text indexes for text fields
-                // FieldType::Text => vec![IndexDefinition::FullText(idx)],
-                FieldType::Text => vec![],
+                // Create full text indexes for text fields only if configured.
+                FieldType::Text => full_text_analyzers
+                    .remove(&idx)
+                    .map(|analyzer| IndexDefinition::FullText(idx, analyzer))
+                    .into_iter()
+                    .collect(),
 
                 // Skip creating indexes
                 FieldType::Binary | FieldType::Bson => vec![],
@@ -216,6 +215,83 @@ impl SinkFactory<SchemaSQLContext> for CacheSinkFactory {
     }
 }
 
+/// Returns the primary index configured for `api_endpoint`, checking it against the one of `schema`.
+pub(crate) fn get_primary_index(
+    schema: &Schema,
+    api_endpoint: &ApiEndpoint,
+) -> Result<Vec<usize>, ExecutionError> {
+    // Generated Cache index based on api_index
+    let configured_index =
+        create_primary_indexes(schema, &api_endpoint.index.to_owned().unwrap_or_default())?;
+    // Generated schema in SQL
+    let upstream_index = schema.primary_index.clone();
+
+    let index = match (configured_index.is_empty(), upstream_index.is_empty()) {
+        (true, true) => vec![],
+        (true, false) => upstream_index,
+        (false, true) => configured_index,
+        (false, false) => {
+            if !upstream_index.eq(&configured_index) {
+                return Err(ExecutionError::MismatchPrimaryKey {
+                    endpoint_name: api_endpoint.name.clone(),
+                    expected: get_field_names(schema, &upstream_index),
+                    actual: get_field_names(schema, &configured_index),
+                });
+            }
+            configured_index
+        }
+    };
+    Ok(index)
+}
+
+/// Returns whether every source of `states` is at or before its position in `applied`.
+pub(crate) fn is_applied(applied: &SourceStates, states: &SourceStates) -> bool {
+    !states.is_empty()
+        && states.iter().all(|(node, op)| {
+            applied
+                .get(node)
+                .map_or(false, |applied_op| applied_op >= op)
+        })
+}
+
+pub(crate) fn encode_source_states(states: &SourceStates) -> Value {
+    Value::Array(
+        states
+            .iter()
+            .map(|(node, op)| {
+                json!({
+                    "ns": node.ns,
+                    "id": node.id,
+                    "txid": op.txid,
+                    "seq_in_tx": op.seq_in_tx,
+                })
+            })
+            .collect(),
+    )
+}
+
+pub(crate) fn decode_source_states(value: Value) -> Result<SourceStates, BoxedError> {
+    #[derive(dozer_types::serde::Deserialize)]
+    #[serde(crate = "dozer_types::serde")]
+    struct SourceState {
+        ns: Option<u16>,
+        id: String,
+        txid: u64,
+        seq_in_tx: u64,
+    }
+
+    let states: Vec<SourceState> = serde_json::from_value(value)?;
+    Ok(states
+        .into_iter()
+        .map(|state| {
+            (
+                NodeHandle::new(state.ns, state.id),
+                OpIdentifier::new(state.txid, state.seq_in_tx),
+            )
+        })
+        .collect())
+}
+
 fn create_primary_indexes(
     schema: &Schema,
     api_index: &ApiIndex,
@@ -235,6 +311,107 @@ fn create_primary_indexes(
     Ok(primary_index)
 }
 
+/// Returns the configured full text analyzers by field index.
+fn get_full_text_analyzers(
+    schema: &Schema,
+    api_index: &ApiIndex,
+) -> Result<HashMap<usize, FullTextAnalyzer>, ExecutionError> {
+    let mut analyzers = HashMap::new();
+    for full_text in &api_index.full_text {
+        let (idx, field) = schema
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(_, fd)| fd.name == full_text.field)
+            .ok_or_else(|| ExecutionError::FieldNotFound(full_text.field.clone()))?;
+        if !matches!(field.typ, FieldType::String | FieldType::Text) {
+            return Err(ExecutionError::InvalidOperation(format!(
+                "full text index on {} requires a string or text field",
+                field.name
+            )));
+        }
+
+        let language = |name: &Option<String>| {
+            name.as_deref()
+                .map(|name| {
+                    Language::from_name(name).ok_or_else(|| {
+                        ExecutionError::InvalidOperation(format!(
+                            "unsupported language {name} in full text index on {}",
+                            field.name
+                        ))
+                    })
+                })
+                .transpose()
+        };
+        let prefix_search = match &full_text.prefix_search {
+            Some(prefix_search) => {
+                if prefix_search.min_len == 0 || prefix_search.min_len > prefix_search.max_len {
+                    return Err(ExecutionError::InvalidOperation(format!(
+                        "prefix lengths of full text index on {} must satisfy 1 <= min_len <= max_len",
+                        field.name
+                    )));
+                }
+                Some(PrefixSearch {
+                    min_len: prefix_search.min_len as usize,
+                    max_len: prefix_search.max_len as usize,
+                })
+            }
+            None => None,
+        };
+
+        analyzers.insert(
+            idx,
+            FullTextAnalyzer {
+                lowercase: full_text.lowercase,
+                normalize: full_text.normalize,
+                stop_words: language(&full_text.stop_words)?,
+                stemming: language(&full_text.stemming)?,
+                prefix_search,
+            },
+        );
+    }
+    Ok(analyzers)
+}
+
+/// Returns the retention policy configured for `api_endpoint`, which is only supported for append-only schemas.
+fn get_retention_policy(
+    schema: &Schema,
+    api_endpoint: &ApiEndpoint,
+) -> Result<Option<RetentionPolicy>, ExecutionError> {
+    let Some(retention) = &api_endpoint.retention else {
+        return Ok(None);
+    };
+    if !schema.primary_index.is_empty() {
+        return Err(ExecutionError::InvalidOperation(format!(
+            "retention of endpoint {} requires an endpoint without primary key",
+            api_endpoint.name
+        )));
+    }
+
+    let max_age = match &retention.max_age {
+        Some(max_age) => {
+            let (idx, field) = schema
+                .fields
+                .iter()
+                .enumerate()
+                .find(|(_, fd)| fd.name == max_age.field)
+                .ok_or_else(|| ExecutionError::FieldNotFound(max_age.field.clone()))?;
+            if field.typ != FieldType::Timestamp {
+                return Err(ExecutionError::InvalidOperation(format!(
+                    "retention of endpoint {} requires {} to be a timestamp field",
+                    api_endpoint.name, field.name
+                )));
+            }
+            Some((idx, Duration::from_secs(max_age.seconds)))
+        }
+        None => None,
+    };
+    Ok(Some(RetentionPolicy {
+        max_age,
+        max_count: retention.max_records,
+    }))
+}
+
 fn get_field_names(schema: &Schema, indexes: &[usize]) -> Vec<String> {
     indexes
         .iter()
@@ -328,6 +505,8 @@ pub struct CacheSink {
     cache_manager: Arc<dyn CacheManager>,
     cache: Box<dyn RwCache>,
     counter: usize,
+    // Number of records deleted by retention since the last commit, which `counter` doesn't account for yet.
+    num_expired: Arc<AtomicUsize>,
     // Number of records in the cache that's currently served, if that's different from the one being written to.
     current_alias_count: Option<usize>,
     api_endpoint: ApiEndpoint,
@@ -339,6 +518,9 @@ impl Sink for CacheSink {
     fn commit(&mut self, epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
         let endpoint_name = self.api_endpoint.name.clone();
         // Update Counter on commit
+        self.counter = self
+            .counter
+            .saturating_sub(self.num_expired.swap(0, Ordering::SeqCst));
         self.pb.set_position(self.counter as u64);
         self.cache.commit(&epoch.details).map_err(|e| {
             ExecutionError::SinkError(SinkError::CacheCommitTransactionFailed(
@@ -450,13 +632,35 @@ impl CacheSink {
         multi_pb: Option<MultiProgress>,
     ) -> Result<Self, ExecutionError> {
         let query = QueryExpression::with_no_limit();
-        let (cache, current_alias_count) = open_or_create_cache(
+        let retention_policy = get_retention_policy(&schema, &api_endpoint)?;
+        let (mut cache, current_alias_count) = open_or_create_cache(
             &*cache_manager,
             &api_endpoint.name,
             checkpoint,
             schema,
             secondary_indexes,
         )?;
+        let num_expired = Arc::new(AtomicUsize::new(0));
+        if let Some(retention_policy) = retention_policy {
+            let on_expired = on_expired(
+                api_endpoint.name.clone(),
+                notifier.as_ref().map(|notifier| notifier.1.clone()),
+                num_expired.clone(),
+            );
+            cache
+                .start_retention(
+                    &api_endpoint.name,
+                    retention_policy,
+                    Default::default(),
+                    on_expired,
+                )
+                .map_err(|e| {
+                    ExecutionError::SinkError(SinkError::CacheStartRetentionFailed(
+                        api_endpoint.name.clone(),
+                        Box::new(e),
+                    ))
+                })?;
+        }
         let counter = cache.count(&api_endpoint.name, &query).map_err(|e| {
             ExecutionError::SinkError(SinkError::CacheCountFailed(
                 api_endpoint.name.clone(),
@@ -475,6 +679,7 @@ impl CacheSink {
             cache,
             current_alias_count,
             counter,
+            num_expired,
             api_endpoint,
             pb,
             notifier,
@@ -498,6 +703,26 @@ impl CacheSink {
     }
 }
 
+/// Broadcasts the records deleted by retention as delete operations, and counts them in `num_expired`.
+fn on_expired(
+    endpoint_name: String,
+    operation_sender: Option<Sender<GrpcOperation>>,
+    num_expired: Arc<AtomicUsize>,
+) -> OnExpired {
+    Box::new(move |records| {
+        num_expired.fetch_add(records.len(), Ordering::SeqCst);
+        if let Some(sender) = &operation_sender {
+            for record in records {
+                let op = types_helper::map_delete_operation(endpoint_name.clone(), record);
+                if let Err(e) = try_send(sender, op) {
+                    error!("Failed to broadcast expired records of {endpoint_name}: {e}");
+                    break;
+                }
+            }
+        }
+    })
+}
+
 fn try_send<T: Send + Sync + 'static>(sender: &Sender<T>, msg: T) -> Result<(), ExecutionError> {
     sender
         .try_send(msg)
@@ -509,14 +734,29 @@ mod tests {
 
     use crate::test_utils;
 
-    use dozer_cache::cache::index;
+    use super::{
+        decode_source_states, encode_source_states, get_full_text_analyzers, get_retention_policy,
+        is_applied, on_expired,
+    };
+    use dozer_cache::cache::{index, RetentionPolicy};
     use dozer_core::node::Sink;
     use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
     use dozer_core::DEFAULT_PORT_HANDLE;
 
-    use dozer_types::node::NodeHandle;
-    use dozer_types::types::{Field, IndexDefinition, Operation, Record, SchemaIdentifier};
+    use dozer_types::crossbeam::channel;
+    use dozer_types::grpc_types::types::OperationType;
+    use dozer_types::models::api_endpoint::{
+        ApiFullTextIndex, ApiIndex, ApiPrefixSearch, ApiRetention, ApiRetentionMaxAge,
+    };
+    use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+    use dozer_types::types::{
+        Field, FieldDefinition, FieldType, FullTextAnalyzer, IndexDefinition, Language, Operation,
+        PrefixSearch, Record, SchemaIdentifier, SourceDefinition,
+    };
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
     use tempdir::TempDir;
 
     #[test]
@@ -610,4 +850,164 @@ mod tests {
 
         assert_eq!(updated_values, record.values);
     }
+
+    #[test]
+    fn full_text_analyzers_from_config() {
+        let schema = test_utils::get_schema();
+        let full_text = ApiFullTextIndex {
+            field: "film_name".to_string(),
+            lowercase: true,
+            stemming: Some("English".to_string()),
+            prefix_search: Some(ApiPrefixSearch {
+                min_len: 2,
+                max_len: 4,
+            }),
+            ..Default::default()
+        };
+        let api_index = |full_text: ApiFullTextIndex| ApiIndex {
+            primary_key: vec![],
+            full_text: vec![full_text],
+        };
+
+        let analyzers = get_full_text_analyzers(&schema, &api_index(full_text.clone())).unwrap();
+        assert_eq!(
+            analyzers[&1],
+            FullTextAnalyzer {
+                lowercase: true,
+                normalize: false,
+                stop_words: None,
+                stemming: Some(Language::English),
+                prefix_search: Some(PrefixSearch {
+                    min_len: 2,
+                    max_len: 4
+                }),
+            }
+        );
+
+        // Not a string field.
+        assert!(get_full_text_analyzers(
+            &schema,
+            &api_index(ApiFullTextIndex {
+                field: "film_id".to_string(),
+                ..full_text.clone()
+            })
+        )
+        .is_err());
+        // Unknown language.
+        assert!(get_full_text_analyzers(
+            &schema,
+            &api_index(ApiFullTextIndex {
+                stop_words: Some("klingon".to_string()),
+                ..full_text.clone()
+            })
+        )
+        .is_err());
+        // Empty prefixes.
+        assert!(get_full_text_analyzers(
+            &schema,
+            &api_index(ApiFullTextIndex {
+                prefix_search: Some(ApiPrefixSearch {
+                    min_len: 0,
+                    max_len: 4,
+                }),
+                ..full_text
+            })
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn retention_policy_from_config() {
+        let mut schema = test_utils::get_schema();
+        schema.primary_index = vec![];
+        schema.fields.push(FieldDefinition {
+            name: "released_at".to_string(),
+            typ: FieldType::Timestamp,
+            nullable: false,
+            source: SourceDefinition::Dynamic,
+        });
+        let endpoint = |max_age_field: &str| {
+            let mut endpoint = test_utils::init_endpoint();
+            endpoint.retention = Some(ApiRetention {
+                max_age: Some(ApiRetentionMaxAge {
+                    field: max_age_field.to_string(),
+                    seconds: 60,
+                }),
+                max_records: Some(100),
+            });
+            endpoint
+        };
+
+        assert_eq!(
+            get_retention_policy(&schema, &endpoint("released_at")).unwrap(),
+            Some(RetentionPolicy {
+                max_age: Some((2, Duration::from_secs(60))),
+                max_count: Some(100),
+            })
+        );
+        assert_eq!(
+            get_retention_policy(&schema, &test_utils::init_endpoint()).unwrap(),
+            None
+        );
+
+        // Not a timestamp field.
+        assert!(get_retention_policy(&schema, &endpoint("film_name")).is_err());
+        // Unknown field.
+        assert!(get_retention_policy(&schema, &endpoint("deleted_at")).is_err());
+        // Not append-only.
+        schema.primary_index = vec![0];
+        assert!(get_retention_policy(&schema, &endpoint("released_at")).is_err());
+    }
+
+    #[test]
+    fn skips_applied_epochs() {
+        let node = NodeHandle::new(None, "films".to_string());
+        let other = NodeHandle::new(None, "actors".to_string());
+        let applied: SourceStates = [(node.clone(), OpIdentifier::new(5, 2))].into();
+
+        assert!(is_applied(
+            &applied,
+            &[(node.clone(), OpIdentifier::new(5, 2))].into()
+        ));
+        assert!(is_applied(
+            &applied,
+            &[(node.clone(), OpIdentifier::new(4, 9))].into()
+        ));
+        assert!(!is_applied(
+            &applied,
+            &[(node.clone(), OpIdentifier::new(5, 3))].into()
+        ));
+        assert!(!is_applied(
+            &applied,
+            &[
+                (node, OpIdentifier::new(5, 2)),
+                (other, OpIdentifier::new(1, 0))
+            ]
+            .into()
+        ));
+        assert!(!is_applied(&applied, &SourceStates::new()));
+
+        assert_eq!(
+            decode_source_states(encode_source_states(&applied)).unwrap(),
+            applied
+        );
+    }
+
+    #[test]
+    fn broadcasts_expired_records() {
+        let (sender, receiver) = channel::unbounded();
+        let num_expired = Arc::new(AtomicUsize::new(0));
+        let on_expired = on_expired("logs".to_string(), Some(sender), num_expired.clone());
+
+        let record = |value| Record::new(None, vec![Field::Int(value)], Some(1));
+        on_expired(vec![record(0), record(1)]);
+
+        assert_eq!(num_expired.load(Ordering::SeqCst), 2);
+        let ops = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(ops.len(), 2);
+        for op in ops {
+            assert_eq!(op.typ, OperationType::Delete as i32);
+            assert_eq!(op.endpoint_name, "logs");
+        }
+    }
 }
@@ -118,7 +118,7 @@ mod tests {
     use dozer_types::ingestion_types::{GrpcConfig, GrpcConfigSchemas};
     use dozer_types::models::app_config::{
         default_app_buffer_size, default_app_max_map_size, default_cache_max_map_size,
-        default_commit_size, default_commit_timeout, Config,
+        default_cache_max_scanned_rows, default_commit_size, default_commit_timeout, Config,
     };
 
     use dozer_core::appsource::{AppSourceId, AppSourceMappings};
@@ -165,6 +165,8 @@ mod tests {
             app_buffer_size: Some(default_app_buffer_size()),
             commit_size: Some(default_commit_size()),
             commit_timeout: Some(default_commit_timeout()),
+            udfs: vec![],
+            cache_max_scanned_rows: Some(default_cache_max_scanned_rows()),
         }
     }
 
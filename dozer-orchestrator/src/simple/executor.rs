@@ -2,8 +2,10 @@ use dozer_api::grpc::internal::internal_pipeline_server::PipelineEventSenders;
 use dozer_cache::cache::CacheManagerOptions;
 use dozer_core::app::{App, AppPipeline};
 use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use dozer_sql::pipeline::udf::register_config_udfs;
 use dozer_types::models::api_endpoint::ApiEndpoint;
 use dozer_types::models::app_config::Config;
+use dozer_types::node::SourceStates;
 use dozer_types::types::{Operation, SourceSchema};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -59,6 +61,7 @@ impl Executor {
     ) -> Result<dozer_core::Dag<SchemaSQLContext>, OrchestrationError> {
         let grouped_connections = self.get_connection_groups();
 
+        register_config_udfs(&self.config.udfs).map_err(OrchestrationError::PipelineError)?;
         let mut pipeline = AppPipeline::new();
         let transform_response = statement_to_pipeline(&sql, &mut pipeline, None)
             .map_err(OrchestrationError::PipelineError)?;
@@ -143,6 +146,29 @@ impl Executor {
         Ok(exec)
     }
 
+    /// Initializes the pipeline state at `checkpoint` unless it has one, returning whether it did.
+    pub fn initialize_checkpoint(
+        &self,
+        cache_manager_options: CacheManagerOptions,
+        settings: CacheSinkSettings,
+        executor_options: &ExecutorOptions,
+        checkpoint: &SourceStates,
+    ) -> Result<bool, OrchestrationError> {
+        let builder = PipelineBuilder::new(
+            self.config.clone(),
+            self.api_endpoints.clone(),
+            self.pipeline_dir.clone(),
+        );
+
+        let dag = builder.build(None, cache_manager_options, settings)?;
+        Ok(DagExecutor::initialize_checkpoint(
+            dag,
+            self.pipeline_dir.clone(),
+            executor_options,
+            checkpoint,
+        )?)
+    }
+
     pub fn run_dag_executor(&self, dag_executor: DagExecutor) -> Result<(), OrchestrationError> {
         let join_handle = dag_executor.start(self.running.clone())?;
         join_handle.join().map_err(ExecutionError)
@@ -2,3 +2,4 @@ mod executor;
 pub mod orchestrator;
 pub use orchestrator::SimpleOrchestrator;
 mod helper;
+mod snapshot;
@@ -1,15 +1,18 @@
 use super::executor::Executor;
+use super::snapshot;
 use crate::console_helper::get_colored_text;
 use crate::errors::OrchestrationError;
 use crate::pipeline::{CacheSinkSettings, PipelineBuilder};
 use crate::simple::helper::validate_config;
 use crate::utils::{
     get_api_dir, get_api_security_config, get_app_grpc_config, get_cache_dir,
-    get_cache_max_map_size, get_executor_options, get_flags, get_grpc_config, get_pipeline_dir,
-    get_rest_config,
+    get_cache_max_map_size, get_cache_max_scanned_rows, get_executor_options, get_flags,
+    get_grpc_config, get_pipeline_dir, get_rest_config,
 };
 use crate::{flatten_join_handle, Orchestrator};
 use dozer_api::auth::{Access, Authorizer};
+use dozer_api::errors::ApiError;
+use dozer_api::generator::client::{ClientGenerator, ClientLanguage};
 use dozer_api::generator::protoc::generator::ProtoGenerator;
 use dozer_api::grpc::internal::internal_pipeline_client::InternalPipelineClient;
 use dozer_api::{
@@ -53,6 +56,7 @@ impl SimpleOrchestrator {
         let cache_manager_options = CacheManagerOptions {
             path: Some(get_cache_dir(&config)),
             max_size: get_cache_max_map_size(&config) as usize,
+            max_scanned_rows: get_cache_max_scanned_rows(&config) as usize,
             ..CacheManagerOptions::default()
         };
         Self {
@@ -105,18 +109,6 @@ impl Orchestrator for SimpleOrchestrator {
                 alias_redirected_receiver,
             ));
 
-            // Initialize API Server
-            let rest_config = get_rest_config(self.config.to_owned());
-            let security = get_api_security_config(self.config.to_owned());
-            let cache_endpoints_for_rest = cache_endpoints.clone();
-            let rest_handle = tokio::spawn(async move {
-                let api_server = rest::ApiServer::new(rest_config, security);
-                api_server
-                    .run(cache_endpoints_for_rest, tx)
-                    .await
-                    .map_err(OrchestrationError::ApiServerFailed)
-            });
-
             // Initialize `PipelineResponse` events.
             let flags = self.config.flags.clone().unwrap_or_default();
             let operation_receiver = if flags.dynamic {
@@ -130,6 +122,23 @@ impl Orchestrator for SimpleOrchestrator {
                 None
             };
 
+            // Initialize API Server
+            let rest_config = get_rest_config(self.config.to_owned());
+            let security = get_api_security_config(self.config.to_owned());
+            let cache_endpoints_for_rest = cache_endpoints.clone();
+            // GraphQL subscriptions follow the same flag as gRPC `on_event`.
+            let operation_receiver_for_rest = operation_receiver
+                .as_ref()
+                .filter(|_| flags.push_events)
+                .map(|receiver| receiver.resubscribe());
+            let rest_handle = tokio::spawn(async move {
+                let api_server = rest::ApiServer::new(rest_config, security);
+                api_server
+                    .run(cache_endpoints_for_rest, operation_receiver_for_rest, tx)
+                    .await
+                    .map_err(OrchestrationError::ApiServerFailed)
+            });
+
             // Initialize gRPC Server
             let api_dir = get_api_dir(&self.config);
             let grpc_config = get_grpc_config(self.config.to_owned());
@@ -231,6 +240,88 @@ impl Orchestrator for SimpleOrchestrator {
         ))
     }
 
+    fn export_cache(
+        &self,
+        endpoint_name: &str,
+        destination: &str,
+    ) -> Result<(), OrchestrationError> {
+        let cache_manager = LmdbCacheManager::new(self.cache_manager_options.clone())
+            .map_err(OrchestrationError::CacheInitFailed)?;
+        snapshot::export_cache(&cache_manager, endpoint_name, destination)
+    }
+
+    fn restore_cache(&self, source: &str) -> Result<(), OrchestrationError> {
+        // The cache manager is closed before the pipeline opens its own.
+        let checkpoint = {
+            let cache_manager = LmdbCacheManager::new(self.cache_manager_options.clone())
+                .map_err(OrchestrationError::CacheInitFailed)?;
+            snapshot::restore_cache(&cache_manager, source)?
+        };
+        if checkpoint.is_empty() {
+            return Ok(());
+        }
+
+        // Let the pipeline resume from the snapshot's checkpoint instead of rebuilding the cache.
+        let pipeline_home_dir = get_pipeline_dir(&self.config);
+        fs::create_dir_all(&pipeline_home_dir).map_err(|e| {
+            OrchestrationError::PipelineDirectoryInitFailed(
+                pipeline_home_dir.to_string_lossy().to_string(),
+                e,
+            )
+        })?;
+        let executor = Executor::new(
+            self.config.clone(),
+            self.config.endpoints.clone(),
+            Arc::new(AtomicBool::new(true)),
+            pipeline_home_dir,
+        );
+        let flags = get_flags(self.config.clone());
+        let api_security = get_api_security_config(self.config.clone());
+        let settings = CacheSinkSettings::new(get_api_dir(&self.config), flags, api_security);
+        if executor.initialize_checkpoint(
+            self.cache_manager_options.clone(),
+            settings,
+            &get_executor_options(&self.config),
+            &checkpoint,
+        )? {
+            info!("Initialized pipeline at checkpoint {:?}", checkpoint);
+        } else {
+            warn!("Pipeline already has a state, caches not at its checkpoint are rebuilt");
+        }
+        Ok(())
+    }
+
+    fn generate_client(
+        &self,
+        language: ClientLanguage,
+        output: Option<&str>,
+    ) -> Result<(), OrchestrationError> {
+        let folder_path = output.map_or_else(
+            || {
+                get_api_dir(&self.config)
+                    .join("client")
+                    .join(language.extension())
+            },
+            PathBuf::from,
+        );
+        fs::create_dir_all(&folder_path)
+            .map_err(|e| OrchestrationError::InternalError(Box::new(e)))?;
+
+        let cache_manager = LmdbCacheManager::new(self.cache_manager_options.clone())
+            .map_err(OrchestrationError::CacheInitFailed)?;
+        for endpoint in &self.config.endpoints {
+            let cache_endpoint = RoCacheEndpoint::new(&cache_manager, endpoint.clone())?;
+            let cache_reader = cache_endpoint.cache_reader();
+            let (schema, _) = cache_reader
+                .get_schema_and_indexes_by_name(&endpoint.name)
+                .map_err(OrchestrationError::CacheInitFailed)?;
+            let path = ClientGenerator::generate(&folder_path, language, endpoint, schema)
+                .map_err(ApiError::GenerationError)?;
+            info!("Generated client of {} at {:?}", endpoint.name, path);
+        }
+        Ok(())
+    }
+
     fn query(
         &self,
         sql: String,
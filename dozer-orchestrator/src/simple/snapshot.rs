@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use dozer_cache::cache::{LmdbCacheManager, RoCache};
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::log::info;
+use dozer_types::node::SourceStates;
+use futures::TryStreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::ObjectStore;
+use tempdir::TempDir;
+use tokio::io::AsyncWriteExt;
+use tokio::runtime::Runtime;
+
+use crate::errors::OrchestrationError;
+
+/// Writes a snapshot of the cache served for `endpoint_name` to `destination`, a file path or `s3://bucket/path`.
+pub fn export_cache(
+    cache_manager: &LmdbCacheManager,
+    endpoint_name: &str,
+    destination: &str,
+) -> Result<(), OrchestrationError> {
+    let write_snapshot = |path: &Path| -> Result<(), BoxedError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        cache_manager.export_snapshot(endpoint_name, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    };
+
+    match parse_s3_url(destination) {
+        Some((bucket, key)) => {
+            let temp_dir = TempDir::new("dozer-snapshot").map_err(snapshot_error)?;
+            let path = temp_dir.path().join("snapshot");
+            write_snapshot(&path).map_err(OrchestrationError::CacheSnapshotFailed)?;
+            Runtime::new()
+                .map_err(snapshot_error)?
+                .block_on(upload(bucket, key, &path))
+                .map_err(OrchestrationError::CacheSnapshotFailed)?;
+        }
+        None => write_snapshot(Path::new(destination))
+            .map_err(OrchestrationError::CacheSnapshotFailed)?,
+    }
+    info!("Exported cache of {} to {}", endpoint_name, destination);
+    Ok(())
+}
+
+/// Creates a cache from the snapshot at `source`, a file path or `s3://bucket/path`, and serves it for the endpoint it was exported from.
+///
+/// Returns the checkpoint of the restored cache.
+pub fn restore_cache(
+    cache_manager: &LmdbCacheManager,
+    source: &str,
+) -> Result<SourceStates, OrchestrationError> {
+    let read_snapshot = |path: &Path| -> Result<(String, SourceStates), BoxedError> {
+        let reader = BufReader::new(File::open(path)?);
+        let cache = cache_manager.restore_snapshot(reader)?;
+        Ok((cache.name().to_string(), cache.get_checkpoint()?))
+    };
+
+    let (name, checkpoint) = match parse_s3_url(source) {
+        Some((bucket, key)) => {
+            let temp_dir = TempDir::new("dozer-snapshot").map_err(snapshot_error)?;
+            let path = temp_dir.path().join("snapshot");
+            Runtime::new()
+                .map_err(snapshot_error)?
+                .block_on(download(bucket, key, &path))
+                .map_err(OrchestrationError::CacheSnapshotFailed)?;
+            read_snapshot(&path)
+        }
+        None => read_snapshot(Path::new(source)),
+    }
+    .map_err(OrchestrationError::CacheSnapshotFailed)?;
+    info!("Restored cache {} from {}", name, source);
+    Ok(checkpoint)
+}
+
+/// Splits `s3://bucket/path` into bucket and path.
+fn parse_s3_url(url: &str) -> Option<(&str, &str)> {
+    url.strip_prefix("s3://")?.split_once('/')
+}
+
+/// Credentials and region are read from the standard `AWS_*` environment variables.
+fn connect(bucket: &str) -> Result<AmazonS3, BoxedError> {
+    Ok(AmazonS3Builder::from_env()
+        .with_bucket_name(bucket)
+        .build()?)
+}
+
+async fn upload(bucket: &str, key: &str, path: &Path) -> Result<(), BoxedError> {
+    let store = connect(bucket)?;
+    let (_, mut writer) = store.put_multipart(&key.into()).await?;
+    let mut file = tokio::fs::File::open(path).await?;
+    tokio::io::copy(&mut file, &mut writer).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+async fn download(bucket: &str, key: &str, path: &Path) -> Result<(), BoxedError> {
+    let store = connect(bucket)?;
+    let mut stream = store.get(&key.into()).await?.into_stream();
+    let mut file = tokio::fs::File::create(path).await?;
+    while let Some(bytes) = stream.try_next().await? {
+        file.write_all(&bytes).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+fn snapshot_error(e: std::io::Error) -> OrchestrationError {
+    OrchestrationError::CacheSnapshotFailed(Box::new(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_cache, parse_s3_url, restore_cache};
+    use crate::test_utils;
+    use dozer_cache::cache::{CacheManager, LmdbCacheManager, RoCache};
+    use dozer_types::node::{NodeHandle, OpIdentifier, SourceStates};
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_parse_s3_url() {
+        assert_eq!(
+            parse_s3_url("s3://bucket/snapshots/films"),
+            Some(("bucket", "snapshots/films"))
+        );
+        assert_eq!(parse_s3_url("./snapshots/films"), None);
+        assert_eq!(parse_s3_url("s3://bucket"), None);
+    }
+
+    #[test]
+    fn test_restore_cache_returns_checkpoint() {
+        let cache_manager = LmdbCacheManager::new(Default::default()).unwrap();
+        let cache = cache_manager
+            .create_cache(vec![(
+                "films".to_string(),
+                test_utils::get_schema(),
+                vec![],
+            )])
+            .unwrap();
+        cache_manager.create_alias(cache.name(), "films").unwrap();
+        let checkpoint = [(
+            NodeHandle::new(Some(1), "films".to_string()),
+            OpIdentifier::new(42, 3),
+        )]
+        .into_iter()
+        .collect::<SourceStates>();
+        cache.commit(&checkpoint).unwrap();
+
+        let temp_dir = TempDir::new("dozer-snapshot").unwrap();
+        let path = temp_dir.path().join("films");
+        export_cache(&cache_manager, "films", path.to_str().unwrap()).unwrap();
+
+        let restored_manager = LmdbCacheManager::new(Default::default()).unwrap();
+        assert_eq!(
+            restore_cache(&restored_manager, path.to_str().unwrap()).unwrap(),
+            checkpoint
+        );
+        assert!(restored_manager.open_ro_cache("films").unwrap().is_some());
+    }
+}
@@ -51,8 +51,11 @@ pub fn init_endpoint() -> ApiEndpoint {
         path: "/films".to_string(),
         index: Some(ApiIndex {
             primary_key: vec!["film_id".to_string()],
+            full_text: vec![],
         }),
         table_name: "films".to_string(),
         // sql: Some("SELECT film_name FROM film WHERE 1=1".to_string()),
+        sinks: vec![],
+        retention: None,
     }
 }
@@ -4,7 +4,7 @@ use dozer_types::models::{
     api_security::ApiSecurity,
     app_config::{
         default_app_buffer_size, default_app_max_map_size, default_cache_max_map_size,
-        default_commit_size, default_commit_timeout, Config,
+        default_cache_max_scanned_rows, default_commit_size, default_commit_timeout, Config,
     },
 };
 use std::{
@@ -28,6 +28,12 @@ pub fn get_cache_max_map_size(config: &Config) -> u64 {
         .unwrap_or(default_cache_max_map_size())
 }
 
+pub fn get_cache_max_scanned_rows(config: &Config) -> u64 {
+    config
+        .cache_max_scanned_rows
+        .unwrap_or(default_cache_max_scanned_rows())
+}
+
 pub fn get_app_max_map_size(config: &Config) -> u64 {
     config
         .app_max_map_size
@@ -1,4 +1,6 @@
 pub mod aggregator;
+mod approx_count_distinct;
+mod approx_percentile;
 mod avg;
 mod count;
 pub mod factory;
@@ -1,3 +1,5 @@
+use crate::pipeline::aggregation::approx_count_distinct::ApproxCountDistinctAggregator;
+use crate::pipeline::aggregation::approx_percentile::ApproxPercentileAggregator;
 use crate::pipeline::aggregation::avg::AvgAggregator;
 use crate::pipeline::aggregation::count::CountAggregator;
 use crate::pipeline::aggregation::max::MaxAggregator;
@@ -9,11 +11,14 @@ use crate::pipeline::expression::aggregate::AggregateFunctionType;
 use crate::pipeline::expression::execution::Expression;
 use dozer_core::storage::common::Database;
 use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{Field, FieldType, Schema};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Aggregator {
+    ApproxCountDistinct,
+    ApproxPercentile(OrderedFloat<f64>),
     Avg,
     Count,
     Max,
@@ -21,66 +26,83 @@ pub enum Aggregator {
     Sum,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregationMeasure {
+    pub arg: Expression,
+    pub aggregator: Aggregator,
+    // Only the first occurrence of each value (per group) reaches the aggregator
+    pub distinct: bool,
+    // Records for which the filter does not evaluate to true are ignored by the aggregator
+    pub filter: Option<Expression>,
+}
+
 pub fn get_aggregator_from_aggregation_expression(
     e: &Expression,
     schema: &Schema,
-) -> Result<(Expression, Aggregator), PipelineError> {
+) -> Result<AggregationMeasure, PipelineError> {
     match e {
         Expression::AggregateFunction {
-            fun: AggregateFunctionType::Sum,
-            args,
-        } => Ok((
-            args.get(0)
-                .ok_or_else(|| {
-                    PipelineError::NotEnoughArguments(AggregateFunctionType::Sum.to_string())
-                })?
-                .clone(),
-            Aggregator::Sum,
-        )),
-        Expression::AggregateFunction {
-            fun: AggregateFunctionType::Min,
+            fun,
             args,
-        } => Ok((
-            args.get(0)
-                .ok_or_else(|| {
-                    PipelineError::NotEnoughArguments(AggregateFunctionType::Min.to_string())
-                })?
-                .clone(),
-            Aggregator::Min,
-        )),
-        Expression::AggregateFunction {
-            fun: AggregateFunctionType::Max,
-            args,
-        } => Ok((
-            args.get(0)
-                .ok_or_else(|| {
-                    PipelineError::NotEnoughArguments(AggregateFunctionType::Max.to_string())
-                })?
-                .clone(),
-            Aggregator::Max,
-        )),
-        Expression::AggregateFunction {
-            fun: AggregateFunctionType::Avg,
-            args,
-        } => Ok((
-            args.get(0)
-                .ok_or_else(|| {
-                    PipelineError::NotEnoughArguments(AggregateFunctionType::Avg.to_string())
-                })?
-                .clone(),
-            Aggregator::Avg,
-        )),
-        Expression::AggregateFunction {
-            fun: AggregateFunctionType::Count,
-            args: _,
-        } => Ok((Expression::Literal(Field::Int(0)), Aggregator::Count)),
+            distinct,
+            filter,
+        } => {
+            let aggregator = match fun {
+                AggregateFunctionType::ApproxCountDistinct => Aggregator::ApproxCountDistinct,
+                AggregateFunctionType::ApproxPercentile => {
+                    Aggregator::ApproxPercentile(get_percentile_argument(args, fun)?)
+                }
+                AggregateFunctionType::Avg => Aggregator::Avg,
+                AggregateFunctionType::Count => Aggregator::Count,
+                AggregateFunctionType::Max => Aggregator::Max,
+                AggregateFunctionType::Min => Aggregator::Min,
+                AggregateFunctionType::Sum => Aggregator::Sum,
+                _ => return Err(PipelineError::InvalidFunction(e.to_string(schema))),
+            };
+            let arg = match (&aggregator, *distinct) {
+                // COUNT(*) and COUNT(x) only need to know a record exists
+                (Aggregator::Count, false) => Expression::Literal(Field::Int(0)),
+                _ => args
+                    .get(0)
+                    .ok_or_else(|| PipelineError::NotEnoughArguments(fun.to_string()))?
+                    .clone(),
+            };
+            Ok(AggregationMeasure {
+                arg,
+                aggregator,
+                distinct: *distinct,
+                filter: filter.as_ref().map(|f| *f.clone()),
+            })
+        }
         _ => Err(PipelineError::InvalidFunction(e.to_string(schema))),
     }
 }
 
+fn get_percentile_argument(
+    args: &[Expression],
+    fun: &AggregateFunctionType,
+) -> Result<OrderedFloat<f64>, PipelineError> {
+    let percentile = match args.get(1) {
+        Some(Expression::Literal(Field::Float(f))) => f.0,
+        Some(Expression::Literal(Field::Int(i))) => *i as f64,
+        Some(e) => return Err(PipelineError::InvalidArgument(format!("{e:?}"))),
+        None => return Err(PipelineError::NotEnoughArguments(fun.to_string())),
+    };
+    if !(0.0..=1.0).contains(&percentile) {
+        return Err(PipelineError::InvalidFunctionArgument(
+            fun.to_string(),
+            Field::Float(OrderedFloat(percentile)),
+            1,
+        ));
+    }
+    Ok(OrderedFloat(percentile))
+}
+
 impl Display for Aggregator {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            Aggregator::ApproxCountDistinct => f.write_str("approx_count_distinct"),
+            Aggregator::ApproxPercentile(_) => f.write_str("approx_percentile"),
             Aggregator::Avg => f.write_str("avg"),
             Aggregator::Count => f.write_str("count"),
             Aggregator::Max => f.write_str("max"),
@@ -102,8 +124,22 @@ impl AggregationResult {
 }
 
 impl Aggregator {
+    /// Value of the aggregation before any record has been added to it
+    pub(crate) fn get_default_value(&self) -> Field {
+        match &self {
+            Aggregator::ApproxCountDistinct | Aggregator::Count => Field::Int(0),
+            Aggregator::ApproxPercentile(_)
+            | Aggregator::Avg
+            | Aggregator::Max
+            | Aggregator::Min
+            | Aggregator::Sum => Field::Null,
+        }
+    }
+
     pub(crate) fn _get_type(&self) -> u32 {
         match &self {
+            Aggregator::ApproxCountDistinct => ApproxCountDistinctAggregator::_get_type(),
+            Aggregator::ApproxPercentile(_) => ApproxPercentileAggregator::_get_type(),
             Aggregator::Avg => AvgAggregator::_get_type(),
             Aggregator::Count => CountAggregator::_get_type(),
             Aggregator::Max => MaxAggregator::_get_type(),
@@ -121,6 +157,12 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::insert(cur_state, new, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(p) => {
+                ApproxPercentileAggregator::insert(cur_state, new, p.0, return_type, txn)
+            }
             Aggregator::Avg => AvgAggregator::insert(cur_state, new, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::insert(cur_state, new, return_type, txn),
             Aggregator::Max => MaxAggregator::insert(cur_state, new, return_type, txn, agg_db),
@@ -139,6 +181,12 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::update(cur_state, old, new, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(p) => {
+                ApproxPercentileAggregator::update(cur_state, old, new, p.0, return_type, txn)
+            }
             Aggregator::Avg => AvgAggregator::update(cur_state, old, new, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::update(cur_state, old, new, return_type, txn),
             Aggregator::Max => MaxAggregator::update(cur_state, old, new, return_type, txn, agg_db),
@@ -156,6 +204,12 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::delete(cur_state, old, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(p) => {
+                ApproxPercentileAggregator::delete(cur_state, old, p.0, return_type, txn)
+            }
             Aggregator::Avg => AvgAggregator::delete(cur_state, old, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::delete(cur_state, old, return_type, txn),
             Aggregator::Max => MaxAggregator::delete(cur_state, old, return_type, txn, agg_db),
@@ -212,6 +266,16 @@ macro_rules! deserialize_decimal {
     };
 }
 
+#[macro_export]
+macro_rules! deserialize_bigint {
+    ($stmt:expr) => {
+        match $stmt {
+            Some(v) => DozerBigInt::from_bytes(v).unwrap(),
+            None => DozerBigInt::from(0_u64),
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! deserialize_u8 {
     ($stmt:expr) => {
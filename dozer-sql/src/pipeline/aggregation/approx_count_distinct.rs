@@ -0,0 +1,109 @@
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::UnsupportedSketchRetraction;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::types::{Field, FieldType};
+
+pub struct ApproxCountDistinctAggregator {}
+const AGGREGATOR_NAME: &str = "APPROX_COUNT_DISTINCT";
+
+// 2^12 one byte registers, ~1.6% standard error
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+
+impl ApproxCountDistinctAggregator {
+    const _AGGREGATOR_ID: u32 = 0x06;
+
+    pub(crate) fn _get_type() -> u32 {
+        ApproxCountDistinctAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        _return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut registers = Self::get_registers(cur_state);
+        if new != &Field::Null {
+            let hash = hash_bytes(&new.encode());
+            let index = (hash >> (64 - PRECISION)) as usize;
+            let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+            if rank > registers[index] {
+                registers[index] = rank;
+            }
+        }
+        Ok(AggregationResult::new(
+            Self::get_value(&registers),
+            Some(registers),
+        ))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        return_type: FieldType,
+        txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        // Registers only ever grow, so a value cannot be taken out of the sketch.
+        // An update that does not change the measured value is still fine.
+        if old != new {
+            return Err(UnsupportedSketchRetraction(AGGREGATOR_NAME.to_string()));
+        }
+        Self::insert(cur_state, new, return_type, txn)
+    }
+
+    pub(crate) fn delete(
+        _cur_state: Option<&[u8]>,
+        _old: &Field,
+        _return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Err(UnsupportedSketchRetraction(AGGREGATOR_NAME.to_string()))
+    }
+
+    pub(crate) fn get_value(registers: &[u8]) -> Field {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let mut sum = 0_f64;
+        let mut zeros = 0_usize;
+        for register in registers {
+            sum += 1.0 / (1_u64 << register) as f64;
+            if *register == 0 {
+                zeros += 1;
+            }
+        }
+
+        let estimate = alpha * m * m / sum;
+        let estimate = if estimate <= 2.5 * m && zeros > 0 {
+            // Small range correction (linear counting)
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        };
+        Field::Int(estimate.round() as i64)
+    }
+
+    fn get_registers(cur_state: Option<&[u8]>) -> Vec<u8> {
+        match cur_state {
+            Some(state) if state.len() == REGISTERS => Vec::from(state),
+            _ => vec![0_u8; REGISTERS],
+        }
+    }
+}
+
+// FNV-1a followed by a 64 bit finalizer. The sketch is persisted, so the
+// hash must not change between runs or versions.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
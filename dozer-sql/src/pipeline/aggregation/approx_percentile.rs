@@ -0,0 +1,234 @@
+use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::InvalidOperandType;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+
+pub struct ApproxPercentileAggregator {}
+const AGGREGATOR_NAME: &str = "APPROX_PERCENTILE";
+
+// Values are bucketed so that every estimate is within 1% of an actual value
+const RELATIVE_ACCURACY: f64 = 0.01;
+// Bounds the state of a sketch to (MAX_BINS * 12) bytes per sign
+const MAX_BINS: usize = 1024;
+
+impl ApproxPercentileAggregator {
+    const _AGGREGATOR_ID: u32 = 0x07;
+
+    pub(crate) fn _get_type() -> u32 {
+        ApproxPercentileAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = PercentileSketch::decode(cur_state)?;
+        if let Some(value) = Self::get_input(new, return_type)? {
+            sketch.add(value);
+        }
+        Ok(sketch.into_result(percentile))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = PercentileSketch::decode(cur_state)?;
+        if let Some(value) = Self::get_input(old, return_type)? {
+            sketch.remove(value);
+        }
+        if let Some(value) = Self::get_input(new, return_type)? {
+            sketch.add(value);
+        }
+        Ok(sketch.into_result(percentile))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = PercentileSketch::decode(cur_state)?;
+        if let Some(value) = Self::get_input(old, return_type)? {
+            sketch.remove(value);
+        }
+        Ok(sketch.into_result(percentile))
+    }
+
+    fn get_input(field: &Field, return_type: FieldType) -> Result<Option<f64>, PipelineError> {
+        match (return_type, field) {
+            (_, Field::Null) => Ok(None),
+            (FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt, _) => {
+                Ok(field.to_float())
+            }
+            _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
+        }
+    }
+}
+
+/// Logarithmically bucketed quantile sketch (DDSketch).
+///
+/// Buckets only hold counts, so unlike most quantile sketches a value can be
+/// removed again by decrementing the bucket it was added to.
+#[derive(Debug, Default)]
+struct PercentileSketch {
+    zero_count: u64,
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+}
+
+impl PercentileSketch {
+    fn gamma() -> f64 {
+        (1.0 + RELATIVE_ACCURACY) / (1.0 - RELATIVE_ACCURACY)
+    }
+
+    fn key(value: f64) -> i32 {
+        (value.ln() / Self::gamma().ln()).ceil() as i32
+    }
+
+    fn add(&mut self, value: f64) {
+        if value > 0.0 {
+            *self.positive.entry(Self::key(value)).or_insert(0) += 1;
+            Self::collapse(&mut self.positive);
+        } else if value < 0.0 {
+            *self.negative.entry(Self::key(-value)).or_insert(0) += 1;
+            Self::collapse(&mut self.negative);
+        } else {
+            self.zero_count += 1;
+        }
+    }
+
+    fn remove(&mut self, value: f64) {
+        if value > 0.0 {
+            Self::decrement(&mut self.positive, Self::key(value));
+        } else if value < 0.0 {
+            Self::decrement(&mut self.negative, Self::key(-value));
+        } else {
+            self.zero_count = self.zero_count.saturating_sub(1);
+        }
+    }
+
+    // Merges the buckets holding the smallest magnitudes once the sketch is full
+    fn collapse(bins: &mut BTreeMap<i32, u64>) {
+        while bins.len() > MAX_BINS {
+            let (_, count) = bins.pop_first().unwrap();
+            *bins.first_entry().unwrap().get_mut() += count;
+        }
+    }
+
+    fn decrement(bins: &mut BTreeMap<i32, u64>, key: i32) {
+        // Values of collapsed buckets were counted in the first remaining bucket
+        let key = match bins.range(key..).next() {
+            Some((k, _)) => *k,
+            None => return,
+        };
+        if let Some(count) = bins.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                bins.remove(&key);
+            }
+        }
+    }
+
+    fn count(&self) -> u64 {
+        self.zero_count + self.positive.values().sum::<u64>() + self.negative.values().sum::<u64>()
+    }
+
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        let rank = q * (count - 1) as f64;
+        let gamma = Self::gamma();
+        let bucket_value = |key: i32| 2.0 * gamma.powi(key) / (gamma + 1.0);
+
+        let mut seen = 0_u64;
+        for (key, n) in self.negative.iter().rev() {
+            seen += n;
+            if seen as f64 > rank {
+                return Some(-bucket_value(*key));
+            }
+        }
+        seen += self.zero_count;
+        if seen as f64 > rank {
+            return Some(0.0);
+        }
+        for (key, n) in self.positive.iter() {
+            seen += n;
+            if seen as f64 > rank {
+                return Some(bucket_value(*key));
+            }
+        }
+        None
+    }
+
+    fn into_result(self, percentile: f64) -> AggregationResult {
+        let value = match self.quantile(percentile) {
+            Some(v) => Field::Float(OrderedFloat(v)),
+            None => Field::Null,
+        };
+        AggregationResult::new(value, Some(self.encode()))
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(12 + (self.positive.len() + self.negative.len()) * 12);
+        buf.extend(self.zero_count.to_be_bytes());
+        for bins in [&self.positive, &self.negative] {
+            buf.extend((bins.len() as u16).to_be_bytes());
+            for (key, count) in bins {
+                buf.extend(key.to_be_bytes());
+                buf.extend(count.to_be_bytes());
+            }
+        }
+        buf
+    }
+
+    fn decode(state: Option<&[u8]>) -> Result<Self, PipelineError> {
+        let buf = match state {
+            Some(buf) if !buf.is_empty() => buf,
+            _ => return Ok(Self::default()),
+        };
+        let corrupted = || {
+            PipelineError::InvalidValue(format!(
+                "{AGGREGATOR_NAME} state of {} bytes is corrupted",
+                buf.len()
+            ))
+        };
+        let read = |offset: usize, len: usize| buf.get(offset..offset + len).ok_or_else(corrupted);
+
+        let mut sketch = Self {
+            zero_count: u64::from_be_bytes(deserialize!(read(0, 8)?)),
+            ..Default::default()
+        };
+        let mut offset = 8;
+        for bins in [&mut sketch.positive, &mut sketch.negative] {
+            let len = u16::from_be_bytes(deserialize!(read(offset, 2)?));
+            offset += 2;
+            for _ in 0..len {
+                let key = i32::from_be_bytes(deserialize!(read(offset, 4)?));
+                let count = u64::from_be_bytes(deserialize!(read(offset + 4, 8)?));
+                bins.insert(key, count);
+                offset += 12;
+            }
+        }
+        if offset != buf.len() {
+            return Err(corrupted());
+        }
+        Ok(sketch)
+    }
+}
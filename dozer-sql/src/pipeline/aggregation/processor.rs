@@ -1,8 +1,9 @@
 #![allow(clippy::too_many_arguments)]
 use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::{AggregationMeasure, AggregationResult};
 use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::execution::ExpressionExecutor;
-use crate::pipeline::{aggregation::aggregator::Aggregator, expression::execution::Expression};
 use dozer_core::channels::ProcessorChannelForwarder;
 use dozer_core::errors::ExecutionError;
 use dozer_core::errors::ExecutionError::InternalError;
@@ -41,21 +42,16 @@ impl<'a> AggregationData<'a> {
 #[derive(Debug)]
 pub struct AggregationProcessor {
     dimensions: Vec<Expression>,
-    measures: Vec<(Expression, Aggregator)>,
+    measures: Vec<AggregationMeasure>,
     projections: Vec<Expression>,
     pub db: Database,
     meta_db: Database,
     aggregators_db: Database,
+    distinct_db: Database,
     input_schema: Schema,
     aggregation_schema: Schema,
 }
 
-enum AggregatorOperation {
-    Insert,
-    Delete,
-    Update,
-}
-
 const AGG_VALUES_DATASET_ID: u16 = 0x0000_u16;
 const AGG_COUNT_DATASET_ID: u16 = 0x0001_u16;
 
@@ -70,7 +66,7 @@ impl AggregationProcessor {
         aggregation_schema: Schema,
         txn: &mut LmdbExclusiveTransaction,
     ) -> Result<Self, PipelineError> {
-        let mut aggregators: Vec<(Expression, Aggregator)> = Vec::new();
+        let mut aggregators: Vec<AggregationMeasure> = Vec::new();
         for measure in measures {
             aggregators.push(get_aggregator_from_aggregation_expression(
                 &measure,
@@ -85,6 +81,8 @@ impl AggregationProcessor {
             db: txn.create_database(Some("aggr"), Some(DatabaseFlags::empty()))?,
             meta_db: txn.create_database(Some("meta"), Some(DatabaseFlags::empty()))?,
             aggregators_db: txn.create_database(Some("aggr_data"), Some(DatabaseFlags::empty()))?,
+            distinct_db: txn
+                .create_database(Some("aggr_distinct"), Some(DatabaseFlags::empty()))?,
             input_schema,
             aggregation_schema,
         })
@@ -164,7 +162,6 @@ impl AggregationProcessor {
         inserted_record: Option<&Record>,
         out_rec_delete: &mut Vec<Field>,
         out_rec_insert: &mut Vec<Field>,
-        op: AggregatorOperation,
     ) -> Result<Vec<u8>, PipelineError> {
         // array holding the list of states for all measures
         let mut next_state = Vec::<u8>::new();
@@ -180,97 +177,54 @@ impl AggregationProcessor {
                 None => None,
             };
 
-            let (prefix, next_state_slice) = match op {
-                AggregatorOperation::Insert => {
-                    let inserted_field = measure
-                        .0
-                        .evaluate(inserted_record.unwrap(), &self.input_schema)?;
-                    if let Some(curr) = curr_agg_data {
-                        out_rec_delete.push(curr.value);
-                        let mut p_tx = PrefixTransaction::new(txn, curr.prefix);
-                        let r = measure.1.insert(
-                            curr.state,
-                            &inserted_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (curr.prefix, r)
-                    } else {
-                        let prefix = self.get_counter(txn)?;
-                        let mut p_tx = PrefixTransaction::new(txn, prefix);
-                        let r = measure.1.insert(
-                            None,
-                            &inserted_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (prefix, r)
-                    }
-                }
-                AggregatorOperation::Delete => {
-                    let deleted_field = measure
-                        .0
-                        .evaluate(deleted_record.unwrap(), &self.input_schema)?;
-                    if let Some(curr) = curr_agg_data {
-                        out_rec_delete.push(curr.value);
-                        let mut p_tx = PrefixTransaction::new(txn, curr.prefix);
-                        let r = measure.1.delete(
-                            curr.state,
-                            &deleted_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (curr.prefix, r)
-                    } else {
-                        let prefix = self.get_counter(txn)?;
-                        let mut p_tx = PrefixTransaction::new(txn, prefix);
-                        let r = measure.1.delete(
-                            None,
-                            &deleted_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (prefix, r)
-                    }
-                }
-                AggregatorOperation::Update => {
-                    let deleted_field = measure
-                        .0
-                        .evaluate(deleted_record.unwrap(), &self.input_schema)?;
-                    let updated_field = measure
-                        .0
-                        .evaluate(inserted_record.unwrap(), &self.input_schema)?;
-
-                    if let Some(curr) = curr_agg_data {
-                        out_rec_delete.push(curr.value);
-                        let mut p_tx = PrefixTransaction::new(txn, curr.prefix);
-                        let r = measure.1.update(
-                            curr.state,
-                            &deleted_field,
-                            &updated_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (curr.prefix, r)
-                    } else {
-                        let prefix = self.get_counter(txn)?;
-                        let mut p_tx = PrefixTransaction::new(txn, prefix);
-                        let r = measure.1.update(
-                            None,
-                            &deleted_field,
-                            &updated_field,
-                            measure.0.get_type(&self.input_schema)?.return_type,
-                            &mut p_tx,
-                            self.aggregators_db,
-                        )?;
-                        (prefix, r)
-                    }
+            let prefix = match curr_agg_data {
+                Some(ref curr) => {
+                    out_rec_delete.push(curr.value.clone());
+                    curr.prefix
                 }
+                None => self.get_counter(txn)?,
+            };
+
+            // FILTER and DISTINCT decide whether each side of the operation reaches the aggregator
+            let deleted_field = match deleted_record {
+                Some(old) => self.get_measure_input(txn, prefix, measure, old, true)?,
+                None => None,
+            };
+            let inserted_field = match inserted_record {
+                Some(new) => self.get_measure_input(txn, prefix, measure, new, false)?,
+                None => None,
+            };
+
+            let return_type = measure.arg.get_type(&self.input_schema)?.return_type;
+            let curr_state = curr_agg_data.as_ref().and_then(|curr| curr.state);
+            let mut p_tx = PrefixTransaction::new(txn, prefix);
+            let next_state_slice = match (deleted_field, inserted_field) {
+                (Some(old), Some(new)) => measure.aggregator.update(
+                    curr_state,
+                    &old,
+                    &new,
+                    return_type,
+                    &mut p_tx,
+                    self.aggregators_db,
+                )?,
+                (Some(old), None) => measure.aggregator.delete(
+                    curr_state,
+                    &old,
+                    return_type,
+                    &mut p_tx,
+                    self.aggregators_db,
+                )?,
+                (None, Some(new)) => measure.aggregator.insert(
+                    curr_state,
+                    &new,
+                    return_type,
+                    &mut p_tx,
+                    self.aggregators_db,
+                )?,
+                (None, None) => match curr_agg_data {
+                    Some(curr) => AggregationResult::new(curr.value, curr.state.map(Vec::from)),
+                    None => AggregationResult::new(measure.aggregator.get_default_value(), None),
+                },
             };
 
             next_state.extend(
@@ -282,6 +236,58 @@ impl AggregationProcessor {
         Ok(next_state)
     }
 
+    fn get_measure_input(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        prefix: u32,
+        measure: &AggregationMeasure,
+        record: &Record,
+        retract: bool,
+    ) -> Result<Option<Field>, PipelineError> {
+        if let Some(filter) = &measure.filter {
+            if filter.evaluate(record, &self.input_schema)? != Field::Boolean(true) {
+                return Ok(None);
+            }
+        }
+
+        let value = measure.arg.evaluate(record, &self.input_schema)?;
+        if !measure.distinct {
+            return Ok(Some(value));
+        }
+        if value == Field::Null {
+            return Ok(None);
+        }
+
+        // Keep the multiplicity of every distinct value so that deletes can tell
+        // when the last occurrence of a value leaves the group
+        let key = value.encode();
+        let mut p_tx = PrefixTransaction::new(txn, prefix);
+        let prev_count = match p_tx.get(self.distinct_db, key.as_slice())? {
+            Some(v) => u64::from_be_bytes(deserialize!(v)),
+            None => 0_u64,
+        };
+        if retract && prev_count == 0 {
+            return Ok(None);
+        }
+
+        let new_count = if retract {
+            prev_count - 1
+        } else {
+            prev_count + 1
+        };
+        if new_count > 0 {
+            p_tx.put(self.distinct_db, key.as_slice(), &new_count.to_be_bytes())?;
+        } else {
+            p_tx.del(self.distinct_db, key.as_slice(), None)?;
+        }
+
+        if (retract && new_count == 0) || (!retract && prev_count == 0) {
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn update_segment_count(
         &self,
         txn: &mut LmdbExclusiveTransaction,
@@ -339,7 +345,6 @@ impl AggregationProcessor {
             None,
             &mut out_rec_delete,
             &mut out_rec_insert,
-            AggregatorOperation::Delete,
         )?;
 
         let res = if prev_count == 1 {
@@ -389,7 +394,6 @@ impl AggregationProcessor {
             Some(new),
             &mut out_rec_delete,
             &mut out_rec_insert,
-            AggregatorOperation::Insert,
         )?;
 
         let res = if cur_state.is_none() {
@@ -427,7 +431,6 @@ impl AggregationProcessor {
             Some(new),
             &mut out_rec_delete,
             &mut out_rec_insert,
-            AggregatorOperation::Update,
         )?;
 
         let res = Operation::Update {
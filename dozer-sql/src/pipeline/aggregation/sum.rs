@@ -1,11 +1,14 @@
 use crate::pipeline::aggregation::aggregator::AggregationResult;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::PipelineError::InvalidOperandType;
-use crate::{deserialize, deserialize_decimal, deserialize_f64, deserialize_i64, deserialize_u64};
+use crate::{
+    deserialize, deserialize_bigint, deserialize_decimal, deserialize_f64, deserialize_i64,
+    deserialize_u64,
+};
 use dozer_core::storage::prefix_transaction::PrefixTransaction;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::{Field, FieldType};
+use dozer_types::types::{DozerBigInt, Field, FieldType};
 use std::ops::{Add, Sub};
 
 pub struct SumAggregator {}
@@ -61,6 +64,18 @@ impl SumAggregator {
                     Some(Vec::from(r_bytes)),
                 ))
             }
+            FieldType::BigInt => {
+                let prev = deserialize_bigint!(cur_state);
+                let curr = &Field::to_bigint(new).unwrap();
+                let r_bytes = prev
+                    .checked_add(curr)
+                    .ok_or_else(|| InvalidOperandType(AGGREGATOR_NAME.to_string()))?
+                    .to_bytes();
+                Ok(AggregationResult::new(
+                    Self::get_value(&r_bytes, return_type)?,
+                    Some(Vec::from(r_bytes)),
+                ))
+            }
             _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
         }
     }
@@ -113,6 +128,20 @@ impl SumAggregator {
                     Some(Vec::from(r_bytes)),
                 ))
             }
+            FieldType::BigInt => {
+                let prev = deserialize_bigint!(cur_state);
+                let curr_del = &Field::to_bigint(old).unwrap();
+                let curr_added = &Field::to_bigint(new).unwrap();
+                let r_bytes = prev
+                    .checked_sub(curr_del)
+                    .and_then(|v| v.checked_add(curr_added))
+                    .ok_or_else(|| InvalidOperandType(AGGREGATOR_NAME.to_string()))?
+                    .to_bytes();
+                Ok(AggregationResult::new(
+                    Self::get_value(&r_bytes, return_type)?,
+                    Some(Vec::from(r_bytes)),
+                ))
+            }
             _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
         }
     }
@@ -160,6 +189,18 @@ impl SumAggregator {
                     Some(Vec::from(r_bytes)),
                 ))
             }
+            FieldType::BigInt => {
+                let prev = deserialize_bigint!(cur_state);
+                let curr = &Field::to_bigint(old).unwrap();
+                let r_bytes = prev
+                    .checked_sub(curr)
+                    .ok_or_else(|| InvalidOperandType(AGGREGATOR_NAME.to_string()))?
+                    .to_bytes();
+                Ok(AggregationResult::new(
+                    Self::get_value(&r_bytes, return_type)?,
+                    Some(Vec::from(r_bytes)),
+                ))
+            }
             _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
         }
     }
@@ -172,6 +213,9 @@ impl SumAggregator {
             )))),
             FieldType::Int => Ok(Field::Int(i64::from_be_bytes(deserialize!(f)))),
             FieldType::UInt => Ok(Field::UInt(u64::from_be_bytes(deserialize!(f)))),
+            FieldType::BigInt => Ok(Field::BigInt(
+                DozerBigInt::from_bytes(f).map_err(|_| PipelineError::DataTypeMismatch)?,
+            )),
             _ => Err(PipelineError::DataTypeMismatch),
         }
     }
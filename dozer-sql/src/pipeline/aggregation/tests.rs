@@ -1,8 +1,12 @@
 #[cfg(test)]
+mod aggregation_approx_tests;
+#[cfg(test)]
 mod aggregation_avg_tests;
 #[cfg(test)]
 mod aggregation_count_tests;
 #[cfg(test)]
+mod aggregation_distinct_tests;
+#[cfg(test)]
 mod aggregation_max_tests;
 #[cfg(test)]
 mod aggregation_min_tests;
@@ -0,0 +1,174 @@
+use crate::output;
+use crate::pipeline::aggregation::aggregator::get_aggregator_from_aggregation_expression;
+use crate::pipeline::aggregation::approx_percentile::ApproxPercentileAggregator;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor, insert_exp, insert_field, update_exp,
+    update_field, FIELD_100_FLOAT, FIELD_100_INT, FIELD_1_INT, FIELD_200_FLOAT, FIELD_200_INT,
+    FIELD_2_INT, FIELD_50_FLOAT, FIELD_50_INT, ITALY,
+};
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::planner::projection::CommonPlanner;
+use crate::pipeline::tests::utils::get_select;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::{Float, Int};
+use dozer_types::types::Operation;
+use std::collections::HashMap;
+
+fn get_inserted_value(op: &Operation) -> f64 {
+    match op {
+        Operation::Insert { new } | Operation::Update { new, .. } => {
+            new.values[1].as_float().unwrap()
+        }
+        Operation::Delete { .. } => panic!("Unexpected delete"),
+    }
+}
+
+#[test]
+fn test_approx_count_distinct_aggregation_int() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        APPROX_COUNT_DISTINCT = 1
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        -------------
+        APPROX_COUNT_DISTINCT = 1
+    */
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 50
+        -------------
+        APPROX_COUNT_DISTINCT = 2
+    */
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Values cannot be removed from the sketch
+    inp = update_field(ITALY, ITALY, FIELD_50_INT, FIELD_200_INT);
+    assert!(processor
+        .aggregate(&mut tx.write(), processor.db, inp)
+        .is_err());
+
+    inp = delete_field(ITALY, FIELD_100_INT);
+    assert!(processor
+        .aggregate(&mut tx.write(), processor.db, inp)
+        .is_err());
+}
+
+#[test]
+fn test_approx_percentile_aggregation_float() {
+    let schema = init_input_schema(Float, "APPROX_PERCENTILE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100, 200 and 50 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 200.0
+        Italy, 50.0
+        -------------
+        APPROX_PERCENTILE = ~100.0
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    assert!((get_inserted_value(&out[0]) - 100.0).abs() <= 1.0);
+
+    inp = insert_field(ITALY, FIELD_200_FLOAT);
+    output!(processor, inp, tx);
+    inp = insert_field(ITALY, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    assert!((get_inserted_value(&out[0]) - 100.0).abs() <= 1.0);
+
+    // Delete 100, median falls back to the lower of the two values left
+    /*
+        Italy, 200.0
+        Italy, 50.0
+        -------------
+        APPROX_PERCENTILE = ~50.0
+    */
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    assert!((get_inserted_value(&out[0]) - 50.0).abs() <= 0.5);
+
+    // Update 50 -> 100
+    /*
+        Italy, 200.0
+        Italy, 100.0
+        -------------
+        APPROX_PERCENTILE = ~100.0
+    */
+    inp = update_field(ITALY, ITALY, FIELD_50_FLOAT, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    assert!((get_inserted_value(&out[0]) - 100.0).abs() <= 1.0);
+}
+
+#[test]
+fn test_approx_percentile_out_of_range() {
+    let schema = init_input_schema(Float, "APPROX_PERCENTILE");
+    let mut planner = CommonPlanner::new(schema.clone());
+    let statement =
+        get_select("SELECT Country, APPROX_PERCENTILE(Salary, 1.5) FROM Users GROUP BY Country")
+            .unwrap();
+    planner.plan(*statement).unwrap();
+
+    let result =
+        get_aggregator_from_aggregation_expression(&planner.aggregation_output[0], &schema);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_approx_percentile_corrupted_state() {
+    let schema = init_input_schema(Float, "APPROX_PERCENTILE");
+    let (_processor, tx) = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+    let mut txn = tx.write();
+    let mut prefix_txn = PrefixTransaction::new(&mut txn, 0);
+
+    // A zero count and one positive bin, without the bin
+    let mut state = vec![0_u8; 8];
+    state.extend(1_u16.to_be_bytes());
+    let result = ApproxPercentileAggregator::insert(
+        Some(&state),
+        FIELD_100_FLOAT,
+        0.5,
+        Float,
+        &mut prefix_txn,
+    );
+    assert!(matches!(result, Err(PipelineError::InvalidValue(_))));
+}
@@ -0,0 +1,262 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_0_INT, FIELD_100_INT, FIELD_150_INT, FIELD_1_INT,
+    FIELD_200_INT, FIELD_2_INT, FIELD_50_INT, ITALY,
+};
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+#[test]
+fn test_count_distinct_aggregation_int() {
+    let schema = init_input_schema(Int, "COUNT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, COUNT(DISTINCT Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        COUNT = 1
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        -------------
+        COUNT = 1
+    */
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 50
+        -------------
+        COUNT = 2
+    */
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Delete one 100
+    /*
+        Italy, 100
+        Italy, 50
+        -------------
+        COUNT = 2
+    */
+    inp = delete_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Delete the last 100
+    /*
+        Italy, 50
+        -------------
+        COUNT = 1
+    */
+    inp = delete_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 50 -> 200
+    /*
+        Italy, 200
+        -------------
+        COUNT = 1
+    */
+    inp = update_field(ITALY, ITALY, FIELD_50_INT, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Delete last record
+    /*
+        -------------
+        COUNT = 0
+    */
+    inp = delete_field(ITALY, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_sum_distinct_aggregation_int() {
+    let schema = init_input_schema(Int, "SUM");
+    let (processor, tx) = init_processor(
+        "SELECT Country, SUM(DISTINCT Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        SUM = 100
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_100_INT)];
+    assert_eq!(out, exp);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        -------------
+        SUM = 100
+    */
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_INT, FIELD_100_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 50
+        -------------
+        SUM = 150
+    */
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_INT, FIELD_150_INT)];
+    assert_eq!(out, exp);
+
+    // Delete one 100
+    /*
+        Italy, 100
+        Italy, 50
+        -------------
+        SUM = 150
+    */
+    inp = delete_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_150_INT, FIELD_150_INT)];
+    assert_eq!(out, exp);
+
+    // Delete 50
+    /*
+        Italy, 100
+        -------------
+        SUM = 100
+    */
+    inp = delete_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_150_INT, FIELD_100_INT)];
+    assert_eq!(out, exp);
+
+    // Delete last record
+    /*
+        -------------
+        SUM = 0
+    */
+    inp = delete_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_100_INT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_count_filter_aggregation_int() {
+    let schema = init_input_schema(Int, "COUNT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, COUNT(Salary) FILTER (WHERE Salary > 60) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 50
+        -------------
+        COUNT = 0
+    */
+    let mut inp = insert_field(ITALY, FIELD_50_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_0_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 50
+        Italy, 100
+        -------------
+        COUNT = 1
+    */
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_0_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 100 -> 50
+    /*
+        Italy, 50
+        Italy, 50
+        -------------
+        COUNT = 0
+    */
+    inp = update_field(ITALY, ITALY, FIELD_100_INT, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_0_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 50 -> 200
+    /*
+        Italy, 50
+        Italy, 200
+        -------------
+        COUNT = 1
+    */
+    inp = update_field(ITALY, ITALY, FIELD_50_INT, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_0_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Delete 50
+    /*
+        Italy, 200
+        -------------
+        COUNT = 1
+    */
+    inp = delete_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Delete last record
+    /*
+        -------------
+        COUNT = 0
+    */
+    inp = delete_field(ITALY, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
@@ -12,7 +12,7 @@ use dozer_core::DEFAULT_PORT_HANDLE;
 use sqlparser::ast::{Join, SetOperator, SetQuantifier, TableFactor, TableWithJoins};
 use sqlparser::{
     ast::{Query, Select, SetExpr, Statement},
-    dialect::AnsiDialect,
+    dialect::{AnsiDialect, Dialect},
     parser::Parser,
 };
 use std::collections::HashMap;
@@ -24,6 +24,24 @@ use super::product::factory::FromProcessorFactory;
 #[derive(Debug, Clone, Default)]
 pub struct SchemaSQLContext {}
 
+/// ANSI SQL, extended with `FILTER (WHERE ...)` clauses on aggregate functions.
+#[derive(Debug, Default)]
+pub struct DozerDialect {}
+
+impl Dialect for DozerDialect {
+    fn is_identifier_start(&self, ch: char) -> bool {
+        AnsiDialect {}.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        AnsiDialect {}.is_identifier_part(ch)
+    }
+
+    fn supports_filter_during_aggregation(&self) -> bool {
+        true
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OutputNodeInfo {
     // Name to connect in dag
@@ -63,7 +81,7 @@ pub fn statement_to_pipeline(
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     override_name: Option<String>,
 ) -> Result<QueryContext, PipelineError> {
-    let dialect = AnsiDialect {};
+    let dialect = DozerDialect {};
     let mut ctx = QueryContext::default();
 
     let ast = Parser::parse_sql(&dialect, sql).unwrap();
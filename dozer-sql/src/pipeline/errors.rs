@@ -63,6 +63,10 @@ pub enum PipelineError {
     InvalidCast { from: Field, to: FieldType },
     #[error("{0}() cannot be called frome here. Aggregations can only be used in SELECT and HAVING and cannot be nested within other aggregations.")]
     InvalidNestedAggregationFunction(String),
+    #[error("DISTINCT and FILTER can only be applied to aggregate functions, not {0}()")]
+    InvalidAggregateModifier(String),
+    #[error("{0}() only supports append-only sources: values cannot be removed from its sketch once added")]
+    UnsupportedSketchRetraction(String),
     #[error("Field {0} is not present in the source schema")]
     UnknownFieldIdentifier(String),
     #[error(
@@ -72,10 +76,19 @@ pub enum PipelineError {
     #[error("The field identifier {0} is invalid. Correct format is: [[connection.]source.]field")]
     IllegalFieldIdentifier(String),
 
+    #[error("Invalid UDF {0}(): {1}")]
+    InvalidUdf(String, String),
+    #[error("UDF {0}() returned {1}, expected a value of type {2}")]
+    InvalidUdfReturnValue(String, Field, FieldType),
+
     #[cfg(feature = "python")]
     #[error("Python Error: {0}")]
     PythonErr(dozer_types::pyo3::PyErr),
 
+    #[cfg(feature = "wasm")]
+    #[error("WASM Error: {0}")]
+    WasmErr(wasmtime::Error),
+
     // Error forwarding
     #[error(transparent)]
     InternalStorageError(#[from] StorageError),
@@ -105,6 +118,13 @@ impl From<dozer_types::pyo3::PyErr> for PipelineError {
     }
 }
 
+#[cfg(feature = "wasm")]
+impl From<wasmtime::Error> for PipelineError {
+    fn from(wasm_err: wasmtime::Error) -> Self {
+        PipelineError::WasmErr(wasm_err)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum UnsupportedSqlError {
     #[error("Recursive CTE is not supported. Please refer to the documentation(https://getdozer.io/docs/reference/sql/introduction) for more information. ")]
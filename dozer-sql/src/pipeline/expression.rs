@@ -10,6 +10,7 @@ pub mod logical;
 pub mod mathematical;
 pub mod operator;
 pub mod scalar;
+pub mod udf;
 
 #[cfg(feature = "python")]
 pub mod python_udf;
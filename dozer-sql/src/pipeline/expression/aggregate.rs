@@ -4,6 +4,8 @@ use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregateFunctionType {
+    ApproxCountDistinct,
+    ApproxPercentile,
     Avg,
     Count,
     Max,
@@ -17,6 +19,8 @@ pub enum AggregateFunctionType {
 impl AggregateFunctionType {
     pub(crate) fn new(name: &str) -> Result<AggregateFunctionType, PipelineError> {
         match name {
+            "approx_count_distinct" => Ok(AggregateFunctionType::ApproxCountDistinct),
+            "approx_percentile" => Ok(AggregateFunctionType::ApproxPercentile),
             "avg" => Ok(AggregateFunctionType::Avg),
             "count" => Ok(AggregateFunctionType::Count),
             "max" => Ok(AggregateFunctionType::Max),
@@ -33,6 +37,8 @@ impl AggregateFunctionType {
 impl Display for AggregateFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregateFunctionType::ApproxCountDistinct => f.write_str("APPROX_COUNT_DISTINCT"),
+            AggregateFunctionType::ApproxPercentile => f.write_str("APPROX_PERCENTILE"),
             AggregateFunctionType::Avg => f.write_str("AVG"),
             AggregateFunctionType::Count => f.write_str("COUNT"),
             AggregateFunctionType::Max => f.write_str("MAX"),
@@ -34,6 +34,16 @@ macro_rules! argv {
     };
 }
 
+/// Returns NULL from the calling function if the argument is NULL.
+#[macro_export]
+macro_rules! null_if_null {
+    ($field: expr) => {
+        if $field == dozer_types::types::Field::Null {
+            return Ok(dozer_types::types::Field::Null);
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! arg_str {
     ($field: expr, $fct: expr, $idx: expr) => {
@@ -1,19 +1,20 @@
 use dozer_types::{
     ordered_float::OrderedFloat,
-    types::{Field, FieldDefinition, Schema, SourceDefinition},
+    types::{DozerBigInt, Field, FieldDefinition, Schema, SourceDefinition},
 };
 use sqlparser::ast::{
-    BinaryOperator as SqlBinaryOperator, DataType, Expr as SqlExpr, Expr, Function, FunctionArg,
-    FunctionArgExpr, Ident, TrimWhereField, UnaryOperator as SqlUnaryOperator, Value as SqlValue,
+    BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr, Function,
+    FunctionArg, FunctionArgExpr, Ident, TrimWhereField, UnaryOperator as SqlUnaryOperator,
+    Value as SqlValue,
 };
 
 use crate::pipeline::errors::PipelineError::{
-    InvalidArgument, InvalidExpression, InvalidNestedAggregationFunction, InvalidOperator,
-    InvalidValue,
+    InvalidAggregateModifier, InvalidArgument, InvalidExpression, InvalidNestedAggregationFunction,
+    InvalidOperator, InvalidValue,
 };
 use crate::pipeline::errors::{PipelineError, SqlError};
 use crate::pipeline::expression::aggregate::AggregateFunctionType;
-use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::datetime::{parse_interval, DateTimeFunctionType, DateTimePart};
 
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::execution::Expression::{
@@ -23,6 +24,7 @@ use crate::pipeline::expression::geo::common::GeoFunctionType;
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::expression::scalar::string::TrimType;
+use crate::pipeline::expression::udf::get_udf;
 
 use super::cast::CastOperatorType;
 
@@ -75,6 +77,28 @@ impl ExpressionBuilder {
                 trim_what,
                 schema,
             ),
+            SqlExpr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+                ..
+            } => {
+                let mut args = vec![self.parse_sql_expression(parse_aggregations, expr, schema)?];
+                for arg in [substring_from, substring_for].into_iter().flatten() {
+                    args.push(self.parse_sql_expression(parse_aggregations, arg, schema)?);
+                }
+                Ok(ScalarFunction {
+                    fun: ScalarFunctionType::Substring,
+                    args,
+                })
+            }
+            SqlExpr::Position { expr, r#in } => Ok(ScalarFunction {
+                fun: ScalarFunctionType::Position,
+                args: vec![
+                    self.parse_sql_expression(parse_aggregations, expr, schema)?,
+                    self.parse_sql_expression(parse_aggregations, r#in, schema)?,
+                ],
+            }),
             SqlExpr::Identifier(ident) => Self::parse_sql_column(&[ident.clone()], schema),
             SqlExpr::CompoundIdentifier(ident) => Self::parse_sql_column(ident, schema),
             SqlExpr::Value(SqlValue::Number(n, _)) => Self::parse_sql_number(n),
@@ -90,8 +114,14 @@ impl ExpressionBuilder {
             }
             SqlExpr::Nested(expr) => self.parse_sql_expression(parse_aggregations, expr, schema),
             SqlExpr::Function(sql_function) => {
-                self.parse_sql_function(parse_aggregations, sql_function, schema)
+                self.parse_sql_function(parse_aggregations, sql_function, None, schema)
             }
+            SqlExpr::AggregateExpressionWithFilter { expr, filter } => match expr.as_ref() {
+                SqlExpr::Function(sql_function) => {
+                    self.parse_sql_function(parse_aggregations, sql_function, Some(filter), schema)
+                }
+                _ => Err(InvalidExpression(format!("{expression:?}"))),
+            },
             SqlExpr::Like {
                 negated,
                 expr,
@@ -108,6 +138,28 @@ impl ExpressionBuilder {
             SqlExpr::Cast { expr, data_type } => {
                 self.parse_sql_cast_operator(parse_aggregations, expr, data_type, schema)
             }
+            SqlExpr::Extract { field, expr } => Ok(DateTimeFunction {
+                fun: DateTimeFunctionType::Extract {
+                    field: DateTimePart::from_sql(field)?,
+                },
+                args: vec![self.parse_sql_expression(parse_aggregations, expr, schema)?],
+            }),
+            SqlExpr::AtTimeZone {
+                timestamp,
+                time_zone,
+            } => Ok(DateTimeFunction {
+                fun: DateTimeFunctionType::AtTimeZone,
+                args: vec![
+                    self.parse_sql_expression(parse_aggregations, timestamp, schema)?,
+                    Expression::Literal(Field::String(time_zone.clone())),
+                ],
+            }),
+            SqlExpr::Interval {
+                value,
+                leading_field,
+                last_field,
+                ..
+            } => Self::parse_sql_interval(value, leading_field, last_field),
             _ => Err(InvalidExpression(format!("{expression:?}"))),
         }
     }
@@ -231,10 +283,17 @@ impl ExpressionBuilder {
         &mut self,
         parse_aggregations: bool,
         sql_function: &Function,
+        filter: Option<&Expr>,
         schema: &Schema,
     ) -> Result<Expression, PipelineError> {
         let function_name = sql_function.name.to_string().to_lowercase();
 
+        if (sql_function.distinct || filter.is_some())
+            && AggregateFunctionType::new(function_name.as_str()).is_err()
+        {
+            return Err(InvalidAggregateModifier(function_name));
+        }
+
         #[cfg(feature = "python")]
         if function_name.starts_with("py_") {
             // The function is from python udf.
@@ -252,9 +311,15 @@ impl ExpressionBuilder {
                     let aggregation = self.parse_sql_function_arg(true, arg, schema)?;
                     arg_expr.push(aggregation);
                 }
+                let filter = match filter {
+                    Some(f) => Some(Box::new(self.parse_sql_expression(false, f, schema)?)),
+                    None => None,
+                };
                 let measure = Expression::AggregateFunction {
                     fun: aggr,
                     args: arg_expr,
+                    distinct: sql_function.distinct,
+                    filter,
                 };
                 let index = match self
                     .aggregations
@@ -294,17 +359,20 @@ impl ExpressionBuilder {
                             args: function_args.clone(),
                         }),
                         Err(_e) => match DateTimeFunctionType::new(function_name.as_str()) {
-                            Ok(dft) => {
-                                let arg = function_args
-                                    .first()
-                                    .ok_or(InvalidArgument(function_name))
-                                    .unwrap();
-                                Ok(DateTimeFunction {
-                                    fun: dft,
-                                    arg: Box::new(arg.clone()),
-                                })
-                            }
-                            Err(_err) => Err(InvalidNestedAggregationFunction(function_name)),
+                            Ok(dft) => Ok(DateTimeFunction {
+                                fun: dft,
+                                args: function_args.clone(),
+                            }),
+                            Err(_err) => match get_udf(function_name.as_str()) {
+                                Some(udf) => {
+                                    udf.validate_args(&function_args, schema)?;
+                                    Ok(Expression::UserDefinedFunction {
+                                        udf,
+                                        args: function_args,
+                                    })
+                                }
+                                None => Err(InvalidNestedAggregationFunction(function_name)),
+                            },
                         },
                     },
                 }
@@ -397,12 +465,16 @@ impl ExpressionBuilder {
     }
 
     fn parse_sql_number(n: &str) -> Result<Expression, PipelineError> {
-        match n.parse::<i64>() {
-            Ok(n) => Ok(Expression::Literal(Field::Int(n))),
-            Err(_) => match n.parse::<f64>() {
-                Ok(f) => Ok(Expression::Literal(Field::Float(OrderedFloat(f)))),
-                Err(_) => Err(InvalidValue(n.to_string())),
-            },
+        if let Ok(n) = n.parse::<i64>() {
+            return Ok(Expression::Literal(Field::Int(n)));
+        }
+        // Integers too large for an INT keep their precision
+        if let Ok(n) = n.parse::<DozerBigInt>() {
+            return Ok(Expression::Literal(Field::BigInt(n)));
+        }
+        match n.parse::<f64>() {
+            Ok(f) => Ok(Expression::Literal(Field::Float(OrderedFloat(f)))),
+            Err(_) => Err(InvalidValue(n.to_string())),
         }
     }
 
@@ -448,6 +520,7 @@ impl ExpressionBuilder {
             DataType::Integer(_) => CastOperatorType::Int,
             DataType::UnsignedInt(_) => CastOperatorType::UInt,
             DataType::UnsignedInteger(_) => CastOperatorType::UInt,
+            DataType::BigInt(_) => CastOperatorType::BigInt,
             DataType::Boolean => CastOperatorType::Boolean,
             DataType::Date => CastOperatorType::Date,
             DataType::Timestamp(..) => CastOperatorType::Timestamp,
@@ -472,6 +545,29 @@ impl ExpressionBuilder {
         })
     }
 
+    fn parse_sql_interval(
+        value: &Expr,
+        leading_field: &Option<DateTimeField>,
+        last_field: &Option<DateTimeField>,
+    ) -> Result<Expression, PipelineError> {
+        let value = match value {
+            SqlExpr::Value(SqlValue::SingleQuotedString(s) | SqlValue::Number(s, _)) => s,
+            _ => return Err(InvalidValue(format!("INTERVAL {value}"))),
+        };
+        if let Some(last_field) = last_field {
+            return Err(InvalidValue(format!(
+                "Unsupported INTERVAL '{value}' ... TO {last_field}"
+            )));
+        }
+        let unit = leading_field
+            .as_ref()
+            .map(DateTimePart::from_sql)
+            .transpose()?;
+        Ok(Expression::Literal(Field::Duration(parse_interval(
+            value, unit,
+        )?)))
+    }
+
     fn parse_sql_string(s: &str) -> Result<Expression, PipelineError> {
         Ok(Expression::Literal(Field::String(s.to_owned())))
     }
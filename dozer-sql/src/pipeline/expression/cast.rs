@@ -22,6 +22,7 @@ pub enum CastOperatorType {
     Timestamp,
     Date,
     Bson,
+    BigInt,
 }
 
 impl Display for CastOperatorType {
@@ -38,6 +39,7 @@ impl Display for CastOperatorType {
             CastOperatorType::Timestamp => f.write_str("CAST AS TIMESTAMP"),
             CastOperatorType::Date => f.write_str("CAST AS DATE"),
             CastOperatorType::Bson => f.write_str("CAST AS BSON"),
+            CastOperatorType::BigInt => f.write_str("CAST AS BIGINT"),
         }
     }
 }
@@ -161,6 +163,16 @@ impl CastOperatorType {
                     })
                 }
             }
+            CastOperatorType::BigInt => {
+                if let Some(value) = field.to_bigint() {
+                    Ok(Field::BigInt(value))
+                } else {
+                    Err(PipelineError::InvalidCast {
+                        from: field,
+                        to: FieldType::BigInt,
+                    })
+                }
+            }
         }
     }
 
@@ -171,15 +183,26 @@ impl CastOperatorType {
     ) -> Result<ExpressionType, PipelineError> {
         let (expected_input_type, return_type) = match self {
             CastOperatorType::UInt => (
-                vec![FieldType::Int, FieldType::String, FieldType::UInt],
+                vec![
+                    FieldType::BigInt,
+                    FieldType::Int,
+                    FieldType::String,
+                    FieldType::UInt,
+                ],
                 FieldType::UInt,
             ),
             CastOperatorType::Int => (
-                vec![FieldType::Int, FieldType::String, FieldType::UInt],
+                vec![
+                    FieldType::BigInt,
+                    FieldType::Int,
+                    FieldType::String,
+                    FieldType::UInt,
+                ],
                 FieldType::Int,
             ),
             CastOperatorType::Float => (
                 vec![
+                    FieldType::BigInt,
                     FieldType::Decimal,
                     FieldType::Float,
                     FieldType::Int,
@@ -190,6 +213,7 @@ impl CastOperatorType {
             ),
             CastOperatorType::Boolean => (
                 vec![
+                    FieldType::BigInt,
                     FieldType::Boolean,
                     FieldType::Decimal,
                     FieldType::Float,
@@ -200,6 +224,7 @@ impl CastOperatorType {
             ),
             CastOperatorType::String => (
                 vec![
+                    FieldType::BigInt,
                     FieldType::Binary,
                     FieldType::Boolean,
                     FieldType::Date,
@@ -215,6 +240,7 @@ impl CastOperatorType {
             ),
             CastOperatorType::Text => (
                 vec![
+                    FieldType::BigInt,
                     FieldType::Binary,
                     FieldType::Boolean,
                     FieldType::Date,
@@ -231,6 +257,7 @@ impl CastOperatorType {
             CastOperatorType::Binary => (vec![FieldType::Binary], FieldType::Binary),
             CastOperatorType::Decimal => (
                 vec![
+                    FieldType::BigInt,
                     FieldType::Decimal,
                     FieldType::Float,
                     FieldType::Int,
@@ -245,6 +272,15 @@ impl CastOperatorType {
             ),
             CastOperatorType::Date => (vec![FieldType::Date, FieldType::String], FieldType::Date),
             CastOperatorType::Bson => (vec![FieldType::Bson], FieldType::Bson),
+            CastOperatorType::BigInt => (
+                vec![
+                    FieldType::BigInt,
+                    FieldType::Int,
+                    FieldType::String,
+                    FieldType::UInt,
+                ],
+                FieldType::BigInt,
+            ),
         };
 
         let expression_type = validate_arg_type(arg, expected_input_type, schema, self, 0)?;
@@ -1,6 +1,6 @@
 use crate::pipeline::errors::PipelineError;
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::Schema;
+use dozer_types::types::{DozerBigInt, Schema};
 use dozer_types::{
     ordered_float::OrderedFloat,
     types::{Field, Record},
@@ -57,6 +57,11 @@ macro_rules! define_comparison {
                             ))?;
                         Ok(Field::Boolean($function(left_v_d, right_v)))
                     }
+                    // left: Int, right: BigInt
+                    Field::BigInt(right_v) => Ok(Field::Boolean($function(
+                        DozerBigInt::from(left_v),
+                        right_v,
+                    ))),
                     // left: Int, right: Null
                     Field::Null => Ok(Field::Boolean(false)),
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
@@ -99,6 +104,11 @@ macro_rules! define_comparison {
                         )))?;
                         Ok(Field::Boolean($function(left_v_d, right_v)))
                     }
+                    // left: UInt, right: BigInt
+                    Field::BigInt(right_v) => Ok(Field::Boolean($function(
+                        DozerBigInt::from(left_v),
+                        right_v,
+                    ))),
                     // left: UInt, right: Null
                     Field::Null => Ok(Field::Boolean(false)),
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
@@ -193,6 +203,24 @@ macro_rules! define_comparison {
                     Field::Null => Ok(Field::Boolean(false)),
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
                 },
+                Field::Duration(left_v) => match right_p {
+                    Field::Duration(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
+                    Field::Null => Ok(Field::Boolean(false)),
+                    _ => Err(PipelineError::InvalidOperandType($op.to_string())),
+                },
+                Field::BigInt(left_v) => match right_p {
+                    Field::BigInt(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
+                    Field::Int(right_v) => Ok(Field::Boolean($function(
+                        left_v,
+                        DozerBigInt::from(right_v),
+                    ))),
+                    Field::UInt(right_v) => Ok(Field::Boolean($function(
+                        left_v,
+                        DozerBigInt::from(right_v),
+                    ))),
+                    Field::Null => Ok(Field::Boolean(false)),
+                    _ => Err(PipelineError::InvalidOperandType($op.to_string())),
+                },
                 Field::Binary(_left_v) => Err(PipelineError::InvalidOperandType($op.to_string())),
 
                 _ => Err(PipelineError::InvalidOperandType($op.to_string())),
@@ -221,6 +249,7 @@ pub fn evaluate_lt(
         },
         Field::Int(left_v) => match right_p {
             Field::Int(right_v) => Ok(Field::Boolean(left_v < right_v)),
+            Field::BigInt(right_v) => Ok(Field::Boolean(DozerBigInt::from(left_v) < right_v)),
             Field::Float(right_v) => {
                 let left_v_f = OrderedFloat::<f64>::from_i64(left_v).unwrap();
                 Ok(Field::Boolean(left_v_f < right_v))
@@ -247,6 +276,17 @@ pub fn evaluate_lt(
             Field::Null => Ok(Field::Boolean(false)),
             _ => Err(PipelineError::InvalidOperandType("<".to_string())),
         },
+        Field::Duration(left_v) => match right_p {
+            Field::Duration(right_v) => Ok(Field::Boolean(left_v < right_v)),
+            Field::Null => Ok(Field::Boolean(false)),
+            _ => Err(PipelineError::InvalidOperandType("<".to_string())),
+        },
+        Field::BigInt(left_v) => match right_p {
+            Field::BigInt(right_v) => Ok(Field::Boolean(left_v < right_v)),
+            Field::Int(right_v) => Ok(Field::Boolean(left_v < DozerBigInt::from(right_v))),
+            Field::Null => Ok(Field::Boolean(false)),
+            _ => Err(PipelineError::InvalidOperandType("<".to_string())),
+        },
         Field::Binary(_left_v) => Err(PipelineError::InvalidOperandType("<".to_string())),
         _ => Err(PipelineError::InvalidOperandType("<".to_string())),
     }
@@ -272,6 +312,7 @@ pub fn evaluate_gt(
         },
         Field::Int(left_v) => match right_p {
             Field::Int(right_v) => Ok(Field::Boolean(left_v > right_v)),
+            Field::BigInt(right_v) => Ok(Field::Boolean(DozerBigInt::from(left_v) > right_v)),
             Field::Float(right_v) => {
                 let left_v_f = OrderedFloat::<f64>::from_i64(left_v).unwrap();
                 Ok(Field::Boolean(left_v_f > right_v))
@@ -298,6 +339,17 @@ pub fn evaluate_gt(
             Field::Null => Ok(Field::Boolean(false)),
             _ => Err(PipelineError::InvalidOperandType(">".to_string())),
         },
+        Field::Duration(left_v) => match right_p {
+            Field::Duration(right_v) => Ok(Field::Boolean(left_v > right_v)),
+            Field::Null => Ok(Field::Boolean(false)),
+            _ => Err(PipelineError::InvalidOperandType(">".to_string())),
+        },
+        Field::BigInt(left_v) => match right_p {
+            Field::BigInt(right_v) => Ok(Field::Boolean(left_v > right_v)),
+            Field::Int(right_v) => Ok(Field::Boolean(left_v > DozerBigInt::from(right_v))),
+            Field::Null => Ok(Field::Boolean(false)),
+            _ => Err(PipelineError::InvalidOperandType(">".to_string())),
+        },
         Field::Binary(_left_v) => Err(PipelineError::InvalidOperandType(">".to_string())),
 
         _ => Err(PipelineError::InvalidOperandType(">".to_string())),
@@ -1,58 +1,320 @@
+use crate::argv;
 use crate::pipeline::errors::PipelineError::{
-    InvalidFunctionArgument, InvalidFunctionArgumentType,
+    InvalidFunctionArgument, InvalidFunctionArgumentType, InvalidValue, TooManyArguments,
 };
 use crate::pipeline::errors::{FieldTypes, PipelineError};
 
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
-use dozer_types::chrono::Datelike;
-use dozer_types::types::{Field, FieldType, Record, Schema};
+use dozer_types::chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use dozer_types::types::{DozerDuration, Field, FieldType, Record, Schema, SourceDefinition};
 use num_traits::ToPrimitive;
-use std::fmt::{Display, Formatter};
+use sqlparser::ast::DateTimeField;
+use std::fmt::{Display, Formatter, Write};
+
+/// A part of a date or timestamp, as used by `EXTRACT`, `DATE_TRUNC` and `INTERVAL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum DateTimePart {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    DayOfWeek,
+    DayOfYear,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Epoch,
+}
+
+impl Display for DateTimePart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DateTimePart::Year => "YEAR",
+            DateTimePart::Quarter => "QUARTER",
+            DateTimePart::Month => "MONTH",
+            DateTimePart::Week => "WEEK",
+            DateTimePart::Day => "DAY",
+            DateTimePart::DayOfWeek => "DOW",
+            DateTimePart::DayOfYear => "DOY",
+            DateTimePart::Hour => "HOUR",
+            DateTimePart::Minute => "MINUTE",
+            DateTimePart::Second => "SECOND",
+            DateTimePart::Millisecond => "MILLISECOND",
+            DateTimePart::Microsecond => "MICROSECOND",
+            DateTimePart::Epoch => "EPOCH",
+        })
+    }
+}
+
+impl DateTimePart {
+    pub fn new(name: &str) -> Result<DateTimePart, PipelineError> {
+        match name.to_lowercase().as_str() {
+            "year" | "years" => Ok(DateTimePart::Year),
+            "quarter" => Ok(DateTimePart::Quarter),
+            "month" | "months" | "mon" | "mons" => Ok(DateTimePart::Month),
+            "week" | "weeks" => Ok(DateTimePart::Week),
+            "day" | "days" => Ok(DateTimePart::Day),
+            "dow" => Ok(DateTimePart::DayOfWeek),
+            "doy" => Ok(DateTimePart::DayOfYear),
+            "hour" | "hours" => Ok(DateTimePart::Hour),
+            "minute" | "minutes" | "min" | "mins" => Ok(DateTimePart::Minute),
+            "second" | "seconds" | "sec" | "secs" => Ok(DateTimePart::Second),
+            "millisecond" | "milliseconds" | "ms" => Ok(DateTimePart::Millisecond),
+            "microsecond" | "microseconds" | "us" => Ok(DateTimePart::Microsecond),
+            "epoch" => Ok(DateTimePart::Epoch),
+            _ => Err(InvalidValue(format!("Unknown date/time part '{name}'"))),
+        }
+    }
+
+    pub fn from_sql(field: &DateTimeField) -> Result<DateTimePart, PipelineError> {
+        match field {
+            DateTimeField::Year => Ok(DateTimePart::Year),
+            DateTimeField::Quarter => Ok(DateTimePart::Quarter),
+            DateTimeField::Month => Ok(DateTimePart::Month),
+            DateTimeField::Week => Ok(DateTimePart::Week),
+            DateTimeField::Day => Ok(DateTimePart::Day),
+            DateTimeField::Dow => Ok(DateTimePart::DayOfWeek),
+            DateTimeField::Doy => Ok(DateTimePart::DayOfYear),
+            DateTimeField::Hour => Ok(DateTimePart::Hour),
+            DateTimeField::Minute => Ok(DateTimePart::Minute),
+            DateTimeField::Second => Ok(DateTimePart::Second),
+            DateTimeField::Millisecond | DateTimeField::Milliseconds => {
+                Ok(DateTimePart::Millisecond)
+            }
+            DateTimeField::Microsecond | DateTimeField::Microseconds => {
+                Ok(DateTimePart::Microsecond)
+            }
+            DateTimeField::Epoch => Ok(DateTimePart::Epoch),
+            _ => Err(InvalidValue(format!("Unsupported date/time part {field}"))),
+        }
+    }
+
+    fn extract<T: Datelike + Timelike>(&self, v: &T) -> Option<i64> {
+        Some(match self {
+            DateTimePart::Year => v.year() as i64,
+            DateTimePart::Quarter => (v.month0() / 3 + 1) as i64,
+            DateTimePart::Month => v.month() as i64,
+            DateTimePart::Week => v.iso_week().week() as i64,
+            DateTimePart::Day => v.day() as i64,
+            DateTimePart::DayOfWeek => v.weekday().num_days_from_sunday() as i64,
+            DateTimePart::DayOfYear => v.ordinal() as i64,
+            DateTimePart::Hour => v.hour() as i64,
+            DateTimePart::Minute => v.minute() as i64,
+            DateTimePart::Second => v.second() as i64,
+            // Like Postgres, these include the full seconds
+            DateTimePart::Millisecond => {
+                v.second() as i64 * 1_000 + v.nanosecond() as i64 / 1_000_000
+            }
+            DateTimePart::Microsecond => {
+                v.second() as i64 * 1_000_000 + v.nanosecond() as i64 / 1_000
+            }
+            DateTimePart::Epoch => return None,
+        })
+    }
+
+    fn truncate(&self, v: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = v.date();
+        match self {
+            DateTimePart::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_hms_opt(0, 0, 0),
+            DateTimePart::Quarter => {
+                NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1)?
+                    .and_hms_opt(0, 0, 0)
+            }
+            DateTimePart::Month => {
+                NaiveDate::from_ymd_opt(date.year(), date.month(), 1)?.and_hms_opt(0, 0, 0)
+            }
+            DateTimePart::Week => (date
+                - Duration::days(date.weekday().num_days_from_monday() as i64))
+            .and_hms_opt(0, 0, 0),
+            DateTimePart::Day => date.and_hms_opt(0, 0, 0),
+            DateTimePart::Hour => date.and_hms_opt(v.hour(), 0, 0),
+            DateTimePart::Minute => date.and_hms_opt(v.hour(), v.minute(), 0),
+            DateTimePart::Second => date.and_hms_opt(v.hour(), v.minute(), v.second()),
+            DateTimePart::Millisecond => {
+                date.and_hms_milli_opt(v.hour(), v.minute(), v.second(), v.nanosecond() / 1_000_000)
+            }
+            DateTimePart::Microsecond => {
+                date.and_hms_micro_opt(v.hour(), v.minute(), v.second(), v.nanosecond() / 1_000)
+            }
+            DateTimePart::DayOfWeek | DateTimePart::DayOfYear | DateTimePart::Epoch => None,
+        }
+    }
+
+    fn to_duration(self, amount: i64) -> Option<DozerDuration> {
+        let nanos = |unit: i64| amount.checked_mul(unit).map(DozerDuration::from_nanos);
+        match self {
+            DateTimePart::Year => Some(DozerDuration::new(
+                i32::try_from(amount.checked_mul(12)?).ok()?,
+                0,
+                0,
+            )),
+            DateTimePart::Quarter => Some(DozerDuration::new(
+                i32::try_from(amount.checked_mul(3)?).ok()?,
+                0,
+                0,
+            )),
+            DateTimePart::Month => Some(DozerDuration::new(i32::try_from(amount).ok()?, 0, 0)),
+            DateTimePart::Week => Some(DozerDuration::new(
+                0,
+                i32::try_from(amount.checked_mul(7)?).ok()?,
+                0,
+            )),
+            DateTimePart::Day => Some(DozerDuration::new(0, i32::try_from(amount).ok()?, 0)),
+            DateTimePart::Hour => nanos(3_600_000_000_000),
+            DateTimePart::Minute => nanos(60_000_000_000),
+            DateTimePart::Second => nanos(1_000_000_000),
+            DateTimePart::Millisecond => nanos(1_000_000),
+            DateTimePart::Microsecond => nanos(1_000),
+            DateTimePart::DayOfWeek | DateTimePart::DayOfYear | DateTimePart::Epoch => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum DateTimeFunctionType {
     DayOfWeek,
+    Extract {
+        field: DateTimePart,
+    },
+    DateTrunc,
+    /// Processing time: the wall clock time (UTC) at which the operation is evaluated.
+    /// It is not stable across re-processing, so results depending on it are not
+    /// reproducible and an update may retract a different value than was emitted.
+    Now,
+    ToTimestamp,
+    ToChar,
+    AtTimeZone,
 }
 
 impl Display for DateTimeFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             DateTimeFunctionType::DayOfWeek => f.write_str("DAY_OF_WEEK"),
+            DateTimeFunctionType::Extract { field } => write!(f, "EXTRACT_{field}"),
+            DateTimeFunctionType::DateTrunc => f.write_str("DATE_TRUNC"),
+            DateTimeFunctionType::Now => f.write_str("NOW"),
+            DateTimeFunctionType::ToTimestamp => f.write_str("TO_TIMESTAMP"),
+            DateTimeFunctionType::ToChar => f.write_str("TO_CHAR"),
+            DateTimeFunctionType::AtTimeZone => f.write_str("AT_TIME_ZONE"),
         }
     }
 }
 
+fn validate_arg(
+    function: &DateTimeFunctionType,
+    args: &[Expression],
+    idx: usize,
+    expected: &[FieldType],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_t = argv!(args, idx, function)?.get_type(schema)?;
+    if !expected.contains(&arg_t.return_type) {
+        return Err(InvalidFunctionArgumentType(
+            function.to_string(),
+            arg_t.return_type,
+            FieldTypes::new(expected.to_vec()),
+            idx,
+        ));
+    }
+    Ok(arg_t)
+}
+
 pub(crate) fn get_datetime_function_type(
     function: &DateTimeFunctionType,
-    arg: &Expression,
+    args: &[Expression],
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
-    match function {
-        DateTimeFunctionType::DayOfWeek => {
-            let return_type = arg.get_type(schema)?.return_type;
-            if return_type != FieldType::Date && return_type != FieldType::Timestamp {
-                return Err(InvalidFunctionArgumentType(
-                    DateTimeFunctionType::DayOfWeek.to_string(),
-                    return_type,
-                    FieldTypes::new(vec![FieldType::Date, FieldType::Timestamp]),
+    let max_args = match function {
+        DateTimeFunctionType::Now => 0,
+        DateTimeFunctionType::DayOfWeek | DateTimeFunctionType::Extract { .. } => 1,
+        DateTimeFunctionType::DateTrunc
+        | DateTimeFunctionType::ToTimestamp
+        | DateTimeFunctionType::ToChar
+        | DateTimeFunctionType::AtTimeZone => 2,
+    };
+    if args.len() > max_args {
+        return Err(TooManyArguments(function.to_string()));
+    }
+
+    let strings = [FieldType::String, FieldType::Text];
+    let dates = [FieldType::Date, FieldType::Timestamp];
+    let return_type = match function {
+        DateTimeFunctionType::DayOfWeek | DateTimeFunctionType::Extract { .. } => {
+            validate_arg(function, args, 0, &dates, schema)?;
+            FieldType::Int
+        }
+        DateTimeFunctionType::DateTrunc => {
+            validate_arg(function, args, 0, &strings, schema)?;
+            if let Some(Expression::Literal(unit)) = args.first() {
+                get_trunc_unit(function, unit.clone())?;
+            }
+            validate_arg(function, args, 1, &dates, schema)?.return_type
+        }
+        DateTimeFunctionType::Now => FieldType::Timestamp,
+        DateTimeFunctionType::ToTimestamp => {
+            if args.len() == 2 {
+                validate_arg(function, args, 0, &strings, schema)?;
+                validate_arg(function, args, 1, &strings, schema)?;
+            } else {
+                validate_arg(
+                    function,
+                    args,
                     0,
-                ));
+                    &[
+                        FieldType::String,
+                        FieldType::Text,
+                        FieldType::Int,
+                        FieldType::UInt,
+                        FieldType::Float,
+                        FieldType::Decimal,
+                    ],
+                    schema,
+                )?;
             }
-
-            Ok(ExpressionType::new(
-                FieldType::Int,
-                false,
-                dozer_types::types::SourceDefinition::Dynamic,
-                false,
-            ))
+            FieldType::Timestamp
+        }
+        DateTimeFunctionType::ToChar => {
+            validate_arg(function, args, 0, &dates, schema)?;
+            validate_arg(function, args, 1, &strings, schema)?;
+            FieldType::String
+        }
+        DateTimeFunctionType::AtTimeZone => {
+            validate_arg(function, args, 0, &[FieldType::Timestamp], schema)?;
+            validate_arg(function, args, 1, &strings, schema)?;
+            if let Some(Expression::Literal(zone)) = args.get(1) {
+                get_time_zone(function, zone.clone())?;
+            }
+            FieldType::Timestamp
         }
+    };
+
+    // Every function returns NULL if any argument is NULL
+    let mut nullable = false;
+    for arg in args {
+        nullable |= arg.get_type(schema)?.nullable;
     }
+    Ok(ExpressionType::new(
+        return_type,
+        nullable,
+        SourceDefinition::Dynamic,
+        false,
+    ))
 }
 
 impl DateTimeFunctionType {
     pub fn new(name: &str) -> Result<DateTimeFunctionType, PipelineError> {
         match name {
             "day_of_week" => Ok(DateTimeFunctionType::DayOfWeek),
+            "date_trunc" => Ok(DateTimeFunctionType::DateTrunc),
+            "now" | "current_timestamp" => Ok(DateTimeFunctionType::Now),
+            "to_timestamp" => Ok(DateTimeFunctionType::ToTimestamp),
+            "to_char" => Ok(DateTimeFunctionType::ToChar),
+            "timezone" => Ok(DateTimeFunctionType::AtTimeZone),
             _ => Err(PipelineError::InvalidFunction(name.to_string())),
         }
     }
@@ -60,11 +322,29 @@ impl DateTimeFunctionType {
     pub(crate) fn evaluate(
         &self,
         schema: &Schema,
-        arg: &Expression,
+        args: &[Expression],
         record: &Record,
     ) -> Result<Field, PipelineError> {
         match self {
-            DateTimeFunctionType::DayOfWeek => evaluate_day_of_week(schema, arg, record),
+            DateTimeFunctionType::DayOfWeek => {
+                evaluate_day_of_week(schema, argv!(args, 0, self)?, record)
+            }
+            DateTimeFunctionType::Extract { field } => {
+                evaluate_extract(schema, self, *field, argv!(args, 0, self)?, record)
+            }
+            DateTimeFunctionType::DateTrunc => {
+                evaluate_date_trunc(schema, argv!(args, 0, self)?, argv!(args, 1, self)?, record)
+            }
+            DateTimeFunctionType::Now => Ok(Field::Timestamp(DateTime::from(Utc::now()))),
+            DateTimeFunctionType::ToTimestamp => {
+                evaluate_to_timestamp(schema, argv!(args, 0, self)?, args.get(1), record)
+            }
+            DateTimeFunctionType::ToChar => {
+                evaluate_to_char(schema, argv!(args, 0, self)?, argv!(args, 1, self)?, record)
+            }
+            DateTimeFunctionType::AtTimeZone => {
+                evaluate_at_time_zone(schema, argv!(args, 0, self)?, argv!(args, 1, self)?, record)
+            }
         }
     }
 }
@@ -76,6 +356,7 @@ pub(crate) fn evaluate_day_of_week(
 ) -> Result<Field, PipelineError> {
     let value = arg.evaluate(record, schema)?;
     match value {
+        Field::Null => Ok(Field::Null),
         Field::Date(d) => Ok(Field::Int(
             d.weekday().num_days_from_monday().to_i64().ok_or(
                 PipelineError::InvalidOperandType(format!("Unable to cast date {d} to i64")),
@@ -94,6 +375,292 @@ pub(crate) fn evaluate_day_of_week(
     }
 }
 
+pub(crate) fn evaluate_extract(
+    schema: &Schema,
+    function: &DateTimeFunctionType,
+    field: DateTimePart,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = arg.evaluate(record, schema)?;
+    if value == Field::Null {
+        return Ok(Field::Null);
+    }
+    let result = match (&value, field) {
+        (Field::Timestamp(ts), DateTimePart::Epoch) => Some(ts.timestamp()),
+        (Field::Timestamp(ts), _) => field.extract(ts),
+        (Field::Date(d), DateTimePart::Epoch) => d.and_hms_opt(0, 0, 0).map(|d| d.timestamp()),
+        (Field::Date(d), _) => d.and_hms_opt(0, 0, 0).and_then(|d| field.extract(&d)),
+        _ => None,
+    };
+    match result {
+        Some(v) => Ok(Field::Int(v)),
+        None => Err(InvalidFunctionArgument(function.to_string(), value, 0)),
+    }
+}
+
+fn get_trunc_unit(
+    function: &DateTimeFunctionType,
+    unit: Field,
+) -> Result<DateTimePart, PipelineError> {
+    match unit.to_string().map(|u| DateTimePart::new(&u)) {
+        Some(Ok(DateTimePart::DayOfWeek | DateTimePart::DayOfYear | DateTimePart::Epoch))
+        | Some(Err(_))
+        | None => Err(InvalidFunctionArgument(function.to_string(), unit, 0)),
+        Some(Ok(part)) => Ok(part),
+    }
+}
+
+pub(crate) fn evaluate_date_trunc(
+    schema: &Schema,
+    unit: &Expression,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let function = DateTimeFunctionType::DateTrunc;
+    let unit = unit.evaluate(record, schema)?;
+    let value = arg.evaluate(record, schema)?;
+    if unit == Field::Null || value == Field::Null {
+        return Ok(Field::Null);
+    }
+    let part = get_trunc_unit(&function, unit)?;
+    let result = match &value {
+        Field::Timestamp(ts) => part
+            .truncate(ts.naive_local())
+            .and_then(|t| ts.offset().from_local_datetime(&t).single())
+            .map(Field::Timestamp),
+        Field::Date(d) => d
+            .and_hms_opt(0, 0, 0)
+            .and_then(|d| part.truncate(d))
+            .map(|d| Field::Date(d.date())),
+        _ => None,
+    };
+    result.ok_or_else(|| InvalidFunctionArgument(function.to_string(), value, 1))
+}
+
+/// Parses a string with a `strftime` style format. The format does not need to contain
+/// an offset, in which case the value is taken as UTC. A format without a time yields
+/// midnight UTC.
+pub(crate) fn evaluate_to_timestamp(
+    schema: &Schema,
+    arg: &Expression,
+    format: Option<&Expression>,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let function = DateTimeFunctionType::ToTimestamp;
+    let value = arg.evaluate(record, schema)?;
+    let utc = |t: NaiveDateTime| DateTime::<FixedOffset>::from(Utc.from_utc_datetime(&t));
+
+    let format = format
+        .map(|format| format.evaluate(record, schema))
+        .transpose()?;
+    if value == Field::Null || format == Some(Field::Null) {
+        return Ok(Field::Null);
+    }
+
+    let result = match (&value, format) {
+        (Field::String(s) | Field::Text(s), Some(format)) => {
+            let format = format
+                .to_string()
+                .ok_or_else(|| InvalidFunctionArgument(function.to_string(), format, 1))?;
+            DateTime::parse_from_str(s, &format)
+                .ok()
+                .or_else(|| NaiveDateTime::parse_from_str(s, &format).ok().map(utc))
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s, &format)
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                        .map(utc)
+                })
+        }
+        (Field::String(s) | Field::Text(s), None) => DateTime::parse_from_rfc3339(s).ok(),
+        // Numbers are seconds since the Unix epoch
+        (Field::Int(_) | Field::UInt(_) | Field::Float(_) | Field::Decimal(_), None) => value
+            .to_float()
+            .and_then(|secs| (secs * 1000.0).round().to_i64())
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+            .map(DateTime::from),
+        _ => None,
+    };
+    result
+        .map(Field::Timestamp)
+        .ok_or_else(|| InvalidFunctionArgument(function.to_string(), value, 0))
+}
+
+/// Formats a date or timestamp with a `strftime` style format, e.g. `%Y-%m-%d %H:%M`.
+pub(crate) fn evaluate_to_char(
+    schema: &Schema,
+    arg: &Expression,
+    format: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let function = DateTimeFunctionType::ToChar;
+    let value = arg.evaluate(record, schema)?;
+    let format_field = format.evaluate(record, schema)?;
+    if value == Field::Null || format_field == Field::Null {
+        return Ok(Field::Null);
+    }
+    let ts = match &value {
+        Field::Timestamp(ts) => *ts,
+        Field::Date(d) => d
+            .and_hms_opt(0, 0, 0)
+            .map(|d| DateTime::from(Utc.from_utc_datetime(&d)))
+            .ok_or_else(|| InvalidFunctionArgument(function.to_string(), value.clone(), 0))?,
+        _ => return Err(InvalidFunctionArgument(function.to_string(), value, 0)),
+    };
+
+    let format = format_field
+        .to_string()
+        .ok_or_else(|| InvalidFunctionArgument(function.to_string(), format_field.clone(), 1))?;
+    // Formatting fails on invalid specifiers, which `to_string()` would turn into a panic
+    let mut result = String::new();
+    write!(result, "{}", ts.format(&format))
+        .map_err(|_| InvalidFunctionArgument(function.to_string(), format_field, 1))?;
+    Ok(Field::String(result))
+}
+
+/// Only fixed offsets are supported: `UTC`, `Z`, `+05:30`, `-08`, `UTC+2` etc.
+/// IANA zone names like `Europe/Paris` are rejected, because their offset depends on
+/// daylight saving time.
+fn get_time_zone(
+    function: &DateTimeFunctionType,
+    zone: Field,
+) -> Result<FixedOffset, PipelineError> {
+    zone.to_string()
+        .and_then(|z| parse_fixed_offset(&z))
+        .ok_or_else(|| InvalidFunctionArgument(function.to_string(), zone, 1))
+}
+
+fn parse_fixed_offset(zone: &str) -> Option<FixedOffset> {
+    let zone = zone.trim().to_uppercase();
+    let offset = zone
+        .strip_prefix("UTC")
+        .or_else(|| zone.strip_prefix("GMT"))
+        .unwrap_or(&zone);
+    if offset.is_empty() || offset == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, offset) = match (offset.strip_prefix('+'), offset.strip_prefix('-')) {
+        (Some(rest), _) => (1, rest),
+        (_, Some(rest)) => (-1, rest),
+        _ => return None,
+    };
+    if !offset.is_ascii() {
+        return None;
+    }
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+    let hours = hours.parse::<i32>().ok().filter(|h| *h <= 14)?;
+    let minutes = minutes.parse::<i32>().ok().filter(|m| *m < 60)?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Returns the same instant, expressed in the given time zone.
+pub(crate) fn evaluate_at_time_zone(
+    schema: &Schema,
+    arg: &Expression,
+    zone: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let function = DateTimeFunctionType::AtTimeZone;
+    let zone = zone.evaluate(record, schema)?;
+    let value = arg.evaluate(record, schema)?;
+    if zone == Field::Null || value == Field::Null {
+        return Ok(Field::Null);
+    }
+    let offset = get_time_zone(&function, zone)?;
+    match value {
+        Field::Timestamp(ts) => Ok(Field::Timestamp(ts.with_timezone(&offset))),
+        value => Err(InvalidFunctionArgument(function.to_string(), value, 0)),
+    }
+}
+
+/// Parses the string of an `INTERVAL` literal. With a unit, the string is a single
+/// amount of that unit (`INTERVAL '3' DAY`), otherwise a list of amounts and units,
+/// optionally followed by a time (`INTERVAL '1 day 2 hours'`, `INTERVAL '1 day 02:30:00'`).
+pub(crate) fn parse_interval(
+    value: &str,
+    unit: Option<DateTimePart>,
+) -> Result<DozerDuration, PipelineError> {
+    let error = || InvalidValue(format!("Invalid interval '{value}'"));
+    if value.trim().is_empty() {
+        return Err(error());
+    }
+
+    let mut duration = DozerDuration::default();
+    let mut tokens = value.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        let component = if token.contains(':') {
+            parse_interval_time(token)
+        } else {
+            let unit = match unit {
+                Some(unit) => Some(unit),
+                None => tokens.next().map(DateTimePart::new).transpose()?,
+            };
+            match unit.ok_or_else(error)? {
+                DateTimePart::Second => parse_interval_seconds(token),
+                unit => token
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|amount| unit.to_duration(amount)),
+            }
+        };
+        duration = component
+            .and_then(|c| duration.checked_add(&c))
+            .ok_or_else(error)?;
+    }
+    Ok(duration)
+}
+
+fn parse_interval_seconds(value: &str) -> Option<DozerDuration> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = format!("{fraction:0<9}").parse::<i64>().ok()?;
+    let nanos = if seconds.starts_with('-') {
+        -nanos
+    } else {
+        nanos
+    };
+    seconds
+        .parse::<i64>()
+        .ok()?
+        .checked_mul(1_000_000_000)?
+        .checked_add(nanos)
+        .map(DozerDuration::from_nanos)
+}
+
+fn parse_interval_time(value: &str) -> Option<DozerDuration> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, value),
+    };
+    let mut parts = value.split(':');
+    let hours = parts.next()?.parse::<i64>().ok()?;
+    let minutes = parts.next()?.parse::<i64>().ok()?;
+    let seconds = match parts.next() {
+        Some(s) => parse_interval_seconds(s)?.nanos,
+        None => 0,
+    };
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60_000_000_000 || seconds < 0 {
+        return None;
+    }
+    let nanos = hours
+        .checked_mul(3_600_000_000_000)?
+        .checked_add(minutes * 60_000_000_000 + seconds)?;
+    Some(DozerDuration::from_nanos(if negative {
+        -nanos
+    } else {
+        nanos
+    }))
+}
+
 #[test]
 fn test_day_of_week() {
     let row = Record::new(None, vec![], None);
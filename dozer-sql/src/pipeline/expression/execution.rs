@@ -8,7 +8,9 @@ use crate::pipeline::expression::geo::common::{get_geo_function_type, GeoFunctio
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
 use crate::pipeline::expression::scalar::common::{get_scalar_function_type, ScalarFunctionType};
 use crate::pipeline::expression::scalar::string::{evaluate_trim, validate_trim, TrimType};
+use crate::pipeline::expression::udf::ScalarUdf;
 use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+use std::sync::Arc;
 
 use super::aggregate::AggregateFunctionType;
 use super::cast::CastOperatorType;
@@ -39,11 +41,13 @@ pub enum Expression {
     },
     DateTimeFunction {
         fun: DateTimeFunctionType,
-        arg: Box<Expression>,
+        args: Vec<Expression>,
     },
     AggregateFunction {
         fun: AggregateFunctionType,
         args: Vec<Expression>,
+        distinct: bool,
+        filter: Option<Box<Expression>>,
     },
     Cast {
         arg: Box<Expression>,
@@ -59,6 +63,10 @@ pub enum Expression {
         pattern: Box<Expression>,
         escape: Option<char>,
     },
+    UserDefinedFunction {
+        udf: Arc<ScalarUdf>,
+        args: Vec<Expression>,
+    },
     #[cfg(feature = "python")]
     PythonUDF {
         name: String,
@@ -97,9 +105,15 @@ impl Expression {
                         .as_str()
                     + ")"
             }
-            Expression::AggregateFunction { fun, args } => {
+            Expression::AggregateFunction {
+                fun,
+                args,
+                distinct,
+                filter,
+            } => {
                 fun.to_string()
                     + "("
+                    + if *distinct { "DISTINCT " } else { "" }
                     + args
                         .iter()
                         .map(|e| e.to_string(schema))
@@ -107,6 +121,23 @@ impl Expression {
                         .join(",")
                         .as_str()
                     + ")"
+                    + if let Some(f) = filter {
+                        " FILTER (WHERE ".to_string() + f.to_string(schema).as_str() + ")"
+                    } else {
+                        "".to_string()
+                    }
+                    .as_str()
+            }
+            Expression::UserDefinedFunction { udf, args } => {
+                udf.name().to_string()
+                    + "("
+                    + args
+                        .iter()
+                        .map(|expr| expr.to_string(schema))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                        .as_str()
+                    + ")"
             }
             #[cfg(feature = "python")]
             Expression::PythonUDF { name, args, .. } => {
@@ -161,9 +192,25 @@ impl Expression {
                         .as_str()
                     + ")"
             }
-            Expression::DateTimeFunction { fun, arg } => {
-                fun.to_string() + "(" + arg.to_string(schema).as_str() + ")"
-            }
+            Expression::DateTimeFunction { fun, args } => match (fun, args.as_slice()) {
+                (DateTimeFunctionType::Extract { field }, [arg]) => {
+                    format!("EXTRACT({field} FROM {})", arg.to_string(schema))
+                }
+                (DateTimeFunctionType::AtTimeZone, [arg, zone]) => {
+                    arg.to_string(schema) + " AT TIME ZONE " + zone.to_string(schema).as_str()
+                }
+                _ => {
+                    fun.to_string()
+                        + "("
+                        + args
+                            .iter()
+                            .map(|e| e.to_string(schema))
+                            .collect::<Vec<String>>()
+                            .join(",")
+                            .as_str()
+                        + ")"
+                }
+            },
         }
     }
 }
@@ -214,6 +261,7 @@ impl ExpressionExecutor for Expression {
                 right,
             } => operator.evaluate(schema, left, right, record),
             Expression::ScalarFunction { fun, args } => fun.evaluate(schema, args, record),
+            Expression::UserDefinedFunction { udf, args } => udf.evaluate(schema, args, record),
 
             #[cfg(feature = "python")]
             Expression::PythonUDF {
@@ -226,11 +274,9 @@ impl ExpressionExecutor for Expression {
                 evaluate_py_udf(schema, name, args, return_type, record)
             }
             Expression::UnaryOperator { operator, arg } => operator.evaluate(schema, arg, record),
-            Expression::AggregateFunction { fun, args: _ } => {
-                Err(PipelineError::InvalidExpression(format!(
-                    "Aggregate Function {fun:?} should not be executed at this point"
-                )))
-            }
+            Expression::AggregateFunction { fun, .. } => Err(PipelineError::InvalidExpression(
+                format!("Aggregate Function {fun:?} should not be executed at this point"),
+            )),
             Expression::Trim { typ, what, arg } => evaluate_trim(schema, arg, what, typ, record),
             Expression::Like {
                 arg,
@@ -239,7 +285,7 @@ impl ExpressionExecutor for Expression {
             } => evaluate_like(schema, arg, pattern, *escape, record),
             Expression::Cast { arg, typ } => typ.evaluate(schema, arg, record),
             Expression::GeoFunction { fun, args } => fun.evaluate(schema, args, record),
-            Expression::DateTimeFunction { fun, arg } => fun.evaluate(schema, arg, record),
+            Expression::DateTimeFunction { fun, args } => fun.evaluate(schema, args, record),
         }
     }
 
@@ -278,7 +324,7 @@ impl ExpressionExecutor for Expression {
                 right,
             } => get_binary_operator_type(left, operator, right, schema),
             Expression::ScalarFunction { fun, args } => get_scalar_function_type(fun, args, schema),
-            Expression::AggregateFunction { fun, args } => {
+            Expression::AggregateFunction { fun, args, .. } => {
                 get_aggregate_function_type(fun, args, schema)
             }
             Expression::Trim {
@@ -293,9 +339,10 @@ impl ExpressionExecutor for Expression {
             } => get_like_operator_type(arg, pattern, schema),
             Expression::Cast { arg, typ } => typ.get_return_type(schema, arg),
             Expression::GeoFunction { fun, args } => get_geo_function_type(fun, args, schema),
-            Expression::DateTimeFunction { fun, arg } => {
-                get_datetime_function_type(fun, arg, schema)
+            Expression::DateTimeFunction { fun, args } => {
+                get_datetime_function_type(fun, args, schema)
             }
+            Expression::UserDefinedFunction { udf, .. } => Ok(udf.get_type()),
             #[cfg(feature = "python")]
             Expression::PythonUDF { return_type, .. } => Ok(ExpressionType::new(
                 *return_type,
@@ -307,7 +354,7 @@ impl ExpressionExecutor for Expression {
     }
 }
 
-fn get_field_type(field: &Field) -> Option<FieldType> {
+pub(crate) fn get_field_type(field: &Field) -> Option<FieldType> {
     match field {
         Field::Int(_) => Some(FieldType::Int),
         Field::Float(_) => Some(FieldType::Float),
@@ -322,6 +369,8 @@ fn get_field_type(field: &Field) -> Option<FieldType> {
         Field::Text(_) => Some(FieldType::Text),
         Field::Date(_) => Some(FieldType::Date),
         Field::Point(_) => Some(FieldType::Point),
+        Field::Duration(_) => Some(FieldType::Duration),
+        Field::BigInt(_) => Some(FieldType::BigInt),
     }
 }
 
@@ -400,12 +449,61 @@ fn get_binary_operator_type(
                     SourceDefinition::Dynamic,
                     false,
                 )),
-                (FieldType::Timestamp, FieldType::Timestamp) => Ok(ExpressionType::new(
-                    FieldType::Int,
+                (FieldType::Timestamp, FieldType::Timestamp)
+                    if operator == &BinaryOperatorType::Sub =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Duration,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Timestamp | FieldType::Date, FieldType::Duration)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Timestamp,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Duration, FieldType::Timestamp | FieldType::Date)
+                    if operator == &BinaryOperatorType::Add =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Timestamp,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::Duration, FieldType::Duration)
+                    if matches!(operator, BinaryOperatorType::Add | BinaryOperatorType::Sub) =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::Duration,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (FieldType::BigInt, FieldType::BigInt | FieldType::Int | FieldType::UInt)
+                | (FieldType::Int | FieldType::UInt, FieldType::BigInt) => Ok(ExpressionType::new(
+                    FieldType::BigInt,
                     false,
                     SourceDefinition::Dynamic,
                     false,
                 )),
+                (FieldType::BigInt, FieldType::Float) | (FieldType::Float, FieldType::BigInt) => {
+                    Ok(ExpressionType::new(
+                        FieldType::Float,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
                 (FieldType::Int, FieldType::Float)
                 | (FieldType::Float, FieldType::Int)
                 | (FieldType::Float, FieldType::Float) => Ok(ExpressionType::new(
@@ -423,6 +521,24 @@ fn get_binary_operator_type(
         }
         BinaryOperatorType::Div | BinaryOperatorType::Mod => {
             match (left_field_type.return_type, right_field_type.return_type) {
+                (FieldType::BigInt, FieldType::BigInt | FieldType::Int | FieldType::UInt)
+                | (FieldType::Int | FieldType::UInt, FieldType::BigInt)
+                    if operator == &BinaryOperatorType::Mod =>
+                {
+                    Ok(ExpressionType::new(
+                        FieldType::BigInt,
+                        false,
+                        SourceDefinition::Dynamic,
+                        false,
+                    ))
+                }
+                (
+                    FieldType::BigInt,
+                    FieldType::BigInt | FieldType::Int | FieldType::UInt | FieldType::Float,
+                )
+                | (FieldType::Int | FieldType::UInt | FieldType::Float, FieldType::BigInt) => Ok(
+                    ExpressionType::new(FieldType::Float, false, SourceDefinition::Dynamic, false),
+                ),
                 (FieldType::Int, FieldType::Float)
                 | (FieldType::Float, FieldType::Int)
                 | (FieldType::Float, FieldType::Float) => Ok(ExpressionType::new(
@@ -447,6 +563,18 @@ fn get_aggregate_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
     match function {
+        AggregateFunctionType::ApproxCountDistinct => Ok(ExpressionType::new(
+            FieldType::Int,
+            false,
+            SourceDefinition::Dynamic,
+            false,
+        )),
+        AggregateFunctionType::ApproxPercentile => Ok(ExpressionType::new(
+            FieldType::Float,
+            true,
+            SourceDefinition::Dynamic,
+            false,
+        )),
         AggregateFunctionType::Avg => Ok(ExpressionType::new(
             FieldType::Float,
             false,
@@ -1,7 +1,8 @@
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_types::chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
 use dozer_types::rust_decimal::Decimal;
-use dozer_types::types::Schema;
+use dozer_types::types::{DozerBigInt, DozerDuration, Schema};
 use dozer_types::{
     ordered_float::OrderedFloat,
     types::{Field, Record},
@@ -23,12 +24,33 @@ macro_rules! define_math_operator {
             match left_p {
                 Field::Timestamp(left_v) => match right_p {
                     Field::Timestamp(right_v) => match $op {
-                        "-" => {
-                            let duration = left_v - right_v;
-                            Ok(Field::Int(duration.num_milliseconds()))
-                        }
+                        "-" => DozerDuration::between(left_v, right_v)
+                            .map(Field::Duration)
+                            .ok_or(PipelineError::InvalidOperandType($op.to_string())),
                         _ => Err(PipelineError::InvalidOperandType($op.to_string())),
                     },
+                    Field::Duration(right_v) => shift_timestamp(left_v, right_v, $op),
+                    _ => Err(PipelineError::InvalidOperandType($op.to_string())),
+                },
+                Field::Date(left_v) => match right_p {
+                    Field::Duration(right_v) => {
+                        shift_timestamp(date_to_timestamp(left_v, $op)?, right_v, $op)
+                    }
+                    _ => Err(PipelineError::InvalidOperandType($op.to_string())),
+                },
+                Field::Duration(left_v) => match (right_p, $op) {
+                    (Field::Duration(right_v), "+") => left_v
+                        .checked_add(&right_v)
+                        .map(Field::Duration)
+                        .ok_or(PipelineError::InvalidOperandType($op.to_string())),
+                    (Field::Duration(right_v), "-") => left_v
+                        .checked_sub(&right_v)
+                        .map(Field::Duration)
+                        .ok_or(PipelineError::InvalidOperandType($op.to_string())),
+                    (Field::Timestamp(right_v), "+") => shift_timestamp(right_v, left_v, $op),
+                    (Field::Date(right_v), "+") => {
+                        shift_timestamp(date_to_timestamp(right_v, $op)?, left_v, $op)
+                    }
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
                 },
                 Field::Float(left_v) => match right_p {
@@ -54,6 +76,10 @@ macro_rules! define_math_operator {
                     ))),
                     // left: Float, right: Float
                     Field::Float(right_v) => Ok(Field::Float($fct(left_v, right_v))),
+                    // left: Float, right: BigInt
+                    Field::BigInt(right_v) => {
+                        Ok(Field::Float($fct(left_v, OrderedFloat(right_v.to_f64()))))
+                    }
                     // left: Float, right: Decimal
                     Field::Decimal(right_v) => Ok(Field::Decimal($fct(
                         Decimal::from_f64(left_v.to_f64().ok_or(
@@ -137,6 +163,10 @@ macro_rules! define_math_operator {
                         ))?,
                         right_v,
                     ))),
+                    // left: Int, right: BigInt
+                    Field::BigInt(right_v) => {
+                        evaluate_big_int(DozerBigInt::from(left_v), right_v, $op)
+                    }
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
                 },
                 Field::UInt(left_v) => match right_p {
@@ -213,6 +243,23 @@ macro_rules! define_math_operator {
                         )))?,
                         right_v,
                     ))),
+                    // left: UInt, right: BigInt
+                    Field::BigInt(right_v) => {
+                        evaluate_big_int(DozerBigInt::from(left_v), right_v, $op)
+                    }
+                    _ => Err(PipelineError::InvalidOperandType($op.to_string())),
+                },
+                Field::BigInt(left_v) => match right_p {
+                    Field::BigInt(right_v) => evaluate_big_int(left_v, right_v, $op),
+                    Field::Int(right_v) => {
+                        evaluate_big_int(left_v, DozerBigInt::from(right_v), $op)
+                    }
+                    Field::UInt(right_v) => {
+                        evaluate_big_int(left_v, DozerBigInt::from(right_v), $op)
+                    }
+                    Field::Float(right_v) => {
+                        Ok(Field::Float($fct(OrderedFloat(left_v.to_f64()), right_v)))
+                    }
                     _ => Err(PipelineError::InvalidOperandType($op.to_string())),
                 },
                 Field::Decimal(left_v) => {
@@ -261,6 +308,49 @@ macro_rules! define_math_operator {
     };
 }
 
+/// Big integers never overflow silently. Division returns a float, like it does for other integers.
+fn evaluate_big_int(
+    left: DozerBigInt,
+    right: DozerBigInt,
+    op: &str,
+) -> Result<Field, PipelineError> {
+    let result = match op {
+        "+" => left.checked_add(&right),
+        "-" => left.checked_sub(&right),
+        "*" => left.checked_mul(&right),
+        "/" => return Ok(Field::Float(OrderedFloat(left.to_f64() / right.to_f64()))),
+        "%" => left.checked_rem(&right),
+        _ => None,
+    };
+    result
+        .map(Field::BigInt)
+        .ok_or_else(|| PipelineError::InvalidOperandType(op.to_string()))
+}
+
+/// Moves a timestamp forward (`+`) or backward (`-`) by a duration.
+fn shift_timestamp(
+    ts: DateTime<FixedOffset>,
+    duration: DozerDuration,
+    op: &str,
+) -> Result<Field, PipelineError> {
+    let duration = match op {
+        "+" => Some(duration),
+        "-" => duration.checked_neg(),
+        _ => None,
+    };
+    duration
+        .and_then(|d| d.add_to(ts))
+        .map(Field::Timestamp)
+        .ok_or_else(|| PipelineError::InvalidOperandType(op.to_string()))
+}
+
+/// Dates take part in interval arithmetic as midnight UTC, like in Postgres.
+fn date_to_timestamp(date: NaiveDate, op: &str) -> Result<DateTime<FixedOffset>, PipelineError> {
+    date.and_hms_opt(0, 0, 0)
+        .map(|d| DateTime::from(Utc.from_utc_datetime(&d)))
+        .ok_or_else(|| PipelineError::InvalidOperandType(op.to_string()))
+}
+
 define_math_operator!(evaluate_add, "+", |a, b| { a + b }, 0);
 define_math_operator!(evaluate_sub, "-", |a, b| { a - b }, 0);
 define_math_operator!(evaluate_mul, "*", |a, b| { a * b }, 0);
@@ -278,6 +368,8 @@ pub fn evaluate_plus(
         Field::Int(v) => Ok(Field::Int(v)),
         Field::Float(v) => Ok(Field::Float(v)),
         Field::Decimal(v) => Ok(Field::Decimal(v)),
+        Field::Duration(v) => Ok(Field::Duration(v)),
+        Field::BigInt(v) => Ok(Field::BigInt(v)),
         _ => Err(PipelineError::InvalidOperandType("+".to_string())),
     }
 }
@@ -293,6 +385,14 @@ pub fn evaluate_minus(
         Field::Int(v) => Ok(Field::Int(-v)),
         Field::Float(v) => Ok(Field::Float(-v)),
         Field::Decimal(v) => Ok(Field::Decimal(v.neg())),
+        Field::Duration(v) => v
+            .checked_neg()
+            .map(Field::Duration)
+            .ok_or_else(|| PipelineError::InvalidOperandType("-".to_string())),
+        Field::BigInt(v) => v
+            .checked_neg()
+            .map(Field::BigInt)
+            .ok_or_else(|| PipelineError::InvalidOperandType("-".to_string())),
         _ => Err(PipelineError::InvalidOperandType("-".to_string())),
     }
 }
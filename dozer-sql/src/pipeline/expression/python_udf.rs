@@ -58,6 +58,8 @@ pub fn evaluate_py_udf(
             | FieldType::Date
             | FieldType::Timestamp
             | FieldType::Point
+            | FieldType::Duration
+            | FieldType::BigInt
             | FieldType::Bson => {
                 return Err(UnsupportedSqlError(GenericError(
                     "Unsupported return type for python udf".to_string(),
@@ -3,7 +3,10 @@ use crate::pipeline::errors::PipelineError;
 use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
 use crate::pipeline::expression::scalar::number::{evaluate_abs, evaluate_round};
 use crate::pipeline::expression::scalar::string::{
-    evaluate_concat, evaluate_length, evaluate_ucase, validate_concat, validate_ucase,
+    evaluate_concat, evaluate_hash, evaluate_left_right, evaluate_length, evaluate_lower,
+    evaluate_pad, evaluate_position, evaluate_regexp_match, evaluate_regexp_replace,
+    evaluate_replace, evaluate_split_part, evaluate_substring, evaluate_ucase, evaluate_upper,
+    validate_concat, validate_hash, validate_string_fct, validate_ucase,
 };
 use dozer_types::types::{Field, FieldType, Record, Schema};
 use std::fmt::{Display, Formatter};
@@ -15,6 +18,22 @@ pub enum ScalarFunctionType {
     Ucase,
     Concat,
     Length,
+    Lower,
+    Upper,
+    Substring,
+    Replace,
+    SplitPart,
+    Lpad,
+    Rpad,
+    Position,
+    Left,
+    Right,
+    RegexpMatch,
+    RegexpReplace,
+    Coalesce,
+    Nullif,
+    Md5,
+    Sha256,
 }
 
 impl Display for ScalarFunctionType {
@@ -25,6 +44,22 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Ucase => f.write_str("UCASE"),
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
+            ScalarFunctionType::Lower => f.write_str("LOWER"),
+            ScalarFunctionType::Upper => f.write_str("UPPER"),
+            ScalarFunctionType::Substring => f.write_str("SUBSTRING"),
+            ScalarFunctionType::Replace => f.write_str("REPLACE"),
+            ScalarFunctionType::SplitPart => f.write_str("SPLIT_PART"),
+            ScalarFunctionType::Lpad => f.write_str("LPAD"),
+            ScalarFunctionType::Rpad => f.write_str("RPAD"),
+            ScalarFunctionType::Position => f.write_str("POSITION"),
+            ScalarFunctionType::Left => f.write_str("LEFT"),
+            ScalarFunctionType::Right => f.write_str("RIGHT"),
+            ScalarFunctionType::RegexpMatch => f.write_str("REGEXP_MATCH"),
+            ScalarFunctionType::RegexpReplace => f.write_str("REGEXP_REPLACE"),
+            ScalarFunctionType::Coalesce => f.write_str("COALESCE"),
+            ScalarFunctionType::Nullif => f.write_str("NULLIF"),
+            ScalarFunctionType::Md5 => f.write_str("MD5"),
+            ScalarFunctionType::Sha256 => f.write_str("SHA256"),
         }
     }
 }
@@ -34,6 +69,8 @@ pub(crate) fn get_scalar_function_type(
     args: &[Expression],
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
+    let strings = || vec![FieldType::String, FieldType::Text];
+    let integers = || vec![FieldType::Int, FieldType::UInt];
     match function {
         ScalarFunctionType::Abs => argv!(args, 0, ScalarFunctionType::Abs)?.get_type(schema),
         ScalarFunctionType::Round => {
@@ -57,6 +94,71 @@ pub(crate) fn get_scalar_function_type(
             dozer_types::types::SourceDefinition::Dynamic,
             false,
         )),
+        ScalarFunctionType::Lower | ScalarFunctionType::Upper => {
+            validate_string_fct(function.clone(), args, &[], schema)
+        }
+        ScalarFunctionType::Substring => {
+            validate_string_fct(function.clone(), args, &[integers(), integers()], schema)
+        }
+        ScalarFunctionType::Replace => {
+            argv!(args, 2, function)?;
+            validate_string_fct(function.clone(), args, &[strings(), strings()], schema)
+        }
+        ScalarFunctionType::SplitPart => {
+            argv!(args, 2, function)?;
+            validate_string_fct(function.clone(), args, &[strings(), integers()], schema)
+        }
+        ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+            argv!(args, 1, function)?;
+            validate_string_fct(function.clone(), args, &[integers(), strings()], schema)
+        }
+        ScalarFunctionType::Left | ScalarFunctionType::Right => {
+            argv!(args, 1, function)?;
+            validate_string_fct(function.clone(), args, &[integers()], schema)
+        }
+        ScalarFunctionType::RegexpMatch => {
+            argv!(args, 1, function)?;
+            let t = validate_string_fct(function.clone(), args, &[strings()], schema)?;
+            Ok(ExpressionType::new(
+                t.return_type,
+                true,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        ScalarFunctionType::RegexpReplace => {
+            argv!(args, 2, function)?;
+            validate_string_fct(
+                function.clone(),
+                args,
+                &[strings(), strings(), strings()],
+                schema,
+            )
+        }
+        ScalarFunctionType::Position => {
+            argv!(args, 1, function)?;
+            let t = validate_string_fct(function.clone(), args, &[strings()], schema)?;
+            Ok(ExpressionType::new(
+                FieldType::UInt,
+                t.nullable,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        ScalarFunctionType::Coalesce => validate_coalesce(args, schema),
+        ScalarFunctionType::Nullif => {
+            argv!(args, 1, function)?;
+            let t = argv!(args, 0, function)?.get_type(schema)?;
+            Ok(ExpressionType::new(
+                t.return_type,
+                true,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
+        ScalarFunctionType::Md5 | ScalarFunctionType::Sha256 => {
+            validate_hash(function.clone(), argv!(args, 0, function)?, schema)
+        }
     }
 }
 
@@ -68,6 +170,22 @@ impl ScalarFunctionType {
             "ucase" => Ok(ScalarFunctionType::Ucase),
             "concat" => Ok(ScalarFunctionType::Concat),
             "length" => Ok(ScalarFunctionType::Length),
+            "lower" => Ok(ScalarFunctionType::Lower),
+            "upper" => Ok(ScalarFunctionType::Upper),
+            "substring" => Ok(ScalarFunctionType::Substring),
+            "replace" => Ok(ScalarFunctionType::Replace),
+            "split_part" => Ok(ScalarFunctionType::SplitPart),
+            "lpad" => Ok(ScalarFunctionType::Lpad),
+            "rpad" => Ok(ScalarFunctionType::Rpad),
+            "position" => Ok(ScalarFunctionType::Position),
+            "left" => Ok(ScalarFunctionType::Left),
+            "right" => Ok(ScalarFunctionType::Right),
+            "regexp_match" => Ok(ScalarFunctionType::RegexpMatch),
+            "regexp_replace" => Ok(ScalarFunctionType::RegexpReplace),
+            "coalesce" => Ok(ScalarFunctionType::Coalesce),
+            "nullif" => Ok(ScalarFunctionType::Nullif),
+            "md5" => Ok(ScalarFunctionType::Md5),
+            "sha256" => Ok(ScalarFunctionType::Sha256),
             _ => Err(PipelineError::InvalidFunction(name.to_string())),
         }
     }
@@ -95,6 +213,122 @@ impl ScalarFunctionType {
             ScalarFunctionType::Length => {
                 evaluate_length(schema, argv!(args, 0, ScalarFunctionType::Length)?, record)
             }
+            ScalarFunctionType::Lower => {
+                evaluate_lower(schema, argv!(args, 0, ScalarFunctionType::Lower)?, record)
+            }
+            ScalarFunctionType::Upper => {
+                evaluate_upper(schema, argv!(args, 0, ScalarFunctionType::Upper)?, record)
+            }
+            ScalarFunctionType::Substring => evaluate_substring(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Substring)?,
+                args.get(1),
+                args.get(2),
+                record,
+            ),
+            ScalarFunctionType::Replace => evaluate_replace(schema, args, record),
+            ScalarFunctionType::SplitPart => evaluate_split_part(schema, args, record),
+            ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+                evaluate_pad(schema, self.clone(), args, record)
+            }
+            ScalarFunctionType::Position => evaluate_position(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Position)?,
+                argv!(args, 1, ScalarFunctionType::Position)?,
+                record,
+            ),
+            ScalarFunctionType::Left | ScalarFunctionType::Right => evaluate_left_right(
+                schema,
+                self.clone(),
+                argv!(args, 0, self)?,
+                argv!(args, 1, self)?,
+                record,
+            ),
+            ScalarFunctionType::RegexpMatch => evaluate_regexp_match(
+                schema,
+                argv!(args, 0, ScalarFunctionType::RegexpMatch)?,
+                argv!(args, 1, ScalarFunctionType::RegexpMatch)?,
+                record,
+            ),
+            ScalarFunctionType::RegexpReplace => evaluate_regexp_replace(schema, args, record),
+            ScalarFunctionType::Coalesce => evaluate_coalesce(schema, args, record),
+            ScalarFunctionType::Nullif => evaluate_nullif(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Nullif)?,
+                argv!(args, 1, ScalarFunctionType::Nullif)?,
+                record,
+            ),
+            ScalarFunctionType::Md5 | ScalarFunctionType::Sha256 => {
+                evaluate_hash(schema, self.clone(), argv!(args, 0, self)?, record)
+            }
         }
     }
 }
+
+fn validate_coalesce(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let mut return_type = None;
+    let mut nullable = true;
+    for (idx, arg) in args.iter().enumerate() {
+        // NULL literals have no type of their own
+        if arg == &Expression::Literal(Field::Null) {
+            continue;
+        }
+        let t = arg.get_type(schema)?;
+        match return_type {
+            None => return_type = Some(t.return_type),
+            Some(r) if r != t.return_type => {
+                return Err(PipelineError::InvalidFunctionArgumentType(
+                    ScalarFunctionType::Coalesce.to_string(),
+                    t.return_type,
+                    crate::pipeline::errors::FieldTypes::new(vec![r]),
+                    idx,
+                ))
+            }
+            Some(_) => {}
+        }
+        nullable &= t.nullable;
+    }
+
+    match return_type {
+        Some(return_type) => Ok(ExpressionType::new(
+            return_type,
+            nullable,
+            dozer_types::types::SourceDefinition::Dynamic,
+            false,
+        )),
+        None => Err(PipelineError::InvalidExpression(
+            "COALESCE() needs at least one non NULL argument".to_string(),
+        )),
+    }
+}
+
+fn evaluate_coalesce(
+    schema: &Schema,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    for arg in args {
+        let value = arg.evaluate(record, schema)?;
+        if value != Field::Null {
+            return Ok(value);
+        }
+    }
+    Ok(Field::Null)
+}
+
+fn evaluate_nullif(
+    schema: &Schema,
+    arg0: &Expression,
+    arg1: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = arg0.evaluate(record, schema)?;
+    if value == arg1.evaluate(record, schema)? {
+        Ok(Field::Null)
+    } else {
+        Ok(value)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::arg_str;
+use crate::{arg_int, arg_str, argv, null_if_null};
 use std::fmt::{Display, Formatter};
 
 use crate::pipeline::errors::PipelineError;
@@ -10,6 +10,22 @@ use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 
 use dozer_types::types::{Field, FieldType, Record, Schema};
 use like::{Escape, Like};
+use md5::Md5;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Back references in a `REGEXP_REPLACE` replacement string.
+static BACK_REFERENCE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\\(\d)").unwrap());
+
+/// Bounds the number of compiled patterns kept when patterns come from the records.
+const MAX_CACHED_REGEXES: usize = 256;
+
+thread_local! {
+    static REGEX_CACHE: RefCell<HashMap<(String, bool), Regex>> = RefCell::new(HashMap::new());
+}
 
 pub(crate) fn validate_ucase(
     arg: &Expression,
@@ -211,3 +227,374 @@ pub(crate) fn evaluate_like(
         .map_err(|e| PipelineError::InvalidArgument(e.to_string()))?;
     Ok(result)
 }
+
+/// Validates a function taking a string as first argument and returning a string of the same
+/// type. `other_args` holds the accepted types of each following (optional or not) argument.
+pub(crate) fn validate_string_fct(
+    fct: ScalarFunctionType,
+    args: &[Expression],
+    other_args: &[Vec<FieldType>],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let ret = validate_arg_type(
+        argv!(args, 0, fct)?,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        fct.clone(),
+        0,
+    )?;
+    let mut nullable = ret.nullable;
+    for (idx, arg) in args.iter().enumerate().skip(1) {
+        let expected = other_args
+            .get(idx - 1)
+            .ok_or_else(|| PipelineError::TooManyArguments(fct.to_string()))?;
+        nullable |= validate_arg_type(arg, expected.clone(), schema, fct.clone(), idx)?.nullable;
+    }
+    Ok(ExpressionType::new(
+        ret.return_type,
+        nullable,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+fn string_result(schema: &Schema, arg: &Expression, value: String) -> Result<Field, PipelineError> {
+    Ok(match arg.get_type(schema)?.return_type {
+        FieldType::String => Field::String(value),
+        _ => Field::Text(value),
+    })
+}
+
+pub(crate) fn evaluate_lower(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::Lower, 0)?;
+    string_result(schema, arg, v.to_lowercase())
+}
+
+pub(crate) fn evaluate_upper(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::Upper, 0)?;
+    string_result(schema, arg, v.to_uppercase())
+}
+
+pub(crate) fn evaluate_substring(
+    schema: &Schema,
+    arg: &Expression,
+    start: Option<&Expression>,
+    length: Option<&Expression>,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::Substring, 0)?;
+
+    // Positions are 1-based and may start before the string, like in Postgres
+    let start = match start {
+        Some(e) => {
+            let f = e.evaluate(record, schema)?;
+            null_if_null!(f);
+            arg_int!(f, ScalarFunctionType::Substring, 1)?
+        }
+        None => 1,
+    };
+    let end = match length {
+        Some(e) => {
+            let f = e.evaluate(record, schema)?;
+            null_if_null!(f);
+            let length = arg_int!(f, ScalarFunctionType::Substring, 2)?;
+            if length < 0 {
+                return Err(PipelineError::InvalidFunctionArgument(
+                    ScalarFunctionType::Substring.to_string(),
+                    Field::Int(length),
+                    2,
+                ));
+            }
+            start.saturating_add(length)
+        }
+        None => i64::MAX,
+    };
+
+    let skip = (start.max(1) - 1) as usize;
+    let take = (end.max(1) - start.max(1)).max(0) as usize;
+    string_result(schema, arg, v.chars().skip(skip).take(take).collect())
+}
+
+pub(crate) fn evaluate_replace(
+    schema: &Schema,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let arg = argv!(args, 0, ScalarFunctionType::Replace)?;
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::Replace, 0)?;
+    let f = argv!(args, 1, ScalarFunctionType::Replace)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let from = arg_str!(f, ScalarFunctionType::Replace, 1)?;
+    let f = argv!(args, 2, ScalarFunctionType::Replace)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let to = arg_str!(f, ScalarFunctionType::Replace, 2)?;
+
+    if from.is_empty() {
+        return string_result(schema, arg, v);
+    }
+    string_result(schema, arg, v.replace(from.as_str(), to.as_str()))
+}
+
+pub(crate) fn evaluate_split_part(
+    schema: &Schema,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let arg = argv!(args, 0, ScalarFunctionType::SplitPart)?;
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::SplitPart, 0)?;
+    let f = argv!(args, 1, ScalarFunctionType::SplitPart)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let delimiter = arg_str!(f, ScalarFunctionType::SplitPart, 1)?;
+    let f = argv!(args, 2, ScalarFunctionType::SplitPart)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let n = arg_int!(f, ScalarFunctionType::SplitPart, 2)?;
+
+    let parts: Vec<&str> = if delimiter.is_empty() {
+        vec![v.as_str()]
+    } else {
+        v.split(delimiter.as_str()).collect()
+    };
+    // Negative positions count from the end
+    let part = match n {
+        0 => {
+            return Err(PipelineError::InvalidFunctionArgument(
+                ScalarFunctionType::SplitPart.to_string(),
+                Field::Int(n),
+                2,
+            ))
+        }
+        n if n > 0 => parts.get(n as usize - 1),
+        n => parts
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .and_then(|i| parts.get(i)),
+    };
+    string_result(schema, arg, part.map_or(String::new(), |p| p.to_string()))
+}
+
+pub(crate) fn evaluate_pad(
+    schema: &Schema,
+    fct: ScalarFunctionType,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let arg = argv!(args, 0, fct)?;
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, fct, 0)?;
+    let f = argv!(args, 1, fct)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let length = arg_int!(f, fct, 1)?.max(0) as usize;
+    let fill: Vec<char> = match args.get(2) {
+        Some(e) => {
+            let f = e.evaluate(record, schema)?;
+            null_if_null!(f);
+            arg_str!(f, fct, 2)?.chars().collect()
+        }
+        None => vec![' '],
+    };
+
+    let chars: Vec<char> = v.chars().collect();
+    // Strings longer than the requested length are truncated, like in Postgres
+    if chars.len() >= length || fill.is_empty() {
+        return string_result(schema, arg, chars.into_iter().take(length).collect());
+    }
+    let padding: String = fill.iter().cycle().take(length - chars.len()).collect();
+    let result = match fct {
+        ScalarFunctionType::Lpad => padding + v.as_str(),
+        _ => v + padding.as_str(),
+    };
+    string_result(schema, arg, result)
+}
+
+pub(crate) fn evaluate_position(
+    schema: &Schema,
+    substring: &Expression,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = substring.evaluate(record, schema)?;
+    null_if_null!(f);
+    let needle = arg_str!(f, ScalarFunctionType::Position, 0)?;
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let haystack = arg_str!(f, ScalarFunctionType::Position, 1)?;
+
+    // 1-based character position, 0 when not found
+    Ok(Field::UInt(match haystack.find(needle.as_str()) {
+        Some(byte_idx) => haystack[..byte_idx].chars().count() as u64 + 1,
+        None => 0,
+    }))
+}
+
+pub(crate) fn evaluate_left_right(
+    schema: &Schema,
+    fct: ScalarFunctionType,
+    arg: &Expression,
+    n: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, fct, 0)?;
+    let f = n.evaluate(record, schema)?;
+    null_if_null!(f);
+    let n = arg_int!(f, fct, 1)?;
+
+    let len = v.chars().count() as i64;
+    // A negative count returns all but the last (or first) |n| characters
+    let take = (if n >= 0 { n.min(len) } else { (len + n).max(0) }) as usize;
+    let result = match fct {
+        ScalarFunctionType::Left => v.chars().take(take).collect(),
+        _ => v.chars().skip(len as usize - take).collect(),
+    };
+    string_result(schema, arg, result)
+}
+
+/// Patterns are usually literals, so they are compiled once and reused for every record.
+fn build_regex(
+    fct: ScalarFunctionType,
+    pattern: &str,
+    case_insensitive: bool,
+) -> Result<Regex, PipelineError> {
+    REGEX_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let key = (pattern.to_string(), case_insensitive);
+        if let Some(regex) = cache.get(&key) {
+            return Ok(regex.clone());
+        }
+
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| PipelineError::InvalidArgument(format!("{fct}(): {e}")))?;
+        if cache.len() >= MAX_CACHED_REGEXES {
+            cache.clear();
+        }
+        cache.insert(key, regex.clone());
+        Ok(regex)
+    })
+}
+
+pub(crate) fn evaluate_regexp_match(
+    schema: &Schema,
+    arg: &Expression,
+    pattern: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::RegexpMatch, 0)?;
+    let f = pattern.evaluate(record, schema)?;
+    null_if_null!(f);
+    let pattern = arg_str!(f, ScalarFunctionType::RegexpMatch, 1)?;
+
+    // Returns the first capture group of the first match, or the whole match
+    // when the pattern has no groups. NULL when nothing matches.
+    let regex = build_regex(ScalarFunctionType::RegexpMatch, pattern.as_str(), false)?;
+    match regex.captures(v.as_str()) {
+        Some(captures) => {
+            let m = captures.get(1).or_else(|| captures.get(0));
+            string_result(schema, arg, m.map_or("", |m| m.as_str()).to_string())
+        }
+        None => Ok(Field::Null),
+    }
+}
+
+pub(crate) fn evaluate_regexp_replace(
+    schema: &Schema,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let arg = argv!(args, 0, ScalarFunctionType::RegexpReplace)?;
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let v = arg_str!(f, ScalarFunctionType::RegexpReplace, 0)?;
+    let f = argv!(args, 1, ScalarFunctionType::RegexpReplace)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let pattern = arg_str!(f, ScalarFunctionType::RegexpReplace, 1)?;
+    let f = argv!(args, 2, ScalarFunctionType::RegexpReplace)?.evaluate(record, schema)?;
+    null_if_null!(f);
+    let replacement = arg_str!(f, ScalarFunctionType::RegexpReplace, 2)?;
+    let flags = match args.get(3) {
+        Some(e) => {
+            let f = e.evaluate(record, schema)?;
+            null_if_null!(f);
+            arg_str!(f, ScalarFunctionType::RegexpReplace, 3)?
+        }
+        None => String::new(),
+    };
+
+    let regex = build_regex(
+        ScalarFunctionType::RegexpReplace,
+        pattern.as_str(),
+        flags.contains('i'),
+    )?;
+    // `$` is literal and back references are written \1, like in Postgres
+    let replacement = BACK_REFERENCE
+        .replace_all(replacement.replace('$', "$$").as_str(), "$${$1}")
+        .to_string();
+    let result = if flags.contains('g') {
+        regex.replace_all(v.as_str(), replacement.as_str())
+    } else {
+        regex.replace(v.as_str(), replacement.as_str())
+    };
+    string_result(schema, arg, result.to_string())
+}
+
+pub(crate) fn validate_hash(
+    fct: ScalarFunctionType,
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_t = validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text, FieldType::Binary],
+        schema,
+        fct,
+        0,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::String,
+        arg_t.nullable,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+pub(crate) fn evaluate_hash(
+    schema: &Schema,
+    fct: ScalarFunctionType,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    null_if_null!(f);
+    let bytes = match &f {
+        Field::Binary(b) => b.clone(),
+        _ => arg_str!(f, fct, 0)?.into_bytes(),
+    };
+    let digest = match fct {
+        ScalarFunctionType::Md5 => hex::encode(Md5::digest(bytes)),
+        _ => hex::encode(Sha256::digest(bytes)),
+    };
+    Ok(Field::String(digest))
+}
@@ -8,3 +8,5 @@ mod number;
 mod scalar_common;
 #[cfg(test)]
 mod string;
+#[cfg(test)]
+mod udf;
@@ -1,6 +1,13 @@
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::datetime::{parse_interval, DateTimePart};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
 use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
-use dozer_types::chrono::{DateTime, NaiveDate};
-use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::chrono::{DateTime, NaiveDate, Utc};
+use dozer_types::types::{
+    DozerDuration, Field, FieldDefinition, FieldType, Schema, SourceDefinition,
+};
+use sqlparser::ast::SelectItem;
 
 #[test]
 fn test_date() {
@@ -73,7 +80,249 @@ fn test_timestamp_diff() {
             Field::Timestamp(DateTime::parse_from_rfc3339("2023-01-02T00:12:10Z").unwrap()),
         ],
     );
-    assert_eq!(f, Field::Int(1000));
+    assert_eq!(f, Field::Duration(DozerDuration::from_nanos(1_000_000_000)));
+}
+
+fn ts_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn run_ts_fct(sql: &str, ts: &str) -> Field {
+    run_scalar_fct(
+        sql,
+        ts_schema(),
+        vec![Field::Timestamp(DateTime::parse_from_rfc3339(ts).unwrap())],
+    )
+}
+
+fn ts(value: &str) -> Field {
+    Field::Timestamp(DateTime::parse_from_rfc3339(value).unwrap())
+}
+
+#[test]
+fn test_extract() {
+    let value = "2023-02-14T13:45:30.250+02:00";
+    for (part, expected) in [
+        ("YEAR", 2023),
+        ("QUARTER", 1),
+        ("MONTH", 2),
+        ("WEEK", 7),
+        ("DAY", 14),
+        ("DOW", 2),
+        ("DOY", 45),
+        ("HOUR", 13),
+        ("MINUTE", 45),
+        ("SECOND", 30),
+        ("MILLISECOND", 30_250),
+        ("EPOCH", 1_676_375_130),
+    ] {
+        let f = run_ts_fct(&format!("SELECT EXTRACT({part} FROM ts) FROM users"), value);
+        assert_eq!(f, Field::Int(expected), "{part}");
+    }
+}
+
+#[test]
+fn test_date_trunc() {
+    let value = "2023-02-15T13:45:30.250+02:00";
+    for (unit, expected) in [
+        ("year", "2023-01-01T00:00:00+02:00"),
+        ("quarter", "2023-01-01T00:00:00+02:00"),
+        ("month", "2023-02-01T00:00:00+02:00"),
+        ("week", "2023-02-13T00:00:00+02:00"),
+        ("day", "2023-02-15T00:00:00+02:00"),
+        ("hour", "2023-02-15T13:00:00+02:00"),
+        ("minute", "2023-02-15T13:45:00+02:00"),
+        ("second", "2023-02-15T13:45:30+02:00"),
+    ] {
+        let f = run_ts_fct(
+            &format!("SELECT DATE_TRUNC('{unit}', ts) FROM users"),
+            value,
+        );
+        assert_eq!(f, ts(expected), "{unit}");
+    }
+}
+
+#[test]
+fn test_now() {
+    let before = Utc::now();
+    let f = run_ts_fct("SELECT NOW() FROM users", "2023-01-01T00:00:00Z");
+    let after = Utc::now();
+
+    let now = f.as_timestamp().unwrap();
+    assert!(now >= before && now <= after);
+}
+
+#[test]
+fn test_to_timestamp_to_char() {
+    let f = run_scalar_fct(
+        "SELECT TO_TIMESTAMP(fn, '%d/%m/%Y %H:%M') FROM users",
+        Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    String::from("fn"),
+                    FieldType::String,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone(),
+        vec![Field::String("14/02/2023 13:45".to_string())],
+    );
+    assert_eq!(f, ts("2023-02-14T13:45:00Z"));
+
+    let f = run_ts_fct(
+        "SELECT TO_TIMESTAMP(1676382330) FROM users",
+        "2023-01-01T00:00:00Z",
+    );
+    assert_eq!(f, ts("2023-02-14T13:45:30Z"));
+
+    let f = run_ts_fct(
+        "SELECT TO_CHAR(ts, '%Y-%m-%d %H:%M:%S %z') FROM users",
+        "2023-02-14T13:45:30+02:00",
+    );
+    assert_eq!(f, Field::String("2023-02-14 13:45:30 +0200".to_string()));
+}
+
+#[test]
+fn test_at_time_zone() {
+    let f = run_ts_fct(
+        "SELECT TO_CHAR(ts AT TIME ZONE '-05:00', '%H:%M %:z') FROM users",
+        "2023-02-14T13:45:30Z",
+    );
+    assert_eq!(f, Field::String("08:45 -05:00".to_string()));
+
+    let f = run_ts_fct(
+        "SELECT EXTRACT(DAY FROM TIMEZONE('UTC+10', ts)) FROM users",
+        "2023-02-14T23:00:00Z",
+    );
+    assert_eq!(f, Field::Int(15));
+}
+
+#[test]
+fn test_interval_arithmetic() {
+    let value = "2023-01-31T10:00:00Z";
+    assert_eq!(
+        run_ts_fct("SELECT ts + INTERVAL '1' MONTH FROM users", value),
+        ts("2023-02-28T10:00:00Z")
+    );
+    assert_eq!(
+        run_ts_fct("SELECT ts - INTERVAL '1 day 2 hours' FROM users", value),
+        ts("2023-01-30T08:00:00Z")
+    );
+    assert_eq!(
+        run_ts_fct("SELECT INTERVAL '90' MINUTE + ts FROM users", value),
+        ts("2023-01-31T11:30:00Z")
+    );
+    assert_eq!(
+        run_ts_fct(
+            "SELECT ts - (ts - INTERVAL '1 day 02:30:00') FROM users",
+            value
+        ),
+        Field::Duration(DozerDuration::from_nanos(
+            (24 * 3600 + 2 * 3600 + 30 * 60) * 1_000_000_000
+        ))
+    );
+}
+
+fn build_expression(sql: &str, schema: &Schema) -> Expression {
+    match &get_select(sql).unwrap().projection[0] {
+        SelectItem::UnnamedExpr(e) => ExpressionBuilder::new(schema.fields.len())
+            .build(false, e, schema)
+            .unwrap(),
+        _ => panic!("Invalid expr"),
+    }
+}
+
+#[test]
+fn test_interval_operator_types() {
+    let schema = ts_schema();
+    for sql in [
+        "SELECT ts + INTERVAL '1' DAY FROM users",
+        "SELECT ts - INTERVAL '1' DAY FROM users",
+        "SELECT INTERVAL '1' DAY - INTERVAL '1' HOUR FROM users",
+    ] {
+        assert!(
+            build_expression(sql, &schema).get_type(&schema).is_ok(),
+            "{sql}"
+        );
+    }
+    for sql in [
+        "SELECT ts * INTERVAL '1' DAY FROM users",
+        "SELECT INTERVAL '1' DAY * INTERVAL '1' HOUR FROM users",
+        "SELECT INTERVAL '1' DAY - ts FROM users",
+    ] {
+        assert!(
+            build_expression(sql, &schema).get_type(&schema).is_err(),
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_datetime_functions_propagate_null() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    for sql in [
+        "SELECT DAY_OF_WEEK(ts) FROM users",
+        "SELECT EXTRACT(YEAR FROM ts) FROM users",
+        "SELECT DATE_TRUNC('day', ts) FROM users",
+        "SELECT TO_CHAR(ts, '%Y') FROM users",
+        "SELECT ts AT TIME ZONE '+02:00' FROM users",
+    ] {
+        assert!(
+            build_expression(sql, &schema)
+                .get_type(&schema)
+                .unwrap()
+                .nullable,
+            "{sql}"
+        );
+        assert_eq!(
+            run_scalar_fct(sql, schema.clone(), vec![Field::Null]),
+            Field::Null,
+            "{sql}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_interval() {
+    assert_eq!(
+        parse_interval("1 year 2 mons 3 days", None).unwrap(),
+        DozerDuration::new(14, 3, 0)
+    );
+    assert_eq!(
+        parse_interval("-1 week -01:30", None).unwrap(),
+        DozerDuration::new(0, -7, -5_400_000_000_000)
+    );
+    assert_eq!(
+        parse_interval("1.5", Some(DateTimePart::Second)).unwrap(),
+        DozerDuration::from_nanos(1_500_000_000)
+    );
+    assert!(parse_interval("", None).is_err());
+    assert!(parse_interval("1", None).is_err());
+    assert!(parse_interval("1 fortnight", None).is_err());
+    assert!(parse_interval("1.5", Some(DateTimePart::Day)).is_err());
+    assert!(parse_interval("01:75", None).is_err());
 }
 
 // #[test]
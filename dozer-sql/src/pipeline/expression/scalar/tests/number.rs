@@ -2,7 +2,9 @@ use crate::pipeline::expression::execution::Expression::Literal;
 use crate::pipeline::expression::scalar::number::evaluate_round;
 use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
 use dozer_types::ordered_float::OrderedFloat;
-use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use dozer_types::types::{
+    DozerBigInt, Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition,
+};
 
 #[test]
 fn test_abs() {
@@ -100,3 +102,67 @@ fn test_round() {
         Field::Null
     );
 }
+
+fn bigint_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("amount"),
+                FieldType::BigInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let u256_max: DozerBigInt =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            .parse()
+            .unwrap();
+
+    let f = run_scalar_fct(
+        "SELECT amount + 1 FROM users",
+        bigint_schema(),
+        vec![Field::BigInt(u256_max)],
+    );
+    assert_eq!(
+        f,
+        Field::BigInt(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+                .parse()
+                .unwrap()
+        )
+    );
+
+    let f = run_scalar_fct(
+        "SELECT -amount * 2 FROM users",
+        bigint_schema(),
+        vec![Field::BigInt(u256_max)],
+    );
+    assert_eq!(
+        f,
+        Field::BigInt(
+            "-231584178474632390847141970017375815706539969331281128078915168015826259279870"
+                .parse()
+                .unwrap()
+        )
+    );
+
+    let f = run_scalar_fct(
+        "SELECT amount > 18446744073709551615 FROM users",
+        bigint_schema(),
+        vec![Field::BigInt(u256_max)],
+    );
+    assert_eq!(f, Field::Boolean(true));
+
+    let f = run_scalar_fct(
+        "SELECT CAST(amount AS STRING) FROM users",
+        bigint_schema(),
+        vec![Field::BigInt(DozerBigInt::from(-5_i64))],
+    );
+    assert_eq!(f, Field::String("-5".to_string()));
+}
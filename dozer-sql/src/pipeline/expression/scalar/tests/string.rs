@@ -435,3 +435,284 @@ fn test_like_escape() {
     );
     assert_eq!(f, Field::String("J%".to_string()));
 }
+
+fn name_schema(typ: FieldType) -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(String::from("fn"), typ, false, SourceDefinition::Dynamic),
+            false,
+        )
+        .clone()
+}
+
+fn run_string_fct(sql: &str, value: &str) -> Field {
+    run_scalar_fct(
+        sql,
+        name_schema(FieldType::String),
+        vec![Field::String(value.to_string())],
+    )
+}
+
+#[test]
+fn test_lower_upper() {
+    assert_eq!(
+        run_string_fct("SELECT LOWER(fn) FROM USERS", "John"),
+        Field::String("john".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT UPPER(fn) FROM USERS", "John"),
+        Field::String("JOHN".to_string())
+    );
+
+    let f = run_scalar_fct(
+        "SELECT LOWER(fn) FROM USERS",
+        name_schema(FieldType::Text),
+        vec![Field::Text("John".to_string())],
+    );
+    assert_eq!(f, Field::Text("john".to_string()));
+}
+
+#[test]
+fn test_substring() {
+    assert_eq!(
+        run_string_fct("SELECT SUBSTRING(fn, 2, 3) FROM USERS", "Hello"),
+        Field::String("ell".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SUBSTRING(fn FROM 3) FROM USERS", "Hello"),
+        Field::String("llo".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SUBSTRING(fn FROM 0 FOR 3) FROM USERS", "Hello"),
+        Field::String("He".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SUBSTRING(fn, 10, 3) FROM USERS", "Hello"),
+        Field::String("".to_string())
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_substring_wrong_schema() {
+    run_string_fct("SELECT SUBSTRING(fn, 'a') FROM USERS", "Hello");
+}
+
+#[test]
+fn test_replace() {
+    assert_eq!(
+        run_string_fct("SELECT REPLACE(fn, 'l', 'L') FROM USERS", "Hello"),
+        Field::String("HeLLo".to_string())
+    );
+}
+
+#[test]
+fn test_split_part() {
+    assert_eq!(
+        run_string_fct("SELECT SPLIT_PART(fn, ',', 2) FROM USERS", "a,b,c"),
+        Field::String("b".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SPLIT_PART(fn, ',', -1) FROM USERS", "a,b,c"),
+        Field::String("c".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SPLIT_PART(fn, ',', 5) FROM USERS", "a,b,c"),
+        Field::String("".to_string())
+    );
+}
+
+#[test]
+fn test_pad() {
+    assert_eq!(
+        run_string_fct("SELECT LPAD(fn, 5, '0') FROM USERS", "42"),
+        Field::String("00042".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT RPAD(fn, 6, 'ab') FROM USERS", "42"),
+        Field::String("42abab".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT LPAD(fn, 3) FROM USERS", "Hello"),
+        Field::String("Hel".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT RPAD(fn, 4) FROM USERS", "42"),
+        Field::String("42  ".to_string())
+    );
+}
+
+#[test]
+fn test_position() {
+    assert_eq!(
+        run_string_fct("SELECT POSITION('lo' IN fn) FROM USERS", "Hello"),
+        Field::UInt(4)
+    );
+    assert_eq!(
+        run_string_fct("SELECT POSITION('x' IN fn) FROM USERS", "Hello"),
+        Field::UInt(0)
+    );
+}
+
+#[test]
+fn test_left_right() {
+    assert_eq!(
+        run_string_fct("SELECT LEFT(fn, 2) FROM USERS", "Hello"),
+        Field::String("He".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT RIGHT(fn, 2) FROM USERS", "Hello"),
+        Field::String("lo".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT LEFT(fn, -2) FROM USERS", "Hello"),
+        Field::String("Hel".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT RIGHT(fn, 10) FROM USERS", "Hello"),
+        Field::String("Hello".to_string())
+    );
+}
+
+#[test]
+fn test_regexp_match() {
+    assert_eq!(
+        run_string_fct(
+            "SELECT REGEXP_MATCH(fn, '([a-z]+)@example') FROM USERS",
+            "john@example.com"
+        ),
+        Field::String("john".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT REGEXP_MATCH(fn, '[0-9]+') FROM USERS", "abc"),
+        Field::Null
+    );
+}
+
+#[test]
+fn test_regexp_replace() {
+    assert_eq!(
+        run_string_fct(
+            "SELECT REGEXP_REPLACE(fn, '([a-z]+)@', '\\1 at ') FROM USERS",
+            "john@example.com"
+        ),
+        Field::String("john at example.com".to_string())
+    );
+    assert_eq!(
+        run_string_fct(
+            "SELECT REGEXP_REPLACE(fn, 'O', '$', 'gi') FROM USERS",
+            "foo"
+        ),
+        Field::String("f$$".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT REGEXP_REPLACE(fn, 'o', '0') FROM USERS", "foo"),
+        Field::String("f0o".to_string())
+    );
+}
+
+#[test]
+fn test_coalesce_nullif() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("fn"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("ln"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+
+    let f = run_scalar_fct(
+        "SELECT COALESCE(fn, NULL, ln) FROM USERS",
+        schema.clone(),
+        vec![Field::Null, Field::String("Doe".to_string())],
+    );
+    assert_eq!(f, Field::String("Doe".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT NULLIF(fn, ln) FROM USERS",
+        schema.clone(),
+        vec![
+            Field::String("Doe".to_string()),
+            Field::String("Doe".to_string()),
+        ],
+    );
+    assert_eq!(f, Field::Null);
+
+    let f = run_scalar_fct(
+        "SELECT NULLIF(fn, ln) FROM USERS",
+        schema,
+        vec![
+            Field::String("John".to_string()),
+            Field::String("Doe".to_string()),
+        ],
+    );
+    assert_eq!(f, Field::String("John".to_string()));
+}
+
+#[test]
+fn test_hash() {
+    assert_eq!(
+        run_string_fct("SELECT MD5(fn) FROM USERS", "hello"),
+        Field::String("5d41402abc4b2a76b9719d911017c592".to_string())
+    );
+    assert_eq!(
+        run_string_fct("SELECT SHA256(fn) FROM USERS", "hello"),
+        Field::String(
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_string_functions_propagate_null() {
+    let schema = Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("fn"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone();
+    for sql in [
+        "SELECT LOWER(fn) FROM USERS",
+        "SELECT UPPER(fn) FROM USERS",
+        "SELECT SUBSTRING(fn, 2) FROM USERS",
+        "SELECT REPLACE(fn, 'a', 'b') FROM USERS",
+        "SELECT SPLIT_PART(fn, ',', 1) FROM USERS",
+        "SELECT LPAD(fn, 5) FROM USERS",
+        "SELECT POSITION('a' IN fn) FROM USERS",
+        "SELECT LEFT(fn, 2) FROM USERS",
+        "SELECT REGEXP_REPLACE(fn, 'a', 'b') FROM USERS",
+        "SELECT MD5(fn) FROM USERS",
+        "SELECT SHA256(fn) FROM USERS",
+    ] {
+        assert_eq!(
+            run_scalar_fct(sql, schema.clone(), vec![Field::Null]),
+            Field::Null,
+            "{sql}"
+        );
+    }
+
+    let f = run_scalar_fct(
+        "SELECT REPLACE('abc', 'b', fn) FROM USERS",
+        schema,
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
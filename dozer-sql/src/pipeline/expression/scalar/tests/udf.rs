@@ -0,0 +1,260 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::ExpressionBuilder;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::expression::udf::{register_udf, unregister_udf, ScalarUdf};
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+
+fn weighted_sum(name: &str) -> ScalarUdf {
+    ScalarUdf::new(
+        name,
+        vec![FieldType::Int, FieldType::Float],
+        FieldType::Float,
+        |args| match args {
+            [Field::Int(value), Field::Float(weight)] => {
+                Ok(Field::Float(OrderedFloat(*value as f64 * weight.0)))
+            }
+            _ => Err(PipelineError::InvalidFunction("weighted_sum".to_string())),
+        },
+    )
+}
+
+fn udf_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                "value".to_string(),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "weight".to_string(),
+                FieldType::Float,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                "label".to_string(),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn build_expression(sql: &str, schema: &Schema) -> Result<Expression, PipelineError> {
+    match &get_select(sql).unwrap().projection[0] {
+        SelectItem::UnnamedExpr(e) => {
+            ExpressionBuilder::new(schema.fields.len()).build(false, e, schema)
+        }
+        _ => panic!("Invalid expr"),
+    }
+}
+
+#[test]
+fn test_native_udf() {
+    register_udf(weighted_sum("test_weighted_sum")).unwrap();
+
+    let f = run_scalar_fct(
+        "SELECT test_weighted_sum(value, weight) FROM users",
+        udf_schema(),
+        vec![
+            Field::Int(3),
+            Field::Float(OrderedFloat(1.5)),
+            Field::String("a".to_string()),
+        ],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(4.5)));
+
+    let f = run_scalar_fct(
+        "SELECT TEST_WEIGHTED_SUM(value, weight) FROM users",
+        udf_schema(),
+        vec![Field::Null, Field::Float(OrderedFloat(1.5)), Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+
+    assert!(unregister_udf("test_weighted_sum"));
+    assert!(!unregister_udf("test_weighted_sum"));
+}
+
+#[test]
+fn test_udf_argument_validation() {
+    register_udf(weighted_sum("test_checked_sum")).unwrap();
+    let schema = udf_schema();
+
+    assert!(matches!(
+        build_expression("SELECT test_checked_sum(value) FROM users", &schema),
+        Err(PipelineError::NotEnoughArguments(_))
+    ));
+    assert!(matches!(
+        build_expression(
+            "SELECT test_checked_sum(value, weight, weight) FROM users",
+            &schema
+        ),
+        Err(PipelineError::TooManyArguments(_))
+    ));
+    assert!(matches!(
+        build_expression("SELECT test_checked_sum(value, label) FROM users", &schema),
+        Err(PipelineError::InvalidFunctionArgumentType(
+            _,
+            FieldType::String,
+            _,
+            1
+        ))
+    ));
+
+    let e = build_expression("SELECT test_checked_sum(value, weight) FROM users", &schema).unwrap();
+    assert_eq!(e.to_string(&schema), "test_checked_sum(value,weight)");
+    assert_eq!(e.get_type(&schema).unwrap().return_type, FieldType::Float);
+}
+
+#[test]
+fn test_udf_return_type_is_checked() {
+    register_udf(ScalarUdf::new(
+        "test_bad_return",
+        vec![FieldType::Int],
+        FieldType::Int,
+        |_| Ok(Field::String("not an int".to_string())),
+    ))
+    .unwrap();
+    let schema = udf_schema();
+
+    let e = build_expression("SELECT test_bad_return(value) FROM users", &schema).unwrap();
+    let record = Record::new(None, vec![Field::Int(1), Field::Null, Field::Null], None);
+    assert!(matches!(
+        e.evaluate(&record, &schema),
+        Err(PipelineError::InvalidUdfReturnValue(_, _, FieldType::Int))
+    ));
+}
+
+#[test]
+fn test_udf_cannot_shadow_builtin() {
+    assert!(matches!(
+        register_udf(weighted_sum("concat")),
+        Err(PipelineError::InvalidUdf(_, _))
+    ));
+    assert!(matches!(
+        register_udf(weighted_sum("SUM")),
+        Err(PipelineError::InvalidUdf(_, _))
+    ));
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_udf() {
+    use crate::pipeline::expression::udf::register_config_udfs;
+    use dozer_types::models::udf_config::UdfConfig;
+    use tempdir::TempDir;
+
+    let tmp_dir = TempDir::new("wasm_udf").unwrap();
+    let path = tmp_dir.path().join("weighted.wat");
+    std::fs::write(
+        &path,
+        r#"(module
+            (func (export "weighted") (param i64 f64) (result f64)
+                local.get 0
+                f64.convert_i64_s
+                local.get 1
+                f64.mul))"#,
+    )
+    .unwrap();
+
+    let config = UdfConfig {
+        name: "test_wasm_weighted".to_string(),
+        path: path.to_string_lossy().to_string(),
+        export: Some("weighted".to_string()),
+        args: vec!["int".to_string(), "float".to_string()],
+        return_type: "float".to_string(),
+    };
+    register_config_udfs(&[config.clone()]).unwrap();
+
+    let f = run_scalar_fct(
+        "SELECT test_wasm_weighted(value, weight) FROM users",
+        udf_schema(),
+        vec![
+            Field::Int(4),
+            Field::Float(OrderedFloat(0.25)),
+            Field::String("a".to_string()),
+        ],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(1.0)));
+
+    let mismatched = UdfConfig {
+        name: "test_wasm_mismatched".to_string(),
+        return_type: "int".to_string(),
+        ..config
+    };
+    assert!(matches!(
+        register_config_udfs(&[mismatched]),
+        Err(PipelineError::InvalidUdf(_, _))
+    ));
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_udf_limits() {
+    use crate::pipeline::expression::udf::{get_udf, register_config_udfs};
+    use dozer_types::models::udf_config::UdfConfig;
+    use tempdir::TempDir;
+
+    let tmp_dir = TempDir::new("wasm_udf_limits").unwrap();
+    let path = tmp_dir.path().join("limits.wat");
+    std::fs::write(
+        &path,
+        r#"(module
+            (func (export "spin") (param i64) (result i64)
+                (loop $forever (br $forever))
+                local.get 0)
+            (func (export "identity") (param i64) (result i64)
+                local.get 0))"#,
+    )
+    .unwrap();
+    let config = |name: &str, export: &str| UdfConfig {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        export: Some(export.to_string()),
+        args: vec!["uint".to_string()],
+        return_type: "uint".to_string(),
+    };
+    register_config_udfs(&[
+        config("test_wasm_spin", "spin"),
+        config("test_wasm_identity", "identity"),
+    ])
+    .unwrap();
+
+    let call = |name: &str, value: Field| {
+        get_udf(name).unwrap().evaluate(
+            &Schema::empty(),
+            &[Expression::Literal(value)],
+            &Record::new(None, vec![], None),
+        )
+    };
+    assert!(matches!(
+        call("test_wasm_spin", Field::UInt(1)),
+        Err(PipelineError::WasmErr(_))
+    ));
+    assert_eq!(
+        call("test_wasm_identity", Field::UInt(1)).unwrap(),
+        Field::UInt(1)
+    );
+    assert!(matches!(
+        call("test_wasm_identity", Field::UInt(u64::MAX)),
+        Err(PipelineError::InvalidFunctionArgument(
+            _,
+            Field::UInt(u64::MAX),
+            0
+        ))
+    ));
+}
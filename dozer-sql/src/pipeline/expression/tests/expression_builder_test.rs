@@ -84,7 +84,9 @@ fn test_simple_aggr_function() {
             offset: schema.fields.len(),
             aggregations: vec![Expression::AggregateFunction {
                 fun: AggregateFunctionType::Sum,
-                args: vec![Expression::Column { index: 0 }]
+                args: vec![Expression::Column { index: 0 }],
+                distinct: false,
+                filter: None,
             }]
         }
     );
@@ -133,7 +135,9 @@ fn test_2_nested_aggr_function() {
                         Expression::Column { index: 1 },
                         Expression::Literal(Field::Int(2))
                     ]
-                }]
+                }],
+                distinct: false,
+                filter: None,
             }]
         }
     );
@@ -182,7 +186,9 @@ fn test_3_nested_aggr_function() {
                         Expression::Column { index: 1 },
                         Expression::Literal(Field::Int(2))
                     ]
-                }]
+                }],
+                distinct: false,
+                filter: None,
             }]
         }
     );
@@ -237,7 +243,9 @@ fn test_3_nested_aggr_function_dup() {
                         Expression::Column { index: 1 },
                         Expression::Literal(Field::Int(2))
                     ]
-                }]
+                }],
+                distinct: false,
+                filter: None,
             }]
         }
     );
@@ -296,11 +304,15 @@ fn test_3_nested_aggr_function_and_sum() {
                             Expression::Column { index: 1 },
                             Expression::Literal(Field::Int(2))
                         ]
-                    }]
+                    }],
+                    distinct: false,
+                    filter: None,
                 },
                 Expression::AggregateFunction {
                     fun: AggregateFunctionType::Sum,
-                    args: vec![Expression::Column { index: 0 }]
+                    args: vec![Expression::Column { index: 0 }],
+                    distinct: false,
+                    filter: None,
                 }
             ]
         }
@@ -361,11 +373,15 @@ fn test_3_nested_aggr_function_and_sum_3() {
                             Expression::Column { index: 1 },
                             Expression::Literal(Field::Int(2))
                         ]
-                    }]
+                    }],
+                    distinct: false,
+                    filter: None,
                 },
                 Expression::AggregateFunction {
                     fun: AggregateFunctionType::Sum,
-                    args: vec![Expression::Column { index: 0 }]
+                    args: vec![Expression::Column { index: 0 }],
+                    distinct: false,
+                    filter: None,
                 }
             ]
         }
@@ -0,0 +1,200 @@
+use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::expression::aggregate::AggregateFunctionType;
+use crate::pipeline::expression::datetime::DateTimeFunctionType;
+use crate::pipeline::expression::execution::{
+    get_field_type, Expression, ExpressionExecutor, ExpressionType,
+};
+use crate::pipeline::expression::geo::common::GeoFunctionType;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::models::udf_config::UdfConfig;
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, PoisonError, RwLock};
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+use wasm::load_wasm_udf;
+
+type UdfFn = dyn Fn(&[Field]) -> Result<Field, PipelineError> + Send + Sync;
+
+/// A scalar function implemented outside of SQL, either natively in Rust or as a WebAssembly
+/// module. Arguments and the returned value must match the declared types. A NULL argument
+/// short-circuits to a NULL result without calling the function.
+pub struct ScalarUdf {
+    name: String,
+    arg_types: Vec<FieldType>,
+    return_type: FieldType,
+    fun: Box<UdfFn>,
+}
+
+impl ScalarUdf {
+    pub fn new<F>(name: &str, arg_types: Vec<FieldType>, return_type: FieldType, fun: F) -> Self
+    where
+        F: Fn(&[Field]) -> Result<Field, PipelineError> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_lowercase(),
+            arg_types,
+            return_type,
+            fun: Box::new(fun),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn arg_types(&self) -> &[FieldType] {
+        &self.arg_types
+    }
+
+    pub fn return_type(&self) -> FieldType {
+        self.return_type
+    }
+
+    pub(crate) fn validate_args(
+        &self,
+        args: &[Expression],
+        schema: &Schema,
+    ) -> Result<(), PipelineError> {
+        if args.len() > self.arg_types.len() {
+            return Err(PipelineError::TooManyArguments(self.name.clone()));
+        }
+        if args.len() < self.arg_types.len() {
+            return Err(PipelineError::NotEnoughArguments(self.name.clone()));
+        }
+        for (idx, (arg, expected)) in args.iter().zip(&self.arg_types).enumerate() {
+            let actual = arg.get_type(schema)?.return_type;
+            if actual != *expected {
+                return Err(PipelineError::InvalidFunctionArgumentType(
+                    self.name.clone(),
+                    actual,
+                    FieldTypes::new(vec![*expected]),
+                    idx,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_type(&self) -> ExpressionType {
+        ExpressionType::new(self.return_type, true, SourceDefinition::Dynamic, false)
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        schema: &Schema,
+        args: &[Expression],
+        record: &Record,
+    ) -> Result<Field, PipelineError> {
+        let values = args
+            .iter()
+            .map(|arg| arg.evaluate(record, schema))
+            .collect::<Result<Vec<_>, PipelineError>>()?;
+        if values.iter().any(|value| value == &Field::Null) {
+            return Ok(Field::Null);
+        }
+
+        let result = (self.fun)(&values)?;
+        match get_field_type(&result) {
+            None => Ok(result),
+            Some(typ) if typ == self.return_type => Ok(result),
+            Some(_) => Err(PipelineError::InvalidUdfReturnValue(
+                self.name.clone(),
+                result,
+                self.return_type,
+            )),
+        }
+    }
+}
+
+impl Debug for ScalarUdf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScalarUdf")
+            .field("name", &self.name)
+            .field("arg_types", &self.arg_types)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+/// Two UDFs are only equal if they are the same registration.
+impl PartialEq for ScalarUdf {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+static UDF_REGISTRY: RwLock<BTreeMap<String, Arc<ScalarUdf>>> = RwLock::new(BTreeMap::new());
+
+/// Makes `udf` callable from SQL by its name. Registering a name again replaces the previous
+/// function for pipelines built afterwards. Names of built-in functions cannot be registered.
+pub fn register_udf(udf: ScalarUdf) -> Result<(), PipelineError> {
+    let name = udf.name.as_str();
+    if ScalarFunctionType::new(name).is_ok()
+        || AggregateFunctionType::new(name).is_ok()
+        || GeoFunctionType::new(name).is_ok()
+        || DateTimeFunctionType::new(name).is_ok()
+    {
+        return Err(PipelineError::InvalidUdf(
+            name.to_string(),
+            "name is already used by a built-in function".to_string(),
+        ));
+    }
+
+    UDF_REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(udf.name.clone(), Arc::new(udf));
+    Ok(())
+}
+
+/// Removes a registered UDF. Returns whether it was registered.
+pub fn unregister_udf(name: &str) -> bool {
+    UDF_REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&name.to_lowercase())
+        .is_some()
+}
+
+pub(crate) fn get_udf(name: &str) -> Option<Arc<ScalarUdf>> {
+    UDF_REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
+
+/// Loads and registers the WebAssembly UDFs listed in the app config.
+pub fn register_config_udfs(udfs: &[UdfConfig]) -> Result<(), PipelineError> {
+    for config in udfs {
+        let arg_types = config
+            .args
+            .iter()
+            .map(|typ| parse_udf_type(&config.name, typ))
+            .collect::<Result<Vec<_>, PipelineError>>()?;
+        let return_type = parse_udf_type(&config.name, &config.return_type)?;
+
+        register_udf(load_wasm_udf(config, arg_types, return_type)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "wasm"))]
+fn load_wasm_udf(
+    config: &UdfConfig,
+    _arg_types: Vec<FieldType>,
+    _return_type: FieldType,
+) -> Result<ScalarUdf, PipelineError> {
+    Err(PipelineError::InvalidUdf(
+        config.name.clone(),
+        "WebAssembly UDFs require the \"wasm\" feature".to_string(),
+    ))
+}
+
+fn parse_udf_type(name: &str, typ: &str) -> Result<FieldType, PipelineError> {
+    FieldType::try_from(typ).map_err(|e| PipelineError::InvalidUdf(name.to_string(), e))
+}
@@ -0,0 +1,159 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::udf::ScalarUdf;
+use dozer_types::models::udf_config::UdfConfig;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use once_cell::sync::Lazy;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimitsBuilder, Val, ValType};
+
+/// Instructions a single call may execute, roughly.
+const FUEL_PER_CALL: u64 = 100_000_000;
+/// Wall clock time a single call may take, in epoch ticks.
+const EPOCH_DEADLINE_TICKS: u64 = 10;
+const EPOCH_TICK: Duration = Duration::from_millis(100);
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+const MAX_TABLE_ELEMENTS: u32 = 10_000;
+
+/// All UDFs share an engine, whose epoch is advanced by a background thread.
+static ENGINE: Lazy<Result<Engine, wasmtime::Error>> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.consume_fuel(true).epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+
+    let ticker = engine.clone();
+    std::thread::Builder::new()
+        .name("wasm-udf-epoch".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(EPOCH_TICK);
+            ticker.increment_epoch();
+        })?;
+    Ok(engine)
+});
+
+/// Loads a WebAssembly module and wraps one of its exports as a UDF.
+///
+/// The module is instantiated without any imports, so it has no access to the host: no
+/// filesystem, network or clock. Only numeric types can cross the boundary: `int` and `uint`
+/// are passed as `i64`, `float` as `f64` and `boolean` as `i32`. `uint` values that don't fit
+/// in an `i64` are rejected.
+///
+/// Each call is limited in fuel and in wall clock time, and the module's memories and tables
+/// are capped, so a faulty UDF fails with an error instead of stalling the pipeline.
+pub(crate) fn load_wasm_udf(
+    config: &UdfConfig,
+    arg_types: Vec<FieldType>,
+    return_type: FieldType,
+) -> Result<ScalarUdf, PipelineError> {
+    let export = config.export.as_deref().unwrap_or(config.name.as_str());
+
+    let engine = ENGINE.as_ref().map_err(|e| {
+        PipelineError::InvalidUdf(
+            config.name.clone(),
+            format!("failed to create WebAssembly engine: {e}"),
+        )
+    })?;
+    let module = Module::from_file(engine, &config.path)?;
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .table_elements(MAX_TABLE_ELEMENTS)
+        .instances(1)
+        .build();
+    let mut store = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    // Module start functions run during instantiation, so they are limited too
+    store.add_fuel(FUEL_PER_CALL)?;
+    store.set_epoch_deadline(EPOCH_DEADLINE_TICKS);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let func = instance.get_func(&mut store, export).ok_or_else(|| {
+        PipelineError::InvalidUdf(
+            config.name.clone(),
+            format!("{} does not export a function named {export}", config.path),
+        )
+    })?;
+
+    let expected_params = arg_types
+        .iter()
+        .map(|typ| to_wasm_type(&config.name, *typ))
+        .collect::<Result<Vec<_>, PipelineError>>()?;
+    let expected_result = to_wasm_type(&config.name, return_type)?;
+    let func_type = func.ty(&store);
+    if !func_type.params().eq(expected_params) || !func_type.results().eq([expected_result]) {
+        return Err(PipelineError::InvalidUdf(
+            config.name.clone(),
+            format!("signature of {export} does not match the declared argument and return types"),
+        ));
+    }
+
+    let name = config.name.clone();
+    let store = Mutex::new(store);
+    Ok(ScalarUdf::new(
+        &config.name,
+        arg_types,
+        return_type,
+        move |args| {
+            let params = args
+                .iter()
+                .enumerate()
+                .map(|(idx, arg)| to_wasm_value(&name, arg, idx))
+                .collect::<Result<Vec<_>, PipelineError>>()?;
+            let mut results = [Val::I32(0)];
+            let mut store = store.lock().unwrap_or_else(PoisonError::into_inner);
+            // Refill the fuel left over by the previous call
+            let remaining = store.consume_fuel(0)?;
+            store.add_fuel(FUEL_PER_CALL.saturating_sub(remaining))?;
+            store.set_epoch_deadline(EPOCH_DEADLINE_TICKS);
+            func.call(&mut *store, &params, &mut results)?;
+            from_wasm_value(&name, &results[0], return_type)
+        },
+    ))
+}
+
+fn to_wasm_type(name: &str, typ: FieldType) -> Result<ValType, PipelineError> {
+    match typ {
+        FieldType::Int | FieldType::UInt => Ok(ValType::I64),
+        FieldType::Float => Ok(ValType::F64),
+        FieldType::Boolean => Ok(ValType::I32),
+        _ => Err(PipelineError::InvalidUdf(
+            name.to_string(),
+            format!("type {typ} is not supported by WebAssembly UDFs"),
+        )),
+    }
+}
+
+fn to_wasm_value(name: &str, field: &Field, idx: usize) -> Result<Val, PipelineError> {
+    match field {
+        Field::Int(i) => Ok(Val::I64(*i)),
+        Field::UInt(u) => i64::try_from(*u).map(Val::I64).map_err(|_| {
+            PipelineError::InvalidFunctionArgument(name.to_string(), field.clone(), idx)
+        }),
+        Field::Float(f) => Ok(Val::from(f.0)),
+        Field::Boolean(b) => Ok(Val::I32(*b as i32)),
+        _ => Err(PipelineError::InvalidFunctionArgument(
+            name.to_string(),
+            field.clone(),
+            idx,
+        )),
+    }
+}
+
+// The export's signature is checked when the module is loaded, so the result always matches.
+fn from_wasm_value(
+    name: &str,
+    value: &Val,
+    return_type: FieldType,
+) -> Result<Field, PipelineError> {
+    match (return_type, value) {
+        (FieldType::Int, Val::I64(i)) => Ok(Field::Int(*i)),
+        (FieldType::UInt, Val::I64(i)) => u64::try_from(*i).map(Field::UInt).map_err(|_| {
+            PipelineError::InvalidUdf(
+                name.to_string(),
+                format!("returned {i}, which is not a valid uint"),
+            )
+        }),
+        (FieldType::Float, Val::F64(_)) => Ok(Field::Float(OrderedFloat(value.unwrap_f64()))),
+        (FieldType::Boolean, Val::I32(b)) => Ok(Field::Boolean(*b != 0)),
+        _ => unreachable!("unsupported WebAssembly UDF result {value:?}"),
+    }
+}
@@ -7,5 +7,7 @@ mod product;
 mod projection;
 mod selection;
 
+pub use expression::udf;
+
 #[cfg(test)]
 mod tests;
@@ -55,11 +55,15 @@ fn test_basic_projection() {
                         Expression::Column { index: 0 },
                         Expression::Literal(Field::Int(2))
                     ]
-                }]
+                }],
+                distinct: false,
+                filter: None,
             },
             Expression::AggregateFunction {
                 fun: AggregateFunctionType::Sum,
-                args: vec![Expression::Column { index: 1 }]
+                args: vec![Expression::Column { index: 1 }],
+                distinct: false,
+                filter: None,
             }
         ]
     );
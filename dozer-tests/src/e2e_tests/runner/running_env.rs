@@ -303,6 +303,31 @@ fn write_docker_compose(path: &Path, services: HashMap<String, Service>) {
         .unwrap_or_else(|e| panic!("Failed to write docker compose file {path:?}: {e}"));
 }
 
+/// Points a postgres `key=value` connection string to the service named `host`.
+fn map_postgres_connection_string(
+    connection_string: &str,
+    host: &str,
+    map_port: impl Fn(u16) -> u16,
+) -> String {
+    let mut port = 5432;
+    let mut params = vec![];
+    for param in connection_string.split_whitespace() {
+        match param.split_once('=') {
+            Some(("host", _)) => (),
+            Some(("port", value)) => {
+                port = value
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid port {value} in {connection_string}: {e}"))
+            }
+            Some(_) => params.push(param),
+            None => panic!(
+                "Only key=value postgres connection strings are supported, got {connection_string}"
+            ),
+        }
+    }
+    format!("host={host} port={} {}", map_port(port), params.join(" "))
+}
+
 fn write_dozer_config_for_running_in_docker_compose(
     mut config: Config,
     connections: &HashMap<String, Connection>,
@@ -349,6 +374,19 @@ fn write_dozer_config_for_running_in_docker_compose(
             }
             ConnectionConfig::S3Storage(_) => {}
             ConnectionConfig::LocalStorage(_) => {}
+            ConnectionConfig::SqlPolling(sql_polling) => match sql_polling.driver.as_str() {
+                "postgres" => {
+                    sql_polling.connection_string = map_postgres_connection_string(
+                        &sql_polling.connection_string,
+                        &connection.name,
+                        &map_port,
+                    );
+                }
+                driver => panic!(
+                    "SQL polling connection {} uses driver {driver}, which can't run in docker compose",
+                    connection.name
+                ),
+            },
         }
     }
 
@@ -21,7 +21,7 @@ async fn test_cache_query() {
         IndexDefinition::SortedInverted(vec![3, 7, 0]),
         IndexDefinition::SortedInverted(vec![5, 0]),
         IndexDefinition::SortedInverted(vec![7, 0]),
-        IndexDefinition::FullText(12),
+        IndexDefinition::FullText(12, Default::default()),
     ];
 
     let (cache, schema_name, collection) = load_database(secondary_indexes).await;
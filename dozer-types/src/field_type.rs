@@ -1,4 +1,4 @@
-use crate::types::{DozerPoint, Field};
+use crate::types::{DozerBigInt, DozerPoint, Field};
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc};
 use geo::Point;
 use ordered_float::OrderedFloat;
@@ -112,6 +112,12 @@ impl From<NaiveTime> for Field {
     }
 }
 
+impl From<DozerBigInt> for Field {
+    fn from(value: DozerBigInt) -> Self {
+        Field::BigInt(value)
+    }
+}
+
 impl From<Point<OrderedFloat<f64>>> for Field {
     fn from(value: Point<OrderedFloat<f64>>) -> Self {
         Field::Point(DozerPoint(value))
@@ -1,10 +1,10 @@
 use crate::errors::types::{DeserializationError, TypeError};
-use crate::types::{DozerPoint, DATE_FORMAT};
+use crate::types::{DozerBigInt, DozerDuration, DozerPoint, DATE_FORMAT};
 use crate::types::{Field, FieldType};
-use chrono::{DateTime, NaiveDate};
+use chrono::{DateTime, NaiveDate, SecondsFormat};
 use ordered_float::OrderedFloat;
 use rust_decimal::Decimal;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::str::FromStr;
 
 /// Used in REST APIs and query expressions for converting JSON value to `Field`
@@ -44,6 +44,11 @@ pub fn json_value_to_field(
         (FieldType::Decimal, Value::String(str)) => return Field::from_str(str, typ, nullable),
         (FieldType::Timestamp, Value::String(str)) => return Field::from_str(str, typ, nullable),
         (FieldType::Date, Value::String(str)) => return Field::from_str(str, typ, nullable),
+        (FieldType::Duration, Value::String(str)) => return Field::from_str(str, typ, nullable),
+        (FieldType::BigInt, Value::String(str)) => return Field::from_str(str, typ, nullable),
+        (FieldType::BigInt, Value::Number(number)) => {
+            return Field::from_str(&number.to_string(), typ, nullable)
+        }
         (FieldType::Bson, _) => serde_json::from_value(value)
             .map_err(DeserializationError::Json)
             .map(Field::Bson),
@@ -59,6 +64,36 @@ pub fn json_value_to_field(
     .map_err(TypeError::DeserializationError)
 }
 
+fn convert_x_y_to_object((x, y): &(OrderedFloat<f64>, OrderedFloat<f64>)) -> Value {
+    let mut m = Map::new();
+    m.insert("x".to_string(), Value::from(x.0));
+    m.insert("y".to_string(), Value::from(y.0));
+    Value::Object(m)
+}
+
+/// Used in REST APIs and sinks for converting `Field` to JSON value.
+///
+/// Should be consistent with `json_value_to_field`.
+pub fn field_to_json_value(field: Field) -> Value {
+    match field {
+        Field::UInt(n) => Value::from(n),
+        Field::Int(n) => Value::from(n),
+        Field::Float(n) => Value::from(n.0),
+        Field::Boolean(b) => Value::from(b),
+        Field::String(s) => Value::from(s),
+        Field::Text(n) => Value::from(n),
+        Field::Binary(b) => Value::from(b),
+        Field::Decimal(n) => Value::String(n.to_string()),
+        Field::Timestamp(ts) => Value::String(ts.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Field::Date(n) => Value::String(n.format(DATE_FORMAT).to_string()),
+        Field::Bson(b) => Value::from(b),
+        Field::Point(point) => convert_x_y_to_object(&point.0.x_y()),
+        Field::Duration(d) => Value::String(d.to_string()),
+        Field::BigInt(i) => Value::String(i.to_string()),
+        Field::Null => Value::Null,
+    }
+}
+
 impl Field {
     pub fn from_str(value: &str, typ: FieldType, nullable: bool) -> Result<Field, TypeError> {
         match typ {
@@ -186,6 +221,20 @@ impl Field {
                     value.parse::<DozerPoint>().map(Field::Point)
                 }
             }
+            FieldType::Duration => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerDuration>().map(Field::Duration)
+                }
+            }
+            FieldType::BigInt => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    value.parse::<DozerBigInt>().map(Field::BigInt)
+                }
+            }
         }
     }
 }
@@ -246,6 +295,30 @@ mod tests {
                 false,
                 Field::Point(DozerPoint(Point::new(OrderedFloat(1.0), OrderedFloat(1.0)))),
             ),
+            (
+                "P1Y2M3DT4H5M6.5S",
+                FieldType::Duration,
+                false,
+                Field::Duration(DozerDuration::new(14, 3, 14_706_500_000_000)),
+            ),
+            (
+                "PT-1M-0.25S",
+                FieldType::Duration,
+                false,
+                Field::Duration(DozerDuration::from_nanos(-60_250_000_000)),
+            ),
+            (
+                "-57896044618658097711785492504343953926634992332820282019728792003956564819968",
+                FieldType::BigInt,
+                false,
+                Field::BigInt(
+                    DozerBigInt::from_signed_bytes_be(&[
+                        0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                        0, 0, 0, 0, 0, 0, 0, 0,
+                    ])
+                    .unwrap(),
+                ),
+            ),
             ("null", FieldType::UInt, true, Field::Null),
             ("null", FieldType::Int, true, Field::Null),
             ("null", FieldType::Float, true, Field::Null),
@@ -268,6 +341,8 @@ mod tests {
             ("null", FieldType::Date, true, Field::Null),
             ("null", FieldType::Bson, true, Field::Null),
             ("null", FieldType::Point, true, Field::Null),
+            ("null", FieldType::Duration, true, Field::Null),
+            ("null", FieldType::BigInt, true, Field::Null),
             ("", FieldType::UInt, true, Field::Null),
             ("", FieldType::Int, true, Field::Null),
             ("", FieldType::Float, true, Field::Null),
@@ -280,6 +355,8 @@ mod tests {
             ("", FieldType::Date, true, Field::Null),
             ("", FieldType::Bson, true, Field::Null),
             ("", FieldType::Point, true, Field::Null),
+            ("", FieldType::Duration, true, Field::Null),
+            ("", FieldType::BigInt, true, Field::Null),
         ];
 
         for case in ok_cases {
@@ -297,6 +374,16 @@ mod tests {
             ("null", FieldType::Date, false),
             ("null", FieldType::Bson, false),
             ("null", FieldType::Point, false),
+            ("null", FieldType::Duration, false),
+            ("P1H", FieldType::Duration, false),
+            ("PT1.0000000001S", FieldType::Duration, false),
+            ("null", FieldType::BigInt, false),
+            ("1.5", FieldType::BigInt, false),
+            (
+                "14821387422376473014217086081112052205218558037201992197050570753012880593911808",
+                FieldType::BigInt,
+                false,
+            ),
             ("", FieldType::UInt, false),
             ("", FieldType::Int, false),
             ("", FieldType::Float, false),
@@ -307,9 +394,29 @@ mod tests {
             ("", FieldType::Date, false),
             ("", FieldType::Bson, false),
             ("", FieldType::Point, false),
+            ("", FieldType::Duration, false),
+            ("", FieldType::BigInt, false),
         ];
         for err_case in err_cases {
             assert!(Field::from_str(err_case.0, err_case.1, err_case.2).is_err());
         }
     }
+
+    #[test]
+    fn test_duration_to_string_roundtrip() {
+        for duration in [
+            DozerDuration::default(),
+            DozerDuration::new(14, 3, 14_706_500_000_000),
+            DozerDuration::new(-1, 0, 0),
+            DozerDuration::from_nanos(-60_250_000_000),
+            DozerDuration::from_nanos(1),
+        ] {
+            let str = duration.to_string();
+            assert_eq!(str.parse::<DozerDuration>().unwrap(), duration, "{str}");
+        }
+        assert_eq!(
+            DozerDuration::new(14, 3, 14_706_500_000_000).to_string(),
+            "P1Y2M3DT4H5M6.5S"
+        );
+    }
 }
@@ -10,6 +10,9 @@ use crate::{errors::internal::BoxedError, node::OpIdentifier, types::Operation};
 pub struct IngestionMessage {
     pub identifier: OpIdentifier,
     pub kind: IngestionMessageKind,
+    /// State the source needs to resume after this message. It's persisted with the checkpoint
+    /// and given back to the source on restart.
+    pub state: Option<Vec<u8>>,
 }
 
 impl IngestionMessage {
@@ -17,6 +20,7 @@ impl IngestionMessage {
         Self {
             identifier: OpIdentifier::new(txn, seq_no),
             kind: IngestionMessageKind::OperationEvent(op),
+            state: None,
         }
     }
 
@@ -24,8 +28,14 @@ impl IngestionMessage {
         Self {
             identifier: OpIdentifier::new(txn, seq_no),
             kind: IngestionMessageKind::SnapshottingDone,
+            state: None,
         }
     }
+
+    pub fn with_state(mut self, state: Vec<u8>) -> Self {
+        self.state = Some(state);
+        self
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -110,6 +120,10 @@ pub struct EthLogConfig {
     #[prost(message, repeated, tag = "3")]
     #[serde(default)]
     pub contracts: Vec<EthContract>,
+    /// number of blocks a log's block must be buried under before the log is forwarded; Default: 0
+    #[prost(uint64, tag = "4", default = "0")]
+    #[serde(default)]
+    pub confirmations: u64,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
@@ -172,10 +186,26 @@ pub struct KafkaConfig {
     pub broker: String,
     #[prost(string, optional, tag = "3")]
     pub schema_registry_url: Option<String>,
+    /// Format of the message values. Debezium envelopes are expected when not set.
+    #[prost(oneof = "KafkaFormat", tags = "4,5")]
+    pub format: Option<KafkaFormat>,
+    /// Name of the column holding the message key when `format` is set.
+    #[prost(string, tag = "6", default = "key")]
+    #[serde(default = "default_kafka_key_column")]
+    pub key_column: String,
+}
+
+fn default_kafka_key_column() -> String {
+    "key".to_owned()
 }
 
 impl KafkaConfig {
     pub fn convert_to_table(&self) -> PrettyTable {
+        let format = match &self.format {
+            None => "debezium",
+            Some(KafkaFormat::Json(_)) => "json",
+            Some(KafkaFormat::Protobuf(_)) => "protobuf",
+        };
         table!(
             ["broker", self.broker],
             [
@@ -183,11 +213,54 @@ impl KafkaConfig {
                 self.schema_registry_url
                     .as_ref()
                     .map_or("--------", |url| url)
-            ]
+            ],
+            ["format", format],
+            ["key column", self.key_column]
         )
     }
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof, Hash)]
+pub enum KafkaFormat {
+    #[prost(message, tag = "4")]
+    Json(KafkaJsonFormat),
+    #[prost(message, tag = "5")]
+    Protobuf(KafkaProtobufFormat),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct KafkaJsonFormat {
+    /// Fields read from the top level object of every message
+    #[prost(message, repeated, tag = "1")]
+    pub fields: Vec<KafkaJsonField>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct KafkaJsonField {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    /// One of the field types, e.g. `int`, `string` or `timestamp`
+    #[prost(string, tag = "2")]
+    pub typ: String,
+    #[prost(bool, tag = "3", default = "true")]
+    #[serde(default = "default_kafka_json_field_nullable")]
+    pub nullable: bool,
+}
+
+fn default_kafka_json_field_nullable() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct KafkaProtobufFormat {
+    /// Path of a `FileDescriptorSet`, as produced by `protoc --descriptor_set_out`
+    #[prost(string, tag = "1")]
+    pub descriptor_path: String,
+    /// Fully qualified name of the message type of the values
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
 pub struct SnowflakeConfig {
     #[prost(string, tag = "1")]
@@ -206,6 +279,14 @@ pub struct SnowflakeConfig {
     pub warehouse: String,
     #[prost(string, optional, tag = "8")]
     pub driver: Option<String>,
+    /// interval in seconds between polls of the table streams; Default: 5
+    #[prost(uint64, tag = "9", default = "5")]
+    #[serde(default = "default_snowflake_poll_interval")]
+    pub poll_interval_seconds: u64,
+}
+
+fn default_snowflake_poll_interval() -> u64 {
+    5
 }
 
 impl SnowflakeConfig {
@@ -218,7 +299,52 @@ impl SnowflakeConfig {
             ["database", self.database],
             ["schema", self.schema],
             ["warehouse", self.warehouse],
-            ["driver", self.driver.as_ref().map_or("default", |d| d)]
+            ["driver", self.driver.as_ref().map_or("default", |d| d)],
+            ["poll_interval_seconds", self.poll_interval_seconds]
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message, Hash)]
+pub struct SqlPollingConfig {
+    /// database driver: `postgres`, `sqlite` or `odbc`
+    #[prost(string, tag = "1")]
+    pub driver: String,
+    /// connection string of the driver: a postgres connection string, a sqlite file path or an odbc connection string
+    #[prost(string, tag = "2")]
+    pub connection_string: String,
+    /// name of the table the query results are exposed as
+    #[prost(string, tag = "3")]
+    pub table_name: String,
+    /// query whose results are polled, e.g. `SELECT id, name, updated_at FROM users`
+    #[prost(string, tag = "4")]
+    pub query: String,
+    /// monotonically increasing integer or timestamp column of the query, e.g. `updated_at`
+    #[prost(string, tag = "5")]
+    pub cursor_column: String,
+    /// columns identifying a row; rows are upserted by these columns
+    #[prost(string, repeated, tag = "6")]
+    pub primary_key: Vec<String>,
+    /// interval in seconds between polls; Default: 5
+    #[prost(uint64, tag = "7", default = "5")]
+    #[serde(default = "default_sql_polling_interval")]
+    pub poll_interval_seconds: u64,
+}
+
+fn default_sql_polling_interval() -> u64 {
+    5
+}
+
+impl SqlPollingConfig {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        table!(
+            ["driver", self.driver],
+            ["connection_string", "************"],
+            ["table_name", self.table_name],
+            ["query", self.query],
+            ["cursor_column", self.cursor_column],
+            ["primary_key", self.primary_key.join(", ")],
+            ["poll_interval_seconds", self.poll_interval_seconds]
         )
     }
 }
@@ -11,7 +11,7 @@ pub mod types;
 // Export grpc types
 pub mod grpc_types;
 
-pub use helper::json_value_to_field;
+pub use helper::{field_to_json_value, json_value_to_field};
 
 // Re-exports
 pub use bincode;
@@ -22,6 +22,7 @@ pub use geo;
 pub use indexmap;
 pub use indicatif;
 pub use log;
+pub use num_bigint;
 pub use ordered_float;
 pub use parking_lot;
 pub use prost;
@@ -1,3 +1,5 @@
+use crate::ingestion_types::{LocalDetails, S3Details};
+use crate::models::connection::PostgresConfig;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
@@ -5,6 +7,46 @@ use serde::{Deserialize, Serialize};
 pub struct ApiIndex {
     #[prost(string, repeated, tag = "1")]
     pub primary_key: Vec<String>,
+    #[prost(message, repeated, tag = "2")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// analyzers of the full text indexes; string fields without one split text into words as they are
+    pub full_text: Vec<ApiFullTextIndex>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ApiFullTextIndex {
+    #[prost(string, tag = "1")]
+    /// string or text field the index is created on
+    pub field: String,
+    #[prost(bool, tag = "2")]
+    #[serde(default)]
+    pub lowercase: bool,
+    #[prost(bool, tag = "3")]
+    #[serde(default)]
+    /// applies unicode NFKC normalization
+    pub normalize: bool,
+    #[prost(string, optional, tag = "4")]
+    #[serde(default)]
+    /// `english`, `french`, `german` or `spanish`: removes the stop words of the language
+    pub stop_words: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    #[serde(default)]
+    /// `english`, `french`, `german` or `spanish`: reduces words to their stem in the language
+    pub stemming: Option<String>,
+    #[prost(message, optional, tag = "6")]
+    #[serde(default)]
+    /// also indexes word prefixes, so that filter words match the words they start
+    pub prefix_search: Option<ApiPrefixSearch>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ApiPrefixSearch {
+    #[prost(uint32, tag = "1")]
+    /// shortest indexed prefix, in characters
+    pub min_len: u32,
+    #[prost(uint32, tag = "2")]
+    /// longest indexed prefix, in characters
+    pub max_len: u32,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
@@ -20,6 +62,16 @@ pub struct ApiEndpoint {
     pub path: String,
     #[prost(message, tag = "4")]
     pub index: Option<ApiIndex>,
+
+    #[prost(message, repeated, tag = "5")]
+    #[serde(default)]
+    /// sinks the endpoint is published to, in addition to the cache
+    pub sinks: Vec<ApiSink>,
+
+    #[prost(message, optional, tag = "6")]
+    #[serde(default)]
+    /// deletes old records of an endpoint without primary key from the cache
+    pub retention: Option<ApiRetention>,
 }
 
 impl Serialize for ApiEndpoint {
@@ -27,15 +79,159 @@ impl Serialize for ApiEndpoint {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("ApiEndpoint", 4)?;
+        let mut state = serializer.serialize_struct("ApiEndpoint", 6)?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("table_name", &self.table_name)?;
         state.serialize_field("path", &self.path)?;
         state.serialize_field("index", &self.index)?;
+        if self.sinks.is_empty() {
+            state.skip_field("sinks")?;
+        } else {
+            state.serialize_field("sinks", &self.sinks)?;
+        }
+        if self.retention.is_none() {
+            state.skip_field("retention")?;
+        } else {
+            state.serialize_field("retention", &self.retention)?;
+        }
 
         state.end()
     }
 }
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ApiRetention {
+    #[prost(message, optional, tag = "1")]
+    #[serde(default)]
+    /// deletes records whose timestamp field is older than the given age
+    pub max_age: Option<ApiRetentionMaxAge>,
+    #[prost(uint64, optional, tag = "2")]
+    #[serde(default)]
+    /// keeps only the newest records up to this count
+    pub max_records: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ApiRetentionMaxAge {
+    #[prost(string, tag = "1")]
+    /// timestamp field records expire by
+    pub field: String,
+    #[prost(uint64, tag = "2")]
+    pub seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ApiSink {
+    #[prost(oneof = "SinkConfig", tags = "1, 2, 3")]
+    pub config: Option<SinkConfig>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum SinkConfig {
+    #[prost(message, tag = "1")]
+    /// In yaml, present as tag: `!Kafka`
+    Kafka(KafkaSinkConfig),
+    #[prost(message, tag = "2")]
+    /// In yaml, present as tag: `!ObjectStore`
+    ObjectStore(ObjectStoreSinkConfig),
+    #[prost(message, tag = "3")]
+    /// In yaml, present as tag: `!Postgres`
+    Postgres(PostgresSinkConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct KafkaSinkConfig {
+    #[prost(string, tag = "1")]
+    pub broker: String,
+    #[prost(string, tag = "2")]
+    /// Topic the changes are published to. The checkpoint of the last published epoch is stored in
+    /// the compacted `__dozer_sink_checkpoints_<topic>` topic, which is created if missing.
+    pub topic: String,
+    #[prost(enumeration = "KafkaSinkFormat", tag = "3")]
+    #[serde(default, with = "kafka_sink_format")]
+    /// `debezium` for before/after envelopes, `upsert` for values keyed by primary key and tombstones
+    pub format: i32,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ::prost::Enumeration,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(i32)]
+pub enum KafkaSinkFormat {
+    /// Values are `{"before", "after", "op"}` envelopes, like the payload of Debezium messages.
+    Debezium = 0,
+    /// Values are the records, and deletes are tombstones.
+    Upsert = 1,
+}
+
+mod kafka_sink_format {
+    use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::KafkaSinkFormat;
+
+    pub fn serialize<S: Serializer>(format: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+        KafkaSinkFormat::from_i32(*format)
+            .ok_or_else(|| S::Error::custom(format!("Invalid kafka sink format {format}")))?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+        KafkaSinkFormat::deserialize(deserializer).map(|format| format as i32)
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct ObjectStoreSinkConfig {
+    #[prost(oneof = "ObjectStoreSinkStorage", tags = "1, 2")]
+    pub storage: Option<ObjectStoreSinkStorage>,
+    #[prost(string, tag = "3")]
+    #[serde(default)]
+    /// path of the written files in the store
+    pub prefix: String,
+    #[prost(string, tag = "4", default = "hour")]
+    #[serde(default = "default_partition_by")]
+    /// `hour`, `day` or `none`: how change files are partitioned by commit time
+    pub partition_by: String,
+    #[prost(uint64, tag = "5")]
+    #[serde(default)]
+    /// writes a compacted snapshot of the endpoint every `snapshot_every` commits; 0 to disable
+    pub snapshot_every: u64,
+}
+
+fn default_partition_by() -> String {
+    "hour".to_owned()
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct PostgresSinkConfig {
+    #[prost(message, optional, tag = "1")]
+    pub connection: Option<PostgresConfig>,
+    #[prost(string, tag = "2")]
+    #[serde(default)]
+    /// table the endpoint is mirrored to; defaults to the endpoint name
+    pub table_name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum ObjectStoreSinkStorage {
+    #[prost(message, tag = "1")]
+    /// In yaml, present as tag: `!S3`
+    S3(S3Details),
+    #[prost(message, tag = "2")]
+    /// In yaml, present as tag: `!Local`
+    Local(LocalDetails),
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub enum Value {
     Ref(String),
@@ -1,6 +1,6 @@
 use super::{
     api_config::ApiConfig, api_endpoint::ApiEndpoint, connection::Connection, flags::Flags,
-    source::Source,
+    source::Source, udf_config::UdfConfig,
 };
 use crate::{constants::DEFAULT_HOME_DIR, models::api_config::default_api_config};
 use serde::{
@@ -71,6 +71,16 @@ pub struct Config {
     #[prost(uint64, optional, tag = "14")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub commit_timeout: Option<u64>,
+
+    /// scalar user defined functions to load before building the SQL pipeline
+    #[prost(message, repeated, tag = "15")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub udfs: Vec<UdfConfig>,
+
+    /// Max number of records a cache query can scan when no index fully answers it
+    #[prost(uint64, optional, tag = "16")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_max_scanned_rows: Option<u64>,
 }
 
 pub fn default_home_dir() -> String {
@@ -81,6 +91,10 @@ pub fn default_cache_max_map_size() -> u64 {
     1024 * 1024 * 1024 * 1024
 }
 
+pub fn default_cache_max_scanned_rows() -> u64 {
+    100_000
+}
+
 pub fn default_app_max_map_size() -> u64 {
     1024 * 1024 * 1024 * 1024
 }
@@ -130,6 +144,9 @@ impl<'de> Deserialize<'de> for Config {
                 let mut app_buffer_size: Option<u32> = Some(default_app_buffer_size());
                 let mut commit_size: Option<u32> = Some(default_commit_size());
                 let mut commit_timeout: Option<u64> = Some(default_commit_timeout());
+                let mut udfs: Vec<UdfConfig> = vec![];
+                let mut cache_max_scanned_rows: Option<u64> =
+                    Some(default_cache_max_scanned_rows());
 
                 while let Some(key) = access.next_key()? {
                     match key {
@@ -172,6 +189,12 @@ impl<'de> Deserialize<'de> for Config {
                         "commit_timeout" => {
                             commit_timeout = access.next_value::<Option<u64>>()?;
                         }
+                        "udfs" => {
+                            udfs = access.next_value::<Vec<UdfConfig>>()?;
+                        }
+                        "cache_max_scanned_rows" => {
+                            cache_max_scanned_rows = access.next_value::<Option<u64>>()?;
+                        }
                         _ => {
                             access.next_value::<IgnoredAny>()?;
                         }
@@ -242,6 +265,8 @@ impl<'de> Deserialize<'de> for Config {
                     app_buffer_size,
                     commit_size,
                     commit_timeout,
+                    udfs,
+                    cache_max_scanned_rows,
                 })
             }
         }
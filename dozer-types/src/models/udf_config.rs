@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, prost::Message)]
+/// A scalar user defined function compiled to WebAssembly
+pub struct UdfConfig {
+    /// name of the function as used in SQL
+    #[prost(string, tag = "1")]
+    pub name: String,
+
+    /// path to the compiled WebAssembly module
+    #[prost(string, tag = "2")]
+    pub path: String,
+
+    /// name of the exported function in the module; Default: same as name
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+
+    /// argument types, in order. Eg: [int, float]
+    #[prost(string, repeated, tag = "4")]
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// type of the returned value. Eg: float
+    #[prost(string, tag = "5")]
+    pub return_type: String,
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod api_config_yaml_deserialize;
 #[cfg(test)]
+mod big_int_test;
+#[cfg(test)]
 mod dozer_yaml_deserialize;
 #[cfg(test)]
 mod eth_yaml_deserialize;
@@ -10,3 +12,5 @@ mod field_serialize_test;
 mod flags_config_yaml_deserialize;
 #[cfg(test)]
 mod postgres_yaml_deserialize;
+#[cfg(test)]
+mod udf_config_yaml_deserialize;
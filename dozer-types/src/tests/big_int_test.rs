@@ -0,0 +1,87 @@
+use crate::types::{DozerBigInt, Field};
+
+fn big_int(value: &str) -> DozerBigInt {
+    value.parse().unwrap()
+}
+
+const U256_MAX: &str =
+    "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+const I256_MIN: &str =
+    "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+
+#[test]
+fn test_big_int_covers_uint256_and_int256() {
+    let max = DozerBigInt::from_unsigned_bytes_be(&[0xff; 32]).unwrap();
+    assert_eq!(max.to_string(), U256_MAX);
+    assert_eq!(max, big_int(U256_MAX));
+
+    let mut min_bytes = [0_u8; 32];
+    min_bytes[0] = 0x80;
+    let min = DozerBigInt::from_signed_bytes_be(&min_bytes).unwrap();
+    assert_eq!(min.to_string(), I256_MIN);
+
+    let minus_one = DozerBigInt::from_signed_bytes_be(&[0xff; 32]).unwrap();
+    assert_eq!(minus_one, DozerBigInt::from(-1_i64));
+    assert!(minus_one.is_negative());
+}
+
+#[test]
+fn test_big_int_ordering_matches_encoding() {
+    let values = [
+        big_int(I256_MIN),
+        DozerBigInt::from(i64::MIN),
+        DozerBigInt::from(-1_i64),
+        DozerBigInt::from(0_u64),
+        DozerBigInt::from(1_u64),
+        DozerBigInt::from(u64::MAX),
+        big_int(U256_MAX),
+    ];
+    for pair in values.windows(2) {
+        assert!(pair[0] < pair[1]);
+        assert!(Field::BigInt(pair[0]).encode() < Field::BigInt(pair[1]).encode());
+        assert!(pair[0].to_bigint() < pair[1].to_bigint());
+    }
+}
+
+#[test]
+fn test_big_int_arithmetic() {
+    let max = big_int(U256_MAX);
+    let one = DozerBigInt::from(1_u64);
+    assert_eq!(
+        max.checked_add(&one).unwrap().to_string(),
+        "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+    );
+    assert_eq!(max.checked_mul(&max), None);
+    assert_eq!(one.checked_div(&DozerBigInt::from(0_u64)), None);
+    assert_eq!(
+        DozerBigInt::from(-7_i64).checked_div(&DozerBigInt::from(2_i64)),
+        Some(DozerBigInt::from(-3_i64))
+    );
+    assert_eq!(
+        DozerBigInt::from(-7_i64).checked_rem(&DozerBigInt::from(2_i64)),
+        Some(DozerBigInt::from(-1_i64))
+    );
+}
+
+#[test]
+fn test_big_int_conversions() {
+    let field = Field::BigInt(DozerBigInt::from(-42_i64));
+    assert_eq!(field.to_int(), Some(-42));
+    assert_eq!(field.to_uint(), None);
+    assert_eq!(field.to_float(), Some(-42.0));
+    assert_eq!(field.to_string(), Some("-42".to_string()));
+
+    let field = Field::BigInt(big_int(U256_MAX));
+    assert_eq!(field.to_int(), None);
+    assert_eq!(field.to_decimal(), None);
+    assert_eq!(
+        Field::String(U256_MAX.to_string()).to_bigint(),
+        Some(big_int(U256_MAX))
+    );
+
+    // Serialized as a string so JSON consumers keep every digit
+    assert_eq!(
+        serde_json::to_string(&big_int(U256_MAX)).unwrap(),
+        format!("\"{U256_MAX}\"")
+    );
+}
@@ -27,6 +27,7 @@ fn standard() {
                 filter: Some(expected_eth_filter),
                 wss_url: "wss://link".to_owned(),
                 contracts: vec![],
+                confirmations: 0,
             },
         )),
     };
@@ -56,6 +57,7 @@ fn config_without_empty_array() {
                 wss_url: "wss://link".to_owned(),
                 filter: Some(expected_eth_filter),
                 contracts: vec![],
+                confirmations: 0,
             },
         )),
     };
@@ -1,4 +1,8 @@
-use crate::types::{field_test_cases, Field};
+use chrono::{DateTime, NaiveDate};
+use ordered_float::OrderedFloat;
+use rust_decimal::Decimal;
+
+use crate::types::{field_test_cases, DozerBigInt, DozerDuration, DozerPoint, Field};
 
 #[test]
 fn test_field_serialize_roundtrip() {
@@ -42,3 +46,35 @@ fn encoding_len_must_agree_with_encode() {
         assert_eq!(bytes.len(), field.encoding_len());
     }
 }
+
+#[test]
+fn field_type_prefixes_must_not_change() {
+    // Prefixes and bincode tags are persisted, so existing values must never be reassigned.
+    let cases = [
+        (Field::UInt(0), 0),
+        (Field::Int(0), 1),
+        (Field::Float(OrderedFloat(0.0)), 2),
+        (Field::Boolean(false), 3),
+        (Field::String(String::new()), 4),
+        (Field::Text(String::new()), 5),
+        (Field::Binary(vec![]), 6),
+        (Field::Decimal(Decimal::new(0, 0)), 7),
+        (
+            Field::Timestamp(DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap()),
+            8,
+        ),
+        (Field::Date(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()), 9),
+        (Field::Bson(vec![]), 10),
+        (Field::Point(DozerPoint::from((0.0, 0.0))), 11),
+        (Field::Null, 12),
+        (Field::Duration(DozerDuration::default()), 13),
+        (Field::BigInt(DozerBigInt::from(0_u64)), 14),
+    ];
+    for (field, prefix) in cases {
+        let bytes = field.encode();
+        assert_eq!(bytes[0], prefix, "{field:?}");
+        assert_eq!(Field::decode(&bytes).unwrap(), field);
+        let bytes = bincode::serialize(&field).unwrap();
+        assert_eq!(bytes[..4], (prefix as u32).to_le_bytes(), "{field:?}");
+    }
+}
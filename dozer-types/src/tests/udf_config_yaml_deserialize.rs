@@ -0,0 +1,47 @@
+use crate::models::{app_config::Config, udf_config::UdfConfig};
+
+#[test]
+fn test_udf_config() {
+    let input_config = r#"
+  app_name: working_app
+  udfs:
+    - name: haversine
+      path: ./udfs/geo.wasm
+      args: [float, float, float, float]
+      return_type: float
+    - name: add_one
+      path: ./udfs/math.wasm
+      export: increment
+      args: [int]
+      return_type: int
+"#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    assert_eq!(
+        config.udfs,
+        vec![
+            UdfConfig {
+                name: "haversine".to_string(),
+                path: "./udfs/geo.wasm".to_string(),
+                export: None,
+                args: vec!["float".to_string(); 4],
+                return_type: "float".to_string(),
+            },
+            UdfConfig {
+                name: "add_one".to_string(),
+                path: "./udfs/math.wasm".to_string(),
+                export: Some("increment".to_string()),
+                args: vec!["int".to_string()],
+                return_type: "int".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_config_without_udfs() {
+    let input_config = r#"
+  app_name: working_app
+"#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    assert!(config.udfs.is_empty());
+}
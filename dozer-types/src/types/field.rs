@@ -7,7 +7,7 @@ use rust_decimal::Decimal;
 use serde::{self, Deserialize, Serialize};
 use std::borrow::Cow;
 
-use crate::types::DozerPoint;
+use crate::types::{DozerBigInt, DozerDuration, DozerPoint};
 use std::fmt::{Display, Formatter};
 
 pub const DATE_FORMAT: &str = "%Y-%m-%d";
@@ -26,6 +26,8 @@ pub enum Field {
     Bson(Vec<u8>),
     Point(DozerPoint),
     Null,
+    Duration(DozerDuration),
+    BigInt(DozerBigInt),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
@@ -43,6 +45,8 @@ pub enum FieldBorrow<'a> {
     Bson(&'a [u8]),
     Point(DozerPoint),
     Null,
+    Duration(DozerDuration),
+    BigInt(DozerBigInt),
 }
 
 impl Field {
@@ -60,6 +64,8 @@ impl Field {
             Field::Date(_) => 10,
             Field::Bson(b) => b.len(),
             Field::Point(_p) => 16,
+            Field::Duration(_) => 16,
+            Field::BigInt(_) => 33,
             Field::Null => 0,
         }
     }
@@ -79,6 +85,8 @@ impl Field {
             Field::Bson(b) => Cow::Borrowed(b),
             Field::Null => Cow::Owned([].into()),
             Field::Point(p) => Cow::Owned(p.to_bytes().into()),
+            Field::Duration(d) => Cow::Owned(d.to_bytes().into()),
+            Field::BigInt(i) => Cow::Owned(i.to_bytes().into()),
         }
     }
 
@@ -113,6 +121,8 @@ impl Field {
             Field::Date(t) => FieldBorrow::Date(*t),
             Field::Bson(b) => FieldBorrow::Bson(b),
             Field::Point(p) => FieldBorrow::Point(*p),
+            Field::Duration(d) => FieldBorrow::Duration(*d),
+            Field::BigInt(i) => FieldBorrow::BigInt(*i),
             Field::Null => FieldBorrow::Null,
         }
     }
@@ -170,6 +180,12 @@ impl Field {
                 DozerPoint::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
             )),
             12 => Ok(FieldBorrow::Null),
+            13 => Ok(FieldBorrow::Duration(
+                DozerDuration::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
+            )),
+            14 => Ok(FieldBorrow::BigInt(
+                DozerBigInt::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
+            )),
             other => Err(DeserializationError::UnrecognisedFieldType(other)),
         }
     }
@@ -189,6 +205,8 @@ impl Field {
             Field::Bson(_) => 10,
             Field::Point(_) => 11,
             Field::Null => 12,
+            Field::Duration(_) => 13,
+            Field::BigInt(_) => 14,
         }
     }
 
@@ -276,6 +294,20 @@ impl Field {
         }
     }
 
+    pub fn as_duration(&self) -> Option<DozerDuration> {
+        match self {
+            Field::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    pub fn as_bigint(&self) -> Option<DozerBigInt> {
+        match self {
+            Field::BigInt(i) => Some(*i),
+            _ => None,
+        }
+    }
+
     pub fn as_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -287,6 +319,7 @@ impl Field {
         match self {
             Field::UInt(i) => Some(*i),
             Field::Int(i) => u64::from_i64(*i),
+            Field::BigInt(i) => i.to_u64(),
             Field::String(s) => s.parse::<u64>().ok(),
             Field::Null => Some(0_u64),
             _ => None,
@@ -297,6 +330,7 @@ impl Field {
         match self {
             Field::Int(i) => Some(*i),
             Field::UInt(u) => i64::from_u64(*u),
+            Field::BigInt(i) => i.to_i64(),
             Field::String(s) => s.parse::<i64>().ok(),
             Field::Null => Some(0_i64),
             _ => None,
@@ -309,6 +343,7 @@ impl Field {
             Field::Decimal(d) => d.to_f64(),
             Field::UInt(u) => f64::from_u64(*u),
             Field::Int(i) => f64::from_i64(*i),
+            Field::BigInt(i) => Some(i.to_f64()),
             Field::Null => Some(0_f64),
             Field::String(s) => s.parse::<f64>().ok(),
             _ => None,
@@ -323,6 +358,7 @@ impl Field {
             Field::UInt(i) => Some(*i > 0_u64),
             Field::Float(i) => Some(i.0 > 0_f64),
             Field::Decimal(i) => Some(i.gt(&Decimal::from(0_u64))),
+            Field::BigInt(i) => Some(!i.is_zero() && !i.is_negative()),
             _ => None,
         }
     }
@@ -333,6 +369,7 @@ impl Field {
             Field::Text(t) => Some(t.to_owned()),
             Field::Int(i) => Some(format!("{i}")),
             Field::UInt(i) => Some(format!("{i}")),
+            Field::BigInt(i) => Some(format!("{i}")),
             Field::Float(i) => Some(format!("{i}")),
             Field::Decimal(i) => Some(format!("{i}")),
             Field::Boolean(i) => Some(if *i {
@@ -342,6 +379,7 @@ impl Field {
             }),
             Field::Date(d) => Some(d.format("%Y-%m-%d").to_string()),
             Field::Timestamp(t) => Some(t.to_rfc3339()),
+            Field::Duration(d) => Some(d.to_string()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Null => Some("".to_string()),
             _ => None,
@@ -354,6 +392,7 @@ impl Field {
             Field::Text(t) => Some(t.to_owned()),
             Field::Int(i) => Some(format!("{i}")),
             Field::UInt(i) => Some(format!("{i}")),
+            Field::BigInt(i) => Some(format!("{i}")),
             Field::Float(i) => Some(format!("{i}")),
             Field::Decimal(i) => Some(format!("{i}")),
             Field::Boolean(i) => Some(if *i {
@@ -363,6 +402,7 @@ impl Field {
             }),
             Field::Date(d) => Some(d.format("%Y-%m-%d").to_string()),
             Field::Timestamp(t) => Some(t.to_rfc3339()),
+            Field::Duration(d) => Some(d.to_string()),
             Field::Binary(b) => Some(format!("{b:X?}")),
             Field::Null => Some("".to_string()),
             _ => None,
@@ -382,6 +422,7 @@ impl Field {
             Field::Float(f) => Decimal::from_f64_retain(f.0),
             Field::Int(i) => Decimal::from_i64(*i),
             Field::UInt(u) => Decimal::from_u64(*u),
+            Field::BigInt(i) => i.to_decimal(),
             Field::Null => Some(Decimal::from(0)),
             Field::String(s) => Decimal::from_str_exact(s).ok(),
             _ => None,
@@ -428,6 +469,24 @@ impl Field {
         }
     }
 
+    pub fn to_duration(&self) -> Option<DozerDuration> {
+        match self {
+            Field::Duration(d) => Some(*d),
+            Field::String(s) => s.parse::<DozerDuration>().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn to_bigint(&self) -> Option<DozerBigInt> {
+        match self {
+            Field::BigInt(i) => Some(*i),
+            Field::Int(i) => Some(DozerBigInt::from(*i)),
+            Field::UInt(u) => Some(DozerBigInt::from(*u)),
+            Field::String(s) => s.parse::<DozerBigInt>().ok(),
+            _ => None,
+        }
+    }
+
     pub fn to_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -452,6 +511,8 @@ impl Display for Field {
             Field::Bson(v) => f.write_str(&format!("{v:x?}")),
             Field::Null => f.write_str("NULL"),
             Field::Point(v) => f.write_str(&format!("{v} (Point)")),
+            Field::Duration(v) => f.write_str(&format!("{v} (Duration)")),
+            Field::BigInt(v) => f.write_str(&format!("{v} (BigInt)")),
         }
     }
 }
@@ -471,6 +532,8 @@ impl<'a> FieldBorrow<'a> {
             FieldBorrow::Date(d) => Field::Date(d),
             FieldBorrow::Bson(b) => Field::Bson(b.to_owned()),
             FieldBorrow::Point(p) => Field::Point(p),
+            FieldBorrow::Duration(d) => Field::Duration(d),
+            FieldBorrow::BigInt(i) => Field::BigInt(i),
             FieldBorrow::Null => Field::Null,
         }
     }
@@ -490,6 +553,8 @@ pub enum FieldType {
     Date,
     Bson,
     Point,
+    Duration,
+    BigInt,
 }
 
 impl TryFrom<&str> for FieldType {
@@ -508,6 +573,8 @@ impl TryFrom<&str> for FieldType {
             "timestamp" => FieldType::Timestamp,
             "date" => FieldType::Date,
             "bson" => FieldType::Bson,
+            "duration" => FieldType::Duration,
+            "bigint" => FieldType::BigInt,
             _ => return Err(format!("Unsupported '{value}' type")),
         };
 
@@ -530,6 +597,8 @@ impl Display for FieldType {
             FieldType::Date => f.write_str("date"),
             FieldType::Bson => f.write_str("bson"),
             FieldType::Point => f.write_str("point"),
+            FieldType::Duration => f.write_str("duration"),
+            FieldType::BigInt => f.write_str("bigint"),
         }
     }
 }
@@ -562,6 +631,11 @@ pub fn field_test_cases() -> impl Iterator<Item = Field> {
             // BSON representation of `{"abc":"foo"}`
             123, 34, 97, 98, 99, 34, 58, 34, 102, 111, 111, 34, 125,
         ]),
+        Field::Duration(DozerDuration::default()),
+        Field::Duration(DozerDuration::new(1, -2, 3_000_000_000)),
+        Field::BigInt(DozerBigInt::from(0_u64)),
+        Field::BigInt(DozerBigInt::from(-1_i64)),
+        Field::BigInt(DozerBigInt::from_unsigned_bytes_be(&[0xff; 32]).unwrap()),
         Field::Null,
     ]
     .into_iter()
@@ -588,6 +662,8 @@ impl pyo3::ToPyObject for Field {
             Field::Bson(val) => val.to_object(py),
             Field::Null => unreachable!(),
             Field::Point(_val) => todo!(),
+            Field::Duration(val) => val.to_string().to_object(py),
+            Field::BigInt(val) => val.to_string().to_object(py),
         }
     }
 }
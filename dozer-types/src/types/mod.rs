@@ -1,5 +1,7 @@
 use ahash::AHasher;
+use chrono::{DateTime, FixedOffset, Months};
 use geo::{point, GeodesicDistance, Point};
+use num_bigint::{BigInt, Sign};
 use ordered_float::OrderedFloat;
 use std::array::TryFromSliceError;
 use std::cmp::Ordering;
@@ -9,6 +11,7 @@ use std::str::FromStr;
 
 use crate::errors::types::TypeError;
 use prettytable::{Cell, Row, Table};
+use rust_decimal::Decimal;
 use serde::{self, Deserialize, Serialize};
 
 mod field;
@@ -167,7 +170,53 @@ pub enum IndexDefinition {
     /// The sorted inverted index, supporting `Eq` filter on multiple fields and `LT`, `LTE`, `GT`, `GTE` filter on at most one field.
     SortedInverted(Vec<usize>),
     /// Full text index, supporting `Contains`, `MatchesAny` and `MatchesAll` filter on exactly one field.
-    FullText(usize),
+    ///
+    /// The analyzer splits the field into the indexed tokens, and filter values into the looked up tokens.
+    FullText(usize, FullTextAnalyzer),
+    /// Geo index on a point field, supporting `Near`, `WithinBox` and `WithinRadius` filter on exactly one field.
+    Geo(usize),
+}
+
+/// How text is split into full text index tokens. The default splits text into unicode words and keeps them as they are.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub struct FullTextAnalyzer {
+    /// Lowercases tokens.
+    pub lowercase: bool,
+    /// Applies unicode NFKC normalization to tokens, so that compatible characters like `ﬁ` and `fi` match.
+    pub normalize: bool,
+    /// Removes the stop words of the language, like `the` and `of` in English.
+    pub stop_words: Option<Language>,
+    /// Reduces tokens to their stem in the language, so that `running` and `runs` match `run`.
+    pub stemming: Option<Language>,
+    /// Also indexes the prefixes of tokens, so that a filter token matches the tokens it's a prefix of.
+    pub prefix_search: Option<PrefixSearch>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "english" => Some(Language::English),
+            "french" => Some(Language::French),
+            "german" => Some(Language::German),
+            "spanish" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+}
+
+/// Lengths, in characters, of the token prefixes that are indexed.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct PrefixSearch {
+    pub min_len: usize,
+    pub max_len: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -300,6 +349,14 @@ impl Record {
                     hasher.write_u8(12);
                     hasher.write(p.to_bytes().as_slice());
                 }
+                Field::Duration(d) => {
+                    hasher.write_u8(13);
+                    hasher.write(d.to_bytes().as_slice());
+                }
+                Field::BigInt(i) => {
+                    hasher.write_u8(14);
+                    hasher.write(i.to_bytes().as_slice());
+                }
                 Field::Null => {
                     hasher.write_u8(0);
                 }
@@ -415,3 +472,378 @@ impl DozerPoint {
         Ok(DozerPoint::from((x, y)))
     }
 }
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_DAY: i64 = 86_400 * NANOS_PER_SECOND;
+
+/// A SQL interval. Months and days are kept apart from the exact part because
+/// how long they are depends on the timestamp they are added to.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub struct DozerDuration {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+impl Ord for DozerDuration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Same convention as Postgres: a month counts as 30 days when comparing
+        self.total_nanos().cmp(&other.total_nanos()).then(
+            (self.months, self.days, self.nanos).cmp(&(other.months, other.days, other.nanos)),
+        )
+    }
+}
+
+impl PartialOrd for DozerDuration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl DozerDuration {
+    pub fn new(months: i32, days: i32, nanos: i64) -> Self {
+        Self {
+            months,
+            days,
+            nanos,
+        }
+    }
+
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self::new(0, 0, nanos)
+    }
+
+    /// Exact time elapsed from `start` to `end`.
+    pub fn between(end: DateTime<FixedOffset>, start: DateTime<FixedOffset>) -> Option<Self> {
+        (end - start).num_nanoseconds().map(Self::from_nanos)
+    }
+
+    fn total_nanos(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * NANOS_PER_DAY as i128 + self.nanos as i128
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self::new(
+            self.months.checked_neg()?,
+            self.days.checked_neg()?,
+            self.nanos.checked_neg()?,
+        ))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.nanos.checked_add(other.nanos)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    /// Adds the duration to a timestamp, months first, then days, then the exact part.
+    /// Month arithmetic clamps to the end of the month, so Jan 31 + 1 month is Feb 28/29.
+    pub fn add_to(&self, ts: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        let ts = if self.months >= 0 {
+            ts.checked_add_months(Months::new(self.months as u32))?
+        } else {
+            ts.checked_sub_months(Months::new(self.months.unsigned_abs()))?
+        };
+        ts.checked_add_signed(chrono::Duration::days(self.days as i64))?
+            .checked_add_signed(chrono::Duration::nanoseconds(self.nanos))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut result = [0_u8; 16];
+        result[0..4].copy_from_slice(&self.months.to_be_bytes());
+        result[4..8].copy_from_slice(&self.days.to_be_bytes());
+        result[8..16].copy_from_slice(&self.nanos.to_be_bytes());
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TryFromSliceError> {
+        let months = i32::from_be_bytes(bytes[0..4].try_into()?);
+        let days = i32::from_be_bytes(bytes[4..8].try_into()?);
+        let nanos = i64::from_be_bytes(bytes[8..16].try_into()?);
+
+        Ok(DozerDuration::new(months, days, nanos))
+    }
+}
+
+/// ISO 8601 duration, e.g. `P1Y2M3DT4H5M6.5S`. Every component carries its own sign.
+impl Display for DozerDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.months == 0 && self.days == 0 && self.nanos == 0 {
+            return f.write_str("PT0S");
+        }
+
+        f.write_str("P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        for (value, unit) in [
+            (years as i64, 'Y'),
+            (months as i64, 'M'),
+            (self.days as i64, 'D'),
+        ] {
+            if value != 0 {
+                write!(f, "{value}{unit}")?;
+            }
+        }
+
+        if self.nanos != 0 {
+            f.write_str("T")?;
+            let sign = if self.nanos < 0 { "-" } else { "" };
+            let nanos = self.nanos.unsigned_abs();
+            let seconds = nanos / NANOS_PER_SECOND as u64;
+            let fraction = nanos % NANOS_PER_SECOND as u64;
+            let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+            if hours != 0 {
+                write!(f, "{sign}{hours}H")?;
+            }
+            if minutes != 0 {
+                write!(f, "{sign}{minutes}M")?;
+            }
+            if fraction != 0 {
+                let fraction = format!("{fraction:09}");
+                write!(f, "{sign}{seconds}.{}S", fraction.trim_end_matches('0'))?;
+            } else if seconds != 0 {
+                write!(f, "{sign}{seconds}S")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DozerDuration {
+    type Err = TypeError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidFieldValue {
+            field_type: FieldType::Duration,
+            nullable: false,
+            value: str.to_string(),
+        };
+
+        let mut chars = str.strip_prefix('P').ok_or_else(error)?.chars().peekable();
+        let mut duration = DozerDuration::default();
+        let mut in_time = false;
+        let mut empty = true;
+        while let Some(c) = chars.next() {
+            if c == 'T' && !in_time {
+                in_time = true;
+                continue;
+            }
+
+            let mut number = c.to_string();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+            let unit = chars.next().ok_or_else(error)?;
+
+            let component = if in_time && unit == 'S' {
+                let (seconds, fraction) = number.split_once('.').unwrap_or((&number, ""));
+                if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(error());
+                }
+                let negative = seconds.starts_with('-');
+                let seconds = seconds.parse::<i64>().map_err(|_| error())?;
+                let fraction = format!("{fraction:0<9}")
+                    .parse::<i64>()
+                    .map_err(|_| error())?;
+                let nanos = seconds
+                    .checked_mul(NANOS_PER_SECOND)
+                    .and_then(|n| n.checked_add(if negative { -fraction } else { fraction }))
+                    .ok_or_else(error)?;
+                DozerDuration::from_nanos(nanos)
+            } else {
+                let value = number.parse::<i64>().map_err(|_| error())?;
+                let to_i32 =
+                    |v: Option<i64>| v.and_then(|v| i32::try_from(v).ok()).ok_or_else(error);
+                match (in_time, unit) {
+                    (false, 'Y') => DozerDuration::new(to_i32(value.checked_mul(12))?, 0, 0),
+                    (false, 'M') => DozerDuration::new(to_i32(Some(value))?, 0, 0),
+                    (false, 'W') => DozerDuration::new(0, to_i32(value.checked_mul(7))?, 0),
+                    (false, 'D') => DozerDuration::new(0, to_i32(Some(value))?, 0),
+                    (true, 'H') => DozerDuration::from_nanos(
+                        value
+                            .checked_mul(3600 * NANOS_PER_SECOND)
+                            .ok_or_else(error)?,
+                    ),
+                    (true, 'M') => DozerDuration::from_nanos(
+                        value.checked_mul(60 * NANOS_PER_SECOND).ok_or_else(error)?,
+                    ),
+                    _ => return Err(error()),
+                }
+            };
+            duration = duration.checked_add(&component).ok_or_else(error)?;
+            empty = false;
+        }
+
+        if empty {
+            return Err(error());
+        }
+        Ok(duration)
+    }
+}
+
+const BIG_INT_BYTES: usize = 33;
+
+/// An integer wide enough for both `uint256` and `int256`, covering [-2^263, 2^263).
+///
+/// Stored as big endian two's complement with the sign bit flipped, so that the bytes sort in the
+/// same order as the numbers, which keeps the derived `Ord` and the cache's sorted indexes correct.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DozerBigInt([u8; BIG_INT_BYTES]);
+
+impl DozerBigInt {
+    /// Returns `None` if `value` is out of range.
+    pub fn from_bigint(value: &BigInt) -> Option<Self> {
+        let bytes = value.to_signed_bytes_be();
+        if bytes.len() > BIG_INT_BYTES {
+            return None;
+        }
+        let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+        let mut result = [fill; BIG_INT_BYTES];
+        result[BIG_INT_BYTES - bytes.len()..].copy_from_slice(&bytes);
+        result[0] ^= 0x80;
+        Some(Self(result))
+    }
+
+    pub fn to_bigint(&self) -> BigInt {
+        let mut bytes = self.0;
+        bytes[0] ^= 0x80;
+        BigInt::from_signed_bytes_be(&bytes)
+    }
+
+    /// Big endian two's complement, as `int<M>` values are encoded in the Ethereum ABI.
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> Option<Self> {
+        Self::from_bigint(&BigInt::from_signed_bytes_be(bytes))
+    }
+
+    /// Big endian unsigned, as `uint<M>` values are encoded in the Ethereum ABI.
+    pub fn from_unsigned_bytes_be(bytes: &[u8]) -> Option<Self> {
+        Self::from_bigint(&BigInt::from_bytes_be(Sign::Plus, bytes))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::from(0_u64)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0[0] & 0x80 == 0
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::from_bigint(&-self.to_bigint())
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Self::from_bigint(&(self.to_bigint() + other.to_bigint()))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Self::from_bigint(&(self.to_bigint() - other.to_bigint()))
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Self::from_bigint(&(self.to_bigint() * other.to_bigint()))
+    }
+
+    /// Truncates towards zero, like integer division in Rust.
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        Self::from_bigint(&(self.to_bigint() / other.to_bigint()))
+    }
+
+    /// The result has the sign of `self`, like `%` in Rust.
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        Self::from_bigint(&(self.to_bigint() % other.to_bigint()))
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        i64::try_from(self.to_bigint()).ok()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self.to_bigint()).ok()
+    }
+
+    /// Rounds to the nearest representable value.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string()
+            .parse::<f64>()
+            .expect("an integer is always a valid float")
+    }
+
+    /// Returns `None` if the value needs more than the 96 bits a `Decimal` can hold.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        Decimal::from_str_exact(&self.to_string()).ok()
+    }
+
+    pub fn to_bytes(&self) -> [u8; BIG_INT_BYTES] {
+        self.0
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TryFromSliceError> {
+        Ok(Self(bytes.try_into()?))
+    }
+}
+
+impl From<i64> for DozerBigInt {
+    fn from(value: i64) -> Self {
+        let mut result = [if value < 0 { 0xff } else { 0 }; BIG_INT_BYTES];
+        result[BIG_INT_BYTES - 8..].copy_from_slice(&value.to_be_bytes());
+        result[0] ^= 0x80;
+        Self(result)
+    }
+}
+
+impl From<u64> for DozerBigInt {
+    fn from(value: u64) -> Self {
+        let mut result = [0; BIG_INT_BYTES];
+        result[BIG_INT_BYTES - 8..].copy_from_slice(&value.to_be_bytes());
+        result[0] ^= 0x80;
+        Self(result)
+    }
+}
+
+impl Display for DozerBigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_bigint())
+    }
+}
+
+impl FromStr for DozerBigInt {
+    type Err = TypeError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        str.parse::<BigInt>()
+            .ok()
+            .and_then(|value| Self::from_bigint(&value))
+            .ok_or_else(|| InvalidFieldValue {
+                field_type: FieldType::BigInt,
+                nullable: false,
+                value: str.to_string(),
+            })
+    }
+}
+
+/// Serialized as a decimal string, so that JSON consumers don't round it to a double.
+impl Serialize for DozerBigInt {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DozerBigInt {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}